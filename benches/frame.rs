@@ -0,0 +1,258 @@
+// Timings of the frame loop's hot paths: drawing into a canvas and presenting it.
+//
+// Each case draws and presents a frame, once through the real emitter writing to a sink and once through a `HeadlessTerminal`.
+// Run with `cargo bench --features input`, optionally followed by `--` and:
+//   a filter         only run cases whose name contains it
+//   --save-baseline NAME   save the timings as baseline NAME
+//   --baseline NAME        compare against baseline NAME, and fail if a case got slower by more than the threshold
+//   --threshold PERCENT    the slowdown `--baseline` allows, 10 by default
+// Baselines are kept in `target/terminalgl-bench`.
+use std::collections::HashMap;
+use std::io::{self, Write};
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+use terminalgl::backend::Backend;
+use terminalgl::canvas::Canvas;
+use terminalgl::drawc::{self, ColorKind};
+use terminalgl::headless::HeadlessTerminal;
+use terminalgl::noise;
+#[cfg(unix)]
+use terminalgl::input::Event;
+
+const COLS: usize = 200;
+const ROWS: usize = 60;
+
+
+/// The ANSI emitter with its output thrown away, so only producing it is timed.
+struct SinkBackend;
+
+impl Backend for SinkBackend {
+    fn write(&mut self, bytes: &[u8]) -> io::Result<()> {
+        io::sink().write_all(std::hint::black_box(bytes))
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+
+    fn size(&self) -> (u16, u16) {
+        (COLS as u16, ROWS as u16)
+    }
+
+    fn set_alternate_screen(&mut self, _on: bool) -> io::Result<()> {
+        Ok(())
+    }
+
+    fn set_cursor_visible(&mut self, _visible: bool) -> io::Result<()> {
+        Ok(())
+    }
+
+    #[cfg(unix)]
+    fn set_raw_mode(&mut self, _on: bool) -> io::Result<()> {
+        Ok(())
+    }
+
+    #[cfg(unix)]
+    fn read_events(&mut self, _timeout: Duration) -> io::Result<Vec<Event>> {
+        Ok(Vec::new())
+    }
+}
+
+
+/// Times cases and keeps the median time of each.
+struct Bencher {
+    filter: Option<String>,
+    results: Vec<(String, Duration)>
+}
+
+impl Bencher {
+    /// Time `f` as the case `name` with `backend` after the name, unless it is filtered out.
+    fn bench(&mut self, name: &str, backend: &str, mut f: impl FnMut()) {
+        let name = format!("{}/{}", name, backend);
+        if self.filter.as_ref().is_some_and(|filter| !name.contains(filter.as_str())) {
+            return;
+        }
+        // Run enough iterations in each sample to take about 5 ms, after a first run to warm up.
+        let start = Instant::now();
+        f();
+        let once = start.elapsed().max(Duration::from_nanos(1));
+        let iterations = (Duration::from_millis(5).as_nanos() / once.as_nanos()).clamp(1, 100_000) as u32;
+        let mut samples: Vec<Duration> = (0..30).map(|_| {
+            let start = Instant::now();
+            for _ in 0..iterations {
+                f();
+            }
+            start.elapsed() / iterations
+        }).collect();
+        samples.sort();
+        let median = samples[samples.len() / 2];
+        println!("{:<32} {:>12}", name, show(median));
+        self.results.push((name, median));
+    }
+}
+
+
+/// Format `time` in the unit that suits it.
+fn show(time: Duration) -> String {
+    match time.as_nanos() {
+        n if n < 10_000 => format!("{} ns", n),
+        n if n < 10_000_000 => format!("{:.1} µs", n as f64 / 1e3),
+        n => format!("{:.2} ms", n as f64 / 1e6)
+    }
+}
+
+
+/// Get the file baseline `name` is saved in.
+fn baseline_path(name: &str) -> PathBuf {
+    let target = std::env::var_os("CARGO_TARGET_DIR").map_or_else(|| PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("target"), PathBuf::from);
+    target.join("terminalgl-bench").join(format!("{}.txt", name))
+}
+
+
+/// Draw and present a frame on both backends as the case `name`, with `draw` drawing it onto a blank canvas.
+fn bench_draw(bencher: &mut Bencher, name: &str, draw: impl Fn(&mut Canvas)) {
+    let mut canvas = Canvas::new(COLS, ROWS);
+    bencher.bench(name, "sink", || {
+        canvas.clear();
+        draw(&mut canvas);
+        canvas.present_to(&mut SinkBackend).unwrap();
+    });
+    let mut terminal = HeadlessTerminal::new(COLS as u16, ROWS as u16);
+    bencher.bench(name, "headless", || {
+        canvas.clear();
+        draw(&mut canvas);
+        canvas.present_to(&mut terminal).unwrap();
+    });
+}
+
+
+/// Present the difference between a full frame and one with `percent` of its cells changed, on both backends.
+fn bench_present_diff(bencher: &mut Bencher, percent: usize) {
+    let mut previous = Canvas::new(COLS, ROWS);
+    for y in 0..ROWS {
+        previous.text(0, y as isize, &"frame ".repeat(COLS / 6 + 1), drawc::GREEN);
+    }
+    let mut next = previous.clone();
+    for i in (0..COLS * ROWS).filter(|i| i * 7919 % 100 < percent) {
+        next.set((i % COLS) as isize, (i / COLS) as isize, '#', drawc::RED);
+    }
+    let name = format!("present_diff_{}%", percent);
+    bencher.bench(&name, "sink", || next.present_diff_to(&previous, &mut SinkBackend).unwrap());
+    let mut terminal = HeadlessTerminal::new(COLS as u16, ROWS as u16);
+    previous.present_to(&mut terminal).unwrap();
+    bencher.bench(&name, "headless", || next.present_diff_to(&previous, &mut terminal).unwrap());
+}
+
+
+/// Draw and present `rectangle_fill`'s frame through a row filter darkening every other row, on both backends,
+/// for the cost of filtering next to `rectangle_fill` without one.
+fn bench_row_filter(bencher: &mut Bencher) {
+    let mut canvas = Canvas::new(COLS, ROWS);
+    canvas.set_row_filter(|y, row| if y % 2 == 1 {
+        for cell in row {
+            cell.ccode = drawc::darken(&cell.ccode, 0.5);
+        }
+    });
+    let draw = |canvas: &mut Canvas| {
+        canvas.clear();
+        canvas.rectangle(0, 0, COLS, ROWS, '#', drawc::BLUE_BG, true);
+    };
+    bencher.bench("row_filter", "sink", || {
+        draw(&mut canvas);
+        canvas.present_to(&mut SinkBackend).unwrap();
+    });
+    let mut terminal = HeadlessTerminal::new(COLS as u16, ROWS as u16);
+    bencher.bench("row_filter", "headless", || {
+        draw(&mut canvas);
+        canvas.present_to(&mut terminal).unwrap();
+    });
+}
+
+
+fn main() {
+    let mut args = std::env::args().skip(1);
+    let (mut filter, mut save, mut baseline, mut threshold) = (None, None, None, 10.0);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--save-baseline" => save = args.next(),
+            "--baseline" => baseline = args.next(),
+            "--threshold" => threshold = args.next().and_then(|t| t.parse().ok()).unwrap_or(threshold),
+            // Flags cargo passes, like `--bench`.
+            arg if arg.starts_with("--") => {},
+            arg => filter = Some(arg.to_string())
+        }
+    }
+    let mut bencher = Bencher { filter, results: Vec::new() };
+
+    let fill = drawc::BLUE_BG;
+    bench_draw(&mut bencher, "rectangle_fill", |canvas| {
+        canvas.rectangle(0, 0, COLS, ROWS, '#', fill, true);
+    });
+    bench_draw(&mut bencher, "line_diagonal", |canvas| {
+        canvas.line(0, 0, COLS as isize - 1, ROWS as isize - 1, '*', drawc::YELLOW);
+    });
+    // Lines between points near the ends of `isize` across the canvas, which clipping keeps as cheap as `line_diagonal`.
+    bench_draw(&mut bencher, "line_huge", |canvas| {
+        canvas.line(isize::MIN + 1, isize::MIN + 1, isize::MAX - 1, isize::MAX - 1, '*', drawc::YELLOW);
+        canvas.line(isize::MIN + 1, ROWS as isize / 2, isize::MAX - 1, ROWS as isize / 2, '-', drawc::YELLOW);
+        canvas.line(isize::MAX - 1, isize::MIN + 1, isize::MIN + 1, isize::MAX - 1, '*', drawc::YELLOW);
+    });
+    bench_draw(&mut bencher, "ellipse_fill", |canvas| {
+        canvas.ellipse(COLS as isize / 2, ROWS as isize / 2, COLS / 2 - 1, ROWS / 2 - 1, '@', drawc::CYAN, true);
+    });
+    let text: String = "the quick brown fox jumps over the lazy dog ".chars().cycle().take(1000).collect();
+    bench_draw(&mut bencher, "text_1000", |canvas| {
+        canvas.text_wrapped(0, 0, COLS, &text, "");
+    });
+    // Half blocks with a truecolor foreground and background in every cell, as `drawc::image_blocks` draws images.
+    let ccodes: Vec<String> = (0..COLS * ROWS).map(|i| {
+        let (x, y) = ((i % COLS) as u8, (i / COLS) as u8);
+        drawc::rgb_to_ccode((x, y.wrapping_mul(4), 128), ColorKind::Fg) + &drawc::rgb_to_ccode((x, y.wrapping_mul(4).wrapping_add(2), 128), ColorKind::Bg)
+    }).collect();
+    bench_draw(&mut bencher, "image_half_blocks", |canvas| {
+        for (i, ccode) in ccodes.iter().enumerate() {
+            canvas.set((i % COLS) as isize, (i / COLS) as isize, '▀', ccode);
+        }
+    });
+    // A fractal noise field over the whole canvas, shaded with a character ramp.
+    let ramp: Vec<char> = " .:-=+*#%@".chars().collect();
+    bench_draw(&mut bencher, "noise_field", |canvas| {
+        for (x, y, v) in noise::field(canvas.rect(), 12.0, 7, (0.0, 0.0)) {
+            let c = ramp[((v * ramp.len() as f64) as usize).min(ramp.len() - 1)];
+            canvas.set(x, y, c, drawc::GREEN);
+        }
+    });
+    for percent in [0, 10, 100] {
+        bench_present_diff(&mut bencher, percent);
+    }
+    bench_row_filter(&mut bencher);
+
+    if let Some(name) = save {
+        let path = baseline_path(&name);
+        let saved: String = bencher.results.iter().map(|(case, time)| format!("{} {}\n", case, time.as_nanos())).collect();
+        std::fs::create_dir_all(path.parent().unwrap()).and_then(|_| std::fs::write(&path, saved)).expect("couldn't save the baseline");
+        println!("\nsaved baseline {:?} to {}", name, path.display());
+    }
+    if let Some(name) = baseline {
+        let saved = std::fs::read_to_string(baseline_path(&name)).unwrap_or_else(|_| panic!("no baseline {:?}; save one with --save-baseline", name));
+        let before: HashMap<&str, u128> = saved.lines().filter_map(|line| {
+            let (case, nanos) = line.rsplit_once(' ')?;
+            Some((case, nanos.parse().ok()?))
+        }).collect();
+        println!("\ncompared with baseline {:?}, allowing {}% slower:", name, threshold);
+        let mut regressed = 0;
+        for (case, time) in &bencher.results {
+            let Some(&old) = before.get(case.as_str()) else {
+                continue;
+            };
+            let change = (time.as_nanos() as f64 / old.max(1) as f64 - 1.0) * 100.0;
+            let verdict = if change > threshold { "REGRESSED" } else { "ok" };
+            regressed += (change > threshold) as usize;
+            println!("{:<32} {:>+8.1}%  {}", case, change, verdict);
+        }
+        if regressed > 0 {
+            eprintln!("\n{} case(s) got more than {}% slower", regressed, threshold);
+            std::process::exit(1);
+        }
+    }
+}
@@ -0,0 +1,12 @@
+#!/bin/sh
+# Build, lint and test terminalgl with no optional features, each feature on its own, the default set and every feature,
+# so that code behind a `#[cfg(feature = ...)]` can't depend on a feature it doesn't enable.
+# Run from the repository root: `sh ci/features.sh`.
+set -eu
+
+for features in "" "std" "image" "input" "async" "bidi" "widgets" "std,image,input,widgets" "std,image,input,async,bidi,widgets"; do
+    echo "== features: [${features}]"
+    cargo build --no-default-features --features "$features"
+    cargo clippy --no-default-features --features "$features" --all-targets -- -D warnings
+    cargo test --no-default-features --features "$features"
+done
@@ -0,0 +1,137 @@
+// Chat-style app: a render loop, async input from `input::event_stream`, and a clock that ticks every second,
+// all on the main thread. Run with `cargo run --example chat --features async`.
+//
+// A runtime like tokio would wait with `tokio::select! { event = events.next() => ..., _ = ticker.tick() => ... }`.
+// To keep the example free of dependencies, `wait_until` below does the same job with a deadline.
+#[cfg(unix)]
+use std::future::Future;
+#[cfg(unix)]
+use std::io;
+#[cfg(unix)]
+use std::pin::pin;
+#[cfg(unix)]
+use std::sync::Arc;
+#[cfg(unix)]
+use std::task::{Context, Poll, Wake, Waker};
+#[cfg(unix)]
+use std::thread::{self, Thread};
+#[cfg(unix)]
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+#[cfg(unix)]
+use terminalgl::canvas::Canvas;
+#[cfg(unix)]
+use terminalgl::drawc;
+#[cfg(unix)]
+use terminalgl::input::{self, Event, Key, RawMode};
+
+
+#[cfg(unix)]
+const TICK: Duration = Duration::from_secs(1);
+
+
+/// Waker that unparks the thread waiting in `wait_until`.
+#[cfg(unix)]
+struct Unpark(Thread);
+
+#[cfg(unix)]
+impl Wake for Unpark {
+    fn wake(self: Arc<Self>) {
+        self.0.unpark();
+    }
+}
+
+
+/// Poll `future` until it finishes, or give up at `deadline`. The thread sleeps until it is woken or the deadline passes.
+#[cfg(unix)]
+fn wait_until<F: Future>(future: F, deadline: Instant) -> Option<F::Output> {
+    let mut future = pin!(future);
+    let waker = Waker::from(Arc::new(Unpark(thread::current())));
+    let mut cx = Context::from_waker(&waker);
+    loop {
+        if let Poll::Ready(output) = future.as_mut().poll(&mut cx) {
+            return Some(output);
+        }
+        let now = Instant::now();
+        if now >= deadline {
+            return None;
+        }
+        thread::park_timeout(deadline - now);
+    }
+}
+
+
+/// Get the time of day as `hh:mm:ss` in UTC.
+#[cfg(unix)]
+fn clock() -> String {
+    let seconds = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs());
+    format!("{:02}:{:02}:{:02}", seconds / 3600 % 24, seconds / 60 % 60, seconds % 60)
+}
+
+
+/// Draw the header with the clock, the most recent messages that fit, and the input line.
+#[cfg(unix)]
+fn render(canvas: &mut Canvas, messages: &[(String, String)], typing: &str) {
+    canvas.clear();
+    let (width, height) = (canvas.width(), canvas.height() as isize);
+    for x in 0..width as isize {
+        canvas.set(x, 0, ' ', drawc::BLUE_BG);
+    }
+    canvas.text(1, 0, "#general - Enter to send, Esc to quit", drawc::BLUE_BG);
+    canvas.text(width as isize - 9, 0, &clock(), drawc::BLUE_BG);
+    let rows = (height - 3).max(0) as usize;
+    for (i, (who, text)) in messages.iter().rev().take(rows).rev().enumerate() {
+        let y = 1 + i as isize;
+        let ccode = if who == "you" { drawc::BRIGHT_GREEN } else { drawc::BRIGHT_CYAN };
+        canvas.text(1, y, &format!("{}:", who), ccode);
+        canvas.text(3 + who.len() as isize, y, text, drawc::WHITE);
+    }
+    canvas.text(0, height - 2, &"-".repeat(width), drawc::BRIGHT_BLACK);
+    canvas.text(1, height - 1, &format!("> {}_", typing), drawc::BRIGHT_WHITE);
+}
+
+
+#[cfg(unix)]
+fn main() -> io::Result<()> {
+    let _raw = RawMode::enable()?;
+    let mut events = input::event_stream();
+    let mut canvas = Canvas::screen();
+    let mut messages = vec![("bot".to_string(), "Say something and I'll say it back backwards.".to_string())];
+    let mut typing = String::new();
+    let mut next_tick = Instant::now() + TICK;
+    loop {
+        render(&mut canvas, &messages, &typing);
+        let damage = canvas.take_damage();
+        canvas.present_rects(&damage);
+
+        let Some(event) = wait_until(events.next(), next_tick) else {
+            // The clock ticked, so go around and redraw it.
+            next_tick += TICK;
+            continue;
+        };
+        match event {
+            None | Some(Event::Key(Key::Esc | Key::Ctrl('c'))) => return Ok(()),
+            Some(Event::Key(Key::Enter)) if !typing.is_empty() => {
+                let reply: String = typing.chars().rev().collect();
+                messages.push(("you".to_string(), std::mem::take(&mut typing)));
+                messages.push(("bot".to_string(), reply));
+            },
+            // Print everything again, in case something else printed over the screen.
+            Some(Event::Key(Key::Ctrl('l'))) => canvas.invalidate(),
+            Some(Event::Key(Key::Backspace)) => {
+                typing.pop();
+            },
+            Some(Event::Key(Key::Char(c))) => typing.push(c),
+            Some(Event::Resize(..)) => {
+                canvas = Canvas::screen();
+                terminalgl::clear();
+            },
+            _ => {}
+        }
+    }
+}
+
+
+#[cfg(not(unix))]
+fn main() {
+    eprintln!("The chat example reads the terminal through termios, so it needs a Unix terminal.");
+}
@@ -0,0 +1,62 @@
+// Bouncing ball: a fixed frame rate, a canvas, only the changed cells printed each frame, and a screen shake on every bounce.
+use std::io;
+use std::time::{Duration, Instant};
+use terminalgl::canvas::Canvas;
+use terminalgl::input;
+use terminalgl::present::{Effect, Presenter};
+use terminalgl::{drawc, raster};
+
+
+const FRAME_TIME: Duration = Duration::from_millis(33);
+
+
+pub fn run() -> io::Result<()> {
+    let (a, b) = (2, 1);
+    let (mut x, mut y) = (4.0, 3.0);
+    let (mut vx, mut vy) = (28.0, 11.0);
+    let mut presenter = Presenter::new(1);
+    let mut last = Instant::now();
+    loop {
+        let start = Instant::now();
+        let dt = start.duration_since(last).as_secs_f64();
+        last = start;
+
+        // A new canvas each frame follows the terminal when it is resized; the presenter falls back to a full redraw then.
+        let mut canvas = Canvas::screen();
+        let (width, height) = (canvas.width(), canvas.height());
+        let (min_x, max_x) = (1.0 + a as f64, (width as f64 - 2.0 - a as f64).max(1.0 + a as f64));
+        let (min_y, max_y) = (1.0 + b as f64, (height as f64 - 2.0 - b as f64).max(1.0 + b as f64));
+        x += vx * dt;
+        y += vy * dt;
+        let mut bounced = false;
+        if x < min_x || x > max_x {
+            vx = -vx;
+            x = x.clamp(min_x, max_x);
+            bounced = true;
+        }
+        if y < min_y || y > max_y {
+            vy = -vy;
+            y = y.clamp(min_y, max_y);
+            bounced = true;
+        }
+        if bounced {
+            presenter.start(Effect::Shake { magnitude: 1.5, duration: 0.25 });
+        }
+
+        for (cx, cy) in raster::rectangle(0, 0, width, height, false) {
+            canvas.set(cx, cy, '#', drawc::BRIGHT_BLACK);
+        }
+        for (cx, cy) in raster::ellipse(x.round() as isize, y.round() as isize, a, b, true) {
+            canvas.set(cx, cy, 'O', drawc::BRIGHT_RED);
+        }
+        canvas.text(2, 0, " Bouncing ball - q to go back ", drawc::WHITE);
+        presenter.present(&canvas, dt);
+
+        // Wait out the rest of the frame, waking early for input.
+        let events = input::read_events(FRAME_TIME.saturating_sub(start.elapsed()))?;
+        if events.iter().any(super::is_back) {
+            return Ok(());
+        }
+        std::thread::sleep(FRAME_TIME.saturating_sub(start.elapsed()));
+    }
+}
@@ -0,0 +1,63 @@
+// Buttons: clicks and hovering resolved by a hit grid, with the buttons and a circle lighting up under the mouse.
+use std::io;
+use std::time::Duration;
+use terminalgl::canvas::Canvas;
+use terminalgl::chars::LineStyle;
+use terminalgl::input;
+use terminalgl::ui::{HitGrid, Hover};
+use terminalgl::{drawc, Rect};
+
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Target {
+    Add,
+    Reset,
+    Circle
+}
+
+
+pub fn run() -> io::Result<()> {
+    let mut hits = HitGrid::new();
+    let mut count = 0;
+    let mut status = String::from("Move the mouse over the buttons and the circle.");
+    let mut previous: Option<Canvas> = None;
+    loop {
+        let mut canvas = Canvas::screen();
+        // Regions from the last frame must not catch clicks, so register this frame's from scratch as they are drawn.
+        hits.clear();
+        canvas.text(1, 0, " Buttons - click the buttons or the circle, q to go back ", drawc::BLACK_BG);
+        for (target, rect, label) in [(Target::Add, Rect::new(2, 2, 12, 3), "Add one"), (Target::Reset, Rect::new(16, 2, 12, 3), "Reset")] {
+            let ccode = if hits.hovered() == Some(target) { drawc::BRIGHT_YELLOW } else { drawc::WHITE };
+            canvas.border(rect, LineStyle::Rounded, ccode);
+            canvas.text(rect.x + 2, rect.y + 1, label, ccode);
+            hits.add_rect(rect, target);
+        }
+        let ccode = if hits.hovered() == Some(Target::Circle) { drawc::BRIGHT_YELLOW } else { drawc::BLUE };
+        canvas.with_hit_id(&mut hits, Target::Circle, |canvas| canvas.ellipse(40, 6, 8, 4, '@', ccode, true));
+        canvas.text(2, 6, &format!("Count: {}", count), drawc::BRIGHT_WHITE);
+        canvas.text(2, 8, &status, drawc::BRIGHT_BLACK);
+        match &previous {
+            Some(previous) => canvas.present_diff(previous),
+            None => canvas.present()
+        }
+        previous = Some(canvas);
+
+        for event in input::read_events(Duration::from_millis(200))? {
+            if super::is_back(&event) {
+                return Ok(());
+            }
+            for change in hits.handle_event(&event) {
+                status = match change {
+                    Hover::Enter(target) => format!("Entered {:?}", target),
+                    Hover::Leave(target) => format!("Left {:?}", target)
+                };
+            }
+            match hits.clicked(&event) {
+                Some(Target::Add) => count += 1,
+                Some(Target::Reset) => count = 0,
+                Some(Target::Circle) => count *= 2,
+                None => {}
+            }
+        }
+    }
+}
@@ -0,0 +1,144 @@
+// Dashboard: bordered panels, a live line chart, progress bars, a stats template, and buttons, refreshed a few times a second.
+use std::io;
+use std::time::{Duration, Instant};
+use terminalgl::canvas::Canvas;
+use terminalgl::chart::{Chart, Series};
+use terminalgl::sketch::Sketch;
+use terminalgl::input::{self, Event};
+use terminalgl::particles::Rng;
+use terminalgl::template::Template;
+use terminalgl::ui::{self, UiContext, UiEvent};
+use terminalgl::{drawc, noise, Rect};
+
+
+const SAMPLE_TIME: Duration = Duration::from_millis(250);
+const HISTORY: usize = 120;
+
+
+/// Draw a bordered panel with `title` in its top edge.
+fn panel(sketch: &mut Sketch, rect: Rect, title: &str) {
+    sketch.with_style(drawc::BRIGHT_BLACK, |f| {
+        f.rect(rect.x, rect.y, rect.width, rect.height, '#');
+    });
+    sketch.with_style(drawc::BRIGHT_WHITE, |f| {
+        f.text(rect.x + 2, rect.y, &format!(" {} ", title));
+    });
+}
+
+
+/// Draw a progress bar `width` cells wide at `(x, y)`, filled to `fraction`, with a label before it and a percentage after it.
+fn progress_bar(sketch: &mut Sketch, x: isize, y: isize, width: usize, label: &str, fraction: f64, ccode: &str) {
+    let filled = (fraction.clamp(0.0, 1.0) * width as f64).round() as usize;
+    sketch.with_style(drawc::WHITE, |f| {
+        f.text(x, y, label);
+    });
+    let bar_x = x + 8;
+    sketch.with_style(drawc::BRIGHT_BLACK, |f| {
+        f.fill_rect(bar_x, y, width, 1, '░');
+    });
+    sketch.with_style(ccode, |f| {
+        f.fill_rect(bar_x, y, filled, 1, '█');
+    });
+    sketch.text(bar_x + width as isize + 1, y, &format!("{:>3.0}%", fraction * 100.0));
+}
+
+
+pub fn run() -> io::Result<()> {
+    let stats = Template::parse("Uptime  {uptime:>9}    Samples {samples:>6}\nCPU avg {avg:>9}    CPU peak {peak:>5}").unwrap();
+    let mut rng = Rng::new(7);
+    let mut ctx = UiContext::new();
+    let mut cpu: Vec<f64> = Vec::new();
+    let mut memory: Vec<f64> = Vec::new();
+    let mut samples = 0u64;
+    let mut paused = false;
+    let started = Instant::now();
+    let mut next_sample = Instant::now();
+    let mut events: Vec<Event> = Vec::new();
+    let mut previous: Option<Canvas> = None;
+    loop {
+        if !paused && Instant::now() >= next_sample {
+            next_sample += SAMPLE_TIME;
+            let t = samples as f64 * 0.1;
+            cpu.push((noise::fractal2(t, 0.5, 1, 3) + rng.range(-0.05, 0.05)).clamp(0.0, 1.0));
+            memory.push((0.35 + 0.3 * noise::noise2(t * 0.2, 3.5, 2)).clamp(0.0, 1.0));
+            samples += 1;
+            for history in [&mut cpu, &mut memory] {
+                if history.len() > HISTORY {
+                    history.remove(0);
+                }
+            }
+        }
+
+        let mut sketch = Sketch::new();
+        let (width, height) = (sketch.canvas().width(), sketch.canvas().height());
+        sketch.style(drawc::BRIGHT_WHITE).text(1, 0, "Dashboard - Tab and Enter or click the buttons, q to go back").style("");
+
+        let chart_rect = Rect::new(0, 1, width * 2 / 3, height.saturating_sub(8));
+        panel(&mut sketch, chart_rect, "Load");
+        let mut chart = Chart::new();
+        chart.y_range = Some((0.0, 100.0));
+        let first = samples.saturating_sub(cpu.len() as u64);
+        let points = |history: &[f64]| history.iter().enumerate().map(|(i, v)| ((first + i as u64) as f64, v * 100.0)).collect();
+        chart.series.push(Series::new("cpu", points(&cpu), drawc::BRIGHT_GREEN));
+        chart.series.push(Series::new("memory", points(&memory), drawc::BRIGHT_CYAN));
+        let inner = Rect::new(chart_rect.x + 2, chart_rect.y + 1, chart_rect.width.saturating_sub(4), chart_rect.height.saturating_sub(2));
+        for (x, y, c, series) in chart.cells(inner) {
+            let ccode = series.map_or(drawc::WHITE, |i| chart.series[i].ccode.as_str());
+            sketch.with_style(ccode, |f| {
+                f.pixel(x, y, c);
+            });
+        }
+
+        let bars_rect = Rect::new(chart_rect.width as isize, 1, width - chart_rect.width, chart_rect.height);
+        panel(&mut sketch, bars_rect, "Now");
+        let bar_width = bars_rect.width.saturating_sub(16);
+        let latest = |history: &[f64]| history.last().copied().unwrap_or(0.0);
+        progress_bar(&mut sketch, bars_rect.x + 2, 3, bar_width, "CPU", latest(&cpu), drawc::BRIGHT_GREEN);
+        progress_bar(&mut sketch, bars_rect.x + 2, 5, bar_width, "Memory", latest(&memory), drawc::BRIGHT_CYAN);
+        let disk = (samples % 400) as f64 / 400.0;
+        progress_bar(&mut sketch, bars_rect.x + 2, 7, bar_width, "Backup", disk, drawc::BRIGHT_YELLOW);
+
+        let stats_rect = Rect::new(0, chart_rect.y + chart_rect.height as isize, width, height - 1 - chart_rect.height);
+        panel(&mut sketch, stats_rect, "Stats");
+        let uptime = started.elapsed().as_secs();
+        let avg = if cpu.is_empty() { 0.0 } else { cpu.iter().sum::<f64>() / cpu.len() as f64 };
+        let peak = cpu.iter().copied().fold(0.0, f64::max);
+        let values = [
+            ("uptime", format!("{}:{:02}:{:02}", uptime / 3600, uptime / 60 % 60, uptime % 60)),
+            ("samples", samples.to_string()),
+            ("avg", format!("{:.1}%", avg * 100.0)),
+            ("peak", format!("{:.0}%", peak * 100.0))
+        ];
+        let values: Vec<(&str, &str)> = values.iter().map(|(name, value)| (*name, value.as_str())).collect();
+        for (i, line) in stats.lines(&values).iter().enumerate() {
+            sketch.text(stats_rect.x + 2, stats_rect.y + 2 + i as isize, line);
+        }
+
+        let canvas = sketch.into_canvas();
+        match &previous {
+            Some(previous) => canvas.present_diff(previous),
+            None => canvas.present()
+        }
+        previous = Some(canvas);
+
+        // Buttons print straight to the terminal over the canvas, which only reprints the cells that changed.
+        let ui_events: Vec<UiEvent> = events.iter().filter_map(Event::ui_event).collect();
+        ctx.begin(&ui_events);
+        let buttons_x = stats_rect.x + stats_rect.width as isize - 26;
+        if ui::button(&mut ctx, Rect::new(buttons_x, stats_rect.y + 1, 11, 3), if paused { "Resume" } else { "Pause" }, drawc::WHITE) {
+            paused = !paused;
+            next_sample = Instant::now();
+        }
+        if ui::button(&mut ctx, Rect::new(buttons_x + 12, stats_rect.y + 1, 11, 3), "Reset", drawc::WHITE) {
+            cpu.clear();
+            memory.clear();
+        }
+        terminalgl::flush();
+
+        let wait = next_sample.saturating_duration_since(Instant::now()).min(SAMPLE_TIME);
+        events = input::read_events(wait)?;
+        if events.iter().any(super::is_back) {
+            return Ok(());
+        }
+    }
+}
@@ -0,0 +1,72 @@
+// Dialogue box: pages of text revealed like a typewriter, with space or Enter to skip ahead and turn the page.
+use std::io;
+use std::time::{Duration, Instant};
+use terminalgl::canvas::Canvas;
+use terminalgl::input::{self, Event, Key};
+use terminalgl::ui::TextReveal;
+use terminalgl::{drawc, Rect};
+
+
+const FRAME_TIME: Duration = Duration::from_millis(33);
+
+const PAGES: [(&str, &str); 4] = [
+    ("Old man", "It's dangerous to go alone! Take this... no, wait. I seem to have misplaced it."),
+    ("Old man", "Well, never mind. The cave is north of here, past the river. You can't miss it, unless you do."),
+    ("You", "Thanks, I think?"),
+    ("Old man", "Good luck, traveler. Press space to hear it all again, or q to go back.")
+];
+
+
+pub fn run() -> io::Result<()> {
+    let screen = Canvas::screen();
+    let (width, height) = (screen.width(), screen.height());
+    let mut page = 0;
+    let mut reveal = new_page(page);
+    let mut previous: Option<Canvas> = None;
+    let mut last = Instant::now();
+    loop {
+        let start = Instant::now();
+        reveal.tick(start.duration_since(last).as_secs_f64());
+        last = start;
+
+        let mut canvas = Canvas::new(width, height);
+        canvas.text(1, 0, " Dialogue - space or Enter to continue, q to go back ", drawc::BLACK_BG);
+        let rect = Rect::new(2, height as isize - 8, width.saturating_sub(4), 7);
+        canvas.rectangle(rect.x, rect.y, rect.width, rect.height, ' ', drawc::BLUE_BG, true);
+        canvas.rectangle(rect.x, rect.y, rect.width, rect.height, '#', drawc::BRIGHT_WHITE, false);
+        canvas.text(rect.x + 2, rect.y, &format!(" {} ", PAGES[page].0), drawc::BRIGHT_YELLOW);
+        reveal.render(&mut canvas, Rect::new(rect.x + 2, rect.y + 2, rect.width.saturating_sub(4), rect.height.saturating_sub(3)));
+        if reveal.done() {
+            canvas.set(rect.x + rect.width as isize - 3, rect.y + rect.height as isize - 2, '▼', drawc::BRIGHT_WHITE);
+        }
+        match &previous {
+            Some(previous) => canvas.present_diff(previous),
+            None => canvas.present()
+        }
+        previous = Some(canvas);
+
+        for event in input::read_events(FRAME_TIME.saturating_sub(start.elapsed()))? {
+            if super::is_back(&event) {
+                return Ok(());
+            }
+            if let Event::Key(Key::Char(' ') | Key::Enter) = event {
+                if reveal.done() {
+                    page = (page + 1) % PAGES.len();
+                    reveal = new_page(page);
+                }
+                else {
+                    reveal.skip();
+                }
+            }
+        }
+        std::thread::sleep(FRAME_TIME.saturating_sub(start.elapsed()));
+    }
+}
+
+
+/// Start revealing page `page`.
+fn new_page(page: usize) -> TextReveal {
+    let mut reveal = TextReveal::new(PAGES[page].1);
+    reveal.ccode = drawc::BRIGHT_WHITE.to_string() + drawc::BLUE_BG;
+    reveal
+}
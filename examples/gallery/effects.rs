@@ -0,0 +1,53 @@
+// Built-in effects: fire and matrix rain, switched with the space bar.
+use std::io;
+use std::time::{Duration, Instant};
+use terminalgl::canvas::Canvas;
+use terminalgl::effects::{Fire, MatrixRain};
+use terminalgl::input::{self, Event, Key};
+use terminalgl::drawc;
+
+
+const FRAME_TIME: Duration = Duration::from_millis(33);
+
+
+pub fn run() -> io::Result<()> {
+    let screen = Canvas::screen();
+    let (width, height) = (screen.width(), screen.height());
+    let mut fire = Fire::new(width, height, 1);
+    let mut rain = MatrixRain::new(width, height, 2);
+    rain.density = 0.5;
+    let mut show_fire = true;
+    let mut previous: Option<Canvas> = None;
+    let mut last = Instant::now();
+    loop {
+        let start = Instant::now();
+        let dt = start.duration_since(last).as_secs_f64();
+        last = start;
+
+        let mut canvas = Canvas::new(width, height);
+        if show_fire {
+            fire.update(dt);
+            fire.render(&mut canvas);
+        }
+        else {
+            rain.update(dt);
+            rain.render(&mut canvas);
+        }
+        canvas.text(1, 0, " Effects - space to switch, q to go back ", drawc::BLACK_BG);
+        match &previous {
+            Some(previous) => canvas.present_diff(previous),
+            None => canvas.present()
+        }
+        previous = Some(canvas);
+
+        for event in input::read_events(FRAME_TIME.saturating_sub(start.elapsed()))? {
+            if super::is_back(&event) {
+                return Ok(());
+            }
+            if let Event::Key(Key::Char(' ')) = event {
+                show_fire = !show_fire;
+            }
+        }
+        std::thread::sleep(FRAME_TIME.saturating_sub(start.elapsed()));
+    }
+}
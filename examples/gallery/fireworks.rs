@@ -0,0 +1,126 @@
+// Fireworks from a particle system: every key launches a rocket that bursts at the top of its climb.
+// Letters and digits launch from a column that follows the key's place in the alphabet, space from a random one,
+// and Enter launches a volley.
+use std::f64::consts::TAU;
+use std::io;
+use std::time::{Duration, Instant};
+use terminalgl::canvas::Canvas;
+use terminalgl::drawc;
+use terminalgl::input::{self, Event, Key};
+use terminalgl::particles::{EmitterShape, Particle, ParticleStyle, ParticleSystem, Rng};
+
+
+const FRAME_TIME: Duration = Duration::from_millis(33);
+/// Downward acceleration of rockets and sparks, in cells per second squared.
+const GRAVITY: f64 = 14.0;
+/// Colors a burst fades from, one style each.
+const COLORS: [(u8, u8, u8); 6] = [(255, 80, 80), (255, 200, 60), (120, 255, 120), (80, 200, 255), (200, 120, 255), (255, 255, 255)];
+
+
+/// A rocket climbing until gravity stops it, then bursting in style `style`.
+struct Rocket {
+    x: f64,
+    y: f64,
+    vy: f64,
+    style: usize
+}
+
+
+/// Launch a rocket from the bottom of a `width` by `height` screen at column `x`, aimed to burst in the top half.
+fn launch(rng: &mut Rng, x: f64, width: usize, height: usize) -> Rocket {
+    let apex = rng.range(0.15, 0.5) * height as f64;
+    let climb = (height as f64 - apex).max(1.0);
+    Rocket {
+        x: x.clamp(1.0, (width as f64 - 2.0).max(1.0)),
+        y: height as f64 - 1.0,
+        vy: -(2.0 * GRAVITY * climb).sqrt(),
+        style: (rng.next_u64() % COLORS.len() as u64) as usize
+    }
+}
+
+
+/// Get the column a key launches from: letters and digits spread across the width in order, anything else at random.
+fn column(rng: &mut Rng, c: char, width: usize) -> f64 {
+    let c = c.to_ascii_lowercase();
+    let place = match c {
+        'a'..='z' => Some((c as u8 - b'a') as f64 / 25.0),
+        '0'..='9' => Some((c as u8 - b'0') as f64 / 9.0),
+        _ => None
+    };
+    place.unwrap_or_else(|| rng.next_f64()) * (width as f64 - 1.0)
+}
+
+
+/// Spawn the sparks of a burst at `(x, y)`. Horizontal speeds are doubled so the burst looks round in cells twice as tall as they are wide.
+fn burst(system: &mut ParticleSystem, rng: &mut Rng, x: f64, y: f64, style: usize) {
+    let speed = rng.range(8.0, 14.0);
+    for _ in 0..80 {
+        let angle = rng.range(0.0, TAU);
+        let speed = speed * rng.range(0.6, 1.0);
+        system.spawn(Particle { x, y, vx: 2.0 * speed * angle.cos(), vy: speed * angle.sin(), age: 0.0, lifetime: rng.range(0.8, 1.6), style });
+    }
+}
+
+
+pub fn run() -> io::Result<()> {
+    let mut system = ParticleSystem::new(11);
+    system.gravity = GRAVITY;
+    for &color in &COLORS {
+        system.add_style(ParticleStyle::new("@*+:.", &[(255, 255, 255), color, (color.0 / 4, color.1 / 4, color.2 / 4)]));
+    }
+    let smoke = system.add_style(ParticleStyle::new("|:.", &[(255, 200, 120), (80, 60, 50)]));
+    let mut rng = Rng::new(3);
+    let mut rockets: Vec<Rocket> = Vec::new();
+    let mut previous: Option<Canvas> = None;
+    let mut last = Instant::now();
+    loop {
+        let start = Instant::now();
+        let dt = start.duration_since(last).as_secs_f64();
+        last = start;
+
+        let mut canvas = Canvas::screen();
+        let (width, height) = (canvas.width(), canvas.height());
+        for event in input::read_events(Duration::ZERO)? {
+            match event {
+                event if super::is_back(&event) => return Ok(()),
+                Event::Key(Key::Enter) => {
+                    for i in 0..8 {
+                        let x = (i as f64 + rng.range(0.2, 0.8)) / 8.0 * width as f64;
+                        rockets.push(launch(&mut rng, x, width, height));
+                    }
+                },
+                Event::Key(Key::Char(c)) => {
+                    let x = column(&mut rng, c, width);
+                    rockets.push(launch(&mut rng, x, width, height));
+                },
+                _ => {}
+            }
+        }
+
+        // Rockets leave a trail of smoke and burst once they stop climbing.
+        for rocket in rockets.iter_mut() {
+            rocket.vy += GRAVITY * dt;
+            rocket.y += rocket.vy * dt;
+            let trail = EmitterShape::Point { x: rocket.x, y: rocket.y + 1.0, speed: 2.0, angle: TAU / 4.0, spread: 1.0 };
+            system.emit(trail, 2, (0.2, 0.5), smoke);
+        }
+        for rocket in rockets.iter().filter(|rocket| rocket.vy >= 0.0) {
+            burst(&mut system, &mut rng, rocket.x, rocket.y, rocket.style);
+        }
+        rockets.retain(|rocket| rocket.vy < 0.0);
+        system.update(dt);
+
+        system.render(&mut canvas);
+        for rocket in &rockets {
+            canvas.set(rocket.x as isize, rocket.y as isize, '^', drawc::BRIGHT_WHITE);
+        }
+        canvas.text(1, 0, " Fireworks - letters, digits and space launch, Enter for a volley, q to go back ", drawc::BLACK_BG);
+        match &previous {
+            Some(previous) => canvas.present_diff(previous),
+            None => canvas.present()
+        }
+        previous = Some(canvas);
+
+        std::thread::sleep(FRAME_TIME.saturating_sub(start.elapsed()));
+    }
+}
@@ -0,0 +1,132 @@
+// Gallery of interactive demos. Pick one from the menu, and press q or Esc in a demo to come back.
+// Run with `cargo run --example gallery --features input,widgets`.
+#[cfg(unix)]
+mod bounce;
+#[cfg(unix)]
+mod buttons;
+#[cfg(unix)]
+mod dashboard;
+#[cfg(unix)]
+mod dialogue;
+#[cfg(unix)]
+mod effects;
+#[cfg(unix)]
+mod fireworks;
+#[cfg(unix)]
+mod paint;
+#[cfg(unix)]
+mod plasma;
+#[cfg(unix)]
+mod roguelike;
+#[cfg(unix)]
+mod worldmap;
+
+#[cfg(unix)]
+use std::io;
+#[cfg(unix)]
+use std::time::Duration;
+#[cfg(unix)]
+use terminalgl::input::{self, Event, Key, RawMode};
+#[cfg(unix)]
+use terminalgl::theme::{self, Theme};
+#[cfg(unix)]
+use terminalgl::ui::{self, UiContext, UiEvent};
+#[cfg(unix)]
+use terminalgl::{drawc, Rect};
+
+
+/// Entry point of a demo, which returns when the user asks to go back.
+#[cfg(unix)]
+type Demo = fn() -> io::Result<()>;
+
+
+/// Name, description, and entry point of each demo, in menu order.
+#[cfg(unix)]
+const DEMOS: [(&str, &str, Demo); 10] = [
+    ("Bouncing ball", "frame timing, a canvas, and printing only what changed", bounce::run),
+    ("Paint", "drag to draw, right click to flood fill", paint::run),
+    ("Dashboard", "panels, a live chart, progress bars, and buttons", dashboard::run),
+    ("Roguelike", "a tile map, field of view, and arrow key movement", roguelike::run),
+    ("Plasma", "color cycling by rotating a 32 entry palette", plasma::run),
+    ("Effects", "built-in fire and matrix rain", effects::run),
+    ("Dialogue", "typewriter text reveal in a dialogue box", dialogue::run),
+    ("World map", "a scrolling 512x512 map with a live minimap", worldmap::run),
+    ("Buttons", "clicks and hover highlights found with a hit grid", buttons::run),
+    ("Fireworks", "keypresses launch rockets that burst into particles", fireworks::run)
+];
+
+
+/// Get whether `event` asks to leave the current screen.
+#[cfg(unix)]
+pub fn is_back(event: &Event) -> bool {
+    matches!(event, Event::Key(Key::Char('q') | Key::Esc | Key::Ctrl('c')))
+}
+
+
+#[cfg(unix)]
+fn main() -> io::Result<()> {
+    // One guard for the whole gallery: demos draw and read input inside it, and the terminal is restored once on the way out.
+    let _raw = RawMode::with_mouse_motion()?;
+    let mut ctx = UiContext::new();
+    let mut events = Vec::new();
+    let mut dark = true;
+    theme::set_theme(Theme::dark());
+    loop {
+        let ui_events: Vec<UiEvent> = events.iter().filter_map(Event::ui_event).collect();
+        ctx.begin(&ui_events);
+        // The buttons are drawn with an empty color code, so they follow the theme, and so does everything else on the menu.
+        let theme = theme::current();
+        terminalgl::clear();
+        drawc::rectangle(0, 0, terminalgl::size().0 as usize, terminalgl::size().1 as usize, ' ', &theme.background, true);
+        drawc::text(2, 1, "terminalgl gallery", &theme.title);
+        drawc::text(2, 2, "Tab/Shift+Tab and Enter, a click, or a number key to start a demo.", &theme.disabled);
+        drawc::text(2, 3, "t to switch themes, q or Esc to quit.", &theme.disabled);
+
+        let mut chosen = None;
+        let mut restyled = false;
+        // One row per button, so the whole menu fits on an 80 by 24 terminal.
+        for (i, (name, about, _)) in DEMOS.iter().enumerate() {
+            let y = 5 + i as isize;
+            // Number keys 1 to 9 pick the first nine demos and 0 the tenth.
+            if ui::button(&mut ctx, Rect::new(2, y, 20, 1), &format!("{} {}", (i + 1) % 10, name), "") {
+                chosen = Some(i);
+            }
+            drawc::text(24, y, about, &theme.disabled);
+        }
+        if ui::button(&mut ctx, Rect::new(2, 6 + DEMOS.len() as isize, 20, 1), "Quit", "") {
+            return Ok(());
+        }
+        for event in &events {
+            match event {
+                Event::Key(Key::Char('t')) => {
+                    dark = !dark;
+                    theme::set_theme(if dark { Theme::dark() } else { Theme::light() });
+                    restyled = true;
+                },
+                Event::Key(Key::Char(c @ '0'..='9')) => chosen = chosen.or(c.to_digit(10).map(|n| (n as usize + 9) % 10).filter(|&i| i < DEMOS.len())),
+                event if is_back(event) => return Ok(()),
+                _ => {}
+            }
+        }
+        terminalgl::flush();
+        if restyled {
+            // Redraw in the new theme straight away.
+            events.clear();
+            continue;
+        }
+
+        if let Some(i) = chosen {
+            terminalgl::clear();
+            (DEMOS[i].2)()?;
+            events.clear();
+            continue;
+        }
+        events = input::read_events(Duration::from_secs(1))?;
+    }
+}
+
+
+#[cfg(not(unix))]
+fn main() {
+    eprintln!("The gallery reads keys and the mouse through termios, so it needs a Unix terminal.");
+}
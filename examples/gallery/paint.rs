@@ -0,0 +1,79 @@
+// Paint program: drag with the left button to draw, right click to flood fill, and pick colors from the palette.
+use std::io;
+use std::time::Duration;
+use terminalgl::canvas::Canvas;
+use terminalgl::input::{self, Event, Key, Mouse, MouseButton, MouseKind};
+use terminalgl::{drawc, raster};
+
+
+const PALETTE: [&str; 7] = [drawc::RED, drawc::YELLOW, drawc::GREEN, drawc::CYAN, drawc::BLUE, drawc::MAGENTA, drawc::WHITE];
+const BRUSH: char = '█';
+
+
+pub fn run() -> io::Result<()> {
+    let mut art = Canvas::screen();
+    let mut color = 0;
+    let mut last: Option<(isize, isize)> = None;
+    let mut previous: Option<Canvas> = None;
+    loop {
+        let mut screen = art.clone();
+        let (width, height) = (screen.width(), screen.height() as isize);
+        for x in 0..width as isize {
+            screen.set(x, 0, ' ', drawc::BLACK_BG);
+        }
+        screen.text(1, 0, "Paint - drag to draw, right click to fill, c to clear, q to go back", drawc::BLACK_BG);
+        for (i, ccode) in PALETTE.iter().enumerate() {
+            let x = 1 + 4 * i as isize;
+            let marker = if i == color { '>' } else { ' ' };
+            screen.set(x, height - 1, marker, drawc::WHITE);
+            screen.text(x + 1, height - 1, &format!("{}{}", BRUSH, BRUSH), ccode);
+        }
+        match &previous {
+            Some(previous) => screen.present_diff(previous),
+            None => screen.present()
+        }
+        previous = Some(screen);
+
+        for event in input::read_events(Duration::from_millis(200))? {
+            match event {
+                event if super::is_back(&event) => return Ok(()),
+                Event::Key(Key::Char('c')) => art.clear(),
+                Event::Key(Key::Char(c @ '1'..='7')) => color = c as usize - '1' as usize,
+                Event::Mouse(Mouse { kind, x, y }) => {
+                    if y == height - 1 {
+                        // The palette row: each swatch is 4 cells wide, starting at column 1.
+                        if matches!(kind, MouseKind::Press(_)) && x >= 1 && (x - 1) / 4 < PALETTE.len() as isize {
+                            color = (x - 1) as usize / 4;
+                        }
+                        continue;
+                    }
+                    if y < 1 {
+                        continue;
+                    }
+                    match kind {
+                        MouseKind::Press(MouseButton::Left) => {
+                            art.set(x, y, BRUSH, PALETTE[color]);
+                            last = Some((x, y));
+                        },
+                        MouseKind::Drag(MouseButton::Left) => {
+                            // Fast drags skip cells, so join them up with a line.
+                            let (x1, y1) = last.unwrap_or((x, y));
+                            for (lx, ly) in raster::line(x1, y1, x, y) {
+                                if ly >= 1 && ly < height - 1 {
+                                    art.set(lx, ly, BRUSH, PALETTE[color]);
+                                }
+                            }
+                            last = Some((x, y));
+                        },
+                        MouseKind::Press(MouseButton::Right) => {
+                            art.flood_fill(x, y, BRUSH, PALETTE[color]);
+                        },
+                        MouseKind::Release => last = None,
+                        _ => {}
+                    }
+                },
+                _ => {}
+            }
+        }
+    }
+}
@@ -0,0 +1,52 @@
+// Plasma: the pattern is drawn once with palette indices, then animated by rotating the palette,
+// so each frame only reprints cells whose color changed and draws nothing new.
+use std::io;
+use std::time::{Duration, Instant};
+use terminalgl::canvas::Canvas;
+use terminalgl::drawc::{self, ColorKind};
+use terminalgl::input;
+
+
+const FRAME_TIME: Duration = Duration::from_millis(50);
+const COLORS: usize = 32;
+
+
+/// Draw the plasma pattern into a new screen-sized canvas, with a palette of `COLORS` entries cycling through `stops`.
+fn plasma(stops: &[(u8, u8, u8)]) -> Canvas {
+    let mut canvas = Canvas::screen();
+    for i in 0..COLORS {
+        let rgb = drawc::gradient(stops, i as f64 / COLORS as f64).unwrap();
+        canvas.set_palette_entry(i, &drawc::rgb_to_ccode(rgb, ColorKind::Fg));
+    }
+    for (x, y) in canvas.rect().cells() {
+        let (fx, fy) = (x as f64 / 8.0, y as f64 / 4.0);
+        let v = fx.sin() + (fy * 1.3).sin() + ((fx + fy) * 0.7).sin() + ((fx * fx + fy * fy).sqrt() * 0.9).sin();
+        let index = ((v + 4.0) / 8.0 * COLORS as f64) as usize % COLORS;
+        canvas.set_indexed(x, y, '█', index);
+    }
+    canvas.text(1, 0, " Plasma - palette rotation only, q to go back ", drawc::BRIGHT_WHITE);
+    canvas
+}
+
+
+pub fn run() -> io::Result<()> {
+    // The last stop repeats the first, so the palette wraps around smoothly as it rotates.
+    let stops = [(20, 0, 80), (200, 0, 120), (255, 160, 0), (255, 255, 160), (0, 160, 255), (20, 0, 80)];
+    let mut canvas = plasma(&stops);
+    canvas.present();
+    loop {
+        let start = Instant::now();
+        if (canvas.width(), canvas.height()) != (terminalgl::size().0 as usize, terminalgl::size().1 as usize) {
+            canvas = plasma(&stops);
+            canvas.present();
+        }
+        let previous = canvas.clone();
+        canvas.rotate_palette(0..COLORS, 1);
+        canvas.present_diff(&previous);
+
+        if input::read_events(FRAME_TIME.saturating_sub(start.elapsed()))?.iter().any(super::is_back) {
+            return Ok(());
+        }
+        std::thread::sleep(FRAME_TIME.saturating_sub(start.elapsed()));
+    }
+}
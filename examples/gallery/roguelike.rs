@@ -0,0 +1,155 @@
+// Mini roguelike: a tile map with a sprite layer, field of view with remembered cells, and monsters that chase the player.
+use std::collections::HashSet;
+use std::io;
+use std::time::Duration;
+use terminalgl::canvas::Canvas;
+use terminalgl::input::{self, Event, Key};
+use terminalgl::tilemap::{TileMap, EMPTY};
+use terminalgl::{drawc, fov, path, Rect};
+
+
+const MAP: [&str; 21] = [
+    "################################################################",
+    "#..........#...................#..............#................#",
+    "#..........#...................#..............#................#",
+    "#..........+.........g.........+..............#.......g........#",
+    "#..........#...................#..............+................#",
+    "#..........#...................#..............#................#",
+    "######+#########+###############..............######+###########",
+    "#..........#..........#........#..............#................#",
+    "#..........#..........#........######+#########................#",
+    "#..........#..........+...............................g........#",
+    "#....@.....+..........#........................................#",
+    "#..........#..........#........######+#########................#",
+    "#..........#..........#........#..............#................#",
+    "######+#################.......#..............#######+##########",
+    "#.......................#......#..g...........#................#",
+    "#.......................#......#..............#................#",
+    "#...........g...........+......+..............+.......g........#",
+    "#.......................#......#..............#................#",
+    "#.......................#......#..............#................#",
+    "#.......................#......#..............#................#",
+    "################################################################"
+];
+
+const FLOOR: u16 = 1;
+const WALL: u16 = 2;
+const DOOR: u16 = 3;
+const PLAYER: u16 = 10;
+const GOBLIN: u16 = 11;
+
+const TERRAIN: usize = 0;
+const SPRITES: usize = 1;
+const SIGHT: usize = 8;
+
+
+pub fn run() -> io::Result<()> {
+    let mut map = TileMap::new(MAP[0].len(), MAP.len(), 2);
+    map.define(FLOOR, '.', drawc::WHITE);
+    map.define(WALL, '#', drawc::YELLOW);
+    map.define(DOOR, '+', drawc::RED);
+    map.define(PLAYER, '@', drawc::BRIGHT_WHITE);
+    map.define(GOBLIN, 'g', drawc::BRIGHT_GREEN);
+    let mut player = (0, 0);
+    let mut goblins = Vec::new();
+    for (y, row) in MAP.iter().enumerate() {
+        for (x, c) in row.chars().enumerate() {
+            let (x, y) = (x as isize, y as isize);
+            map.set(TERRAIN, x, y, match c {
+                '#' => WALL,
+                '+' => DOOR,
+                _ => FLOOR
+            });
+            match c {
+                '@' => player = (x, y),
+                'g' => goblins.push((x, y)),
+                _ => {}
+            }
+        }
+    }
+
+    let mut seen: HashSet<(isize, isize)> = HashSet::new();
+    let mut message = String::from("Arrow keys or hjkl to move, walk into a goblin to fight it, q to go back.");
+    let mut previous: Option<Canvas> = None;
+    loop {
+        for (x, y) in goblins.iter().copied().chain([player]) {
+            map.set(SPRITES, x, y, if (x, y) == player { PLAYER } else { GOBLIN });
+        }
+        let opaque = |x: isize, y: isize| map.get(TERRAIN, x, y).is_none_or(|id| id != FLOOR);
+        let visible = fov::compute(player, SIGHT, opaque);
+        seen.extend(visible.iter().copied());
+
+        // Draw the map around the player, then hide what can't be seen and dim what is only remembered.
+        let mut canvas = Canvas::screen();
+        let view = Rect::new(0, 1, canvas.width(), canvas.height().saturating_sub(2));
+        let camera = (player.0 - view.width as isize / 2, player.1 - view.height as isize / 2);
+        map.render(&mut canvas, view, camera.0, camera.1);
+        for (x, y) in view.cells() {
+            let cell = (camera.0 + x - view.x, camera.1 + y - view.y);
+            if visible.contains(cell.0, cell.1) {
+                continue;
+            }
+            match map.get(TERRAIN, cell.0, cell.1).and_then(|id| map.tile(id)) {
+                Some(tile) if seen.contains(&cell) => canvas.set(x, y, tile.c, drawc::BRIGHT_BLACK),
+                _ => canvas.set(x, y, ' ', "")
+            }
+        }
+        canvas.text(1, 0, &format!("Goblins left: {}", goblins.len()), drawc::BRIGHT_WHITE);
+        canvas.text(1, canvas.height() as isize - 1, &message, drawc::WHITE);
+        match &previous {
+            Some(previous) => canvas.present_diff(previous),
+            None => canvas.present()
+        }
+        previous = Some(canvas);
+
+        let mut moved = false;
+        for event in input::read_events(Duration::from_secs(1))? {
+            if super::is_back(&event) {
+                return Ok(());
+            }
+            let step = match event {
+                Event::Key(Key::Up | Key::Char('k' | 'w')) => (0, -1),
+                Event::Key(Key::Down | Key::Char('j' | 's')) => (0, 1),
+                Event::Key(Key::Left | Key::Char('h' | 'a')) => (-1, 0),
+                Event::Key(Key::Right | Key::Char('l' | 'd')) => (1, 0),
+                _ => continue
+            };
+            let target = (player.0 + step.0, player.1 + step.1);
+            if let Some(i) = goblins.iter().position(|&g| g == target) {
+                let goblin = goblins.remove(i);
+                map.set(SPRITES, goblin.0, goblin.1, EMPTY);
+                message = if goblins.is_empty() { "The last goblin falls. The dungeon is quiet.".to_string() } else { "You defeat a goblin.".to_string() };
+            }
+            else if map.get(TERRAIN, target.0, target.1).is_some_and(|id| id != WALL) {
+                map.set(SPRITES, player.0, player.1, EMPTY);
+                player = target;
+            }
+            moved = true;
+        }
+        if !moved {
+            continue;
+        }
+
+        // Goblins that can see the player close in along the shortest path; the rest stay put.
+        let mut occupied: HashSet<(isize, isize)> = goblins.iter().copied().collect();
+        for goblin in goblins.iter_mut() {
+            if !visible.contains(goblin.0, goblin.1) {
+                continue;
+            }
+            let passable = |x: isize, y: isize| map.get(TERRAIN, x, y).is_some_and(|id| id != WALL) && ((x, y) == player || !occupied.contains(&(x, y)));
+            let Some(route) = path::astar(*goblin, player, passable, false) else {
+                continue;
+            };
+            match route.get(1) {
+                Some(&next) if next == player => message = "A goblin hits you!".to_string(),
+                Some(&next) => {
+                    map.set(SPRITES, goblin.0, goblin.1, EMPTY);
+                    occupied.remove(goblin);
+                    occupied.insert(next);
+                    *goblin = next;
+                },
+                None => {}
+            }
+        }
+    }
+}
@@ -0,0 +1,84 @@
+// Scrolling world map: a 512 by 512 tile map generated from noise, with a live minimap showing where the camera is.
+use std::io;
+use std::time::Duration;
+use terminalgl::canvas::Canvas;
+use terminalgl::drawc::ColorKind;
+use terminalgl::input::{self, Event, Key};
+use terminalgl::tilemap::TileMap;
+use terminalgl::{drawc, noise, Rect};
+
+
+const SIZE: usize = 512;
+const MINIMAP_WIDTH: usize = 32;
+const MINIMAP_HEIGHT: usize = 16;
+
+/// A kind of terrain: its tile id, the noise value it starts at, its character, and its color.
+struct Terrain {
+    id: u16,
+    from: f64,
+    c: char,
+    rgb: (u8, u8, u8)
+}
+
+// From lowest to highest.
+const TERRAIN: [Terrain; 5] = [
+    Terrain { id: 1, from: 0.0, c: '~', rgb: (0, 0, 205) },
+    Terrain { id: 2, from: 0.42, c: '.', rgb: (205, 205, 0) },
+    Terrain { id: 3, from: 0.47, c: '"', rgb: (0, 205, 0) },
+    Terrain { id: 4, from: 0.6, c: '^', rgb: (127, 127, 127) },
+    Terrain { id: 5, from: 0.7, c: 'A', rgb: (255, 255, 255) }
+];
+
+
+pub fn run() -> io::Result<()> {
+    let mut map = TileMap::new(SIZE, SIZE, 1);
+    for terrain in &TERRAIN {
+        map.define(terrain.id, terrain.c, &drawc::rgb_to_ccode(terrain.rgb, ColorKind::Fg));
+    }
+    for y in 0..SIZE {
+        for x in 0..SIZE {
+            let height = noise::fractal2(x as f64 / 48.0, y as f64 / 24.0, 7, 5);
+            let id = TERRAIN.iter().rev().find(|t| height >= t.from).map_or(1, |t| t.id);
+            map.set(0, x as isize, y as isize, id);
+        }
+    }
+    let color_for_tile = |id: u16| TERRAIN.iter().find(|t| t.id == id).map_or((0, 0, 0), |t| t.rgb);
+
+    let screen = Canvas::screen();
+    let (width, height) = (screen.width(), screen.height());
+    let (mut camera_x, mut camera_y) = ((SIZE - width.min(SIZE)) as isize / 2, (SIZE - height.min(SIZE)) as isize / 2);
+    let mut previous: Option<Canvas> = None;
+    loop {
+        let mut canvas = Canvas::new(width, height);
+        map.render(&mut canvas, Rect::new(0, 0, width, height), camera_x, camera_y);
+
+        // The minimap sits in the top right corner inside a border, with the camera's view outlined.
+        let minimap = Rect::new(width as isize - MINIMAP_WIDTH as isize - 2, 1, MINIMAP_WIDTH, MINIMAP_HEIGHT);
+        canvas.rectangle(minimap.x - 1, minimap.y - 1, MINIMAP_WIDTH + 2, MINIMAP_HEIGHT + 2, '#', drawc::WHITE, false);
+        map.render_minimap(&mut canvas, minimap, color_for_tile);
+        let view = map.minimap_rect(minimap, Rect::new(camera_x, camera_y, width, height));
+        canvas.rectangle(view.x, view.y, view.width, view.height, '+', drawc::BRIGHT_RED, false);
+
+        canvas.text(1, 0, &format!(" World map ({}, {}) - arrow keys to scroll, q to go back ", camera_x, camera_y), drawc::BLACK_BG);
+        match &previous {
+            Some(previous) => canvas.present_diff(previous),
+            None => canvas.present()
+        }
+        previous = Some(canvas);
+
+        for event in input::read_events(Duration::from_millis(100))? {
+            if super::is_back(&event) {
+                return Ok(());
+            }
+            let (dx, dy) = match event {
+                Event::Key(Key::Up | Key::Char('k' | 'w')) => (0, -2),
+                Event::Key(Key::Down | Key::Char('j' | 's')) => (0, 2),
+                Event::Key(Key::Left | Key::Char('h' | 'a')) => (-4, 0),
+                Event::Key(Key::Right | Key::Char('l' | 'd')) => (4, 0),
+                _ => (0, 0)
+            };
+            camera_x = (camera_x + dx).clamp(0, SIZE.saturating_sub(width) as isize);
+            camera_y = (camera_y + dy).clamp(0, SIZE.saturating_sub(height) as isize);
+        }
+    }
+}
@@ -0,0 +1,66 @@
+// Show a small embedded test image as a real image where the terminal supports it (kitty, iTerm2, or sixels), and with half blocks elsewhere.
+// Run with `cargo run --example image --features image`.
+use std::io;
+use terminalgl as tgl;
+use tgl::drawc::{self, ImageOptions};
+
+
+/// A 16 by 16 test card, one character per pixel, with colors from `color`.
+const TEST_CARD: [&str; 16] = [
+    "................",
+    ".RRRRGGGGBBBBWW.",
+    ".RRRRGGGGBBBBWW.",
+    ".RR..GG..BB..WW.",
+    ".RRRRGGGGBBBBWW.",
+    "................",
+    ".YYYYYYYYYYYYYY.",
+    ".Y............Y.",
+    ".Y..KKKKKKKK..Y.",
+    ".Y..K......K..Y.",
+    ".Y..K......K..Y.",
+    ".Y..KKKKKKKK..Y.",
+    ".Y............Y.",
+    ".YYYYYYYYYYYYYY.",
+    "................",
+    "                "
+];
+
+
+/// Get the RGBA color of a test card character. Spaces are transparent.
+fn color(c: char) -> [u8; 4] {
+    match c {
+        'R' => [230, 40, 40, 255],
+        'G' => [40, 200, 60, 255],
+        'B' => [40, 90, 230, 255],
+        'W' => [240, 240, 240, 255],
+        'Y' => [240, 200, 30, 255],
+        'K' => [20, 20, 20, 255],
+        '.' => [90, 90, 110, 255],
+        _ => [0, 0, 0, 0]
+    }
+}
+
+
+fn main() -> io::Result<()> {
+    let rgba: Vec<u8> = TEST_CARD.iter().flat_map(|row| row.chars().flat_map(color)).collect();
+    #[cfg(all(feature = "input", unix))]
+    {
+        let raw = tgl::input::RawMode::enable()?;
+        drawc::set_sixel_support(tgl::input::query_sixel_support(std::time::Duration::from_millis(200))?);
+        drop(raw);
+    }
+
+    tgl::clear();
+    tgl::draw::text(0, 0, &format!("protocol: {:?}", drawc::image_protocol()));
+    let small = drawc::image_native(0, 2, &rgba, 16, 16, ImageOptions { cols: Some(8), ..ImageOptions::default() });
+    let large = drawc::image_native(small.x + small.width as isize + 2, 2, &rgba, 16, 16, ImageOptions { rows: Some(12), ..ImageOptions::default() });
+    let bottom = small.y.max(large.y) + small.height.max(large.height) as isize;
+    tgl::draw::text(0, bottom + 1, "press enter to clear");
+    tgl::cursorto(0, (bottom + 2) as usize);
+    tgl::flush();
+
+    io::stdin().read_line(&mut String::new())?;
+    tgl::clear();
+    tgl::flush();
+    Ok(())
+}
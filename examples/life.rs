@@ -0,0 +1,93 @@
+// Conway's Game of Life filling the terminal at 60 generations a second, printing only the cells that change.
+// Run with `cargo run --release --example life --features input`. Click cells to flip them, Space to pause, n to step while paused,
+// r for a new random grid, w to switch between wrapping and bounded edges, c to clear, Ctrl+L to redraw the screen, and q or Esc to quit.
+// In terminals that report focus, the game pauses and dims while the terminal is in the background.
+#[cfg(unix)]
+use std::io;
+#[cfg(unix)]
+use std::thread;
+#[cfg(unix)]
+use std::time::{Duration, Instant};
+#[cfg(unix)]
+use terminalgl::automata::{Edges, Grid};
+#[cfg(unix)]
+use terminalgl::canvas::{Canvas, Cell};
+#[cfg(unix)]
+use terminalgl::input::{self, Event, Key, Mouse, MouseButton, MouseKind, RawMode};
+#[cfg(unix)]
+use terminalgl::{drawc, fmt, Rect};
+
+
+#[cfg(unix)]
+const FRAME_TIME: Duration = Duration::from_nanos(1_000_000_000 / 60);
+
+/// Share of cells alive in a new random grid.
+#[cfg(unix)]
+const DENSITY: f64 = 0.3;
+
+
+#[cfg(unix)]
+fn main() -> io::Result<()> {
+    let _raw = RawMode::with_mouse()?;
+    let mut canvas = Canvas::screen();
+    // The top row is the status line, and the grid fills the rest.
+    let area = Rect::new(0, 1, canvas.width(), canvas.height().saturating_sub(1));
+    let mut grid = Grid::new(area.width, area.height, Edges::Wrap);
+    let mut seed = 1;
+    grid.randomize(seed, DENSITY);
+    let (alive, dead) = (Cell::new('█', drawc::GREEN), Cell::new(' ', ""));
+    let mut paused = false;
+    let mut focused = true;
+    let mut busy = Duration::ZERO;
+    loop {
+        let start = Instant::now();
+        let mut step = !paused && focused;
+        for event in input::read_events(Duration::ZERO)? {
+            match event {
+                Event::Key(Key::Char('q') | Key::Esc | Key::Ctrl('c')) => return Ok(()),
+                Event::Key(Key::Char(' ')) => paused = !paused,
+                Event::Key(Key::Char('n')) => step = true,
+                Event::Key(Key::Char('r')) => {
+                    seed += 1;
+                    grid.randomize(seed, DENSITY);
+                },
+                Event::Key(Key::Char('w')) => grid.edges = if grid.edges == Edges::Wrap { Edges::Bounded } else { Edges::Wrap },
+                Event::Key(Key::Char('c')) => grid.clear(),
+                Event::Key(Key::Ctrl('l')) => canvas.invalidate(),
+                Event::FocusGained => focused = true,
+                Event::FocusLost => focused = false,
+                Event::Mouse(Mouse { kind: MouseKind::Press(MouseButton::Left), x, y }) => {
+                    let (x, y) = (x - area.x, y - area.y);
+                    grid.set(x, y, !grid.is_alive(x, y) as u8);
+                },
+                _ => {}
+            }
+        }
+        if step {
+            grid.step();
+        }
+
+        grid.render(&mut canvas, area, &alive, &dead);
+        let status = format!(
+            " Life - generation {}, {} alive, {:?} edges, {} per frame{} - Space pause, n step, r random, w edges, c clear, q quit",
+            grid.generation(), grid.population(), grid.edges, fmt::duration(busy), if paused || !focused { ", paused" } else { "" }
+        );
+        canvas.rectangle(0, 0, canvas.width(), 1, ' ', drawc::BLACK_BG, true);
+        canvas.text(0, 0, &status, &(drawc::BRIGHT_WHITE.to_string() + drawc::BLACK_BG));
+        if !focused {
+            canvas.darken(0.5);
+        }
+        // Only the cells that changed since the last frame are printed.
+        let damage = canvas.take_damage();
+        canvas.present_rects(&damage);
+
+        busy = start.elapsed();
+        thread::sleep(FRAME_TIME.saturating_sub(busy));
+    }
+}
+
+
+#[cfg(not(unix))]
+fn main() {
+    eprintln!("The life example reads keys and the mouse through termios, so it needs a Unix terminal.");
+}
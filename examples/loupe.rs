@@ -0,0 +1,104 @@
+// A magnifying loupe that follows the mouse over a scene, showing the cells around the pointer zoomed in.
+// Run with `cargo run --example loupe --features input`. Move the mouse to move the loupe, + and - to change the zoom,
+// a to switch between square and plain blocks, and q or Esc to quit.
+// The loupe is an overlay: the cells it covers are saved with `snapshot` and put back with `restore` before it moves.
+#[cfg(unix)]
+use std::io;
+#[cfg(unix)]
+use std::time::Duration;
+#[cfg(unix)]
+use terminalgl::canvas::{Canvas, Snapshot};
+#[cfg(unix)]
+use terminalgl::chars::LineStyle;
+#[cfg(unix)]
+use terminalgl::input::{self, Event, Key, Mouse, RawMode};
+#[cfg(unix)]
+use terminalgl::{drawc, Rect};
+
+
+/// Size of the loupe, border included.
+#[cfg(unix)]
+const LOUPE: (usize, usize) = (34, 13);
+
+
+/// Draw something worth magnifying: colored text, a gradient and a few shapes.
+#[cfg(unix)]
+fn draw_scene(canvas: &mut Canvas) {
+    for x in 0..canvas.width() {
+        let shade = (x * 255 / canvas.width().max(1)) as u8;
+        let ccode = drawc::rgb_to_ccode((shade, 80, 255 - shade), drawc::ColorKind::Fg);
+        canvas.line(x as isize, 0, x as isize, canvas.height() as isize - 1, '░', &ccode);
+    }
+    canvas.ellipse(20, 12, 14, 7, '@', drawc::YELLOW, true);
+    canvas.rectangle(44, 4, 20, 10, '#', drawc::RED, true);
+    canvas.text(2, 1, "Move the mouse to look closer. +/- zoom, a aspect, q quit", drawc::BRIGHT_WHITE);
+    canvas.text(46, 8, "small print", drawc::BRIGHT_WHITE);
+}
+
+
+#[cfg(unix)]
+fn main() -> io::Result<()> {
+    let _raw = RawMode::with_mouse_motion()?;
+    let mut canvas = Canvas::screen();
+    draw_scene(&mut canvas);
+    let mut factor = 2;
+    let mut square = true;
+    let mut pointer = None;
+    // The cells under the loupe, to put back when it moves.
+    let mut under: Option<Snapshot> = None;
+    canvas.present();
+    loop {
+        let mut moved = false;
+        for event in input::read_events(Duration::from_millis(50))? {
+            match event {
+                Event::Key(Key::Char('q') | Key::Esc | Key::Ctrl('c')) => return Ok(()),
+                Event::Key(Key::Char('+' | '=')) => factor = (factor + 1).min(6),
+                Event::Key(Key::Char('-')) => factor = (factor - 1).max(1),
+                Event::Key(Key::Char('a')) => square = !square,
+                Event::Mouse(Mouse { x, y, .. }) => pointer = Some((x, y)),
+                _ => continue
+            }
+            moved = true;
+        }
+        let Some((x, y)) = pointer else {
+            continue;
+        };
+        if !moved {
+            continue;
+        }
+
+        if let Some(under) = under.take() {
+            canvas.restore(&under);
+        }
+        // Keep the loupe below and to the right of the pointer, flipping to the other side near the edges.
+        let (w, h) = (LOUPE.0 as isize, LOUPE.1 as isize);
+        let lx = if x + 2 + w <= canvas.width() as isize { x + 2 } else { x - 2 - w };
+        let ly = if y + 1 + h <= canvas.height() as isize { y + 1 } else { y - 1 - h };
+        let loupe = Rect::new(lx, ly, LOUPE.0, LOUPE.1);
+        under = Some(canvas.snapshot(loupe));
+
+        // The source is the block of cells around the pointer that fills the inside of the loupe once magnified.
+        // It is copied before anything is drawn, so it can overlap the loupe.
+        let inside = Rect::new(lx + 1, ly + 1, LOUPE.0 - 2, LOUPE.1 - 2);
+        let scale = (if square { factor * 2 } else { factor }, factor);
+        let (cols, rows) = (inside.width.div_ceil(scale.0), inside.height.div_ceil(scale.1));
+        let src = Rect::new(x - cols as isize / 2, y - rows as isize / 2, cols, rows);
+        if square {
+            canvas.render_zoomed_square(src, inside, factor);
+        }
+        else {
+            canvas.render_zoomed(src, inside, factor);
+        }
+        canvas.border(loupe, LineStyle::Rounded, drawc::BRIGHT_WHITE);
+        canvas.text(lx + 2, ly, &format!(" {}x ", factor), drawc::BRIGHT_WHITE);
+
+        let damage = canvas.take_damage();
+        canvas.present_rects(&damage);
+    }
+}
+
+
+#[cfg(not(unix))]
+fn main() {
+    eprintln!("The loupe example reads the mouse through termios, so it needs a Unix terminal.");
+}
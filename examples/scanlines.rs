@@ -0,0 +1,90 @@
+// Moving color bands shown through a CRT-style filter that darkens every other row, set with `Canvas::set_row_filter`.
+// Run with `cargo run --release --example scanlines --features input`. Press s to turn the scanlines on and off, + and - to change how dark
+// they are, and q or Esc to quit. The filter only changes what is printed: the canvas keeps the colors as drawn.
+#[cfg(unix)]
+use std::io;
+#[cfg(unix)]
+use std::sync::atomic::{AtomicU32, Ordering};
+#[cfg(unix)]
+use std::sync::Arc;
+#[cfg(unix)]
+use std::thread;
+#[cfg(unix)]
+use std::time::{Duration, Instant};
+#[cfg(unix)]
+use terminalgl::canvas::Canvas;
+#[cfg(unix)]
+use terminalgl::drawc::{self, ColorKind};
+#[cfg(unix)]
+use terminalgl::input::{self, Event, Key, RawMode};
+
+
+#[cfg(unix)]
+const FRAME_TIME: Duration = Duration::from_nanos(1_000_000_000 / 30);
+
+
+/// Set `canvas` to darken its odd rows by the percentage in `darkness`, read each time it is presented.
+#[cfg(unix)]
+fn scanlines(canvas: &mut Canvas, darkness: Arc<AtomicU32>) {
+    canvas.set_row_filter(move |y, row| {
+        if y % 2 == 1 {
+            let amount = darkness.load(Ordering::Relaxed) as f64 / 100.0;
+            for cell in row {
+                cell.ccode = drawc::darken(&cell.ccode, amount);
+            }
+        }
+    });
+}
+
+
+#[cfg(unix)]
+fn main() -> io::Result<()> {
+    let _raw = RawMode::enable()?;
+    let mut canvas = Canvas::screen();
+    let mut previous = canvas.clone();
+    let darkness = Arc::new(AtomicU32::new(50));
+    let mut filtered = true;
+    scanlines(&mut canvas, darkness.clone());
+    let start = Instant::now();
+    loop {
+        let frame_start = Instant::now();
+        for event in input::read_events(Duration::ZERO)? {
+            match event {
+                Event::Key(Key::Char('q') | Key::Esc | Key::Ctrl('c')) => return Ok(()),
+                Event::Key(Key::Char('s')) => {
+                    filtered = !filtered;
+                    if filtered { scanlines(&mut canvas, darkness.clone()) } else { canvas.clear_filters() }
+                },
+                Event::Key(Key::Char('+' | '=')) => {
+                    let _ = darkness.fetch_update(Ordering::Relaxed, Ordering::Relaxed, |d| Some((d + 10).min(100)));
+                },
+                Event::Key(Key::Char('-')) => {
+                    let _ = darkness.fetch_update(Ordering::Relaxed, Ordering::Relaxed, |d| Some(d.saturating_sub(10)));
+                },
+                _ => {}
+            }
+        }
+
+        let t = start.elapsed().as_secs_f64();
+        for y in 0..canvas.height() {
+            for x in 0..canvas.width() {
+                let hue = (x as f64 * 4.0 + y as f64 * 2.0 + t * 90.0) % 360.0;
+                let ccode = drawc::rgb_to_ccode(drawc::hsv_to_rgb(hue, 0.8, 1.0), ColorKind::Bg);
+                canvas.set(x as isize, y as isize, ' ', &ccode);
+            }
+        }
+        let status = format!(" scanlines {} at {}% - s toggle, +/- darkness, q quit ", if filtered { "on" } else { "off" }, darkness.load(Ordering::Relaxed));
+        canvas.text(1, 1, &status, &(drawc::BRIGHT_WHITE.to_string() + drawc::BLACK_BG));
+        // Both canvases are compared as filtered, so only cells whose printed colors changed are printed.
+        canvas.present_diff(&previous);
+        previous = canvas.clone();
+
+        thread::sleep(FRAME_TIME.saturating_sub(frame_start.elapsed()));
+    }
+}
+
+
+#[cfg(not(unix))]
+fn main() {
+    eprintln!("The scanlines example reads keys through termios, so it needs a Unix terminal.");
+}
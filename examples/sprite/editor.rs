@@ -0,0 +1,468 @@
+// The editor: the sprite and its undo history, the panels around it, and what the mouse and keys do to them.
+use std::fs;
+use std::io;
+use std::time::{Duration, Instant};
+use terminalgl::canvas::{Canvas, Cell, Snapshot};
+use terminalgl::chars::LineStyle;
+use terminalgl::drawc::{self, ColorKind, NamedColor};
+use terminalgl::input::{self, Event, Key, Mouse, MouseButton, MouseKind};
+use terminalgl::keymap::KeyMap;
+use terminalgl::ui::{HitGrid, ToastManager};
+use terminalgl::{ansi, fmt, Anchor, Rect};
+
+
+/// Characters to draw with, picked in turn with `b`.
+const BRUSHES: [char; 6] = ['█', '▓', '▒', '░', '#', '*'];
+
+/// Most steps that can be undone.
+const MAX_UNDO: usize = 200;
+
+/// How long messages stay up.
+const TOAST_TIME: Duration = Duration::from_secs(3);
+
+/// Top left corner of the sprite on the screen, inside the border of its panel.
+const SPRITE_AT: (isize, isize) = (1, 2);
+
+/// Width of the panels to the right of the sprite.
+const SIDE_WIDTH: usize = 26;
+
+/// Colors of the panel borders and labels.
+const BORDER: &str = drawc::BRIGHT_BLACK;
+const LABEL: &str = drawc::WHITE;
+
+
+/// What a drag over the sprite does.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Tool {
+    Pixel,
+    Line,
+    Rect,
+    Ellipse,
+    Fill
+}
+
+impl Tool {
+    const ALL: [Tool; 5] = [Tool::Pixel, Tool::Line, Tool::Rect, Tool::Ellipse, Tool::Fill];
+
+    fn name(self) -> &'static str {
+        match self {
+            Tool::Pixel => "Pixel",
+            Tool::Line => "Line",
+            Tool::Rect => "Rectangle",
+            Tool::Ellipse => "Ellipse",
+            Tool::Fill => "Flood fill"
+        }
+    }
+
+    /// The key that picks the tool.
+    fn key(self) -> char {
+        match self {
+            Tool::Pixel => 'p',
+            Tool::Line => 'l',
+            Tool::Rect => 'r',
+            Tool::Ellipse => 'e',
+            Tool::Fill => 'f'
+        }
+    }
+}
+
+
+/// Everything on the screen the mouse can click.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Target {
+    Sprite,
+    Tool(Tool),
+    Color(NamedColor),
+    Rgb
+}
+
+
+/// Everything the keys do.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Action {
+    Tool(Tool),
+    Brush,
+    Rgb,
+    Undo,
+    Redo,
+    Save,
+    Reload,
+    Redraw,
+    Help,
+    Quit
+}
+
+
+/// A drag over the sprite, in sprite cells: where it started and last was, the sprite before it, and whether it erases.
+struct Stroke {
+    start: (isize, isize),
+    last: (isize, isize),
+    before: Snapshot,
+    erase: bool
+}
+
+
+pub struct Editor {
+    sprite: Canvas,
+    path: String,
+    tool: Tool,
+    color: String,
+    brush: usize,
+    undo: Vec<Snapshot>,
+    redo: Vec<Snapshot>,
+    stroke: Option<Stroke>,
+    /// The hex digits typed so far while entering an rgb color.
+    rgb: Option<String>,
+    /// The sprite cell under the mouse.
+    hover: Option<(isize, isize)>,
+    help: bool,
+    /// Whether the whole screen should be printed again, after something else printed over it.
+    redraw: bool,
+    hits: HitGrid<Target>,
+    keys: KeyMap<Action>,
+    toasts: ToastManager
+}
+
+impl Editor {
+    /// Open the sprite saved at `path`, read `size.0` cells wide, or start a blank one of `size` if there is no such file.
+    pub fn open(path: &str, size: (usize, usize)) -> io::Result<Editor> {
+        let mut editor = Editor {
+            sprite: Canvas::new(size.0, size.1),
+            path: path.to_string(),
+            tool: Tool::Pixel,
+            color: drawc::BRIGHT_WHITE.to_string(),
+            brush: 0,
+            undo: Vec::new(),
+            redo: Vec::new(),
+            stroke: None,
+            rgb: None,
+            hover: None,
+            help: false,
+            redraw: false,
+            hits: HitGrid::new(),
+            keys: key_map(),
+            toasts: ToastManager::new(Anchor::BottomRight)
+        };
+        match fs::read_to_string(path) {
+            Ok(text) => editor.load(&text),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => editor.message(&format!("New sprite, saved to {} with Ctrl+S", path), ""),
+            Err(err) => return Err(err)
+        }
+        Ok(editor)
+    }
+
+    /// Edit until the user quits.
+    pub fn run(mut self) -> io::Result<()> {
+        let mut previous: Option<Canvas> = None;
+        let mut last = Instant::now();
+        loop {
+            let now = Instant::now();
+            self.toasts.update(now.duration_since(last).as_secs_f64());
+            last = now;
+
+            let screen = self.render();
+            match &previous {
+                // After a resize, the whole screen has to be drawn again.
+                Some(previous) if previous.rect() == screen.rect() => screen.present_diff(previous),
+                _ => screen.present()
+            }
+            previous = Some(screen);
+
+            for event in input::read_events(Duration::from_millis(50))? {
+                let running = match event {
+                    Event::Mouse(mouse) => {
+                        self.handle_mouse(mouse);
+                        true
+                    },
+                    event => self.handle_key(&event)
+                };
+                if !running {
+                    return Ok(());
+                }
+            }
+            if let (true, Some(previous)) = (std::mem::take(&mut self.redraw), &mut previous) {
+                previous.invalidate();
+            }
+        }
+    }
+
+    fn message(&mut self, text: &str, ccode: &str) {
+        self.toasts.toast(text, ccode, TOAST_TIME);
+    }
+
+    /// Replace the sprite with the one in ANSI text `text`, as wide as the sprite and at least as tall. This can't be undone.
+    fn load(&mut self, text: &str) {
+        let (width, height) = (self.sprite.width(), self.sprite.height());
+        let mut sprite = ansi::parse(text, width);
+        sprite.resize(width, sprite.height().max(height), Anchor::TopLeft, Cell::default());
+        self.sprite = sprite;
+        self.undo.clear();
+        self.redo.clear();
+        self.message(&format!("Loaded {} ({})", self.path, fmt::bytes(text.len() as u64)), "");
+    }
+
+    /// Write the sprite to its file as ANSI text.
+    fn save(&mut self) {
+        let text = self.sprite.to_ansi();
+        match fs::write(&self.path, &text) {
+            Ok(()) => self.message(&format!("Saved {} ({})", self.path, fmt::bytes(text.len() as u64)), ""),
+            Err(err) => self.message(&format!("Couldn't save {}: {}", self.path, err), drawc::BRIGHT_RED)
+        }
+    }
+
+    /// Read the sprite back from its file, throwing away changes since it was saved.
+    fn reload(&mut self) {
+        match fs::read_to_string(&self.path) {
+            Ok(text) => self.load(&text),
+            Err(err) => self.message(&format!("Couldn't read {}: {}", self.path, err), drawc::BRIGHT_RED)
+        }
+    }
+
+    /// Save the sprite as it is now so the next change can be undone.
+    fn checkpoint(&mut self) {
+        self.undo.push(self.sprite.snapshot(self.sprite.rect()));
+        if self.undo.len() > MAX_UNDO {
+            self.undo.remove(0);
+        }
+        self.redo.clear();
+    }
+
+    fn undo(&mut self) {
+        if let Some(snapshot) = self.undo.pop() {
+            self.redo.push(self.sprite.snapshot(self.sprite.rect()));
+            self.sprite.restore(&snapshot);
+        }
+    }
+
+    fn redo(&mut self) {
+        if let Some(snapshot) = self.redo.pop() {
+            self.undo.push(self.sprite.snapshot(self.sprite.rect()));
+            self.sprite.restore(&snapshot);
+        }
+    }
+
+    /// Draw with the current tool from `from` to `to`, in sprite cells, with the brush or with blanks.
+    fn draw(&mut self, from: (isize, isize), to: (isize, isize), erase: bool) {
+        let (c, ccode) = match erase {
+            true => (' ', ""),
+            false => (BRUSHES[self.brush], self.color.as_str())
+        };
+        let rect = Rect::spanning(from, to);
+        match self.tool {
+            Tool::Pixel | Tool::Line => {
+                self.sprite.line(from.0, from.1, to.0, to.1, c, ccode);
+            },
+            Tool::Rect => {
+                self.sprite.rectangle(rect.x, rect.y, rect.width, rect.height, c, ccode, false);
+            },
+            Tool::Ellipse => {
+                let (h, k) = (rect.x + rect.width as isize / 2, rect.y + rect.height as isize / 2);
+                self.sprite.ellipse(h, k, rect.width / 2, rect.height / 2, c, ccode, false);
+            },
+            Tool::Fill => {
+                self.sprite.flood_fill(to.0, to.1, c, ccode);
+            }
+        }
+    }
+
+    fn handle_mouse(&mut self, mouse: Mouse) {
+        let at = (mouse.x - SPRITE_AT.0, mouse.y - SPRITE_AT.1);
+        self.hover = self.sprite.rect().contains(at.0, at.1).then_some(at);
+        match mouse.kind {
+            MouseKind::Press(button) => match self.hits.hit_test(mouse.x, mouse.y) {
+                Some(Target::Sprite) if button != MouseButton::Middle => {
+                    self.checkpoint();
+                    let before = self.sprite.snapshot(self.sprite.rect());
+                    let erase = button == MouseButton::Right;
+                    self.draw(at, at, erase);
+                    self.stroke = Some(Stroke { start: at, last: at, before, erase });
+                },
+                Some(Target::Tool(tool)) => self.tool = tool,
+                Some(Target::Color(color)) => self.color = color.fg_code().to_string(),
+                Some(Target::Rgb) => self.rgb = Some(String::new()),
+                _ => {}
+            },
+            MouseKind::Drag(_) => {
+                let Some(stroke) = &mut self.stroke else {
+                    return;
+                };
+                let (start, last, erase) = (stroke.start, stroke.last, stroke.erase);
+                stroke.last = at;
+                match self.tool {
+                    // Fast drags skip cells, so join them up with a line from the last one.
+                    Tool::Pixel => self.draw(last, at, erase),
+                    Tool::Fill => {},
+                    // Shapes follow the mouse: put back the sprite from before the drag and draw the shape again.
+                    _ => {
+                        let before = stroke.before.clone();
+                        self.sprite.restore(&before);
+                        self.draw(start, at, erase);
+                    }
+                }
+            },
+            MouseKind::Release => {
+                // A stroke that changed nothing, like filling an area with its own color, isn't worth an undo step.
+                if let Some(stroke) = self.stroke.take() {
+                    if self.sprite.snapshot(self.sprite.rect()) == stroke.before {
+                        self.undo.pop();
+                    }
+                }
+            },
+            _ => {}
+        }
+    }
+
+    /// Apply a key press, and get whether to keep editing.
+    fn handle_key(&mut self, event: &Event) -> bool {
+        if let Some(hex) = &mut self.rgb {
+            match event {
+                Event::Key(Key::Char(c)) if c.is_ascii_hexdigit() && hex.len() < 6 => hex.push(*c),
+                Event::Key(Key::Backspace) => {
+                    hex.pop();
+                },
+                Event::Key(Key::Enter) => {
+                    match parse_hex(hex) {
+                        Some(rgb) => self.color = drawc::rgb_to_ccode(rgb, ColorKind::Fg),
+                        None => self.message("An rgb color needs 6 hex digits, like ff8000", drawc::BRIGHT_RED)
+                    }
+                    self.rgb = None;
+                },
+                Event::Key(Key::Esc) => self.rgb = None,
+                _ => {}
+            }
+            return true;
+        }
+        if matches!(event, Event::Key(Key::Esc | Key::Ctrl('c')) | Event::Interrupt) {
+            if self.help {
+                self.help = false;
+                return true;
+            }
+            return false;
+        }
+        let Some(&action) = self.keys.lookup(event) else {
+            return true;
+        };
+        match action {
+            Action::Tool(tool) => self.tool = tool,
+            Action::Brush => self.brush = (self.brush + 1) % BRUSHES.len(),
+            Action::Rgb => self.rgb = Some(String::new()),
+            Action::Undo => self.undo(),
+            Action::Redo => self.redo(),
+            Action::Save => self.save(),
+            Action::Reload => self.reload(),
+            Action::Redraw => self.redraw = true,
+            Action::Help => self.help = !self.help,
+            Action::Quit => return false
+        }
+        true
+    }
+
+    /// Draw the screen: the sprite in its panel, the tools and colors beside it, and a status line, registering
+    /// everything clickable in the hit grid.
+    fn render(&mut self) -> Canvas {
+        let mut screen = Canvas::screen();
+        self.hits.clear();
+        let (width, height) = (self.sprite.width(), self.sprite.height());
+
+        screen.rectangle(0, 0, screen.width(), 1, ' ', drawc::BLACK_BG, true);
+        screen.text(1, 0, &format!("Sprite editor - {} ({}x{})", self.path, width, height), drawc::BLACK_BG);
+
+        // The sprite over a grid of dots, so the cells nothing is drawn in still show.
+        let panel = Rect::new(SPRITE_AT.0 - 1, SPRITE_AT.1 - 1, width + 2, height + 2);
+        let area = Rect::new(SPRITE_AT.0, SPRITE_AT.1, width, height);
+        screen.border(panel, LineStyle::Rounded, BORDER);
+        screen.text(panel.x + 2, panel.y, " Sprite ", LABEL);
+        screen.rectangle(area.x, area.y, width, height, '·', BORDER, true);
+        screen.paste(&self.sprite.snapshot(self.sprite.rect()), area.x, area.y, 0.0);
+        self.hits.add_rect(area, Target::Sprite);
+        if let (Some((x, y)), None) = (self.hover, &self.stroke) {
+            let (x, y) = (area.x + x, area.y + y);
+            if let Some(cell) = screen.get(x, y) {
+                let ccode = screen.ccode_at(x, y).unwrap_or("").to_string() + "\x1b[7m";
+                screen.set(x, y, cell.c, &ccode);
+            }
+        }
+
+        let side = panel.x + panel.width as isize + 1;
+        let tools = Rect::new(side, 1, SIDE_WIDTH, Tool::ALL.len() + 2);
+        screen.border(tools, LineStyle::Rounded, BORDER);
+        screen.text(tools.x + 2, tools.y, " Tools ", LABEL);
+        for (i, tool) in Tool::ALL.into_iter().enumerate() {
+            let row = Rect::new(tools.x + 1, tools.y + 1 + i as isize, SIDE_WIDTH - 2, 1);
+            let ccode = if tool == self.tool { drawc::BLACK.to_string() + drawc::WHITE_BG } else { LABEL.to_string() };
+            screen.text(row.x, row.y, &format!(" {} {:<w$}", tool.key(), tool.name(), w = row.width - 3), &ccode);
+            self.hits.add_rect(row, Target::Tool(tool));
+        }
+
+        let colors = Rect::new(side, tools.y + tools.height as isize, SIDE_WIDTH, 7);
+        screen.border(colors, LineStyle::Rounded, BORDER);
+        screen.text(colors.x + 2, colors.y, " Colors ", LABEL);
+        for (i, color) in NamedColor::ALL.into_iter().enumerate() {
+            let swatch = Rect::new(colors.x + 2 + 3 * (i % 8) as isize, colors.y + 1 + (i / 8) as isize, 2, 1);
+            // The chosen color is marked with brackets, in black or white, whichever shows up on it.
+            let (r, g, b) = color.to_rgb();
+            let mark = if r as u32 * 299 + g as u32 * 587 + b as u32 * 114 > 128_000 { drawc::BLACK } else { drawc::BRIGHT_WHITE };
+            let label = if self.color == color.fg_code() { "[]" } else { "  " };
+            screen.text(swatch.x, swatch.y, label, &(mark.to_string() + color.bg_code()));
+            self.hits.add_rect(swatch, Target::Color(color));
+        }
+        let field = Rect::new(colors.x + 2, colors.y + 3, SIDE_WIDTH - 4, 1);
+        let rgb = match &self.rgb {
+            Some(hex) => format!("RGB #{}_", hex),
+            None => match drawc::ccode_to_rgb(&self.color).0 {
+                Some((r, g, b)) => format!("RGB #{:02x}{:02x}{:02x}", r, g, b),
+                None => "RGB #".to_string()
+            }
+        };
+        let field_ccode = if self.rgb.is_some() { drawc::BRIGHT_WHITE.to_string() + drawc::BLUE_BG } else { LABEL.to_string() };
+        screen.text(field.x, field.y, &format!("{:<w$}", rgb, w = field.width), &field_ccode);
+        self.hits.add_rect(field, Target::Rgb);
+        screen.text(colors.x + 2, colors.y + 5, "Brush", LABEL);
+        let brush: String = [BRUSHES[self.brush]; 3].iter().collect();
+        screen.text(colors.x + 8, colors.y + 5, &brush, &self.color);
+
+        let status = match self.hover {
+            Some((x, y)) => format!("({}, {})", x, y),
+            None => String::new()
+        };
+        let bottom = screen.height() as isize - 1;
+        screen.text(1, bottom, &format!("{:<12}{} - left drag draws, right drag erases, ? for keys", status, self.tool.name()), BORDER);
+
+        if self.help {
+            let help = screen.rect().anchor(Anchor::Center, (44, self.keys.len() + 2), 0);
+            self.keys.render_help(&mut screen, help, 0, "");
+        }
+        self.toasts.render(&mut screen);
+        screen
+    }
+}
+
+
+/// The keys of the editor, described for its help screen.
+fn key_map() -> KeyMap<Action> {
+    let mut keys = KeyMap::new();
+    let mut bind = |key, action, description: &str| keys.bind(key, action, description).expect("every key is bound once");
+    for tool in Tool::ALL {
+        bind(Key::Char(tool.key()), Action::Tool(tool), &format!("{} tool", tool.name()));
+    }
+    bind(Key::Char('b'), Action::Brush, "Next brush character");
+    bind(Key::Char('#'), Action::Rgb, "Type an rgb color");
+    bind(Key::Ctrl('z'), Action::Undo, "Undo");
+    bind(Key::Ctrl('y'), Action::Redo, "Redo");
+    bind(Key::Ctrl('s'), Action::Save, "Save");
+    bind(Key::Ctrl('o'), Action::Reload, "Reload from the file");
+    bind(Key::Ctrl('l'), Action::Redraw, "Redraw the screen");
+    bind(Key::Char('?'), Action::Help, "Show or hide these keys");
+    bind(Key::Char('q'), Action::Quit, "Quit (also Esc)");
+    keys
+}
+
+
+/// Read a color written as 6 hex digits, like `ff8000`.
+fn parse_hex(hex: &str) -> Option<(u8, u8, u8)> {
+    if hex.len() != 6 || !hex.is_ascii() {
+        return None;
+    }
+    let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).ok();
+    Some((channel(0)?, channel(2)?, channel(4)?))
+}
@@ -0,0 +1,34 @@
+// Sprite editor: draw ANSI art with the mouse, pick colors, undo and redo, and save it as ANSI text.
+// Run with `cargo run --example sprite --features input,widgets -- art.ans [width height]`. The file is loaded if it exists, and written on Ctrl+S.
+#[cfg(unix)]
+mod editor;
+
+#[cfg(unix)]
+use std::env;
+#[cfg(unix)]
+use std::io;
+#[cfg(unix)]
+use terminalgl::input::RawMode;
+
+
+/// Size of a new sprite, and the width files are read with, unless given on the command line.
+#[cfg(unix)]
+const DEFAULT_SIZE: (usize, usize) = (32, 16);
+
+
+#[cfg(unix)]
+fn main() -> io::Result<()> {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let path = args.first().cloned().unwrap_or_else(|| "sprite.ans".to_string());
+    let number = |i: usize, default: usize| args.get(i).and_then(|arg| arg.parse().ok()).filter(|&n| n > 0).unwrap_or(default);
+    let size = (number(1, DEFAULT_SIZE.0), number(2, DEFAULT_SIZE.1));
+
+    let _raw = RawMode::with_mouse_motion()?;
+    editor::Editor::open(&path, size)?.run()
+}
+
+
+#[cfg(not(unix))]
+fn main() {
+    eprintln!("The sprite editor reads keys and the mouse through termios, so it needs a Unix terminal.");
+}
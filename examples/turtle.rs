@@ -0,0 +1,110 @@
+// L-system fractals drawn with turtle graphics: a branching plant, a dragon curve and a Koch snowflake, scaled to fill the terminal.
+// Run with `cargo run --example turtle --features input`. Press Tab to switch fractals, + and - to change the depth, and q or Esc to quit.
+#[cfg(unix)]
+use std::io;
+#[cfg(unix)]
+use std::time::Duration;
+#[cfg(unix)]
+use terminalgl::canvas::Canvas;
+#[cfg(unix)]
+use terminalgl::drawc;
+#[cfg(unix)]
+use terminalgl::input::{self, Event, Key, RawMode};
+#[cfg(unix)]
+use terminalgl::lsystem::{self, Spec};
+#[cfg(unix)]
+use terminalgl::Rect;
+
+
+/// An L-system, how to draw it, and how deep to start and let it go.
+#[cfg(unix)]
+struct Fractal {
+    name: &'static str,
+    axiom: &'static str,
+    rules: &'static [(char, &'static str)],
+    angle: f64,
+    heading: f64,
+    depth: usize,
+    max_depth: usize,
+    color: &'static str
+}
+
+
+#[cfg(unix)]
+const FRACTALS: [Fractal; 3] = [
+    Fractal {
+        name: "Fractal plant",
+        axiom: "X",
+        rules: &[('X', "F+[[X]-X]-F[-FX]+X"), ('F', "FF")],
+        angle: 25.0,
+        heading: 65.0,
+        depth: 5,
+        max_depth: 7,
+        color: drawc::GREEN
+    },
+    Fractal {
+        name: "Dragon curve",
+        axiom: "FX",
+        rules: &[('X', "X+YF+"), ('Y', "-FX-Y")],
+        angle: 90.0,
+        heading: 0.0,
+        depth: 10,
+        max_depth: 16,
+        color: drawc::BRIGHT_CYAN
+    },
+    Fractal {
+        name: "Koch snowflake",
+        axiom: "F--F--F",
+        rules: &[('F', "F+F--F+F")],
+        angle: 60.0,
+        heading: 0.0,
+        depth: 3,
+        max_depth: 6,
+        color: drawc::BRIGHT_WHITE
+    }
+];
+
+/// Terminal cells are about twice as tall as they are wide.
+#[cfg(unix)]
+const ASPECT: f64 = 0.5;
+
+
+/// Draw `fractal` at `depth`, scaled and moved to fill `canvas` below its top row.
+#[cfg(unix)]
+fn draw(canvas: &mut Canvas, fractal: &Fractal, depth: usize) {
+    let spec = Spec { heading: fractal.heading, aspect: ASPECT, c: '•', ccode: fractal.color, ..Spec::new(fractal.axiom, fractal.rules, depth, fractal.angle) };
+    let area = Rect::new(1, 2, canvas.width().saturating_sub(2), canvas.height().saturating_sub(3));
+    lsystem::draw_fit(canvas, area, &spec);
+}
+
+
+#[cfg(unix)]
+fn main() -> io::Result<()> {
+    let _raw = RawMode::enable()?;
+    let mut which = 0;
+    let mut depths: Vec<usize> = FRACTALS.iter().map(|fractal| fractal.depth).collect();
+    loop {
+        let fractal = &FRACTALS[which];
+        let mut canvas = Canvas::screen();
+        draw(&mut canvas, fractal, depths[which]);
+        let status = format!(" {}, depth {} - Tab switch, + and - depth, q quit", fractal.name, depths[which]);
+        canvas.text(0, 0, &status, drawc::BRIGHT_WHITE);
+        canvas.present();
+
+        for event in input::read_events(Duration::from_secs(3600))? {
+            match event {
+                Event::Key(Key::Char('q') | Key::Esc | Key::Ctrl('c')) => return Ok(()),
+                Event::Key(Key::Tab) => which = (which + 1) % FRACTALS.len(),
+                Event::Key(Key::Char('+' | '=')) => depths[which] = (depths[which] + 1).min(fractal.max_depth),
+                Event::Key(Key::Char('-')) => depths[which] = depths[which].saturating_sub(1),
+                _ => {}
+            }
+        }
+    }
+}
+
+
+#[cfg(not(unix))]
+fn main() {
+    eprintln!("The turtle example reads keys through termios, so it needs a Unix terminal.");
+}
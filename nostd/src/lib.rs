@@ -0,0 +1,21 @@
+// Draws with `terminalgl::core` in a `no_std` crate, as on an embedded device with a character LCD.
+#![no_std]
+use terminalgl::core::{self, Buffer};
+
+
+/// Draw a diagonal line from corner to corner of a 16 by 8 character buffer.
+///
+/// Example
+/// ```
+/// let lcd = terminalgl_nostd::diagonal();
+/// assert_eq!(lcd[0][0], '*');
+/// assert_eq!(lcd[7][15], '*');
+/// assert_eq!(lcd.iter().flatten().filter(|&&c| c == '*').count(), 16);
+/// assert_eq!(lcd[3].iter().collect::<String>(), "      **        ");
+/// ```
+pub fn diagonal() -> [[char; 16]; 8] {
+    let mut lcd = [[' '; 16]; 8];
+    let clip = lcd.rect();
+    core::plot(&mut lcd, core::line_clipped(0, 0, 15, 7, clip), '*');
+    lcd
+}
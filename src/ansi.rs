@@ -0,0 +1,211 @@
+// Read text with ANSI escape sequences back into cells.
+use super::canvas::{Canvas, Cell};
+
+
+/// Most rows `parse` will produce. Text positioned below this is dropped.
+pub const MAX_ROWS: usize = 1000;
+
+
+/// Graphic state built up from SGR sequences.
+/// Colors are kept as the parameters that select them (e.g. `31`, `38;5;208` or `38;2;255;0;0`).
+#[derive(Clone, Default)]
+struct Style {
+    bold: bool,
+    fg: Option<String>,
+    bg: Option<String>
+}
+
+impl Style {
+    /// Apply the parameters of an SGR sequence. Unknown or incomplete parameters are skipped.
+    fn apply(&mut self, params: &str) {
+        let numbers: Vec<Option<u32>> = params.split(';').map(|p| if p.is_empty() { Some(0) } else { p.parse().ok() }).collect();
+        let mut i = 0;
+        while i < numbers.len() {
+            let n = match numbers[i] {
+                Some(n) => n,
+                None => {
+                    i += 1;
+                    continue;
+                }
+            };
+            match n {
+                0 => *self = Style::default(),
+                1 => self.bold = true,
+                22 => self.bold = false,
+                30..=37 | 90..=97 => self.fg = Some(n.to_string()),
+                40..=47 | 100..=107 => self.bg = Some(n.to_string()),
+                39 => self.fg = None,
+                49 => self.bg = None,
+                38 | 48 => {
+                    let color = match numbers.get(i + 1) {
+                        Some(Some(5)) => extended_color(&numbers[i + 2..], 1),
+                        Some(Some(2)) => extended_color(&numbers[i + 2..], 3),
+                        _ => None
+                    };
+                    let Some((color, used)) = color else {
+                        return;
+                    };
+                    let color = Some(format!("{};{}", n, color));
+                    if n == 38 { self.fg = color } else { self.bg = color }
+                    i += used;
+                },
+                _ => {}
+            }
+            i += 1;
+        }
+    }
+
+    /// Get the color code that draws with this style, or an empty string for the default style.
+    fn ccode(&self) -> String {
+        let mut ccode = String::new();
+        if self.bold {
+            ccode.push_str("\x1b[1m");
+        }
+        for color in [&self.fg, &self.bg].into_iter().flatten() {
+            ccode.push_str(&format!("\x1b[{}m", color));
+        }
+        ccode
+    }
+}
+
+
+/// Read the `count` color components after `38;5` (one) or `38;2` (three) and get them joined with the selector,
+/// along with how many parameters were used. Components must be `0..=255`.
+fn extended_color(numbers: &[Option<u32>], count: usize) -> Option<(String, usize)> {
+    let components: Vec<u32> = numbers.iter().take(count).map(|n| n.filter(|n| *n <= 255)).collect::<Option<_>>()?;
+    if components.len() < count {
+        return None;
+    }
+    let selector = if count == 1 { 5 } else { 2 };
+    let joined: Vec<String> = components.iter().map(|c| c.to_string()).collect();
+    Some((format!("{};{}", selector, joined.join(";")), count + 1))
+}
+
+
+/// Parse `input`, text with ANSI escape sequences such as the output of `Canvas::to_ansi` or a `.ans` art file,
+/// into a canvas `width` cells wide and as tall as the lowest row written to.
+///
+/// SGR sequences set each cell's `ccode`: 16-color, 256-color and truecolor foregrounds and backgrounds, bold, and resets.
+/// The color code is rebuilt from the resulting state as bold, then foreground, then background, so
+/// `ESC[31mESC[32m` gives cells the color code `ESC[32m`.
+/// Cursor positioning (`ESC[row;colH`) and movement (`ESC[nA`, `B`, `C`, `D`) is clamped to the canvas's left and right edges,
+/// newlines and carriage returns move the cursor, and text past the right edge wraps.
+/// Other sequences and control characters are skipped, and malformed input never panics.
+///
+/// Examples
+/// ```
+/// use terminalgl::{ansi, drawc};
+/// let canvas = ansi::parse("ab\n\x1b[31mc\x1b[0md\x1b[1;4Hx\x1b[2Dy", 5);
+/// assert_eq!((canvas.width(), canvas.height()), (5, 2));
+/// assert_eq!(canvas.get(3, 0).unwrap().c, 'x');
+/// assert_eq!(canvas.get(2, 0).unwrap().c, 'y');
+/// assert_eq!(canvas.get(0, 1).unwrap().ccode, drawc::RED);
+/// assert_eq!(canvas.get(1, 1).unwrap().ccode, "");
+///
+/// let canvas = ansi::parse("\x1b[1;38;5;208;44mA\x1b[22;39mB\x1b[38;2;1;2;3mC\x1b[38;2;999mD", 4);
+/// assert_eq!(canvas.get(0, 0).unwrap().ccode, "\x1b[1m\x1b[38;5;208m\x1b[44m");
+/// assert_eq!(canvas.get(1, 0).unwrap().ccode, drawc::BLUE_BG);
+/// assert_eq!(canvas.get(2, 0).unwrap().ccode, "\x1b[38;2;1;2;3m\x1b[44m");
+/// assert_eq!(canvas.get(3, 0).unwrap().ccode, "\x1b[38;2;1;2;3m\x1b[44m");
+/// ```
+///
+/// Output from `Canvas::to_ansi` parses back to the same cells:
+/// ```
+/// use terminalgl::sketch::Sketch;
+/// use terminalgl::{ansi, drawc};
+/// let mut sketch = Sketch::with_size(6, 3);
+/// sketch.style(drawc::GREEN).text(0, 0, "hello")
+///     .style(&(drawc::rgb_to_ccode((255, 128, 0), drawc::ColorKind::Fg) + drawc::BLUE_BG)).pixel(5, 1, '#')
+///     .style(drawc::BRIGHT_WHITE).text(1, 2, "end");
+/// assert_eq!(&ansi::parse(&sketch.canvas().to_ansi(), 6), sketch.canvas());
+/// ```
+///
+/// Random input is handled without panicking:
+/// ```
+/// use terminalgl::ansi;
+/// use terminalgl::particles::Rng;
+/// let mut rng = Rng::new(1);
+/// let alphabet: Vec<char> = "\x1b[;0123456789mHfABCDJ?\n\r\tx█".chars().collect();
+/// for _ in 0..500 {
+///     let bytes: Vec<u8> = (0..rng.next_u64() % 64).map(|_| rng.next_u64() as u8).collect();
+///     ansi::parse(&String::from_utf8_lossy(&bytes), 8);
+///     let text: String = (0..rng.next_u64() % 64).map(|_| alphabet[rng.next_u64() as usize % alphabet.len()]).collect();
+///     ansi::parse(&text, (rng.next_u64() % 4) as usize);
+/// }
+/// ansi::parse("\x1b[99999999999999999999;5H\x1b[18446744073709551615Bx\x1b[38;5m\x1b[", 3);
+/// ```
+pub fn parse(input: &str, width: usize) -> Canvas {
+    let mut rows: Vec<Vec<Cell>> = Vec::new();
+    let mut style = Style::default();
+    let last_col = width.saturating_sub(1);
+    let (mut x, mut y) = (0usize, 0usize);
+    let mut chars = input.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\x1b' => {
+                if chars.peek() != Some(&'[') {
+                    chars.next();
+                    continue;
+                }
+                chars.next();
+                let mut params = String::new();
+                let mut command = None;
+                for c in chars.by_ref() {
+                    if ('\x40'..='\x7e').contains(&c) {
+                        command = Some(c);
+                        break;
+                    }
+                    if !('\x20'..='\x3f').contains(&c) {
+                        break;
+                    }
+                    params.push(c);
+                }
+                let count = || params.parse::<usize>().unwrap_or(1).max(1);
+                match command {
+                    Some('m') => style.apply(&params),
+                    Some('H') | Some('f') => {
+                        let mut numbers = params.split(';').map(|p| p.parse::<usize>().unwrap_or(1).max(1));
+                        y = (numbers.next().unwrap_or(1) - 1).min(MAX_ROWS);
+                        x = (numbers.next().unwrap_or(1) - 1).min(last_col);
+                    },
+                    Some('A') => y = y.saturating_sub(count()),
+                    Some('B') => y = y.saturating_add(count()).min(MAX_ROWS),
+                    Some('C') => x = x.saturating_add(count()).min(last_col),
+                    Some('D') => x = x.min(last_col).saturating_sub(count()),
+                    _ => {}
+                }
+            },
+            '\n' => {
+                x = 0;
+                y = (y + 1).min(MAX_ROWS);
+            },
+            '\r' => x = 0,
+            c if c.is_control() => {},
+            c => {
+                if x >= width {
+                    x = 0;
+                    y = (y + 1).min(MAX_ROWS);
+                }
+                if width > 0 && y < MAX_ROWS {
+                    if rows.len() <= y {
+                        rows.resize(y + 1, Vec::new());
+                    }
+                    let row = &mut rows[y];
+                    if row.len() <= x {
+                        row.resize(x + 1, Cell::default());
+                    }
+                    row[x] = Cell { c, ccode: style.ccode(), palette: None };
+                }
+                x += 1;
+            }
+        }
+    }
+
+    let mut canvas = Canvas::new(width, rows.len());
+    for (y, row) in rows.into_iter().enumerate() {
+        for (x, cell) in row.into_iter().enumerate() {
+            canvas.put(x as isize, y as isize, cell);
+        }
+    }
+    canvas
+}
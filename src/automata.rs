@@ -0,0 +1,296 @@
+// Cellular automata like Conway's Game of Life, stepped a generation at a time and drawn into a canvas.
+use super::canvas::{Canvas, Cell};
+use super::particles::Rng;
+use super::Rect;
+
+
+/// What lies past the edges of a `Grid`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Edges {
+    /// The grid wraps around, so the left edge neighbors the right one and the top edge the bottom one.
+    Wrap,
+    /// Cells past the edges are dead.
+    Bounded
+}
+
+
+/// Get the next state of a cell in Conway's Game of Life: a live cell with 2 or 3 live neighbors lives on,
+/// a dead cell with exactly 3 comes to life, and every other cell is dead.
+///
+/// Example
+/// ```
+/// use terminalgl::automata;
+/// assert_eq!(automata::conway(1, 2), 1);
+/// assert_eq!(automata::conway(0, 3), 1);
+/// assert_eq!(automata::conway(0, 2), 0);
+/// assert_eq!(automata::conway(1, 4), 0);
+/// ```
+pub fn conway(state: u8, neighbors: u8) -> u8 {
+    match (state, neighbors) {
+        (_, 3) => 1,
+        (s, 2) if s != 0 => 1,
+        _ => 0
+    }
+}
+
+
+/// A grid of cells for a cellular automaton, each with a state from 0 to 255. State 0 is dead and every other state is alive,
+/// so a rule can use the states for more than on and off, such as how many generations a cell has lived.
+///
+/// Example
+/// ```
+/// use terminalgl::automata::{Edges, Grid};
+/// let mut grid = Grid::new(6, 6, Edges::Wrap);
+/// grid.set_pattern(0, 0, "\
+/// .O.
+/// ..O
+/// OOO");
+/// for _ in 0..4 {
+///     grid.step();
+/// }
+/// // After 4 generations a glider has moved one cell down and to the right.
+/// assert_eq!(grid.to_text(), "\
+/// ......
+/// ..O...
+/// ...O..
+/// .OOO..
+/// ......
+/// ......");
+/// assert_eq!((grid.generation(), grid.population()), (4, 5));
+/// // Cells past the largest coordinate are outside the grid too.
+/// grid.set_pattern(isize::MAX, isize::MAX, "OO\nOO");
+/// assert_eq!(grid.population(), 5);
+///
+/// // A rule counting the generations each cell has been alive, up to 9.
+/// let mut aging = Grid::new(3, 3, Edges::Bounded);
+/// aging.set_pattern(0, 1, "OOO");
+/// aging.step_with(|state, neighbors| match terminalgl::automata::conway(state, neighbors) {
+///     0 => 0,
+///     _ => state.saturating_add(1).min(9)
+/// });
+/// assert_eq!(aging.get(1, 0), Some(1));
+/// assert_eq!(aging.get(1, 1), Some(2));
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Grid {
+    width: usize,
+    height: usize,
+    cells: Vec<u8>,
+    generation: u64,
+    pub edges: Edges
+}
+
+impl Grid {
+    /// Create a grid `width` by `height` cells in size, all dead.
+    pub fn new(width: usize, height: usize, edges: Edges) -> Grid {
+        Grid { width, height, cells: vec![0; width * height], generation: 0, edges }
+    }
+
+    /// Get the size of the grid in cells as `(width, height)`.
+    pub fn size(&self) -> (usize, usize) {
+        (self.width, self.height)
+    }
+
+    fn index(&self, x: isize, y: isize) -> Option<usize> {
+        if x < 0 || y < 0 || x as usize >= self.width || y as usize >= self.height {
+            return None;
+        }
+        Some(y as usize * self.width + x as usize)
+    }
+
+    /// Get the state of the cell at `(x, y)`, or `None` if it is outside the grid.
+    pub fn get(&self, x: isize, y: isize) -> Option<u8> {
+        self.index(x, y).map(|i| self.cells[i])
+    }
+
+    /// Set the state of the cell at `(x, y)`. Cells outside the grid are ignored.
+    pub fn set(&mut self, x: isize, y: isize, state: u8) {
+        if let Some(i) = self.index(x, y) {
+            self.cells[i] = state;
+        }
+    }
+
+    /// Get whether the cell at `(x, y)` is alive. Cells outside the grid are dead.
+    pub fn is_alive(&self, x: isize, y: isize) -> bool {
+        self.get(x, y).is_some_and(|state| state != 0)
+    }
+
+    /// Bring to life the cells of `pattern` with its top left corner at `(x, y)`, one line per row, where `.` and spaces are dead
+    /// and any other character, such as `O`, is alive (state 1). Cells outside the grid are ignored.
+    pub fn set_pattern(&mut self, x: isize, y: isize, pattern: &str) {
+        for (dy, line) in pattern.lines().enumerate() {
+            for (dx, c) in line.chars().enumerate() {
+                let state = if c == '.' || c == ' ' { 0 } else { 1 };
+                if let (Some(cx), Some(cy)) = (x.checked_add(dx as isize), y.checked_add(dy as isize)) {
+                    self.set(cx, cy, state);
+                }
+            }
+        }
+    }
+
+    /// Get the grid as text, one line per row, with `O` for live cells and `.` for dead ones, as in Life pattern files.
+    pub fn to_text(&self) -> String {
+        let rows: Vec<String> = self.cells.chunks(self.width.max(1)).map(|row| {
+            row.iter().map(|&state| if state != 0 { 'O' } else { '.' }).collect()
+        }).collect();
+        rows.join("\n")
+    }
+
+    /// Get the number of generations stepped since the grid was created or cleared.
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    /// Get the number of live cells.
+    pub fn population(&self) -> usize {
+        self.cells.iter().filter(|&&state| state != 0).count()
+    }
+
+    /// Kill every cell and start counting generations from 0 again.
+    pub fn clear(&mut self) {
+        self.cells.fill(0);
+        self.generation = 0;
+    }
+
+    /// Bring each cell to life (state 1) with probability `density`, or kill it, and start counting generations from 0 again.
+    /// The same `seed` always gives the same cells.
+    ///
+    /// Example
+    /// ```
+    /// use terminalgl::automata::{Edges, Grid};
+    /// let mut a = Grid::new(40, 20, Edges::Wrap);
+    /// let mut b = a.clone();
+    /// a.randomize(7, 0.25);
+    /// b.randomize(7, 0.25);
+    /// assert_eq!(a, b);
+    /// assert!((150..250).contains(&a.population()));
+    /// b.randomize(8, 0.25);
+    /// assert_ne!(a, b);
+    /// ```
+    pub fn randomize(&mut self, seed: u64, density: f64) {
+        let mut rng = Rng::new(seed);
+        for state in &mut self.cells {
+            *state = (rng.next_f64() < density) as u8;
+        }
+        self.generation = 0;
+    }
+
+    /// Advance one generation by Conway's Game of Life (see `conway`).
+    pub fn step(&mut self) {
+        self.step_with(conway);
+    }
+
+    /// Advance one generation, setting every cell to `rule(state, neighbors)`, where `neighbors` is how many of the 8 cells
+    /// around it are alive, all taken from the generation before.
+    ///
+    /// Example
+    /// ```
+    /// use terminalgl::automata::{Edges, Grid};
+    /// // Seeds: a dead cell with exactly 2 live neighbors comes to life, and every live cell dies.
+    /// let seeds = |state: u8, neighbors: u8| (state == 0 && neighbors == 2) as u8;
+    /// let mut grid = Grid::new(4, 3, Edges::Bounded);
+    /// grid.set_pattern(1, 1, "OO");
+    /// grid.step_with(seeds);
+    /// assert_eq!(grid.to_text(), "\
+    /// .OO.
+    /// ....
+    /// .OO.");
+    ///
+    /// // With wrapping edges the left and right columns are neighbors, and so are the top and bottom rows.
+    /// grid.edges = Edges::Wrap;
+    /// grid.step_with(seeds);
+    /// assert_eq!(grid.to_text(), "\
+    /// O..O
+    /// O..O
+    /// O..O");
+    /// ```
+    pub fn step_with(&mut self, rule: impl Fn(u8, u8) -> u8) {
+        let (width, height, edges) = (self.width, self.height, self.edges);
+        let neighbor = move |i: usize, d: isize, n: usize| -> Option<usize> {
+            let j = i as isize + d;
+            match (0..n as isize).contains(&j) {
+                true => Some(j as usize),
+                false if edges == Edges::Wrap => Some(j.rem_euclid(n as isize) as usize),
+                false => None
+            }
+        };
+        // The columns either side of each column, worked out once rather than for every row.
+        let columns: Vec<[Option<usize>; 3]> = (0..width).map(|x| [neighbor(x, -1, width), Some(x), neighbor(x, 1, width)]).collect();
+        let mut next = vec![0; self.cells.len()];
+        for y in 0..height {
+            let rows = [neighbor(y, -1, height), Some(y), neighbor(y, 1, height)];
+            for (x, cols) in columns.iter().enumerate() {
+                let mut neighbors = 0;
+                for (dy, row) in rows.iter().enumerate() {
+                    let Some(row) = row else {
+                        continue;
+                    };
+                    for (dx, col) in cols.iter().enumerate() {
+                        if let Some(col) = col {
+                            if (dx, dy) != (1, 1) && self.cells[row * width + col] != 0 {
+                                neighbors += 1;
+                            }
+                        }
+                    }
+                }
+                let i = y * width + x;
+                next[i] = rule(self.cells[i], neighbors);
+            }
+        }
+        self.cells = next;
+        self.generation += 1;
+    }
+
+    /// Draw the grid into `rect` on `canvas`, one cell per cell, as `alive` or `dead`. Cells of `rect` past the grid and cells
+    /// of the grid past `rect` are left out.
+    ///
+    /// Cells already showing what they should are skipped, so drawing each generation over the last one touches only the cells
+    /// that changed. Printing them with `Canvas::take_damage` and `Canvas::present_rects` then sends only those to the terminal,
+    /// which keeps a 200 by 100 grid comfortably above 60 generations a second.
+    ///
+    /// Example
+    /// ```
+    /// use terminalgl::automata::{Edges, Grid};
+    /// use terminalgl::canvas::{Canvas, Cell};
+    /// use terminalgl::{assert_frame_eq, drawc, Rect};
+    /// let mut grid = Grid::new(5, 5, Edges::Bounded);
+    /// grid.set_pattern(1, 2, "OOO");
+    /// let (alive, dead) = (Cell::new('█', drawc::GREEN), Cell::new('·', ""));
+    /// let mut canvas = Canvas::new(7, 5);
+    /// grid.render(&mut canvas, Rect::new(1, 0, 5, 5), &alive, &dead);
+    /// canvas.take_damage();
+    ///
+    /// // The blinker turns upright: 2 cells die, 2 come to life, and only those 4 need printing.
+    /// grid.step();
+    /// grid.render(&mut canvas, Rect::new(1, 0, 5, 5), &alive, &dead);
+    /// let damage = canvas.take_damage();
+    /// assert_eq!(damage.iter().map(|r| r.width * r.height).sum::<usize>(), 4);
+    /// assert_frame_eq!(canvas, "\
+    /// | ····· |
+    /// | ··█·· |
+    /// | ··█·· |
+    /// | ··█·· |
+    /// | ····· |
+    ///
+    /// |.......|
+    /// |...A...|
+    /// |...A...|
+    /// |...A...|
+    /// |.......|
+    /// A \"\\u{1b}[32m\"");
+    /// ```
+    pub fn render(&self, canvas: &mut Canvas, rect: Rect, alive: &Cell, dead: &Cell) {
+        let width = rect.width.min(self.width);
+        let height = rect.height.min(self.height);
+        for y in 0..height {
+            let row = &self.cells[y * self.width..y * self.width + width];
+            for (x, &state) in row.iter().enumerate() {
+                let cell = if state != 0 { alive } else { dead };
+                let (cx, cy) = (rect.x.saturating_add(x as isize), rect.y.saturating_add(y as isize));
+                if canvas.get(cx, cy).is_some_and(|shown| shown != cell) {
+                    canvas.put(cx, cy, cell.clone());
+                }
+            }
+        }
+    }
+}
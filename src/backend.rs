@@ -0,0 +1,311 @@
+// The terminal operations the crate needs, behind a trait, so output and input can go somewhere other than the real terminal.
+#[cfg(all(feature = "input", unix))]
+use super::input::{self, Event, EventParser, RawMode};
+#[cfg(all(feature = "input", unix))]
+use std::collections::VecDeque;
+use std::io::{self, Write};
+#[cfg(all(feature = "input", unix))]
+use std::time::Duration;
+
+
+/// The terminal operations the crate needs: writing and flushing output, the terminal's size, the alternate screen and cursor,
+/// and (on Unix) raw mode and reading input. `AnsiBackend` does them on the real terminal, and is what everything uses
+/// unless given a backend; `TestBackend` records them instead, for tests that run without a terminal.
+///
+/// Implement it to drive the terminal through another library, and pass the backend to the functions that take one,
+/// such as `Canvas::present_to` and `BackendGuard::new`.
+pub trait Backend {
+    /// Write `bytes`, which may hold escape sequences, without flushing them.
+    fn write(&mut self, bytes: &[u8]) -> io::Result<()>;
+
+    /// Send everything written so far to the terminal.
+    fn flush(&mut self) -> io::Result<()>;
+
+    /// Get the terminal size as `(cols, rows)`.
+    fn size(&self) -> (u16, u16);
+
+    /// Switch to the alternate screen, or back to the normal one.
+    fn set_alternate_screen(&mut self, on: bool) -> io::Result<()>;
+
+    /// Show or hide the cursor.
+    fn set_cursor_visible(&mut self, visible: bool) -> io::Result<()>;
+
+    /// Put the terminal in raw mode, so keys arrive as they are pressed, or take it out again.
+    #[cfg(all(feature = "input", unix))]
+    fn set_raw_mode(&mut self, on: bool) -> io::Result<()>;
+
+    /// Wait up to `timeout` for input, then get the events read, like `input::read_events`.
+    #[cfg(all(feature = "input", unix))]
+    fn read_events(&mut self, timeout: Duration) -> io::Result<Vec<Event>>;
+}
+
+
+/// The backend that drives the real terminal with ANSI escape sequences on stdout, reading input with `input::read_events`.
+/// Raw mode is a `RawMode` held while it is on, so it turns on mouse reporting like `RawMode::with_mouse`.
+#[derive(Default)]
+pub struct AnsiBackend {
+    #[cfg(all(feature = "input", unix))]
+    raw: Option<RawMode>
+}
+
+impl AnsiBackend {
+    /// Create a backend for the terminal on stdout.
+    pub fn new() -> AnsiBackend {
+        AnsiBackend::default()
+    }
+}
+
+impl Backend for AnsiBackend {
+    fn write(&mut self, bytes: &[u8]) -> io::Result<()> {
+        io::stdout().write_all(bytes)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        io::stdout().flush()
+    }
+
+    fn size(&self) -> (u16, u16) {
+        super::size()
+    }
+
+    fn set_alternate_screen(&mut self, on: bool) -> io::Result<()> {
+        self.write(if on { b"\x1b[?1049h" } else { b"\x1b[?1049l" })
+    }
+
+    fn set_cursor_visible(&mut self, visible: bool) -> io::Result<()> {
+        self.write(if visible { b"\x1b[?25h" } else { b"\x1b[?25l" })
+    }
+
+    #[cfg(all(feature = "input", unix))]
+    fn set_raw_mode(&mut self, on: bool) -> io::Result<()> {
+        match (on, self.raw.is_some()) {
+            (true, false) => self.raw = Some(RawMode::with_mouse()?),
+            (false, true) => self.raw = None,
+            _ => {}
+        }
+        Ok(())
+    }
+
+    #[cfg(all(feature = "input", unix))]
+    fn read_events(&mut self, timeout: Duration) -> io::Result<Vec<Event>> {
+        input::read_events(timeout)
+    }
+}
+
+
+/// An operation a `TestBackend` recorded.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Op {
+    Write(Vec<u8>),
+    Flush,
+    AlternateScreen(bool),
+    CursorVisible(bool),
+    RawMode(bool),
+    ReadEvents
+}
+
+
+/// A backend that records every operation instead of touching a terminal, with a fixed size and input queued up by the test.
+/// Writes with nothing recorded between them are kept as one `Op::Write`.
+///
+/// Example
+/// ```
+/// use terminalgl::backend::{Backend, Op, TestBackend};
+/// use terminalgl::canvas::Canvas;
+/// let mut backend = TestBackend::new(3, 1);
+/// let mut canvas = Canvas::new(3, 1);
+/// canvas.text(0, 0, "hi", "");
+/// canvas.present_to(&mut backend).unwrap();
+/// assert_eq!(backend.output(), "\x1b[1;1Hhi \x1b[0m");
+/// assert_eq!(backend.ops().last(), Some(&Op::Flush));
+/// assert_eq!(backend.size(), (3, 1));
+/// ```
+#[derive(Clone, Debug)]
+pub struct TestBackend {
+    size: (u16, u16),
+    ops: Vec<Op>,
+    #[cfg(all(feature = "input", unix))]
+    input: VecDeque<Event>,
+    #[cfg(all(feature = "input", unix))]
+    parser: EventParser
+}
+
+impl TestBackend {
+    /// Create a backend for a terminal of `cols` by `rows` with no input waiting.
+    pub fn new(cols: u16, rows: u16) -> TestBackend {
+        TestBackend {
+            size: (cols, rows),
+            ops: Vec::new(),
+            #[cfg(all(feature = "input", unix))]
+            input: VecDeque::new(),
+            #[cfg(all(feature = "input", unix))]
+            parser: EventParser::new()
+        }
+    }
+
+    /// Change the size the backend reports, as if the terminal was resized.
+    pub fn resize(&mut self, cols: u16, rows: u16) {
+        self.size = (cols, rows);
+    }
+
+    /// Get every operation recorded so far, in order.
+    pub fn ops(&self) -> &[Op] {
+        &self.ops
+    }
+
+    /// Get everything written so far, as text.
+    pub fn output(&self) -> String {
+        let bytes: Vec<u8> = self.ops.iter().filter_map(|op| match op {
+            Op::Write(bytes) => Some(bytes.as_slice()),
+            _ => None
+        }).flatten().copied().collect();
+        String::from_utf8_lossy(&bytes).into_owned()
+    }
+
+    /// Forget the operations recorded so far.
+    pub fn clear(&mut self) {
+        self.ops.clear();
+    }
+
+    /// Queue `event` for the next `read_events`.
+    #[cfg(all(feature = "input", unix))]
+    pub fn push_event(&mut self, event: Event) {
+        self.input.push_back(event);
+    }
+
+    /// Queue the events in `bytes`, as a terminal would send them, for the next `read_events`.
+    ///
+    /// Example
+    /// ```
+    /// use terminalgl::backend::{Backend, TestBackend};
+    /// use terminalgl::input::{Event, Key};
+    /// use std::time::Duration;
+    /// let mut backend = TestBackend::new(80, 24);
+    /// backend.push_input(b"q\x1b[A\x1b[200~pasted\x1b[201~");
+    /// assert_eq!(backend.read_events(Duration::ZERO).unwrap(), vec![
+    ///     Event::Key(Key::Char('q')),
+    ///     Event::Key(Key::Up),
+    ///     Event::Paste("pasted".to_string()),
+    /// ]);
+    /// assert_eq!(backend.read_events(Duration::ZERO).unwrap(), vec![]);
+    /// ```
+    #[cfg(all(feature = "input", unix))]
+    pub fn push_input(&mut self, bytes: &[u8]) {
+        let mut events = self.parser.feed(bytes);
+        events.extend(self.parser.flush());
+        self.input.extend(events);
+    }
+}
+
+impl Backend for TestBackend {
+    fn write(&mut self, bytes: &[u8]) -> io::Result<()> {
+        match self.ops.last_mut() {
+            Some(Op::Write(written)) => written.extend_from_slice(bytes),
+            _ => self.ops.push(Op::Write(bytes.to_vec()))
+        }
+        Ok(())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.ops.push(Op::Flush);
+        Ok(())
+    }
+
+    fn size(&self) -> (u16, u16) {
+        self.size
+    }
+
+    fn set_alternate_screen(&mut self, on: bool) -> io::Result<()> {
+        self.ops.push(Op::AlternateScreen(on));
+        Ok(())
+    }
+
+    fn set_cursor_visible(&mut self, visible: bool) -> io::Result<()> {
+        self.ops.push(Op::CursorVisible(visible));
+        Ok(())
+    }
+
+    #[cfg(all(feature = "input", unix))]
+    fn set_raw_mode(&mut self, on: bool) -> io::Result<()> {
+        self.ops.push(Op::RawMode(on));
+        Ok(())
+    }
+
+    #[cfg(all(feature = "input", unix))]
+    fn read_events(&mut self, _timeout: Duration) -> io::Result<Vec<Event>> {
+        self.ops.push(Op::ReadEvents);
+        Ok(self.input.drain(..).collect())
+    }
+}
+
+
+/// Guard that sets a backend up for a full screen program, like `input::RawMode` does for the terminal:
+/// the alternate screen, a hidden cursor, and (on Unix) raw mode, all put back in reverse order when it is dropped.
+/// The backend stays usable through `backend`.
+///
+/// Example
+/// ```
+/// # #[cfg(all(feature = "input", unix))] {
+/// use terminalgl::backend::{Backend, BackendGuard, Op, TestBackend};
+/// let mut backend = TestBackend::new(80, 24);
+/// {
+///     let mut guard = BackendGuard::new(&mut backend).unwrap();
+///     guard.backend().write(b"hello").unwrap();
+/// }
+/// assert_eq!(backend.ops(), &[
+///     Op::RawMode(true),
+///     Op::AlternateScreen(true),
+///     Op::CursorVisible(false),
+///     Op::Flush,
+///     Op::Write(b"hello\x1b[0m".to_vec()),
+///     Op::CursorVisible(true),
+///     Op::AlternateScreen(false),
+///     Op::RawMode(false),
+///     Op::Flush,
+/// ]);
+/// # }
+/// ```
+pub struct BackendGuard<'a, B: Backend + ?Sized> {
+    backend: &'a mut B
+}
+
+impl<'a, B: Backend + ?Sized> BackendGuard<'a, B> {
+    /// Set `backend` up, or get the error of the first step that failed, after undoing the steps before it.
+    pub fn new(backend: &'a mut B) -> io::Result<BackendGuard<'a, B>> {
+        #[cfg(all(feature = "input", unix))]
+        backend.set_raw_mode(true)?;
+        let setup = backend.set_alternate_screen(true)
+            .and_then(|_| backend.set_cursor_visible(false))
+            .and_then(|_| backend.flush());
+        if let Err(err) = setup {
+            let _ = restore(backend);
+            return Err(err);
+        }
+        Ok(BackendGuard { backend })
+    }
+
+    /// Get the backend, to draw to or read input from.
+    pub fn backend(&mut self) -> &mut B {
+        self.backend
+    }
+}
+
+impl<B: Backend + ?Sized> Drop for BackendGuard<'_, B> {
+    fn drop(&mut self) {
+        let _ = restore(self.backend);
+    }
+}
+
+
+/// Undo what `BackendGuard::new` set up, carrying on past errors and returning the first.
+fn restore<B: Backend + ?Sized>(backend: &mut B) -> io::Result<()> {
+    let results = [
+        backend.write(super::drawc::RESET.as_bytes()),
+        backend.set_cursor_visible(true),
+        backend.set_alternate_screen(false),
+        #[cfg(all(feature = "input", unix))]
+        backend.set_raw_mode(false),
+        backend.flush()
+    ];
+    results.into_iter().collect()
+}
@@ -0,0 +1,160 @@
+// Reordering mixed left-to-right and right-to-left text for display, with the Unicode bidirectional algorithm.
+use super::raster;
+use super::TextDirection;
+
+
+/// The bidirectional character types the algorithm tells apart. Types it has no use for without explicit
+/// embeddings fall in with `ON`, and marks that combine with a character share its grapheme and so its type.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Class {
+    L,
+    R,
+    AL,
+    EN,
+    ES,
+    ET,
+    AN,
+    CS,
+    WS,
+    ON
+}
+
+
+/// Get the bidirectional type of `c`.
+fn class(c: char) -> Class {
+    match c as u32 {
+        0x30..=0x39 | 0x06F0..=0x06F9 | 0xFF10..=0xFF19 => Class::EN,
+        0x0660..=0x0669 | 0x066B..=0x066C => Class::AN,
+        0x2B | 0x2D | 0x207A..=0x207B | 0xFF0B | 0xFF0D => Class::ES,
+        0x23..=0x25 | 0xA2..=0xA5 | 0xB0..=0xB1 | 0x066A | 0x2030..=0x2034 | 0x20A0..=0x20CF => Class::ET,
+        0x2C | 0x2E | 0x2F | 0x3A | 0xA0 | 0x060C | 0x202F | 0xFF0C | 0xFF0E | 0xFF1A => Class::CS,
+        0x20 | 0x09 | 0x0C | 0x2000..=0x200A | 0x3000 => Class::WS,
+        0x0590..=0x05FF | 0x07C0..=0x085F | 0xFB1D..=0xFB4F | 0x10800..=0x10FFF | 0x1E800..=0x1EFFF => Class::R,
+        0x0600..=0x07BF | 0x0860..=0x08FF | 0xFB50..=0xFDFF | 0xFE70..=0xFEFF => Class::AL,
+        _ if c.is_alphanumeric() => Class::L,
+        _ => Class::ON
+    }
+}
+
+
+/// Reorder one line of `text`, read in `direction`, into the order its graphemes appear on screen from left to right,
+/// with brackets in right-to-left runs mirrored. Runs of right-to-left letters are reversed, numbers inside them keep
+/// their digits in order, and spaces and punctuation take the direction of the text around them.
+///
+/// This follows the implicit rules of the Unicode bidirectional algorithm (UAX #9) with `direction` as the paragraph level.
+/// Explicit embedding and isolate controls and bracket pairing are not applied.
+///
+/// Example
+/// ```
+/// use terminalgl::{bidi, TextDirection};
+/// assert_eq!(bidi::reorder("abc שלום 123", TextDirection::Ltr), "abc 123 םולש");
+/// assert_eq!(bidi::reorder("שלום abc 123", TextDirection::Rtl), "abc 123 םולש");
+/// assert_eq!(bidi::reorder("מחיר: 42 ש\"ח.", TextDirection::Rtl), ".ח\"ש 42 :ריחמ");
+/// assert_eq!(bidi::reorder("(שלום)", TextDirection::Rtl), "(םולש)");
+/// assert_eq!(bidi::reorder("plain text", TextDirection::Ltr), "plain text");
+/// // The bidi types of Arabic letters turn European digits after them into Arabic numbers, still kept in order.
+/// assert_eq!(bidi::reorder("عدد 12", TextDirection::Ltr), "12 ددع");
+/// ```
+pub fn reorder(text: &str, direction: TextDirection) -> String {
+    let graphemes: Vec<&str> = raster::graphemes(text).collect();
+    let base = if direction == TextDirection::Rtl { 1 } else { 0 };
+    let mut classes: Vec<Class> = graphemes.iter().map(|g| g.chars().next().map_or(Class::ON, class)).collect();
+    let strong_before = |classes: &[Class], i: usize| {
+        classes[..i].iter().rev().copied().find(|c| matches!(c, Class::L | Class::R | Class::AL))
+            .unwrap_or(if base == 1 { Class::R } else { Class::L })
+    };
+
+    // W2 and W3: numbers after Arabic letters are Arabic numbers, and Arabic letters are right-to-left letters.
+    for i in 0..classes.len() {
+        if classes[i] == Class::EN && strong_before(&classes, i) == Class::AL {
+            classes[i] = Class::AN;
+        }
+    }
+    for class in classes.iter_mut().filter(|c| **c == Class::AL) {
+        *class = Class::R;
+    }
+    // W4: a single separator between two numbers of the same type joins them.
+    for i in 1..classes.len().saturating_sub(1) {
+        let (before, after) = (classes[i - 1], classes[i + 1]);
+        classes[i] = match (before, classes[i], after) {
+            (Class::EN, Class::ES | Class::CS, Class::EN) => Class::EN,
+            (Class::AN, Class::CS, Class::AN) => Class::AN,
+            (_, class, _) => class
+        };
+    }
+    // W5: terminators next to European numbers join them.
+    let mut i = 0;
+    while i < classes.len() {
+        let end = i + classes[i..].iter().take_while(|c| **c == Class::ET).count();
+        if end > i {
+            if (i > 0 && classes[i - 1] == Class::EN) || classes.get(end) == Some(&Class::EN) {
+                classes[i..end].fill(Class::EN);
+            }
+            i = end;
+        }
+        else {
+            i += 1;
+        }
+    }
+    // W6 and W7: leftover separators and terminators are neutral, and numbers in left-to-right text are left-to-right.
+    for class in classes.iter_mut().filter(|c| matches!(c, Class::ES | Class::ET | Class::CS)) {
+        *class = Class::ON;
+    }
+    for i in 0..classes.len() {
+        if classes[i] == Class::EN && strong_before(&classes, i) == Class::L {
+            classes[i] = Class::L;
+        }
+    }
+    // N1 and N2: neutrals between text of one direction take it, and other neutrals take the paragraph's.
+    let direction_of = |class: Class| if class == Class::L { Class::L } else { Class::R };
+    let paragraph = if base == 1 { Class::R } else { Class::L };
+    let mut i = 0;
+    while i < classes.len() {
+        let end = i + classes[i..].iter().take_while(|c| matches!(c, Class::WS | Class::ON)).count();
+        if end > i {
+            let before = if i == 0 { paragraph } else { direction_of(classes[i - 1]) };
+            let after = classes.get(end).map_or(paragraph, |&c| direction_of(c));
+            let resolved = if before == after { before } else { paragraph };
+            classes[i..end].fill(resolved);
+            i = end;
+        }
+        else {
+            i += 1;
+        }
+    }
+
+    // I1 and I2: give each grapheme its embedding level.
+    let mut levels: Vec<u8> = classes.iter().map(|class| match (base, class) {
+        (0, Class::R) => 1,
+        (0, Class::AN | Class::EN) => 2,
+        (1, Class::L | Class::AN | Class::EN) => 2,
+        _ => base
+    }).collect();
+    // L1: spaces at the end of the line go back to the paragraph level.
+    for (level, grapheme) in levels.iter_mut().zip(&graphemes).rev() {
+        if !grapheme.chars().all(|c| class(c) == Class::WS) {
+            break;
+        }
+        *level = base;
+    }
+
+    // L2: reverse every run at or above each level, from the highest down to the lowest odd one.
+    let mut order: Vec<usize> = (0..graphemes.len()).collect();
+    let highest = levels.iter().copied().max().unwrap_or(0);
+    for level in (1..=highest).rev() {
+        let mut i = 0;
+        while i < order.len() {
+            if levels[order[i]] < level {
+                i += 1;
+                continue;
+            }
+            let end = i + order[i..].iter().take_while(|&&g| levels[g] >= level).count();
+            order[i..end].reverse();
+            i = end;
+        }
+    }
+    order.iter().flat_map(|&i| {
+        let odd = levels[i] % 2 == 1;
+        graphemes[i].chars().map(move |c| if odd { raster::mirror(c) } else { c })
+    }).collect()
+}
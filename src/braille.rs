@@ -0,0 +1,95 @@
+// Sub-cell drawing with Braille characters.
+
+
+/// Bit of each dot in a Braille cell, indexed by `[y][x]` within the 2x4 dot grid.
+const DOT_BITS: [[u8; 2]; 4] = [
+    [0x01, 0x08],
+    [0x02, 0x10],
+    [0x04, 0x20],
+    [0x40, 0x80]
+];
+
+
+/// Get the Braille character with the dots in `mask` raised, like `chars::braille`.
+/// Bits 0 to 7 correspond to Braille dots 1 to 8.
+///
+/// Example
+/// ```
+/// use terminalgl::braille;
+/// assert_eq!(braille::char_from_mask(0), '⠀');
+/// assert_eq!(braille::char_from_mask(0xff), '⣿');
+/// ```
+pub fn char_from_mask(mask: u8) -> char {
+    super::chars::braille(mask)
+}
+
+
+/// A grid of Braille cells `width` by `height` cells in size, addressed in dots.
+/// Each cell holds 2x4 dots, so the grid is `width * 2` dots wide and `height * 4` dots tall.
+///
+/// Example
+/// ```
+/// use terminalgl::braille::BrailleGrid;
+/// let mut grid = BrailleGrid::new(2, 1);
+/// grid.line(0, 0, 3, 3);
+/// assert_eq!(grid.cells(), vec![(0, 0, '⠑'), (1, 0, '⢄')]);
+/// ```
+#[derive(Clone, Debug)]
+pub struct BrailleGrid {
+    width: usize,
+    height: usize,
+    masks: Vec<u8>
+}
+
+impl BrailleGrid {
+    /// Create an empty grid `width` by `height` cells in size.
+    pub fn new(width: usize, height: usize) -> BrailleGrid {
+        BrailleGrid { width, height, masks: vec![0; width * height] }
+    }
+
+    /// Get the size of the grid in dots as `(width, height)`.
+    pub fn dot_size(&self) -> (usize, usize) {
+        (self.width * 2, self.height * 4)
+    }
+
+    /// Raise the dot at `(x, y)`. Dots outside the grid are ignored.
+    pub fn set(&mut self, x: isize, y: isize) {
+        let (w, h) = self.dot_size();
+        if x >= 0 && y >= 0 && (x as usize) < w && (y as usize) < h {
+            let (x, y) = (x as usize, y as usize);
+            self.masks[y / 4 * self.width + x / 2] |= DOT_BITS[y % 4][x % 2];
+        }
+    }
+
+    /// Raise the dots along a line from `(x1, y1)` to `(x2, y2)`. Only the part of the line on the grid is visited.
+    pub fn line(&mut self, x1: isize, y1: isize, x2: isize, y2: isize) {
+        let (w, h) = self.dot_size();
+        for (x, y) in super::raster::line_clipped(x1, y1, x2, y2, super::Rect::new(0, 0, w, h)) {
+            self.set(x, y);
+        }
+    }
+
+    /// Get the dot mask of the cell at `(x, y)`.
+    pub fn mask(&self, x: usize, y: usize) -> u8 {
+        self.masks[y * self.width + x]
+    }
+
+    /// Raise the dots in `mask` in the cell at `(x, y)`.
+    pub fn add_mask(&mut self, x: usize, y: usize, mask: u8) {
+        self.masks[y * self.width + x] |= mask;
+    }
+
+    /// Get every cell with at least one raised dot as `(x, y, c)`, in row order.
+    pub fn cells(&self) -> Vec<(usize, usize, char)> {
+        let mut cells = Vec::new();
+        for y in 0..self.height {
+            for x in 0..self.width {
+                let mask = self.mask(x, y);
+                if mask != 0 {
+                    cells.push((x, y, char_from_mask(mask)));
+                }
+            }
+        }
+        cells
+    }
+}
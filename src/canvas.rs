@@ -0,0 +1,2574 @@
+// Draw into a buffer of cells and print it all at once.
+use super::backend::{AnsiBackend, Backend};
+use super::chars::{self, LineStyle};
+#[cfg(feature = "widgets")]
+use super::ui::HitGrid;
+use super::{drawc, raster, Anchor, Direction, Quadrant, Rect, TextAlignment, TextDirection};
+use std::borrow::Cow;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::io;
+use std::ops::{Deref, DerefMut, Range};
+use std::fmt;
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::{Duration, Instant};
+
+
+/// A character and the color code it is drawn with. An empty `ccode` draws with the terminal's default colors.
+/// A cell with a `palette` index is drawn with that entry of its canvas's palette instead of `ccode`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Cell {
+    pub c: char,
+    pub ccode: String,
+    pub palette: Option<usize>
+}
+
+impl Cell {
+    /// Create a cell of `c` drawn with `ccode`.
+    pub fn new(c: char, ccode: &str) -> Cell {
+        Cell { c, ccode: ccode.to_string(), palette: None }
+    }
+
+    /// Create a cell of `c` drawn with entry `index` of the canvas's palette.
+    pub fn indexed(c: char, index: usize) -> Cell {
+        Cell { c, ccode: String::new(), palette: Some(index) }
+    }
+
+    /// Get this cell as a canvas stores it: its character passed through `chars::sanitize`,
+    /// and a `ccode` that isn't a color code (see `drawc::is_ccode`) replaced by the default colors.
+    fn sanitized(mut self) -> Cell {
+        self.c = chars::sanitize(self.c);
+        if !drawc::is_ccode(&self.ccode) {
+            self.ccode.clear();
+        }
+        self
+    }
+}
+
+impl Default for Cell {
+    fn default() -> Cell {
+        Cell { c: ' ', ccode: String::new(), palette: None }
+    }
+}
+
+
+/// How a canvas combines a cell drawn over one already there, from `Canvas::set_blend`.
+/// `Replace` draws the new cell. `KeepChar` keeps the character there and takes the new colors, and `KeepColors`
+/// takes the new character and keeps the colors there. `Under` only draws into empty cells (spaces in the default colors),
+/// as if behind what was drawn before. `Max` and `Min` keep whichever cell is brighter or dimmer (see `Canvas::set_blend`),
+/// with the new cell winning ties.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum BlendMode {
+    #[default]
+    Replace,
+    KeepChar,
+    KeepColors,
+    Under,
+    Max,
+    Min
+}
+
+
+/// A saved rectangle of cells from `Canvas::snapshot`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Snapshot {
+    rect: Rect,
+    cells: Vec<Cell>
+}
+
+impl Snapshot {
+    /// Get the rectangle the snapshot covers, after clamping to the canvas it was taken from.
+    pub fn rect(&self) -> Rect {
+        self.rect
+    }
+}
+
+
+/// The canvas inside `Canvas::with_pen`, which puts back the pen and style from before when dropped, even while unwinding.
+struct PenGuard<'a> {
+    canvas: &'a mut Canvas,
+    previous: (char, String)
+}
+
+impl Deref for PenGuard<'_> {
+    type Target = Canvas;
+
+    fn deref(&self) -> &Canvas {
+        self.canvas
+    }
+}
+
+impl DerefMut for PenGuard<'_> {
+    fn deref_mut(&mut self) -> &mut Canvas {
+        self.canvas
+    }
+}
+
+impl Drop for PenGuard<'_> {
+    fn drop(&mut self) {
+        self.canvas.pen = self.previous.0;
+        self.canvas.style = std::mem::take(&mut self.previous.1);
+    }
+}
+
+
+/// A filter from `Canvas::set_row_filter`, given each row's index and a copy of its cells.
+type RowFilter = Arc<dyn Fn(usize, &mut [Cell]) + Send + Sync>;
+
+/// A filter from `Canvas::set_cell_filter`, given each cell's position and a copy of it.
+type CellFilter = Arc<dyn Fn(usize, usize, &mut Cell) + Send + Sync>;
+
+
+/// The filters a canvas is presented through. Clones share them.
+#[derive(Clone, Default)]
+struct Filters {
+    row: Option<RowFilter>,
+    cell: Option<CellFilter>
+}
+
+impl fmt::Debug for Filters {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Filters").field("row", &self.row.is_some()).field("cell", &self.cell.is_some()).finish()
+    }
+}
+
+
+/// Options for `Canvas::find_text`. With `case_insensitive`, letters match whatever their case,
+/// and with `whole_word`, matches must not have a letter, digit or `_` just before or after them on their row.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct FindOptions {
+    pub case_insensitive: bool,
+    pub whole_word: bool
+}
+
+
+/// What a drawing call on a `Canvas` drew, or would draw for the matching `measure_` call:
+/// the smallest rectangle holding every cell drawn, and how many cells were drawn.
+/// Only cells inside the canvas count, and a call that draws nothing has empty `bounds` at `(0, 0)`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DrawResult {
+    pub bounds: Rect,
+    pub cells: usize
+}
+
+impl DrawResult {
+    /// Get the result of drawing `cells`.
+    fn of(cells: impl IntoIterator<Item = (isize, isize)>) -> DrawResult {
+        let mut count = 0;
+        let (mut left, mut top, mut right, mut bottom) = (isize::MAX, isize::MAX, isize::MIN, isize::MIN);
+        for (x, y) in cells {
+            count += 1;
+            left = left.min(x);
+            top = top.min(y);
+            right = right.max(x);
+            bottom = bottom.max(y);
+        }
+        if count == 0 {
+            return DrawResult { bounds: Rect::new(0, 0, 0, 0), cells: 0 };
+        }
+        let bounds = Rect::new(left, top, right.abs_diff(left) + 1, bottom.abs_diff(top) + 1);
+        DrawResult { bounds, cells: count }
+    }
+}
+
+
+/// Measurements of the last frame printed by `Canvas::present`, `present_diff`, or `present_rects`, from `last_frame_stats`.
+/// The debug overlay (see `Canvas::debug_stats`) is printed separately, so its cells, bytes, and time are never counted.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct FrameStats {
+    /// Frames per second, from the time since the frame before started presenting. `0.0` for the first frame.
+    pub fps: f64,
+    /// Number of cells printed.
+    pub cells_changed: usize,
+    /// Number of bytes written to the terminal.
+    pub bytes: usize,
+    /// Time spent working out and printing the frame.
+    pub present_time: Duration
+}
+
+
+struct StatsState {
+    last: FrameStats,
+    last_start: Option<Instant>,
+    overlay: Option<Rect>,
+    refresh_interval: Option<Duration>,
+    last_full: Option<Instant>
+}
+
+static STATS: Mutex<StatsState> = Mutex::new(StatsState {
+    last: FrameStats { fps: 0.0, cells_changed: 0, bytes: 0, present_time: Duration::ZERO },
+    last_start: None,
+    overlay: None,
+    refresh_interval: None,
+    last_full: None
+});
+
+
+/// Get the measurements of the last frame a canvas presented. They are collected for every frame, whether or not
+/// the debug overlay is shown, and are all zero before the first frame.
+///
+/// Example
+/// ```
+/// use terminalgl::canvas::{self, Canvas};
+/// let before = Canvas::new(4, 2);
+/// before.present();
+/// assert_eq!(canvas::last_frame_stats().cells_changed, 8);
+/// assert_eq!(canvas::last_frame_stats().fps, 0.0);
+///
+/// let mut after = before.clone();
+/// after.text(1, 1, "ab", "");
+/// after.debug_stats(true);
+/// after.present_diff(&before);
+/// let stats = canvas::last_frame_stats();
+/// assert_eq!(stats.cells_changed, 2);
+/// assert_eq!(stats.bytes, after.diff_ansi(&before).len());
+/// assert!(stats.fps > 0.0);
+/// ```
+pub fn last_frame_stats() -> FrameStats {
+    STATS.lock().unwrap_or_else(PoisonError::into_inner).last
+}
+
+
+/// Repaint the whole terminal at least every `interval`, or stop with `None`, the default. Once `interval` has passed since
+/// the last full `Canvas::present`, the next `present_diff` or `present_rects` prints every cell instead, in one write like `present`.
+/// This heals what other programs print over the terminal, such as kernel messages, which the diff would never notice.
+///
+/// Example
+/// ```
+/// use terminalgl::canvas::{self, Canvas};
+/// use std::time::Duration;
+/// let before = Canvas::new(4, 2);
+/// before.present();
+/// before.present_diff(&before);
+/// assert_eq!(canvas::last_frame_stats().cells_changed, 0);
+///
+/// canvas::set_refresh_interval(Some(Duration::ZERO));
+/// before.present_diff(&before);
+/// assert_eq!(canvas::last_frame_stats().cells_changed, 8);
+/// canvas::set_refresh_interval(None);
+/// ```
+pub fn set_refresh_interval(interval: Option<Duration>) {
+    STATS.lock().unwrap_or_else(PoisonError::into_inner).refresh_interval = interval;
+}
+
+
+/// Get the interval set with `set_refresh_interval`.
+pub fn refresh_interval() -> Option<Duration> {
+    STATS.lock().unwrap_or_else(PoisonError::into_inner).refresh_interval
+}
+
+
+/// Get whether a frame started at `start` is due to repaint the whole terminal (see `set_refresh_interval`).
+fn refresh_due(start: Instant) -> bool {
+    let stats = STATS.lock().unwrap_or_else(PoisonError::into_inner);
+    match (stats.refresh_interval, stats.last_full) {
+        (Some(interval), Some(last)) => start.saturating_duration_since(last) >= interval,
+        (Some(_), None) => true,
+        (None, _) => false
+    }
+}
+
+
+/// Highlight the changes in `diff`, from `Canvas::diff`, on `canvas`. A cell that now holds something other than a space
+/// is drawn with its new character in `added_style`, and a cell cleared to a space is drawn with the character it lost
+/// in `removed_style`.
+///
+/// Example
+/// ```
+/// use terminalgl::canvas::{self, Canvas};
+/// use terminalgl::drawc;
+/// let mut before = Canvas::new(8, 2);
+/// before.text(0, 0, "cpu 12%", "");
+/// before.text(0, 1, "mem 4G", "");
+/// let mut after = Canvas::new(8, 2);
+/// after.text(0, 0, "cpu 15%", "");
+/// after.text(0, 1, "mem", "");
+/// let mut overlay = after.clone();
+/// canvas::render_diff_overlay(&mut overlay, &after.diff(&before), drawc::GREEN, drawc::RED);
+/// assert_eq!(overlay.get(5, 0).unwrap().c, '5');
+/// assert_eq!(overlay.ccode_at(5, 0), Some(drawc::GREEN));
+/// assert_eq!(overlay.get(5, 1).unwrap().c, 'G');
+/// assert_eq!(overlay.ccode_at(5, 1), Some(drawc::RED));
+/// assert_eq!(overlay.ccode_at(4, 0), Some(""));
+/// ```
+pub fn render_diff_overlay(canvas: &mut Canvas, diff: &[(isize, isize, Cell, Cell)], added_style: &str, removed_style: &str) {
+    for (x, y, before, after) in diff {
+        if after.c == ' ' {
+            canvas.set(*x, *y, before.c, removed_style);
+        }
+        else {
+            canvas.set(*x, *y, after.c, added_style);
+        }
+    }
+}
+
+
+/// Running summary of a block of cells being shrunk into one, for `Canvas::render_scaled` and `TileMap::render_minimap`:
+/// how often each character appears and the total of their colors.
+#[derive(Default)]
+pub(crate) struct Tally {
+    chars: Vec<(char, usize)>,
+    fg: [u64; 3],
+    bg: [u64; 3],
+    count: u64,
+    bg_count: u64
+}
+
+impl Tally {
+    /// Count a cell of `c` drawn in `fg` over `bg`, or over the terminal's default background if `bg` is `None`.
+    pub(crate) fn add(&mut self, c: char, fg: (u8, u8, u8), bg: Option<(u8, u8, u8)>) {
+        match self.chars.iter_mut().find(|(seen, _)| *seen == c) {
+            Some((_, n)) => *n += 1,
+            None => self.chars.push((c, 1))
+        }
+        for (total, channel) in self.fg.iter_mut().zip([fg.0, fg.1, fg.2]) {
+            *total += channel as u64;
+        }
+        if let Some(bg) = bg {
+            for (total, channel) in self.bg.iter_mut().zip([bg.0, bg.1, bg.2]) {
+                *total += channel as u64;
+            }
+            self.bg_count += 1;
+        }
+        self.count += 1;
+    }
+
+    /// Get the cell standing for the block: its most common character (the first counted, on ties) in its average foreground,
+    /// over its average background if most of its cells have one. `None` if nothing was counted.
+    pub(crate) fn cell(&self) -> Option<Cell> {
+        let mut best = *self.chars.first()?;
+        for &(c, n) in &self.chars[1..] {
+            if n > best.1 {
+                best = (c, n);
+            }
+        }
+        let average = |total: [u64; 3], count: u64| ((total[0] / count) as u8, (total[1] / count) as u8, (total[2] / count) as u8);
+        let mut ccode = drawc::rgb_to_ccode(average(self.fg, self.count), drawc::ColorKind::Fg);
+        if self.bg_count * 2 > self.count {
+            ccode += &drawc::rgb_to_ccode(average(self.bg, self.bg_count), drawc::ColorKind::Bg);
+        }
+        Some(Cell::new(best.0, &ccode))
+    }
+}
+
+
+/// The cell changes of one undoable step of a canvas's journal (see `Canvas::journal`).
+#[derive(Clone, Debug)]
+enum Changes {
+    /// Each change as `(index, before, after)`, in the order they were made.
+    Cells(Vec<(usize, Cell, Cell)>),
+    /// Every cell before and after, for steps that changed too many cells to list. `after` is filled in when the step is committed.
+    Whole { before: Vec<Cell>, after: Vec<Cell> }
+}
+
+
+/// A committed or open step of a canvas's journal, with the label it was committed with.
+#[derive(Clone, Debug)]
+struct Transaction {
+    label: String,
+    changes: Changes
+}
+
+
+/// Undo history of a canvas, from `Canvas::journal`.
+#[derive(Clone, Debug)]
+struct Journal {
+    max_transactions: usize,
+    open: Option<Transaction>,
+    undo: VecDeque<Transaction>,
+    redo: Vec<Transaction>
+}
+
+impl Journal {
+    /// Record cell `i` of `cells` changing from `before` to `after` in the open transaction, opening one if needed,
+    /// and forget what could be redone. Once the transaction lists as many changes as the canvas has cells,
+    /// it is replaced with a copy of the whole canvas from before it started.
+    fn record(&mut self, i: usize, before: &Cell, after: &Cell, cells: &[Cell]) {
+        self.redo.clear();
+        let open = self.open.get_or_insert_with(|| Transaction { label: String::new(), changes: Changes::Cells(Vec::new()) });
+        if let Changes::Cells(changes) = &mut open.changes {
+            changes.push((i, before.clone(), after.clone()));
+            if changes.len() >= cells.len() {
+                let mut whole = cells.to_vec();
+                for (i, before, _) in changes.iter().rev() {
+                    whole[*i] = before.clone();
+                }
+                open.changes = Changes::Whole { before: whole, after: Vec::new() };
+            }
+        }
+    }
+
+    /// Close the open transaction with `label`, if it changed anything, dropping the oldest step if there are too many.
+    fn commit(&mut self, label: &str, cells: &[Cell]) {
+        let Some(mut transaction) = self.open.take() else {
+            return;
+        };
+        match &mut transaction.changes {
+            Changes::Cells(changes) if changes.is_empty() => return,
+            Changes::Cells(_) => {},
+            Changes::Whole { after, .. } => *after = cells.to_vec()
+        }
+        transaction.label = label.to_string();
+        self.undo.push_back(transaction);
+        while self.undo.len() > self.max_transactions {
+            self.undo.pop_front();
+        }
+    }
+}
+
+
+/// A `width` by `height` buffer of cells that is printed to the terminal with `present`.
+///
+/// Example
+/// ```
+/// use terminalgl::canvas::Canvas;
+/// use terminalgl::drawc;
+/// let mut canvas = Canvas::new(20, 5);
+/// canvas.set(1, 1, '#', drawc::RED);
+/// canvas.text(3, 1, "hello", "");
+/// assert_eq!(canvas.get(1, 1).unwrap().c, '#');
+/// assert_eq!(canvas.get(20, 1), None);
+/// canvas.present();
+/// ```
+#[derive(Clone, Debug)]
+pub struct Canvas {
+    width: usize,
+    height: usize,
+    cells: Vec<Cell>,
+    palette: Vec<String>,
+    damaged: Vec<bool>,
+    protected: Vec<bool>,
+    blend: BlendMode,
+    debug_stats: bool,
+    merge_borders: bool,
+    force: bool,
+    recording: Option<Vec<(isize, isize)>>,
+    stale: Vec<Rect>,
+    journal: Option<Journal>,
+    highlights: Vec<(usize, Cell, Cell)>,
+    pen: char,
+    style: String,
+    filters: Filters
+}
+
+// Canvases are equal when they draw the same cells, whatever damage they have left to take, cells they protect, undo history,
+// highlights to clear, pen, filters, or modes and flags they have set.
+impl PartialEq for Canvas {
+    fn eq(&self, other: &Canvas) -> bool {
+        self.width == other.width && self.height == other.height && self.cells == other.cells && self.palette == other.palette
+    }
+}
+
+impl Eq for Canvas {}
+
+impl Canvas {
+    /// Create a blank canvas `width` by `height` cells in size.
+    pub fn new(width: usize, height: usize) -> Canvas {
+        Canvas {
+            width,
+            height,
+            cells: vec![Cell::default(); width * height],
+            palette: Vec::new(),
+            damaged: vec![true; width * height],
+            protected: vec![false; width * height],
+            blend: BlendMode::Replace,
+            debug_stats: false,
+            merge_borders: false,
+            force: false,
+            recording: None,
+            stale: Vec::new(),
+            journal: None,
+            highlights: Vec::new(),
+            pen: '#',
+            style: String::new(),
+            filters: Filters::default()
+        }
+    }
+
+    /// Create a blank canvas the size of the terminal.
+    pub fn screen() -> Canvas {
+        let (cols, rows) = super::size();
+        Canvas::new(cols as usize, rows as usize)
+    }
+
+    /// Get the width of the canvas.
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Get the height of the canvas.
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Get the rectangle covering the whole canvas.
+    pub fn rect(&self) -> Rect {
+        Rect::new(0, 0, self.width, self.height)
+    }
+
+    fn index(&self, x: isize, y: isize) -> Option<usize> {
+        if x >= 0 && y >= 0 && (x as usize) < self.width && (y as usize) < self.height {
+            Some(y as usize * self.width + x as usize)
+        }
+        else {
+            None
+        }
+    }
+
+    /// Get the cell at `(x, y)`, or `None` if it is outside the canvas.
+    pub fn get(&self, x: isize, y: isize) -> Option<&Cell> {
+        self.index(x, y).map(|i| &self.cells[i])
+    }
+
+    /// Set the cell at `(x, y)` to `c` drawn with `ccode`. Cells outside the canvas are ignored.
+    pub fn set(&mut self, x: isize, y: isize, c: char, ccode: &str) {
+        self.put(x, y, Cell::new(c, ccode));
+    }
+
+    /// Set the cell at `(x, y)` to `cell`, with its character passed through `chars::sanitize` and a `ccode` that isn't
+    /// a color code (see `drawc::is_ccode`) dropped, so nothing drawn can print its own escape sequences.
+    /// Cells outside the canvas or protected (see `set_protected`) are ignored.
+    /// The cell is combined with the one there by the canvas's `BlendMode` (see `set_blend`), then with `merge_borders` on,
+    /// a box-drawing character put over another is joined with it by `chars::merge`.
+    ///
+    /// Example
+    /// ```
+    /// use terminalgl::canvas::{Canvas, Cell};
+    /// use terminalgl::drawc;
+    /// let mut canvas = Canvas::new(2, 1);
+    /// canvas.put(0, 0, Cell::new('\x1b', "\x1b[2J"));
+    /// canvas.set(1, 0, '#', drawc::RED);
+    /// assert_eq!(canvas.get(0, 0), Some(&Cell::new('·', "")));
+    /// assert_eq!(canvas.ccode_at(1, 0), Some(drawc::RED));
+    /// ```
+    pub fn put(&mut self, x: isize, y: isize, cell: Cell) {
+        if let Some(i) = self.index(x, y).filter(|&i| self.writable(i)) {
+            let Some(mut cell) = self.blended(&self.cells[i], cell.sanitized()) else {
+                return;
+            };
+            if self.merge_borders {
+                cell.c = chars::merge(self.cells[i].c, cell.c);
+            }
+            self.write(i, cell);
+            if let Some(cells) = &mut self.recording {
+                cells.push((x, y));
+            }
+        }
+    }
+
+    /// Run `draw` with the canvas, and register every cell it draws in `hits` as belonging to `id`, so clicks are tested against
+    /// the shape drawn rather than a rectangle around it. Cells left as they were, like protected ones, aren't registered.
+    /// See `HitGrid` for an example.
+    #[cfg(feature = "widgets")]
+    pub fn with_hit_id<Id: Copy + PartialEq, R>(&mut self, hits: &mut HitGrid<Id>, id: Id, draw: impl FnOnce(&mut Canvas) -> R) -> R {
+        let outer = self.recording.replace(Vec::new());
+        let result = draw(self);
+        let cells = self.recording.take().unwrap_or_default();
+        if let Some(mut outer) = outer {
+            // Drawn inside an outer call too, so its region covers them as well.
+            outer.extend_from_slice(&cells);
+            self.recording = Some(outer);
+        }
+        hits.add_cells(cells, id);
+        result
+    }
+
+    /// Set how cells drawn from now on combine with the cells already there (see `BlendMode`). `Replace` by default.
+    /// Every drawing method goes through `put`, so this applies to all of them, and to tile maps and anything else drawn
+    /// onto the canvas. Cells something leaves transparent, like `EMPTY` tiles, are never put and stay as they are in every mode,
+    /// and protected cells (see `set_protected`) aren't drawn over in any mode unless `force` is on.
+    /// `clear`, `darken`, `flood_fill`, and `restore` set cells directly whatever the mode.
+    ///
+    /// For `Max` and `Min`, a cell's brightness is the luminance of the color its character is drawn in (the default
+    /// foreground, and 256-color indices, count as `WHITE`), or of its background if it is a space, or zero for an empty cell.
+    ///
+    /// Example
+    /// ```
+    /// use terminalgl::canvas::{BlendMode, Canvas, Cell};
+    /// use terminalgl::drawc;
+    /// let empty = Cell::default();
+    /// let plain = Cell::new('a', "");
+    /// let dim = Cell::new('d', drawc::BLUE);
+    /// let filled = Cell::new(' ', drawc::RED_BG);
+    /// let glyph = Cell::new('#', drawc::GREEN);
+    /// let rows = [
+    ///     //                  drawn over: empty            plain                     dim                       filled
+    ///     (BlendMode::Replace,    [('#', drawc::GREEN), ('#', drawc::GREEN),     ('#', drawc::GREEN),     ('#', drawc::GREEN)]),
+    ///     (BlendMode::KeepChar,   [(' ', drawc::GREEN), ('a', drawc::GREEN),     ('d', drawc::GREEN),     (' ', drawc::GREEN)]),
+    ///     (BlendMode::KeepColors, [('#', ""),           ('#', ""),               ('#', drawc::BLUE),      ('#', drawc::RED_BG)]),
+    ///     (BlendMode::Under,      [('#', drawc::GREEN), ('a', ""),               ('d', drawc::BLUE),      (' ', drawc::RED_BG)]),
+    ///     (BlendMode::Max,        [('#', drawc::GREEN), ('a', ""),               ('#', drawc::GREEN),     ('#', drawc::GREEN)]),
+    ///     (BlendMode::Min,        [(' ', ""),           ('#', drawc::GREEN),     ('d', drawc::BLUE),      (' ', drawc::RED_BG)])
+    /// ];
+    /// for (mode, expected) in rows {
+    ///     for (under, (c, ccode)) in [&empty, &plain, &dim, &filled].into_iter().zip(expected) {
+    ///         let mut canvas = Canvas::new(1, 1);
+    ///         canvas.put(0, 0, under.clone());
+    ///         canvas.set_blend(mode);
+    ///         canvas.put(0, 0, glyph.clone());
+    ///         assert_eq!(canvas.get(0, 0), Some(&Cell::new(c, ccode)), "{:?} over {:?}", mode, under);
+    ///     }
+    /// }
+    ///
+    /// // A background texture drawn after the foreground fills in around it, and protected cells stay as they are.
+    /// let mut canvas = Canvas::new(5, 1);
+    /// canvas.text(1, 0, "hi", "");
+    /// canvas.set_protected(terminalgl::Rect::new(4, 0, 1, 1), true);
+    /// canvas.set_blend(BlendMode::Under);
+    /// canvas.rectangle(0, 0, 5, 1, '.', "", true);
+    /// assert_eq!((0..5).map(|x| canvas.get(x, 0).unwrap().c).collect::<String>(), ".hi. ");
+    /// assert_eq!(canvas.blend(), BlendMode::Under);
+    /// ```
+    pub fn set_blend(&mut self, mode: BlendMode) {
+        self.blend = mode;
+    }
+
+    /// Get how drawn cells combine with the cells already there, as set with `set_blend`.
+    pub fn blend(&self) -> BlendMode {
+        self.blend
+    }
+
+    /// Get the cell `cell` drawn over `under` leaves in the canvas's `BlendMode`, or `None` if `under` stays.
+    fn blended(&self, under: &Cell, cell: Cell) -> Option<Cell> {
+        match self.blend {
+            BlendMode::Replace => Some(cell),
+            BlendMode::KeepChar => Some(Cell { c: under.c, ..cell }),
+            BlendMode::KeepColors => Some(Cell { c: cell.c, ..under.clone() }),
+            BlendMode::Under => self.is_empty_cell(under).then_some(cell),
+            BlendMode::Max => (self.brightness(&cell) >= self.brightness(under)).then_some(cell),
+            BlendMode::Min => (self.brightness(&cell) <= self.brightness(under)).then_some(cell)
+        }
+    }
+
+    /// Get whether `cell` is a space drawn in the default colors.
+    fn is_empty_cell(&self, cell: &Cell) -> bool {
+        cell.c == ' ' && self.ccode_of(cell).is_empty()
+    }
+
+    /// Get the brightness of `cell` from `0.0` to `1.0` for `BlendMode::Max` and `Min`.
+    fn brightness(&self, cell: &Cell) -> f64 {
+        let (fg, bg) = drawc::ccode_to_rgb(self.ccode_of(cell));
+        let rgb = match cell.c {
+            ' ' => bg,
+            _ => Some(fg.unwrap_or(drawc::NamedColor::White.to_rgb()))
+        };
+        rgb.map_or(0.0, |(r, g, b)| (0.2126 * r as f64 + 0.7152 * g as f64 + 0.0722 * b as f64) / 255.0)
+    }
+
+    /// Turn merging of box-drawing characters on or off. While on, drawing a box-drawing character over another joins
+    /// their lines instead of replacing it, so `│` over `─` becomes `┼` and a corner meeting an edge becomes a tee,
+    /// in the style of the character drawn last. Any other character still replaces what is under it. Off by default.
+    ///
+    /// Example
+    /// ```
+    /// use terminalgl::canvas::Canvas;
+    /// use terminalgl::chars::LineStyle;
+    /// use terminalgl::{assert_frame_eq, Rect};
+    /// let mut canvas = Canvas::new(9, 5);
+    /// canvas.merge_borders(true);
+    /// for (x, y) in [(0, 0), (4, 0), (0, 2), (4, 2)] {
+    ///     canvas.border(Rect::new(x, y, 5, 3), LineStyle::Light, "");
+    /// }
+    /// assert_frame_eq!(canvas, "\
+    /// |┌───┬───┐|
+    /// |│   │   │|
+    /// |├───┼───┤|
+    /// |│   │   │|
+    /// |└───┴───┘|");
+    ///
+    /// canvas.border(Rect::new(2, 0, 1, 5), LineStyle::Heavy, "");
+    /// canvas.text(1, 3, "ab", "");
+    /// assert_frame_eq!(canvas, "\
+    /// |┌─┳─┬───┐|
+    /// |│ ┃ │   │|
+    /// |├─╋─┼───┤|
+    /// |│ab │   │|
+    /// |└─┻─┴───┘|");
+    /// ```
+    pub fn merge_borders(&mut self, merge: bool) {
+        self.merge_borders = merge;
+    }
+
+    /// Protect the cells inside `rect`, or stop protecting them. Drawing, `clear`, `darken`, and `flood_fill` leave protected cells as
+    /// they are unless `force` is on, so static chrome like a border can be drawn once and survive every frame drawn over it.
+    /// `present` and the other ways of printing the canvas show protected cells like any other, and `restore` puts them back exactly.
+    /// Cells outside the canvas are ignored.
+    ///
+    /// Example
+    /// ```
+    /// use terminalgl::canvas::Canvas;
+    /// use terminalgl::chars::LineStyle;
+    /// use terminalgl::{assert_frame_eq, Rect};
+    /// let mut canvas = Canvas::new(6, 4);
+    /// canvas.border(canvas.rect(), LineStyle::Light, "");
+    /// canvas.set_protected(Rect::new(0, 0, 6, 4), true);
+    /// canvas.set_protected(Rect::new(1, 1, 4, 2), false);
+    /// canvas.rectangle(0, 0, 6, 4, '#', "", true);
+    /// assert_frame_eq!(canvas, "\
+    /// |┌────┐|
+    /// |│####│|
+    /// |│####│|
+    /// |└────┘|");
+    ///
+    /// canvas.clear();
+    /// assert!(canvas.is_protected(0, 0) && !canvas.is_protected(1, 1));
+    /// assert_eq!(canvas.get(0, 0).unwrap().c, '┌');
+    /// assert_eq!(canvas.get(1, 1).unwrap().c, ' ');
+    ///
+    /// canvas.force(true);
+    /// canvas.text(0, 0, "title", "");
+    /// canvas.force(false);
+    /// assert_eq!(canvas.get(0, 0).unwrap().c, 't');
+    /// ```
+    pub fn set_protected(&mut self, rect: Rect, protected: bool) {
+        for (x, y) in rect.intersect(self.rect()).cells() {
+            self.protected[y as usize * self.width + x as usize] = protected;
+        }
+    }
+
+    /// Get whether the cell at `(x, y)` is protected (see `set_protected`). Cells outside the canvas are not.
+    pub fn is_protected(&self, x: isize, y: isize) -> bool {
+        self.index(x, y).is_some_and(|i| self.protected[i])
+    }
+
+    /// Turn drawing over protected cells on or off (see `set_protected`). Off by default.
+    pub fn force(&mut self, force: bool) {
+        self.force = force;
+    }
+
+    /// Get whether cell `i` can be drawn over: it isn't protected, or `force` is on.
+    fn writable(&self, i: usize) -> bool {
+        self.force || !self.protected[i]
+    }
+
+    /// Set cell `i` to `cell`, marking it damaged if it changed.
+    fn write(&mut self, i: usize, cell: Cell) {
+        if self.cells[i] != cell {
+            if let Some(journal) = &mut self.journal {
+                journal.record(i, &self.cells[i], &cell, &self.cells);
+            }
+            self.cells[i] = cell;
+            self.damaged[i] = true;
+        }
+    }
+
+    /// Mark every cell drawn with a palette entry in `entries` as damaged.
+    fn damage_palette(&mut self, entries: impl Fn(usize) -> bool) {
+        for (cell, damaged) in self.cells.iter().zip(self.damaged.iter_mut()) {
+            if cell.palette.is_some_and(&entries) {
+                *damaged = true;
+            }
+        }
+    }
+
+    /// Set the cell at `(x, y)` to `c` drawn with entry `index` of the palette. Cells outside the canvas are ignored.
+    pub fn set_indexed(&mut self, x: isize, y: isize, c: char, index: usize) {
+        self.put(x, y, Cell::indexed(c, index));
+    }
+
+    /// Get the color code the cell at `(x, y)` is drawn with, which for a cell with a palette index is that palette entry,
+    /// or `None` if it is outside the canvas.
+    pub fn ccode_at(&self, x: isize, y: isize) -> Option<&str> {
+        self.get(x, y).map(|cell| self.ccode_of(cell))
+    }
+
+    fn ccode_of<'a>(&'a self, cell: &'a Cell) -> &'a str {
+        match cell.palette {
+            Some(i) => self.palette.get(i).map_or("", String::as_str),
+            None => &cell.ccode
+        }
+    }
+
+    /// Get entry `index` of the palette, or `None` if it has never been set.
+    pub fn palette_entry(&self, index: usize) -> Option<&str> {
+        self.palette.get(index).map(String::as_str)
+    }
+
+    /// Set entry `index` of the palette to `ccode`, recoloring every cell drawn with it.
+    /// Entries below `index` that have never been set, or set to something that isn't a color code (see `drawc::is_ccode`),
+    /// draw with the terminal's default colors.
+    pub fn set_palette_entry(&mut self, index: usize, ccode: &str) {
+        let ccode = if drawc::is_ccode(ccode) { ccode } else { "" };
+        if index >= self.palette.len() {
+            self.palette.resize(index + 1, String::new());
+        }
+        if self.palette[index] != ccode {
+            self.palette[index] = ccode.to_string();
+            self.damage_palette(|i| i == index);
+        }
+    }
+
+    /// Rotate the palette entries in `range` by `by` places, so entry `i` moves to `i + by`, wrapping around within `range`.
+    /// Negative `by` rotates the other way. Cells drawn with these entries cycle through their colors without being redrawn,
+    /// and `present_diff` reprints only the cells whose color changed.
+    ///
+    /// Example
+    /// ```
+    /// use terminalgl::canvas::Canvas;
+    /// use terminalgl::drawc;
+    /// let mut canvas = Canvas::new(4, 1);
+    /// for (i, ccode) in [drawc::RED, drawc::GREEN, drawc::BLUE].iter().enumerate() {
+    ///     canvas.set_palette_entry(i, ccode);
+    ///     canvas.set_indexed(i as isize, 0, '#', i);
+    /// }
+    /// canvas.set_indexed(3, 0, '#', 0);
+    /// let before = canvas.clone();
+    /// canvas.rotate_palette(1..3, 1);
+    /// assert_eq!(canvas.ccode_at(1, 0), Some(drawc::BLUE));
+    /// assert_eq!(canvas.ccode_at(2, 0), Some(drawc::GREEN));
+    /// assert_eq!(canvas.diff_ansi(&before), "\x1b[1;2H\x1b[0m\x1b[34m#\x1b[0m\x1b[32m#\x1b[0m");
+    /// ```
+    pub fn rotate_palette(&mut self, range: Range<usize>, by: isize) {
+        if range.start >= range.end {
+            return;
+        }
+        if range.end > self.palette.len() {
+            self.palette.resize(range.end, String::new());
+        }
+        let before = self.palette[range.clone()].to_vec();
+        let by = by.rem_euclid(before.len() as isize) as usize;
+        self.palette[range.clone()].rotate_right(by);
+        let changed: Vec<bool> = before.iter().zip(&self.palette[range.clone()]).map(|(a, b)| a != b).collect();
+        self.damage_palette(|i| range.contains(&i) && changed[i - range.start]);
+    }
+
+    /// Draw `text` starting at `(x, y)` with `ccode`, sanitized with `chars::sanitize_text`, and get what was drawn.
+    /// Characters outside the canvas are skipped.
+    ///
+    /// Example
+    /// ```
+    /// use terminalgl::canvas::Canvas;
+    /// use terminalgl::Rect;
+    /// let mut canvas = Canvas::new(10, 3);
+    /// assert_eq!(canvas.measure_text(7, 1, "hello").bounds, Rect::new(7, 1, 3, 1));
+    /// assert_eq!(canvas.text(7, 1, "hello", ""), canvas.measure_text(7, 1, "hello"));
+    ///
+    /// // Tabs expand and control characters are replaced, so the only escapes printed are the canvas's own.
+    /// let mut canvas = Canvas::new(16, 2);
+    /// canvas.text(0, 0, "a\tb\r\x1b[2Jc\x07", "");
+    /// canvas.set(0, 1, '\n', "");
+    /// canvas.text_wrapped(1, 1, 15, "\x1b]0;title\x07", "");
+    /// let out = canvas.to_ansi();
+    /// assert_eq!(out, "\x1b[1;1Ha   b··[2Jc·    \x1b[0m\x1b[2;1H··]0;title·     \x1b[0m");
+    /// assert_eq!(out.matches('\x1b').count(), 4);
+    /// assert!(!out.contains(['\r', '\n', '\t', '\x07']));
+    /// ```
+    pub fn text(&mut self, x: isize, y: isize, text: &str, ccode: &str) -> DrawResult {
+        let cells = self.text_cells(x, y, text);
+        for &(x, y, c) in &cells {
+            self.set(x, y, c, ccode);
+        }
+        DrawResult::of(cells.into_iter().map(|(x, y, _)| (x, y)))
+    }
+
+    /// Get what `text` would draw, without drawing anything.
+    pub fn measure_text(&self, x: isize, y: isize, text: &str) -> DrawResult {
+        DrawResult::of(self.text_cells(x, y, text).into_iter().map(|(x, y, _)| (x, y)))
+    }
+
+    /// Draw `spans` of text one after another starting at `(x, y)`, each `(text, ccode)` drawn like `text`,
+    /// and get what was drawn. This is how to style parts of a line: escape sequences embedded in text are never printed,
+    /// and only characters reach the cells the text covers.
+    ///
+    /// Example
+    /// ```
+    /// use terminalgl::canvas::Canvas;
+    /// use terminalgl::{drawc, Rect};
+    /// let mut canvas = Canvas::new(12, 3);
+    /// canvas.rectangle(0, 0, 12, 3, '.', "", true);
+    /// let drawn = canvas.spans(1, 1, &[("hp ", ""), ("\x1b[2J\x1b[H", drawc::RED), ("\x1b]0;pwned\x07", drawc::GREEN)]);
+    /// assert_eq!(drawn.bounds, Rect::new(1, 1, 11, 1));
+    /// let row = |y| (0..12).map(|x| canvas.get(x, y).unwrap().c).collect::<String>();
+    /// assert_eq!([row(0), row(1), row(2)], ["............", ".hp ·[2J·[H·", "............"]);
+    /// assert_eq!(canvas.ccode_at(4, 1), Some(drawc::RED));
+    ///
+    /// // Every escape printed is the canvas's own: a cursor movement per row, and colors.
+    /// let out = canvas.to_ansi();
+    /// for injected in ["\x1b[2J", "\x1b[H", "\x1b]", "\x07"] {
+    ///     assert!(!out.contains(injected));
+    /// }
+    /// ```
+    pub fn spans(&mut self, x: isize, y: isize, spans: &[(&str, &str)]) -> DrawResult {
+        let mut result = Vec::new();
+        let mut x = x;
+        for &(text, ccode) in spans {
+            let cells = self.text_cells(x, y, text);
+            for &(x, y, c) in &cells {
+                self.set(x, y, c, ccode);
+            }
+            result.extend(cells.into_iter().map(|(x, y, _)| (x, y)));
+            x = x.saturating_add(chars::sanitize_text(text).count() as isize);
+        }
+        DrawResult::of(result)
+    }
+
+    /// Get the cells inside the canvas that `text` draws, and the character each gets.
+    fn text_cells(&self, x: isize, y: isize, text: &str) -> Vec<(isize, isize, char)> {
+        chars::sanitize_text(text).enumerate()
+            .map(|(i, c)| (x.saturating_add(i as isize), y, c))
+            .filter(|&(x, y, _)| self.index(x, y).is_some())
+            .collect()
+    }
+
+    /// Draw `text` wrapped to lines at most `width` cells wide with `raster::wrap_words`, starting at `(x, y)`,
+    /// and get what was drawn. The bounds' height is the number of lines used, when they all fit on the canvas.
+    ///
+    /// Example
+    /// ```
+    /// use terminalgl::canvas::Canvas;
+    /// use terminalgl::Rect;
+    /// let mut canvas = Canvas::new(20, 6);
+    /// let measured = canvas.measure_text_wrapped(1, 1, 10, "the quick brown fox jumps");
+    /// assert_eq!(measured.bounds, Rect::new(1, 1, 9, 3));
+    /// assert_eq!(canvas.text_wrapped(1, 1, 10, "the quick brown fox jumps", ""), measured);
+    /// assert_eq!(canvas.get(1, 3).unwrap().c, 'j');
+    /// ```
+    pub fn text_wrapped(&mut self, x: isize, y: isize, width: usize, text: &str, ccode: &str) -> DrawResult {
+        let mut result = Vec::new();
+        for (i, line) in raster::wrap_words(text, width).iter().enumerate() {
+            let cells = self.text_cells(x, y.saturating_add(i as isize), line);
+            for &(x, y, c) in &cells {
+                self.set(x, y, c, ccode);
+            }
+            result.extend(cells.into_iter().map(|(x, y, _)| (x, y)));
+        }
+        DrawResult::of(result)
+    }
+
+    /// Get what `text_wrapped` would draw, without drawing anything.
+    pub fn measure_text_wrapped(&self, x: isize, y: isize, width: usize, text: &str) -> DrawResult {
+        let lines = raster::wrap_words(text, width);
+        DrawResult::of(lines.iter().enumerate().flat_map(|(i, line)| {
+            self.text_cells(x, y.saturating_add(i as isize), line).into_iter().map(|(x, y, _)| (x, y))
+        }))
+    }
+
+    /// Draw `text` read in `direction` at `(x, y)` with alignment `align`, laid out with `raster::text_directed`,
+    /// and get what was drawn.
+    ///
+    /// Example
+    /// ```
+    /// use terminalgl::canvas::Canvas;
+    /// use terminalgl::{assert_frame_eq, TextAlignment, TextDirection};
+    /// let mut canvas = Canvas::new(10, 2);
+    /// canvas.text_directed(10, 0, "שלום", TextAlignment::Left, TextDirection::Rtl, "");
+    /// canvas.text_directed(0, 1, "(עברית)", TextAlignment::Right, TextDirection::Rtl, "");
+    /// assert_frame_eq!(canvas, "\
+    /// |      םולש|
+    /// |(תירבע)   |");
+    /// ```
+    pub fn text_directed(&mut self, x: isize, y: isize, text: &str, align: TextAlignment, direction: TextDirection, ccode: &str) -> DrawResult {
+        let cells = raster::text_directed(x, y, text, align, direction);
+        self.draw_text_cells(cells, ccode)
+    }
+
+    /// Draw `text` read in `direction` wrapped to lines at most `width` cells wide, with each line aligned with `align`
+    /// between `x` and `x + width`, laid out with `raster::text_wrapped_directed`, and get what was drawn.
+    ///
+    /// Example
+    /// ```
+    /// use terminalgl::canvas::Canvas;
+    /// use terminalgl::{assert_frame_eq, raster, TextAlignment, TextDirection, Truncation};
+    /// let mut canvas = Canvas::new(8, 4);
+    /// canvas.text_wrapped_directed(0, 0, 8, "אחת שתיים שלוש", TextAlignment::Left, TextDirection::Rtl, "");
+    /// canvas.text_wrapped_directed(0, 3, 8, &raster::truncate_to_width("ארבע חמש שש", 8, Truncation::End), TextAlignment::Left, TextDirection::Rtl, "");
+    /// assert_frame_eq!(canvas, "\
+    /// |     תחא|
+    /// |   םייתש|
+    /// |    שולש|
+    /// |…מח עברא|");
+    /// ```
+    pub fn text_wrapped_directed(&mut self, x: isize, y: isize, width: usize, text: &str, align: TextAlignment, direction: TextDirection, ccode: &str) -> DrawResult {
+        let cells = raster::text_wrapped_directed(x, y, width, text, align, direction);
+        self.draw_text_cells(cells, ccode)
+    }
+
+    /// Set each of `cells` inside the canvas to its character drawn with `ccode`, and get the result.
+    fn draw_text_cells(&mut self, cells: Vec<(isize, isize, char)>, ccode: &str) -> DrawResult {
+        let cells: Vec<_> = cells.into_iter().filter(|&(x, y, _)| self.index(x, y).is_some()).collect();
+        for &(x, y, c) in &cells {
+            self.set(x, y, c, ccode);
+        }
+        DrawResult::of(cells.into_iter().map(|(x, y, _)| (x, y)))
+    }
+
+    /// Draw a `width` by `height` rectangle of `c` at `(x, y)` with `ccode`, outlined or filled, and get what was drawn.
+    ///
+    /// Example
+    /// ```
+    /// use terminalgl::canvas::Canvas;
+    /// use terminalgl::Rect;
+    /// let mut canvas = Canvas::new(10, 5);
+    /// let result = canvas.rectangle(-2, 1, 6, 3, '#', "", false);
+    /// assert_eq!(result.bounds, Rect::new(0, 1, 4, 3));
+    /// assert_eq!(result.cells, 9);
+    /// assert_eq!(canvas.measure_rectangle(-2, 1, 6, 3, false), result);
+    /// ```
+    pub fn rectangle(&mut self, x: isize, y: isize, width: usize, height: usize, c: char, ccode: &str, fill: bool) -> DrawResult {
+        self.draw_cells(raster::rectangle_clipped(x, y, width, height, fill, self.rect()), c, ccode)
+    }
+
+    /// Get what `rectangle` would draw, without drawing anything.
+    pub fn measure_rectangle(&self, x: isize, y: isize, width: usize, height: usize, fill: bool) -> DrawResult {
+        DrawResult::of(raster::rectangle_clipped(x, y, width, height, fill, self.rect()))
+    }
+
+    /// Draw a box-drawing border around the edge of `rect` in `style` with `ccode`, and get what was drawn.
+    /// With `merge_borders` on, panels drawn next to or over each other share connected edges.
+    ///
+    /// Example
+    /// ```
+    /// use terminalgl::canvas::Canvas;
+    /// use terminalgl::chars::LineStyle;
+    /// use terminalgl::{assert_frame_eq, Rect};
+    /// let mut canvas = Canvas::new(9, 3);
+    /// canvas.border(Rect::new(0, 0, 5, 3), LineStyle::Light, "");
+    /// canvas.border(Rect::new(4, 0, 5, 3), LineStyle::Double, "");
+    /// assert_frame_eq!(canvas, "\
+    /// |┌───╔═══╗|
+    /// |│   ║   ║|
+    /// |└───╚═══╝|");
+    /// assert_eq!(canvas.border(Rect::new(isize::MIN, 0, 0, 3), LineStyle::Light, "").cells, 0);
+    /// ```
+    pub fn border(&mut self, rect: Rect, style: LineStyle, ccode: &str) -> DrawResult {
+        let (left, top) = (rect.x, rect.y);
+        let (right, bottom) = (left.saturating_add_unsigned(rect.width).saturating_sub(1), top.saturating_add_unsigned(rect.height).saturating_sub(1));
+        let cells = raster::rectangle_clipped(rect.x, rect.y, rect.width, rect.height, false, self.rect());
+        for &(x, y) in &cells {
+            let (vertical, horizontal) = (x == left || x == right, y == top || y == bottom);
+            let c = chars::junction(vertical && y > top, vertical && y < bottom, horizontal && x > left, horizontal && x < right, style);
+            self.set(x, y, c, ccode);
+        }
+        DrawResult::of(cells)
+    }
+
+    /// Draw a line of `c` from `(x1, y1)` to `(x2, y2)` with `ccode`, and get what was drawn.
+    ///
+    /// Example
+    /// ```
+    /// use terminalgl::canvas::Canvas;
+    /// use terminalgl::Rect;
+    /// let mut canvas = Canvas::new(10, 5);
+    /// let result = canvas.line(1, 1, 6, 3, '#', "");
+    /// assert_eq!(result.bounds, Rect::new(1, 1, 6, 3));
+    /// assert_eq!(canvas.measure_line(1, 1, 6, 3), result);
+    /// ```
+    pub fn line(&mut self, x1: isize, y1: isize, x2: isize, y2: isize, c: char, ccode: &str) -> DrawResult {
+        self.draw_cells(raster::line_clipped(x1, y1, x2, y2, self.rect()), c, ccode)
+    }
+
+    /// Get what `line` would draw, without drawing anything.
+    pub fn measure_line(&self, x1: isize, y1: isize, x2: isize, y2: isize) -> DrawResult {
+        DrawResult::of(raster::line_clipped(x1, y1, x2, y2, self.rect()))
+    }
+
+    /// Draw an ellipse of `c` centered at `(h, k)` with horizontal radius `a` and vertical radius `b` with `ccode`,
+    /// outlined or filled, and get what was drawn.
+    ///
+    /// Example
+    /// ```
+    /// use terminalgl::canvas::Canvas;
+    /// use terminalgl::Rect;
+    /// let mut canvas = Canvas::new(10, 6);
+    /// assert_eq!(canvas.measure_ellipse(4, 2, 3, 2, true).bounds, Rect::new(1, 0, 7, 5));
+    /// let clipped = canvas.ellipse(8, 2, 3, 2, '#', "", false);
+    /// assert_eq!(clipped.bounds, Rect::new(5, 0, 5, 5));
+    /// assert_eq!(canvas.measure_ellipse(8, 2, 3, 2, false), clipped);
+    /// ```
+    pub fn ellipse(&mut self, h: isize, k: isize, a: usize, b: usize, c: char, ccode: &str, fill: bool) -> DrawResult {
+        self.draw_cells(raster::ellipse_clipped(h, k, a, b, fill, self.rect()), c, ccode)
+    }
+
+    /// Get what `ellipse` would draw, without drawing anything.
+    pub fn measure_ellipse(&self, h: isize, k: isize, a: usize, b: usize, fill: bool) -> DrawResult {
+        DrawResult::of(raster::ellipse_clipped(h, k, a, b, fill, self.rect()))
+    }
+
+    /// Draw one quarter of the ellipse `ellipse` draws with the same arguments, for corner decorations, and get what was drawn.
+    /// See `raster::ellipse_quadrant`: drawing all four quadrants draws exactly the whole ellipse.
+    ///
+    /// Example
+    /// ```
+    /// use terminalgl::canvas::Canvas;
+    /// use terminalgl::{assert_frame_eq, Quadrant};
+    /// let mut canvas = Canvas::new(10, 3);
+    /// canvas.ellipse_quadrant(4, 2, 4, 2, Quadrant::TopLeft, '#', "", false);
+    /// canvas.ellipse_quadrant(5, 2, 4, 2, Quadrant::TopRight, '#', "", false);
+    /// assert_frame_eq!(canvas, "\
+    /// |  ######  |
+    /// | #      # |
+    /// |#        #|");
+    /// ```
+    pub fn ellipse_quadrant(&mut self, h: isize, k: isize, a: usize, b: usize, quadrant: Quadrant, c: char, ccode: &str, fill: bool) -> DrawResult {
+        self.draw_cells(raster::ellipse_quadrant_clipped(h, k, a, b, quadrant, fill, self.rect()), c, ccode)
+    }
+
+    /// Draw the half of the ellipse `ellipse` draws with the same arguments on `side` of its center, and get what was drawn.
+    /// See `raster::ellipse_half`.
+    pub fn ellipse_half(&mut self, h: isize, k: isize, a: usize, b: usize, side: Direction, c: char, ccode: &str, fill: bool) -> DrawResult {
+        self.draw_cells(raster::ellipse_half_clipped(h, k, a, b, side, fill, self.rect()), c, ccode)
+    }
+
+    /// Draw a filled `width` by `height` rectangle at `(x, y)` with its edge cells as `stroke` and the rest as `fill`, each a
+    /// character and the color code to draw it with, and get what was drawn. Leaving one out leaves those cells as they are.
+    /// The stroke is the boundary of the filled shape, worked out in the same pass, so it covers the outline `rectangle` draws
+    /// exactly, never leaving a gap between it and the fill or drawing over the fill. Cut off by the edge of the canvas,
+    /// the shape has no stroke along the cut.
+    ///
+    /// Example
+    /// ```
+    /// use terminalgl::canvas::Canvas;
+    /// use terminalgl::{assert_frame_eq, drawc};
+    /// let mut canvas = Canvas::new(7, 4);
+    /// let drawn = canvas.rectangle_sf(1, 0, 5, 5, Some(('.', drawc::BLUE)), Some(('#', drawc::WHITE)));
+    /// assert_eq!(drawn.cells, 20);
+    /// assert_frame_eq!(canvas, r#"| ##### |
+    /// | #...# |
+    /// | #...# |
+    /// | #...# |
+    ///
+    /// |.AAAAA.|
+    /// |.ABBBA.|
+    /// |.ABBBA.|
+    /// |.ABBBA.|
+    /// A "\u{1b}[37m"
+    /// B "\u{1b}[34m""#);
+    ///
+    /// let mut outline = Canvas::new(7, 4);
+    /// outline.rectangle(1, 0, 5, 5, '#', "", false);
+    /// let mut stroke = Canvas::new(7, 4);
+    /// stroke.rectangle_sf(1, 0, 5, 5, None, Some(('#', "")));
+    /// assert_eq!(stroke, outline);
+    /// ```
+    pub fn rectangle_sf(&mut self, x: isize, y: isize, width: usize, height: usize, fill: Option<(char, &str)>, stroke: Option<(char, &str)>) -> DrawResult {
+        self.stroke_and_fill(|clip| raster::rectangle_clipped(x, y, width, height, true, clip), fill, stroke)
+    }
+
+    /// Draw a filled ellipse centered at `(h, k)` with radii `a` and `b`, with its boundary cells as `stroke` and the rest as `fill`,
+    /// like `rectangle_sf`. The stroke covers the outline `ellipse` draws exactly.
+    ///
+    /// Example
+    /// ```
+    /// use terminalgl::canvas::Canvas;
+    /// use terminalgl::assert_frame_eq;
+    /// let mut canvas = Canvas::new(9, 5);
+    /// canvas.ellipse_sf(4, 2, 4, 2, Some(('.', "")), Some(('#', "")));
+    /// assert_frame_eq!(canvas, "\
+    /// |  #####  |
+    /// | #.....# |
+    /// |#.......#|
+    /// | #.....# |
+    /// |  #####  |");
+    ///
+    /// // The stroke is the outline and the fill the rest of the filled ellipse, at every size.
+    /// for (a, b) in [(0, 0), (1, 0), (3, 1), (7, 3), (2, 6), (12, 5)] {
+    ///     let mut outline = Canvas::new(30, 16);
+    ///     outline.ellipse(14, 7, a, b, '#', "", true);
+    ///     outline.ellipse(14, 7, a, b, '@', "", false);
+    ///     let mut combined = Canvas::new(30, 16);
+    ///     combined.ellipse_sf(14, 7, a, b, Some(('#', "")), Some(('@', "")));
+    ///     assert_eq!(combined, outline, "{}x{}", a, b);
+    /// }
+    /// ```
+    pub fn ellipse_sf(&mut self, h: isize, k: isize, a: usize, b: usize, fill: Option<(char, &str)>, stroke: Option<(char, &str)>) -> DrawResult {
+        self.stroke_and_fill(|clip| raster::ellipse_clipped(h, k, a, b, true, clip), fill, stroke)
+    }
+
+    /// Draw a filled circle at `(cx, cy)` with radius `radius`, with its boundary cells as `stroke` and the rest as `fill`,
+    /// like `ellipse_sf`. The circle is twice as wide as it is tall, so it looks round in a terminal.
+    ///
+    /// Example
+    /// ```
+    /// use terminalgl::canvas::Canvas;
+    /// use terminalgl::assert_frame_eq;
+    /// let mut canvas = Canvas::new(9, 5);
+    /// canvas.circle_sf(4, 2, 2, Some(('o', "")), Some(('*', "")));
+    /// assert_frame_eq!(canvas, "\
+    /// |  *****  |
+    /// | *ooooo* |
+    /// |*ooooooo*|
+    /// | *ooooo* |
+    /// |  *****  |");
+    /// ```
+    pub fn circle_sf(&mut self, cx: isize, cy: isize, radius: usize, fill: Option<(char, &str)>, stroke: Option<(char, &str)>) -> DrawResult {
+        self.ellipse_sf(cx, cy, radius.saturating_mul(2), radius, fill, stroke)
+    }
+
+    /// Draw a filled polygon with vertices `points`, with its boundary cells as `stroke` and the rest as `fill`, like `rectangle_sf`.
+    /// The filled shape is what `drawc::polygon` fills: the rows inside the polygon and the lines joining its vertices.
+    ///
+    /// Example
+    /// ```
+    /// use terminalgl::canvas::Canvas;
+    /// use terminalgl::assert_frame_eq;
+    /// let mut canvas = Canvas::new(9, 5);
+    /// canvas.polygon_sf(&[(0, 0), (8, 0), (4, 4)], Some(('.', "")), Some(('#', "")));
+    /// assert_frame_eq!(canvas, "\
+    /// |#########|
+    /// | #.....# |
+    /// |  #...#  |
+    /// |   #.#   |
+    /// |    #    |");
+    /// ```
+    pub fn polygon_sf(&mut self, points: &[(isize, isize)], fill: Option<(char, &str)>, stroke: Option<(char, &str)>) -> DrawResult {
+        self.stroke_and_fill(|clip| {
+            let mut cells: Vec<(isize, isize)> = raster::polygon_spans_clipped(points, clip).into_iter()
+                .flat_map(|(y, x1, x2)| (x1..=x2).map(move |x| (x, y)))
+                .collect();
+            for (i, &(x1, y1)) in points.iter().enumerate() {
+                let (x2, y2) = points[(i + 1) % points.len()];
+                cells.extend(raster::line_clipped(x1, y1, x2, y2, clip));
+            }
+            cells
+        }, fill, stroke)
+    }
+
+    /// Draw the cells `shape` gives inside the canvas, with those on its boundary as `stroke` and the rest as `fill`.
+    /// `shape` is given a clip one cell larger than the canvas on each side, to tell cells at the canvas's edge on the
+    /// boundary from cells with more of the shape past the edge.
+    fn stroke_and_fill(&mut self, shape: impl FnOnce(Rect) -> Vec<(isize, isize)>, fill: Option<(char, &str)>, stroke: Option<(char, &str)>) -> DrawResult {
+        let around = Rect::new(-1, -1, self.width.saturating_add(2), self.height.saturating_add(2));
+        let mut cells = shape(around);
+        cells.sort_unstable();
+        cells.dedup();
+        let filled: HashSet<(isize, isize)> = cells.iter().copied().collect();
+        let mut drawn = Vec::new();
+        for (x, y) in cells {
+            if self.index(x, y).is_none() {
+                continue;
+            }
+            let edge = [(x - 1, y), (x + 1, y), (x, y - 1), (x, y + 1)].iter().any(|cell| !filled.contains(cell));
+            if let Some((c, ccode)) = if edge { stroke } else { fill } {
+                self.set(x, y, c, ccode);
+                drawn.push((x, y));
+            }
+        }
+        DrawResult::of(drawn)
+    }
+
+    /// Set each of `cells` to `c` drawn with `ccode`, and get the result.
+    fn draw_cells(&mut self, cells: Vec<(isize, isize)>, c: char, ccode: &str) -> DrawResult {
+        for &(x, y) in &cells {
+            self.set(x, y, c, ccode);
+        }
+        DrawResult::of(cells)
+    }
+
+    /// Set the character the `_p` drawing methods (`set_p`, `line_p`, and the rest) draw with, until it is changed again. `#` by default.
+    /// The pen belongs to the canvas, so anything drawing onto it shares it; use `with_pen` to change it for one widget only.
+    pub fn set_pen(&mut self, c: char) {
+        self.pen = c;
+    }
+
+    /// Set the color code the `_p` drawing methods draw with, until it is changed again. Empty, for the terminal's default colors, by default.
+    pub fn set_style(&mut self, ccode: &str) {
+        self.style = ccode.to_string();
+    }
+
+    /// Get the character the `_p` drawing methods draw with.
+    pub fn pen(&self) -> char {
+        self.pen
+    }
+
+    /// Get the color code the `_p` drawing methods draw with.
+    pub fn style(&self) -> &str {
+        &self.style
+    }
+
+    /// Run `draw` with the pen set to `c` and the style to `ccode`, then put back the pen and style from before, even if
+    /// `draw` changes them or panics, so one widget's pen never leaks into the next.
+    ///
+    /// Example
+    /// ```
+    /// use terminalgl::canvas::Canvas;
+    /// use terminalgl::{assert_frame_eq, drawc};
+    /// use std::panic::{self, AssertUnwindSafe};
+    /// let mut canvas = Canvas::new(6, 3);
+    /// canvas.set_pen('.');
+    /// canvas.line_p(0, 0, 5, 0);
+    /// let drawn = canvas.with_pen('*', drawc::RED, |canvas| {
+    ///     canvas.line_p(0, 1, 2, 1);
+    ///     canvas.set_pen('?');
+    ///     if canvas.pen() == '?' {
+    ///         return canvas.rectangle_p(3, 1, 3, 1, true).cells;
+    ///     }
+    ///     0
+    /// });
+    /// assert_eq!(drawn, 3);
+    /// assert_eq!((canvas.pen(), canvas.style()), ('.', ""));
+    ///
+    /// let panicked = panic::catch_unwind(AssertUnwindSafe(|| canvas.with_pen('!', drawc::BLUE, |_| panic!("widget failed"))));
+    /// assert!(panicked.is_err());
+    /// assert_eq!((canvas.pen(), canvas.style()), ('.', ""));
+    /// canvas.text_p(0, 2, "ok");
+    /// assert_frame_eq!(canvas, "\
+    /// |......|
+    /// |***???|
+    /// |ok    |
+    ///
+    /// |......|
+    /// |AAAAAA|
+    /// |......|
+    /// A \"\\u{1b}[31m\"");
+    /// ```
+    pub fn with_pen<R>(&mut self, c: char, ccode: &str, draw: impl FnOnce(&mut Canvas) -> R) -> R {
+        let previous = (std::mem::replace(&mut self.pen, c), std::mem::replace(&mut self.style, ccode.to_string()));
+        let mut guard = PenGuard { canvas: self, previous };
+        draw(&mut guard)
+    }
+
+    /// Set the cell at `(x, y)` to the pen drawn with the style, like `set`.
+    pub fn set_p(&mut self, x: isize, y: isize) {
+        let ccode = self.style.clone();
+        self.set(x, y, self.pen, &ccode);
+    }
+
+    /// Draw `text` starting at `(x, y)` with the style, like `text`.
+    pub fn text_p(&mut self, x: isize, y: isize, text: &str) -> DrawResult {
+        let ccode = self.style.clone();
+        self.text(x, y, text, &ccode)
+    }
+
+    /// Draw a `width` by `height` rectangle of the pen at `(x, y)` with the style, outlined or filled, like `rectangle`.
+    pub fn rectangle_p(&mut self, x: isize, y: isize, width: usize, height: usize, fill: bool) -> DrawResult {
+        let ccode = self.style.clone();
+        self.rectangle(x, y, width, height, self.pen, &ccode, fill)
+    }
+
+    /// Draw a line of the pen from `(x1, y1)` to `(x2, y2)` with the style, like `line`.
+    pub fn line_p(&mut self, x1: isize, y1: isize, x2: isize, y2: isize) -> DrawResult {
+        let ccode = self.style.clone();
+        self.line(x1, y1, x2, y2, self.pen, &ccode)
+    }
+
+    /// Draw an ellipse of the pen centered at `(h, k)` with radii `a` and `b` with the style, outlined or filled, like `ellipse`.
+    pub fn ellipse_p(&mut self, h: isize, k: isize, a: usize, b: usize, fill: bool) -> DrawResult {
+        let ccode = self.style.clone();
+        self.ellipse(h, k, a, b, self.pen, &ccode, fill)
+    }
+
+    /// Draw `src` shrunk (or stretched) to fit `dest` on this canvas, for a minimap or thumbnail. Each cell of `dest` covers a block of `src`
+    /// and gets its most common character, in the average of its colors (read with `drawc::ccode_to_rgb`, with the default foreground
+    /// counted as `WHITE`). Only cells inside `dest` and the canvas are touched, and the result depends only on `src`.
+    ///
+    /// Example
+    /// ```
+    /// use terminalgl::canvas::Canvas;
+    /// use terminalgl::{drawc, Rect};
+    /// let mut world = Canvas::new(4, 2);
+    /// world.text(0, 0, "~#~~", drawc::rgb_to_ccode((0, 0, 200), drawc::ColorKind::Fg).as_str());
+    /// world.text(0, 1, "##..", drawc::rgb_to_ccode((100, 100, 0), drawc::ColorKind::Fg).as_str());
+    /// let mut canvas = Canvas::new(3, 1);
+    /// canvas.render_scaled(&world, Rect::new(1, 0, 2, 1));
+    /// assert_eq!(canvas.get(0, 0).unwrap().c, ' ');
+    /// assert_eq!(canvas.get(1, 0).unwrap().c, '#');
+    /// assert_eq!(canvas.get(2, 0).unwrap().c, '~');
+    /// assert_eq!(canvas.ccode_at(1, 0), Some("\x1b[38;2;50;50;100m"));
+    /// ```
+    pub fn render_scaled(&mut self, src: &Canvas, dest: Rect) {
+        let mut colors: HashMap<&str, drawc::CcodeColors> = HashMap::new();
+        let white = drawc::NamedColor::White.to_rgb();
+        let area = dest.intersect(self.rect());
+        for y in area.y..area.y + area.height as isize {
+            let rows = raster::block(y.abs_diff(dest.y), src.height, dest.height);
+            for x in area.x..area.x + area.width as isize {
+                let columns = raster::block(x.abs_diff(dest.x), src.width, dest.width);
+                let mut tally = Tally::default();
+                for sy in rows.clone() {
+                    for cell in &src.cells[sy * src.width + columns.start..sy * src.width + columns.end] {
+                        let ccode = src.ccode_of(cell);
+                        let (fg, bg) = *colors.entry(ccode).or_insert_with(|| drawc::ccode_to_rgb(ccode));
+                        tally.add(cell.c, fg.unwrap_or(white), bg);
+                    }
+                }
+                if let Some(cell) = tally.cell() {
+                    self.put(x, y, cell);
+                }
+            }
+        }
+    }
+
+    /// Draw the cells of this canvas in `src` magnified into `dest`, for a loupe or a pixel editor's zoomed view: each cell
+    /// becomes a `factor` by `factor` block of copies of itself, keeping its character and colors, so text comes out as
+    /// blocks of repeated letters rather than bigger letters. `src` is clamped to the canvas, and it is copied before
+    /// drawing, so `dest` may overlap it. Cells of `dest` past the magnified source are left alone, and the rest go through
+    /// `put`, so they are clipped to the canvas and follow the blend mode. A `factor` of 0 draws nothing.
+    ///
+    /// Example
+    /// ```
+    /// use terminalgl::canvas::Canvas;
+    /// use terminalgl::{assert_frame_eq, Rect};
+    /// let mut canvas = Canvas::new(8, 4);
+    /// canvas.text(0, 0, "ab", "");
+    /// canvas.text(0, 1, "cd", "");
+    /// canvas.render_zoomed(Rect::new(0, 0, 2, 2), Rect::new(3, 0, 5, 4), 2);
+    /// assert_frame_eq!(canvas, "\
+    /// |ab aabb |
+    /// |cd aabb |
+    /// |   ccdd |
+    /// |   ccdd |");
+    /// // The source is clamped to the canvas, and the destination clipped to it.
+    /// canvas.render_zoomed(Rect::new(-1, 3, 3, 4), Rect::new(6, 2, 4, 4), 3);
+    /// assert_eq!(canvas.get(7, 3).unwrap().c, ' ');
+    /// ```
+    pub fn render_zoomed(&mut self, src: Rect, dest: Rect, factor: usize) {
+        self.zoomed(src, dest, (factor, factor));
+    }
+
+    /// Draw the cells in `src` magnified into `dest` like `render_zoomed`, but with each cell becoming a block twice as wide
+    /// as it is tall, `2 * factor` by `factor`, so the magnified picture keeps its shape on terminals whose cells are about
+    /// twice as tall as they are wide.
+    ///
+    /// Example
+    /// ```
+    /// use terminalgl::canvas::Canvas;
+    /// use terminalgl::{assert_frame_eq, Rect};
+    /// let mut canvas = Canvas::new(8, 2);
+    /// canvas.text(0, 0, "ab", "");
+    /// canvas.render_zoomed_square(Rect::new(0, 0, 2, 1), Rect::new(0, 0, 8, 2), 2);
+    /// assert_frame_eq!(canvas, "\
+    /// |aaaabbbb|
+    /// |aaaabbbb|");
+    /// ```
+    pub fn render_zoomed_square(&mut self, src: Rect, dest: Rect, factor: usize) {
+        self.zoomed(src, dest, (factor * 2, factor));
+    }
+
+    /// Draw the cells in `src` into `dest` with each one a block of `scale.0` by `scale.1` cells.
+    fn zoomed(&mut self, src: Rect, dest: Rect, scale: (usize, usize)) {
+        if scale.0 == 0 || scale.1 == 0 {
+            return;
+        }
+        let source = self.snapshot(src);
+        let (src, cells) = (source.rect, &source.cells);
+        let width = dest.width.min(src.width.saturating_mul(scale.0));
+        let height = dest.height.min(src.height.saturating_mul(scale.1));
+        for (x, y) in Rect::new(dest.x, dest.y, width, height).intersect(self.rect()).cells() {
+            let (sx, sy) = (x.abs_diff(dest.x) / scale.0, y.abs_diff(dest.y) / scale.1);
+            self.put(x, y, cells[sy * src.width + sx].clone());
+        }
+    }
+
+    /// Change the size of the canvas to `cols` by `rows`, keeping the part of the picture at `anchor` where it is:
+    /// `BottomLeft` keeps a log's newest lines in view, and `Center` keeps a game camera's view centered. Cells that
+    /// move off the canvas are dropped, and newly exposed cells are set to `fill`. Centering rounds like `Rect::align_in`.
+    /// Protected cells (see `set_protected`) move with the picture, and new cells aren't protected.
+    ///
+    /// Every cell is marked damaged, as the terminal has to be redrawn anyway. Rects kept from before, such as from
+    /// `take_damage` or in a `Snapshot`, still refer to the old layout, so work them out again for the new size.
+    ///
+    /// Example
+    /// ```
+    /// use terminalgl::canvas::{Canvas, Cell};
+    /// use terminalgl::{assert_frame_eq, Anchor};
+    /// let mut canvas = Canvas::new(4, 3);
+    /// canvas.text(0, 0, "abcd", "");
+    /// canvas.text(0, 1, "efgh", "");
+    /// canvas.text(0, 2, "ijkl", "");
+    ///
+    /// let mut log = canvas.clone();
+    /// log.resize(6, 2, Anchor::BottomLeft, Cell::new('.', ""));
+    /// assert_frame_eq!(log, "\
+    /// |efgh..|
+    /// |ijkl..|");
+    ///
+    /// let mut camera = canvas.clone();
+    /// camera.resize(2, 5, Anchor::Center, Cell::new('.', ""));
+    /// assert_frame_eq!(camera, "\
+    /// |..|
+    /// |bc|
+    /// |fg|
+    /// |jk|
+    /// |..|");
+    ///
+    /// // Every anchor, growing and shrinking in each direction.
+    /// let anchors = [
+    ///     Anchor::TopLeft, Anchor::Top, Anchor::TopRight, Anchor::Left, Anchor::Center,
+    ///     Anchor::Right, Anchor::BottomLeft, Anchor::Bottom, Anchor::BottomRight
+    /// ];
+    /// for (i, &anchor) in anchors.iter().enumerate() {
+    ///     for (cols, rows) in [(2, 1), (2, 5), (7, 1), (7, 6), (4, 3), (0, 0)] {
+    ///         let mut resized = canvas.clone();
+    ///         resized.resize(cols, rows, anchor, Cell::new('.', ""));
+    ///         assert_eq!((resized.width(), resized.height()), (cols, rows));
+    ///         // Offset of the old picture: nothing, all the spare space, or half of it rounded down.
+    ///         let offset = |spare: isize, part: usize| [0, spare.div_euclid(2), spare][part];
+    ///         let dx = offset(cols as isize - 4, i % 3);
+    ///         let dy = offset(rows as isize - 3, i / 3);
+    ///         for (x, y) in resized.rect().cells() {
+    ///             let expected = canvas.get(x - dx, y - dy).map_or('.', |cell| cell.c);
+    ///             assert_eq!(resized.get(x, y).unwrap().c, expected, "{:?} to {}x{} at ({}, {})", anchor, cols, rows, x, y);
+    ///         }
+    ///     }
+    /// }
+    /// ```
+    pub fn resize(&mut self, cols: usize, rows: usize, anchor: Anchor, fill: Cell) {
+        let fill = fill.sanitized();
+        let at = Rect::new(0, 0, cols, rows).anchor(anchor, (self.width, self.height), 0);
+        let mut cells = vec![fill; cols * rows];
+        let mut protected = vec![false; cols * rows];
+        for (i, (cell, protected)) in cells.iter_mut().zip(&mut protected).enumerate() {
+            let (x, y) = ((i % cols) as isize, (i / cols) as isize);
+            if let Some(old) = self.index(x - at.x, y - at.y) {
+                *cell = self.cells[old].clone();
+                *protected = self.protected[old];
+            }
+        }
+        self.width = cols;
+        self.height = rows;
+        self.cells = cells;
+        self.protected = protected;
+        self.damaged = vec![true; cols * rows];
+        self.stale.clear();
+        self.highlights.clear();
+        if let Some(journal) = &mut self.journal {
+            *journal = Journal { max_transactions: journal.max_transactions, open: None, undo: VecDeque::new(), redo: Vec::new() };
+        }
+    }
+
+    /// Reset every cell to a blank space, leaving protected cells as they are (see `set_protected`).
+    pub fn clear(&mut self) {
+        for i in 0..self.cells.len() {
+            if self.writable(i) {
+                self.write(i, Cell::default());
+            }
+        }
+    }
+
+    /// Blend every cell's colors toward black by `amount` with `drawc::darken`, where `1.0` makes the whole canvas black.
+    /// Cells with a palette index are given the darkened color of their palette entry directly. Protected cells are left as they are (see `set_protected`).
+    ///
+    /// Example
+    /// ```
+    /// use terminalgl::canvas::Canvas;
+    /// use terminalgl::drawc;
+    /// let mut canvas = Canvas::new(2, 1);
+    /// canvas.set(0, 0, '#', "\x1b[38;2;200;0;0m");
+    /// canvas.darken(0.5);
+    /// assert_eq!(canvas.ccode_at(0, 0), Some("\x1b[38;2;100;0;0m"));
+    /// assert_eq!(canvas.ccode_at(1, 0), Some(drawc::darken("", 0.5).as_str()));
+    /// ```
+    pub fn darken(&mut self, amount: f64) {
+        for i in 0..self.cells.len() {
+            if self.writable(i) {
+                let ccode = drawc::darken(self.ccode_of(&self.cells[i]), amount);
+                self.write(i, Cell { c: self.cells[i].c, ccode, palette: None });
+            }
+        }
+    }
+
+    /// Print every cell to the terminal, with the canvas's top left corner at the terminal's top left corner, and flush the output.
+    pub fn present(&self) {
+        let _ = self.present_to(&mut AnsiBackend::new());
+    }
+
+    /// Write what `present` prints to `backend` instead of the terminal, and flush it.
+    pub fn present_to(&self, backend: &mut dyn Backend) -> io::Result<()> {
+        let start = Instant::now();
+        STATS.lock().unwrap_or_else(PoisonError::into_inner).last_full = Some(start);
+        self.emit(backend, start, &self.to_ansi(), self.width * self.height)
+    }
+
+    /// Print only the cells that differ from `previous`, which should be the canvas presented last, and the cells invalidated
+    /// in `previous` (see `invalidate`). If `previous` is a different size, or a full repaint is due (see `set_refresh_interval`),
+    /// every cell is printed. The output is flushed like `present`.
+    pub fn present_diff(&self, previous: &Canvas) {
+        let _ = self.present_diff_to(previous, &mut AnsiBackend::new());
+    }
+
+    /// Write what `present_diff` prints to `backend` instead of the terminal, and flush it.
+    ///
+    /// Example
+    /// ```
+    /// use terminalgl::backend::TestBackend;
+    /// use terminalgl::canvas::Canvas;
+    /// let previous = Canvas::new(4, 1);
+    /// let mut canvas = previous.clone();
+    /// canvas.set(2, 0, '#', "");
+    /// let mut backend = TestBackend::new(4, 1);
+    /// canvas.present_diff_to(&previous, &mut backend).unwrap();
+    /// assert_eq!(backend.output(), canvas.diff_ansi(&previous));
+    /// ```
+    pub fn present_diff_to(&self, previous: &Canvas, backend: &mut dyn Backend) -> io::Result<()> {
+        let start = Instant::now();
+        if refresh_due(start) {
+            return self.present_to(backend);
+        }
+        let (out, cells) = self.diff_output(previous);
+        self.emit(backend, start, &out, cells)
+    }
+
+    /// Show or hide a line of frame stats in the top right corner whenever this canvas is presented:
+    /// the stats of the frame before, as from `last_frame_stats`. The line is printed over the canvas without changing it,
+    /// and the cells it covered are printed again once it moves or is hidden.
+    pub fn debug_stats(&mut self, show: bool) {
+        self.debug_stats = show;
+    }
+
+    /// Pass a copy of each row through `filter` whenever the canvas is presented, for effects like scanlines or tinting rows
+    /// through a lookup table: it gets the row's index and the row's cells, with palette indices replaced by the color codes
+    /// they are drawn with, and what it leaves is printed instead. The canvas itself is never changed, so `get`, `diff`,
+    /// and `to_debug_string` still see the cells as drawn. Filtered characters and color codes are checked like `put` checks them.
+    ///
+    /// `to_ansi`, `rects_ansi`, and `diff_ansi` print filtered cells, and `present_diff` compares the filtered rows of both canvases,
+    /// so a filter that changes from frame to frame prints the cells it changes. Clones of the canvas share its filters.
+    /// Setting a filter replaces the one set before; see `clear_filters`.
+    ///
+    /// Example
+    /// ```
+    /// use terminalgl::canvas::Canvas;
+    /// use terminalgl::drawc;
+    /// let mut canvas = Canvas::new(3, 2);
+    /// canvas.text(0, 0, "abc", "");
+    /// canvas.text(0, 1, "def", "");
+    /// // Dim every other row, like the gaps between a CRT's scanlines.
+    /// canvas.set_row_filter(|y, row| if y % 2 == 1 {
+    ///     for cell in row {
+    ///         cell.ccode = drawc::darken(&cell.ccode, 0.5);
+    ///     }
+    /// });
+    /// assert_eq!(canvas.to_ansi(), "\x1b[1;1Habc\x1b[0m\x1b[2;1H\x1b[0m\x1b[38;2;115;115;115mdef\x1b[0m");
+    /// assert_eq!(canvas.get(0, 1).unwrap().ccode, "");
+    ///
+    /// // Only cells whose filtered output changed are printed.
+    /// let previous = canvas.clone();
+    /// canvas.set(1, 1, 'E', "");
+    /// assert_eq!(canvas.diff_ansi(&previous), "\x1b[2;2H\x1b[0m\x1b[38;2;115;115;115mE\x1b[0m");
+    /// ```
+    pub fn set_row_filter(&mut self, filter: impl Fn(usize, &mut [Cell]) + Send + Sync + 'static) {
+        self.filters.row = Some(Arc::new(filter));
+    }
+
+    /// Pass a copy of each cell through `filter` whenever the canvas is presented, like `set_row_filter` but one cell at a time,
+    /// given the cell's position as `(x, y)`. With both set, the row filter runs first.
+    ///
+    /// Example
+    /// ```
+    /// use terminalgl::canvas::Canvas;
+    /// let mut canvas = Canvas::new(4, 1);
+    /// canvas.text(0, 0, "abcd", "");
+    /// canvas.set_cell_filter(|x, _, cell| if x % 2 == 0 { cell.c = cell.c.to_ascii_uppercase() });
+    /// assert_eq!(canvas.to_ansi(), "\x1b[1;1HAbCd\x1b[0m");
+    /// canvas.clear_filters();
+    /// assert_eq!(canvas.to_ansi(), "\x1b[1;1Habcd\x1b[0m");
+    /// ```
+    pub fn set_cell_filter(&mut self, filter: impl Fn(usize, usize, &mut Cell) + Send + Sync + 'static) {
+        self.filters.cell = Some(Arc::new(filter));
+    }
+
+    /// Remove the filters set with `set_row_filter` and `set_cell_filter`, so the canvas is presented as drawn.
+    pub fn clear_filters(&mut self) {
+        self.filters = Filters::default();
+    }
+
+    /// Get every row as it is presented: the stored cells, or with filters set, filtered copies of them.
+    fn shown_rows(&self) -> Vec<Cow<'_, [Cell]>> {
+        let rows = (0..self.height).map(|y| &self.cells[y * self.width..(y + 1) * self.width]);
+        let Filters { row: row_filter, cell: cell_filter } = &self.filters;
+        if row_filter.is_none() && cell_filter.is_none() {
+            return rows.map(Cow::Borrowed).collect();
+        }
+        rows.enumerate().map(|(y, cells)| {
+            let mut row: Vec<Cell> = cells.iter().map(|cell| Cell::new(cell.c, self.ccode_of(cell))).collect();
+            if let Some(filter) = row_filter {
+                filter(y, &mut row);
+            }
+            if let Some(filter) = cell_filter {
+                for (x, cell) in row.iter_mut().enumerate() {
+                    filter(x, y, cell);
+                }
+            }
+            // A filter can't resize the row, but can set characters and color codes `put` wouldn't allow.
+            Cow::Owned(row.into_iter().map(Cell::sanitized).collect())
+        }).collect()
+    }
+
+    /// Write `out`, the frame started at `start` with `cells` cells, to `backend` and record its stats, then write the debug overlay.
+    fn emit(&self, backend: &mut dyn Backend, start: Instant, out: &str, cells: usize) -> io::Result<()> {
+        backend.write(out.as_bytes())?;
+        backend.flush()?;
+        let mut stats = STATS.lock().unwrap_or_else(PoisonError::into_inner);
+        let shown = stats.last;
+        let fps = stats.last_start.map_or(0.0, |last| 1.0 / start.duration_since(last).as_secs_f64().max(1e-9));
+        stats.last = FrameStats { fps, cells_changed: cells, bytes: out.len(), present_time: start.elapsed() };
+        stats.last_start = Some(start);
+
+        let mut overlay = stats.overlay.take().map(|rect| self.rects_ansi(&[rect])).unwrap_or_default();
+        if self.debug_stats && self.width > 0 && self.height > 0 {
+            let line = format!(
+                " {:.1} fps | {} cells | {} B | {:.2} ms ",
+                shown.fps, shown.cells_changed, shown.bytes, shown.present_time.as_secs_f64() * 1000.0
+            );
+            let line: String = line.chars().take(self.width).collect();
+            let rect = self.rect().anchor(Anchor::TopRight, (line.chars().count(), 1), 0);
+            overlay.push_str(&format!("\x1b[{};{}H{}\x1b[7m{}{}", rect.y + 1, rect.x + 1, drawc::RESET, line, drawc::RESET));
+            stats.overlay = Some(rect);
+        }
+        if !overlay.is_empty() {
+            backend.write(overlay.as_bytes())?;
+            backend.flush()?;
+        }
+        Ok(())
+    }
+
+    /// Get the text `present` prints: each row positioned with a cursor movement, and colors reset between runs of different color codes.
+    ///
+    /// Example
+    /// ```
+    /// use terminalgl::canvas::Canvas;
+    /// use terminalgl::drawc;
+    /// let mut canvas = Canvas::new(3, 1);
+    /// canvas.set(1, 0, '#', drawc::RED);
+    /// assert_eq!(canvas.to_ansi(), "\x1b[1;1H \x1b[0m\x1b[31m#\x1b[0m \x1b[0m");
+    /// ```
+    pub fn to_ansi(&self) -> String {
+        let mut out = String::new();
+        for (y, row) in self.shown_rows().iter().enumerate() {
+            out.push_str(&format!("\x1b[{};1H", y+1));
+            let mut current = "";
+            for cell in row.iter() {
+                let ccode = self.ccode_of(cell);
+                if ccode != current {
+                    out.push_str(drawc::RESET);
+                    out.push_str(ccode);
+                    current = ccode;
+                }
+                out.push(cell.c);
+            }
+            out.push_str(drawc::RESET);
+        }
+        out
+    }
+
+    /// Get the text `present_diff` prints: a cursor movement before each run of changed cells, and the changed cells themselves.
+    /// A cell counts as changed when its character or the color code it is drawn with changed, including through the palette.
+    ///
+    /// Example
+    /// ```
+    /// use terminalgl::canvas::Canvas;
+    /// let before = Canvas::new(4, 2);
+    /// let mut after = before.clone();
+    /// assert_eq!(after.diff_ansi(&before), "");
+    /// after.text(1, 1, "ab", "");
+    /// assert_eq!(after.diff_ansi(&before), "\x1b[2;2H\x1b[0mab\x1b[0m");
+    /// ```
+    pub fn diff_ansi(&self, previous: &Canvas) -> String {
+        self.diff_output(previous).0
+    }
+
+    /// Get the text `present_diff` prints and the number of cells in it.
+    fn diff_output(&self, previous: &Canvas) -> (String, usize) {
+        if self.width != previous.width || self.height != previous.height {
+            return (self.to_ansi(), self.width * self.height);
+        }
+        let (rows, previous_rows) = (self.shown_rows(), previous.shown_rows());
+        let mut out = String::new();
+        let mut count = 0;
+        let mut current: Option<&str> = None;
+        for (y, (row, previous_row)) in rows.iter().zip(&previous_rows).enumerate() {
+            let mut cursor = None;
+            for (x, (cell, previous_cell)) in row.iter().zip(previous_row.iter()).enumerate() {
+                if self.drawn_same(cell, previous, previous_cell) && !previous.is_stale(x as isize, y as isize) {
+                    continue;
+                }
+                let ccode = self.ccode_of(cell);
+                if cursor != Some(x) {
+                    out.push_str(&format!("\x1b[{};{}H", y+1, x+1));
+                }
+                if current != Some(ccode) {
+                    out.push_str(drawc::RESET);
+                    out.push_str(ccode);
+                    current = Some(ccode);
+                }
+                out.push(cell.c);
+                count += 1;
+                cursor = Some(x + 1);
+            }
+        }
+        if current.is_some() {
+            out.push_str(drawc::RESET);
+        }
+        (out, count)
+    }
+
+    /// Get every cell that differs from `previous` as `(x, y, before, after)`, in row order, with palette indices
+    /// replaced by the color codes they are drawn with. Cells count as different exactly when `present_diff` would print them,
+    /// unless filters are set (see `set_row_filter`): this compares the cells as drawn.
+    /// Canvases of different sizes are compared over the larger of each dimension, with cells outside a canvas being blank.
+    ///
+    /// Example
+    /// ```
+    /// use terminalgl::canvas::{self, Canvas, Cell};
+    /// use terminalgl::drawc;
+    /// let mut before = Canvas::new(3, 1);
+    /// before.text(0, 0, "ab", "");
+    /// let mut after = Canvas::new(4, 1);
+    /// after.text(0, 0, "a", "");
+    /// after.set(1, 0, 'b', drawc::RED);
+    /// after.set(3, 0, 'd', "");
+    /// assert_eq!(after.diff(&before), vec![
+    ///     (1, 0, Cell::new('b', ""), Cell::new('b', drawc::RED)),
+    ///     (3, 0, Cell::default(), Cell::new('d', ""))
+    /// ]);
+    ///
+    /// // The same cells `present_diff` prints.
+    /// let mut next = before.clone();
+    /// next.set_palette_entry(0, "");
+    /// next.set_indexed(0, 0, 'a', 0);
+    /// next.set(2, 0, '!', drawc::GREEN);
+    /// next.present_diff(&before);
+    /// assert_eq!(canvas::last_frame_stats().cells_changed, next.diff(&before).len());
+    /// assert_eq!(next.diff(&before).len(), 1);
+    /// ```
+    pub fn diff(&self, previous: &Canvas) -> Vec<(isize, isize, Cell, Cell)> {
+        let blank = Cell::default();
+        let mut changes = Vec::new();
+        for y in 0..self.height.max(previous.height) as isize {
+            for x in 0..self.width.max(previous.width) as isize {
+                let before = previous.get(x, y).unwrap_or(&blank);
+                let after = self.get(x, y).unwrap_or(&blank);
+                if !self.drawn_same(after, previous, before) {
+                    changes.push((x, y, Cell::new(before.c, previous.ccode_of(before)), Cell::new(after.c, self.ccode_of(after))));
+                }
+            }
+        }
+        changes
+    }
+
+    /// Get whether `cell` of this canvas and `other_cell` of `other` draw the same character with the same color code.
+    fn drawn_same(&self, cell: &Cell, other: &Canvas, other_cell: &Cell) -> bool {
+        cell.c == other_cell.c && self.ccode_of(cell) == other.ccode_of(other_cell)
+    }
+
+    /// Mark the cells inside `rect` as damaged, so the next `take_damage` includes them even if they have not changed,
+    /// e.g. after something else has printed over that part of the terminal. Cells outside the canvas are ignored.
+    pub fn damage(&mut self, rect: Rect) {
+        let rect = rect.intersect(self.rect());
+        for (x, y) in rect.cells() {
+            let i = y as usize * self.width + x as usize;
+            self.damaged[i] = true;
+        }
+    }
+
+    /// Mark every cell as needing to be printed again, for when the terminal no longer shows what was presented,
+    /// such as after another program printed over it. Bind it to Ctrl+L, which redraws the screen in most terminal programs.
+    /// See `invalidate_rect`.
+    pub fn invalidate(&mut self) {
+        self.invalidate_rect(self.rect());
+    }
+
+    /// Mark the cells inside `rect` as needing to be printed again, whether or not they have changed. The next `take_damage`
+    /// includes them, like with `damage`, and `present_diff` prints them when this canvas is the `previous` one it compares against,
+    /// the one standing for what the terminal shows. Cells outside the canvas are ignored.
+    ///
+    /// Example
+    /// ```
+    /// use terminalgl::canvas::Canvas;
+    /// use terminalgl::Rect;
+    /// let mut shown = Canvas::new(4, 2);
+    /// shown.text(0, 0, "abcd", "");
+    /// let frame = shown.clone();
+    /// assert_eq!(frame.diff_ansi(&shown), "");
+    ///
+    /// // Something printed over the middle of the top row.
+    /// shown.invalidate_rect(Rect::new(1, 0, 2, 1));
+    /// assert_eq!(frame.diff_ansi(&shown), "\x1b[1;2H\x1b[0mbc\x1b[0m");
+    ///
+    /// // With damage tracking instead.
+    /// shown.take_damage();
+    /// shown.invalidate();
+    /// assert_eq!(shown.take_damage(), vec![Rect::new(0, 0, 4, 2)]);
+    /// assert_eq!(frame.diff_ansi(&shown), "");
+    /// ```
+    pub fn invalidate_rect(&mut self, rect: Rect) {
+        let rect = rect.intersect(self.rect());
+        if rect.width > 0 && rect.height > 0 {
+            self.damage(rect);
+            self.stale.push(rect);
+        }
+    }
+
+    /// Get whether the cell at `(x, y)` was invalidated since damage was last taken.
+    fn is_stale(&self, x: isize, y: isize) -> bool {
+        self.stale.iter().any(|rect| rect.contains(x, y))
+    }
+
+    /// Get rectangles covering every cell changed since the last call, and mark them all as clean.
+    /// A new canvas is damaged everywhere. Cells count as changed when their character, color code, or palette entry changed,
+    /// even if they were later changed back. Changed cells in each row are joined into runs,
+    /// and runs with the same columns in neighboring rows are joined into one rectangle, so no cell is covered twice.
+    ///
+    /// Presenting the rectangles with `present_rects` after the previous contents were presented
+    /// leaves the terminal just as a full `present` would.
+    ///
+    /// Example
+    /// ```
+    /// use terminalgl::canvas::Canvas;
+    /// use terminalgl::{ansi, drawc, Rect};
+    /// let mut canvas = Canvas::new(10, 4);
+    /// assert_eq!(canvas.take_damage(), vec![Rect::new(0, 0, 10, 4)]);
+    /// assert_eq!(canvas.take_damage(), vec![]);
+    ///
+    /// let shown = canvas.to_ansi();
+    /// canvas.text(2, 1, "ab", drawc::RED);
+    /// canvas.text(2, 2, "cd", "");
+    /// canvas.set(9, 3, '#', "");
+    /// canvas.set(0, 0, ' ', "");
+    /// let damage = canvas.take_damage();
+    /// assert_eq!(damage, vec![Rect::new(2, 1, 2, 2), Rect::new(9, 3, 1, 1)]);
+    /// let terminal = ansi::parse(&(shown + &canvas.rects_ansi(&damage)), 10);
+    /// assert_eq!(terminal.to_debug_string(), canvas.to_debug_string());
+    /// ```
+    pub fn take_damage(&mut self) -> Vec<Rect> {
+        let done = self.covering_rects(&self.damaged);
+        self.damaged.fill(false);
+        self.stale.clear();
+        done
+    }
+
+    /// Get rectangles covering the cells marked in `marked`, one flag for each cell, as `take_damage` joins them.
+    fn covering_rects(&self, marked: &[bool]) -> Vec<Rect> {
+        let mut done = Vec::new();
+        let mut open: Vec<Rect> = Vec::new();
+        for y in 0..self.height {
+            let row = &marked[y * self.width..(y + 1) * self.width];
+            let mut runs = Vec::new();
+            let mut x = 0;
+            while x < self.width {
+                if !row[x] {
+                    x += 1;
+                    continue;
+                }
+                let start = x;
+                while x < self.width && row[x] {
+                    x += 1;
+                }
+                runs.push((start as isize, x - start));
+            }
+            let mut next = Vec::with_capacity(runs.len());
+            for (x, width) in runs {
+                match open.iter().position(|r| r.x == x && r.width == width) {
+                    Some(i) => {
+                        let mut rect = open.swap_remove(i);
+                        rect.height += 1;
+                        next.push(rect);
+                    },
+                    None => next.push(Rect::new(x, y as isize, width, 1))
+                }
+            }
+            done.append(&mut open);
+            open = next;
+        }
+        done.append(&mut open);
+        done.sort_by_key(|r| (r.y, r.x));
+        done
+    }
+
+    /// Print only the cells inside `rects`, such as those from `take_damage`, and flush the output.
+    /// Cells outside the canvas are skipped. If a full repaint is due (see `set_refresh_interval`), every cell is printed.
+    pub fn present_rects(&self, rects: &[Rect]) {
+        let _ = self.present_rects_to(rects, &mut AnsiBackend::new());
+    }
+
+    /// Write what `present_rects` prints to `backend` instead of the terminal, and flush it.
+    pub fn present_rects_to(&self, rects: &[Rect], backend: &mut dyn Backend) -> io::Result<()> {
+        let start = Instant::now();
+        if refresh_due(start) {
+            return self.present_to(backend);
+        }
+        let cells = rects.iter().map(|rect| rect.intersect(self.rect())).map(|rect| rect.width * rect.height).sum();
+        self.emit(backend, start, &self.rects_ansi(rects), cells)
+    }
+
+    /// Get the text `present_rects` prints: a cursor movement before each row of each rectangle, and the cells in it.
+    ///
+    /// Example
+    /// ```
+    /// use terminalgl::canvas::Canvas;
+    /// use terminalgl::Rect;
+    /// let mut canvas = Canvas::new(4, 2);
+    /// canvas.text(0, 1, "abcd", "");
+    /// assert_eq!(canvas.rects_ansi(&[Rect::new(1, 1, 2, 5)]), "\x1b[2;2H\x1b[0mbc\x1b[0m");
+    /// ```
+    pub fn rects_ansi(&self, rects: &[Rect]) -> String {
+        let rows = self.shown_rows();
+        let mut out = String::new();
+        let mut current: Option<&str> = None;
+        for rect in rects {
+            let rect = rect.intersect(self.rect());
+            for y in rect.y..rect.y + rect.height as isize {
+                out.push_str(&format!("\x1b[{};{}H", y + 1, rect.x + 1));
+                for cell in &rows[y as usize][rect.x as usize..rect.x as usize + rect.width] {
+                    let ccode = self.ccode_of(cell);
+                    if current != Some(ccode) {
+                        out.push_str(drawc::RESET);
+                        out.push_str(ccode);
+                        current = Some(ccode);
+                    }
+                    out.push(cell.c);
+                }
+            }
+        }
+        if current.is_some() {
+            out.push_str(drawc::RESET);
+        }
+        out
+    }
+
+    /// Get a plain text picture of the canvas for snapshot tests, such as with `assert_frame_eq!`.
+    ///
+    /// Each row is written between `|` marks, so trailing spaces stay visible. If any cell has a color code,
+    /// a blank line and a second grid follow, with `.` for cells without one and a letter for each color code,
+    /// then a legend of `letter "code"` lines. Letters are given out in row order of first use, `A` to `Z` then `a` to `z`,
+    /// so the text depends only on the cells. Cells with a palette index show the palette entry they are drawn with.
+    ///
+    /// Example
+    /// ```
+    /// use terminalgl::canvas::Canvas;
+    /// use terminalgl::drawc;
+    /// let mut canvas = Canvas::new(4, 2);
+    /// canvas.text(0, 0, "ab", drawc::RED);
+    /// canvas.set(3, 1, '#', drawc::GREEN);
+    /// assert_eq!(canvas.to_debug_string(), "|ab  |\n|   #|\n\n|AA..|\n|...B|\nA \"\\u{1b}[31m\"\nB \"\\u{1b}[32m\"");
+    /// assert_eq!(Canvas::new(2, 1).to_debug_string(), "|  |");
+    /// ```
+    pub fn to_debug_string(&self) -> String {
+        let rows = |cell: &dyn Fn(&Cell) -> char| -> Vec<String> {
+            (0..self.height).map(|y| {
+                format!("|{}|", self.cells[y*self.width..(y+1)*self.width].iter().map(cell).collect::<String>())
+            }).collect()
+        };
+        let mut lines = rows(&|cell| cell.c);
+        let mut codes: Vec<&str> = Vec::new();
+        for cell in &self.cells {
+            let ccode = self.ccode_of(cell);
+            if !ccode.is_empty() && !codes.contains(&ccode) {
+                codes.push(ccode);
+            }
+        }
+        if !codes.is_empty() {
+            let key = |i: usize| (b'A'..=b'Z').chain(b'a'..=b'z').nth(i).map_or('?', char::from);
+            lines.push(String::new());
+            lines.extend(rows(&|cell| match codes.iter().position(|&code| code == self.ccode_of(cell)) {
+                Some(i) => key(i),
+                None => '.'
+            }));
+            lines.extend(codes.iter().enumerate().map(|(i, code)| format!("{} {:?}", key(i), code)));
+        }
+        lines.join("\n")
+    }
+
+    /// Set the cell at `(x, y)` and every cell connected to it through cells equal to it (above, below, left or right) to `c` drawn with `ccode`,
+    /// and get the number of cells changed. Nothing changes if `(x, y)` is outside the canvas or already holds the new cell. Protected cells stop the fill like walls (see `set_protected`).
+    ///
+    /// Example
+    /// ```
+    /// use terminalgl::assert_frame_eq;
+    /// use terminalgl::canvas::Canvas;
+    /// let mut canvas = Canvas::new(7, 4);
+    /// canvas.text(0, 0, " #   # ", "");
+    /// canvas.text(0, 1, " #   # ", "");
+    /// canvas.text(0, 2, " ##### ", "");
+    /// assert_eq!(canvas.flood_fill(3, 1, '~', ""), 6);
+    /// assert_eq!(canvas.flood_fill(3, 1, '~', ""), 0);
+    /// assert_frame_eq!(canvas, "\
+    /// | #~~~# |
+    /// | #~~~# |
+    /// | ##### |
+    /// |       |");
+    /// ```
+    pub fn flood_fill(&mut self, x: isize, y: isize, c: char, ccode: &str) -> usize {
+        let Some(start) = self.index(x, y) else {
+            return 0;
+        };
+        let target = self.cells[start].clone();
+        let fill = Cell::new(c, ccode).sanitized();
+        if target == fill {
+            return 0;
+        }
+        let mut count = 0;
+        let mut stack = vec![(x, y)];
+        while let Some((x, y)) = stack.pop() {
+            match self.index(x, y) {
+                Some(i) if self.cells[i] == target && self.writable(i) => {
+                    self.write(i, fill.clone());
+                    count += 1;
+                    stack.extend([(x - 1, y), (x + 1, y), (x, y - 1), (x, y + 1)]);
+                },
+                _ => {}
+            }
+        }
+        count
+    }
+
+    /// Save the cells inside `rect`, clamped to the canvas, so they can be put back with `restore`.
+    ///
+    /// Example
+    /// ```
+    /// use terminalgl::canvas::Canvas;
+    /// use terminalgl::Rect;
+    /// let mut canvas = Canvas::new(10, 4);
+    /// canvas.text(0, 1, "background", "");
+    /// let saved = canvas.snapshot(Rect::new(-3, 1, 8, 8));
+    /// assert_eq!(saved.rect(), Rect::new(0, 1, 5, 3));
+    /// canvas.text(0, 1, "tooltip", "");
+    /// canvas.restore(&saved);
+    /// assert_eq!(canvas.get(0, 1).unwrap().c, 'b');
+    /// assert_eq!(canvas.get(5, 1).unwrap().c, 'i');
+    /// ```
+    pub fn snapshot(&self, rect: Rect) -> Snapshot {
+        let rect = rect.intersect(self.rect());
+        let mut cells = Vec::with_capacity(rect.width * rect.height);
+        for y in rect.y..rect.y + rect.height as isize {
+            let start = self.index(rect.x, y).unwrap();
+            cells.extend_from_slice(&self.cells[start..start + rect.width]);
+        }
+        Snapshot { rect, cells }
+    }
+
+    /// Put back the cells saved in `snapshot` exactly, even with `merge_borders` on. Cells that no longer fit in the canvas are skipped.
+    ///
+    /// Example
+    /// ```
+    /// use terminalgl::canvas::Canvas;
+    /// let mut canvas = Canvas::new(3, 1);
+    /// canvas.merge_borders(true);
+    /// canvas.set(1, 0, '─', "");
+    /// let saved = canvas.snapshot(canvas.rect());
+    /// canvas.set(1, 0, '│', "");
+    /// assert_eq!(canvas.get(1, 0).unwrap().c, '┼');
+    /// canvas.restore(&saved);
+    /// assert_eq!(canvas.get(1, 0).unwrap().c, '─');
+    /// ```
+    pub fn restore(&mut self, snapshot: &Snapshot) {
+        let rect = snapshot.rect;
+        for (i, cell) in snapshot.cells.iter().enumerate() {
+            let x = rect.x + (i % rect.width) as isize;
+            let y = rect.y + (i / rect.width) as isize;
+            if let Some(i) = self.index(x, y) {
+                self.write(i, cell.clone());
+            }
+        }
+    }
+
+    /// Draw the cells saved in `snapshot` with its top left corner at `(x, y)`, darkened by `darken` (see `drawc::darken`),
+    /// such as for a faded copy of something being dragged. Cells that were empty are left out, so what is underneath shows
+    /// through, and the rest go through `put`, so they are clipped to the canvas and follow the blend mode.
+    ///
+    /// Example
+    /// ```
+    /// use terminalgl::canvas::Canvas;
+    /// use terminalgl::{assert_frame_eq, Rect};
+    /// let mut canvas = Canvas::new(8, 2);
+    /// canvas.text(0, 0, "a b", "");
+    /// let saved = canvas.snapshot(Rect::new(0, 0, 3, 1));
+    /// canvas.text(0, 1, "........", "");
+    /// canvas.paste(&saved, 6, 1, 0.0);
+    /// assert_frame_eq!(canvas, "\
+    /// |a b     |
+    /// |......a.|");
+    /// ```
+    pub fn paste(&mut self, snapshot: &Snapshot, x: isize, y: isize, darken: f64) {
+        let width = snapshot.rect.width.max(1);
+        for (i, cell) in snapshot.cells.iter().enumerate() {
+            if self.is_empty_cell(cell) {
+                continue;
+            }
+            let cell = match darken == 0.0 {
+                true => cell.clone(),
+                false => Cell { c: cell.c, ccode: drawc::darken(self.ccode_of(cell), darken), palette: None }
+            };
+            self.put(x.saturating_add((i % width) as isize), y.saturating_add((i / width) as isize), cell);
+        }
+    }
+
+    /// Find every place `needle` is written on the canvas, each as a one row rectangle of the cells it covers, in reading order.
+    /// Each row is read as a string of its cells' characters, so matches never span rows, and matches never overlap.
+    /// A wide character (see `raster::char_width`) followed by a space is read as taking both cells, as `TextField` draws them,
+    /// so the space isn't part of the string. An empty `needle` matches nothing.
+    ///
+    /// Example
+    /// ```
+    /// use terminalgl::canvas::{Canvas, FindOptions};
+    /// use terminalgl::Rect;
+    /// let mut canvas = Canvas::new(12, 2);
+    /// canvas.text(0, 0, "Cat catalog", "");
+    /// canvas.text(0, 1, "漢 字 cat", "");
+    /// assert_eq!(canvas.find_text("cat", FindOptions::default()), vec![Rect::new(4, 0, 3, 1), Rect::new(4, 1, 3, 1)]);
+    /// let options = FindOptions { case_insensitive: true, whole_word: true };
+    /// // `字` is a letter too, so the `cat` after it is not a whole word.
+    /// assert_eq!(canvas.find_text("CAT", options), vec![Rect::new(0, 0, 3, 1)]);
+    /// assert_eq!(canvas.find_text("漢字", FindOptions::default()), vec![Rect::new(0, 1, 4, 1)]);
+    /// assert_eq!(canvas.find_text("", FindOptions::default()), vec![]);
+    /// ```
+    pub fn find_text(&self, needle: &str, options: FindOptions) -> Vec<Rect> {
+        let needle: Vec<char> = needle.chars().collect();
+        let same = |a: char, b: char| a == b || (options.case_insensitive && a.to_lowercase().eq(b.to_lowercase()));
+        let wordy = |c: Option<&(char, usize, usize)>| c.is_some_and(|&(c, _, _)| c.is_alphanumeric() || c == '_');
+        let mut found = Vec::new();
+        if needle.is_empty() {
+            return found;
+        }
+        for y in 0..self.height {
+            // Each character on the row, with the column it starts at and the cells it takes.
+            let row = &self.cells[y * self.width..(y + 1) * self.width];
+            let mut chars: Vec<(char, usize, usize)> = Vec::new();
+            let mut x = 0;
+            while x < self.width {
+                let wide = raster::char_width(row[x].c) == 2 && row.get(x + 1).is_some_and(|next| next.c == ' ');
+                let cells = if wide { 2 } else { 1 };
+                chars.push((row[x].c, x, cells));
+                x += cells;
+            }
+            let mut i = 0;
+            while i + needle.len() <= chars.len() {
+                let matched = chars[i..i + needle.len()].iter().zip(&needle).all(|(&(c, _, _), &n)| same(c, n));
+                let end = i + needle.len();
+                if matched && !(options.whole_word && (wordy(i.checked_sub(1).and_then(|j| chars.get(j))) || wordy(chars.get(end)))) {
+                    let (start, last) = (chars[i].1, chars[end - 1].1 + chars[end - 1].2);
+                    found.push(Rect::new(start as isize, y as isize, last - start, 1));
+                    i = end;
+                }
+                else {
+                    i += 1;
+                }
+            }
+        }
+        found
+    }
+
+    /// Draw the cells inside `matches`, such as from `find_text`, with `ccode` instead of their own colors, keeping their characters.
+    /// The cells they had are kept, so `clear_highlights` can put them back. Cells outside the canvas are ignored,
+    /// and cells already highlighted are given the new color but keep the cells they had before the first highlight.
+    ///
+    /// Example
+    /// ```
+    /// use terminalgl::assert_frame_eq;
+    /// use terminalgl::canvas::{Canvas, FindOptions};
+    /// use terminalgl::drawc;
+    /// let mut canvas = Canvas::new(8, 1);
+    /// canvas.text(0, 0, "ab ab", drawc::GREEN);
+    /// canvas.set_palette_entry(0, drawc::BLUE);
+    /// canvas.set_indexed(4, 0, 'b', 0);
+    /// let before = canvas.clone();
+    /// let matches = canvas.find_text("ab", FindOptions::default());
+    /// canvas.highlight(&matches, drawc::YELLOW_BG);
+    /// assert_frame_eq!(canvas, r#"|ab ab   |
+    ///
+    /// |AABAA...|
+    /// A "\u{1b}[43m"
+    /// B "\u{1b}[32m""#);
+    /// canvas.clear_highlights();
+    /// assert_eq!(canvas, before);
+    /// assert_eq!(canvas.get(4, 0).unwrap().palette, Some(0));
+    ///
+    /// // Cells drawn over since they were highlighted keep what was drawn.
+    /// canvas.highlight(&matches, drawc::YELLOW_BG);
+    /// canvas.set(0, 0, 'x', "");
+    /// canvas.clear_highlights();
+    /// assert_eq!(canvas.get(0, 0).unwrap().c, 'x');
+    /// assert_eq!(canvas.get(1, 0), before.get(1, 0));
+    /// ```
+    pub fn highlight(&mut self, matches: &[Rect], ccode: &str) {
+        for rect in matches {
+            for (x, y) in rect.intersect(self.rect()).cells() {
+                let i = y as usize * self.width + x as usize;
+                let lit = Cell::new(self.cells[i].c, ccode).sanitized();
+                match self.highlights.iter_mut().find(|(j, _, _)| *j == i) {
+                    Some((_, _, shown)) => *shown = lit.clone(),
+                    None => self.highlights.push((i, self.cells[i].clone(), lit.clone()))
+                }
+                self.write(i, lit);
+            }
+        }
+    }
+
+    /// Put back the cells `highlight` recolored, except those drawn over since, which keep what was drawn.
+    pub fn clear_highlights(&mut self) {
+        for (i, original, lit) in std::mem::take(&mut self.highlights).into_iter().rev() {
+            if self.cells[i] == lit {
+                self.write(i, original);
+            }
+        }
+    }
+
+    /// Turn on the undo journal, keeping the last `max_transactions` steps, or turn it off with `None`, forgetting them.
+    /// Turning it on again only changes the limit. While it is on, every change to a cell is recorded with the cell before and after,
+    /// into the transaction opened with `begin_txn` and closed with `commit_txn`, such as one stroke of a drawing tool.
+    /// Changes made outside one are gathered into a transaction of their own, closed without a label by the next `begin_txn`,
+    /// `undo` or `redo`. Making a change forgets whatever could be redone.
+    ///
+    /// A transaction that changes as many cells as the canvas has, like a fill of the whole canvas, keeps a copy of the canvas instead,
+    /// so no transaction takes more than two copies of the canvas. Resizing the canvas forgets every step.
+    /// Only cells are recorded, not palette entries or protection.
+    ///
+    /// Example
+    /// ```
+    /// use terminalgl::assert_frame_eq;
+    /// use terminalgl::canvas::Canvas;
+    /// use terminalgl::Rect;
+    /// let mut canvas = Canvas::new(6, 2);
+    /// canvas.journal(Some(10));
+    /// canvas.begin_txn();
+    /// canvas.text(0, 0, "abc", "");
+    /// canvas.commit_txn("type");
+    /// canvas.begin_txn();
+    /// canvas.line(0, 1, 5, 1, '-', "");
+    /// canvas.set(1, 0, 'B', "");
+    /// canvas.commit_txn("line");
+    /// assert_eq!(canvas.undo_label(), Some("line"));
+    ///
+    /// assert_eq!(canvas.undo(), vec![Rect::new(1, 0, 1, 1), Rect::new(0, 1, 6, 1)]);
+    /// assert_frame_eq!(canvas, "\
+    /// |abc   |
+    /// |      |");
+    /// canvas.undo();
+    /// assert_frame_eq!(canvas, "\
+    /// |      |
+    /// |      |");
+    /// assert_eq!(canvas.undo(), vec![]);
+    /// canvas.redo();
+    /// assert_eq!(canvas.redo_label(), Some("line"));
+    /// assert_frame_eq!(canvas, "\
+    /// |abc   |
+    /// |      |");
+    ///
+    /// // A new change forgets the steps that could be redone.
+    /// canvas.set(5, 0, '!', "");
+    /// assert_eq!(canvas.redo_label(), None);
+    /// assert_eq!(canvas.redo(), vec![]);
+    /// canvas.undo();
+    /// assert_frame_eq!(canvas, "\
+    /// |abc   |
+    /// |      |");
+    /// canvas.redo();
+    /// assert_frame_eq!(canvas, "\
+    /// |abc  !|
+    /// |      |");
+    /// ```
+    ///
+    /// Whole-canvas steps, and the limit on how many are kept:
+    /// ```
+    /// use terminalgl::assert_frame_eq;
+    /// use terminalgl::canvas::Canvas;
+    /// let mut canvas = Canvas::new(3, 2);
+    /// canvas.journal(Some(2));
+    /// for (i, c) in ['a', 'b', 'c'].into_iter().enumerate() {
+    ///     canvas.begin_txn();
+    ///     canvas.rectangle(0, 0, 3, 2, c, "", true);
+    ///     canvas.set(i as isize, 0, '#', "");
+    ///     canvas.rectangle(0, 0, 3, 2, c, "", true);
+    ///     canvas.set(i as isize, 1, '#', "");
+    ///     canvas.commit_txn("fill");
+    /// }
+    /// canvas.undo();
+    /// assert_frame_eq!(canvas, "\
+    /// |bbb|
+    /// |b#b|");
+    /// canvas.redo();
+    /// assert_frame_eq!(canvas, "\
+    /// |ccc|
+    /// |cc#|");
+    /// canvas.undo();
+    /// canvas.undo();
+    /// assert_frame_eq!(canvas, "\
+    /// |aaa|
+    /// |#aa|");
+    /// assert_eq!(canvas.undo_label(), None);
+    /// ```
+    pub fn journal(&mut self, max_transactions: Option<usize>) {
+        match (max_transactions, &mut self.journal) {
+            (Some(max), Some(journal)) => {
+                journal.max_transactions = max;
+                while journal.undo.len() > max {
+                    journal.undo.pop_front();
+                }
+            },
+            (Some(max), None) => {
+                self.journal = Some(Journal { max_transactions: max, open: None, undo: VecDeque::new(), redo: Vec::new() });
+            },
+            (None, _) => self.journal = None
+        }
+    }
+
+    /// Start a transaction of the journal, closing the one open, if any, without a label. Does nothing while the journal is off.
+    pub fn begin_txn(&mut self) {
+        if let Some(journal) = &mut self.journal {
+            journal.commit("", &self.cells);
+            journal.open = Some(Transaction { label: String::new(), changes: Changes::Cells(Vec::new()) });
+        }
+    }
+
+    /// Close the open transaction of the journal as one step to undo, labeled with `label`, such as for an "Undo fill" menu item.
+    /// A transaction that changed nothing is dropped.
+    pub fn commit_txn(&mut self, label: &str) {
+        if let Some(journal) = &mut self.journal {
+            journal.commit(label, &self.cells);
+        }
+    }
+
+    /// Put back the cells changed by the last step of the journal, and get rectangles covering them (see `take_damage`)
+    /// to present. Gets no rectangles if there is nothing to undo.
+    pub fn undo(&mut self) -> Vec<Rect> {
+        self.step(true)
+    }
+
+    /// Make the changes of the last step undone again, and get rectangles covering them, or none if there is nothing to redo.
+    pub fn redo(&mut self) -> Vec<Rect> {
+        self.step(false)
+    }
+
+    /// Get the label of the step `undo` would undo, or `None` if there is none.
+    pub fn undo_label(&self) -> Option<&str> {
+        self.journal.as_ref()?.undo.back().map(|t| t.label.as_str())
+    }
+
+    /// Get the label of the step `redo` would redo, or `None` if there is none.
+    pub fn redo_label(&self) -> Option<&str> {
+        self.journal.as_ref()?.redo.last().map(|t| t.label.as_str())
+    }
+
+    /// Undo the last step of the journal, or redo the last step undone, and get rectangles covering the cells changed.
+    fn step(&mut self, undo: bool) -> Vec<Rect> {
+        let Some(mut journal) = self.journal.take() else {
+            return Vec::new();
+        };
+        journal.commit("", &self.cells);
+        let transaction = if undo { journal.undo.pop_back() } else { journal.redo.pop() };
+        let mut changed = vec![false; self.cells.len()];
+        if let Some(transaction) = transaction {
+            match &transaction.changes {
+                Changes::Cells(changes) if undo => {
+                    for (i, before, _) in changes.iter().rev() {
+                        self.write(*i, before.clone());
+                        changed[*i] = true;
+                    }
+                },
+                Changes::Cells(changes) => {
+                    for (i, _, after) in changes {
+                        self.write(*i, after.clone());
+                        changed[*i] = true;
+                    }
+                },
+                Changes::Whole { before, after } => {
+                    for (i, cell) in (if undo { before } else { after }).iter().enumerate() {
+                        changed[i] = self.cells[i] != *cell;
+                        self.write(i, cell.clone());
+                    }
+                }
+            }
+            if undo { journal.redo.push(transaction) } else { journal.undo.push_back(transaction) }
+        }
+        self.journal = Some(journal);
+        self.covering_rects(&changed)
+    }
+}
+
+
+/// Get the first of `matches`, such as from `Canvas::find_text`, that starts after `(x, y)` in reading order,
+/// going back to the first match after the last one, or `None` if there are no matches. Pass the start of the match
+/// shown now to step to the next one.
+///
+/// Example
+/// ```
+/// use terminalgl::canvas::{self, Canvas, FindOptions};
+/// use terminalgl::Rect;
+/// let mut canvas = Canvas::new(6, 3);
+/// canvas.text(1, 0, "x", "");
+/// canvas.text(4, 0, "x", "");
+/// canvas.text(0, 2, "x", "");
+/// let matches = canvas.find_text("x", FindOptions::default());
+/// assert_eq!(canvas::next_match(&matches, (1, 0)), Some(Rect::new(4, 0, 1, 1)));
+/// assert_eq!(canvas::next_match(&matches, (5, 1)), Some(Rect::new(0, 2, 1, 1)));
+/// assert_eq!(canvas::next_match(&matches, (0, 2)), Some(Rect::new(1, 0, 1, 1)));
+/// assert_eq!(canvas::prev_match(&matches, (1, 0)), Some(Rect::new(0, 2, 1, 1)));
+/// assert_eq!(canvas::prev_match(&matches, (0, 2)), Some(Rect::new(4, 0, 1, 1)));
+/// assert_eq!(canvas::next_match(&[], (0, 0)), None);
+/// ```
+pub fn next_match(matches: &[Rect], from: (isize, isize)) -> Option<Rect> {
+    let after = matches.iter().filter(|r| (r.y, r.x) > (from.1, from.0)).min_by_key(|r| (r.y, r.x));
+    after.or_else(|| matches.iter().min_by_key(|r| (r.y, r.x))).copied()
+}
+
+
+/// Get the last of `matches` that starts before `(x, y)` in reading order, going round to the last match before the first one,
+/// or `None` if there are no matches. The reverse of `next_match`.
+pub fn prev_match(matches: &[Rect], from: (isize, isize)) -> Option<Rect> {
+    let before = matches.iter().filter(|r| (r.y, r.x) < (from.1, from.0)).max_by_key(|r| (r.y, r.x));
+    before.or_else(|| matches.iter().max_by_key(|r| (r.y, r.x))).copied()
+}
+
+
+/// Compare two pictures from `Canvas::to_debug_string`, and get `None` if they match or a report of every difference if they don't.
+/// Differing cells are listed by their `(x, y)` position, as a character or, in the color grid, a color letter.
+/// Used by `assert_frame_eq!`.
+///
+/// Example
+/// ```
+/// use terminalgl::canvas::frame_diff;
+/// assert_eq!(frame_diff("|ab|", "|ab|"), None);
+/// let report = frame_diff("|ab|\n|cd|", "|ab|\n|cx|").unwrap();
+/// assert!(report.starts_with("cell (1, 1): expected 'x', found 'd'\n"));
+/// ```
+pub fn frame_diff(actual: &str, expected: &str) -> Option<String> {
+    if actual == expected {
+        return None;
+    }
+    let (actual_lines, expected_lines): (Vec<&str>, Vec<&str>) = (actual.lines().collect(), expected.lines().collect());
+    let mut report = String::new();
+    let mut colors = false;
+    let mut y = 0;
+    for i in 0..actual_lines.len().max(expected_lines.len()) {
+        let (a, e) = (actual_lines.get(i).copied(), expected_lines.get(i).copied());
+        if a == Some("") || e == Some("") {
+            colors = true;
+            y = 0;
+        }
+        let row = |line: Option<&str>| line.and_then(|l| l.strip_prefix('|')?.strip_suffix('|')).map(|r| r.chars().collect::<Vec<char>>());
+        match (row(a), row(e)) {
+            (Some(a_row), Some(e_row)) => {
+                for x in 0..a_row.len().max(e_row.len()) {
+                    let (ac, ec) = (a_row.get(x), e_row.get(x));
+                    if ac != ec {
+                        let show = |c: Option<&char>| c.map_or("nothing".to_string(), |c| format!("{:?}", c));
+                        let kind = if colors { "color of cell" } else { "cell" };
+                        report.push_str(&format!("{} ({}, {}): expected {}, found {}\n", kind, x, y, show(ec), show(ac)));
+                    }
+                }
+            },
+            _ if a != e => {
+                report.push_str(&format!("line {}: expected {:?}, found {:?}\n", i + 1, e.unwrap_or(""), a.unwrap_or("")));
+            },
+            _ => {}
+        }
+        if a != Some("") && e != Some("") {
+            y += 1;
+        }
+    }
+    report.push_str(&format!("\nexpected:\n{}\n\nfound:\n{}", expected, actual));
+    Some(report)
+}
+
+
+/// Assert that a canvas's `to_debug_string` equals `expected`, panicking with each differing cell and both pictures if not.
+///
+/// Example
+/// ```
+/// use terminalgl::{assert_frame_eq, raster};
+/// use terminalgl::canvas::Canvas;
+/// let mut canvas = Canvas::new(5, 3);
+/// for (x, y) in raster::rectangle(0, 0, 5, 3, false) {
+///     canvas.set(x, y, '#', "");
+/// }
+/// assert_frame_eq!(canvas, "\
+/// |#####|
+/// |#   #|
+/// |#####|");
+/// ```
+#[macro_export]
+macro_rules! assert_frame_eq {
+    ($canvas:expr, $expected:expr $(,)?) => {
+        if let Some(report) = $crate::canvas::frame_diff(&$canvas.to_debug_string(), $expected) {
+            panic!("frames differ:\n{}", report);
+        }
+    };
+}
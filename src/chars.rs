@@ -0,0 +1,364 @@
+// Lookup tables for box-drawing, block, shade, and Braille characters, and the sanitizing every drawn character goes through.
+use core::sync::atomic::{AtomicBool, AtomicU32, AtomicUsize, Ordering};
+
+
+/// Line style for `box_drawing` and `junction`. `Rounded` is `Light` with rounded corners,
+/// `Ascii` draws with `-`, `|` and `+`, and `Dashed` is `Light` with dashed straight lines.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LineStyle {
+    Light,
+    Heavy,
+    Double,
+    Rounded,
+    Ascii,
+    Dashed
+}
+
+
+/// The characters for drawing boxes in one `LineStyle`, from `box_drawing`.
+/// Each `tee_` field is named after the direction its middle arm points, so `tee_down` is `┬` in the `Light` style.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BoxChars {
+    pub tl: char,
+    pub tr: char,
+    pub bl: char,
+    pub br: char,
+    pub h: char,
+    pub v: char,
+    pub cross: char,
+    pub tee_up: char,
+    pub tee_down: char,
+    pub tee_left: char,
+    pub tee_right: char
+}
+
+
+/// Bits of a `junction` mask for each direction a line leaves a cell in.
+const UP: usize = 1;
+const DOWN: usize = 2;
+const LEFT: usize = 4;
+const RIGHT: usize = 8;
+
+/// The character for every combination of arms in each style, indexed by mask.
+/// Styles without single arms repeat the straight line through the cell.
+const JUNCTIONS: [(LineStyle, [char; 16]); 6] = [
+    (LineStyle::Light, [' ', '╵', '╷', '│', '╴', '┘', '┐', '┤', '╶', '└', '┌', '├', '─', '┴', '┬', '┼']),
+    (LineStyle::Heavy, [' ', '╹', '╻', '┃', '╸', '┛', '┓', '┫', '╺', '┗', '┏', '┣', '━', '┻', '┳', '╋']),
+    (LineStyle::Double, [' ', '║', '║', '║', '═', '╝', '╗', '╣', '═', '╚', '╔', '╠', '═', '╩', '╦', '╬']),
+    (LineStyle::Rounded, [' ', '╵', '╷', '│', '╴', '╯', '╮', '┤', '╶', '╰', '╭', '├', '─', '┴', '┬', '┼']),
+    (LineStyle::Ascii, [' ', '|', '|', '|', '-', '+', '+', '+', '-', '+', '+', '+', '-', '+', '+', '+']),
+    (LineStyle::Dashed, [' ', '╵', '╷', '┆', '╴', '┘', '┐', '┤', '╶', '└', '┌', '├', '┄', '┴', '┬', '┼'])
+];
+
+
+fn junctions(style: LineStyle) -> &'static [char; 16] {
+    &JUNCTIONS[style as usize].1
+}
+
+
+/// Get the characters for drawing boxes in `style`.
+///
+/// Example
+/// ```
+/// use terminalgl::chars::{self, LineStyle};
+/// let light = chars::box_drawing(LineStyle::Light);
+/// assert_eq!([light.tl, light.h, light.tee_down, light.tr], ['┌', '─', '┬', '┐']);
+/// assert_eq!(chars::box_drawing(LineStyle::Double).cross, '╬');
+/// assert_eq!(chars::box_drawing(LineStyle::Rounded).br, '╯');
+/// assert_eq!(chars::box_drawing(LineStyle::Ascii).v, '|');
+/// assert_eq!(chars::box_drawing(LineStyle::Dashed).h, '┄');
+/// ```
+pub fn box_drawing(style: LineStyle) -> BoxChars {
+    let j = junctions(style);
+    BoxChars {
+        tl: j[DOWN | RIGHT],
+        tr: j[DOWN | LEFT],
+        bl: j[UP | RIGHT],
+        br: j[UP | LEFT],
+        h: j[LEFT | RIGHT],
+        v: j[UP | DOWN],
+        cross: j[UP | DOWN | LEFT | RIGHT],
+        tee_up: j[UP | LEFT | RIGHT],
+        tee_down: j[DOWN | LEFT | RIGHT],
+        tee_left: j[UP | DOWN | LEFT],
+        tee_right: j[UP | DOWN | RIGHT]
+    }
+}
+
+
+/// Get the character joining lines that leave a cell in each direction given as `true`, in `style`.
+/// With no directions this is a space.
+///
+/// Example
+/// ```
+/// use terminalgl::chars::{self, LineStyle};
+/// assert_eq!(chars::junction(true, true, true, true, LineStyle::Light), '┼');
+/// assert_eq!(chars::junction(true, true, false, true, LineStyle::Light), '├');
+/// assert_eq!(chars::junction(true, false, true, true, LineStyle::Light), '┴');
+/// assert_eq!(chars::junction(true, true, true, true, LineStyle::Heavy), '╋');
+/// assert_eq!(chars::junction(false, false, false, true, LineStyle::Light), '╶');
+/// ```
+pub fn junction(up: bool, down: bool, left: bool, right: bool, style: LineStyle) -> char {
+    let arm = |on: bool, bit: usize| if on { bit } else { 0 };
+    let mask = arm(up, UP) | arm(down, DOWN) | arm(left, LEFT) | arm(right, RIGHT);
+    junctions(style)[mask]
+}
+
+
+/// Get the directions the lines of box-drawing character `c` leave its cell in as `(up, down, left, right)`,
+/// and its style, or `None` if `c` isn't one of the characters `junction` gives.
+/// Characters shared by `Light` and `Rounded` are `Light`.
+///
+/// Example
+/// ```
+/// use terminalgl::chars::{self, LineStyle};
+/// assert_eq!(chars::arms('┤'), Some((true, true, true, false, LineStyle::Light)));
+/// assert_eq!(chars::arms('═'), Some((false, false, true, true, LineStyle::Double)));
+/// assert_eq!(chars::arms('x'), None);
+/// ```
+pub fn arms(c: char) -> Option<(bool, bool, bool, bool, LineStyle)> {
+    if c == ' ' {
+        return None;
+    }
+    // Searching from the most arms down picks `║` as a full vertical line, not a single arm.
+    JUNCTIONS.iter().find_map(|(style, chars)| {
+        (0..16).rev().find(|&mask| chars[mask] == c).map(|mask| {
+            (mask & UP != 0, mask & DOWN != 0, mask & LEFT != 0, mask & RIGHT != 0, *style)
+        })
+    })
+}
+
+
+/// Get the character for drawing box-drawing character `over` on top of `under`, joining their lines
+/// in the style of `over`, so adjoining borders share connected edges. If either isn't a character `junction` gives, this is `over`.
+///
+/// Example
+/// ```
+/// use terminalgl::chars::{self, LineStyle};
+/// let b = chars::box_drawing(LineStyle::Light);
+/// // Two panels side by side sharing a column: `┐` and `┌` become `┬`, and `┘` and `└` become `┴`.
+/// assert_eq!(chars::merge(b.tr, b.tl), '┬');
+/// assert_eq!(chars::merge(b.br, b.bl), '┴');
+/// // A panel below another whose left edge touches its top edge.
+/// assert_eq!(chars::merge(b.h, b.tee_right), '┼');
+/// assert_eq!(chars::merge('─', '║'), '╬');
+/// assert_eq!(chars::merge('x', '│'), '│');
+/// ```
+pub fn merge(under: char, over: char) -> char {
+    match (arms(under), arms(over)) {
+        (Some((u1, d1, l1, r1, _)), Some((u2, d2, l2, r2, style))) => junction(u1 || u2, d1 || d2, l1 || l2, r1 || r2, style),
+        _ => over
+    }
+}
+
+
+/// The block element characters, from `blocks`.
+/// Each eighths array goes from empty to full in eighths, and `quadrants` is indexed by a mask of the quarters filled in:
+/// `1` for the top left, `2` for the top right, `4` for the bottom left, and `8` for the bottom right.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Blocks {
+    pub full: char,
+    pub upper_half: char,
+    pub lower_half: char,
+    pub left_half: char,
+    pub right_half: char,
+    pub lower_eighths: [char; 9],
+    pub left_eighths: [char; 9],
+    pub quadrants: [char; 16]
+}
+
+
+/// Get the block element characters.
+///
+/// Example
+/// ```
+/// use terminalgl::chars;
+/// let blocks = chars::blocks();
+/// assert_eq!(blocks.lower_eighths[4], blocks.lower_half);
+/// assert_eq!(blocks.left_eighths[8], blocks.full);
+/// assert_eq!(blocks.quadrants[1 | 8], '▚');
+/// ```
+pub fn blocks() -> Blocks {
+    Blocks {
+        full: '█',
+        upper_half: '▀',
+        lower_half: '▄',
+        left_half: '▌',
+        right_half: '▐',
+        lower_eighths: [' ', '▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'],
+        left_eighths: [' ', '▏', '▎', '▍', '▌', '▋', '▊', '▉', '█'],
+        quadrants: [' ', '▘', '▝', '▀', '▖', '▌', '▞', '▛', '▗', '▚', '▐', '▜', '▄', '▙', '▟', '█']
+    }
+}
+
+
+/// Get the shade character for `level`, from `0` for a space through `░`, `▒` and `▓` to `4` for `█`.
+/// Levels past `4` are `█`.
+///
+/// Example
+/// ```
+/// use terminalgl::chars;
+/// assert_eq!((0..=5).map(chars::shade).collect::<Vec<_>>(), [' ', '░', '▒', '▓', '█', '█']);
+/// ```
+pub fn shade(level: usize) -> char {
+    [' ', '░', '▒', '▓', '█'][level.min(4)]
+}
+
+
+/// Get the Braille character with the dots in `mask` raised. Bits 0 to 7 correspond to Braille dots 1 to 8.
+///
+/// Example
+/// ```
+/// use terminalgl::chars;
+/// assert_eq!(chars::braille(0), '⠀');
+/// assert_eq!(chars::braille(0x01 | 0x08), '⠉');
+/// assert_eq!(chars::braille(0xff), '⣿');
+/// ```
+pub fn braille(mask: u8) -> char {
+    char::from_u32(0x2800 + mask as u32).unwrap_or(' ')
+}
+
+
+/// How drawing treats characters that would corrupt the layout, from `sanitize_options` and set with `set_sanitize_options`.
+/// Control characters (see `is_disruptive`) are drawn as `replacement`, and tabs in text expand with spaces to the next multiple of
+/// `tab_width` columns from the start of the text (a lone tab is one space). With `ascii_only` on, every character is also drawn
+/// as its `to_ascii` approximation, for dumb terminals and fonts without box-drawing glyphs.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Sanitize {
+    pub replacement: char,
+    pub tab_width: usize,
+    pub ascii_only: bool
+}
+
+impl Default for Sanitize {
+    fn default() -> Self {
+        Self { replacement: '·', tab_width: 4, ascii_only: false }
+    }
+}
+
+static REPLACEMENT: AtomicU32 = AtomicU32::new('·' as u32);
+static TAB_WIDTH: AtomicUsize = AtomicUsize::new(4);
+static ASCII_ONLY: AtomicBool = AtomicBool::new(false);
+
+
+/// Set how drawing sanitizes characters from now on. A `replacement` that is itself disruptive is replaced by a space,
+/// and a `tab_width` of 0 counts as 1.
+///
+/// Example
+/// ```
+/// use terminalgl::chars::{self, Sanitize};
+/// chars::set_sanitize_options(Sanitize { replacement: '\x07', tab_width: 0, ascii_only: false });
+/// assert_eq!(chars::sanitize_options(), Sanitize { replacement: ' ', tab_width: 1, ascii_only: false });
+/// chars::set_sanitize_options(Sanitize::default());
+/// ```
+pub fn set_sanitize_options(options: Sanitize) {
+    let replacement = if is_disruptive(options.replacement) { ' ' } else { options.replacement };
+    REPLACEMENT.store(replacement as u32, Ordering::Relaxed);
+    TAB_WIDTH.store(options.tab_width.max(1), Ordering::Relaxed);
+    ASCII_ONLY.store(options.ascii_only, Ordering::Relaxed);
+}
+
+
+/// Get how drawing sanitizes characters, as last set with `set_sanitize_options`.
+pub fn sanitize_options() -> Sanitize {
+    Sanitize {
+        replacement: char::from_u32(REPLACEMENT.load(Ordering::Relaxed)).unwrap_or(' '),
+        tab_width: TAB_WIDTH.load(Ordering::Relaxed),
+        ascii_only: ASCII_ONLY.load(Ordering::Relaxed)
+    }
+}
+
+
+/// Get whether printing `c` would move the cursor, start an escape sequence, or otherwise disturb the terminal:
+/// C0 and C1 control characters and DEL, the bidirectional formatting characters that reorder the text around them,
+/// and the noncharacters Unicode never assigns.
+///
+/// Example
+/// ```
+/// use terminalgl::chars;
+/// assert!(['\t', '\r', '\x1b', '\x7f', '\u{9b}', '\u{202e}', '\u{fffe}'].iter().all(|&c| chars::is_disruptive(c)));
+/// assert!(!['a', ' ', '─', '漢', '\u{301}'].iter().any(|&c| chars::is_disruptive(c)));
+/// ```
+pub fn is_disruptive(c: char) -> bool {
+    c.is_control()
+        || matches!(c, '\u{200e}' | '\u{200f}' | '\u{202a}'..='\u{202e}' | '\u{2066}'..='\u{2069}' | '\u{fdd0}'..='\u{fdef}')
+        || c as u32 & 0xfffe == 0xfffe
+}
+
+
+/// Get an ASCII character approximating `c`: box-drawing characters become their `LineStyle::Ascii` junction,
+/// shades and blocks become `.`, `:` or `#`, Braille dots become `.`, common punctuation and arrows their nearest ASCII,
+/// and anything else outside ASCII `?`. ASCII characters are unchanged.
+///
+/// Example
+/// ```
+/// use terminalgl::chars;
+/// let text: String = "┌─┬─┐ ║ ░▒█ ⣿ ← · …".chars().map(chars::to_ascii).collect();
+/// assert_eq!(text, "+-+-+ | .:# . < . .");
+/// assert_eq!(chars::to_ascii('漢'), '?');
+/// ```
+pub fn to_ascii(c: char) -> char {
+    if c.is_ascii() {
+        return c;
+    }
+    if let Some((up, down, left, right, _)) = arms(c) {
+        return junction(up, down, left, right, LineStyle::Ascii);
+    }
+    match c {
+        '\u{2500}'..='\u{257f}' => '+',
+        '░' => '.',
+        '⠀' | '\u{a0}' => ' ',
+        '▒' => ':',
+        '\u{2580}'..='\u{259f}' => '#',
+        '\u{2801}'..='\u{28ff}' | '·' | '•' | '…' => '.',
+        '←' => '<',
+        '→' => '>',
+        '↑' => '^',
+        '↓' => 'v',
+        '‘' | '’' => '\'',
+        '“' | '”' => '"',
+        '–' | '—' => '-',
+        _ => '?'
+    }
+}
+
+
+/// Get the character drawn for `c` under the current `sanitize_options`: `replacement` if `c` is disruptive
+/// (a tab, not being in text, is a space), then its `to_ascii` approximation if `ascii_only` is on.
+///
+/// Example
+/// ```
+/// use terminalgl::chars::{self, Sanitize};
+/// assert_eq!(chars::sanitize('\x1b'), '·');
+/// assert_eq!(chars::sanitize('\t'), ' ');
+/// chars::set_sanitize_options(Sanitize { ascii_only: true, ..Sanitize::default() });
+/// assert_eq!(chars::sanitize('\x1b'), '.');
+/// assert_eq!(chars::sanitize('┼'), '+');
+/// chars::set_sanitize_options(Sanitize::default());
+/// ```
+pub fn sanitize(c: char) -> char {
+    let c = match c {
+        '\t' => ' ',
+        c if is_disruptive(c) => char::from_u32(REPLACEMENT.load(Ordering::Relaxed)).unwrap_or(' '),
+        c => c
+    };
+    if ASCII_ONLY.load(Ordering::Relaxed) { to_ascii(c) } else { c }
+}
+
+
+/// Get the characters drawn for `text`: each one passed through `sanitize`, with tabs expanded to the next tab stop.
+///
+/// Example
+/// ```
+/// use terminalgl::chars;
+/// assert_eq!(chars::sanitize_text("a\tbcde\tf\r\n").collect::<String>(), "a   bcde    f··");
+/// ```
+pub fn sanitize_text(text: &str) -> impl Iterator<Item = char> + '_ {
+    let tab_width = TAB_WIDTH.load(Ordering::Relaxed).max(1);
+    let mut column = 0;
+    text.chars().flat_map(move |c| {
+        let count = if c == '\t' { tab_width - column % tab_width } else { 1 };
+        column += count;
+        core::iter::repeat_n(sanitize(c), count)
+    })
+}
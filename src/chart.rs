@@ -0,0 +1,926 @@
+// Line charts drawn with Braille characters.
+use super::braille::BrailleGrid;
+use super::chars;
+use super::fmt;
+use super::Rect;
+
+
+/// A named series of `(x, y)` points for a `Chart`, drawn with `ccode`.
+/// Points that are not finite break the line.
+#[derive(Clone, Debug, Default)]
+pub struct Series {
+    pub name: String,
+    pub points: Vec<(f64, f64)>,
+    pub ccode: String
+}
+
+impl Series {
+    /// Create a series named `name` with points `points`, drawn with `ccode`.
+    pub fn new(name: &str, points: Vec<(f64, f64)>, ccode: &str) -> Series {
+        Series { name: name.to_string(), points, ccode: ccode.to_string() }
+    }
+}
+
+
+/// A line chart with axes, an optional title, a legend, and any number of series.
+/// Leave `x_range` or `y_range` as `None` to scale that axis to fit the data.
+#[derive(Clone, Debug, Default)]
+pub struct Chart {
+    pub title: Option<String>,
+    pub x_range: Option<(f64, f64)>,
+    pub y_range: Option<(f64, f64)>,
+    pub series: Vec<Series>
+}
+
+impl Chart {
+    /// Create an empty chart.
+    pub fn new() -> Chart {
+        Chart::default()
+    }
+
+    /// Get the axis ranges as `((x_min, x_max), (y_min, y_max))`.
+    /// Automatic ranges cover every finite point; an axis with no data spans `0.0..1.0`,
+    /// and one where every value is `v` spans `v - 1.0..v + 1.0`.
+    ///
+    /// Example
+    /// ```
+    /// use terminalgl::chart::{Chart, Series};
+    /// let mut chart = Chart::new();
+    /// assert_eq!(chart.ranges(), ((0.0, 1.0), (0.0, 1.0)));
+    /// chart.series.push(Series::new("flat", vec![(0.0, 5.0), (4.0, 5.0)], ""));
+    /// chart.series.push(Series::new("empty", vec![], ""));
+    /// assert_eq!(chart.ranges(), ((0.0, 4.0), (4.0, 6.0)));
+    /// ```
+    pub fn ranges(&self) -> ((f64, f64), (f64, f64)) {
+        let points = self.series.iter()
+            .flat_map(|s| s.points.iter())
+            .filter(|(x, y)| x.is_finite() && y.is_finite());
+        let xs = points.clone().map(|p| p.0);
+        let ys = points.map(|p| p.1);
+        (
+            self.x_range.unwrap_or_else(|| auto_range(xs)),
+            self.y_range.unwrap_or_else(|| auto_range(ys))
+        )
+    }
+
+    /// Lay out the chart inside `rect` as `(x, y, c, series)` cells,
+    /// where `series` is the index of the series the cell belongs to, or `None` for the title, axes, and labels.
+    ///
+    /// Example
+    /// ```
+    /// use terminalgl::chart::{Chart, Series};
+    /// use terminalgl::Rect;
+    /// let mut chart = Chart::new();
+    /// chart.series.push(Series::new("", vec![(0.0, 0.0), (1.0, 1.0)], ""));
+    /// let cells = chart.cells(Rect::new(0, 0, 6, 4));
+    /// assert!(cells.contains(&(1, 2, '└', None)));
+    /// assert!(cells.contains(&(2, 1, '⡠', Some(0))));
+    /// assert!(cells.contains(&(5, 0, '⠊', Some(0))));
+    /// ```
+    pub fn cells(&self, rect: Rect) -> Vec<(isize, isize, char, Option<usize>)> {
+        // Laid out at the origin and moved into place, so cells past the limits of `isize` are dropped instead of overflowing.
+        self.layout(Rect::new(0, 0, rect.width, rect.height)).into_iter()
+            .filter_map(|(x, y, c, series)| rect.translate(x, y).map(|(x, y)| (x, y, c, series)))
+            .collect()
+    }
+
+    fn layout(&self, rect: Rect) -> Vec<(isize, isize, char, Option<usize>)> {
+        let mut cells = Vec::new();
+        let mut top = rect.y;
+        let bottom = rect.y + rect.height as isize;
+        let right = rect.x + rect.width as isize;
+
+        if let Some(title) = &self.title {
+            let x = rect.x + (rect.width as isize - title.chars().count() as isize).max(0) / 2;
+            push_text(&mut cells, x, top, title, right, None);
+            top += 1;
+        }
+        if self.series.iter().any(|s| !s.name.is_empty()) {
+            let mut x = rect.x;
+            for (i, series) in self.series.iter().enumerate().filter(|(_, s)| !s.name.is_empty()) {
+                push_text(&mut cells, x, top, "■", right, Some(i));
+                push_text(&mut cells, x+2, top, &series.name, right, None);
+                x += series.name.chars().count() as isize + 4;
+            }
+            top += 1;
+        }
+
+        let ((x_min, x_max), (y_min, y_max)) = self.ranges();
+        let plot_h = bottom - top - 2;
+        let mut y_labels = vec![(top, format_tick(y_max)), (top + plot_h - 1, format_tick(y_min))];
+        if plot_h >= 5 {
+            y_labels.push((top + plot_h / 2, format_tick((y_min + y_max) / 2.0)));
+        }
+        let label_w = y_labels.iter().map(|(_, l)| l.chars().count()).max().unwrap() as isize;
+        let axis_x = rect.x + label_w;
+        let plot_x = axis_x + 1;
+        let plot_w = right - plot_x;
+        if plot_h < 1 || plot_w < 1 {
+            return cells;
+        }
+
+        // Axes and labels
+        for (y, label) in y_labels {
+            push_text(&mut cells, axis_x - label.chars().count() as isize, y, &label, right, None);
+        }
+        for y in top..top+plot_h {
+            cells.push((axis_x, y, '│', None));
+        }
+        let axis_y = top + plot_h;
+        cells.push((axis_x, axis_y, '└', None));
+        for x in plot_x..right {
+            cells.push((x, axis_y, '─', None));
+        }
+        let min_label = format_tick(x_min);
+        let max_label = format_tick(x_max);
+        let mid_label = format_tick((x_min + x_max) / 2.0);
+        let min_end = plot_x + min_label.chars().count() as isize;
+        let max_start = right - max_label.chars().count() as isize;
+        let mid_start = plot_x + plot_w / 2 - mid_label.chars().count() as isize / 2;
+        push_text(&mut cells, plot_x, axis_y+1, &min_label, right, None);
+        if max_start > min_end {
+            push_text(&mut cells, max_start, axis_y+1, &max_label, right, None);
+        }
+        if mid_start > min_end && mid_start + (mid_label.chars().count() as isize) < max_start {
+            push_text(&mut cells, mid_start, axis_y+1, &mid_label, right, None);
+        }
+
+        // Series
+        let (dot_w, dot_h) = ((plot_w * 2 - 1) as f64, (plot_h * 4 - 1) as f64);
+        let to_dot = |(x, y): (f64, f64)| {
+            let dx = (x - x_min) / (x_max - x_min) * dot_w;
+            let dy = (1.0 - (y - y_min) / (y_max - y_min)) * dot_h;
+            (dx.round() as isize, dy.round() as isize)
+        };
+        let mut owners: Vec<Option<usize>> = vec![None; (plot_w * plot_h) as usize];
+        let mut grid = BrailleGrid::new(plot_w as usize, plot_h as usize);
+        for (i, series) in self.series.iter().enumerate() {
+            let mut series_grid = BrailleGrid::new(plot_w as usize, plot_h as usize);
+            let finite = |p: &(f64, f64)| p.0.is_finite() && p.1.is_finite();
+            for (j, p) in series.points.iter().enumerate() {
+                let prev = if j > 0 { series.points.get(j-1).filter(|q| finite(q)) } else { None };
+                if !finite(p) {
+                    continue;
+                }
+                let segment = match prev {
+                    Some(q) => clip_segment(*q, *p, (x_min, x_max), (y_min, y_max)),
+                    None => clip_segment(*p, *p, (x_min, x_max), (y_min, y_max))
+                };
+                if let Some((a, b)) = segment {
+                    let (a, b) = (to_dot(a), to_dot(b));
+                    series_grid.line(a.0, a.1, b.0, b.1);
+                }
+            }
+            for (x, y, _) in series_grid.cells() {
+                grid.add_mask(x, y, series_grid.mask(x, y));
+                owners[y * plot_w as usize + x] = Some(i);
+            }
+        }
+        for (x, y, c) in grid.cells() {
+            cells.push((plot_x + x as isize, top + y as isize, c, owners[y * plot_w as usize + x]));
+        }
+        cells
+    }
+}
+
+
+fn auto_range(values: impl Iterator<Item = f64>) -> (f64, f64) {
+    let (min, max) = values.fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), v| (lo.min(v), hi.max(v)));
+    if min > max {
+        (0.0, 1.0)
+    }
+    else if min == max {
+        (min - 1.0, max + 1.0)
+    }
+    else {
+        (min, max)
+    }
+}
+
+
+/// The most columns an axis label takes. Longer labels are shortened with an SI prefix (see `fmt::si`).
+const MAX_TICK_WIDTH: usize = 6;
+
+
+/// Format an axis label, dropping insignificant decimals, in at most `MAX_TICK_WIDTH` columns.
+fn format_tick(value: f64) -> String {
+    let s = format!("{:.2}", value);
+    let s = s.trim_end_matches('0').trim_end_matches('.');
+    let rounded_away = value != 0.0 && !s.chars().any(|c| ('1'..='9').contains(&c));
+    match s.chars().count() > MAX_TICK_WIDTH || rounded_away {
+        true => fmt::si(value),
+        false => s.to_string()
+    }
+}
+
+
+fn push_text(cells: &mut Vec<(isize, isize, char, Option<usize>)>, x: isize, y: isize, text: &str, right: isize, series: Option<usize>) {
+    for (i, c) in text.chars().enumerate() {
+        if x + (i as isize) < right {
+            cells.push((x + i as isize, y, c, series));
+        }
+    }
+}
+
+
+/// Clip the segment from `a` to `b` to the box spanned by `x_range` and `y_range` (Liang–Barsky).
+fn clip_segment(a: (f64, f64), b: (f64, f64), x_range: (f64, f64), y_range: (f64, f64)) -> Option<((f64, f64), (f64, f64))> {
+    let (dx, dy) = (b.0 - a.0, b.1 - a.1);
+    let mut t0: f64 = 0.0;
+    let mut t1: f64 = 1.0;
+    let edges = [
+        (-dx, a.0 - x_range.0),
+        (dx, x_range.1 - a.0),
+        (-dy, a.1 - y_range.0),
+        (dy, y_range.1 - a.1)
+    ];
+    for (p, q) in edges {
+        if p == 0.0 {
+            if q < 0.0 {
+                return None;
+            }
+        }
+        else {
+            let t = q / p;
+            if p < 0.0 {
+                t0 = t0.max(t);
+            }
+            else {
+                t1 = t1.min(t);
+            }
+        }
+    }
+    if t0 > t1 {
+        return None;
+    }
+    Some(((a.0 + t0 * dx, a.1 + t0 * dy), (a.0 + t1 * dx, a.1 + t1 * dy)))
+}
+
+
+/// Open, high, low, and close prices of one candle for `candle_cells`.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Ohlc {
+    pub open: f64,
+    pub high: f64,
+    pub low: f64,
+    pub close: f64
+}
+
+impl Ohlc {
+    /// Create a candle from its open, high, low, and close.
+    pub fn new(open: f64, high: f64, low: f64, close: f64) -> Ohlc {
+        Ohlc { open, high, low, close }
+    }
+
+    /// Get whether the candle closed at or above its open.
+    pub fn is_up(&self) -> bool {
+        self.close >= self.open
+    }
+}
+
+
+/// Options for `candle_cells`.
+/// Each candle is `width` columns wide with `gap` empty columns after it.
+/// Leave `y_range` as `None` to scale the y axis to fit the data.
+#[derive(Clone, Copy, Debug)]
+pub struct CandleOptions {
+    pub width: usize,
+    pub gap: usize,
+    pub y_range: Option<(f64, f64)>
+}
+
+impl Default for CandleOptions {
+    fn default() -> CandleOptions {
+        CandleOptions { width: 1, gap: 1, y_range: None }
+    }
+}
+
+
+/// Lay out candlesticks for `data` inside `rect` as `(x, y, c, up)` cells, where `up` tells whether the cell
+/// belongs to a candle that closed at or above its open. Wicks are drawn with `│`, bodies with `█`,
+/// and bodies where `open == close` with a single row of `─`.
+/// When there are more candles than fit in `rect`, only the most recent ones are shown.
+///
+/// Example
+/// ```
+/// use terminalgl::chart::{self, CandleOptions, Ohlc};
+/// use terminalgl::Rect;
+/// let data = [
+///     Ohlc::new(1.0, 3.0, 0.0, 2.0),
+///     Ohlc::new(3.0, 4.0, 1.0, 1.0),
+///     Ohlc::new(2.0, 2.0, 2.0, 2.0),
+/// ];
+/// let mut rows = vec![vec![' '; 5]; 5];
+/// for (x, y, c, _) in chart::candle_cells(Rect::new(0, 0, 5, 5), &data, CandleOptions::default()) {
+///     rows[y as usize][x as usize] = c;
+/// }
+/// let rows: Vec<String> = rows.into_iter().map(|r| r.into_iter().collect()).collect();
+/// assert_eq!(rows, vec![
+///     "  │  ",
+///     "│ █  ",
+///     "█ █ ─",
+///     "█ █  ",
+///     "│    ",
+/// ]);
+/// ```
+pub fn candle_cells(rect: Rect, data: &[Ohlc], opts: CandleOptions) -> Vec<(isize, isize, char, bool)> {
+    let mut cells = Vec::new();
+    let width = opts.width.max(1);
+    let slot = width + opts.gap;
+    let count = ((rect.width + opts.gap) / slot).min(data.len());
+    if count == 0 || rect.height == 0 {
+        return cells;
+    }
+    let data = &data[data.len()-count..];
+    let (lo, hi) = opts.y_range.unwrap_or_else(|| {
+        auto_range(data.iter().flat_map(|d| [d.low, d.high]).filter(|v| v.is_finite()))
+    });
+    let rows = rect.height as f64 - 1.0;
+    let to_row = |v: f64| ((hi - v) / (hi - lo) * rows).round().clamp(0.0, rows) as isize;
+    let mut push = |x: isize, y: isize, c: char, up: bool| {
+        if let Some((x, y)) = rect.translate(x, y) {
+            cells.push((x, y, c, up));
+        }
+    };
+
+    for (i, candle) in data.iter().enumerate() {
+        let x = (i * slot) as isize;
+        let up = candle.is_up();
+        let wick_x = x + width as isize / 2;
+        for y in to_row(candle.high)..=to_row(candle.low) {
+            push(wick_x, y, '│', up);
+        }
+        let (body_top, body_bottom) = (to_row(candle.open.max(candle.close)), to_row(candle.open.min(candle.close)));
+        let body_c = if candle.open == candle.close { '─' } else { '█' };
+        for y in body_top..=body_bottom {
+            for dx in 0..width as isize {
+                push(x+dx, y, body_c, up);
+            }
+        }
+    }
+    cells
+}
+
+
+
+/// Get the lowest and highest of `samples` in each of `columns` equal runs of them, clamped to `-1..=1`, as `(min, max)` pairs.
+/// Keeping both ends of every run means a spike one sample long still shows however many samples share its column,
+/// where picking one sample per column would almost always miss it. With more columns than samples, samples repeat across columns.
+/// Samples that are not finite are skipped, and columns with none left are `None`.
+///
+/// Example
+/// ```
+/// use terminalgl::chart;
+/// // A click one sample long in a second of quiet hiss.
+/// let mut samples: Vec<f32> = (0..48_000).map(|i| ((i * 7919 % 200) as f32 - 100.0) / 10_000.0).collect();
+/// samples[30_001] = -0.9;
+/// let extents = chart::column_extents(&samples, 80);
+/// assert_eq!(extents.len(), 80);
+/// assert_eq!(extents[50].unwrap().0, -0.9);
+/// assert!(extents.iter().enumerate().all(|(i, e)| i == 50 || e.unwrap().0 > -0.02));
+///
+/// // Taking every 600th sample would have missed it.
+/// assert!((0..80).all(|i| samples[i * 600] > -0.02));
+///
+/// // Clamping, and more columns than samples.
+/// assert_eq!(chart::column_extents(&[2.0, f32::NAN, -0.5], 4), vec![Some((1.0, 1.0)), Some((1.0, 1.0)), None, Some((-0.5, -0.5))]);
+/// assert_eq!(chart::column_extents(&[], 2), vec![None, None]);
+/// ```
+pub fn column_extents(samples: &[f32], columns: usize) -> Vec<Option<(f32, f32)>> {
+    let n = samples.len();
+    (0..columns).map(|i| {
+        if n == 0 {
+            return None;
+        }
+        let start = i * n / columns;
+        let end = ((i + 1) * n / columns).clamp(start + 1, n);
+        samples[start..end].iter()
+            .filter(|s| s.is_finite())
+            .map(|s| s.clamp(-1.0, 1.0))
+            .fold(None, |extent, s| Some(match extent {
+                Some((lo, hi)) => (s.min(lo), s.max(hi)),
+                None => (s, s)
+            }))
+    }).collect()
+}
+
+
+/// Options for `waveform_cells`.
+/// Use `mirror` to draw each column symmetric about the midline, as tall as its larger swing either way,
+/// and `baseline` to draw the midline with `─` in columns the trace leaves it empty.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct WaveformOptions {
+    pub mirror: bool,
+    pub baseline: bool
+}
+
+
+/// Lay out an oscilloscope trace of `samples`, from `-1` at the bottom of `rect` to `1` at the top, as `(x, y, c)` cells.
+/// Each column spans the lowest to the highest sample falling in it (see `column_extents`), drawn with half blocks
+/// so the trace has twice as many rows as `rect`.
+///
+/// Example
+/// ```
+/// use terminalgl::chart::{self, WaveformOptions};
+/// use terminalgl::Rect;
+/// let samples: Vec<f32> = (0..8).map(|i| (i as f32 * std::f32::consts::PI / 4.0).sin() * 0.8).collect();
+/// let render = |opts: WaveformOptions| {
+///     let mut rows = vec![vec![' '; 8]; 3];
+///     for (x, y, c) in chart::waveform_cells(Rect::new(0, 0, 8, 3), &samples, opts) {
+///         rows[y as usize][x as usize] = c;
+///     }
+///     rows.into_iter().map(|r| r.into_iter().collect()).collect::<Vec<String>>()
+/// };
+/// assert_eq!(render(WaveformOptions::default()), vec![
+///     " ▄▀▄    ",
+///     "▄   ▄   ",
+///     "     ▀▄▀",
+/// ]);
+/// assert_eq!(render(WaveformOptions { mirror: true, baseline: true }), vec![
+///     " ▄▄▄ ▄▄▄",
+///     "─███─███",
+///     " ▀▀▀ ▀▀▀",
+/// ]);
+/// ```
+pub fn waveform_cells(rect: Rect, samples: &[f32], opts: WaveformOptions) -> Vec<(isize, isize, char)> {
+    let mut cells = Vec::new();
+    if rect.width == 0 || rect.height == 0 {
+        return cells;
+    }
+    let blocks = chars::blocks();
+    let last = (rect.height * 2 - 1) as f32;
+    let to_half = |v: f32| ((1.0 - v) / 2.0 * last).round() as usize;
+    let middle = to_half(0.0) / 2;
+    for (x, extent) in column_extents(samples, rect.width).into_iter().enumerate() {
+        let mut covers_middle = false;
+        if let Some((lo, hi)) = extent {
+            // Mirrored spans reach the same number of half rows either side of the midline, so they stay symmetric,
+            // and swings too small to reach a whole one leave the column empty.
+            let halves = match opts.mirror {
+                true => {
+                    let reach = (lo.abs().max(hi.abs()) * rect.height as f32).round() as usize;
+                    rect.height - reach..rect.height + reach
+                },
+                false => to_half(hi)..to_half(lo) + 1
+            };
+            for row in halves.start / 2..halves.end.div_ceil(2) {
+                let c = match (halves.contains(&(row * 2)), halves.contains(&(row * 2 + 1))) {
+                    (true, true) => blocks.full,
+                    (true, false) => blocks.upper_half,
+                    (false, true) => blocks.lower_half,
+                    (false, false) => continue
+                };
+                covers_middle |= row == middle;
+                if let Some((x, y)) = rect.translate(x as isize, row as isize) {
+                    cells.push((x, y, c));
+                }
+            }
+        }
+        if opts.baseline && !covers_middle {
+            if let Some((x, y)) = rect.translate(x as isize, middle as isize) {
+                cells.push((x, y, '─'));
+            }
+        }
+    }
+    cells
+}
+
+
+/// Options for `spectrum_cells`.
+/// With `scale` set to `Log10`, each column covers the same ratio of frequencies rather than the same number of bins,
+/// which gives the low end, where most of what you hear is, most of the width.
+#[derive(Clone, Copy, Debug)]
+pub struct SpectrumOptions {
+    pub scale: Scale
+}
+
+impl Default for SpectrumOptions {
+    fn default() -> SpectrumOptions {
+        SpectrumOptions { scale: Scale::Linear }
+    }
+}
+
+
+/// Lay out a bar spectrum of `magnitudes`, one per frequency bin from 0 upward in even steps as an FFT gives them,
+/// as `(x, y, c)` cells, with bars rising from the bottom of `rect` to its top at `1`. Each column shows the largest
+/// magnitude among its bins, in eighths of a cell. Magnitudes clamp to `0..=1`, and ones that are not finite are skipped.
+/// The log scale leaves out bin 0, which has no place on it.
+///
+/// Example
+/// ```
+/// use terminalgl::chart::{self, Scale, SpectrumOptions};
+/// use terminalgl::Rect;
+/// let magnitudes = [0.0, 1.0, 0.5, 0.25, 0.1, 0.05, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.3];
+/// let render = |opts: SpectrumOptions| {
+///     let mut rows = vec![vec![' '; 4]; 2];
+///     for (x, y, c) in chart::spectrum_cells(Rect::new(0, 0, 4, 2), &magnitudes, opts) {
+///         rows[y as usize][x as usize] = c;
+///     }
+///     rows.into_iter().map(|r| r.into_iter().collect()).collect::<Vec<String>>()
+/// };
+/// // Evenly spread, the first column holds bins 0 to 3.
+/// assert_eq!(render(SpectrumOptions::default()), vec![
+///     "█   ",
+///     "█▂ ▅",
+/// ]);
+/// // On a log scale, bins 1, 2 to 3, 4 to 7 and 8 to 15 get a column each.
+/// assert_eq!(render(SpectrumOptions { scale: Scale::Log10 }), vec![
+///     "█   ",
+///     "██▂▅",
+/// ]);
+/// ```
+pub fn spectrum_cells(rect: Rect, magnitudes: &[f32], opts: SpectrumOptions) -> Vec<(isize, isize, char)> {
+    let mut cells = Vec::new();
+    let (n, columns) = (magnitudes.len(), rect.width);
+    if n == 0 || columns == 0 || rect.height == 0 {
+        return cells;
+    }
+    let bins = |i: usize| match opts.scale {
+        Scale::Log10 if n > 1 => {
+            // Columns narrower than a bin show the bin they fall in.
+            let edge = |i: usize| ((n as f64).powf(i as f64 / columns as f64).round() as usize).clamp(1, n);
+            let start = edge(i).min(n - 1);
+            start..edge(i + 1).max(start + 1)
+        },
+        _ => {
+            let start = i * n / columns;
+            start..((i + 1) * n / columns).clamp(start + 1, n)
+        }
+    };
+    let blocks = chars::blocks();
+    for x in 0..columns {
+        let peak = magnitudes[bins(x)].iter().filter(|m| m.is_finite()).fold(0.0f32, |peak, &m| peak.max(m.clamp(0.0, 1.0)));
+        let eighths = (peak * rect.height as f32 * 8.0).round() as usize;
+        for row in 0..rect.height.min(eighths.div_ceil(8)) {
+            let c = blocks.lower_eighths[(eighths - row * 8).min(8)];
+            if let Some((x, y)) = rect.translate(x as isize, (rect.height - 1 - row) as isize) {
+                cells.push((x, y, c));
+            }
+        }
+    }
+    cells
+}
+
+/// How `scatter_cells` renders points.
+/// `Marker` draws each point as its series' marker, `Braille` draws each point as a single Braille dot,
+/// and `Density` draws cells holding more than one point with `░▒▓█` by how many points they hold.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ScatterMode {
+    Marker,
+    Braille,
+    Density
+}
+
+
+/// Options for `scatter_cells`.
+/// Leave `x_range` or `y_range` as `None` to scale that axis to fit the data,
+/// widened on both sides by `margin` times the data's span.
+#[derive(Clone, Copy, Debug)]
+pub struct ScatterOptions {
+    pub mode: ScatterMode,
+    pub margin: f64,
+    pub x_range: Option<(f64, f64)>,
+    pub y_range: Option<(f64, f64)>
+}
+
+impl Default for ScatterOptions {
+    fn default() -> ScatterOptions {
+        ScatterOptions { mode: ScatterMode::Marker, margin: 0.05, x_range: None, y_range: None }
+    }
+}
+
+
+/// Lay out a scatter plot of `series`, given as `(points, marker)` pairs, inside `rect` as `(x, y, c, series)` cells,
+/// where `series` is the index of the series the cell belongs to. When series share a cell, the later one wins.
+/// Points outside the axis ranges and points that are not finite are skipped.
+///
+/// Example
+/// ```
+/// use terminalgl::chart::{self, ScatterMode, ScatterOptions};
+/// use terminalgl::Rect;
+/// let a = [(0.0, 0.0), (1.0, 1.0), (f64::NAN, 0.5)];
+/// let b = [(1.0, 0.0)];
+/// let opts = ScatterOptions { margin: 0.0, ..Default::default() };
+/// let cells = chart::scatter_cells(Rect::new(0, 0, 3, 3), &[(&a, 'o'), (&b, 'x')], opts);
+/// assert_eq!(cells, vec![(2, 0, 'o', 0), (0, 2, 'o', 0), (2, 2, 'x', 1)]);
+///
+/// let dense = [(0.0, 0.0), (0.0, 0.0), (0.0, 0.0), (1.0, 1.0)];
+/// let opts = ScatterOptions { mode: ScatterMode::Density, margin: 0.0, ..Default::default() };
+/// let cells = chart::scatter_cells(Rect::new(0, 0, 3, 3), &[(&dense, '.')], opts);
+/// assert_eq!(cells, vec![(2, 0, '.', 0), (0, 2, '█', 0)]);
+/// ```
+pub fn scatter_cells(rect: Rect, series: &[(&[(f64, f64)], char)], opts: ScatterOptions) -> Vec<(isize, isize, char, usize)> {
+    let mut cells = Vec::new();
+    if rect.width == 0 || rect.height == 0 {
+        return cells;
+    }
+    let finite = series.iter()
+        .flat_map(|(points, _)| points.iter())
+        .filter(|(x, y)| x.is_finite() && y.is_finite());
+    let with_margin = |(lo, hi): (f64, f64)| {
+        let pad = (hi - lo) * opts.margin;
+        (lo - pad, hi + pad)
+    };
+    let (x_min, x_max) = opts.x_range.unwrap_or_else(|| with_margin(auto_range(finite.clone().map(|p| p.0))));
+    let (y_min, y_max) = opts.y_range.unwrap_or_else(|| with_margin(auto_range(finite.map(|p| p.1))));
+
+    let (w, h) = match opts.mode {
+        ScatterMode::Braille => (rect.width * 2, rect.height * 4),
+        _ => (rect.width, rect.height)
+    };
+    let to_grid = |(x, y): (f64, f64)| {
+        if !(x >= x_min && x <= x_max && y >= y_min && y <= y_max) {
+            return None;
+        }
+        let gx = ((x - x_min) / (x_max - x_min) * (w - 1) as f64).round() as usize;
+        let gy = ((1.0 - (y - y_min) / (y_max - y_min)) * (h - 1) as f64).round() as usize;
+        Some((gx, gy))
+    };
+
+    let mut owners: Vec<Option<usize>> = vec![None; rect.width * rect.height];
+    let mut counts: Vec<usize> = vec![0; rect.width * rect.height];
+    let mut grid = BrailleGrid::new(rect.width, rect.height);
+    for (i, (points, _)) in series.iter().enumerate() {
+        for (gx, gy) in points.iter().filter_map(|p| to_grid(*p)) {
+            let index = match opts.mode {
+                ScatterMode::Braille => {
+                    grid.set(gx as isize, gy as isize);
+                    gy / 4 * rect.width + gx / 2
+                },
+                _ => gy * rect.width + gx
+            };
+            owners[index] = Some(i);
+            counts[index] += 1;
+        }
+    }
+
+    let max_count = counts.iter().copied().max().unwrap_or(0);
+    for (index, owner) in owners.iter().enumerate() {
+        let Some(owner) = *owner else { continue };
+        let (x, y) = (index % rect.width, index / rect.width);
+        let c = match opts.mode {
+            ScatterMode::Braille => super::braille::char_from_mask(grid.mask(x, y)),
+            ScatterMode::Density if counts[index] > 1 => {
+                let level = (counts[index] - 1) as f64 / (max_count - 1) as f64 * 3.0;
+                super::chars::shade(level.round() as usize + 1)
+            },
+            _ => series[owner].1
+        };
+        if let Some((x, y)) = rect.translate(x as isize, y as isize) {
+            cells.push((x, y, c, owner));
+        }
+    }
+    cells
+}
+
+
+/// Scale of an axis for `axes`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Scale {
+    Linear,
+    Log10
+}
+
+
+/// Options for `axes`.
+/// Use `ascii` to draw with `|`, `-` and `+` instead of box-drawing characters.
+#[derive(Clone, Copy, Debug)]
+pub struct AxisOptions {
+    pub ascii: bool,
+    pub x_scale: Scale,
+    pub y_scale: Scale
+}
+
+impl Default for AxisOptions {
+    fn default() -> AxisOptions {
+        AxisOptions { ascii: false, x_scale: Scale::Linear, y_scale: Scale::Linear }
+    }
+}
+
+
+/// Maps data coordinates to cells inside the plotting area `plot` of a set of axes.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Transform {
+    pub plot: Rect,
+    pub x_range: (f64, f64),
+    pub y_range: (f64, f64),
+    pub x_scale: Scale,
+    pub y_scale: Scale
+}
+
+impl Transform {
+    /// Get the cell that `(x, y)` falls in, or `None` if it is outside the axis ranges or not finite.
+    ///
+    /// Example
+    /// ```
+    /// use terminalgl::chart::{Scale, Transform};
+    /// use terminalgl::Rect;
+    /// let t = Transform { plot: Rect::new(5, 0, 11, 6), x_range: (0.0, 10.0), y_range: (1.0, 1e5), x_scale: Scale::Linear, y_scale: Scale::Log10 };
+    /// assert_eq!(t.to_cell(0.0, 1.0), Some((5, 5)));
+    /// assert_eq!(t.to_cell(10.0, 1e5), Some((15, 0)));
+    /// assert_eq!(t.to_cell(5.0, 1e3), Some((10, 2)));
+    /// assert_eq!(t.to_cell(11.0, 1.0), None);
+    /// ```
+    pub fn to_cell(&self, x: f64, y: f64) -> Option<(isize, isize)> {
+        let fx = scale_fraction(x, self.x_range, self.x_scale)?;
+        let fy = scale_fraction(y, self.y_range, self.y_scale)?;
+        let cx = (fx * (self.plot.width as f64 - 1.0)).round() as isize;
+        let cy = ((1.0 - fy) * (self.plot.height as f64 - 1.0)).round() as isize;
+        self.plot.translate(cx, cy)
+    }
+}
+
+
+/// Get how far `v` is along `range` on a `scale` axis, from `0.0` to `1.0`.
+fn scale_fraction(v: f64, range: (f64, f64), scale: Scale) -> Option<f64> {
+    let (v, lo, hi) = match scale {
+        Scale::Linear => (v, range.0, range.1),
+        Scale::Log10 => (v.log10(), range.0.log10(), range.1.log10())
+    };
+    let f = if hi == lo { 0.5 } else { (v - lo) / (hi - lo) };
+    if f.is_finite() && (0.0..=1.0).contains(&f) { Some(f) } else { None }
+}
+
+
+/// Get evenly spaced ticks inside `lo..=hi` at a "nice" interval (1, 2, or 5 times a power of 10),
+/// with at most about `max_ticks` ticks.
+///
+/// Example
+/// ```
+/// use terminalgl::chart;
+/// assert_eq!(chart::nice_ticks(0.0, 10.0, 6), vec![0.0, 2.0, 4.0, 6.0, 8.0, 10.0]);
+/// assert_eq!(chart::nice_ticks(-0.3, 0.35, 4), vec![-0.2, 0.0, 0.2]);
+/// assert_eq!(chart::nice_ticks(3.0, 3.0, 5), vec![3.0]);
+/// ```
+pub fn nice_ticks(lo: f64, hi: f64, max_ticks: usize) -> Vec<f64> {
+    let step = nice_step(lo, hi, max_ticks);
+    if step == 0.0 {
+        return if lo.is_finite() { vec![lo] } else { Vec::new() };
+    }
+    let first = (lo / step).ceil() as i64;
+    let last = (hi / step + 1e-9).floor() as i64;
+    (first..=last).map(|i| {
+        let v = i as f64 * step;
+        // Round away floating point noise such as 0.30000000000000004
+        let digits = (-step.log10().floor()).max(0.0) as i32 + 1;
+        let p = 10f64.powi(digits);
+        (v * p).round() / p
+    }).collect()
+}
+
+
+fn nice_step(lo: f64, hi: f64, max_ticks: usize) -> f64 {
+    let range = hi - lo;
+    if !range.is_finite() || range <= 0.0 {
+        return 0.0;
+    }
+    let raw = range / max_ticks.max(1) as f64;
+    let mag = 10f64.powf(raw.log10().floor());
+    let norm = raw / mag;
+    let nice = if norm <= 1.0 { 1.0 } else if norm <= 2.0 { 2.0 } else if norm <= 5.0 { 5.0 } else { 10.0 };
+    nice * mag
+}
+
+
+/// Get the ticks of a `scale` axis spanning `range` with room for about `max_ticks` ticks.
+fn axis_ticks(range: (f64, f64), scale: Scale, max_ticks: usize) -> Vec<f64> {
+    match scale {
+        Scale::Linear => nice_ticks(range.0, range.1, max_ticks),
+        Scale::Log10 => {
+            let (lo, hi) = (range.0.log10().ceil() as i32, range.1.log10().floor() as i32);
+            let every = ((hi - lo + 1) as usize).div_ceil(max_ticks.max(1)).max(1);
+            (lo..=hi).step_by(every).map(|e| 10f64.powi(e)).collect()
+        }
+    }
+}
+
+
+/// Format the tick labels `ticks` with just enough decimals to tell them apart, or with SI prefixes if that would take more than `MAX_TICK_WIDTH` columns.
+fn tick_labels(ticks: &[f64], scale: Scale) -> Vec<String> {
+    if scale == Scale::Log10 {
+        return ticks.iter().map(|t| format_tick(*t)).collect();
+    }
+    let step = if ticks.len() > 1 { ticks[1] - ticks[0] } else { 1.0 };
+    let decimals = (-step.log10().floor()).max(0.0) as usize;
+    let labels: Vec<String> = ticks.iter().map(|t| format!("{:.*}", decimals, t)).collect();
+    match labels.iter().any(|label| label.chars().count() > MAX_TICK_WIDTH) {
+        true => ticks.iter().map(|t| fmt::si(*t)).collect(),
+        false => labels
+    }
+}
+
+
+/// Lay out axes spanning `x_range` and `y_range` inside `rect` as `(x, y, c)` cells.
+/// The left axis has tick labels to its left, and the bottom axis has tick labels below it.
+/// The axis lines run along the left column and bottom row of the plotting area, so the lowest values land on them.
+/// Ticks are placed at "nice" intervals with labels that never overlap.
+/// Also returns the transform from data coordinates to cells inside the plotting area.
+///
+/// Example
+/// ```
+/// use terminalgl::chart::{self, AxisOptions};
+/// use terminalgl::Rect;
+/// let (cells, transform) = chart::axes_cells(Rect::new(0, 0, 20, 7), (0.0, 100.0), (0.0, 1.0), AxisOptions::default());
+/// assert_eq!(transform.plot, Rect::new(3, 0, 17, 6));
+/// let mut rows = vec![vec![' '; 20]; 7];
+/// for (x, y, c) in cells {
+///     rows[y as usize][x as usize] = c;
+/// }
+/// let rows: Vec<String> = rows.into_iter().map(|r| r.into_iter().collect()).collect();
+/// assert_eq!(rows, vec![
+///     "1.0┤                ",
+///     "   │                ",
+///     "   │                ",
+///     "0.5┤                ",
+///     "   │                ",
+///     "0.0┼───────┬───────┬",
+///     "   0      50     100",
+/// ]);
+///
+/// // Labels too wide for the axis are shortened with SI prefixes.
+/// let (cells, _) = chart::axes_cells(Rect::new(0, 0, 24, 5), (0.0, 3e9), (0.0, 2e6), AxisOptions::default());
+/// let mut rows = vec![vec![' '; 24]; 5];
+/// for (x, y, c) in cells {
+///     rows[y as usize][x as usize] = c;
+/// }
+/// let rows: Vec<String> = rows.into_iter().map(|r| r.into_iter().collect()).collect();
+/// assert_eq!(rows[3], " 0┼──────┬──────┬──────┬");
+/// assert_eq!(rows[4], "  0     1G     2G     3G");
+/// assert_eq!(rows[0], "2M┤                     ");
+///
+/// let far = Rect::new(isize::MAX - 10, 0, 20, 7);
+/// let (cells, transform) = chart::axes_cells(far, (0.0, 100.0), (0.0, 1.0), AxisOptions::default());
+/// assert!(cells.iter().all(|&(x, _, _)| x >= isize::MAX - 10));
+/// assert_eq!(transform.to_cell(100.0, 0.0), None);
+/// ```
+pub fn axes_cells(rect: Rect, x_range: (f64, f64), y_range: (f64, f64), opts: AxisOptions) -> (Vec<(isize, isize, char)>, Transform) {
+    // Laid out at the origin and moved into place, so cells past the limits of `isize` are dropped instead of overflowing.
+    let (cells, transform) = axes_layout(Rect::new(0, 0, rect.width, rect.height), x_range, y_range, opts);
+    let plot = Rect { x: rect.x.saturating_add(transform.plot.x), y: rect.y.saturating_add(transform.plot.y), ..transform.plot };
+    let cells = cells.into_iter().filter_map(|(x, y, c)| rect.translate(x, y).map(|(x, y)| (x, y, c))).collect();
+    (cells, Transform { plot, ..transform })
+}
+
+
+fn axes_layout(rect: Rect, x_range: (f64, f64), y_range: (f64, f64), opts: AxisOptions) -> (Vec<(isize, isize, char)>, Transform) {
+    let (vertical, horizontal, y_tick, x_tick, origin) = if opts.ascii {
+        ('|', '-', '+', '+', '+')
+    } else {
+        ('│', '─', '┤', '┬', '┼')
+    };
+    let mut cells = Vec::new();
+    let plot_h = rect.height.saturating_sub(1);
+    let y_ticks = axis_ticks(y_range, opts.y_scale, (plot_h / 2).max(2));
+    let y_labels = tick_labels(&y_ticks, opts.y_scale);
+    let label_w = y_labels.iter().map(|l| l.chars().count()).max().unwrap_or(0);
+    let plot = Rect::new(rect.x + label_w as isize, rect.y, rect.width.saturating_sub(label_w), plot_h);
+    let transform = Transform { plot, x_range, y_range, x_scale: opts.x_scale, y_scale: opts.y_scale };
+    if plot.width == 0 || plot.height == 0 {
+        return (cells, transform);
+    }
+
+    // The y axis sits in the plot's first column, and the x axis in the row below it.
+    let axis_x = plot.x;
+    let axis_y = plot.y + plot.height as isize - 1;
+    for y in plot.y..=axis_y {
+        cells.push((axis_x, y, vertical));
+    }
+    for x in axis_x+1..plot.x + plot.width as isize {
+        cells.push((x, axis_y, horizontal));
+    }
+    let mut last_label_row = isize::MAX;
+    for (tick, label) in y_ticks.iter().zip(y_labels.iter()).rev() {
+        if let Some((_, y)) = transform.to_cell(x_range.0, *tick) {
+            if y == last_label_row {
+                continue;
+            }
+            last_label_row = y;
+            if y != axis_y {
+                cells.push((axis_x, y, y_tick));
+            }
+            let start = axis_x - label.chars().count() as isize;
+            cells.extend(label.chars().enumerate().map(|(i, c)| (start + i as isize, y, c)));
+        }
+    }
+
+    let x_max_ticks = (plot.width / 6).max(2);
+    let x_ticks = axis_ticks(x_range, opts.x_scale, x_max_ticks);
+    let x_labels = tick_labels(&x_ticks, opts.x_scale);
+    let right = rect.x + rect.width as isize;
+    let mut free_from = rect.x;
+    for (tick, label) in x_ticks.iter().zip(x_labels.iter()) {
+        if let Some((x, _)) = transform.to_cell(*tick, y_range.0) {
+            if x != axis_x {
+                cells.push((x, axis_y, x_tick));
+            }
+            let len = label.chars().count() as isize;
+            let start = (x - len / 2).min(right - len);
+            if start >= free_from {
+                cells.extend(label.chars().enumerate().map(|(i, c)| (start + i as isize, axis_y + 1, c)));
+                free_from = start + len + 1;
+            }
+        }
+    }
+    cells.push((axis_x, axis_y, origin));
+    (cells, transform)
+}
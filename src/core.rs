@@ -0,0 +1,285 @@
+// Geometry that needs neither std nor an allocator, for drawing into fixed buffers.
+use super::{Direction, Rect};
+
+
+/// A grid of characters that the rasterizers in this module can draw into, such as a character LCD's frame buffer.
+/// Implemented for `[[char; W]; H]` arrays, indexed as `buffer[y][x]`.
+pub trait Buffer {
+    /// Get the rectangle covering the whole buffer.
+    fn rect(&self) -> Rect;
+
+    /// Set the cell at `(x, y)` to `c`. Cells outside the buffer should be ignored.
+    fn set(&mut self, x: isize, y: isize, c: char);
+}
+
+impl<const W: usize, const H: usize> Buffer for [[char; W]; H] {
+    fn rect(&self) -> Rect {
+        Rect::new(0, 0, W, H)
+    }
+
+    fn set(&mut self, x: isize, y: isize, c: char) {
+        if x >= 0 && y >= 0 && (x as usize) < W && (y as usize) < H {
+            self[y as usize][x as usize] = c;
+        }
+    }
+}
+
+
+/// Set every cell in `cells` to `c` in `buffer`.
+///
+/// Example
+/// ```
+/// use terminalgl::core::{self, Buffer};
+/// let mut lcd = [[' '; 16]; 2];
+/// let clip = lcd.rect();
+/// core::plot(&mut lcd, core::line_clipped(0, 0, 15, 1, clip), '*');
+/// core::plot(&mut lcd, core::rectangle_clipped(-1, 0, 3, 9, false, clip), '#');
+/// assert_eq!(lcd[0].iter().collect::<String>(), "##******        ");
+/// assert_eq!(lcd[1].iter().collect::<String>(), " #      ********");
+/// ```
+pub fn plot(buffer: &mut impl Buffer, cells: impl IntoIterator<Item = (isize, isize)>, c: char) {
+    for (x, y) in cells {
+        buffer.set(x, y, c);
+    }
+}
+
+
+/// Iterator over the cells of a rectangle, from `rectangle` and `rectangle_clipped`.
+#[derive(Clone, Debug)]
+pub struct Rectangle {
+    left: i128,
+    right: i128,
+    top: i128,
+    bottom: i128,
+    fill: bool,
+    first_col: i128,
+    last_col: i128,
+    last_row: i128,
+    col: i128,
+    row: i128
+}
+
+impl Iterator for Rectangle {
+    type Item = (isize, isize);
+
+    fn next(&mut self) -> Option<(isize, isize)> {
+        while self.row <= self.last_row && self.first_col <= self.last_col {
+            if self.col > self.last_col {
+                self.row += 1;
+                self.col = self.first_col;
+                continue;
+            }
+            let col = self.col;
+            self.col += 1;
+            if self.fill || self.row == self.top || self.row == self.bottom || col == self.left || col == self.right {
+                return Some((col as isize, self.row as isize));
+            }
+            // Jump over the inside of an outline row.
+            self.col = self.col.max(self.right);
+        }
+        None
+    }
+}
+
+
+/// Iterate over the cells of a rectangle like `raster::rectangle`.
+pub fn rectangle(x: isize, y: isize, width: usize, height: usize, fill: bool) -> Rectangle {
+    rectangle_clipped(x, y, width, height, fill, Rect::new(x, y, width, height))
+}
+
+
+/// Iterate over the cells of a rectangle like `raster::rectangle_clipped`, visiting only the clipped area.
+pub fn rectangle_clipped(x: isize, y: isize, width: usize, height: usize, fill: bool, clip: Rect) -> Rectangle {
+    let (left, top) = (x as i128, y as i128);
+    let (right, bottom) = (left + width as i128 - 1, top + height as i128 - 1);
+    let (cx0, cy0, cx1, cy1) = clip_bounds(clip);
+    let (row, last_row) = if width == 0 || height == 0 { (1, 0) } else { (top.max(cy0), bottom.min(cy1)) };
+    let (first_col, last_col) = (left.max(cx0), right.min(cx1));
+    Rectangle { left, right, top, bottom, fill, first_col, last_col, last_row, col: first_col, row }
+}
+
+
+/// Get the first and last columns and rows of `clip` as `(x0, y0, x1, y1)`, leaving out any past `isize::MAX`.
+/// An empty `clip` gives `x1 < x0` or `y1 < y0`.
+pub(crate) fn clip_bounds(clip: Rect) -> (i128, i128, i128, i128) {
+    let (x0, y0) = (clip.x as i128, clip.y as i128);
+    let last = |start: i128, len: usize| (start + len as i128 - 1).min(isize::MAX as i128);
+    (x0, y0, last(x0, clip.width), last(y0, clip.height))
+}
+
+
+/// Iterator over the cells of a straight line, from `straight_line_clipped`.
+#[derive(Clone, Debug)]
+pub struct StraightLine {
+    x: i128,
+    y: i128,
+    dx: i128,
+    dy: i128,
+    t: i128,
+    last: i128
+}
+
+impl Iterator for StraightLine {
+    type Item = (isize, isize);
+
+    fn next(&mut self) -> Option<(isize, isize)> {
+        if self.t > self.last {
+            return None;
+        }
+        let t = self.t;
+        self.t += 1;
+        Some(((self.x + t * self.dx) as isize, (self.y + t * self.dy) as isize))
+    }
+}
+
+
+/// Iterate over the cells of a straight line like `raster::straight_line_clipped`, visiting only the clipped part.
+pub fn straight_line_clipped(x: isize, y: isize, length: isize, dir: Direction, clip: Rect) -> StraightLine {
+    let (dx, dy): (i128, i128) = match dir {
+        Direction::Left => (-1, 0),
+        Direction::Right => (1, 0),
+        Direction::Up => (0, -1),
+        Direction::Down => (0, 1)
+    };
+    let (dx, dy) = if length < 0 { (-dx, -dy) } else { (dx, dy) };
+    let (x, y) = (x as i128, y as i128);
+    let (cx0, cy0, cx1, cy1) = clip_bounds(clip);
+
+    // Steps along the line whose cells land inside `clip`.
+    let (mut lo, mut hi) = (0, length.unsigned_abs() as i128 - 1);
+    for (start, d, min, max) in [(x, dx, cx0, cx1), (y, dy, cy0, cy1)] {
+        match d {
+            0 if start < min || start > max => (lo, hi) = (0, -1),
+            0 => {},
+            1 => (lo, hi) = (lo.max(min - start), hi.min(max - start)),
+            _ => (lo, hi) = (lo.max(start - max), hi.min(start - min))
+        }
+    }
+    StraightLine { x, y, dx, dy, t: lo, last: hi }
+}
+
+
+/// Iterator over the cells of a line, from `line` and `line_clipped`.
+#[derive(Clone, Debug)]
+pub struct Line {
+    x1: isize,
+    y1: isize,
+    dx: i128,
+    dy: i128,
+    steps: i128,
+    t: i128,
+    last: i128,
+    clip: Option<Rect>
+}
+
+impl Iterator for Line {
+    type Item = (isize, isize);
+
+    fn next(&mut self) -> Option<(isize, isize)> {
+        while self.t <= self.last {
+            let cell = line_cell(self.x1, self.y1, self.dx, self.dy, self.steps, self.t);
+            self.t += 1;
+            if self.clip.is_none_or(|clip| clip.contains(cell.0, cell.1)) {
+                return Some(cell);
+            }
+        }
+        None
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let left = usize::try_from((self.last - self.t + 1).max(0)).ok();
+        (if self.clip.is_none() { left.unwrap_or(usize::MAX) } else { 0 }, left)
+    }
+}
+
+
+/// Iterate over the cells of a line like `raster::line`.
+///
+/// Example
+/// ```
+/// use terminalgl::core;
+/// let mut cells = core::line(0, 0, 2, 1);
+/// assert_eq!(cells.next(), Some((0, 0)));
+/// assert_eq!(cells.next(), Some((1, 1)));
+/// assert_eq!(cells.next(), Some((2, 1)));
+/// assert_eq!(cells.next(), None);
+/// ```
+pub fn line(x1: isize, y1: isize, x2: isize, y2: isize) -> Line {
+    let (dx, dy, steps) = line_steps(x1, y1, x2, y2);
+    Line { x1, y1, dx, dy, steps, t: 0, last: steps, clip: None }
+}
+
+
+/// Iterate over the cells of a line like `raster::line_clipped`, visiting only the part of the line inside `clip`.
+/// The line is clipped to `clip` before any cells are stepped through, so every step lands inside it.
+///
+/// Example
+/// ```
+/// use terminalgl::{core, Rect};
+/// let cells = core::line_clipped(-10000, -10000, 10000, 10000, Rect::new(0, 0, 80, 24));
+/// assert_eq!(cells.size_hint(), (0, Some(24)));
+/// assert!(core::line_clipped(-10000, -3, 10000, -2, Rect::new(0, 0, 80, 24)).next().is_none());
+/// ```
+pub fn line_clipped(x1: isize, y1: isize, x2: isize, y2: isize, clip: Rect) -> Line {
+    let (dx, dy, steps) = line_steps(x1, y1, x2, y2);
+    let (mut lo, mut hi) = (0, steps);
+    let (cx0, cy0, cx1, cy1) = clip_bounds(clip);
+    for (start, d, min, max) in [(x1 as i128, dx, cx0, cx1), (y1 as i128, dy, cy0, cy1)] {
+        let (min, max) = (min - start, max - start);
+        if d == 0 {
+            if min > 0 || max < 0 {
+                (lo, hi) = (0, -1);
+            }
+            continue;
+        }
+        // The offset `mul_div_round(t, d, steps)` only moves one way as `t` grows, so the steps landing in
+        // `min..=max` on this axis are one run, found by searching for its ends.
+        let offset = |t| mul_div_round(t, d, steps);
+        let (first, past) = if d > 0 {
+            (first_step(steps, |t| offset(t) >= min), first_step(steps, |t| offset(t) > max))
+        }
+        else {
+            (first_step(steps, |t| offset(t) <= max), first_step(steps, |t| offset(t) < min))
+        };
+        lo = lo.max(first);
+        hi = hi.min(past - 1);
+    }
+    Line { x1, y1, dx, dy, steps, t: lo, last: hi, clip: Some(clip) }
+}
+
+
+/// Get the first step in `0..=steps` where `pred` holds, or `steps + 1` if there is none.
+/// `pred` must stay true once it holds.
+fn first_step(steps: i128, pred: impl Fn(i128) -> bool) -> i128 {
+    let (mut lo, mut hi) = (0, steps + 1);
+    while lo < hi {
+        let mid = lo + (hi - lo) / 2;
+        if pred(mid) { hi = mid } else { lo = mid + 1 }
+    }
+    lo
+}
+
+
+/// Get the offsets `(dx, dy)` from `(x1, y1)` to `(x2, y2)` and the number of steps along the longer axis.
+fn line_steps(x1: isize, y1: isize, x2: isize, y2: isize) -> (i128, i128, i128) {
+    let (dx, dy) = (x2 as i128 - x1 as i128, y2 as i128 - y1 as i128);
+    (dx, dy, dx.abs().max(dy.abs()))
+}
+
+
+/// Get the cell `t` steps along a line from `(x1, y1)` with offsets `(dx, dy)` covered in `steps` steps.
+fn line_cell(x1: isize, y1: isize, dx: i128, dy: i128, steps: i128, t: i128) -> (isize, isize) {
+    if steps == 0 {
+        return (x1, y1);
+    }
+    ((x1 as i128 + mul_div_round(t, dx, steps)) as isize, (y1 as i128 + mul_div_round(t, dy, steps)) as isize)
+}
+
+
+/// Get `a * b / c` rounded to the nearest integer (halves away from zero), saturating instead of overflowing.
+/// Exact as long as `a` and `b` are within `u64::MAX` of zero.
+fn mul_div_round(a: i128, b: i128, c: i128) -> i128 {
+    let (p, c_abs) = (a.unsigned_abs().saturating_mul(b.unsigned_abs()), c.unsigned_abs());
+    let q = i128::try_from(p / c_abs + u128::from(p % c_abs >= c_abs - c_abs / 2)).unwrap_or(i128::MAX);
+    if (a < 0) ^ (b < 0) ^ (c < 0) { -q } else { q }
+}
@@ -0,0 +1,183 @@
+// Development aids for finding out exactly what was drawn into a cell.
+use super::canvas::Canvas;
+use super::drawc;
+use super::ui::HitGrid;
+#[cfg(all(feature = "input", unix))]
+use super::input::{self, Event, Key, Mouse, RawMode};
+use std::fmt;
+#[cfg(all(feature = "input", unix))]
+use std::io;
+#[cfg(all(feature = "input", unix))]
+use std::time::Duration;
+
+
+/// Reverse video, for the cursor.
+const REVERSE: &str = "\x1b[7m";
+
+
+/// Describe a color from `drawc::ccode_to_rgb` as its rgb triplet and the named color nearest to it.
+fn describe_color(rgb: Option<(u8, u8, u8)>) -> String {
+    match rgb {
+        Some((r, g, b)) => format!("{},{},{} ({})", r, g, b, drawc::rgb_to_named((r, g, b)).name()),
+        None => "default".to_string()
+    }
+}
+
+
+/// Describe cell `(x, y)` of `canvas` in one line: its position, its character and code point, and its foreground
+/// and background colors as rgb and the nearest named color. Colors the cell doesn't set, or sets with a 256 color index,
+/// are `default`. `None` if the cell is off the canvas.
+///
+/// Example
+/// ```
+/// use terminalgl::debug;
+/// use terminalgl::canvas::Canvas;
+/// use terminalgl::drawc::{self, ColorKind};
+/// let mut canvas = Canvas::new(4, 2);
+/// canvas.set(1, 0, 'é', &(drawc::rgb_to_ccode((250, 10, 10), ColorKind::Fg) + drawc::BLUE_BG));
+/// assert_eq!(debug::describe_cell(&canvas, 1, 0).unwrap(), "(1, 0) 'é' U+00E9  fg 250,10,10 (bright red)  bg 0,0,238 (blue)");
+/// assert_eq!(debug::describe_cell(&canvas, 0, 1).unwrap(), "(0, 1) ' ' U+0020  fg default  bg default");
+/// assert_eq!(debug::describe_cell(&canvas, 4, 0), None);
+/// ```
+pub fn describe_cell(canvas: &Canvas, x: isize, y: isize) -> Option<String> {
+    let cell = canvas.get(x, y)?;
+    let (fg, bg) = drawc::ccode_to_rgb(canvas.ccode_at(x, y).unwrap_or(""));
+    Some(format!("({}, {}) {:?} U+{:04X}  fg {}  bg {}", x, y, cell.c, cell.c as u32, describe_color(fg), describe_color(bg)))
+}
+
+
+/// The cursor of `inspect`, moved over a canvas by arrow keys and the mouse. Use it directly to inspect a canvas inside
+/// an application's own loop: pass it the events, and present the frame it draws instead of the canvas.
+///
+/// Example
+/// ```
+/// # #[cfg(all(feature = "input", unix))] {
+/// use terminalgl::debug::Inspector;
+/// use terminalgl::input::{Event, Key, Mouse, MouseKind};
+/// use terminalgl::canvas::Canvas;
+/// use terminalgl::ui::HitGrid;
+/// use terminalgl::Rect;
+/// let mut canvas = Canvas::new(30, 4);
+/// canvas.text(0, 0, "ok", "");
+/// let mut hits = HitGrid::new();
+/// hits.add_rect(Rect::new(0, 0, 2, 1), "ok button");
+///
+/// let mut inspector = Inspector::new(&canvas);
+/// assert_eq!((inspector.x, inspector.y), (15, 2));
+/// assert!(inspector.handle_event(&Event::Mouse(Mouse { kind: MouseKind::Move, x: 0, y: 0 }), &canvas));
+/// assert!(inspector.handle_event(&Event::Key(Key::Right), &canvas));
+/// assert_eq!(inspector.status(&canvas, &hits), "(1, 0) 'k' U+006B  fg default  bg default  region \"ok button\"");
+///
+/// let frame = inspector.frame(&canvas, &hits);
+/// assert_eq!(frame.ccode_at(1, 0), Some("\x1b[7m"));
+/// assert!(frame.get(0, 3).unwrap().c == '(');
+///
+/// // The cursor stays on the canvas, and Esc ends the inspection.
+/// assert!(inspector.handle_event(&Event::Key(Key::Up), &canvas));
+/// assert_eq!((inspector.x, inspector.y), (1, 0));
+/// assert!(!inspector.handle_event(&Event::Key(Key::Esc), &canvas));
+/// # }
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Inspector {
+    pub x: isize,
+    pub y: isize
+}
+
+impl Inspector {
+    /// Create an inspector with its cursor in the middle of `canvas`.
+    pub fn new(canvas: &Canvas) -> Inspector {
+        Inspector { x: canvas.width() as isize / 2, y: canvas.height() as isize / 2 }
+    }
+
+    /// Apply `event`, moving the cursor over `canvas` with the arrow keys or to wherever the mouse is,
+    /// and get whether to keep inspecting, which is until Esc or Ctrl+C.
+    #[cfg(all(feature = "input", unix))]
+    pub fn handle_event(&mut self, event: &Event, canvas: &Canvas) -> bool {
+        let (x, y) = match *event {
+            Event::Key(Key::Esc | Key::Ctrl('c')) | Event::Interrupt => return false,
+            Event::Key(Key::Up) => (self.x, self.y - 1),
+            Event::Key(Key::Down) => (self.x, self.y + 1),
+            Event::Key(Key::Left) => (self.x - 1, self.y),
+            Event::Key(Key::Right) => (self.x + 1, self.y),
+            Event::Mouse(Mouse { x, y, .. }) => (x, y),
+            _ => return true
+        };
+        if canvas.rect().contains(x, y) {
+            (self.x, self.y) = (x, y);
+        }
+        true
+    }
+
+    /// Get the status line for the cell under the cursor: its `describe_cell` description,
+    /// and the id of the region of `hits` on top there, if any.
+    pub fn status<Id: Copy + PartialEq + fmt::Debug>(&self, canvas: &Canvas, hits: &HitGrid<Id>) -> String {
+        let mut status = describe_cell(canvas, self.x, self.y).unwrap_or_default();
+        if let Some(id) = hits.hit_test(self.x, self.y) {
+            status += &format!("  region {:?}", id);
+        }
+        status
+    }
+
+    /// Get a copy of `canvas` with the cell under the cursor in reverse video and the status line across the bottom row,
+    /// or the top row while the cursor is on the bottom one.
+    pub fn frame<Id: Copy + PartialEq + fmt::Debug>(&self, canvas: &Canvas, hits: &HitGrid<Id>) -> Canvas {
+        let mut frame = canvas.clone();
+        if let Some(cell) = canvas.get(self.x, self.y) {
+            let ccode = canvas.ccode_at(self.x, self.y).unwrap_or("").to_string() + REVERSE;
+            frame.set(self.x, self.y, cell.c, &ccode);
+        }
+        let row = if self.y == canvas.height() as isize - 1 { 0 } else { canvas.height() as isize - 1 };
+        let status = format!("{}  (arrows or mouse to move, Esc to exit)", self.status(canvas, hits));
+        frame.rectangle(0, row, canvas.width(), 1, ' ', drawc::BLACK_BG, true);
+        frame.text(0, row, &status, &(drawc::BRIGHT_WHITE.to_string() + drawc::BLACK_BG));
+        frame
+    }
+}
+
+
+/// Show `canvas` on the terminal with a cursor to point at cells, and a status line describing the one under it
+/// (see `describe_cell`), until Esc is pressed. Then `canvas` is shown again as it was.
+/// Works from inside an application that already has the terminal in raw mode, and otherwise turns it on until done.
+///
+/// Example
+/// ```no_run
+/// use terminalgl::{canvas::Canvas, debug};
+/// let mut canvas = Canvas::screen();
+/// canvas.text(2, 1, "something looks off here", "");
+/// debug::inspect(&canvas).unwrap();
+/// ```
+#[cfg(all(feature = "input", unix))]
+pub fn inspect(canvas: &Canvas) -> io::Result<()> {
+    inspect_with_hits::<()>(canvas, &HitGrid::new())
+}
+
+
+/// Inspect `canvas` like `inspect`, also showing the id of the region of `hits` under the cursor.
+#[cfg(all(feature = "input", unix))]
+pub fn inspect_with_hits<Id: Copy + PartialEq + fmt::Debug>(canvas: &Canvas, hits: &HitGrid<Id>) -> io::Result<()> {
+    let raw = match RawMode::with_mouse_motion() {
+        Ok(raw) => Some(raw),
+        Err(err) if err.kind() == io::ErrorKind::AlreadyExists => None,
+        Err(err) => return Err(err)
+    };
+    let mut inspector = Inspector::new(canvas);
+    let mut previous: Option<Canvas> = None;
+    loop {
+        let frame = inspector.frame(canvas, hits);
+        match &previous {
+            Some(previous) => frame.present_diff(previous),
+            None => frame.present()
+        }
+        previous = Some(frame);
+        for event in input::read_events(Duration::from_millis(100))? {
+            if !inspector.handle_event(&event, canvas) {
+                // Raw mode turned on here puts back the screen by leaving the alternate screen; otherwise redraw it.
+                if raw.is_none() {
+                    canvas.present();
+                }
+                return Ok(());
+            }
+        }
+    }
+}
@@ -1,155 +1,208 @@
 // Draw without color.
-use super::{Direction, TextAlignment};
+use super::chart::{self, AxisOptions, Transform};
+use super::{chars, noise, raster, ArrowHead, CalendarOptions, ConnectorStyle, Direction, Rect, TextAlignment, TextDirection, TreeNode, TreeStyle};
 
 
-/// Get euclidean distance between two points.
-fn distance(x1: isize, y1: isize, x2: isize, y2: isize) -> f64 {
-    let x = ((x2-x1).pow(2) + (y2-y1).pow(2)) as f64;
-    x.sqrt()
-}
-
-
-/// Draw character `c` at `(x, y)`.
+/// Draw character `c` at `(x, y)`. Cells off the terminal are skipped, so any coordinates are safe to draw at.
+/// `c` is drawn as `chars::sanitize` gives it, so control characters never reach the terminal.
 /// 
 /// Example
 /// ```
 /// use terminalgl as tgl;
 /// tgl::draw::pixel(1, 1, '#');
+/// tgl::draw::pixel(isize::MIN, isize::MAX, '#');
 /// ```
 pub fn pixel(x: isize, y: isize, c: char) {
-    //let tsize = super::size();
-    if x >= 0 && y >= 0 {
-        super::cursorto(x as usize, y as usize);
-        print!("{}", c);
+    if super::cursorto_checked(x, y).is_ok() {
+        print!("{}", chars::sanitize(c));
+    }
+}
+
+
+/// Draw character `c` at `(x, y)` if it is inside `screen`, for shapes that looked the terminal up once with `screen_rect`.
+fn pixel_in(screen: Rect, x: isize, y: isize, c: char) {
+    if screen.contains(x, y) {
+        pixel_unchecked(x as usize, y as usize, c);
     }
 }
 
 
+/// Draw character `c` at `(x, y)` without checking that it is on the terminal.
+/// Faster than `pixel` since it skips the bounds check, for cells already known to be on the terminal.
+/// 
+/// Example
+/// ```
+/// use terminalgl as tgl;
+/// tgl::draw::pixel_unchecked(1, 1, '#');
+/// ```
+pub fn pixel_unchecked(x: usize, y: usize, c: char) {
+    super::cursorto(x, y);
+    print!("{}", chars::sanitize(c));
+}
+
+
 /// Draw a straight line of `c` starting at `(x, y)` with length `length` in direction `dir`.
+/// A negative `length` goes the opposite way, so `-5` cells `Left` draws 5 cells to the right.
+/// Only cells on the terminal are visited, so even `isize::MIN` or `isize::MAX` lengths finish immediately.
 /// 
 /// Example
 /// ```
 /// use terminalgl as tgl;
 /// use terminalgl::Direction;
 /// tgl::draw::straight_line(1, 2, 5, Direction::Right, '#');
+/// tgl::draw::straight_line(isize::MAX, 2, isize::MIN, Direction::Right, '#');
 /// ```
-pub fn straight_line(mut x: isize, mut y: isize, mut length: isize, dir: Direction, c: char) {
-    let mut addx: isize = 0;
-    let mut addy: isize = 0;
-    match dir {
-        Direction::Left => addx = -1,
-        Direction::Right => addx = 1,
-        Direction::Up => addy = -1,
-        Direction::Down => addy = 1
-    }
-    
-    if length < 0 {
-        length = -length;
-        addx = -addx;
-        addy = -addy;
-    }
-    
-    for _ in 0..length {
-        pixel(x, y, c);
-        x += addx;
-        y += addy;
+pub fn straight_line(x: isize, y: isize, length: isize, dir: Direction, c: char) {
+    let screen = super::screen_rect();
+    for (x, y) in raster::straight_line_clipped(x, y, length, dir, screen) {
+        pixel_in(screen, x, y, c);
     }
 }
 
 
 /// Draw a rectangle of `c` at `(x, y)` with width `width` and height `height`.
 /// Use `fill` to specify whether the rectangle is outlined (`false`) or filled (`true`).
+/// Each cell is drawn once, a width or height of 0 draws nothing, and only cells on the terminal are visited.
 /// 
 /// Example
 /// ```
 /// use terminalgl as tgl;
 /// tgl::draw::rectangle(1, 1, 7, 4, '#', false);
+/// tgl::draw::rectangle(-5, 0, usize::MAX, usize::MAX, '#', false);
 /// ```
 pub fn rectangle(x: isize, y: isize, width: usize, height: usize, c: char, fill: bool) {
-    let w = width as isize;
-    let h = height as isize;
-    if fill {
-        for i in 0..width {
-            straight_line(x+i as isize, y, height as isize, Direction::Down, c);
-        }
-        return;
+    let screen = super::screen_rect();
+    for (x, y) in raster::rectangle_clipped(x, y, width, height, fill, screen) {
+        pixel_in(screen, x, y, c);
     }
-    straight_line(x, y, w, Direction::Right, c);
-    straight_line(x, y+h-1, w, Direction::Right, c);
-    straight_line(x, y+1, h-2, Direction::Down, c);
-    straight_line(x+w-1, y+1, h-2, Direction::Down, c);
 }
 
 
 /// Draw a line of `c` with starting point `(x1, y1)` and ending point (`x2, y2`).
+/// Only cells on the terminal are visited, so lines between far-off points are cheap.
 /// 
 /// Example
 /// ```
 /// use terminalgl as tgl;
 /// tgl::draw::line(1, 1, 6, 3, '#');
+/// tgl::draw::line(isize::MIN, isize::MIN, isize::MAX, isize::MAX, '#');
 /// ```
 pub fn line(x1: isize, y1: isize, x2: isize, y2: isize, c: char) {
-    if x1 == x2 {
-        straight_line(x1, y1, y2-y1, Direction::Down, c);
-    }
-    if y1 == y2 {
-        straight_line(x1, y1, x2-x1, Direction::Right, c);
+    let screen = super::screen_rect();
+    for (x, y) in raster::line_clipped(x1, y1, x2, y2, screen) {
+        pixel_in(screen, x, y, c);
     }
-    
-    let dist = distance(x1, y1, x2, y2);
-    let dx = (x2-x1) as f64 / dist;
-    let dy = (y2-y1) as f64 / dist;
-    let mut x = x1 as f64;
-    let mut y = y1 as f64;
-    for _ in 0..dist.round() as isize {
-        pixel(x.round() as isize, y.round() as isize, c);
-        x += dx;
-        y += dy;
-    }
-    pixel(x2, y2, c);
 }
 
 
 /// Draw an ellipse at `(h, k)` with width `a` and height `b`.
 /// Use `fill` to specify whether the ellipse is outlined (`false`) or filled (`true`).
+/// Each cell is drawn once, the outline is the boundary of the filled ellipse, and only cells on the terminal are visited,
+/// so huge radii are cheap.
 /// 
 /// Example
 /// ```
 /// use terminalgl as tgl;
 /// tgl::draw::ellipse(5, 5, 4, 3, '#', true);
+/// tgl::draw::ellipse(5, 5, usize::MAX / 2, 3, '#', false);
 /// ```
 pub fn ellipse(h: isize, k: isize, a: usize, b: usize, c: char, fill: bool) {
-    for x in 0..a*2+1 {
-        let shiftx: isize = x as isize + h - a as isize;
-        let inside_y = ((a*a) as isize - (shiftx-h).pow(2)).abs() as f64;
-        let y: f64 = (b as f64) / (a as f64) * inside_y.sqrt() + k as f64;
-        if fill {
-            let ydist: isize = 2 * (k - y.round() as isize).abs();
-            straight_line(shiftx as isize, y.round() as isize, ydist+1, Direction::Up, c);
-            continue;
+    let screen = super::screen_rect();
+    for (x, y) in raster::ellipse_clipped(h, k, a, b, fill, screen) {
+        pixel_in(screen, x, y, c);
+    }
+}
+
+
+/// Draw an antialiased line with starting point `(x1, y1)` and ending point (`x2, y2`),
+/// shading each cell with a character from `ramp` (empty to solid, like `raster::AA_RAMP`) by how much the line covers it.
+/// Cells that get a space are left untouched.
+/// 
+/// Example
+/// ```
+/// use terminalgl as tgl;
+/// tgl::draw::line_aa(1, 1, 19, 7, tgl::raster::AA_RAMP);
+/// tgl::draw::line_aa(isize::MIN, isize::MIN, isize::MAX, 7, " ░▒▓█");
+/// ```
+pub fn line_aa(x1: isize, y1: isize, x2: isize, y2: isize, ramp: &str) {
+    let screen = super::screen_rect();
+    shade(screen, raster::line_aa_clipped(x1, y1, x2, y2, screen), ramp);
+}
+
+
+/// Draw the antialiased outline of an ellipse at `(h, k)` with width `a` and height `b`, shaded with `ramp` like `line_aa`.
+/// 
+/// Example
+/// ```
+/// use terminalgl as tgl;
+/// tgl::draw::ellipse_aa(12, 6, 10, 5, tgl::raster::AA_RAMP);
+/// tgl::draw::ellipse_aa(12, 6, usize::MAX / 2, 5, tgl::raster::AA_RAMP);
+/// ```
+pub fn ellipse_aa(h: isize, k: isize, a: usize, b: usize, ramp: &str) {
+    let screen = super::screen_rect();
+    shade(screen, raster::ellipse_aa_clipped(h, k, a, b, screen), ramp);
+}
+
+
+/// Draw the antialiased outline of a circle at `(cx, cy)` with radius `radius`, shaded with `ramp` like `line_aa`.
+/// The circle is twice as wide as it is tall, so it looks round in a terminal.
+/// 
+/// Example
+/// ```
+/// use terminalgl as tgl;
+/// tgl::draw::circle_aa(12, 6, 5, tgl::raster::AA_RAMP);
+/// ```
+pub fn circle_aa(cx: isize, cy: isize, radius: usize, ramp: &str) {
+    ellipse_aa(cx, cy, radius.saturating_mul(2), radius, ramp);
+}
+
+
+/// Draw antialiased `cells` inside `screen` with the characters of `ramp` for their coverage, skipping spaces.
+fn shade(screen: Rect, cells: Vec<(isize, isize, f64)>, ramp: &str) {
+    for (x, y, coverage) in cells {
+        let c = raster::ramp_char(ramp, coverage);
+        if c != ' ' {
+            pixel_in(screen, x, y, c);
         }
-        let ydist: isize = 2 * (k - y.round() as isize);
-        pixel(shiftx, y.round() as isize, c);
-        pixel(shiftx, (y.round() as isize + ydist) as isize, c);
     }
 }
 
 
-/// Draw `text` starting at `(x, y)`.
+/// Draw `text` starting at `(x, y)`, sanitized with `chars::sanitize_text`.
 /// 
 /// Example
 /// ```
 /// use terminalgl as tgl;
 /// tgl::draw::text(1, 1, "sample text");
+/// tgl::draw::text(1, 2, "tab\tbell\x07escape\x1b[2J");
+/// tgl::draw::text(isize::MAX - 3, 1, "sample text");
 /// ```
 pub fn text(x: isize, y: isize, text: &str) {
-    for (i, c) in text.chars().enumerate() {
-        pixel(x+i as isize, y, c);
+    for (i, c) in chars::sanitize_text(text).enumerate() {
+        pixel(x.saturating_add(i as isize), y, c);
     }
 }
 
 
-/// Draw `text` starting at `(x, y)` with alignment `align`.
+/// Print `text` starting at `(x, y)` exactly as given, escape sequences and all, when `(x, y)` is on the terminal.
+/// Unlike every other drawing function nothing is sanitized, so this is only for trusted, pre-styled text such as
+/// the output of another program. To style text, draw it with `drawc::spans` instead, and to place pre-styled text
+/// on a canvas, read it into cells with `ansi::parse`.
+/// 
+/// Example
+/// ```
+/// use terminalgl as tgl;
+/// tgl::draw::raw_text(1, 1, "\x1b[1mbold\x1b[0m");
+/// tgl::draw::raw_text(isize::MAX, 1, "off screen");
+/// ```
+pub fn raw_text(x: isize, y: isize, text: &str) {
+    if super::cursorto_checked(x, y).is_ok() {
+        print!("{}", text);
+    }
+}
+
+
+/// Draw `text` starting at `(x, y)` with alignment `align`, sanitized with `chars::sanitize_text`.
 /// 
 /// Examples
 /// ```
@@ -158,15 +211,300 @@ pub fn text(x: isize, y: isize, text: &str) {
 /// tgl::draw::text_aligned(1, 1, "sample text", Left);
 /// tgl::draw::text_aligned(10, 3, "sample text", Center);
 /// tgl::draw::text_aligned(15, 5, "sample text", Right);
+/// tgl::draw::text_aligned(isize::MIN, 5, "sample text", Right);
 /// ```
 pub fn text_aligned(x: isize, y: isize, text: &str, align: TextAlignment) {
+    let text: String = chars::sanitize_text(text).collect();
     let mut x = x;
     match align {
         TextAlignment::Left => {},
-        TextAlignment::Center => x -= text.len() as isize / 2,
-        TextAlignment::Right => x -= text.len() as isize
+        TextAlignment::Center => x = x.saturating_sub(text.chars().count() as isize / 2),
+        TextAlignment::Right => x = x.saturating_sub(text.chars().count() as isize)
     }
     for (i, c) in text.chars().enumerate() {
-        pixel(x+i as isize, y, c);
+        pixel(x.saturating_add(i as isize), y, c);
+    }
+}
+
+
+/// Draw `text` read in `direction` at `(x, y)` with alignment `align`, laid out with `raster::text_directed`,
+/// so right-to-left text reads correctly and its alignment is mirrored.
+///
+/// Example
+/// ```
+/// use terminalgl as tgl;
+/// use tgl::{TextAlignment, TextDirection};
+/// tgl::draw::text_directed(20, 1, "שלום", TextAlignment::Left, TextDirection::Rtl);
+/// tgl::draw::text_directed(1, 2, "sample text", TextAlignment::Left, TextDirection::Ltr);
+/// tgl::draw::text_directed(isize::MIN, 3, "مرحبا", TextAlignment::Center, TextDirection::Rtl);
+/// ```
+pub fn text_directed(x: isize, y: isize, text: &str, align: TextAlignment, direction: TextDirection) {
+    for (x, y, c) in raster::text_directed(x, y, text, align, direction) {
+        pixel(x, y, c);
+    }
+}
+
+
+/// Draw a polygon of `c` with vertices `points`, connecting the last vertex back to the first.
+/// Use `fill` to specify whether the polygon is outlined (`false`) or filled (`true`).
+/// 
+/// Example
+/// ```
+/// use terminalgl as tgl;
+/// tgl::draw::polygon(&[(1, 1), (8, 2), (4, 6)], '#', false);
+/// tgl::draw::polygon(&[(isize::MIN, 0), (isize::MAX, 2), (0, isize::MAX)], '#', true);
+/// ```
+pub fn polygon(points: &[(isize, isize)], c: char, fill: bool) {
+    if fill {
+        let screen = super::screen_rect();
+        for (y, x1, x2) in raster::polygon_spans_clipped(points, screen) {
+            for x in x1..=x2 {
+                pixel_in(screen, x, y, c);
+            }
+        }
+    }
+    for i in 0..points.len() {
+        let (x1, y1) = points[i];
+        let (x2, y2) = points[(i+1) % points.len()];
+        line(x1, y1, x2, y2, c);
+    }
+}
+
+
+/// Draw a regular polygon of `c` centered at `(cx, cy)` with `sides` sides and radius `radius`.
+/// `rotation` is in radians, `sides` is clamped to a minimum of 3, and vertical distances are scaled by `aspect`
+/// (see `raster::regular_polygon_points`), so `0.5` looks regular in most terminal fonts.
+/// Use `fill` to specify whether the polygon is outlined (`false`) or filled (`true`).
+/// 
+/// Example
+/// ```
+/// use terminalgl as tgl;
+/// tgl::draw::regular_polygon(8, 8, 6, 5, 0.0, 0.5, '#', false);
+/// ```
+pub fn regular_polygon(cx: isize, cy: isize, radius: usize, sides: usize, rotation: f64, aspect: f64, c: char, fill: bool) {
+    polygon(&raster::regular_polygon_points(cx, cy, radius, sides, rotation, aspect), c, fill);
+}
+
+
+/// Draw a star of `c` centered at `(cx, cy)` with `points` points.
+/// The tips lie on `outer_radius` and the inner corners on `inner_radius`.
+/// `rotation` is in radians, `points` is clamped to a minimum of 3, and vertical distances are scaled by `aspect`.
+/// Use `fill` to specify whether the star is outlined (`false`) or filled (`true`).
+/// 
+/// Example
+/// ```
+/// use terminalgl as tgl;
+/// tgl::draw::star(10, 8, 7, 3, 5, 0.0, 0.5, '*', true);
+/// ```
+pub fn star(cx: isize, cy: isize, outer_radius: usize, inner_radius: usize, points: usize, rotation: f64, aspect: f64, c: char, fill: bool) {
+    polygon(&raster::star_points(cx, cy, outer_radius, inner_radius, points, rotation, aspect), c, fill);
+}
+
+
+/// Draw an arrow of `c` from `(x1, y1)` to `(x2, y2)` with an arrowhead of style `head` at `(x2, y2)`.
+/// A zero-length arrow draws just the head.
+/// 
+/// Examples
+/// ```
+/// use terminalgl as tgl;
+/// use tgl::ArrowHead;
+/// tgl::draw::arrow(1, 1, 12, 1, '-', ArrowHead::Char);
+/// tgl::draw::arrow(1, 3, 12, 8, '*', ArrowHead::Chevron);
+/// tgl::draw::arrow(20, 10, 20, 2, '|', ArrowHead::Triangle(2));
+/// tgl::draw::arrow(isize::MIN, 0, 10, 10, '|', ArrowHead::Triangle(usize::MAX));
+/// ```
+pub fn arrow(x1: isize, y1: isize, x2: isize, y2: isize, c: char, head: ArrowHead) {
+    line(x1, y1, x2, y2, c);
+    let screen = super::screen_rect();
+    for (x, y, hc) in raster::arrow_head_clipped(x1, y1, x2, y2, head, c, screen) {
+        pixel_in(screen, x, y, hc);
+    }
+}
+
+
+/// Draw an arrow of `c` between `(x1, y1)` and `(x2, y2)` with an arrowhead of style `head` at both ends.
+/// 
+/// Example
+/// ```
+/// use terminalgl as tgl;
+/// use tgl::ArrowHead;
+/// tgl::draw::double_arrow(1, 1, 12, 1, '-', ArrowHead::Char);
+/// ```
+pub fn double_arrow(x1: isize, y1: isize, x2: isize, y2: isize, c: char, head: ArrowHead) {
+    arrow(x1, y1, x2, y2, c, head);
+    let screen = super::screen_rect();
+    for (x, y, hc) in raster::arrow_head_clipped(x2, y2, x1, y1, head, c, screen) {
+        pixel_in(screen, x, y, hc);
+    }
+}
+
+
+/// Draw an orthogonal connector between the nearest edges of `from` and `to` using box-drawing characters.
+/// The connector bends once or twice at the midpoint, and `style` controls ASCII fallback and the arrowhead.
+/// 
+/// Example
+/// ```
+/// use terminalgl as tgl;
+/// use tgl::{ConnectorStyle, Rect};
+/// let a = Rect::new(1, 1, 8, 3);
+/// let b = Rect::new(16, 6, 8, 3);
+/// tgl::draw::rectangle(a.x, a.y, a.width, a.height, '#', false);
+/// tgl::draw::rectangle(b.x, b.y, b.width, b.height, '#', false);
+/// tgl::draw::connector(a, b, ConnectorStyle { ascii: false, arrow: true });
+/// tgl::draw::connector(a, Rect::new(isize::MAX - 1, 2, usize::MAX, 3), ConnectorStyle::default());
+/// ```
+pub fn connector(from: Rect, to: Rect, style: ConnectorStyle) {
+    let screen = super::screen_rect();
+    for (x, y, c) in raster::connector_clipped(from, to, style, screen) {
+        pixel_in(screen, x, y, c);
+    }
+}
+
+
+/// Draw the tree `root` with its root label at `(x, y)` and one row per node below it,
+/// connecting children to their parents with `├──`, `└──` and `│` guides.
+/// 
+/// Example
+/// ```
+/// use terminalgl as tgl;
+/// use tgl::{TreeNode, TreeStyle};
+/// let root = TreeNode::new("src", vec![TreeNode::leaf("draw.rs"), TreeNode::leaf("lib.rs")]);
+/// tgl::draw::tree(1, 1, &root, TreeStyle::default());
+/// ```
+pub fn tree(x: isize, y: isize, root: &TreeNode, style: TreeStyle) {
+    for (i, (guide, label, _)) in raster::tree_rows(root, style).into_iter().enumerate() {
+        text(x, y.saturating_add(i as isize), &(guide + &label));
+    }
+}
+
+
+/// Draw the calendar for `month` (1 to 12) of `year` with its top left corner at `(x, y)`.
+/// The calendar is always `raster::CALENDAR_WIDTH` (20) columns wide, and 6 to 8 rows tall:
+/// a title, the weekday headers, and one row per week.
+/// 
+/// Example
+/// ```
+/// use terminalgl as tgl;
+/// tgl::draw::calendar(1, 1, 2024, 2, &tgl::CalendarOptions::default());
+/// ```
+pub fn calendar(x: isize, y: isize, year: i32, month: u32, opts: &CalendarOptions) {
+    for (i, row) in raster::calendar_rows(year, month, opts.monday_first).iter().enumerate() {
+        text(x, y.saturating_add(i as isize), row);
+    }
+}
+
+
+/// Draw an elliptical arc of `c` centered at `(h, k)` with width `a` and height `b`, from angle `start` to angle `end`.
+/// Angles are in radians and increase clockwise, so `0` points right and `PI / 2` points down.
+/// 
+/// Example
+/// ```
+/// use terminalgl as tgl;
+/// use std::f64::consts::PI;
+/// tgl::draw::arc(10, 6, 8, 4, PI, 2.0 * PI, '#');
+/// tgl::draw::arc(10, 6, usize::MAX, 4, PI, 2.0 * PI, '#');
+/// ```
+pub fn arc(h: isize, k: isize, a: usize, b: usize, start: f64, end: f64, c: char) {
+    let screen = super::screen_rect();
+    for (x, y) in raster::arc_clipped(h, k, a, b, start, end, screen) {
+        pixel_in(screen, x, y, c);
+    }
+}
+
+
+/// Draw axes spanning `x_range` and `y_range` inside `rect`, with tick marks at "nice" intervals and labels that never overlap.
+/// Returns the transform from data coordinates to cells, whose `plot` is the plotting area inside the axes.
+/// 
+/// Example
+/// ```
+/// use terminalgl as tgl;
+/// use tgl::chart::AxisOptions;
+/// let transform = tgl::draw::axes(tgl::Rect::new(0, 0, 40, 12), (0.0, 10.0), (-1.0, 1.0), AxisOptions::default());
+/// for i in 0..=100 {
+///     let x = i as f64 / 10.0;
+///     if let Some((cx, cy)) = transform.to_cell(x, x.sin()) {
+///         tgl::draw::pixel(cx, cy, '*');
+///     }
+/// }
+/// ```
+pub fn axes(rect: Rect, x_range: (f64, f64), y_range: (f64, f64), opts: AxisOptions) -> Transform {
+    let (cells, transform) = chart::axes_cells(rect, x_range, y_range, opts);
+    for (x, y, c) in cells {
+        pixel(x, y, c);
+    }
+    transform
+}
+
+
+/// Draw a bordered, word-wrapped tooltip for `text` pointing at `(anchor_x, anchor_y)`, and get the rectangle it covers.
+/// The tooltip flips above or to the left of the anchor when it would run off the terminal.
+/// Draw over the returned rectangle, or restore it from a `canvas::Snapshot`, to erase the tooltip.
+/// 
+/// Example
+/// ```
+/// use terminalgl as tgl;
+/// let covered = tgl::draw::tooltip(5, 2, "Size: 4.2 KiB, modified yesterday");
+/// tgl::draw::tooltip(isize::MAX, isize::MIN, "Off the terminal");
+/// tgl::draw::rectangle(covered.x, covered.y, covered.width, covered.height, ' ', true);
+/// ```
+pub fn tooltip(anchor_x: isize, anchor_y: isize, text: &str) -> Rect {
+    let screen = super::screen_rect();
+    let (rect, cells) = raster::tooltip(anchor_x, anchor_y, text, screen);
+    for (x, y, c) in cells {
+        pixel_in(screen, x, y, c);
+    }
+    rect
+}
+
+
+/// Draw a path of `c` through the cells `points`, connecting consecutive cells with lines.
+/// 
+/// Example
+/// ```
+/// use terminalgl as tgl;
+/// tgl::draw::path(&[(1, 1), (2, 1), (3, 2), (3, 3)], '*');
+/// ```
+pub fn path(points: &[(isize, isize)], c: char) {
+    if let [(x, y)] = points {
+        pixel(*x, *y, c);
+    }
+    for pair in points.windows(2) {
+        line(pair[0].0, pair[0].1, pair[1].0, pair[1].1, c);
+    }
+}
+
+
+/// Draw fractal noise over `rect` as characters from `ramp` (e.g. `raster::DENSITY_RAMP`), with features about `scale` cells across.
+/// The same `seed` always draws the same field; use `noise::field` with an offset to animate it.
+/// 
+/// Example
+/// ```
+/// use terminalgl as tgl;
+/// tgl::draw::noise_field(tgl::Rect::new(0, 0, 40, 10), 8.0, 1, tgl::raster::DENSITY_RAMP);
+/// ```
+pub fn noise_field(rect: Rect, scale: f64, seed: u64, ramp: &str) {
+    for (x, y, v) in noise::field(rect, scale, seed, (0.0, 0.0)) {
+        pixel(x, y, raster::ramp_char(ramp, v));
+    }
+}
+
+
+/// Draw the row-major luminance buffer `luminance` (`0.0` to `1.0`), `src_width` samples wide, as `width` by `height`
+/// characters from `ramp` (e.g. `raster::DENSITY_RAMP`) at `(x, y)`. The buffer is resampled to fit, and cells off the terminal are skipped.
+/// 
+/// Example
+/// ```
+/// use terminalgl as tgl;
+/// let gradient: Vec<f64> = (0..64).map(|i| (i % 8) as f64 / 7.0).collect();
+/// tgl::draw::ascii_image(0, 0, 16, 4, &gradient, 8, tgl::raster::DENSITY_RAMP);
+/// ```
+#[cfg(feature = "image")]
+pub fn ascii_image(x: isize, y: isize, width: usize, height: usize, luminance: &[f64], src_width: usize, ramp: &str) {
+    let screen = super::screen_rect();
+    for (row, line) in raster::ascii_rows(luminance, src_width, width, height, ramp).iter().enumerate() {
+        for (col, c) in line.chars().enumerate() {
+            let (cx, cy) = (x.saturating_add(col as isize), y.saturating_add(row as isize));
+            pixel_in(screen, cx, cy, c);
+        }
     }
 }
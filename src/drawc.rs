@@ -1,5 +1,8 @@
 // Draw in color.
-use super::{Direction, TextAlignment};
+use super::chart::{self, AxisOptions, CandleOptions, Chart, Ohlc, ScatterOptions, SpectrumOptions, Transform, WaveformOptions};
+use super::{chars, noise, raster, ArrowHead, CalendarOptions, ConnectorStyle, Direction, GaugeOptions, Rect, TextAlignment, TextDirection, TreeNode, TreeStyle};
+use std::collections::HashMap;
+use std::ops::Range;
 
 
 /// Color type for `rgb_to_ccode`. Can be either foreground (`fg`) or background (`bg`).
@@ -8,47 +11,155 @@ pub enum ColorKind {
     Bg
 }
 
+/// How `line_aa`, `ellipse_aa`, and `circle_aa` show how much of each cell the ideal shape covers.
+/// `Ramp` picks a character from `ramp` (empty to solid, like `raster::AA_RAMP`) drawn with `ccode`, leaving cells that get a space untouched.
+/// `Blend` draws every cell with `c`, blending its color from `background` toward `color` by coverage.
+/// Either way, cells the shape passes straight through get the solid character or `color` itself.
+#[derive(Clone, Copy, Debug)]
+pub enum Shading<'a> {
+    Ramp { ramp: &'a str, ccode: &'a str },
+    Blend { c: char, color: (u8, u8, u8), background: (u8, u8, u8) }
+}
+
 pub const RESET: &str = "\x1b[0m";
-pub const BLACK: &str = "\x1b[30m";
-pub const RED: &str = "\x1b[31m";
-pub const GREEN: &str = "\x1b[32m";
-pub const YELLOW: &str = "\x1b[33m";
-pub const BLUE: &str = "\x1b[34m";
-pub const MAGENTA: &str = "\x1b[35m";
-pub const CYAN: &str = "\x1b[36m";
-pub const WHITE: &str = "\x1b[37m";
-
-pub const BRIGHT_BLACK: &str = "\x1b[90m";
-pub const BRIGHT_RED: &str = "\x1b[91m";
-pub const BRIGHT_GREEN: &str = "\x1b[92m";
-pub const BRIGHT_YELLOW: &str = "\x1b[93m";
-pub const BRIGHT_BLUE: &str = "\x1b[94m";
-pub const BRIGHT_MAGENTA: &str = "\x1b[95m";
-pub const BRIGHT_CYAN: &str = "\x1b[96m";
-pub const BRIGHT_WHITE: &str = "\x1b[97m";
-
-pub const BLACK_BG: &str = "\x1b[40m";
-pub const RED_BG: &str = "\x1b[41m";
-pub const GREEN_BG: &str = "\x1b[42m";
-pub const YELLOW_BG: &str = "\x1b[43m";
-pub const BLUE_BG: &str = "\x1b[44m";
-pub const MAGENTA_BG: &str = "\x1b[45m";
-pub const CYAN_BG: &str = "\x1b[46m";
-pub const WHITE_BG: &str = "\x1b[47m";
-
-pub const BRIGHT_BLACK_BG: &str = "\x1b[100m";
-pub const BRIGHT_RED_BG: &str = "\x1b[101m";
-pub const BRIGHT_GREEN_BG: &str = "\x1b[102m";
-pub const BRIGHT_YELLOW_BG: &str = "\x1b[103m";
-pub const BRIGHT_BLUE_BG: &str = "\x1b[104m";
-pub const BRIGHT_MAGENTA_BG: &str = "\x1b[105m";
-pub const BRIGHT_CYAN_BG: &str = "\x1b[106m";
-pub const BRIGHT_WHITE_BG: &str = "\x1b[107m";
-
-
-fn distance(x1: isize, y1: isize, x2: isize, y2: isize) -> f64 {
-    let x = ((x2-x1).pow(2) + (y2-y1).pow(2)) as f64;
-    x.sqrt()
+pub const BLACK: &str = NamedColor::Black.fg_code();
+pub const RED: &str = NamedColor::Red.fg_code();
+pub const GREEN: &str = NamedColor::Green.fg_code();
+pub const YELLOW: &str = NamedColor::Yellow.fg_code();
+pub const BLUE: &str = NamedColor::Blue.fg_code();
+pub const MAGENTA: &str = NamedColor::Magenta.fg_code();
+pub const CYAN: &str = NamedColor::Cyan.fg_code();
+pub const WHITE: &str = NamedColor::White.fg_code();
+
+pub const BRIGHT_BLACK: &str = NamedColor::BrightBlack.fg_code();
+pub const BRIGHT_RED: &str = NamedColor::BrightRed.fg_code();
+pub const BRIGHT_GREEN: &str = NamedColor::BrightGreen.fg_code();
+pub const BRIGHT_YELLOW: &str = NamedColor::BrightYellow.fg_code();
+pub const BRIGHT_BLUE: &str = NamedColor::BrightBlue.fg_code();
+pub const BRIGHT_MAGENTA: &str = NamedColor::BrightMagenta.fg_code();
+pub const BRIGHT_CYAN: &str = NamedColor::BrightCyan.fg_code();
+pub const BRIGHT_WHITE: &str = NamedColor::BrightWhite.fg_code();
+
+pub const BLACK_BG: &str = NamedColor::Black.bg_code();
+pub const RED_BG: &str = NamedColor::Red.bg_code();
+pub const GREEN_BG: &str = NamedColor::Green.bg_code();
+pub const YELLOW_BG: &str = NamedColor::Yellow.bg_code();
+pub const BLUE_BG: &str = NamedColor::Blue.bg_code();
+pub const MAGENTA_BG: &str = NamedColor::Magenta.bg_code();
+pub const CYAN_BG: &str = NamedColor::Cyan.bg_code();
+pub const WHITE_BG: &str = NamedColor::White.bg_code();
+
+pub const BRIGHT_BLACK_BG: &str = NamedColor::BrightBlack.bg_code();
+pub const BRIGHT_RED_BG: &str = NamedColor::BrightRed.bg_code();
+pub const BRIGHT_GREEN_BG: &str = NamedColor::BrightGreen.bg_code();
+pub const BRIGHT_YELLOW_BG: &str = NamedColor::BrightYellow.bg_code();
+pub const BRIGHT_BLUE_BG: &str = NamedColor::BrightBlue.bg_code();
+pub const BRIGHT_MAGENTA_BG: &str = NamedColor::BrightMagenta.bg_code();
+pub const BRIGHT_CYAN_BG: &str = NamedColor::BrightCyan.bg_code();
+pub const BRIGHT_WHITE_BG: &str = NamedColor::BrightWhite.bg_code();
+
+
+/// One of the 16 standard terminal colors, in ANSI order.
+///
+/// Example
+/// ```
+/// use terminalgl::drawc::{self, NamedColor};
+/// assert_eq!(NamedColor::Blue.fg_code(), drawc::BLUE);
+/// assert_eq!(NamedColor::Blue.bg_code(), drawc::BLUE_BG);
+/// assert_eq!(NamedColor::Blue.bright(), NamedColor::BrightBlue);
+/// assert_eq!(NamedColor::BrightBlue.dim(), NamedColor::Blue);
+/// assert_eq!(NamedColor::Red.to_rgb(), (205, 0, 0));
+/// assert_eq!(NamedColor::ALL.iter().filter(|c| c.is_bright()).count(), 8);
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum NamedColor {
+    Black,
+    Red,
+    Green,
+    Yellow,
+    Blue,
+    Magenta,
+    Cyan,
+    White,
+    BrightBlack,
+    BrightRed,
+    BrightGreen,
+    BrightYellow,
+    BrightBlue,
+    BrightMagenta,
+    BrightCyan,
+    BrightWhite
+}
+
+const FG_CODES: [&str; 16] = [
+    "\x1b[30m", "\x1b[31m", "\x1b[32m", "\x1b[33m", "\x1b[34m", "\x1b[35m", "\x1b[36m", "\x1b[37m",
+    "\x1b[90m", "\x1b[91m", "\x1b[92m", "\x1b[93m", "\x1b[94m", "\x1b[95m", "\x1b[96m", "\x1b[97m"
+];
+
+const BG_CODES: [&str; 16] = [
+    "\x1b[40m", "\x1b[41m", "\x1b[42m", "\x1b[43m", "\x1b[44m", "\x1b[45m", "\x1b[46m", "\x1b[47m",
+    "\x1b[100m", "\x1b[101m", "\x1b[102m", "\x1b[103m", "\x1b[104m", "\x1b[105m", "\x1b[106m", "\x1b[107m"
+];
+
+/// xterm's default palette.
+const XTERM_RGB: [(u8, u8, u8); 16] = [
+    (0, 0, 0), (205, 0, 0), (0, 205, 0), (205, 205, 0), (0, 0, 238), (205, 0, 205), (0, 205, 205), (229, 229, 229),
+    (127, 127, 127), (255, 0, 0), (0, 255, 0), (255, 255, 0), (92, 92, 255), (255, 0, 255), (0, 255, 255), (255, 255, 255)
+];
+
+const NAMES: [&str; 16] = [
+    "black", "red", "green", "yellow", "blue", "magenta", "cyan", "white",
+    "bright black", "bright red", "bright green", "bright yellow", "bright blue", "bright magenta", "bright cyan", "bright white"
+];
+
+impl NamedColor {
+    /// Every named color, in ANSI order (the 8 normal colors, then their bright versions).
+    pub const ALL: [NamedColor; 16] = [
+        NamedColor::Black, NamedColor::Red, NamedColor::Green, NamedColor::Yellow,
+        NamedColor::Blue, NamedColor::Magenta, NamedColor::Cyan, NamedColor::White,
+        NamedColor::BrightBlack, NamedColor::BrightRed, NamedColor::BrightGreen, NamedColor::BrightYellow,
+        NamedColor::BrightBlue, NamedColor::BrightMagenta, NamedColor::BrightCyan, NamedColor::BrightWhite
+    ];
+
+    /// Get the color's ANSI index, from `0` (black) to `15` (bright white).
+    pub const fn index(self) -> usize {
+        self as usize
+    }
+
+    /// Get the color code that sets this color as the foreground.
+    pub const fn fg_code(self) -> &'static str {
+        FG_CODES[self.index()]
+    }
+
+    /// Get the color code that sets this color as the background.
+    pub const fn bg_code(self) -> &'static str {
+        BG_CODES[self.index()]
+    }
+
+    /// Get the color as an rgb triplet, using xterm's default palette. Terminals with other palettes will differ.
+    pub const fn to_rgb(self) -> (u8, u8, u8) {
+        XTERM_RGB[self.index()]
+    }
+
+    /// Get the color's lowercase name, e.g. `"bright red"`.
+    pub const fn name(self) -> &'static str {
+        NAMES[self.index()]
+    }
+
+    /// Get whether this is one of the 8 bright colors.
+    pub const fn is_bright(self) -> bool {
+        self.index() >= 8
+    }
+
+    /// Get the bright version of this color. Bright colors are returned unchanged.
+    pub const fn bright(self) -> NamedColor {
+        NamedColor::ALL[self.index() | 8]
+    }
+
+    /// Get the normal version of this color. Normal colors are returned unchanged.
+    pub const fn dim(self) -> NamedColor {
+        NamedColor::ALL[self.index() & 7]
+    }
 }
 
 
@@ -59,7 +170,7 @@ fn distance(x1: isize, y1: isize, x2: isize, y2: isize) -> f64 {
 /// ```
 /// use terminalgl::drawc;
 /// let col1 = drawc::rgb_to_ccode((0, 255, 255), drawc::ColorKind::Fg);  // Cyan foreground
-/// let col2 = drawc::rgb_to_ccode((255, 0, 0), drawc::ColorKind::Fg) + drawc::rgb_to_ccode((0, 255, 0), drawc::ColorKind::Bg);  // Red foreground and green background
+/// let col2 = drawc::rgb_to_ccode((255, 0, 0), drawc::ColorKind::Fg) + &drawc::rgb_to_ccode((0, 255, 0), drawc::ColorKind::Bg);  // Red foreground and green background
 /// ```
 pub fn rgb_to_ccode(rgb: (u8, u8, u8), kind: ColorKind) -> String {
     let (r, g, b) = rgb;
@@ -70,148 +181,720 @@ pub fn rgb_to_ccode(rgb: (u8, u8, u8), kind: ColorKind) -> String {
 }
 
 
-/// Draw character `c` at `(x, y)`.
+/// Get the named color nearest to the rgb triplet `rgb`, comparing against `NamedColor::to_rgb`.
+/// Distance is the "redmean" weighted RGB distance, which weights red, green and blue differences
+/// by how sensitive the eye is to each depending on how red the colors are. It is cheap and much closer to
+/// perceived difference than plain RGB distance. Colors from `NamedColor::to_rgb` map to themselves.
+///
+/// Example
+/// ```
+/// use terminalgl::drawc::{self, NamedColor};
+/// let cases = [
+///     ((0, 0, 0), NamedColor::Black),
+///     ((255, 255, 255), NamedColor::BrightWhite),
+///     ((200, 10, 10), NamedColor::Red),
+///     ((255, 40, 40), NamedColor::BrightRed),
+///     ((255, 165, 0), NamedColor::Yellow),
+///     ((128, 0, 128), NamedColor::Magenta),
+///     ((100, 100, 255), NamedColor::BrightBlue),
+///     ((0, 200, 255), NamedColor::Cyan),
+///     ((34, 139, 34), NamedColor::Green),
+///     ((128, 128, 128), NamedColor::BrightBlack),
+///     ((192, 192, 192), NamedColor::White),
+///     ((0, 0, 128), NamedColor::Blue),
+///     ((60, 60, 60), NamedColor::Black),
+/// ];
+/// for (rgb, named) in cases {
+///     assert_eq!(drawc::rgb_to_named(rgb), named, "{:?}", rgb);
+/// }
+/// for named in NamedColor::ALL {
+///     assert_eq!(drawc::rgb_to_named(named.to_rgb()), named);
+/// }
+/// ```
+pub fn rgb_to_named(rgb: (u8, u8, u8)) -> NamedColor {
+    let distance = |other: (u8, u8, u8)| {
+        let rmean = (rgb.0 as f64 + other.0 as f64) / 2.0;
+        let dr = rgb.0 as f64 - other.0 as f64;
+        let dg = rgb.1 as f64 - other.1 as f64;
+        let db = rgb.2 as f64 - other.2 as f64;
+        (2.0 + rmean / 256.0) * dr * dr + 4.0 * dg * dg + (2.0 + (255.0 - rmean) / 256.0) * db * db
+    };
+    let mut nearest = NamedColor::Black;
+    let mut nearest_distance = f64::INFINITY;
+    for named in NamedColor::ALL {
+        let d = distance(named.to_rgb());
+        if d < nearest_distance {
+            nearest = named;
+            nearest_distance = d;
+        }
+    }
+    nearest
+}
+
+
+/// Reduce the colors in `rgb` to a palette of at most `max_colors` (clamped to `1..=256`) with median cut, and get the palette
+/// and the index of each color of `rgb` in it. Images with few enough colors keep them exactly. The result depends only on which
+/// colors `rgb` holds and how often, not their order.
+///
+/// Example
+/// ```
+/// use terminalgl::drawc;
+/// let rgb = [(255, 0, 0), (0, 0, 255), (255, 0, 0), (250, 0, 0)];
+/// let (palette, indices) = drawc::quantize(&rgb, 8);
+/// assert_eq!(palette.len(), 3);
+/// assert!(indices.iter().zip(&rgb).all(|(&i, &c)| palette[i as usize] == c));
+///
+/// let grays = [(0, 0, 0), (250, 250, 250), (10, 10, 10), (255, 255, 255)];
+/// let (palette, indices) = drawc::quantize(&grays, 2);
+/// assert_eq!(palette, vec![(5, 5, 5), (253, 253, 253)]);
+/// assert_eq!(indices, vec![0, 1, 0, 1]);
+///
+/// let gradient: Vec<(u8, u8, u8)> = (0..4096).map(|i| ((i % 64 * 4) as u8, (i / 64 * 4) as u8, 128)).collect();
+/// let (palette, indices) = drawc::quantize(&gradient, 256);
+/// assert_eq!(palette.len(), 256);
+/// for (&i, &(r, g, _)) in indices.iter().zip(&gradient) {
+///     let (pr, pg, _) = palette[i as usize];
+///     assert!(r.abs_diff(pr) <= 8 && g.abs_diff(pg) <= 8);
+/// }
+/// ```
+pub fn quantize(rgb: &[(u8, u8, u8)], max_colors: usize) -> (Vec<(u8, u8, u8)>, Vec<u8>) {
+    let max_colors = max_colors.clamp(1, 256);
+    let mut counts: HashMap<(u8, u8, u8), usize> = HashMap::new();
+    for &c in rgb {
+        *counts.entry(c).or_default() += 1;
+    }
+    let mut colors: Vec<((u8, u8, u8), usize)> = counts.into_iter().collect();
+    colors.sort_unstable();
+    let channel = |c: (u8, u8, u8), ch: usize| [c.0, c.1, c.2][ch];
+    // The channel each box of colors is most spread out in, and how far.
+    let widest = |colors: &[((u8, u8, u8), usize)]| (0..3).map(|ch| {
+        let values = colors.iter().map(|&(c, _)| channel(c, ch));
+        (values.clone().max().unwrap_or(0) - values.min().unwrap_or(0), ch)
+    }).fold((0, 0), |best, next| if next.0 > best.0 { next } else { best });
+
+    let mut boxes: Vec<Range<usize>> = Some(0..colors.len()).filter(|all| !all.is_empty()).into_iter().collect();
+    while boxes.len() < max_colors {
+        // Split the box with the widest spread (the first of any tied) at the median pixel along that channel.
+        let mut best: Option<(u8, usize, usize)> = None;
+        for (i, range) in boxes.iter().enumerate() {
+            let (spread, ch) = widest(&colors[range.clone()]);
+            if spread > 0 && best.is_none_or(|b| spread > b.0) {
+                best = Some((spread, i, ch));
+            }
+        }
+        let Some((_, i, ch)) = best else { break };
+        let range = boxes[i].clone();
+        colors[range.clone()].sort_unstable_by_key(|&(c, _)| (channel(c, ch), c));
+        let total: usize = colors[range.clone()].iter().map(|&(_, n)| n).sum();
+        let mut seen = 0;
+        let mut mid = range.end - 1;
+        for j in range.clone() {
+            seen += colors[j].1;
+            if seen * 2 >= total {
+                mid = j + 1;
+                break;
+            }
+        }
+        let mid = mid.clamp(range.start + 1, range.end - 1);
+        boxes[i] = range.start..mid;
+        boxes.push(mid..range.end);
+    }
+
+    // Each box becomes the average of its pixels, with the palette in color order.
+    let mut entries: Vec<((u8, u8, u8), Range<usize>)> = boxes.into_iter().map(|range| {
+        let pixels = &colors[range.clone()];
+        let total: usize = pixels.iter().map(|&(_, n)| n).sum();
+        let mean = |ch| ((pixels.iter().map(|&(c, n)| channel(c, ch) as usize * n).sum::<usize>() + total / 2) / total) as u8;
+        ((mean(0), mean(1), mean(2)), range)
+    }).collect();
+    entries.sort_by_key(|(mean, range)| (*mean, range.start));
+    let mut index = HashMap::new();
+    for (i, (_, range)) in entries.iter().enumerate() {
+        for &(c, _) in &colors[range.clone()] {
+            index.insert(c, i as u8);
+        }
+    }
+    (entries.into_iter().map(|(mean, _)| mean).collect(), rgb.iter().map(|c| index[c]).collect())
+}
+
+
+/// Blend from rgb triplet `a` to `b` by `t`, where `0.0` gives `a` and `1.0` gives `b`.
+/// `t` is clamped to `0.0..=1.0`.
+///
+/// Example
+/// ```
+/// use terminalgl::drawc;
+/// assert_eq!(drawc::lerp_rgb((0, 0, 0), (255, 100, 10), 0.5), (128, 50, 5));
+/// assert_eq!(drawc::lerp_rgb((0, 0, 0), (255, 100, 10), 2.0), (255, 100, 10));
+/// ```
+pub fn lerp_rgb(a: (u8, u8, u8), b: (u8, u8, u8), t: f64) -> (u8, u8, u8) {
+    let t = t.clamp(0.0, 1.0);
+    let lerp = |a: u8, b: u8| (a as f64 + (b as f64 - a as f64) * t).round() as u8;
+    (lerp(a.0, b.0), lerp(a.1, b.1), lerp(a.2, b.2))
+}
+
+
+/// Get the color `t` of the way along a gradient through `colors`, where `0.0` gives the first color and `1.0` the last.
+/// Returns `None` if `colors` is empty.
+///
+/// Example
+/// ```
+/// use terminalgl::drawc;
+/// let colors = [(0, 0, 0), (255, 0, 0), (255, 255, 255)];
+/// assert_eq!(drawc::gradient(&colors, 0.25), Some((128, 0, 0)));
+/// assert_eq!(drawc::gradient(&colors, 1.0), Some((255, 255, 255)));
+/// assert_eq!(drawc::gradient(&[], 0.5), None);
+/// ```
+pub fn gradient(colors: &[(u8, u8, u8)], t: f64) -> Option<(u8, u8, u8)> {
+    match colors.len() {
+        0 => None,
+        1 => Some(colors[0]),
+        n => {
+            let pos = t.clamp(0.0, 1.0) * (n - 1) as f64;
+            let i = (pos as usize).min(n - 2);
+            Some(lerp_rgb(colors[i], colors[i + 1], pos - i as f64))
+        }
+    }
+}
+
+
+/// Get the color at `t` along a gradient with color `stops`, each a position and the color there, in order of position.
+/// Colors are blended between the stops either side of `t`, and `t` before the first stop or after the last takes that stop's color.
+/// Returns `None` if `stops` is empty.
+///
+/// Example
+/// ```
+/// use terminalgl::drawc;
+/// let stops = [(0.0, (0, 0, 0)), (0.8, (255, 0, 0)), (1.0, (255, 255, 255))];
+/// assert_eq!(drawc::gradient_stops(&stops, 0.4), Some((128, 0, 0)));
+/// assert_eq!(drawc::gradient_stops(&stops, 0.9), Some((255, 128, 128)));
+/// assert_eq!(drawc::gradient_stops(&stops, -1.0), Some((0, 0, 0)));
+/// assert_eq!(drawc::gradient_stops(&[], 0.5), None);
+/// ```
+pub fn gradient_stops(stops: &[(f64, (u8, u8, u8))], t: f64) -> Option<(u8, u8, u8)> {
+    let (first, last) = (stops.first()?, stops.last()?);
+    if t <= first.0 {
+        return Some(first.1);
+    }
+    for pair in stops.windows(2) {
+        let ((p1, c1), (p2, c2)) = (pair[0], pair[1]);
+        if t <= p2 {
+            return Some(if p2 > p1 { lerp_rgb(c1, c2, (t - p1) / (p2 - p1)) } else { c2 });
+        }
+    }
+    Some(last.1)
+}
+
+
+/// Convert hue `h` (in degrees, wrapping around at 360), saturation `s`, and value `v` (both `0.0` to `1.0`) to an rgb triplet.
+///
+/// Example
+/// ```
+/// use terminalgl::drawc;
+/// assert_eq!(drawc::hsv_to_rgb(0.0, 1.0, 1.0), (255, 0, 0));
+/// assert_eq!(drawc::hsv_to_rgb(120.0, 1.0, 1.0), (0, 255, 0));
+/// assert_eq!(drawc::hsv_to_rgb(-120.0, 1.0, 0.5), (0, 0, 128));
+/// assert_eq!(drawc::hsv_to_rgb(77.0, 0.0, 1.0), (255, 255, 255));
+/// ```
+pub fn hsv_to_rgb(h: f64, s: f64, v: f64) -> (u8, u8, u8) {
+    let (s, v) = (s.clamp(0.0, 1.0), v.clamp(0.0, 1.0));
+    let h = if h.is_finite() { h.rem_euclid(360.0) / 60.0 } else { 0.0 };
+    let chroma = v * s;
+    let x = chroma * (1.0 - (h % 2.0 - 1.0).abs());
+    let (r, g, b) = match h as u8 {
+        0 => (chroma, x, 0.0),
+        1 => (x, chroma, 0.0),
+        2 => (0.0, chroma, x),
+        3 => (0.0, x, chroma),
+        4 => (x, 0.0, chroma),
+        _ => (chroma, 0.0, x)
+    };
+    let m = v - chroma;
+    let channel = |c: f64| ((c + m) * 255.0).round() as u8;
+    (channel(r), channel(g), channel(b))
+}
+
+
+/// Get `ccode` with its colors blended toward black by `amount`, where `0.0` leaves it unchanged and `1.0` makes it black.
+/// Named and rgb colors are darkened and written as rgb codes. Any other parameters, such as bold or 256 color indices, are kept as they are.
+/// The terminal's default foreground is treated as `WHITE`, so text without a foreground color fades too.
+///
+/// Example
+/// ```
+/// use terminalgl::drawc;
+/// assert_eq!(drawc::darken("\x1b[38;2;200;100;0m", 0.5), "\x1b[38;2;100;50;0m");
+/// assert_eq!(drawc::darken("\x1b[1m\x1b[31m", 0.0), "\x1b[1m\x1b[31m");
+/// assert_eq!(drawc::darken(drawc::RED_BG, 1.0), "\x1b[38;2;0;0;0m\x1b[48;2;0;0;0m");
+/// assert_eq!(drawc::darken("", 0.5), "\x1b[38;2;115;115;115m");
+/// ```
+pub fn darken(ccode: &str, amount: f64) -> String {
+    if amount <= 0.0 {
+        return ccode.to_string();
+    }
+    let dark = |rgb: (u8, u8, u8)| lerp_rgb(rgb, (0, 0, 0), amount);
+    let ((fg, bg), mut kept) = parse_ccode(ccode);
+    kept.push_str(&rgb_to_ccode(dark(fg.unwrap_or(NamedColor::White.to_rgb())), ColorKind::Fg));
+    if let Some(bg) = bg {
+        kept.push_str(&rgb_to_ccode(dark(bg), ColorKind::Bg));
+    }
+    kept
+}
+
+
+/// Get a color code between `a` and `b`, where `0.0` gives `a` and `1.0` gives `b`, with each color blended by `lerp_rgb`
+/// and written as an rgb code. Any other parameters are taken from `a` before halfway and from `b` after.
+/// The terminal's default foreground is treated as `WHITE` and its default background as black, and there is
+/// no background if neither code sets one.
+///
+/// Example
+/// ```
+/// use terminalgl::drawc::{self, ColorKind};
+/// let (a, b) = (drawc::rgb_to_ccode((200, 0, 0), ColorKind::Fg), drawc::rgb_to_ccode((0, 0, 100), ColorKind::Fg));
+/// assert_eq!(drawc::lerp_ccode(&a, &b, 0.5), "\x1b[38;2;100;0;50m");
+/// assert_eq!(drawc::lerp_ccode(&a, &b, 1.0), b);
+/// assert_eq!(drawc::lerp_ccode("\x1b[1m", drawc::BLUE_BG, 0.25), "\x1b[1m\x1b[38;2;229;229;229m\x1b[48;2;0;0;60m");
+/// ```
+pub fn lerp_ccode(a: &str, b: &str, t: f64) -> String {
+    if t <= 0.0 {
+        return a.to_string();
+    }
+    if t >= 1.0 {
+        return b.to_string();
+    }
+    let ((a_fg, a_bg), a_kept) = parse_ccode(a);
+    let ((b_fg, b_bg), b_kept) = parse_ccode(b);
+    let white = NamedColor::White.to_rgb();
+    let mut out = if t < 0.5 { a_kept } else { b_kept };
+    out.push_str(&rgb_to_ccode(lerp_rgb(a_fg.unwrap_or(white), b_fg.unwrap_or(white), t), ColorKind::Fg));
+    if a_bg.is_some() || b_bg.is_some() {
+        out.push_str(&rgb_to_ccode(lerp_rgb(a_bg.unwrap_or((0, 0, 0)), b_bg.unwrap_or((0, 0, 0)), t), ColorKind::Bg));
+    }
+    out
+}
+
+
+/// Foreground and background colors set by a color code, from `ccode_to_rgb`.
+pub type CcodeColors = (Option<(u8, u8, u8)>, Option<(u8, u8, u8)>);
+
+
+/// Get the foreground and background colors `ccode` sets as rgb triplets, with named colors converted by `NamedColor::to_rgb`.
+/// A color `ccode` doesn't set, or sets with a 256 color index, is `None`.
+///
+/// Example
+/// ```
+/// use terminalgl::drawc::{self, ColorKind};
+/// assert_eq!(drawc::ccode_to_rgb(drawc::RED), (Some((205, 0, 0)), None));
+/// assert_eq!(drawc::ccode_to_rgb(&(drawc::rgb_to_ccode((1, 2, 3), ColorKind::Fg) + drawc::BLUE_BG)), (Some((1, 2, 3)), Some((0, 0, 238))));
+/// assert_eq!(drawc::ccode_to_rgb("\x1b[1;38;5;200m"), (None, None));
+/// assert_eq!(drawc::ccode_to_rgb(""), (None, None));
+/// ```
+pub fn ccode_to_rgb(ccode: &str) -> CcodeColors {
+    parse_ccode(ccode).0
+}
+
+
+/// Split `ccode` into the foreground and background it sets as rgb triplets, and a code with every other parameter it sets.
+fn parse_ccode(ccode: &str) -> (CcodeColors, String) {
+    let mut fg = None;
+    let mut bg = None;
+    let mut kept = String::new();
+    for sequence in ccode.split("\x1b[").filter(|s| !s.is_empty()) {
+        let params: Vec<u8> = sequence.trim_end_matches('m').split(';').map(|p| p.parse().unwrap_or(0)).collect();
+        let mut i = 0;
+        while i < params.len() {
+            match params[i] {
+                code @ (30..=37 | 90..=97) => fg = Some(NamedColor::ALL[(code % 10 + if code >= 90 { 8 } else { 0 }) as usize].to_rgb()),
+                code @ (40..=47 | 100..=107) => bg = Some(NamedColor::ALL[(code % 10 + if code >= 100 { 8 } else { 0 }) as usize].to_rgb()),
+                code @ (38 | 48) if params.get(i + 1) == Some(&2) && i + 4 < params.len() => {
+                    let rgb = Some((params[i + 2], params[i + 3], params[i + 4]));
+                    if code == 38 { fg = rgb } else { bg = rgb }
+                    i += 4;
+                },
+                code @ (38 | 48) if params.get(i + 1) == Some(&5) && i + 2 < params.len() => {
+                    kept.push_str(&format!("\x1b[{};5;{}m", code, params[i + 2]));
+                    i += 2;
+                },
+                code => kept.push_str(&format!("\x1b[{}m", code))
+            }
+            i += 1;
+        }
+    }
+    ((fg, bg), kept)
+}
+
+
+/// Get whether `ccode` is a color code the drawing functions in this module accept: empty for the terminal's default colors,
+/// or one or more SGR sequences (`ESC [`, numbers separated by `;`, then `m`), like the constants in this module
+/// and the output of `rgb_to_ccode`.
+///
+/// Example
+/// ```
+/// use terminalgl::drawc::{self, ColorKind};
+/// assert!(drawc::is_ccode(drawc::RED));
+/// assert!(drawc::is_ccode(""));
+/// assert!(drawc::is_ccode(&(drawc::rgb_to_ccode((255, 128, 0), ColorKind::Fg) + drawc::BLUE_BG)));
+/// assert!(!drawc::is_ccode("red"));
+/// assert!(!drawc::is_ccode("\x1b[31"));
+/// assert!(!drawc::is_ccode("\x1b[2J"));
+/// ```
+pub fn is_ccode(ccode: &str) -> bool {
+    let mut rest = ccode;
+    while !rest.is_empty() {
+        let Some(sequence) = rest.strip_prefix("\x1b[") else {
+            return false;
+        };
+        let Some(end) = sequence.find('m') else {
+            return false;
+        };
+        if !sequence[..end].bytes().all(|b| b.is_ascii_digit() || b == b';') {
+            return false;
+        }
+        rest = &sequence[end + 1..];
+    }
+    true
+}
+
+
+/// Get the code to print before a character drawn with `ccode`. Invalid codes fail a debug assertion,
+/// and both invalid and empty codes draw with the default colors.
+fn checked(ccode: &str) -> &str {
+    let valid = is_ccode(ccode);
+    debug_assert!(valid, "{:?} is not a color code: use a constant like drawc::RED, drawc::rgb_to_ccode, or \"\" for the default colors", ccode);
+    if ccode.is_empty() || !valid { RESET } else { ccode }
+}
+
+
+/// Draw character `c` at `(x, y)`. Cells off the terminal are skipped, so any coordinates are safe to draw at.
+/// An empty `ccode` draws with the terminal's default colors. Anything else that isn't a color code (see `is_ccode`),
+/// such as `"red"`, panics in debug builds and draws with the default colors in release builds.
+/// `c` is drawn as `chars::sanitize` gives it, so control characters never reach the terminal.
+/// Every other function in this module draws through this one or `pixel_unchecked`, so they all check their color codes the same way.
 /// 
 /// Example
 /// ```
 /// use terminalgl as tgl;
 /// tgl::drawc::pixel(1, 1, '#', tgl::drawc::RED);
+/// tgl::drawc::pixel(2, 1, '#', "");
+/// tgl::drawc::pixel(isize::MIN, isize::MAX, '#', tgl::drawc::RED);
+/// ```
+///
+/// ```should_panic
+/// terminalgl::drawc::pixel(1, 1, '#', "red");
 /// ```
 pub fn pixel(x: isize, y: isize, c: char, ccode: &str) {
-    let tsize = super::size();
-    if x >= 0 && x < tsize.0 as isize && y >= 0 && y < tsize.1 as isize && ccode.starts_with('\x1b') {
-        super::cursorto(x as usize, y as usize);
-        print!("{}{}", ccode, c);
+    let ccode = checked(ccode);
+    if super::cursorto_checked(x, y).is_ok() {
+        print!("{}{}", ccode, chars::sanitize(c));
+    }
+}
+
+
+/// Draw character `c` at `(x, y)` with `ccode` if it is inside `screen`, for shapes that looked the terminal up once with `screen_rect`.
+fn pixel_in(screen: Rect, x: isize, y: isize, c: char, ccode: &str) {
+    if screen.contains(x, y) {
+        pixel_unchecked(x as usize, y as usize, c, ccode);
     }
 }
 
 
+/// Draw character `c` at `(x, y)` without checking that it is on the terminal.
+/// Faster than `pixel` since it skips the bounds check, for cells already known to be on the terminal. `ccode` is checked like in `pixel`.
+/// 
+/// Example
+/// ```
+/// use terminalgl as tgl;
+/// tgl::drawc::pixel_unchecked(1, 1, '#', tgl::drawc::RED);
+/// ```
+pub fn pixel_unchecked(x: usize, y: usize, c: char, ccode: &str) {
+    super::cursorto(x, y);
+    print!("{}{}", checked(ccode), chars::sanitize(c));
+}
+
+
 /// Draw a straight line of `c` starting at `(x, y)` with length `length` in direction `dir`.
+/// A negative `length` goes the opposite way, so `-5` cells `Left` draws 5 cells to the right.
+/// Only cells on the terminal are visited, so even `isize::MIN` or `isize::MAX` lengths finish immediately.
 /// 
 /// Example
 /// ```
 /// use terminalgl as tgl;
 /// use terminalgl::Direction;
 /// tgl::drawc::straight_line(1, 2, 5, Direction::Right, '#', tgl::drawc::RED);
+/// tgl::drawc::straight_line(isize::MAX, 2, isize::MIN, Direction::Right, '#', tgl::drawc::RED);
 /// ```
-pub fn straight_line(mut x: isize, mut y: isize, mut length: isize, dir: Direction, c: char, ccode: &str) {
-    let mut addx: isize = 0;
-    let mut addy: isize = 0;
-    match dir {
-        Direction::Left => addx = -1,
-        Direction::Right => addx = 1,
-        Direction::Up => addy = -1,
-        Direction::Down => addy = 1
-    }
-    
-    if length < 0 {
-        length = -length;
-        addx = -addx;
-        addy = -addy;
-    }
-    
-    for _ in 0..length {
-        pixel(x, y, c, ccode);
-        x += addx;
-        y += addy;
+pub fn straight_line(x: isize, y: isize, length: isize, dir: Direction, c: char, ccode: &str) {
+    let screen = super::screen_rect();
+    for (x, y) in raster::straight_line_clipped(x, y, length, dir, screen) {
+        pixel_in(screen, x, y, c, ccode);
     }
 }
 
 
 /// Draw a rectangle of `c` at `(x, y)` with width `width` and height `height`.
 /// Use `fill` to specify whether the rectangle is outlined (`false`) or filled (`true`).
+/// Each cell is drawn once, a width or height of 0 draws nothing, and only cells on the terminal are visited.
 /// 
 /// Example
 /// ```
 /// use terminalgl as tgl;
 /// tgl::drawc::rectangle(1, 1, 7, 4, '#', tgl::drawc::RED, false);
+/// tgl::drawc::rectangle(-5, 0, usize::MAX, usize::MAX, '#', tgl::drawc::RED, false);
 /// ```
 pub fn rectangle(x: isize, y: isize, width: usize, height: usize, c: char, ccode: &str, fill: bool) {
-    let w = width as isize;
-    let h = height as isize;
-    if fill {
-        for i in 0..width {
-            straight_line(x+i as isize, y, height as isize, Direction::Down, c, ccode);
-        }
-        return;
+    let screen = super::screen_rect();
+    for (x, y) in raster::rectangle_clipped(x, y, width, height, fill, screen) {
+        pixel_in(screen, x, y, c, ccode);
     }
-    straight_line(x, y, w, Direction::Right, c, ccode);
-    straight_line(x, y+h-1, w, Direction::Right, c, ccode);
-    straight_line(x, y+1, h-2, Direction::Down, c, ccode);
-    straight_line(x+w-1, y+1, h-2, Direction::Down, c, ccode);
 }
 
 
 /// Draw a line of `c` with starting point `(x1, y1)` and ending point (`x2, y2`).
+/// Only cells on the terminal are visited, so lines between far-off points are cheap.
 /// 
 /// Example
 /// ```
 /// use terminalgl as tgl;
 /// tgl::drawc::line(1, 1, 6, 3, '#', tgl::drawc::RED);
+/// tgl::drawc::line(isize::MIN, isize::MIN, isize::MAX, isize::MAX, '#', tgl::drawc::RED);
 /// ```
 pub fn line(x1: isize, y1: isize, x2: isize, y2: isize, c: char, ccode: &str) {
-    if x1 == x2 {
-        straight_line(x1, y1, y2-y1, Direction::Down, c, ccode);
-    }
-    if y1 == y2 {
-        straight_line(x1, y1, x2-x1, Direction::Right, c, ccode);
+    let screen = super::screen_rect();
+    for (x, y) in raster::line_clipped(x1, y1, x2, y2, screen) {
+        pixel_in(screen, x, y, c, ccode);
     }
-    
-    let dist = distance(x1, y1, x2, y2);
-    let dx = (x2-x1) as f64 / dist;
-    let dy = (y2-y1) as f64 / dist;
-    let mut x = x1 as f64;
-    let mut y = y1 as f64;
-    for _ in 0..dist.round() as isize {
-        pixel(x.round() as isize, y.round() as isize, c, ccode);
-        x += dx;
-        y += dy;
-    }
-    pixel(x2, y2, c, ccode);
 }
 
 
 /// Draw an ellipse at `(h, k)` with width `a` and height `b`.
 /// Use `fill` to specify whether the ellipse is outlined (`false`) or filled (`true`).
+/// Each cell is drawn once, the outline is the boundary of the filled ellipse, and only cells on the terminal are visited,
+/// so huge radii are cheap.
 /// 
 /// Example
 /// ```
 /// use terminalgl as tgl;
 /// tgl::drawc::ellipse(5, 5, 4, 3, '#', tgl::drawc::RED, true);
+/// tgl::drawc::ellipse(5, 5, usize::MAX / 2, 3, '#', tgl::drawc::RED, false);
 /// ```
 pub fn ellipse(h: isize, k: isize, a: usize, b: usize, c: char, ccode: &str, fill: bool) {
-    for x in 0..a*2+1 {
-        let shiftx: isize = x as isize + h - a as isize;
-        let inside_y = ((a*a) as isize - (shiftx-h).pow(2)).abs() as f64;
-        let y: f64 = (b as f64) / (a as f64) * inside_y.sqrt() + k as f64;
-        if fill {
-            let ydist: isize = 2 * (k - y.round() as isize).abs();
-            straight_line(shiftx as isize, y.round() as isize, ydist+1, Direction::Up, c, ccode);
-            continue;
+    let screen = super::screen_rect();
+    for (x, y) in raster::ellipse_clipped(h, k, a, b, fill, screen) {
+        pixel_in(screen, x, y, c, ccode);
+    }
+}
+
+
+/// Draw an antialiased line with starting point `(x1, y1)` and ending point (`x2, y2`), shaded by `shading`.
+/// 
+/// Example
+/// ```
+/// use terminalgl as tgl;
+/// use tgl::drawc::Shading;
+/// tgl::drawc::line_aa(1, 1, 19, 7, Shading::Ramp { ramp: tgl::raster::AA_RAMP, ccode: tgl::drawc::RED });
+/// tgl::drawc::line_aa(1, 1, 19, 7, Shading::Blend { c: '█', color: (255, 255, 255), background: (0, 0, 0) });
+/// tgl::drawc::line_aa(isize::MIN, isize::MIN, isize::MAX, 7, Shading::Ramp { ramp: tgl::raster::AA_RAMP, ccode: "" });
+/// ```
+pub fn line_aa(x1: isize, y1: isize, x2: isize, y2: isize, shading: Shading) {
+    let screen = super::screen_rect();
+    shade(screen, raster::line_aa_clipped(x1, y1, x2, y2, screen), shading);
+}
+
+
+/// Draw the antialiased outline of an ellipse at `(h, k)` with width `a` and height `b`, shaded by `shading`.
+/// 
+/// Example
+/// ```
+/// use terminalgl as tgl;
+/// use tgl::drawc::Shading;
+/// tgl::drawc::ellipse_aa(12, 6, 10, 5, Shading::Ramp { ramp: tgl::raster::AA_RAMP, ccode: tgl::drawc::CYAN });
+/// tgl::drawc::ellipse_aa(12, 6, usize::MAX / 2, 5, Shading::Blend { c: '█', color: (0, 255, 255), background: (0, 0, 0) });
+/// ```
+pub fn ellipse_aa(h: isize, k: isize, a: usize, b: usize, shading: Shading) {
+    let screen = super::screen_rect();
+    shade(screen, raster::ellipse_aa_clipped(h, k, a, b, screen), shading);
+}
+
+
+/// Draw the antialiased outline of a circle at `(cx, cy)` with radius `radius`, shaded by `shading`.
+/// The circle is twice as wide as it is tall, so it looks round in a terminal.
+/// 
+/// Example
+/// ```
+/// use terminalgl as tgl;
+/// use tgl::drawc::Shading;
+/// tgl::drawc::circle_aa(12, 6, 5, Shading::Ramp { ramp: tgl::raster::AA_RAMP, ccode: tgl::drawc::GREEN });
+/// ```
+pub fn circle_aa(cx: isize, cy: isize, radius: usize, shading: Shading) {
+    ellipse_aa(cx, cy, radius.saturating_mul(2), radius, shading);
+}
+
+
+/// Draw antialiased `cells` inside `screen` with their coverage shaded by `shading`.
+fn shade(screen: Rect, cells: Vec<(isize, isize, f64)>, shading: Shading) {
+    for (x, y, coverage) in cells {
+        match shading {
+            Shading::Ramp { ramp, ccode } => {
+                let c = raster::ramp_char(ramp, coverage);
+                if c != ' ' {
+                    pixel_in(screen, x, y, c, ccode);
+                }
+            },
+            Shading::Blend { c, color, background } => {
+                pixel_in(screen, x, y, c, &rgb_to_ccode(lerp_rgb(background, color, coverage), ColorKind::Fg));
+            }
         }
-        let ydist: isize = 2 * (k - y.round() as isize);
-        pixel(shiftx, y.round() as isize, c, ccode);
-        pixel(shiftx, (y.round() as isize + ydist) as isize, c, ccode);
     }
 }
 
 
-/// Draw `text` starting at `(x, y)`.
+/// Draw `text` starting at `(x, y)`, sanitized with `chars::sanitize_text`.
 /// 
 /// Example
 /// ```
 /// use terminalgl as tgl;
 /// let s = String::from("sample text");
 /// tgl::drawc::text(1, 1, &s, tgl::drawc::RED);
+/// tgl::drawc::text(isize::MAX - 3, 1, &s, tgl::drawc::RED);
 /// ```
 pub fn text(x: isize, y: isize, text: &str, ccode: &str) {
-    for (i, c) in text.chars().enumerate() {
-        pixel(x+i as isize, y, c, ccode);
+    for (i, c) in chars::sanitize_text(text).enumerate() {
+        pixel(x.saturating_add(i as isize), y, c, ccode);
+    }
+}
+
+
+/// Draw `spans` of text one after another starting at `(x, y)`, each `(text, ccode)` drawn like `text`.
+/// This is how to style parts of a line: escape sequences embedded in text are never printed.
+///
+/// Example
+/// ```
+/// use terminalgl as tgl;
+/// tgl::drawc::spans(1, 1, &[("hp ", ""), ("12", tgl::drawc::RED), ("/20", tgl::drawc::BRIGHT_BLACK)]);
+/// tgl::drawc::spans(1, 2, &[("\x1b[2J", tgl::drawc::RED), ("\x1b]0;title\x07", "")]);
+/// ```
+pub fn spans(x: isize, y: isize, spans: &[(&str, &str)]) {
+    let mut x = x;
+    for &(span, ccode) in spans {
+        text(x, y, span, ccode);
+        x = x.saturating_add(chars::sanitize_text(span).count() as isize);
+    }
+}
+
+
+/// Split `text` into runs of characters that share a color, as `(column, run, color)` with `column` counted in cells from the start.
+/// Each character gets the color `color_at` gives for where its middle falls across the text's width, from `0.0` at the first cell
+/// to `1.0` at the last, a grapheme at a time (see `raster::graphemes`), with wide ones counting double.
+///
+/// Example
+/// ```
+/// use terminalgl::drawc;
+/// let runs = drawc::gradient_runs("ab漢c", |t| if t < 0.5 { (255, 0, 0) } else { (0, 0, 255) });
+/// assert_eq!(runs, vec![(0, String::from("ab"), (255, 0, 0)), (2, String::from("漢c"), (0, 0, 255))]);
+/// let runs = drawc::gradient_runs("abcd", |t| drawc::lerp_rgb((0, 0, 0), (255, 255, 255), t));
+/// assert_eq!(runs.iter().map(|run| run.2).collect::<Vec<_>>(), vec![(0, 0, 0), (85, 85, 85), (170, 170, 170), (255, 255, 255)]);
+/// ```
+pub fn gradient_runs(text: &str, color_at: impl Fn(f64) -> (u8, u8, u8)) -> Vec<(usize, String, (u8, u8, u8))> {
+    let span = raster::text_width(text).saturating_sub(1).max(1) as f64;
+    let mut runs: Vec<(usize, String, (u8, u8, u8))> = Vec::new();
+    let mut column = 0;
+    for grapheme in raster::graphemes(text) {
+        let width = raster::grapheme_width(grapheme);
+        let middle = column as f64 + width.saturating_sub(1) as f64 / 2.0;
+        let color = match runs.last() {
+            // Marks with nothing before them to combine with take no cells, so stay with the run before.
+            Some(last) if width == 0 => last.2,
+            _ => color_at(middle / span)
+        };
+        match runs.last_mut() {
+            Some(last) if last.2 == color => last.1.push_str(grapheme),
+            _ => runs.push((column, grapheme.to_string(), color))
+        }
+        column += width;
+    }
+    runs
+}
+
+
+/// Draw `text`, sanitized with `chars::sanitize_text`, starting at `(x, y)` in the colors `gradient_runs` gives it, printing each run's color once.
+/// Graphemes whose cells aren't all on the terminal are skipped.
+fn text_runs(x: isize, y: isize, text: &str, color_at: impl Fn(f64) -> (u8, u8, u8)) {
+    let screen = super::screen_rect();
+    let text: String = chars::sanitize_text(text).collect();
+    for (column, run, color) in gradient_runs(&text, color_at) {
+        let ccode = rgb_to_ccode(color, ColorKind::Fg);
+        let mut cx = x.saturating_add_unsigned(column);
+        let mut printing = false;
+        for grapheme in raster::graphemes(&run) {
+            let width = raster::grapheme_width(grapheme);
+            if width == 0 {
+                if printing {
+                    print!("{}", grapheme);
+                }
+                continue;
+            }
+            if screen.contains(cx, y) && screen.contains(cx.saturating_add_unsigned(width - 1), y) {
+                if !printing {
+                    super::cursorto(cx as usize, y as usize);
+                    print!("{}", checked(&ccode));
+                    printing = true;
+                }
+                print!("{}", grapheme);
+            }
+            else {
+                printing = false;
+            }
+            cx = cx.saturating_add_unsigned(width);
+        }
     }
 }
 
 
-/// Draw `text` starting at `(x, y)` with alignment `align`.
+/// Draw `text` starting at `(x, y)`, blending its color from `from_rgb` at the first character to `to_rgb` at the last.
+/// Characters are laid out by their width in cells, so CJK and emoji take two cells and the gradient stays even across them,
+/// and the color is only printed again when it changes.
+///
+/// Example
+/// ```
+/// use terminalgl as tgl;
+/// tgl::drawc::text_gradient(1, 1, "sample text", (255, 0, 0), (0, 0, 255));
+/// tgl::drawc::text_gradient(1, 2, "漢字 and emoji 🙂", (255, 255, 0), (0, 255, 255));
+/// tgl::drawc::text_gradient(isize::MAX - 3, 1, "sample text", (255, 0, 0), (0, 0, 255));
+/// ```
+pub fn text_gradient(x: isize, y: isize, text: &str, from_rgb: (u8, u8, u8), to_rgb: (u8, u8, u8)) {
+    text_runs(x, y, text, |t| lerp_rgb(from_rgb, to_rgb, t));
+}
+
+
+/// Draw `text` starting at `(x, y)` like `text_gradient`, colored along a gradient with color `stops` (see `gradient_stops`, and `WHITE` if empty),
+/// where position `0.0` is the first character and `1.0` the last.
+///
+/// Example
+/// ```
+/// use terminalgl as tgl;
+/// let stops = [(0.0, (255, 0, 0)), (0.3, (255, 255, 0)), (1.0, (0, 128, 255))];
+/// tgl::drawc::text_gradient_stops(1, 1, "sample text", &stops);
+/// tgl::drawc::text_gradient_stops(1, 2, "sample text", &[]);
+/// ```
+pub fn text_gradient_stops(x: isize, y: isize, text: &str, stops: &[(f64, (u8, u8, u8))]) {
+    text_runs(x, y, text, |t| gradient_stops(stops, t).unwrap_or(NamedColor::White.to_rgb()));
+}
+
+
+/// Draw `text` starting at `(x, y)` like `text_gradient`, with the hue going once around the color wheel across it.
+/// `phase` shifts the starting hue by that fraction of a turn, so increasing it each frame makes the colors scroll.
+///
+/// Example
+/// ```
+/// use terminalgl as tgl;
+/// tgl::drawc::text_rainbow(1, 1, "sample text", 0.0);
+/// tgl::drawc::text_rainbow(1, 2, "sample text", 0.25);
+/// ```
+pub fn text_rainbow(x: isize, y: isize, text: &str, phase: f64) {
+    text_runs(x, y, text, |t| hsv_to_rgb((phase + t) * 360.0, 1.0, 1.0));
+}
+
+
+/// Draw `text` starting at `(x, y)` with alignment `align`, sanitized with `chars::sanitize_text`.
 /// 
 /// Examples
 /// ```
@@ -220,15 +903,993 @@ pub fn text(x: isize, y: isize, text: &str, ccode: &str) {
 /// tgl::drawc::text_aligned(1, 1, "sample text", Left, tgl::drawc::RED);
 /// tgl::drawc::text_aligned(10, 3, "sample text", Center, tgl::drawc::GREEN);
 /// tgl::drawc::text_aligned(15, 5, "sample text", Right, tgl::drawc::BLUE);
+/// tgl::drawc::text_aligned(isize::MIN, 5, "sample text", Right, tgl::drawc::BLUE);
 /// ```
 pub fn text_aligned(x: isize, y: isize, text: &str, align: TextAlignment, ccode: &str) {
+    let text: String = chars::sanitize_text(text).collect();
     let mut x = x;
     match align {
         TextAlignment::Left => {},
-        TextAlignment::Center => x -= text.len() as isize / 2,
-        TextAlignment::Right => x -= text.len() as isize
+        TextAlignment::Center => x = x.saturating_sub(text.chars().count() as isize / 2),
+        TextAlignment::Right => x = x.saturating_sub(text.chars().count() as isize)
     }
     for (i, c) in text.chars().enumerate() {
-        pixel(x+i as isize, y, c, ccode);
+        pixel(x.saturating_add(i as isize), y, c, ccode);
+    }
+}
+
+
+/// Draw `text` read in `direction` at `(x, y)` with alignment `align` in color `ccode`, laid out with `raster::text_directed`,
+/// so right-to-left text reads correctly and its alignment is mirrored.
+///
+/// Example
+/// ```
+/// use terminalgl as tgl;
+/// use tgl::{TextAlignment, TextDirection};
+/// tgl::drawc::text_directed(20, 1, "שלום", TextAlignment::Left, TextDirection::Rtl, tgl::drawc::GREEN);
+/// tgl::drawc::text_directed(isize::MIN, 2, "مرحبا", TextAlignment::Right, TextDirection::Rtl, tgl::drawc::RED);
+/// ```
+pub fn text_directed(x: isize, y: isize, text: &str, align: TextAlignment, direction: TextDirection, ccode: &str) {
+    for (x, y, c) in raster::text_directed(x, y, text, align, direction) {
+        pixel(x, y, c, ccode);
+    }
+}
+
+
+/// Draw a polygon of `c` with vertices `points`, connecting the last vertex back to the first.
+/// Use `fill` to specify whether the polygon is outlined (`false`) or filled (`true`).
+/// 
+/// Example
+/// ```
+/// use terminalgl as tgl;
+/// tgl::drawc::polygon(&[(1, 1), (8, 2), (4, 6)], '#', tgl::drawc::RED, false);
+/// tgl::drawc::polygon(&[(isize::MIN, 0), (isize::MAX, 2), (0, isize::MAX)], '#', tgl::drawc::RED, true);
+/// ```
+pub fn polygon(points: &[(isize, isize)], c: char, ccode: &str, fill: bool) {
+    if fill {
+        let screen = super::screen_rect();
+        for (y, x1, x2) in raster::polygon_spans_clipped(points, screen) {
+            for x in x1..=x2 {
+                pixel_in(screen, x, y, c, ccode);
+            }
+        }
+    }
+    for i in 0..points.len() {
+        let (x1, y1) = points[i];
+        let (x2, y2) = points[(i+1) % points.len()];
+        line(x1, y1, x2, y2, c, ccode);
+    }
+}
+
+
+/// Draw a regular polygon of `c` centered at `(cx, cy)` with `sides` sides and radius `radius`.
+/// `rotation` is in radians, `sides` is clamped to a minimum of 3, and vertical distances are scaled by `aspect`
+/// (see `raster::regular_polygon_points`), so `0.5` looks regular in most terminal fonts.
+/// Use `fill` to specify whether the polygon is outlined (`false`) or filled (`true`).
+/// 
+/// Example
+/// ```
+/// use terminalgl as tgl;
+/// tgl::drawc::regular_polygon(8, 8, 6, 5, 0.0, 0.5, '#', tgl::drawc::GREEN, false);
+/// ```
+pub fn regular_polygon(cx: isize, cy: isize, radius: usize, sides: usize, rotation: f64, aspect: f64, c: char, ccode: &str, fill: bool) {
+    polygon(&raster::regular_polygon_points(cx, cy, radius, sides, rotation, aspect), c, ccode, fill);
+}
+
+
+/// Draw a star of `c` centered at `(cx, cy)` with `points` points.
+/// The tips lie on `outer_radius` and the inner corners on `inner_radius`.
+/// `rotation` is in radians, `points` is clamped to a minimum of 3, and vertical distances are scaled by `aspect`.
+/// Use `fill` to specify whether the star is outlined (`false`) or filled (`true`).
+/// 
+/// Example
+/// ```
+/// use terminalgl as tgl;
+/// tgl::drawc::star(10, 8, 7, 3, 5, 0.0, 0.5, '*', tgl::drawc::YELLOW, true);
+/// ```
+pub fn star(cx: isize, cy: isize, outer_radius: usize, inner_radius: usize, points: usize, rotation: f64, aspect: f64, c: char, ccode: &str, fill: bool) {
+    polygon(&raster::star_points(cx, cy, outer_radius, inner_radius, points, rotation, aspect), c, ccode, fill);
+}
+
+
+/// Draw an arrow of `c` from `(x1, y1)` to `(x2, y2)` with an arrowhead of style `head` at `(x2, y2)`.
+/// A zero-length arrow draws just the head.
+/// 
+/// Examples
+/// ```
+/// use terminalgl as tgl;
+/// use tgl::ArrowHead;
+/// tgl::drawc::arrow(1, 1, 12, 1, '-', ArrowHead::Char, tgl::drawc::RED);
+/// tgl::drawc::arrow(1, 3, 12, 8, '*', ArrowHead::Chevron, tgl::drawc::RED);
+/// tgl::drawc::arrow(20, 10, 20, 2, '|', ArrowHead::Triangle(2), tgl::drawc::RED);
+/// tgl::drawc::arrow(isize::MIN, 0, 10, 10, '|', ArrowHead::Triangle(usize::MAX), tgl::drawc::RED);
+/// ```
+pub fn arrow(x1: isize, y1: isize, x2: isize, y2: isize, c: char, head: ArrowHead, ccode: &str) {
+    line(x1, y1, x2, y2, c, ccode);
+    let screen = super::screen_rect();
+    for (x, y, hc) in raster::arrow_head_clipped(x1, y1, x2, y2, head, c, screen) {
+        pixel_in(screen, x, y, hc, ccode);
+    }
+}
+
+
+/// Draw an arrow of `c` between `(x1, y1)` and `(x2, y2)` with an arrowhead of style `head` at both ends.
+/// 
+/// Example
+/// ```
+/// use terminalgl as tgl;
+/// use tgl::ArrowHead;
+/// tgl::drawc::double_arrow(1, 1, 12, 1, '-', ArrowHead::Char, tgl::drawc::RED);
+/// ```
+pub fn double_arrow(x1: isize, y1: isize, x2: isize, y2: isize, c: char, head: ArrowHead, ccode: &str) {
+    arrow(x1, y1, x2, y2, c, head, ccode);
+    let screen = super::screen_rect();
+    for (x, y, hc) in raster::arrow_head_clipped(x2, y2, x1, y1, head, c, screen) {
+        pixel_in(screen, x, y, hc, ccode);
+    }
+}
+
+
+/// Draw an orthogonal connector between the nearest edges of `from` and `to` using box-drawing characters.
+/// The connector bends once or twice at the midpoint, and `style` controls ASCII fallback and the arrowhead.
+/// 
+/// Example
+/// ```
+/// use terminalgl as tgl;
+/// use tgl::{ConnectorStyle, Rect};
+/// let a = Rect::new(1, 1, 8, 3);
+/// let b = Rect::new(16, 6, 8, 3);
+/// tgl::drawc::connector(a, b, ConnectorStyle { ascii: false, arrow: true }, tgl::drawc::CYAN);
+/// tgl::drawc::connector(a, Rect::new(isize::MAX - 1, 2, usize::MAX, 3), ConnectorStyle::default(), tgl::drawc::CYAN);
+/// ```
+pub fn connector(from: Rect, to: Rect, style: ConnectorStyle, ccode: &str) {
+    let screen = super::screen_rect();
+    for (x, y, c) in raster::connector_clipped(from, to, style, screen) {
+        pixel_in(screen, x, y, c, ccode);
+    }
+}
+
+
+/// Draw the tree `root` with its root label at `(x, y)` and one row per node below it,
+/// connecting children to their parents with `├──`, `└──` and `│` guides.
+/// Guides are drawn with `ccode`, and labels with their node's `ccode` if it has one.
+/// 
+/// Example
+/// ```
+/// use terminalgl as tgl;
+/// use tgl::{TreeNode, TreeStyle};
+/// let mut root = TreeNode::new("src", vec![TreeNode::leaf("draw.rs"), TreeNode::leaf("lib.rs")]);
+/// root.children[1].ccode = Some(tgl::drawc::GREEN.to_string());
+/// tgl::drawc::tree(1, 1, &root, TreeStyle::default(), tgl::drawc::BRIGHT_BLACK);
+/// ```
+pub fn tree(x: isize, y: isize, root: &TreeNode, style: TreeStyle, ccode: &str) {
+    for (i, (guide, label, node)) in raster::tree_rows(root, style).into_iter().enumerate() {
+        let row = y.saturating_add(i as isize);
+        text(x, row, &guide, ccode);
+        let label_ccode = node.and_then(|n| n.ccode.as_deref()).unwrap_or(ccode);
+        text(x.saturating_add(guide.chars().count() as isize), row, &label, label_ccode);
+    }
+}
+
+
+/// Draw the calendar for `month` (1 to 12) of `year` with its top left corner at `(x, y)`.
+/// The calendar is always `raster::CALENDAR_WIDTH` (20) columns wide, and 6 to 8 rows tall:
+/// a title, the weekday headers, and one row per week.
+/// Days in `opts.marked` are drawn with `marked_ccode`, and `opts.today` with `today_ccode`.
+/// 
+/// Example
+/// ```
+/// use terminalgl as tgl;
+/// let opts = tgl::CalendarOptions { monday_first: true, today: Some(14), marked: vec![1, 29] };
+/// let today = tgl::drawc::BLACK.to_string() + tgl::drawc::WHITE_BG;
+/// tgl::drawc::calendar(1, 1, 2024, 2, &opts, tgl::drawc::WHITE, &today, tgl::drawc::RED);
+/// ```
+pub fn calendar(x: isize, y: isize, year: i32, month: u32, opts: &CalendarOptions, ccode: &str, today_ccode: &str, marked_ccode: &str) {
+    for (i, row) in raster::calendar_rows(year, month, opts.monday_first).iter().enumerate() {
+        text(x, y.saturating_add(i as isize), row, ccode);
+    }
+    let highlights = opts.marked.iter().map(|&d| (d, marked_ccode)).chain(opts.today.map(|d| (d, today_ccode)));
+    for (day, day_ccode) in highlights {
+        if let Some((col, row)) = raster::calendar_position(year, month, day, opts.monday_first) {
+            text(x.saturating_add(col as isize), y.saturating_add(row as isize), &format!("{:>2}", day), day_ccode);
+        }
+    }
+    print!("{}", RESET);
+}
+
+
+/// Draw an elliptical arc of `c` centered at `(h, k)` with width `a` and height `b`, from angle `start` to angle `end`.
+/// Angles are in radians and increase clockwise, so `0` points right and `PI / 2` points down.
+/// 
+/// Example
+/// ```
+/// use terminalgl as tgl;
+/// use std::f64::consts::PI;
+/// tgl::drawc::arc(10, 6, 8, 4, PI, 2.0 * PI, '#', tgl::drawc::BLUE);
+/// tgl::drawc::arc(10, 6, usize::MAX, 4, PI, 2.0 * PI, '#', tgl::drawc::BLUE);
+/// ```
+pub fn arc(h: isize, k: isize, a: usize, b: usize, start: f64, end: f64, c: char, ccode: &str) {
+    let screen = super::screen_rect();
+    for (x, y) in raster::arc_clipped(h, k, a, b, start, end, screen) {
+        pixel_in(screen, x, y, c, ccode);
+    }
+}
+
+
+/// Draw a dial centered at `(cx, cy)` with radius `radius` filled to `fraction` (clamped to `0.0..=1.0`), laid out with
+/// `raster::gauge_clipped`. The unfilled part is drawn with `ccode`, and the filled part, needle, and label with `fill_ccode`.
+/// 
+/// Example
+/// ```
+/// use terminalgl as tgl;
+/// let opts = tgl::GaugeOptions { needle: Some('*'), ..Default::default() };
+/// tgl::drawc::gauge(12, 6, 5, 0.42, opts, tgl::drawc::BRIGHT_BLACK, tgl::drawc::GREEN);
+/// tgl::drawc::gauge(isize::MAX, isize::MIN, usize::MAX, 0.42, opts, tgl::drawc::BRIGHT_BLACK, tgl::drawc::GREEN);
+/// ```
+pub fn gauge(cx: isize, cy: isize, radius: usize, fraction: f64, opts: GaugeOptions, ccode: &str, fill_ccode: &str) {
+    let screen = super::screen_rect();
+    for (x, y, c, filled) in raster::gauge_clipped(cx, cy, radius, fraction, opts, screen) {
+        pixel_in(screen, x, y, c, if filled { fill_ccode } else { ccode });
+    }
+}
+
+
+/// Draw `chart` inside `rect`, with the title, axes, and labels drawn with `ccode` and each series with its own color.
+/// 
+/// Example
+/// ```
+/// use terminalgl as tgl;
+/// use tgl::chart::{Chart, Series};
+/// let mut chart = Chart::new();
+/// chart.title = Some(String::from("sin and cos"));
+/// let sin = (0..50).map(|i| (i as f64 / 5.0, (i as f64 / 5.0).sin())).collect();
+/// let cos = (0..50).map(|i| (i as f64 / 5.0, (i as f64 / 5.0).cos())).collect();
+/// chart.series.push(Series::new("sin", sin, tgl::drawc::RED));
+/// chart.series.push(Series::new("cos", cos, tgl::drawc::CYAN));
+/// tgl::drawc::chart(tgl::Rect::new(0, 0, 40, 12), &chart, tgl::drawc::WHITE);
+/// ```
+pub fn chart(rect: Rect, chart: &Chart, ccode: &str) {
+    for (x, y, c, series) in chart.cells(rect) {
+        let series_ccode = series.map(|i| chart.series[i].ccode.as_str()).unwrap_or(ccode);
+        pixel(x, y, c, series_ccode);
+    }
+}
+
+
+/// Draw candlesticks for `data` inside `rect`, using `up_ccode` for candles that closed at or above their open
+/// and `down_ccode` for the rest. When there are more candles than fit in `rect`, only the most recent ones are drawn.
+/// 
+/// Example
+/// ```
+/// use terminalgl as tgl;
+/// use tgl::chart::{CandleOptions, Ohlc};
+/// let data = [Ohlc::new(10.0, 12.5, 9.0, 12.0), Ohlc::new(12.0, 13.0, 10.5, 11.0), Ohlc::new(11.0, 14.0, 11.0, 13.5)];
+/// let opts = CandleOptions { width: 3, gap: 1, y_range: None };
+/// tgl::drawc::candles(tgl::Rect::new(1, 1, 20, 10), &data, opts, tgl::drawc::GREEN, tgl::drawc::RED);
+/// ```
+pub fn candles(rect: Rect, data: &[Ohlc], opts: CandleOptions, up_ccode: &str, down_ccode: &str) {
+    for (x, y, c, up) in chart::candle_cells(rect, data, opts) {
+        pixel(x, y, c, if up { up_ccode } else { down_ccode });
+    }
+}
+
+
+
+/// Draw an oscilloscope trace of `samples` inside `rect` with `ccode`, from `-1` at the bottom to `1` at the top (see `chart::waveform_cells`).
+/// Every column shows the full swing of the samples falling in it, so short transients stay visible however long the buffer.
+///
+/// Example
+/// ```
+/// use terminalgl as tgl;
+/// use tgl::chart::WaveformOptions;
+/// let samples: Vec<f32> = (0..4800).map(|i| (i as f32 / 40.0).sin() * (-(i as f32) / 2000.0).exp()).collect();
+/// let opts = WaveformOptions { mirror: false, baseline: true };
+/// tgl::drawc::waveform(tgl::Rect::new(1, 1, 60, 9), &samples, opts, tgl::drawc::GREEN);
+/// ```
+pub fn waveform(rect: Rect, samples: &[f32], opts: WaveformOptions, ccode: &str) {
+    for (x, y, c) in chart::waveform_cells(rect, samples, opts) {
+        pixel(x, y, c, ccode);
+    }
+}
+
+
+/// Draw a bar spectrum of `magnitudes`, one per frequency bin as an FFT gives them, inside `rect` with `ccode` (see `chart::spectrum_cells`).
+///
+/// Example
+/// ```
+/// use terminalgl as tgl;
+/// use tgl::chart::{Scale, SpectrumOptions};
+/// let magnitudes: Vec<f32> = (0..512).map(|bin| 1.0 / (1.0 + bin as f32 / 16.0)).collect();
+/// tgl::drawc::spectrum(tgl::Rect::new(1, 1, 60, 8), &magnitudes, SpectrumOptions { scale: Scale::Log10 }, tgl::drawc::CYAN);
+/// ```
+pub fn spectrum(rect: Rect, magnitudes: &[f32], opts: SpectrumOptions, ccode: &str) {
+    for (x, y, c) in chart::spectrum_cells(rect, magnitudes, opts) {
+        pixel(x, y, c, ccode);
+    }
+}
+
+/// A series for `scatter_series` as `(points, marker, ccode)`.
+pub type ScatterSeries<'a> = (&'a [(f64, f64)], char, &'a str);
+
+
+/// Draw a scatter plot of `points` inside `rect`, with each point drawn as `marker`.
+/// 
+/// Example
+/// ```
+/// use terminalgl as tgl;
+/// use tgl::chart::ScatterOptions;
+/// let points: Vec<(f64, f64)> = (0..100).map(|i| (i as f64, (i * 37 % 100) as f64)).collect();
+/// tgl::drawc::scatter(tgl::Rect::new(1, 1, 30, 10), &points, 'o', ScatterOptions::default(), tgl::drawc::YELLOW);
+/// ```
+pub fn scatter(rect: Rect, points: &[(f64, f64)], marker: char, opts: ScatterOptions, ccode: &str) {
+    scatter_series(rect, &[(points, marker, ccode)], opts);
+}
+
+
+/// Draw a scatter plot of several series inside `rect`, given as `(points, marker, ccode)` triplets.
+/// All series share the same axis ranges.
+/// 
+/// Example
+/// ```
+/// use terminalgl as tgl;
+/// use tgl::chart::{ScatterMode, ScatterOptions};
+/// let a: Vec<(f64, f64)> = (0..50).map(|i| (i as f64, i as f64)).collect();
+/// let b: Vec<(f64, f64)> = (0..50).map(|i| (i as f64, 50.0 - i as f64)).collect();
+/// let opts = ScatterOptions { mode: ScatterMode::Braille, ..Default::default() };
+/// tgl::drawc::scatter_series(tgl::Rect::new(1, 1, 30, 10), &[(&a, 'o', tgl::drawc::RED), (&b, 'x', tgl::drawc::BLUE)], opts);
+/// ```
+pub fn scatter_series(rect: Rect, series: &[ScatterSeries], opts: ScatterOptions) {
+    let markers: Vec<(&[(f64, f64)], char)> = series.iter().map(|s| (s.0, s.1)).collect();
+    for (x, y, c, i) in chart::scatter_cells(rect, &markers, opts) {
+        pixel(x, y, c, series[i].2);
+    }
+}
+
+
+/// Draw axes spanning `x_range` and `y_range` inside `rect`, with tick marks at "nice" intervals and labels that never overlap.
+/// Returns the transform from data coordinates to cells, whose `plot` is the plotting area inside the axes.
+/// 
+/// Example
+/// ```
+/// use terminalgl as tgl;
+/// use tgl::chart::AxisOptions;
+/// let transform = tgl::drawc::axes(tgl::Rect::new(0, 0, 40, 12), (0.0, 10.0), (-1.0, 1.0), AxisOptions::default(), tgl::drawc::WHITE);
+/// if let Some((cx, cy)) = transform.to_cell(5.0, 0.5) {
+///     tgl::drawc::pixel(cx, cy, '*', tgl::drawc::RED);
+/// }
+/// ```
+pub fn axes(rect: Rect, x_range: (f64, f64), y_range: (f64, f64), opts: AxisOptions, ccode: &str) -> Transform {
+    let (cells, transform) = chart::axes_cells(rect, x_range, y_range, opts);
+    for (x, y, c) in cells {
+        pixel(x, y, c, ccode);
+    }
+    transform
+}
+
+
+/// Draw a bordered, word-wrapped tooltip for `text` pointing at `(anchor_x, anchor_y)`, and get the rectangle it covers.
+/// The tooltip flips above or to the left of the anchor when it would run off the terminal.
+/// Draw over the returned rectangle, or restore it from a `canvas::Snapshot`, to erase the tooltip.
+/// 
+/// Example
+/// ```
+/// use terminalgl as tgl;
+/// let covered = tgl::drawc::tooltip(5, 2, "Size: 4.2 KiB, modified yesterday", tgl::drawc::BLACK);
+/// tgl::drawc::tooltip(isize::MAX, isize::MIN, "Off the terminal", tgl::drawc::BLACK);
+/// tgl::drawc::rectangle(covered.x, covered.y, covered.width, covered.height, ' ', tgl::drawc::RESET, true);
+/// ```
+pub fn tooltip(anchor_x: isize, anchor_y: isize, text: &str, ccode: &str) -> Rect {
+    let screen = super::screen_rect();
+    let (rect, cells) = raster::tooltip(anchor_x, anchor_y, text, screen);
+    for (x, y, c) in cells {
+        pixel_in(screen, x, y, c, ccode);
+    }
+    rect
+}
+
+
+/// Draw `text` starting at `(x, y)` with `ccode` over a shadow: a copy of it drawn first with `shadow_ccode`, moved by `offset`
+/// (usually `(1, 1)`). The shadow skips the cells the text covers, so no cell is printed twice.
+/// 
+/// Example
+/// ```
+/// use terminalgl as tgl;
+/// tgl::drawc::text_shadowed(1, 1, "sample text", tgl::drawc::BRIGHT_WHITE, tgl::drawc::BRIGHT_BLACK, (1, 1));
+/// tgl::drawc::text_shadowed(isize::MAX - 3, 1, "sample text", tgl::drawc::BRIGHT_WHITE, tgl::drawc::BRIGHT_BLACK, (1, 1));
+/// ```
+pub fn text_shadowed(x: isize, y: isize, text: &str, ccode: &str, shadow_ccode: &str, offset: (isize, isize)) {
+    for (x, y, c) in raster::text_shadow(x, y, text, offset) {
+        pixel(x, y, c, shadow_ccode);
+    }
+    self::text(x, y, text, ccode);
+}
+
+
+/// Draw `text` starting at `(x, y)` with `ccode`, outlined by copies of it drawn first with `outline_ccode` in the 8 cells around
+/// each character, which keeps labels readable over busy backgrounds. Each outline cell is printed once, and never under the text.
+/// 
+/// Example
+/// ```
+/// use terminalgl as tgl;
+/// tgl::drawc::text_outlined(2, 2, "label", tgl::drawc::BRIGHT_YELLOW, tgl::drawc::BLACK);
+/// ```
+pub fn text_outlined(x: isize, y: isize, text: &str, ccode: &str, outline_ccode: &str) {
+    for (x, y, c) in raster::text_outline(x, y, text) {
+        pixel(x, y, c, outline_ccode);
+    }
+    self::text(x, y, text, ccode);
+}
+
+
+/// Draw the shadow a panel covering `rect` casts down and to the right, in `c` with `ccode`, using `'░'` or a half block
+/// like `'▀'` for `c`. Only the column right of `rect` and the row below it are drawn, so draw the panel before or after.
+/// 
+/// Example
+/// ```
+/// use terminalgl as tgl;
+/// let panel = tgl::Rect::new(2, 1, 20, 6);
+/// tgl::drawc::rectangle(panel.x, panel.y, panel.width, panel.height, ' ', tgl::drawc::BLUE_BG, true);
+/// tgl::drawc::box_shadow(panel, '░', tgl::drawc::BRIGHT_BLACK);
+/// tgl::drawc::box_shadow(tgl::Rect::new(-5, -5, usize::MAX, 10), '░', tgl::drawc::BRIGHT_BLACK);
+/// ```
+pub fn box_shadow(rect: Rect, c: char, ccode: &str) {
+    let screen = super::screen_rect();
+    for (x, y) in raster::box_shadow_clipped(rect, screen) {
+        pixel_in(screen, x, y, c, ccode);
+    }
+}
+
+
+/// Draw a path of `c` through the cells `points`, connecting consecutive cells with lines.
+/// 
+/// Example
+/// ```
+/// use terminalgl as tgl;
+/// tgl::drawc::path(&[(1, 1), (2, 1), (3, 2), (3, 3)], '*', tgl::drawc::GREEN);
+/// ```
+pub fn path(points: &[(isize, isize)], c: char, ccode: &str) {
+    if let [(x, y)] = points {
+        pixel(*x, *y, c, ccode);
+    }
+    for pair in points.windows(2) {
+        line(pair[0].0, pair[0].1, pair[1].0, pair[1].1, c, ccode);
+    }
+}
+
+
+/// Draw fractal noise over `rect` as characters from `ramp`, with features about `scale` cells across,
+/// colored along the gradient `colors` (low values take the first color and high values the last).
+/// 
+/// Example
+/// ```
+/// use terminalgl as tgl;
+/// let terrain = [(0, 0, 128), (0, 160, 0), (128, 128, 128), (255, 255, 255)];
+/// tgl::drawc::noise_field(tgl::Rect::new(0, 0, 40, 10), 8.0, 1, "~~..^^", &terrain);
+/// ```
+pub fn noise_field(rect: Rect, scale: f64, seed: u64, ramp: &str, colors: &[(u8, u8, u8)]) {
+    for (x, y, v) in noise::field(rect, scale, seed, (0.0, 0.0)) {
+        let ccode = gradient(colors, v).map(|rgb| rgb_to_ccode(rgb, ColorKind::Fg)).unwrap_or_else(|| RESET.to_string());
+        pixel(x, y, raster::ramp_char(ramp, v), &ccode);
+    }
+    print!("{}", RESET);
+}
+
+
+/// Draw the row-major luminance buffer `luminance` (`0.0` to `1.0`), `src_width` samples wide, as `width` by `height`
+/// characters from `ramp` at `(x, y)`, like `draw::ascii_image`.
+/// If `rgb` is given, it must be the same size as `luminance`, and each character is drawn in its resampled truecolor color;
+/// otherwise characters are drawn with `ccode`.
+/// 
+/// Example
+/// ```
+/// use terminalgl as tgl;
+/// let luminance: Vec<f64> = (0..64).map(|i| (i % 8) as f64 / 7.0).collect();
+/// let rgb: Vec<(u8, u8, u8)> = (0..64).map(|i| ((i % 8) as u8 * 32, 64, 255 - (i / 8) as u8 * 32)).collect();
+/// tgl::drawc::ascii_image(0, 0, 16, 4, &luminance, 8, tgl::raster::DENSITY_RAMP, Some(&rgb), "");
+/// tgl::drawc::ascii_image(0, 4, 16, 4, &luminance, 8, tgl::raster::DENSITY_RAMP, None, tgl::drawc::GREEN);
+/// ```
+#[cfg(feature = "image")]
+pub fn ascii_image(x: isize, y: isize, width: usize, height: usize, luminance: &[f64], src_width: usize, ramp: &str, rgb: Option<&[(u8, u8, u8)]>, ccode: &str) {
+    let rows = raster::ascii_rows(luminance, src_width, width, height, ramp);
+    let colors = rgb.map(|rgb| {
+        let channel = |f: fn(&(u8, u8, u8)) -> u8| {
+            let values: Vec<f64> = rgb.iter().map(|c| f(c) as f64).collect();
+            raster::resample(&values, src_width, width, height)
+        };
+        (channel(|c| c.0), channel(|c| c.1), channel(|c| c.2))
+    });
+    for (row, line) in rows.iter().enumerate() {
+        for (col, c) in line.chars().enumerate() {
+            let i = row * width + col;
+            let ccode = match &colors {
+                Some((r, g, b)) if i < r.len() => rgb_to_ccode((r[i].round() as u8, g[i].round() as u8, b[i].round() as u8), ColorKind::Fg),
+                _ => ccode.to_string()
+            };
+            pixel(x.saturating_add(col as isize), y.saturating_add(row as isize), c, &ccode);
+        }
+    }
+    print!("{}", RESET);
+}
+
+
+/// Draw the row-major RGBA pixels `rgba`, `width` by `height` pixels, with half blocks at `(x, y)`, scaled to `cols` by `rows` cells
+/// as `size`. Each cell shows two pixels, one above the other, and pixels with less than half alpha are left out.
+/// Nothing is drawn if `rgba` holds fewer than `width * height` pixels.
+///
+/// Example
+/// ```
+/// use terminalgl as tgl;
+/// let rgba: Vec<u8> = (0..16 * 16).flat_map(|i| [(i % 16 * 16) as u8, (i / 16 * 16) as u8, 128, 255]).collect();
+/// tgl::drawc::image_blocks(0, 0, &rgba, 16, 16, (16, 8));
+/// tgl::drawc::image_blocks(-8, 20, &rgba, 16, 16, (8, 8));
+/// ```
+pub fn image_blocks(x: isize, y: isize, rgba: &[u8], width: usize, height: usize, size: (usize, usize)) {
+    let (cols, rows) = size;
+    if width == 0 || height == 0 || rgba.len() / 4 / width < height {
+        return;
+    }
+    // The pixel nearest the middle of each half cell, or `None` if it is transparent.
+    let sample = |col: usize, half_row: usize| {
+        let px = ((2 * col + 1) * width / (2 * cols)).min(width - 1);
+        let py = ((2 * half_row + 1) * height / (4 * rows)).min(height - 1);
+        let p = &rgba[(py * width + px) * 4..][..4];
+        (p[3] >= 128).then_some((p[0], p[1], p[2]))
+    };
+    for row in 0..rows {
+        for col in 0..cols {
+            let (cx, cy) = (x.saturating_add_unsigned(col), y.saturating_add_unsigned(row));
+            match (sample(col, 2 * row), sample(col, 2 * row + 1)) {
+                (Some(top), Some(bottom)) => pixel(cx, cy, '▀', &(rgb_to_ccode(top, ColorKind::Fg) + &rgb_to_ccode(bottom, ColorKind::Bg))),
+                (Some(top), None) => pixel(cx, cy, '▀', &rgb_to_ccode(top, ColorKind::Fg)),
+                (None, Some(bottom)) => pixel(cx, cy, '▄', &rgb_to_ccode(bottom, ColorKind::Fg)),
+                (None, None) => {}
+            }
+        }
+    }
+    print!("{}", RESET);
+}
+
+
+/// How `image_native` shows an image: with the kitty graphics protocol, iTerm2's inline images, sixels, or `image_blocks`.
+#[cfg(feature = "image")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ImageProtocol {
+    Kitty,
+    Iterm2,
+    Sixel,
+    HalfBlocks
+}
+
+
+/// Options for `image_native`. `cols` and `rows` set the size in cells, with a missing one worked out from the other to keep
+/// the image's shape and both missing showing it at one image pixel per screen pixel. `protocol` overrides `image_protocol`.
+#[cfg(feature = "image")]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ImageOptions {
+    pub cols: Option<usize>,
+    pub rows: Option<usize>,
+    pub protocol: Option<ImageProtocol>
+}
+
+
+/// Set once a kitty image has been shown, so `delete_images` only prints when there is something to delete.
+#[cfg(feature = "image")]
+static KITTY_IMAGES_SHOWN: std::sync::atomic::AtomicBool = std::sync::atomic::AtomicBool::new(false);
+
+
+/// Set by `set_sixel_support`.
+#[cfg(feature = "image")]
+static SIXEL_SUPPORTED: std::sync::atomic::AtomicBool = std::sync::atomic::AtomicBool::new(false);
+
+
+/// Get the best way the terminal can show images, going by the environment variables terminals set:
+/// `KITTY_WINDOW_ID`, a `TERM` of `xterm-kitty`, or a `TERM_PROGRAM` of `WezTerm` or `ghostty` for `Kitty`,
+/// a `TERM_PROGRAM` of `iTerm.app` or an `LC_TERMINAL` of `iTerm2` for `Iterm2`, and a `TERM` of `mlterm` or `foot` for `Sixel`.
+/// Other terminals with sixels, such as xterm, can't be told apart by their environment, so they get `Sixel` only after
+/// `set_sixel_support(true)`, for example with the answer from `input::query_sixel_support`. Anything else gets `HalfBlocks`.
+#[cfg(feature = "image")]
+pub fn image_protocol() -> ImageProtocol {
+    let var = |name: &str| std::env::var(name).unwrap_or_default();
+    if !var("KITTY_WINDOW_ID").is_empty() || var("TERM") == "xterm-kitty" || ["WezTerm", "ghostty"].contains(&var("TERM_PROGRAM").as_str()) {
+        ImageProtocol::Kitty
+    }
+    else if var("TERM_PROGRAM") == "iTerm.app" || var("LC_TERMINAL") == "iTerm2" {
+        ImageProtocol::Iterm2
+    }
+    else if SIXEL_SUPPORTED.load(std::sync::atomic::Ordering::Relaxed) || ["mlterm", "foot"].contains(&var("TERM").as_str()) {
+        ImageProtocol::Sixel
+    }
+    else {
+        ImageProtocol::HalfBlocks
+    }
+}
+
+
+/// Say whether the terminal shows sixels, for `image_protocol`. Off until set.
+#[cfg(feature = "image")]
+pub fn set_sixel_support(supported: bool) {
+    SIXEL_SUPPORTED.store(supported, std::sync::atomic::Ordering::Relaxed);
+}
+
+
+/// Get whether the terminal can show real images, as found by `image_protocol`.
+#[cfg(feature = "image")]
+pub fn supports_native_images() -> bool {
+    image_protocol() != ImageProtocol::HalfBlocks
+}
+
+
+/// Get the size in pixels of one terminal cell, or `(8, 16)` if the terminal doesn't report it.
+#[cfg(feature = "image")]
+pub fn cell_pixels() -> (usize, usize) {
+    #[cfg(unix)]
+    {
+        // SAFETY: `winsize` is plain data, so zeroed is a valid value, and `TIOCGWINSZ` only writes a `winsize`.
+        let mut ws: libc::winsize = unsafe { std::mem::zeroed() };
+        let ok = unsafe { libc::ioctl(libc::STDOUT_FILENO, libc::TIOCGWINSZ, &mut ws) } == 0;
+        if ok && ws.ws_col > 0 && ws.ws_row > 0 && ws.ws_xpixel >= ws.ws_col && ws.ws_ypixel >= ws.ws_row {
+            return ((ws.ws_xpixel / ws.ws_col) as usize, (ws.ws_ypixel / ws.ws_row) as usize);
+        }
+    }
+    (8, 16)
+}
+
+
+/// Get the size in cells `(cols, rows)` that `image_native` shows a `width` by `height` pixel image at with `opts`,
+/// going by `cell_pixels`. Sizes worked out from the image are rounded up, and never less than one cell.
+///
+/// Example
+/// ```
+/// use terminalgl::drawc::{self, ImageOptions};
+/// // Not a terminal, so cells count as 8 by 16 pixels.
+/// assert_eq!(drawc::image_cells(64, 40, ImageOptions::default()), (8, 3));
+/// assert_eq!(drawc::image_cells(64, 64, ImageOptions { cols: Some(4), ..ImageOptions::default() }), (4, 2));
+/// assert_eq!(drawc::image_cells(64, 64, ImageOptions { rows: Some(4), ..ImageOptions::default() }), (8, 4));
+/// assert_eq!(drawc::image_cells(64, 64, ImageOptions { cols: Some(3), rows: Some(9), ..ImageOptions::default() }), (3, 9));
+/// ```
+#[cfg(feature = "image")]
+pub fn image_cells(width: usize, height: usize, opts: ImageOptions) -> (usize, usize) {
+    let (cell_w, cell_h) = cell_pixels();
+    let (width, height) = (width.max(1), height.max(1));
+    match (opts.cols, opts.rows) {
+        (Some(cols), Some(rows)) => (cols, rows),
+        (Some(cols), None) => (cols, (cols * cell_w * height).div_ceil(width * cell_h).max(1)),
+        (None, Some(rows)) => ((rows * cell_h * width).div_ceil(height * cell_w).max(1), rows),
+        (None, None) => (width.div_ceil(cell_w), height.div_ceil(cell_h))
+    }
+}
+
+
+/// Show the row-major RGBA pixels `rgba`, `width` by `height` pixels, as a real image with its top left corner at cell `(x, y)`,
+/// and get the cells it covers. The image is sent with `opts.protocol`, or `image_protocol` if that is `None`, and scaled to
+/// the size from `image_cells`. Images are drawn with `image_blocks` instead when the terminal can't show them or their top left corner
+/// is off the terminal, so callers can always use this. Nothing is drawn if `rgba` holds fewer than `width * height` pixels.
+///
+/// Kitty images stay on screen until `delete_images`, which `clear` calls, and iTerm2 images are cleared with the text under them.
+///
+/// Example
+/// ```
+/// use terminalgl as tgl;
+/// use tgl::drawc::{ImageOptions, ImageProtocol};
+/// let rgba: Vec<u8> = (0..32 * 32).flat_map(|i| [(i % 32 * 8) as u8, (i / 32 * 8) as u8, 200, 255]).collect();
+/// let covered = tgl::drawc::image_native(2, 1, &rgba, 32, 32, ImageOptions { cols: Some(8), ..ImageOptions::default() });
+/// assert_eq!(covered, tgl::Rect::new(2, 1, 8, 4));
+/// tgl::drawc::image_native(12, 1, &rgba, 32, 32, ImageOptions { protocol: Some(ImageProtocol::Kitty), ..ImageOptions::default() });
+/// tgl::clear();
+/// ```
+#[cfg(feature = "image")]
+pub fn image_native(x: isize, y: isize, rgba: &[u8], width: usize, height: usize, opts: ImageOptions) -> Rect {
+    let (cols, rows) = image_cells(width, height, opts);
+    let rect = Rect::new(x, y, cols, rows);
+    if width == 0 || height == 0 || rgba.len() / 4 / width < height {
+        return Rect::new(x, y, 0, 0);
+    }
+    let protocol = opts.protocol.unwrap_or_else(image_protocol);
+    if protocol == ImageProtocol::HalfBlocks || super::cursorto_checked(x, y).is_err() {
+        image_blocks(x, y, rgba, width, height, (cols, rows));
+        return rect;
+    }
+    if protocol == ImageProtocol::Kitty {
+        KITTY_IMAGES_SHOWN.store(true, std::sync::atomic::Ordering::Relaxed);
+    }
+    print!("{}", image_escape(rgba, width, height, (cols, rows), protocol));
+    rect
+}
+
+
+/// Get the escape sequence `image_native` prints to show an image with `protocol` scaled to `size` in cells,
+/// once the cursor is at its top left corner. `HalfBlocks` gets an empty string.
+///
+/// Kitty images are sent as raw RGBA in chunks of at most 4096 base64 characters, each marked with whether more follow,
+/// with replies turned off and the cursor left where it was. iTerm2 images are sent as one PNG.
+/// Sixel images are scaled to fill `size` going by `cell_pixels`, like `sixel_escape` with transparent pixels left out.
+///
+/// Example
+/// ```
+/// use terminalgl::drawc::{self, ImageProtocol};
+/// let rgba = vec![255; 64 * 64 * 4];
+/// let kitty = drawc::image_escape(&rgba, 64, 64, (8, 4), ImageProtocol::Kitty);
+/// let chunks: Vec<&str> = kitty.split_terminator("\x1b\\").collect();
+/// assert_eq!(chunks.len(), 6);
+/// assert!(chunks[0].starts_with("\x1b_Ga=T,f=32,s=64,v=64,c=8,r=4,C=1,q=2,m=1;"));
+/// assert!(chunks[1..5].iter().all(|c| c.starts_with("\x1b_Gm=1;") && c.len() == 7 + 4096));
+/// assert!(chunks[5].starts_with("\x1b_Gm=0;"));
+///
+/// let iterm = drawc::image_escape(&rgba, 64, 64, (8, 4), ImageProtocol::Iterm2);
+/// assert!(iterm.starts_with("\x1b]1337;File=inline=1;size="));
+/// assert!(iterm.contains(";width=8;height=4;preserveAspectRatio=0:iVBORw0KGgo"));
+/// assert!(iterm.ends_with('\x07'));
+/// ```
+#[cfg(feature = "image")]
+pub fn image_escape(rgba: &[u8], width: usize, height: usize, size: (usize, usize), protocol: ImageProtocol) -> String {
+    let pixels = &rgba[..(width * height * 4).min(rgba.len())];
+    match protocol {
+        ImageProtocol::Kitty => {
+            let data = base64(pixels);
+            let mut out = String::new();
+            let mut chunks = data.as_bytes().chunks(4096).peekable();
+            let mut first = true;
+            while let Some(chunk) = chunks.next() {
+                let more = chunks.peek().is_some() as u8;
+                let chunk = std::str::from_utf8(chunk).unwrap_or_default();
+                if first {
+                    out.push_str(&format!("\x1b_Ga=T,f=32,s={},v={},c={},r={},C=1,q=2,m={};{}\x1b\\", width, height, size.0, size.1, more, chunk));
+                    first = false;
+                }
+                else {
+                    out.push_str(&format!("\x1b_Gm={};{}\x1b\\", more, chunk));
+                }
+            }
+            out
+        },
+        ImageProtocol::Iterm2 => {
+            let png = png(pixels, width, height);
+            format!("\x1b]1337;File=inline=1;size={};width={};height={};preserveAspectRatio=0:{}\x07", png.len(), size.0, size.1, base64(&png))
+        },
+        ImageProtocol::Sixel => {
+            let (cell_w, cell_h) = cell_pixels();
+            let (out_w, out_h) = (size.0 * cell_w, size.1 * cell_h);
+            let scaled: Vec<Option<(u8, u8, u8)>> = (0..out_w * out_h).map(|i| {
+                let (px, py) = (i % out_w * width / out_w, i / out_w * height / out_h);
+                let p = pixels.get((py * width + px) * 4..(py * width + px) * 4 + 4)?;
+                (p[3] >= 128).then_some((p[0], p[1], p[2]))
+            }).collect();
+            sixel(&scaled, out_w, out_h)
+        },
+        ImageProtocol::HalfBlocks => String::new()
+    }
+}
+
+
+/// Show the row-major pixels `rgb`, `width` by `height` pixels, as a sixel image at one pixel per screen pixel with its top left corner
+/// at cell `(x, y)`, and get the cells it covers, going by `cell_pixels`. Like `image_native`, the image is drawn with `image_blocks` instead
+/// if its top left corner is off the terminal, and nothing is drawn if `rgb` holds fewer than `width * height` pixels.
+///
+/// Example
+/// ```
+/// use terminalgl as tgl;
+/// let rgb: Vec<(u8, u8, u8)> = (0..40 * 32).map(|i| ((i % 40 * 6) as u8, (i / 40 * 8) as u8, 90)).collect();
+/// assert_eq!(tgl::drawc::image_sixel(3, 2, &rgb, 40, 32), tgl::Rect::new(3, 2, 5, 2));
+/// ```
+#[cfg(feature = "image")]
+pub fn image_sixel(x: isize, y: isize, rgb: &[(u8, u8, u8)], width: usize, height: usize) -> Rect {
+    if width == 0 || height == 0 || rgb.len() / width < height {
+        return Rect::new(x, y, 0, 0);
+    }
+    let (cell_w, cell_h) = cell_pixels();
+    let (cols, rows) = (width.div_ceil(cell_w), height.div_ceil(cell_h));
+    if super::cursorto_checked(x, y).is_err() {
+        let rgba: Vec<u8> = rgb[..width * height].iter().flat_map(|&(r, g, b)| [r, g, b, 255]).collect();
+        image_blocks(x, y, &rgba, width, height, (cols, rows));
+    }
+    else {
+        print!("{}", sixel_escape(rgb, width, height));
+    }
+    Rect::new(x, y, cols, rows)
+}
+
+
+/// Get the sixel escape sequence `image_sixel` prints for the row-major pixels `rgb`, `width` by `height` pixels,
+/// with the colors reduced by `quantize` to at most 256. Each run of four or more matching sixels is written as a repeat.
+/// If `rgb` holds fewer than `width * height` pixels, or the image is empty, the sequence is empty.
+///
+/// Example
+/// ```
+/// use terminalgl::drawc;
+/// // Decode the sixels back into pixels, going by the palette the image defines.
+/// fn decode(sixel: &str) -> (usize, usize, Vec<(u8, u8, u8)>) {
+///     let body = &sixel[sixel.find('q').unwrap() + 1..sixel.rfind("\x1b\\").unwrap()];
+///     let (attributes, mut rest) = body[1..].split_at(body[1..].find('#').unwrap());
+///     let size: Vec<usize> = attributes.split(';').map(|n| n.parse().unwrap()).collect();
+///     let (width, height) = (size[2], size[3]);
+///     let mut pixels = vec![(0, 0, 0); width * height];
+///     let mut palette = vec![(0, 0, 0); 256];
+///     let (mut x, mut band, mut color) = (0, 0, 0);
+///     let number = |rest: &mut &str| {
+///         let end = rest.find(|c: char| !c.is_ascii_digit()).unwrap_or(rest.len());
+///         let n = rest[..end].parse::<usize>().unwrap();
+///         *rest = &rest[end..];
+///         n
+///     };
+///     while let Some(c) = rest.chars().next() {
+///         rest = &rest[1..];
+///         let mut count = 1;
+///         let c = match c {
+///             '#' => {
+///                 color = number(&mut rest);
+///                 if rest.starts_with(";2;") {
+///                     rest = &rest[3..];
+///                     let pct = |n: usize| ((n * 255 + 50) / 100) as u8;
+///                     let r = pct(number(&mut rest));
+///                     rest = &rest[1..];
+///                     let g = pct(number(&mut rest));
+///                     rest = &rest[1..];
+///                     palette[color] = (r, g, pct(number(&mut rest)));
+///                 }
+///                 continue;
+///             },
+///             '$' => { x = 0; continue; },
+///             '-' => { x = 0; band += 1; continue; },
+///             '!' => {
+///                 count = number(&mut rest);
+///                 let c = rest.chars().next().unwrap();
+///                 rest = &rest[1..];
+///                 c
+///             },
+///             c => c
+///         };
+///         let bits = c as u8 - b'?';
+///         for _ in 0..count {
+///             for row in (0..6).filter(|row| bits & 1 << row != 0) {
+///                 pixels[(band * 6 + row) * width + x] = palette[color];
+///             }
+///             x += 1;
+///         }
+///     }
+///     (width, height, pixels)
+/// }
+///
+/// // Channels that are whole percents survive the trip exactly.
+/// let levels = [0, 51, 102, 153, 204, 255];
+/// for (width, height) in [(1, 1), (7, 5), (13, 6), (20, 13)] {
+///     let rgb: Vec<(u8, u8, u8)> = (0..width * height).map(|i| (levels[i % 6], levels[i / 6 % 6], levels[i * 7 % 6])).collect();
+///     assert_eq!(decode(&drawc::sixel_escape(&rgb, width, height)), (width, height, rgb));
+/// }
+/// let flat = vec![(255, 0, 0); 30 * 6];
+/// assert!(drawc::sixel_escape(&flat, 30, 6).contains("#0!30~-"));
+/// assert_eq!(drawc::sixel_escape(&[(0, 0, 0)], 2, 2), "");
+/// ```
+#[cfg(feature = "image")]
+pub fn sixel_escape(rgb: &[(u8, u8, u8)], width: usize, height: usize) -> String {
+    if width == 0 || height == 0 || rgb.len() / width < height {
+        return String::new();
+    }
+    let pixels: Vec<Option<(u8, u8, u8)>> = rgb.iter().take(width * height).map(|&c| Some(c)).collect();
+    sixel(&pixels, width, height)
+}
+
+
+/// Encode `pixels` as sixels, leaving `None` pixels transparent.
+#[cfg(feature = "image")]
+fn sixel(pixels: &[Option<(u8, u8, u8)>], width: usize, height: usize) -> String {
+    let shown: Vec<(u8, u8, u8)> = pixels.iter().flatten().copied().collect();
+    let (palette, indices) = quantize(&shown, 256);
+    let mut indices = indices.into_iter();
+    let pixels: Vec<Option<u8>> = pixels.iter().map(|p| p.and_then(|_| indices.next())).collect();
+
+    let mut out = format!("\x1bP0;1;0q\"1;1;{};{}", width, height);
+    let percent = |c: u8| (c as usize * 100 + 127) / 255;
+    for (i, &(r, g, b)) in palette.iter().enumerate() {
+        out.push_str(&format!("#{};2;{};{};{}", i, percent(r), percent(g), percent(b)));
+    }
+    for band in 0..height.div_ceil(6) {
+        let rows = band * 6..(band * 6 + 6).min(height);
+        let mut used = [false; 256];
+        for y in rows.clone() {
+            for p in pixels[y * width..(y + 1) * width].iter().flatten() {
+                used[*p as usize] = true;
+            }
+        }
+        let mut first = true;
+        for color in (0..256).filter(|&c| used[c]) {
+            let sixels: Vec<u8> = (0..width).map(|x| {
+                rows.clone().filter(|&y| pixels[y * width + x] == Some(color as u8)).fold(0, |bits, y| bits | 1 << (y - band * 6))
+            }).collect();
+            if !first {
+                out.push('$');
+            }
+            first = false;
+            out.push_str(&format!("#{}", color));
+            let end = sixels.iter().rposition(|&s| s != 0).map_or(0, |i| i + 1);
+            let mut x = 0;
+            while x < end {
+                let run = sixels[x..end].iter().take_while(|&&s| s == sixels[x]).count();
+                let c = (b'?' + sixels[x]) as char;
+                if run >= 4 {
+                    out.push_str(&format!("!{}{}", run, c));
+                }
+                else {
+                    out.extend(std::iter::repeat_n(c, run));
+                }
+                x += run;
+            }
+        }
+        out.push('-');
+    }
+    out.push_str("\x1b\\");
+    out
+}
+
+
+/// Remove every image shown with the kitty graphics protocol. Does nothing if none have been shown.
+#[cfg(feature = "image")]
+pub fn delete_images() {
+    if KITTY_IMAGES_SHOWN.swap(false, std::sync::atomic::Ordering::Relaxed) {
+        print!("\x1b_Ga=d,d=A,q=2\x1b\\");
+    }
+}
+
+
+#[cfg(feature = "image")]
+fn base64(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut out = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let n = chunk.iter().enumerate().fold(0u32, |n, (i, &b)| n | (b as u32) << (16 - 8 * i));
+        for i in 0..4 {
+            if i <= chunk.len() {
+                out.push(ALPHABET[(n >> (18 - 6 * i) & 63) as usize] as char);
+            }
+            else {
+                out.push('=');
+            }
+        }
+    }
+    out
+}
+
+
+/// Encode RGBA pixels as a PNG, with the image data stored uncompressed.
+#[cfg(feature = "image")]
+fn png(rgba: &[u8], width: usize, height: usize) -> Vec<u8> {
+    let mut raw = Vec::with_capacity(height * (width * 4 + 1));
+    for row in rgba.chunks(width * 4).take(height) {
+        raw.push(0);
+        raw.extend_from_slice(row);
+    }
+    let mut zlib = vec![0x78, 0x01];
+    let mut blocks = raw.chunks(65535).peekable();
+    if blocks.peek().is_none() {
+        zlib.extend_from_slice(&[1, 0, 0, 0xff, 0xff]);
+    }
+    while let Some(block) = blocks.next() {
+        let len = block.len() as u16;
+        zlib.push(blocks.peek().is_none() as u8);
+        zlib.extend_from_slice(&len.to_le_bytes());
+        zlib.extend_from_slice(&(!len).to_le_bytes());
+        zlib.extend_from_slice(block);
+    }
+    let (a, b) = raw.iter().fold((1u32, 0u32), |(a, b), &byte| {
+        let a = (a + byte as u32) % 65521;
+        (a, (b + a) % 65521)
+    });
+    zlib.extend_from_slice(&(b << 16 | a).to_be_bytes());
+
+    let mut header = Vec::new();
+    header.extend_from_slice(&(width as u32).to_be_bytes());
+    header.extend_from_slice(&(height as u32).to_be_bytes());
+    header.extend_from_slice(&[8, 6, 0, 0, 0]);
+
+    let mut out = b"\x89PNG\r\n\x1a\n".to_vec();
+    for (kind, data) in [(b"IHDR", header), (b"IDAT", zlib), (b"IEND", Vec::new())] {
+        out.extend_from_slice(&(data.len() as u32).to_be_bytes());
+        let start = out.len();
+        out.extend_from_slice(kind);
+        out.extend_from_slice(&data);
+        let crc = out[start..].iter().fold(!0u32, |crc, &byte| {
+            (0..8).fold(crc ^ byte as u32, |crc, _| if crc & 1 != 0 { crc >> 1 ^ 0xedb88320 } else { crc >> 1 })
+        });
+        out.extend_from_slice(&(!crc).to_be_bytes());
     }
+    out
 }
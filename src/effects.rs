@@ -0,0 +1,297 @@
+// Classic full-screen animated effects drawn into a canvas.
+use super::canvas::Canvas;
+use super::drawc::{self, ColorKind};
+use super::particles::Rng;
+use super::raster;
+
+
+/// Colors for `Fire`, from cold to hot.
+pub const HEAT_PALETTE: [(u8, u8, u8); 5] = [(0, 0, 0), (120, 10, 0), (230, 70, 0), (255, 190, 20), (255, 255, 200)];
+
+/// Colors for `Plasma`. The last color repeats the first, so the colors wrap around smoothly as they cycle.
+pub const PLASMA_PALETTE: [(u8, u8, u8); 6] = [(20, 0, 80), (200, 0, 120), (255, 160, 0), (255, 255, 160), (0, 160, 255), (20, 0, 80)];
+
+/// Glyphs for `MatrixRain`: digits, a few letters, and half-width katakana, which are all one cell wide.
+pub const MATRIX_GLYPHS: &str = "0123456789ABCDEFZｦｱｳｴｵｶｷｹｺｻｼｽｾｿﾀﾂﾃﾅﾆﾇﾈﾊﾋﾎﾏﾐﾑﾒﾓﾔﾕﾗﾘﾜ";
+
+
+/// Get `count` color codes blending evenly through `colors`, for looking up a color by `value * (count - 1)`.
+fn shades(colors: &[(u8, u8, u8)], count: usize) -> Vec<String> {
+    (0..count).map(|i| match drawc::gradient(colors, i as f64 / (count.max(2) - 1) as f64) {
+        Some(rgb) => drawc::rgb_to_ccode(rgb, ColorKind::Fg),
+        None => String::new()
+    }).collect()
+}
+
+
+/// Fire rising from the bottom edge: each step the bottom row is seeded with random heat,
+/// and every other cell takes the average heat of the cells below it, minus some cooling.
+/// Heat is drawn with `ramp` and colored with `palette`.
+///
+/// Example
+/// ```
+/// use terminalgl::canvas::Canvas;
+/// use terminalgl::effects::Fire;
+/// let mut a = Fire::new(30, 10, 42);
+/// let mut b = Fire::new(30, 10, 42);
+/// for _ in 0..20 {
+///     a.update(0.05);
+///     b.update(0.05);
+/// }
+/// let (mut canvas_a, mut canvas_b) = (Canvas::new(30, 10), Canvas::new(30, 10));
+/// a.render(&mut canvas_a);
+/// b.render(&mut canvas_b);
+/// assert_eq!(canvas_a, canvas_b);
+/// assert!(a.heat(15, 9) > a.heat(15, 0));
+/// a.update(f64::INFINITY);
+/// b.update(1e300);
+/// ```
+#[derive(Clone, Debug)]
+pub struct Fire {
+    width: usize,
+    height: usize,
+    heat: Vec<f64>,
+    rng: Rng,
+    pending: f64,
+    /// Steps per second.
+    pub speed: f64,
+    /// Most heat a cell loses per step, as a fraction of full heat.
+    pub cooling: f64,
+    pub palette: Vec<(u8, u8, u8)>,
+    pub ramp: String
+}
+
+impl Fire {
+    /// Create a cold fire `width` by `height` cells in size, seeding its random number generator with `seed`.
+    pub fn new(width: usize, height: usize, seed: u64) -> Fire {
+        Fire {
+            width,
+            height,
+            heat: vec![0.0; width * height],
+            rng: Rng::new(seed),
+            pending: 0.0,
+            speed: 30.0,
+            cooling: 0.06,
+            palette: HEAT_PALETTE.to_vec(),
+            ramp: raster::DENSITY_RAMP.to_string()
+        }
+    }
+
+    /// Get the heat of the cell `(x, y)` from `0.0` (cold) to `1.0`, or `0.0` outside the fire.
+    pub fn heat(&self, x: usize, y: usize) -> f64 {
+        if x < self.width && y < self.height { self.heat[y * self.width + x] } else { 0.0 }
+    }
+
+    /// Advance the fire by `dt` seconds. Every row has been replaced after `height` steps,
+    /// so no more than that many are taken at once, however long `dt` is.
+    pub fn update(&mut self, dt: f64) {
+        self.pending += dt * self.speed;
+        let most = self.height as f64;
+        if self.pending.is_nan() || self.pending > most {
+            self.pending = most;
+        }
+        while self.pending >= 1.0 {
+            self.pending -= 1.0;
+            self.step();
+        }
+    }
+
+    fn step(&mut self) {
+        let (w, h) = (self.width, self.height);
+        if w == 0 || h == 0 {
+            return;
+        }
+        for x in 0..w {
+            self.heat[(h - 1) * w + x] = if self.rng.next_f64() < 0.6 { self.rng.range(0.8, 1.0) } else { self.rng.range(0.0, 0.3) };
+        }
+        for y in 0..h - 1 {
+            for x in 0..w {
+                let below = |dx: isize, dy: usize| {
+                    let nx = x as isize + dx;
+                    if nx < 0 || nx >= w as isize { 0.0 } else { self.heat(nx as usize, y + dy) }
+                };
+                let average = (below(-1, 1) + below(0, 1) + below(1, 1) + below(0, 2)) / 4.0;
+                let cooled = average - self.rng.range(0.0, self.cooling);
+                self.heat[y * w + x] = cooled.max(0.0);
+            }
+        }
+    }
+
+    /// Draw the fire into `canvas` with its top left corner at `(0, 0)`. Cells outside the canvas are skipped.
+    pub fn render(&self, canvas: &mut Canvas) {
+        let colors = shades(&self.palette, 32);
+        for y in 0..self.height {
+            for x in 0..self.width {
+                let heat = self.heat[y * self.width + x];
+                let c = raster::ramp_char(&self.ramp, heat);
+                canvas.set(x as isize, y as isize, c, &colors[(heat * 31.0).round() as usize]);
+            }
+        }
+    }
+}
+
+
+/// Plasma from a sum of sines, animated by cycling colors through a fixed pattern.
+/// The pattern is drawn with palette indices into the first `colors` entries of the canvas's palette,
+/// so once it has been drawn each frame only has to rotate the palette (see `Canvas::rotate_palette`).
+///
+/// Example
+/// ```
+/// use terminalgl::canvas::Canvas;
+/// use terminalgl::effects::Plasma;
+/// let mut plasma = Plasma::new(20, 8, 1);
+/// let mut canvas = Canvas::new(20, 8);
+/// plasma.render(&mut canvas);
+/// let before = canvas.clone();
+/// plasma.update(1.0);
+/// plasma.render(&mut canvas);
+/// assert_eq!(canvas.get(3, 3), before.get(3, 3));
+/// assert_ne!(canvas.ccode_at(3, 3), before.ccode_at(3, 3));
+/// ```
+#[derive(Clone, Debug)]
+pub struct Plasma {
+    width: usize,
+    pattern: Vec<usize>,
+    time: f64,
+    /// Palette entries moved per second.
+    pub speed: f64,
+    /// Number of palette entries the colors are spread over.
+    pub colors: usize,
+    pub palette: Vec<(u8, u8, u8)>,
+    pub c: char
+}
+
+impl Plasma {
+    /// Create a plasma `width` by `height` cells in size, with its pattern shifted by `seed`.
+    pub fn new(width: usize, height: usize, seed: u64) -> Plasma {
+        let mut rng = Rng::new(seed);
+        let (px, py, pd) = (rng.range(0.0, 10.0), rng.range(0.0, 10.0), rng.range(0.0, 10.0));
+        let pattern = (0..width * height).map(|i| {
+            let (x, y) = ((i % width) as f64 / 8.0 + px, (i / width) as f64 / 4.0 + py);
+            let v = x.sin() + (y * 1.3).sin() + ((x + y) * 0.7).sin() + ((x * x + y * y).sqrt() * 0.9 + pd).sin();
+            ((v + 4.0) / 8.0 * 1024.0) as usize
+        }).collect();
+        Plasma { width, pattern, time: 0.0, speed: 20.0, colors: 32, palette: PLASMA_PALETTE.to_vec(), c: '█' }
+    }
+
+    /// Advance the plasma by `dt` seconds.
+    pub fn update(&mut self, dt: f64) {
+        self.time += dt;
+    }
+
+    /// Draw the plasma into `canvas` with its top left corner at `(0, 0)`, setting palette entries `0..colors` for the current time.
+    pub fn render(&self, canvas: &mut Canvas) {
+        let colors = self.colors.max(1);
+        let offset = (self.time * self.speed).rem_euclid(colors as f64) as usize;
+        for (i, ccode) in shades(&self.palette, colors + 1).iter().take(colors).enumerate() {
+            canvas.set_palette_entry((i + offset) % colors, ccode);
+        }
+        for (i, &v) in self.pattern.iter().enumerate() {
+            canvas.set_indexed((i % self.width) as isize, (i / self.width) as isize, self.c, v * colors / 1024 % colors);
+        }
+    }
+}
+
+
+/// A falling column of glyphs in `MatrixRain`.
+#[derive(Clone, Debug)]
+struct Drop {
+    x: usize,
+    y: f64,
+    speed: f64,
+    length: usize
+}
+
+
+/// Columns of falling glyphs with bright heads and fading tails.
+/// `density` is the chance per second that a new drop starts in any given column,
+/// and glyphs under the drops change at random as they fall.
+///
+/// Example
+/// ```
+/// use terminalgl::canvas::Canvas;
+/// use terminalgl::effects::MatrixRain;
+/// let mut rain = MatrixRain::new(40, 12, 3);
+/// for _ in 0..30 {
+///     rain.update(0.1);
+/// }
+/// let mut canvas = Canvas::new(40, 12);
+/// rain.render(&mut canvas);
+/// assert!(canvas.rect().cells().any(|(x, y)| canvas.get(x, y).unwrap().c != ' '));
+/// rain.update(1e300);
+/// rain.update(f64::INFINITY);
+/// ```
+#[derive(Clone, Debug)]
+pub struct MatrixRain {
+    width: usize,
+    height: usize,
+    glyphs: Vec<char>,
+    drops: Vec<Drop>,
+    rng: Rng,
+    /// Speed multiplier for every drop.
+    pub speed: f64,
+    pub density: f64,
+    pub head_color: (u8, u8, u8),
+    pub tail_color: (u8, u8, u8)
+}
+
+impl MatrixRain {
+    /// Create rain `width` by `height` cells in size with no drops yet, seeding its random number generator with `seed`.
+    pub fn new(width: usize, height: usize, seed: u64) -> MatrixRain {
+        let mut rng = Rng::new(seed);
+        let choices: Vec<char> = MATRIX_GLYPHS.chars().collect();
+        let glyphs = (0..width * height).map(|_| choices[rng.next_u64() as usize % choices.len()]).collect();
+        MatrixRain {
+            width,
+            height,
+            glyphs,
+            drops: Vec::new(),
+            rng,
+            speed: 1.0,
+            density: 0.3,
+            head_color: (220, 255, 220),
+            tail_color: (0, 200, 60)
+        }
+    }
+
+    /// Advance the rain by `dt` seconds: move the drops, remove the ones whose tail has left the bottom, and start new ones.
+    /// At most one glyph per cell changes at once, however long `dt` is.
+    pub fn update(&mut self, dt: f64) {
+        let height = self.height as f64;
+        for drop in self.drops.iter_mut() {
+            drop.y += drop.speed * self.speed * dt;
+        }
+        self.drops.retain(|d| d.y - (d.length as f64) < height);
+        for x in 0..self.width {
+            if self.rng.next_f64() < self.density * dt {
+                let speed = self.rng.range(6.0, 20.0);
+                let length = 4 + (self.rng.next_u64() % (self.height as u64 / 2 + 1)) as usize;
+                self.drops.push(Drop { x, y: 0.0, speed, length });
+            }
+        }
+        let choices: Vec<char> = MATRIX_GLYPHS.chars().collect();
+        let changes = ((self.glyphs.len() as f64 * dt * 0.5) as usize).min(self.glyphs.len());
+        for _ in 0..changes {
+            let i = self.rng.next_u64() as usize % self.glyphs.len();
+            self.glyphs[i] = choices[self.rng.next_u64() as usize % choices.len()];
+        }
+    }
+
+    /// Draw the rain into `canvas` with its top left corner at `(0, 0)`. Only the drops are drawn, leaving the rest of the canvas as it was.
+    pub fn render(&self, canvas: &mut Canvas) {
+        let head = drawc::rgb_to_ccode(self.head_color, ColorKind::Fg);
+        let tail = shades(&[self.tail_color, (0, 0, 0)], 16);
+        for drop in &self.drops {
+            let head_y = drop.y as isize;
+            for i in 0..drop.length as isize {
+                let y = head_y - i;
+                if y < 0 || y >= self.height as isize {
+                    continue;
+                }
+                let c = self.glyphs[y as usize * self.width + drop.x];
+                let ccode = if i == 0 { &head } else { &tail[i as usize * 15 / drop.length] };
+                canvas.set(drop.x as isize, y, c, ccode);
+            }
+        }
+    }
+}
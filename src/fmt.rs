@@ -0,0 +1,253 @@
+// Compact number, size, and duration labels that fit the cells set aside for them.
+use std::time::Duration;
+
+
+/// SI prefixes from `y` (10^-24) to `Y` (10^24), with no prefix at index 8.
+const SI_PREFIXES: [&str; 17] = ["y", "z", "a", "f", "p", "n", "µ", "m", "", "k", "M", "G", "T", "P", "E", "Z", "Y"];
+
+/// Binary units from bytes to exbibytes.
+const BYTE_UNITS: [&str; 7] = ["B", "KiB", "MiB", "GiB", "TiB", "PiB", "EiB"];
+
+
+/// Drop trailing zeros after the decimal point of `number`, and the point itself if nothing is left after it.
+fn trim_zeros(number: String) -> String {
+    if !number.contains('.') {
+        return number;
+    }
+    number.trim_end_matches('0').trim_end_matches('.').to_string()
+}
+
+
+/// Scale `value` by the power of `base` between `first` and `last` that leaves it in `1..base` (or as near as those allow),
+/// round it to 3 significant digits (or to a whole number if it has more digits than that), and get it without trailing zeros
+/// and the power used. Values that round up to `base` move to the next power.
+fn scaled(value: f64, base: f64, first: i32, last: i32) -> (String, i32) {
+    let mut exp = match value == 0.0 {
+        true => 0,
+        false => (value.abs().ln() / base.ln()).floor() as i32
+    }.clamp(first, last);
+    loop {
+        let m = value / base.powi(exp);
+        let decimals = if m == 0.0 { 0 } else { (2 - m.abs().log10().floor() as i32).max(0) as usize };
+        let number = format!("{:.*}", decimals, m);
+        if number.trim_start_matches('-').parse::<f64>().unwrap_or(0.0) >= base && exp < last {
+            exp += 1;
+            continue;
+        }
+        return (trim_zeros(number), exp);
+    }
+}
+
+
+/// Format `value` to 3 significant digits with an SI prefix, from `y` (10^-24) to `Y` (10^24), dropping trailing zeros.
+/// Rounds to nearest, moving to the next prefix when rounding reaches 1000.
+///
+/// Example
+/// ```
+/// use terminalgl::fmt;
+/// let table = [
+///     (0.001234, "1.23m"),
+///     (0.5, "500m"),
+///     (1.0, "1"),
+///     (12.345, "12.3"),
+///     (999.0, "999"),
+///     (999.6, "1k"),
+///     (1536.0, "1.54k"),
+///     (1_234_567.0, "1.23M"),
+///     (1e9, "1G"),
+///     (45.6e9, "45.6G"),
+///     (1e12, "1T"),
+///     (-1_234_567.0, "-1.23M"),
+///     (0.0, "0"),
+///     (f64::INFINITY, "inf"),
+/// ];
+/// for (value, expected) in table {
+///     assert_eq!(fmt::si(value), expected, "{}", value);
+/// }
+/// ```
+pub fn si(value: f64) -> String {
+    if !value.is_finite() {
+        return value.to_string();
+    }
+    let (number, exp) = scaled(value, 1000.0, -8, 8);
+    number + SI_PREFIXES[(exp + 8) as usize]
+}
+
+
+/// Format a size of `bytes` in binary units (`KiB` is 1024 bytes), to 3 significant digits past 1 KiB, dropping trailing zeros.
+///
+/// Example
+/// ```
+/// use terminalgl::fmt;
+/// assert_eq!(fmt::bytes(0), "0 B");
+/// assert_eq!(fmt::bytes(1023), "1023 B");
+/// assert_eq!(fmt::bytes(1024), "1 KiB");
+/// assert_eq!(fmt::bytes(1536), "1.5 KiB");
+/// assert_eq!(fmt::bytes(1_048_575), "1 MiB");
+/// assert_eq!(fmt::bytes(5_368_709_120), "5 GiB");
+/// assert_eq!(fmt::bytes(u64::MAX), "16 EiB");
+/// ```
+pub fn bytes(bytes: u64) -> String {
+    if bytes < 1024 {
+        return format!("{} B", bytes);
+    }
+    let (number, exp) = scaled(bytes as f64, 1024.0, 0, BYTE_UNITS.len() as i32 - 1);
+    format!("{} {}", number, BYTE_UNITS[exp as usize])
+}
+
+
+/// Format `duration` in its two largest units, like `2m 03s` or `1h 05m`, or in one unit under a minute, like `42s`, `250ms` or `15µs`.
+/// Rounds to the nearest of the smallest unit shown, moving to larger units when that rounds up to the next one.
+///
+/// Example
+/// ```
+/// use terminalgl::fmt;
+/// use std::time::Duration;
+/// let table = [
+///     (Duration::ZERO, "0s"),
+///     (Duration::from_nanos(15_400), "15µs"),
+///     (Duration::from_micros(999_600), "1s"),
+///     (Duration::from_millis(250), "250ms"),
+///     (Duration::from_millis(42_400), "42s"),
+///     (Duration::from_millis(59_600), "1m 00s"),
+///     (Duration::from_secs(123), "2m 03s"),
+///     (Duration::from_secs(3_900), "1h 05m"),
+///     (Duration::from_secs(3_599), "59m 59s"),
+///     (Duration::from_secs(100_000), "1d 04h"),
+/// ];
+/// for (duration, expected) in table {
+///     assert_eq!(fmt::duration(duration), expected, "{:?}", duration);
+/// }
+/// ```
+pub fn duration(duration: Duration) -> String {
+    let ns = duration.as_nanos();
+    let round = |unit: u128| (ns + unit / 2) / unit;
+    if ns == 0 {
+        return "0s".to_string();
+    }
+    if ns < 999_500 {
+        return format!("{}µs", round(1_000));
+    }
+    if ns < 999_500_000 {
+        return format!("{}ms", round(1_000_000));
+    }
+    let secs = round(1_000_000_000);
+    if secs < 60 {
+        return format!("{}s", secs);
+    }
+    if secs < 3_600 {
+        return format!("{}m {:02}s", secs / 60, secs % 60);
+    }
+    let mins = round(60_000_000_000);
+    if mins < 24 * 60 {
+        return format!("{}h {:02}m", mins / 60, mins % 60);
+    }
+    let hours = round(3_600_000_000_000);
+    format!("{}d {:02}h", hours / 24, hours % 24)
+}
+
+
+/// Format `value` right-aligned in exactly `width` columns with as many digits as fit, for labels that must line up
+/// and never spill out of their cells. Uses plain decimals, unless they keep fewer than 2 significant digits and an SI
+/// prefix (see `si`) keeps more, dropping the `0` before the point when that is what it takes to fit. Values that don't
+/// fit at all, which only happens below 5 columns, are `#` repeated. Rounds to nearest.
+///
+/// Example
+/// ```
+/// use terminalgl::fmt;
+/// let table = [
+///     // value       7 columns  5 columns  4 columns  3 columns
+///     (0.001,        "0.00100", "1.00m",   "1.0m",    " 1m"),
+///     (0.012345,     "0.01235", "0.012",   " 12m",    "12m"),
+///     (0.5,          "0.50000", "0.500",   "0.50",    "0.5"),
+///     (-0.0004,      "-400.0µ", "-400µ",   "-.4m",    "###"),
+///     (0.0,          "0.00000", "0.000",   "0.00",    "0.0"),
+///     (3.14159,      "3.14159", "3.142",   "3.14",    "3.1"),
+///     (-2.5,         "-2.5000", "-2.50",   "-2.5",    " -2"),
+///     (99.96,        "99.9600", "99.96",   " 100",    "100"),
+///     (1000.0,       "1000.00", " 1000",   "1000",    " 1k"),
+///     (12345.678,    "12345.7", "12346",   " 12k",    "12k"),
+///     (-12345.678,   " -12346", " -12k",   "-12k",    "###"),
+///     (100_000.0,    " 100000", " 100k",   "100k",    ".1M"),
+///     (999_999.0,    " 999999", "1.00M",   "1.0M",    " 1M"),
+///     (1_234_567.0,  "1234567", "1.23M",   "1.2M",    " 1M"),
+///     (2.5e9,        "2.5000G", "2.50G",   "2.5G",    " 2G"),
+///     (123e9,        "123.00G", " 123G",   "123G",    ".1T"),
+///     (1e12,         "1.0000T", "1.00T",   "1.0T",    " 1T"),
+/// ];
+/// for (value, seven, five, four, three) in table {
+///     assert_eq!(fmt::fixed_width(value, 7), seven, "{}", value);
+///     assert_eq!(fmt::fixed_width(value, 5), five, "{}", value);
+///     assert_eq!(fmt::fixed_width(value, 4), four, "{}", value);
+///     assert_eq!(fmt::fixed_width(value, 3), three, "{}", value);
+/// }
+/// ```
+pub fn fixed_width(value: f64, width: usize) -> String {
+    if !value.is_finite() {
+        let label = value.to_string();
+        return match label.len() <= width {
+            true => format!("{:>width$}", label, width = width),
+            false => "#".repeat(width)
+        };
+    }
+    let label = match (plain_fit(value, width), prefixed_fit(value, width)) {
+        (Some(plain), Some(prefixed)) if significant_digits(&plain) < 2.min(significant_digits(&prefixed)) => prefixed,
+        (Some(plain), _) => plain,
+        (None, Some(prefixed)) => prefixed,
+        (None, None) => "#".repeat(width)
+    };
+    format!("{:>width$}", label, width = width)
+}
+
+
+/// Count the digits of `label` from its first nonzero one.
+fn significant_digits(label: &str) -> usize {
+    label.chars().skip_while(|c| !('1'..='9').contains(c)).filter(char::is_ascii_digit).count()
+}
+
+
+/// Get `value` with as many decimals as fit in `width` columns, unless that rounds a nonzero value to zero.
+fn plain_fit(value: f64, width: usize) -> Option<String> {
+    for decimals in (0..width).rev() {
+        let label = format!("{:.*}", decimals, value);
+        if label.chars().count() > width {
+            continue;
+        }
+        if significant_digits(&label) == 0 {
+            return (value == 0.0).then(|| label.trim_start_matches('-').to_string());
+        }
+        return Some(label);
+    }
+    None
+}
+
+
+/// Get `value` with an SI prefix and as many decimals as fit in `width` columns, trying the prefix above too
+/// with the `0` before the point dropped.
+fn prefixed_fit(value: f64, width: usize) -> Option<String> {
+    if value == 0.0 {
+        return None;
+    }
+    let natural = ((value.abs().log10() / 3.0).floor() as i32).clamp(-8, 8);
+    // Without a prefix it is plain decimals.
+    for exp in (natural..=(natural + 1).min(8)).filter(|&exp| exp != 0) {
+        let m = value / 1000f64.powi(exp);
+        let prefix = SI_PREFIXES[(exp + 8) as usize];
+        for decimals in (0..width).rev() {
+            let number = format!("{:.*}", decimals, m);
+            if number.trim_start_matches('-').parse::<f64>().unwrap_or(0.0) >= 1000.0 || significant_digits(&number) == 0 {
+                continue;
+            }
+            let short = match number.strip_prefix("-0.") {
+                Some(rest) => format!("-.{}", rest),
+                None => number.replacen("0.", ".", number.starts_with("0.") as usize)
+            };
+            for label in [number + prefix, short + prefix] {
+                if label.chars().count() <= width {
+                    return Some(label);
+                }
+            }
+        }
+    }
+    None
+}
@@ -0,0 +1,154 @@
+// Field of view on grid maps.
+use std::collections::HashSet;
+
+
+/// The cells visible from an origin, as computed by `compute`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct VisibleSet {
+    cells: HashSet<(isize, isize)>
+}
+
+impl VisibleSet {
+    /// Get whether `(x, y)` is visible.
+    pub fn contains(&self, x: isize, y: isize) -> bool {
+        self.cells.contains(&(x, y))
+    }
+
+    /// Get the number of visible cells.
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    /// Get whether no cells are visible.
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    /// Iterate over the visible cells in no particular order.
+    pub fn iter(&self) -> impl Iterator<Item = &(isize, isize)> {
+        self.cells.iter()
+    }
+}
+
+
+/// A slope as an exact fraction `num / den` with a positive denominator.
+#[derive(Clone, Copy)]
+struct Slope {
+    num: isize,
+    den: isize
+}
+
+
+/// The largest radius whose slope and distance products fit in an `isize`. Larger radii are clamped to it.
+const MAX_RADIUS: isize = isize::MAX.isqrt() / 2;
+
+
+/// Get the cells visible from `origin` within `radius`, where `is_opaque(x, y)` tells whether a cell blocks sight.
+/// Uses symmetric shadowcasting, so if a floor cell A can see a floor cell B, B can also see A.
+///
+/// Opaque cells are visible when lit, but nothing behind them is. The origin is always visible and never blocks
+/// its own view, even if it is opaque. The map has no bounds of its own, so have `is_opaque` return `true`
+/// outside the map to stop sight there. Cells whose coordinates would overflow an `isize` count as outside the map:
+/// they are opaque and never visible.
+///
+/// Examples
+/// ```
+/// use terminalgl::fov;
+/// let map = [
+///     "#######",
+///     "#.....#",
+///     "#..#..#",
+///     "#.....#",
+///     "#######",
+/// ];
+/// let opaque = |x: isize, y: isize| {
+///     map.get(y as usize).and_then(|row| row.as_bytes().get(x as usize)).map_or(true, |&c| c == b'#')
+/// };
+/// let visible = fov::compute((1, 2), 10, opaque);
+/// assert!(visible.contains(3, 2));  // The pillar
+/// assert!(!visible.contains(4, 2));  // Behind the pillar
+/// assert!(visible.contains(5, 1));
+/// assert!(visible.contains(0, 0));
+/// assert_eq!(fov::compute((1, 2), 0, opaque).len(), 1);
+///
+/// // Sight stops at the edge of the coordinates.
+/// let corner = fov::compute((isize::MAX, isize::MIN), 3, |_, _| false);
+/// assert!(corner.contains(isize::MAX - 2, isize::MIN + 2));
+/// assert_eq!(corner.len(), fov::compute((0, 0), 3, |x, y| x > 0 || y < 0).iter().filter(|&&(x, y)| x <= 0 && y >= 0).count());
+///
+/// // Symmetry between every pair of floor cells
+/// let map = [
+///     "##########",
+///     "#..#.....#",
+///     "#....#.#.#",
+///     "##.#...#.#",
+///     "#...#....#",
+///     "#.#....#.#",
+///     "##########",
+/// ];
+/// let opaque = |x: isize, y: isize| {
+///     map.get(y as usize).and_then(|row| row.as_bytes().get(x as usize)).map_or(true, |&c| c == b'#')
+/// };
+/// let floors: Vec<(isize, isize)> = map.iter().enumerate()
+///     .flat_map(|(y, row)| row.match_indices('.').map(move |(x, _)| (x as isize, y as isize)))
+///     .collect();
+/// for &(x1, y1) in &floors {
+///     let a = fov::compute((x1, y1), 6, opaque);
+///     for &(x2, y2) in &floors {
+///         let b = fov::compute((x2, y2), 6, opaque);
+///         assert_eq!(a.contains(x2, y2), b.contains(x1, y1));
+///     }
+/// }
+/// ```
+pub fn compute(origin: (isize, isize), radius: usize, is_opaque: impl Fn(isize, isize) -> bool) -> VisibleSet {
+    let mut visible = VisibleSet::default();
+    visible.cells.insert(origin);
+    let radius = radius.min(MAX_RADIUS as usize) as isize;
+    for quadrant in 0..4 {
+        let transform = |depth: isize, col: isize| {
+            let (ox, oy) = origin;
+            match quadrant {
+                0 => Some((ox.checked_add(col)?, oy.checked_sub(depth)?)),
+                1 => Some((ox.checked_add(depth)?, oy.checked_add(col)?)),
+                2 => Some((ox.checked_add(col)?, oy.checked_add(depth)?)),
+                _ => Some((ox.checked_sub(depth)?, oy.checked_add(col)?))
+            }
+        };
+        scan(1, Slope { num: -1, den: 1 }, Slope { num: 1, den: 1 }, radius, &transform, &is_opaque, &mut visible);
+    }
+    visible
+}
+
+
+/// Scan one row of a quadrant between two slopes, recursing into the rows behind it.
+fn scan(depth: isize, mut start: Slope, end: Slope, radius: isize, transform: &impl Fn(isize, isize) -> Option<(isize, isize)>,
+        is_opaque: &impl Fn(isize, isize) -> bool, visible: &mut VisibleSet) {
+    if depth > radius {
+        return;
+    }
+    // Columns whose centers fall between the slopes, rounding ties towards the middle of the row.
+    let min_col = (2 * depth * start.num + start.den).div_euclid(2 * start.den);
+    let max_col = -(-(2 * depth * end.num - end.den)).div_euclid(2 * end.den);
+
+    let mut prev_opaque: Option<bool> = None;
+    for col in min_col..=max_col {
+        let cell = transform(depth, col);
+        let opaque = cell.is_none_or(|(x, y)| is_opaque(x, y));
+        let symmetric = col * start.den >= depth * start.num && col * end.den <= depth * end.num;
+        if let Some(cell) = cell {
+            if (opaque || symmetric) && depth * depth + col * col <= radius * radius + radius {
+                visible.cells.insert(cell);
+            }
+        }
+        if prev_opaque == Some(true) && !opaque {
+            start = Slope { num: 2 * col - 1, den: 2 * depth };
+        }
+        if prev_opaque == Some(false) && opaque {
+            scan(depth + 1, start, Slope { num: 2 * col - 1, den: 2 * depth }, radius, transform, is_opaque, visible);
+        }
+        prev_opaque = Some(opaque);
+    }
+    if prev_opaque == Some(false) {
+        scan(depth + 1, start, end, radius, transform, is_opaque, visible);
+    }
+}
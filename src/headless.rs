@@ -0,0 +1,475 @@
+// A terminal emulated in memory, to check what was presented without a real terminal.
+use super::backend::{Backend, TestBackend};
+use super::canvas::{Canvas, Cell};
+#[cfg(all(feature = "input", unix))]
+use super::input::Event;
+use std::io;
+#[cfg(all(feature = "input", unix))]
+use std::time::Duration;
+
+
+/// A backend that decodes what is written to it the way a terminal would, into a grid of cells, so frames can be checked
+/// after they went through the escape sequences `Canvas::present_to` and friends produce. Operations are recorded by a
+/// `TestBackend` underneath, reachable through `backend`.
+///
+/// It understands the sequences the crate writes and the ones terminal programs commonly rely on:
+/// cursor positioning and movement (`H`, `f`, `A` to `G`, `d`, save and restore), SGR color codes, clearing the screen and lines
+/// (`J`, `K`, `X`), scroll regions (`r`) and scrolling (`S`, `T`, `L`, `M`, reverse index), the alternate screen (`?1049`),
+/// and cursor visibility (`?25`). Newlines go to the start of the next line, text wraps at the right edge, and anything
+/// else is skipped. Escape sequences and characters split across writes are put back together.
+///
+/// Each cell's color code is the SGR sequences written since the last reset, as written, so the emulated screen of a
+/// presented canvas matches the canvas cell for cell. Like a canvas, it puts one character in each cell whatever its width.
+/// Erased cells become spaces in the current color code, as terminals fill them with the current background.
+///
+/// Example
+/// ```
+/// use terminalgl::assert_frame_eq;
+/// use terminalgl::canvas::Canvas;
+/// use terminalgl::drawc;
+/// use terminalgl::headless::HeadlessTerminal;
+/// let mut canvas = Canvas::new(6, 2);
+/// canvas.text(0, 0, "score", drawc::GREEN);
+/// let mut terminal = HeadlessTerminal::new(6, 2);
+/// canvas.present_to(&mut terminal).unwrap();
+/// let previous = canvas.clone();
+/// canvas.text(1, 1, "42", drawc::RED);
+/// canvas.present_diff_to(&previous, &mut terminal).unwrap();
+/// assert_eq!(terminal.canvas(), canvas);
+/// assert_frame_eq!(terminal, r#"|score |
+/// | 42   |
+///
+/// |AAAAA.|
+/// |.BB...|
+/// A "\u{1b}[32m"
+/// B "\u{1b}[31m""#);
+/// ```
+///
+/// Scroll regions, and a sequence split across writes:
+/// ```
+/// use terminalgl::assert_frame_eq;
+/// use terminalgl::backend::Backend;
+/// use terminalgl::headless::HeadlessTerminal;
+/// let mut terminal = HeadlessTerminal::new(4, 4);
+/// terminal.write(b"head\x1b[2;3r\x1b[2;1Ha\nb\nc\x1b[4;1Hfoot\x1b[1").unwrap();
+/// terminal.write(b";1H\x1b[2KH").unwrap();
+/// assert_frame_eq!(terminal, "\
+/// |H   |
+/// |b   |
+/// |c   |
+/// |foot|");
+/// assert_eq!(terminal.cursor(), (1, 0));
+/// ```
+#[derive(Clone, Debug)]
+pub struct HeadlessTerminal {
+    backend: TestBackend,
+    cols: usize,
+    rows: usize,
+    cells: Vec<Cell>,
+    normal: Option<Vec<Cell>>,
+    cursor: (usize, usize),
+    saved: (usize, usize),
+    wrap_pending: bool,
+    cursor_visible: bool,
+    ccode: String,
+    region: (usize, usize),
+    pending: Vec<u8>
+}
+
+impl HeadlessTerminal {
+    /// Create a terminal `cols` by `rows` with a blank screen and the cursor in the top left corner.
+    pub fn new(cols: u16, rows: u16) -> HeadlessTerminal {
+        let backend = TestBackend::new(cols, rows);
+        let (cols, rows) = (cols as usize, rows as usize);
+        HeadlessTerminal {
+            backend,
+            cols,
+            rows,
+            cells: vec![Cell::default(); cols * rows],
+            normal: None,
+            cursor: (0, 0),
+            saved: (0, 0),
+            wrap_pending: false,
+            cursor_visible: true,
+            ccode: String::new(),
+            region: (0, rows.saturating_sub(1)),
+            pending: Vec::new()
+        }
+    }
+
+    /// Resize the terminal, keeping the cells that still fit at their positions, and reset the scroll region to the whole screen.
+    pub fn resize(&mut self, cols: u16, rows: u16) {
+        let (from, to) = ((self.cols, self.rows), (cols as usize, rows as usize));
+        let resized = |cells: &[Cell]| {
+            let mut grid = vec![Cell::default(); to.0 * to.1];
+            for y in 0..from.1.min(to.1) {
+                for x in 0..from.0.min(to.0) {
+                    grid[y * to.0 + x] = cells[y * from.0 + x].clone();
+                }
+            }
+            grid
+        };
+        self.normal = self.normal.as_deref().map(resized);
+        self.cells = resized(&self.cells);
+        (self.cols, self.rows) = (cols as usize, rows as usize);
+        self.cursor = (self.cursor.0.min(self.cols.saturating_sub(1)), self.cursor.1.min(self.rows.saturating_sub(1)));
+        self.region = (0, self.rows.saturating_sub(1));
+        self.wrap_pending = false;
+        self.backend.resize(cols, rows);
+    }
+
+    /// Get the screen as a canvas the size of the terminal.
+    pub fn canvas(&self) -> Canvas {
+        let mut canvas = Canvas::new(self.cols, self.rows);
+        for (i, cell) in self.cells.iter().enumerate() {
+            canvas.put((i % self.cols) as isize, (i / self.cols) as isize, cell.clone());
+        }
+        canvas
+    }
+
+    /// Get a plain text picture of the screen, as `Canvas::to_debug_string` gives for a canvas, so `assert_frame_eq!` works on terminals too.
+    pub fn to_debug_string(&self) -> String {
+        self.canvas().to_debug_string()
+    }
+
+    /// Get the cursor position as `(x, y)`.
+    pub fn cursor(&self) -> (usize, usize) {
+        self.cursor
+    }
+
+    /// Get whether the cursor is shown.
+    pub fn cursor_visible(&self) -> bool {
+        self.cursor_visible
+    }
+
+    /// Get whether the alternate screen is in use.
+    pub fn is_alternate_screen(&self) -> bool {
+        self.normal.is_some()
+    }
+
+    /// Get the recording backend underneath, with every operation done on the terminal.
+    pub fn backend(&self) -> &TestBackend {
+        &self.backend
+    }
+
+    /// Get the recording backend underneath mutably, such as to queue input with `TestBackend::push_input`.
+    pub fn backend_mut(&mut self) -> &mut TestBackend {
+        &mut self.backend
+    }
+
+    /// Decode `bytes` onto the screen, keeping an incomplete escape sequence or character at the end for the next write.
+    fn decode(&mut self, bytes: &[u8]) {
+        let mut input = std::mem::take(&mut self.pending);
+        input.extend_from_slice(bytes);
+        let mut i = 0;
+        while i < input.len() {
+            let used = match input[i] {
+                0x1b => match self.escape(&input[i..]) {
+                    Some(used) => used,
+                    None => break
+                },
+                b'\n' => {
+                    self.cursor.0 = 0;
+                    self.line_feed();
+                    1
+                },
+                b'\r' => {
+                    self.move_to(0, self.cursor.1);
+                    1
+                },
+                0x08 => {
+                    self.move_to(self.cursor.0.saturating_sub(1), self.cursor.1);
+                    1
+                },
+                b'\t' => {
+                    self.move_to((self.cursor.0 / 8 + 1) * 8, self.cursor.1);
+                    1
+                },
+                byte if byte < 0x20 || byte == 0x7f => 1,
+                byte => {
+                    let len = match byte {
+                        0xf0..=0xf7 => 4,
+                        0xe0..=0xef => 3,
+                        0xc0..=0xdf => 2,
+                        _ => 1
+                    };
+                    if i + len > input.len() && std::str::from_utf8(&input[i..]).is_err_and(|e| e.error_len().is_none()) {
+                        break;
+                    }
+                    match input.get(i..i + len).and_then(|b| std::str::from_utf8(b).ok()) {
+                        Some(s) => {
+                            self.print(s.chars().next().unwrap_or(char::REPLACEMENT_CHARACTER));
+                            len
+                        },
+                        None => {
+                            self.print(char::REPLACEMENT_CHARACTER);
+                            1
+                        }
+                    }
+                }
+            };
+            i += used;
+        }
+        self.pending = input.split_off(i);
+    }
+
+    /// Decode the escape sequence at the start of `input`, and get how many bytes it took, or `None` if it isn't complete yet.
+    fn escape(&mut self, input: &[u8]) -> Option<usize> {
+        match *input.get(1)? {
+            b'[' => {
+                let end = 2 + input[2..].iter().position(|b| !(0x20..=0x3f).contains(b))?;
+                if !(0x40..=0x7e).contains(&input[end]) {
+                    return Some(end);
+                }
+                let params = String::from_utf8_lossy(&input[2..end]).into_owned();
+                self.csi(&params, input[end]);
+                Some(end + 1)
+            },
+            b']' | b'P' | b'_' | b'^' => {
+                // String sequences like window titles, ended by BEL or ESC \, have nothing to draw.
+                let end = input[2..].iter().enumerate().find_map(|(i, &b)| match b {
+                    0x07 => Some(i + 1),
+                    0x1b if input.get(i + 3) == Some(&b'\\') => Some(i + 2),
+                    _ => None
+                })?;
+                Some(2 + end)
+            },
+            b'7' => {
+                self.saved = self.cursor;
+                Some(2)
+            },
+            b'8' => {
+                self.move_to(self.saved.0, self.saved.1);
+                Some(2)
+            },
+            b'D' => {
+                self.line_feed();
+                Some(2)
+            },
+            b'E' => {
+                self.cursor.0 = 0;
+                self.line_feed();
+                Some(2)
+            },
+            b'M' => {
+                self.wrap_pending = false;
+                if self.cursor.1 == self.region.0 {
+                    self.scroll(-1);
+                }
+                else {
+                    self.cursor.1 = self.cursor.1.saturating_sub(1);
+                }
+                Some(2)
+            },
+            b'c' => {
+                let (cols, rows) = (self.cols as u16, self.rows as u16);
+                let backend = std::mem::replace(&mut self.backend, TestBackend::new(0, 0));
+                *self = HeadlessTerminal { backend, ..HeadlessTerminal::new(cols, rows) };
+                Some(2)
+            },
+            _ => Some(2)
+        }
+    }
+
+    /// Apply a control sequence: `ESC [`, then `params`, then `command`.
+    fn csi(&mut self, params: &str, command: u8) {
+        if let Some(private) = params.strip_prefix('?') {
+            for mode in private.split(';') {
+                match (mode, command) {
+                    ("25", b'h' | b'l') => self.cursor_visible = command == b'h',
+                    ("1049", b'h' | b'l') => self.alternate_screen(command == b'h'),
+                    _ => {}
+                }
+            }
+            return;
+        }
+        if command == b'm' {
+            self.sgr(params);
+            return;
+        }
+        let numbers: Vec<usize> = params.split(';').map(|p| p.parse().unwrap_or(0)).collect();
+        let arg = |i: usize| numbers.get(i).copied().unwrap_or(0);
+        let count = arg(0).max(1);
+        let (x, y) = self.cursor;
+        match command {
+            b'H' | b'f' => self.move_to(arg(1).max(1) - 1, arg(0).max(1) - 1),
+            b'A' => self.move_to(x, y.saturating_sub(count)),
+            b'B' => self.move_to(x, y.saturating_add(count)),
+            b'C' => self.move_to(x.saturating_add(count), y),
+            b'D' => self.move_to(x.saturating_sub(count), y),
+            b'E' => self.move_to(0, y.saturating_add(count)),
+            b'F' => self.move_to(0, y.saturating_sub(count)),
+            b'G' => self.move_to(count - 1, y),
+            b'd' => self.move_to(x, count - 1),
+            b's' => self.saved = self.cursor,
+            b'u' => self.move_to(self.saved.0, self.saved.1),
+            b'J' => match arg(0) {
+                0 => self.erase(y * self.cols + x, self.cols * self.rows),
+                1 => self.erase(0, y * self.cols + x + 1),
+                _ => self.erase(0, self.cols * self.rows)
+            },
+            b'K' => match arg(0) {
+                0 => self.erase(y * self.cols + x, (y + 1) * self.cols),
+                1 => self.erase(y * self.cols, y * self.cols + x + 1),
+                _ => self.erase(y * self.cols, (y + 1) * self.cols)
+            },
+            b'X' => self.erase(y * self.cols + x, y * self.cols + (x + count).min(self.cols)),
+            b'r' => {
+                let top = arg(0).max(1) - 1;
+                let bottom = if arg(1) == 0 { self.rows } else { arg(1).min(self.rows) };
+                if top + 1 < bottom {
+                    self.region = (top, bottom - 1);
+                    self.move_to(0, 0);
+                }
+            },
+            b'S' => self.scroll(count as isize),
+            b'T' => self.scroll(-(count as isize)),
+            b'L' | b'M' if (self.region.0..=self.region.1).contains(&y) => {
+                let region = self.region;
+                self.region.0 = y;
+                self.scroll(if command == b'L' { -(count as isize) } else { count as isize });
+                self.region = region;
+                self.move_to(0, y);
+            },
+            _ => {}
+        }
+    }
+
+    /// Apply the parameters of an SGR sequence to the current color code: a reset clears it, and anything else is added to it as written.
+    fn sgr(&mut self, params: &str) {
+        let params: Vec<&str> = params.split(';').collect();
+        let mut kept = Vec::new();
+        let mut i = 0;
+        while i < params.len() {
+            // The components of an extended color can be 0 without being resets.
+            let len = match (params[i], params.get(i + 1).copied()) {
+                ("38" | "48" | "58", Some("5")) => 3,
+                ("38" | "48" | "58", Some("2")) => 5,
+                _ => 1
+            };
+            if matches!(params[i], "" | "0") {
+                self.ccode.clear();
+                kept.clear();
+            }
+            else {
+                kept.extend_from_slice(&params[i..(i + len).min(params.len())]);
+            }
+            i += len;
+        }
+        if !kept.is_empty() {
+            self.ccode.push_str(&format!("\x1b[{}m", kept.join(";")));
+        }
+    }
+
+    /// Print `c` at the cursor, wrapping to the next line first if the last one ended at the right edge.
+    fn print(&mut self, c: char) {
+        if self.cols == 0 || self.rows == 0 {
+            return;
+        }
+        if self.wrap_pending {
+            self.cursor.0 = 0;
+            self.line_feed();
+        }
+        let (x, y) = self.cursor;
+        self.cells[y * self.cols + x] = Cell::new(c, &self.ccode);
+        if x + 1 < self.cols {
+            self.cursor.0 += 1;
+        }
+        else {
+            self.wrap_pending = true;
+        }
+    }
+
+    /// Move the cursor down a line, scrolling the scroll region up if the cursor is on its bottom line.
+    fn line_feed(&mut self) {
+        self.wrap_pending = false;
+        if self.cursor.1 == self.region.1 {
+            self.scroll(1);
+        }
+        else if self.cursor.1 + 1 < self.rows {
+            self.cursor.1 += 1;
+        }
+    }
+
+    /// Move the cursor to `(x, y)`, clamped to the screen.
+    fn move_to(&mut self, x: usize, y: usize) {
+        self.cursor = (x.min(self.cols.saturating_sub(1)), y.min(self.rows.saturating_sub(1)));
+        self.wrap_pending = false;
+    }
+
+    /// Scroll the lines of the scroll region up by `by` lines, or down if it is negative, filling the lines uncovered with blanks.
+    fn scroll(&mut self, by: isize) {
+        let (top, bottom) = self.region;
+        if self.rows == 0 {
+            return;
+        }
+        let lines = &mut self.cells[top * self.cols..(bottom + 1) * self.cols];
+        let by = by.clamp(-((bottom - top + 1) as isize), (bottom - top + 1) as isize);
+        let shift = by.unsigned_abs() * self.cols;
+        let blank = Cell::new(' ', &self.ccode);
+        if by > 0 {
+            lines.rotate_left(shift);
+            let len = lines.len();
+            lines[len - shift..].fill(blank);
+        }
+        else {
+            lines.rotate_right(shift);
+            lines[..shift].fill(blank);
+        }
+    }
+
+    /// Turn the cells from index `start` up to `end` into spaces in the current color code.
+    fn erase(&mut self, start: usize, end: usize) {
+        let end = end.min(self.cells.len());
+        if start < end {
+            self.cells[start..end].fill(Cell::new(' ', &self.ccode));
+        }
+    }
+
+    /// Switch to the alternate screen, blank, saving the normal one and the cursor, or back to the normal screen and cursor.
+    fn alternate_screen(&mut self, on: bool) {
+        if on && self.normal.is_none() {
+            self.saved = self.cursor;
+            self.normal = Some(std::mem::replace(&mut self.cells, vec![Cell::default(); self.cols * self.rows]));
+        }
+        else if let (false, Some(normal)) = (on, self.normal.take()) {
+            self.cells = normal;
+            self.move_to(self.saved.0, self.saved.1);
+        }
+    }
+}
+
+impl Backend for HeadlessTerminal {
+    fn write(&mut self, bytes: &[u8]) -> io::Result<()> {
+        self.decode(bytes);
+        self.backend.write(bytes)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.backend.flush()
+    }
+
+    fn size(&self) -> (u16, u16) {
+        self.backend.size()
+    }
+
+    fn set_alternate_screen(&mut self, on: bool) -> io::Result<()> {
+        self.alternate_screen(on);
+        self.backend.set_alternate_screen(on)
+    }
+
+    fn set_cursor_visible(&mut self, visible: bool) -> io::Result<()> {
+        self.cursor_visible = visible;
+        self.backend.set_cursor_visible(visible)
+    }
+
+    #[cfg(all(feature = "input", unix))]
+    fn set_raw_mode(&mut self, on: bool) -> io::Result<()> {
+        self.backend.set_raw_mode(on)
+    }
+
+    #[cfg(all(feature = "input", unix))]
+    fn read_events(&mut self, timeout: Duration) -> io::Result<Vec<Event>> {
+        self.backend.read_events(timeout)
+    }
+}
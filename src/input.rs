@@ -0,0 +1,1029 @@
+// Keyboard and mouse input read straight from the terminal.
+#[cfg(feature = "widgets")]
+use super::ui::UiEvent;
+use std::cell::UnsafeCell;
+use std::collections::VecDeque;
+use std::fmt;
+use std::io::{self, Write};
+use std::mem::MaybeUninit;
+use std::sync::atomic::{AtomicBool, AtomicI32, AtomicUsize, Ordering};
+use std::sync::{Mutex, Once};
+use std::time::{Duration, Instant};
+#[cfg(feature = "async")]
+use std::{pin::Pin, sync::{Condvar, OnceLock}, task::{Context, Poll, Waker}};
+
+
+/// A key press. Letters typed with Ctrl held come in as `Ctrl` with the lowercase letter, so Ctrl+C is `Ctrl('c')`,
+/// and function keys as `F` with their number, from `F(1)` to `F(12)`.
+///
+/// Keys display as the chord to press, the way help screens write them:
+/// ```
+/// use terminalgl::input::Key;
+/// assert_eq!(Key::Ctrl('x').to_string(), "Ctrl+X");
+/// assert_eq!(Key::F(5).to_string(), "F5");
+/// assert_eq!(Key::Up.to_string(), "↑");
+/// assert_eq!(Key::BackTab.to_string(), "Shift+Tab");
+/// assert_eq!(Key::Char(' ').to_string(), "Space");
+/// assert_eq!(Key::Char('?').to_string(), "?");
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Key {
+    Char(char),
+    Ctrl(char),
+    Enter,
+    Tab,
+    BackTab,
+    Backspace,
+    Esc,
+    Up,
+    Down,
+    Left,
+    Right,
+    Home,
+    End,
+    PageUp,
+    PageDown,
+    Insert,
+    Delete,
+    F(u8)
+}
+
+impl fmt::Display for Key {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Key::Char(' ') => write!(f, "Space"),
+            Key::Char(c) => write!(f, "{}", c),
+            Key::Ctrl(c) => write!(f, "Ctrl+{}", c.to_uppercase()),
+            Key::Enter => write!(f, "Enter"),
+            Key::Tab => write!(f, "Tab"),
+            Key::BackTab => write!(f, "Shift+Tab"),
+            Key::Backspace => write!(f, "Backspace"),
+            Key::Esc => write!(f, "Esc"),
+            Key::Up => write!(f, "↑"),
+            Key::Down => write!(f, "↓"),
+            Key::Left => write!(f, "←"),
+            Key::Right => write!(f, "→"),
+            Key::Home => write!(f, "Home"),
+            Key::End => write!(f, "End"),
+            Key::PageUp => write!(f, "PgUp"),
+            Key::PageDown => write!(f, "PgDn"),
+            Key::Insert => write!(f, "Ins"),
+            Key::Delete => write!(f, "Del"),
+            Key::F(n) => write!(f, "F{}", n)
+        }
+    }
+}
+
+
+/// A mouse button for `MouseKind`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MouseButton {
+    Left,
+    Middle,
+    Right
+}
+
+
+/// What happened in a `Mouse` event. `Drag` is a move with a button held, and `Move` one without,
+/// which terminals only report in some modes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MouseKind {
+    Press(MouseButton),
+    Drag(MouseButton),
+    Release,
+    Move,
+    ScrollUp,
+    ScrollDown
+}
+
+
+/// A mouse event at the cell `(x, y)`, with the top left corner being `(0, 0)`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Mouse {
+    pub kind: MouseKind,
+    pub x: isize,
+    pub y: isize
+}
+
+
+/// An input event from `read_events` or `event_stream`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Event {
+    Key(Key),
+    Mouse(Mouse),
+    /// Text pasted into the terminal while `RawMode` is on, all in one event instead of a key per character.
+    /// Any `ESC` bytes in it are removed, and line breaks are `\n` whichever way the terminal sent them.
+    Paste(String),
+    /// The terminal was resized to `(columns, rows)`. Only `event_stream` reports these.
+    Resize(u16, u16),
+    /// The terminal window gained or lost focus. Only reported while `RawMode` is on, and only by terminals that support
+    /// focus reporting, so don't rely on ever getting one.
+    FocusGained,
+    FocusLost,
+    /// The program got `SIGINT` or `SIGTERM`, or Ctrl+C was typed in raw mode. Only reported after `on_interrupt(OnInterrupt::Event)`.
+    Interrupt
+}
+
+impl Event {
+    /// Get the `UiEvent` this event usually stands for: Tab and Shift+Tab move focus, Enter activates,
+    /// Esc cancels, and a left click clicks. Other events give `None`.
+    ///
+    /// Example
+    /// ```
+    /// use terminalgl::input::{Event, Key, Mouse, MouseButton, MouseKind};
+    /// use terminalgl::ui::UiEvent;
+    /// assert_eq!(Event::Key(Key::BackTab).ui_event(), Some(UiEvent::Previous));
+    /// let click = Event::Mouse(Mouse { kind: MouseKind::Press(MouseButton::Left), x: 3, y: 4 });
+    /// assert_eq!(click.ui_event(), Some(UiEvent::Click(3, 4)));
+    /// assert_eq!(Event::Key(Key::Char('x')).ui_event(), None);
+    /// ```
+    #[cfg(feature = "widgets")]
+    pub fn ui_event(&self) -> Option<UiEvent> {
+        match *self {
+            Event::Key(Key::Tab) => Some(UiEvent::Next),
+            Event::Key(Key::BackTab) => Some(UiEvent::Previous),
+            Event::Key(Key::Enter) => Some(UiEvent::Activate),
+            Event::Key(Key::Esc) => Some(UiEvent::Cancel),
+            Event::Mouse(Mouse { kind: MouseKind::Press(MouseButton::Left), x, y }) => Some(UiEvent::Click(x, y)),
+            _ => None
+        }
+    }
+}
+
+
+/// Decode bytes read from a terminal into events. Understands UTF-8 text, control keys,
+/// the usual escape sequences for arrow and editing keys, and SGR mouse reports (as enabled by `RawMode::with_mouse`).
+/// Unknown escape sequences are skipped, and an `ESC` that doesn't start a sequence is the Esc key.
+/// Focus reports are `Event::FocusGained` and `Event::FocusLost`, and a bracketed paste is one `Event::Paste`, even if its end is missing. Use an `EventParser` for input read a piece at a time.
+///
+/// Example
+/// ```
+/// use terminalgl::input::{self, Event, Key, Mouse, MouseButton, MouseKind};
+/// let events = input::parse_events("aé\x1b[A\x1b\x03\r\x1bOQ\x1b[15~".as_bytes());
+/// assert_eq!(events, vec![
+///     Event::Key(Key::Char('a')),
+///     Event::Key(Key::Char('é')),
+///     Event::Key(Key::Up),
+///     Event::Key(Key::Esc),
+///     Event::Key(Key::Ctrl('c')),
+///     Event::Key(Key::Enter),
+///     Event::Key(Key::F(2)),
+///     Event::Key(Key::F(5)),
+/// ]);
+/// let events = input::parse_events(b"\x1b[<0;5;2M\x1b[<32;6;2M\x1b[<0;6;2m\x1b[<65;1;1M");
+/// assert_eq!(events, vec![
+///     Event::Mouse(Mouse { kind: MouseKind::Press(MouseButton::Left), x: 4, y: 1 }),
+///     Event::Mouse(Mouse { kind: MouseKind::Drag(MouseButton::Left), x: 5, y: 1 }),
+///     Event::Mouse(Mouse { kind: MouseKind::Release, x: 5, y: 1 }),
+///     Event::Mouse(Mouse { kind: MouseKind::ScrollDown, x: 0, y: 0 }),
+/// ]);
+/// assert_eq!(input::parse_events(b"\x1b[<0;-9223372036854775808;1M\x1b[<0;0;1M"), vec![]);
+/// let events = input::parse_events(b"a\x1b[O\x1b[<0;5;2M\x1b[Ib\x1b[I\x1b[O\x1bOA");
+/// assert_eq!(events, vec![
+///     Event::Key(Key::Char('a')),
+///     Event::FocusLost,
+///     Event::Mouse(Mouse { kind: MouseKind::Press(MouseButton::Left), x: 4, y: 1 }),
+///     Event::FocusGained,
+///     Event::Key(Key::Char('b')),
+///     Event::FocusGained,
+///     Event::FocusLost,
+///     Event::Key(Key::Up),
+/// ]);
+/// ```
+pub fn parse_events(input: &[u8]) -> Vec<Event> {
+    let mut parser = EventParser::new();
+    let mut events = parser.feed(input);
+    events.extend(parser.flush());
+    if let Some(mut paste) = parser.paste.take() {
+        paste.append(&mut parser.pending);
+        events.push(Event::Paste(paste_text(&paste)));
+    }
+    events
+}
+
+
+/// Start and end of a bracketed paste, which `RawMode` turns on.
+const PASTE_START: &[u8] = b"\x1b[200~";
+const PASTE_END: &[u8] = b"\x1b[201~";
+
+
+/// Decodes input that arrives a piece at a time like `parse_events` does, but keeps a paste, or the start of one,
+/// that is cut off at the end of a piece to finish with the next piece. `read_events` and `event_stream` share one.
+///
+/// Example
+/// ```
+/// use terminalgl::input::{Event, EventParser, Key};
+/// let captured = b"a\x1b[200~one\r\ntwo\x1b[31m\rthree\x1b[201~\x1b[Ab";
+/// let expected = vec![
+///     Event::Key(Key::Char('a')),
+///     Event::Paste("one\ntwo[31m\nthree".to_string()),
+///     Event::Key(Key::Up),
+///     Event::Key(Key::Char('b')),
+/// ];
+/// // Split anywhere, including inside the sequences around the paste.
+/// for i in 0..=captured.len() {
+///     for j in i..=captured.len() {
+///         let mut parser = EventParser::new();
+///         let mut events = parser.feed(&captured[..i]);
+///         events.extend(parser.feed(&captured[i..j]));
+///         events.extend(parser.feed(&captured[j..]));
+///         events.extend(parser.flush());
+///         assert_eq!(events, expected, "split at {} and {}", i, j);
+///     }
+/// }
+/// // A byte at a time.
+/// let mut parser = EventParser::new();
+/// let mut events: Vec<Event> = captured.iter().flat_map(|b| parser.feed(&[*b])).collect();
+/// events.extend(parser.flush());
+/// assert_eq!(events, expected);
+///
+/// // A paste still going is kept by `flush`, and a lone ESC is the Esc key.
+/// let mut parser = EventParser::new();
+/// assert_eq!(parser.feed(b"\x1b[200~half"), vec![]);
+/// assert_eq!(parser.flush(), vec![]);
+/// assert_eq!(parser.feed(b"\x1b[201~\x1b"), vec![Event::Paste("half".to_string())]);
+/// assert_eq!(parser.flush(), vec![Event::Key(Key::Esc)]);
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct EventParser {
+    pending: Vec<u8>,
+    paste: Option<Vec<u8>>
+}
+
+impl EventParser {
+    /// Create a parser that hasn't seen any input.
+    pub fn new() -> EventParser {
+        EventParser::default()
+    }
+
+    /// Decode the next piece of input, and get the events it finishes. A cut off start or end of a paste,
+    /// including a lone `ESC` at the end, is kept until the next piece or `flush`.
+    pub fn feed(&mut self, input: &[u8]) -> Vec<Event> {
+        self.pending.extend_from_slice(input);
+        let mut events = Vec::new();
+        let mut i = 0;
+        loop {
+            let rest = &self.pending[i..];
+            let marker = if self.paste.is_some() { PASTE_END } else { PASTE_START };
+            let (end, next) = match rest.windows(marker.len()).position(|w| w == marker) {
+                Some(at) => (at, at + marker.len()),
+                None => {
+                    let end = rest.len() - partial_marker(rest, marker);
+                    (end, end)
+                }
+            };
+            match &mut self.paste {
+                Some(paste) => paste.extend_from_slice(&rest[..end]),
+                None => events.extend(decode_events(&rest[..end]))
+            }
+            i += next;
+            if next == end {
+                break;
+            }
+            match self.paste.take() {
+                Some(paste) => events.push(Event::Paste(paste_text(&paste))),
+                None => self.paste = Some(Vec::new())
+            }
+        }
+        self.pending.drain(..i);
+        events
+    }
+
+    /// Decode the input kept back by `feed` as it is, for when no more input is waiting. A paste that hasn't ended yet
+    /// stays kept, since the terminal always ends one.
+    pub fn flush(&mut self) -> Vec<Event> {
+        if self.paste.is_some() {
+            return Vec::new();
+        }
+        let events = decode_events(&self.pending);
+        self.pending.clear();
+        events
+    }
+}
+
+
+/// Get the length of the longest end of `input` that `marker` could start with.
+fn partial_marker(input: &[u8], marker: &[u8]) -> usize {
+    (1..marker.len().min(input.len() + 1)).rev().find(|&len| input.ends_with(&marker[..len])).unwrap_or(0)
+}
+
+
+/// Get the text of a paste, without `ESC` bytes and with `\r\n` and `\r` line breaks turned into `\n`.
+fn paste_text(paste: &[u8]) -> String {
+    String::from_utf8_lossy(paste).replace("\r\n", "\n").replace('\r', "\n").replace('\x1b', "")
+}
+
+
+/// Decode input with no pastes in it into events.
+fn decode_events(input: &[u8]) -> Vec<Event> {
+    let mut events = Vec::new();
+    let mut i = 0;
+    while i < input.len() {
+        let key = match input[i] {
+            0x1b => {
+                let (event, len) = parse_escape(&input[i..]);
+                events.extend(event);
+                i += len;
+                continue;
+            },
+            b'\r' | b'\n' => Key::Enter,
+            b'\t' => Key::Tab,
+            0x7f | 0x08 => Key::Backspace,
+            b @ 0x01..=0x1a => Key::Ctrl((b'a' + b - 1) as char),
+            b if b < 0x20 => {
+                i += 1;
+                continue;
+            },
+            b => {
+                let len = match b {
+                    0xf0.. => 4,
+                    0xe0.. => 3,
+                    0xc0.. => 2,
+                    _ => 1
+                };
+                let decoded = input.get(i..i + len).and_then(|bytes| std::str::from_utf8(bytes).ok());
+                match decoded.and_then(|s| s.chars().next()) {
+                    Some(c) => {
+                        i += len;
+                        events.push(Event::Key(Key::Char(c)));
+                    },
+                    None => i += 1
+                }
+                continue;
+            }
+        };
+        events.push(Event::Key(key));
+        i += 1;
+    }
+    events
+}
+
+
+/// Decode the escape sequence at the start of `input`, which starts with `ESC`, and get its event (if any) and length in bytes.
+fn parse_escape(input: &[u8]) -> (Option<Event>, usize) {
+    let key = |key: Key| Some(Event::Key(key));
+    match input.get(1) {
+        Some(b'[') => {
+            let Some(end) = input[2..].iter().position(|b| (0x40..=0x7e).contains(b)) else {
+                return (key(Key::Esc), 1);
+            };
+            let params = std::str::from_utf8(&input[2..2 + end]).unwrap_or("");
+            let last = input[2 + end];
+            let len = end + 3;
+            if let Some(mouse) = params.strip_prefix('<') {
+                return (parse_mouse(mouse, last == b'm').map(Event::Mouse), len);
+            }
+            let event = match last {
+                b'I' if params.is_empty() => Some(Event::FocusGained),
+                b'O' if params.is_empty() => Some(Event::FocusLost),
+                b'A' => key(Key::Up),
+                b'B' => key(Key::Down),
+                b'C' => key(Key::Right),
+                b'D' => key(Key::Left),
+                b'H' => key(Key::Home),
+                b'F' => key(Key::End),
+                b'Z' => key(Key::BackTab),
+                // F1 to F4 with modifiers held, which are ignored.
+                b'P'..=b'S' => key(Key::F(last - b'P' + 1)),
+                b'~' => match params.split(';').next() {
+                    Some("1" | "7") => key(Key::Home),
+                    Some("2") => key(Key::Insert),
+                    Some("3") => key(Key::Delete),
+                    Some("4" | "8") => key(Key::End),
+                    Some("5") => key(Key::PageUp),
+                    Some("6") => key(Key::PageDown),
+                    Some(n) => match n.parse::<u8>() {
+                        Ok(n @ 11..=15) => key(Key::F(n - 10)),
+                        Ok(n @ 17..=21) => key(Key::F(n - 11)),
+                        Ok(n @ 23..=24) => key(Key::F(n - 12)),
+                        _ => None
+                    },
+                    None => None
+                },
+                _ => None
+            };
+            (event, len)
+        },
+        Some(b'O') => {
+            let event = match input.get(2) {
+                Some(b'A') => key(Key::Up),
+                Some(b'B') => key(Key::Down),
+                Some(b'C') => key(Key::Right),
+                Some(b'D') => key(Key::Left),
+                Some(b'H') => key(Key::Home),
+                Some(b'F') => key(Key::End),
+                Some(&b @ b'P'..=b'S') => key(Key::F(b - b'P' + 1)),
+                _ => return (key(Key::Esc), 1)
+            };
+            (event, 3)
+        },
+        _ => (key(Key::Esc), 1)
+    }
+}
+
+
+/// Decode the parameters `button;x;y` of an SGR mouse report, where `release` is whether it ended in `m`.
+/// Coordinates are 1-based, so reports with one below 1 are malformed and give `None`.
+fn parse_mouse(params: &str, release: bool) -> Option<Mouse> {
+    let mut numbers = params.split(';').map(|n| n.parse::<isize>().ok());
+    let (code, x, y) = (numbers.next()??, numbers.next()??, numbers.next()??);
+    if x < 1 || y < 1 {
+        return None;
+    }
+    let button = match code & 3 {
+        0 => Some(MouseButton::Left),
+        1 => Some(MouseButton::Middle),
+        2 => Some(MouseButton::Right),
+        _ => None
+    };
+    let kind = if code & 64 != 0 {
+        if code & 1 == 0 { MouseKind::ScrollUp } else { MouseKind::ScrollDown }
+    }
+    else if code & 32 != 0 {
+        button.map_or(MouseKind::Move, MouseKind::Drag)
+    }
+    else if release {
+        MouseKind::Release
+    }
+    else {
+        button.map_or(MouseKind::Release, MouseKind::Press)
+    };
+    Some(Mouse { kind, x: x - 1, y: y - 1 })
+}
+
+
+/// Decodes what `read_input` reads, keeping pastes cut off between reads.
+static PARSER: Mutex<EventParser> = Mutex::new(EventParser { pending: Vec::new(), paste: None });
+
+
+/// Events read from stdin but not handed out yet. `read_events` and `event_stream` both take from here first,
+/// so using one after the other never loses or repeats input.
+static PENDING: Mutex<VecDeque<Event>> = Mutex::new(VecDeque::new());
+
+
+/// Wait up to `timeout` for input, then get the events typed since the last call, or none if the time ran out.
+/// If `on_interrupt` asked for `Event::Interrupt`, an interrupt ends the wait early and comes first in the events.
+/// Use with the terminal in raw mode (see `RawMode`), or keys only arrive once Enter is pressed.
+pub fn read_events(timeout: Duration) -> io::Result<Vec<Event>> {
+    let mut events: Vec<Event> = PENDING.lock().unwrap_or_else(|e| e.into_inner()).drain(..).collect();
+    if events.is_empty() {
+        events = read_input(timeout)?.unwrap_or_default();
+    }
+    if INTERRUPTED.swap(false, Ordering::Relaxed) {
+        events.insert(0, Event::Interrupt);
+    }
+    Ok(events)
+}
+
+
+/// Wait up to `timeout` for input and get the events in it, or `None` at the end of input.
+/// A signal handler writing to the wake pipe ends the wait early with no events.
+fn read_input(timeout: Duration) -> io::Result<Option<Vec<Event>>> {
+    let mut fds = [
+        libc::pollfd { fd: libc::STDIN_FILENO, events: libc::POLLIN, revents: 0 },
+        libc::pollfd { fd: WAKE_PIPE[0].load(Ordering::Relaxed), events: libc::POLLIN, revents: 0 }
+    ];
+    let ms = timeout.as_millis().min(i32::MAX as u128) as i32;
+    // SAFETY: `fds` is two valid `pollfd`s. A wake pipe of `-1` is ignored by `poll`.
+    let ready = unsafe { libc::poll(fds.as_mut_ptr(), 2, ms) };
+    if ready <= 0 {
+        return interrupted_or(ready).map(Some);
+    }
+    if fds[1].revents != 0 {
+        drain_wake_pipe();
+    }
+    if fds[0].revents == 0 {
+        return Ok(Some(Vec::new()));
+    }
+    let mut buf = [0u8; 1024];
+    // SAFETY: `buf` is valid for writes of its whole length.
+    let n = unsafe { libc::read(libc::STDIN_FILENO, buf.as_mut_ptr().cast(), buf.len()) };
+    match n {
+        0 => Ok(None),
+        ..0 => interrupted_or(n as i32).map(Some),
+        _ => {
+            let mut parser = PARSER.lock().unwrap_or_else(|e| e.into_inner());
+            let mut events = parser.feed(&buf[..n as usize]);
+            // A read that didn't fill the buffer got everything waiting, so nothing kept back is still coming.
+            if (n as usize) < buf.len() {
+                events.extend(parser.flush());
+            }
+            drop(parser);
+            if INTERRUPT_EVENTS.load(Ordering::Relaxed) {
+                for event in events.iter_mut().filter(|e| **e == Event::Key(Key::Ctrl('c'))) {
+                    *event = Event::Interrupt;
+                }
+            }
+            Ok(Some(events))
+        }
+    }
+}
+
+
+/// Ask the terminal whether it shows sixel images, by sending a Primary Device Attributes request and looking for
+/// attribute 4 in the reply, waiting up to `timeout` for it. A terminal that doesn't reply in time counts as not showing them.
+/// Use with the terminal in raw mode (see `RawMode`). Anything typed while waiting is kept for `read_events`.
+///
+/// Example
+/// ```no_run
+/// use std::time::Duration;
+/// use terminalgl::input::{self, RawMode};
+/// let raw = RawMode::enable().unwrap();
+/// let sixels = input::query_sixel_support(Duration::from_millis(200)).unwrap();
+/// drop(raw);
+/// println!("sixels: {}", sixels);
+/// ```
+pub fn query_sixel_support(timeout: Duration) -> io::Result<bool> {
+    let mut stdout = io::stdout();
+    stdout.write_all(b"\x1b[c")?;
+    stdout.flush()?;
+    let deadline = Instant::now() + timeout;
+    let mut input = Vec::new();
+    let mut attributes = None;
+    while attributes.is_none() {
+        let ms = deadline.saturating_duration_since(Instant::now()).as_millis().min(i32::MAX as u128) as i32;
+        let mut fd = libc::pollfd { fd: libc::STDIN_FILENO, events: libc::POLLIN, revents: 0 };
+        // SAFETY: `fd` is a valid `pollfd`.
+        let ready = unsafe { libc::poll(&mut fd, 1, ms) };
+        if ready < 0 {
+            interrupted_or(ready)?;
+            continue;
+        }
+        if ready == 0 {
+            break;
+        }
+        let mut buf = [0u8; 256];
+        // SAFETY: `buf` is valid for writes of its whole length.
+        let n = unsafe { libc::read(libc::STDIN_FILENO, buf.as_mut_ptr().cast(), buf.len()) };
+        if n < 0 {
+            interrupted_or(n as i32)?;
+            continue;
+        }
+        if n == 0 {
+            break;
+        }
+        input.extend_from_slice(&buf[..n as usize]);
+        attributes = take_device_attributes(&mut input);
+    }
+    PENDING.lock().unwrap_or_else(|e| e.into_inner()).extend(parse_events(&input));
+    Ok(attributes.is_some_and(|a| a.contains(&4)))
+}
+
+
+/// Find a complete Primary Device Attributes reply (`ESC [ ? attributes c`) in `input`, remove it, and get its attributes.
+fn take_device_attributes(input: &mut Vec<u8>) -> Option<Vec<u32>> {
+    let start = input.windows(3).position(|w| w == b"\x1b[?")? + 3;
+    let len = input[start..].iter().position(|&b| !b.is_ascii_digit() && b != b';')?;
+    if input[start + len] != b'c' {
+        return None;
+    }
+    let attributes = input[start..start + len].split(|&b| b == b';')
+        .filter_map(|a| std::str::from_utf8(a).ok()?.parse().ok())
+        .collect();
+    input.drain(start - 3..=start + len);
+    Some(attributes)
+}
+
+
+/// Get no events for a call that returned `result` `0` or was interrupted by a signal, or the error otherwise.
+fn interrupted_or(result: i32) -> io::Result<Vec<Event>> {
+    if result < 0 {
+        let err = io::Error::last_os_error();
+        if err.kind() != io::ErrorKind::Interrupted {
+            return Err(err);
+        }
+    }
+    Ok(Vec::new())
+}
+
+
+/// What happens when the program gets `SIGINT` or `SIGTERM`, set with `on_interrupt`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OnInterrupt {
+    /// Put the terminal back as `RawMode` found it, then let the signal end the program as it would have. The default.
+    Exit,
+    /// Keep running, and report the signal as an `Event::Interrupt` from `read_events` (and `event_stream`).
+    /// Ctrl+C typed in raw mode comes in as `Event::Interrupt` too, instead of `Key::Ctrl('c')`.
+    Event
+}
+
+
+/// The signals `on_interrupt` and `RawMode` handle.
+const INTERRUPT_SIGNALS: [libc::c_int; 2] = [libc::SIGINT, libc::SIGTERM];
+
+/// Whether interrupts become `Event::Interrupt` instead of ending the program.
+static INTERRUPT_EVENTS: AtomicBool = AtomicBool::new(false);
+
+/// Whether an interrupt arrived that hasn't been reported as an `Event::Interrupt` yet.
+static INTERRUPTED: AtomicBool = AtomicBool::new(false);
+
+/// Whether the handlers for `INTERRUPT_SIGNALS` are installed, and whether `on_interrupt` asked for them to stay.
+static HANDLERS_INSTALLED: AtomicBool = AtomicBool::new(false);
+static KEEP_HANDLERS: AtomicBool = AtomicBool::new(false);
+
+/// The handlers `INTERRUPT_SIGNALS` had before ours, to put back and to pass signals on to.
+static PREVIOUS_HANDLERS: [AtomicUsize; 2] = [AtomicUsize::new(libc::SIG_DFL), AtomicUsize::new(libc::SIG_DFL)];
+
+/// Read and write ends of the pipe signal handlers write to, waking whatever is waiting for input, or `-1` before it is made.
+static WAKE_PIPE: [AtomicI32; 2] = [AtomicI32::new(-1), AtomicI32::new(-1)];
+
+
+/// Make the wake pipe, the first time this is called. Both ends are non-blocking, so a full pipe drops bytes instead of blocking a signal handler.
+fn make_wake_pipe() {
+    static MADE: Once = Once::new();
+    MADE.call_once(|| {
+        let mut fds = [0; 2];
+        // SAFETY: `fds` has room for the two descriptors `pipe` writes.
+        if unsafe { libc::pipe(fds.as_mut_ptr()) } == 0 {
+            for (end, fd) in WAKE_PIPE.iter().zip(fds) {
+                // SAFETY: `fd` is an open descriptor from `pipe`.
+                unsafe { libc::fcntl(fd, libc::F_SETFL, libc::O_NONBLOCK) };
+                end.store(fd, Ordering::Relaxed);
+            }
+        }
+    });
+}
+
+
+/// Wake whatever is waiting for input. Only makes async-signal-safe calls.
+fn wake() {
+    let fd = WAKE_PIPE[1].load(Ordering::Relaxed);
+    if fd >= 0 {
+        // SAFETY: `write` is async-signal-safe, and the pipe is non-blocking.
+        unsafe { libc::write(fd, [0u8].as_ptr().cast(), 1) };
+    }
+}
+
+
+/// Empty the wake pipe after it woke a wait.
+fn drain_wake_pipe() {
+    let mut buf = [0u8; 64];
+    // SAFETY: `buf` is valid for writes of its whole length, and the pipe is non-blocking.
+    while unsafe { libc::read(WAKE_PIPE[0].load(Ordering::Relaxed), buf.as_mut_ptr().cast(), buf.len()) } > 0 {}
+}
+
+
+extern "C" fn on_interrupt_signal(signal: libc::c_int) {
+    if INTERRUPT_EVENTS.load(Ordering::Relaxed) {
+        INTERRUPTED.store(true, Ordering::Relaxed);
+        wake();
+        return;
+    }
+    restore_terminal();
+    let i = INTERRUPT_SIGNALS.iter().position(|&s| s == signal).unwrap_or(0);
+    // SAFETY: `sigaction` and `raise` are async-signal-safe. The signal is blocked until this handler returns,
+    // and then delivered again to the handler it had before, which by default ends the program.
+    unsafe {
+        let mut action: libc::sigaction = std::mem::zeroed();
+        action.sa_sigaction = PREVIOUS_HANDLERS[i].load(Ordering::Relaxed);
+        libc::sigaction(signal, &action, std::ptr::null_mut());
+        libc::raise(signal);
+    }
+}
+
+
+/// Install the handlers for `INTERRUPT_SIGNALS`, and get whether this call installed them.
+fn install_handlers() -> bool {
+    if HANDLERS_INSTALLED.swap(true, Ordering::AcqRel) {
+        return false;
+    }
+    make_wake_pipe();
+    for (signal, previous) in INTERRUPT_SIGNALS.iter().zip(&PREVIOUS_HANDLERS) {
+        // SAFETY: both `sigaction`s are valid, and the handler only makes async-signal-safe calls.
+        // `SA_RESTART` is left out so a signal ends a blocking `poll` in `read_events`.
+        unsafe {
+            let mut action: libc::sigaction = std::mem::zeroed();
+            action.sa_sigaction = on_interrupt_signal as extern "C" fn(libc::c_int) as libc::sighandler_t;
+            let mut old: libc::sigaction = std::mem::zeroed();
+            libc::sigaction(*signal, &action, &mut old);
+            previous.store(old.sa_sigaction, Ordering::Relaxed);
+        }
+    }
+    true
+}
+
+
+/// Put back the handlers `install_handlers` replaced, unless `on_interrupt` asked for them to stay.
+fn uninstall_handlers() {
+    if KEEP_HANDLERS.load(Ordering::Relaxed) || !HANDLERS_INSTALLED.swap(false, Ordering::AcqRel) {
+        return;
+    }
+    for (signal, previous) in INTERRUPT_SIGNALS.iter().zip(&PREVIOUS_HANDLERS) {
+        // SAFETY: `action` is a valid `sigaction` holding the handler the signal had before.
+        unsafe {
+            let mut action: libc::sigaction = std::mem::zeroed();
+            action.sa_sigaction = previous.load(Ordering::Relaxed);
+            libc::sigaction(*signal, &action, std::ptr::null_mut());
+        }
+    }
+}
+
+
+/// Choose what happens when the program gets `SIGINT` or `SIGTERM`, and handle them from now on.
+/// `RawMode` handles them while it is on even without this, with `OnInterrupt::Exit`.
+///
+/// Example
+/// ```
+/// use terminalgl::input::{self, Event, OnInterrupt};
+/// use std::process::Command;
+/// use std::time::Duration;
+/// input::on_interrupt(OnInterrupt::Event);
+/// Command::new("kill").args(["-INT", &std::process::id().to_string()]).status().unwrap();
+/// let mut events = Vec::new();
+/// while events.is_empty() {
+///     events = input::read_events(Duration::from_millis(100)).unwrap();
+/// }
+/// assert_eq!(events[0], Event::Interrupt);
+/// ```
+pub fn on_interrupt(action: OnInterrupt) {
+    INTERRUPT_EVENTS.store(action == OnInterrupt::Event, Ordering::Relaxed);
+    KEEP_HANDLERS.store(true, Ordering::Relaxed);
+    install_handlers();
+}
+
+
+/// Whether the terminal is in raw mode from a `RawMode` that hasn't been torn down yet.
+static RAW_MODE: AtomicBool = AtomicBool::new(false);
+
+/// Whether the `RawMode` that is on turned mouse reporting on.
+static RAW_MOUSE: AtomicBool = AtomicBool::new(false);
+
+/// The terminal settings from before `RawMode` started, for `restore_terminal` to put back.
+struct SavedTermios(UnsafeCell<MaybeUninit<libc::termios>>);
+
+// SAFETY: the settings are only written by `RawMode::start` before it sets `RAW_MODE`,
+// and only read by the one `restore_terminal` call that clears `RAW_MODE`.
+unsafe impl Sync for SavedTermios {}
+
+static SAVED_TERMIOS: SavedTermios = SavedTermios(UnsafeCell::new(MaybeUninit::uninit()));
+
+
+/// Leave raw mode: turn off mouse and focus reporting and bracketed paste, reset colors, show the cursor, leave the alternate screen, and put back the
+/// terminal settings. Dropping the `RawMode`, the panic hook, and the interrupt handler all call this, and only the first call
+/// after `RawMode` starts does anything. Only makes async-signal-safe calls, and writes straight to stdout,
+/// so flush anything printed before calling it outside a signal handler.
+fn restore_terminal() {
+    if RAW_MODE.compare_exchange(true, false, Ordering::AcqRel, Ordering::Acquire).is_err() {
+        return;
+    }
+    let reset: &[u8] = if RAW_MOUSE.load(Ordering::Relaxed) {
+        b"\x1b[?1006l\x1b[?1003l\x1b[?1002l\x1b[?1000l\x1b[?1004l\x1b[?2004l\x1b[0m\x1b[?25h\x1b[?1049l"
+    }
+    else {
+        b"\x1b[?1004l\x1b[?2004l\x1b[0m\x1b[?25h\x1b[?1049l"
+    };
+    // SAFETY: `write` and `tcsetattr` are async-signal-safe, and `SAVED_TERMIOS` was filled in before `RAW_MODE` was set.
+    unsafe {
+        libc::write(libc::STDOUT_FILENO, reset.as_ptr().cast(), reset.len());
+        libc::tcsetattr(libc::STDIN_FILENO, libc::TCSAFLUSH, (*SAVED_TERMIOS.0.get()).as_ptr());
+    }
+}
+
+
+/// Whether the terminal was resized since an `EventStream` last reported it.
+#[cfg(feature = "async")]
+static RESIZED: AtomicBool = AtomicBool::new(false);
+
+
+/// The waker of the `EventStream` waiting for input, and the signal that one is waiting.
+#[cfg(feature = "async")]
+struct Watcher {
+    waker: Mutex<Option<Waker>>,
+    waiting: Condvar
+}
+
+
+#[cfg(feature = "async")]
+extern "C" fn on_resize(_: libc::c_int) {
+    RESIZED.store(true, Ordering::Relaxed);
+    wake();
+}
+
+
+/// Get the watcher, starting it the first time: a `SIGWINCH` handler that writes to the wake pipe,
+/// and a thread that waits for stdin or the wake pipe to be readable while an `EventStream` is waiting, then wakes it.
+#[cfg(feature = "async")]
+fn watcher() -> &'static Watcher {
+    static WATCHER: OnceLock<Watcher> = OnceLock::new();
+    WATCHER.get_or_init(|| {
+        make_wake_pipe();
+        // SAFETY: the handler only makes async-signal-safe calls.
+        unsafe {
+            let mut action: libc::sigaction = std::mem::zeroed();
+            action.sa_sigaction = on_resize as extern "C" fn(libc::c_int) as libc::sighandler_t;
+            action.sa_flags = libc::SA_RESTART;
+            libc::sigaction(libc::SIGWINCH, &action, std::ptr::null_mut());
+        }
+        std::thread::spawn(|| {
+            let watcher = watcher();
+            loop {
+                let mut waker = watcher.waker.lock().unwrap_or_else(|e| e.into_inner());
+                while waker.is_none() {
+                    waker = watcher.waiting.wait(waker).unwrap_or_else(|e| e.into_inner());
+                }
+                drop(waker);
+                let mut fds = [
+                    libc::pollfd { fd: libc::STDIN_FILENO, events: libc::POLLIN, revents: 0 },
+                    libc::pollfd { fd: WAKE_PIPE[0].load(Ordering::Relaxed), events: libc::POLLIN, revents: 0 }
+                ];
+                // SAFETY: `fds` is two valid `pollfd`s. A wake pipe of `-1` is ignored by `poll`.
+                if unsafe { libc::poll(fds.as_mut_ptr(), 2, -1) } <= 0 {
+                    continue;
+                }
+                if fds[1].revents != 0 {
+                    drain_wake_pipe();
+                }
+                if let Some(waker) = watcher.waker.lock().unwrap_or_else(|e| e.into_inner()).take() {
+                    waker.wake();
+                }
+            }
+        });
+        Watcher { waker: Mutex::new(None), waiting: Condvar::new() }
+    })
+}
+
+
+/// A stream of input events for async code, from `event_stream`.
+///
+/// `poll_next` has the signature of `futures::Stream::poll_next`, so the stream can be wrapped as one,
+/// and `next` gives a future that can wait in `tokio::select!` alongside timers and other futures.
+/// The stream ends at the end of input or on a read error.
+#[cfg(feature = "async")]
+pub struct EventStream {
+    ended: bool
+}
+
+#[cfg(feature = "async")]
+impl EventStream {
+    /// Get the next event if one is ready, or register `cx`'s waker to be woken when input or a resize arrives.
+    pub fn poll_next(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Option<Event>> {
+        let stream = self.get_mut();
+        if stream.ended {
+            return Poll::Ready(None);
+        }
+        if INTERRUPTED.swap(false, Ordering::Relaxed) {
+            return Poll::Ready(Some(Event::Interrupt));
+        }
+        if RESIZED.swap(false, Ordering::Relaxed) {
+            let (columns, rows) = super::size();
+            return Poll::Ready(Some(Event::Resize(columns, rows)));
+        }
+        let mut pending = PENDING.lock().unwrap_or_else(|e| e.into_inner());
+        if pending.is_empty() {
+            match read_input(Duration::ZERO) {
+                Ok(Some(events)) => pending.extend(events),
+                Ok(None) | Err(_) => {
+                    stream.ended = true;
+                    return Poll::Ready(None);
+                }
+            }
+        }
+        if let Some(event) = pending.pop_front() {
+            return Poll::Ready(Some(event));
+        }
+        drop(pending);
+        let watcher = watcher();
+        *watcher.waker.lock().unwrap_or_else(|e| e.into_inner()) = Some(cx.waker().clone());
+        watcher.waiting.notify_one();
+        // The watcher may have been woken by a signal just before the waker was registered, with no one to wake.
+        if RESIZED.load(Ordering::Relaxed) || INTERRUPTED.load(Ordering::Relaxed) {
+            cx.waker().wake_by_ref();
+        }
+        Poll::Pending
+    }
+
+    /// Wait for the next event, or get `None` once the stream has ended.
+    pub async fn next(&mut self) -> Option<Event> {
+        std::future::poll_fn(|cx| Pin::new(&mut *self).poll_next(cx)).await
+    }
+}
+
+
+/// Get a stream of input events for async code, including `Event::Resize` when the terminal is resized.
+/// Waiting for input doesn't block the task: a single background thread, shared by every stream,
+/// watches stdin and wakes the task when there is something to read.
+///
+/// Like `read_events`, it reads the terminal in whatever mode it is in, so enter raw mode with `RawMode` first.
+/// Events read by either are only handed out once, so the two can be used one after the other.
+///
+/// Example
+/// ```no_run
+/// use terminalgl::input::{self, Event, Key, RawMode};
+/// async fn run() {
+///     let _raw = RawMode::enable().unwrap();
+///     let mut events = input::event_stream();
+///     while let Some(event) = events.next().await {
+///         match event {
+///             Event::Key(Key::Char('q')) => break,
+///             Event::Resize(columns, rows) => println!("now {}x{}", columns, rows),
+///             _ => {}
+///         }
+///     }
+/// }
+/// ```
+#[cfg(feature = "async")]
+pub fn event_stream() -> EventStream {
+    watcher();
+    EventStream { ended: false }
+}
+
+
+/// Guard that puts the terminal in raw mode, switches to the alternate screen and hides the cursor,
+/// then puts everything back when dropped. Only one can be on at a time.
+/// Keys arrive as they are pressed with nothing echoed, and Ctrl+C comes in as `Key::Ctrl('c')` instead of stopping the program.
+/// Bracketed paste is turned on, so pasted text comes in as one `Event::Paste` rather than as keys,
+/// and so is focus reporting, for `Event::FocusGained` and `Event::FocusLost` from terminals that send them.
+/// Output still turns `\n` into a new line, so printing and drawing work as usual.
+///
+/// The terminal is put back exactly once, by whichever comes first: dropping the guard, a panic (before the panic message
+/// is printed, so it shows on the normal screen), or `SIGINT` or `SIGTERM` (see `on_interrupt`). The signal handlers
+/// it installs are removed again when it is dropped.
+///
+/// Example
+/// ```no_run
+/// use terminalgl::input::{self, Event, Key, RawMode};
+/// use terminalgl::draw;
+/// use std::time::Duration;
+/// let _raw = RawMode::with_mouse().unwrap();
+/// loop {
+///     for event in input::read_events(Duration::from_millis(50)).unwrap() {
+///         match event {
+///             Event::Key(Key::Char('q') | Key::Esc | Key::Ctrl('c')) => return,
+///             Event::Mouse(mouse) => draw::pixel(mouse.x, mouse.y, '#'),
+///             _ => {}
+///         }
+///     }
+///     terminalgl::flush();
+/// }
+/// ```
+pub struct RawMode {
+    installed_handlers: bool
+}
+
+impl RawMode {
+    /// Enter raw mode, or get the error if stdin is not a terminal or raw mode is already on.
+    pub fn enable() -> io::Result<RawMode> {
+        RawMode::start(false, false)
+    }
+
+    /// Enter raw mode like `enable`, and also turn on mouse reporting for `read_events`.
+    pub fn with_mouse() -> io::Result<RawMode> {
+        RawMode::start(true, false)
+    }
+
+    /// Enter raw mode with mouse reporting like `with_mouse`, and also report the mouse moving with no button held,
+    /// as `MouseKind::Move` events, for tracking what it hovers over.
+    pub fn with_mouse_motion() -> io::Result<RawMode> {
+        RawMode::start(true, true)
+    }
+
+    fn start(mouse: bool, motion: bool) -> io::Result<RawMode> {
+        if RAW_MODE.load(Ordering::Acquire) {
+            return Err(io::Error::new(io::ErrorKind::AlreadyExists, "raw mode is already on"));
+        }
+        // SAFETY: `termios` is plain data, and is only used after `tcgetattr` fills it in.
+        let mut termios: libc::termios = unsafe { std::mem::zeroed() };
+        if unsafe { libc::tcgetattr(libc::STDIN_FILENO, &mut termios) } != 0 {
+            return Err(io::Error::last_os_error());
+        }
+        // SAFETY: raw mode is off, so nothing reads the saved settings until `RAW_MODE` is set below.
+        unsafe { (*SAVED_TERMIOS.0.get()).write(termios) };
+        // SAFETY: `termios` is a valid `termios` from `tcgetattr`.
+        unsafe { libc::cfmakeraw(&mut termios) };
+        termios.c_oflag |= libc::OPOST;
+        if unsafe { libc::tcsetattr(libc::STDIN_FILENO, libc::TCSAFLUSH, &termios) } != 0 {
+            return Err(io::Error::last_os_error());
+        }
+        print!("\x1b[?1049h\x1b[?25l\x1b[?2004h\x1b[?1004h");
+        if mouse {
+            print!("\x1b[?1000h\x1b[?1002h\x1b[?1006h");
+        }
+        if motion {
+            print!("\x1b[?1003h");
+        }
+        io::stdout().flush()?;
+        RAW_MOUSE.store(mouse, Ordering::Relaxed);
+        RAW_MODE.store(true, Ordering::Release);
+
+        static PANIC_HOOK: Once = Once::new();
+        PANIC_HOOK.call_once(|| {
+            let previous = std::panic::take_hook();
+            std::panic::set_hook(Box::new(move |info| {
+                let _ = io::stdout().flush();
+                restore_terminal();
+                previous(info);
+            }));
+        });
+        Ok(RawMode { installed_handlers: install_handlers() })
+    }
+}
+
+impl Drop for RawMode {
+    fn drop(&mut self) {
+        let _ = io::stdout().flush();
+        restore_terminal();
+        if self.installed_handlers {
+            uninstall_handlers();
+        }
+    }
+}
@@ -0,0 +1,188 @@
+// Key bindings: the action for each key, looked up from input events, and a help screen listing them.
+use super::canvas::Canvas;
+use super::chars::LineStyle;
+use super::input::{Event, Key};
+use super::theme;
+use super::{raster, Rect, Truncation};
+use std::fmt;
+
+
+/// A key bound to an action in a `KeyMap`, with what it does in words for the help screen.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Binding<A> {
+    pub key: Key,
+    pub action: A,
+    pub description: String
+}
+
+
+/// Error for binding a key that is already bound, with the description of the binding it already has.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct KeyConflict {
+    pub key: Key,
+    pub existing: String
+}
+
+impl fmt::Display for KeyConflict {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} is already bound to \"{}\"", self.key, self.existing)
+    }
+}
+
+impl std::error::Error for KeyConflict {}
+
+
+/// The actions of type `A` (such as an enum of everything the application does) that keys are bound to, in the order they
+/// were bound. Look up the action for each input event with `lookup`, and show every binding with `render_help`,
+/// so the keys an application handles and its help screen can't disagree.
+///
+/// Example
+/// ```
+/// use terminalgl::keymap::KeyMap;
+/// use terminalgl::input::{Event, Key};
+/// #[derive(Debug, PartialEq)]
+/// enum Action { Save, Quit, Help }
+/// let mut keys = KeyMap::new();
+/// keys.bind(Key::Ctrl('s'), Action::Save, "Save the file").unwrap();
+/// keys.bind(Key::Char('q'), Action::Quit, "Quit").unwrap();
+/// keys.bind(Key::Char('?'), Action::Help, "Show this help").unwrap();
+/// assert_eq!(keys.lookup(&Event::Key(Key::Ctrl('s'))), Some(&Action::Save));
+/// assert_eq!(keys.lookup(&Event::Key(Key::Char('x'))), None);
+///
+/// let err = keys.bind(Key::Char('q'), Action::Save, "Save and quit").unwrap_err();
+/// assert_eq!(err.to_string(), "q is already bound to \"Quit\"");
+/// assert_eq!(keys.unbind(Key::Char('q')), Some(Action::Quit));
+/// assert!(keys.bind(Key::Char('q'), Action::Save, "Save and quit").is_ok());
+/// ```
+#[derive(Clone, Debug)]
+pub struct KeyMap<A> {
+    bindings: Vec<Binding<A>>
+}
+
+impl<A> Default for KeyMap<A> {
+    fn default() -> Self {
+        KeyMap::new()
+    }
+}
+
+impl<A> KeyMap<A> {
+    /// Create a key map with no keys bound.
+    pub fn new() -> KeyMap<A> {
+        KeyMap { bindings: Vec::new() }
+    }
+
+    /// Bind `key` to `action`, described as `description`, or get a `KeyConflict` if `key` is already bound.
+    pub fn bind(&mut self, key: Key, action: A, description: &str) -> Result<(), KeyConflict> {
+        if let Some(existing) = self.binding(key) {
+            return Err(KeyConflict { key, existing: existing.description.clone() });
+        }
+        self.bindings.push(Binding { key, action, description: description.to_string() });
+        Ok(())
+    }
+
+    /// Remove the binding for `key`, and get the action it was bound to, if any.
+    pub fn unbind(&mut self, key: Key) -> Option<A> {
+        let i = self.bindings.iter().position(|binding| binding.key == key)?;
+        Some(self.bindings.remove(i).action)
+    }
+
+    /// Get the action bound to the key pressed in `event`, if it is a key event with a bound key.
+    pub fn lookup(&self, event: &Event) -> Option<&A> {
+        match event {
+            Event::Key(key) => self.binding(*key).map(|binding| &binding.action),
+            _ => None
+        }
+    }
+
+    /// Get the binding for `key`, if it is bound.
+    pub fn binding(&self, key: Key) -> Option<&Binding<A>> {
+        self.bindings.iter().find(|binding| binding.key == key)
+    }
+
+    /// Get every binding, in the order they were bound.
+    pub fn bindings(&self) -> &[Binding<A>] {
+        &self.bindings
+    }
+
+    /// Get the number of keys bound.
+    pub fn len(&self) -> usize {
+        self.bindings.len()
+    }
+
+    /// Get whether no keys are bound.
+    pub fn is_empty(&self) -> bool {
+        self.bindings.is_empty()
+    }
+
+    /// Get the number of pages `render_help` splits the bindings into to fit in `rect`.
+    pub fn help_pages(&self, rect: Rect) -> usize {
+        self.bindings.len().div_ceil(rect.height.saturating_sub(2).max(1)).max(1)
+    }
+
+    /// Draw a help screen filling `rect` with `ccode`, or the theme's colors if it is empty: a box titled "Keys" listing each key chord and its description in two columns,
+    /// in the order they were bound. If the bindings don't all fit, they are split into pages, `page` of them is drawn
+    /// (the last if `page` is past the end), and the box shows the page number on its bottom edge.
+    /// Descriptions too long for the box are cut short with `…`. Returns the number of pages.
+    ///
+    /// Example
+    /// ```
+    /// use terminalgl::keymap::KeyMap;
+    /// use terminalgl::input::Key;
+    /// use terminalgl::canvas::Canvas;
+    /// use terminalgl::{assert_frame_eq, Rect};
+    /// let mut keys = KeyMap::new();
+    /// keys.bind(Key::Ctrl('s'), 0, "Save").unwrap();
+    /// keys.bind(Key::F(5), 1, "Refresh").unwrap();
+    /// keys.bind(Key::Up, 2, "Move up a line").unwrap();
+    /// keys.bind(Key::Char('?'), 3, "Help").unwrap();
+    /// let mut canvas = Canvas::new(22, 5);
+    /// let rect = canvas.rect();
+    /// assert_eq!(keys.render_help(&mut canvas, rect, 0, ""), 2);
+    /// assert_frame_eq!(canvas, "\
+    /// |╭─────── Keys ───────╮|
+    /// |│ Ctrl+S  Save       │|
+    /// |│ F5      Refresh    │|
+    /// |│ ↑       Move up a… │|
+    /// |╰─────────────── 1/2 ╯|");
+    ///
+    /// assert_eq!(keys.render_help(&mut canvas, rect, 1, ""), 2);
+    /// assert_frame_eq!(canvas, "\
+    /// |╭─────── Keys ───────╮|
+    /// |│ ?  Help            │|
+    /// |│                    │|
+    /// |│                    │|
+    /// |╰─────────────── 2/2 ╯|");
+    /// ```
+    pub fn render_help(&self, canvas: &mut Canvas, rect: Rect, page: usize, ccode: &str) -> usize {
+        let pages = self.help_pages(rect);
+        let page = page.min(pages - 1);
+        let rows = rect.height.saturating_sub(2).max(1);
+        let shown = self.bindings.iter().skip(page * rows).take(rows).map(|binding| (binding.key.to_string(), &binding.description));
+        let shown: Vec<(String, &String)> = shown.collect();
+        let key_width = shown.iter().map(|(key, _)| raster::text_width(key)).max().unwrap_or(0);
+        let inner = rect.width.saturating_sub(4);
+        let theme = theme::current();
+        let border = theme::pick(ccode, &theme.border);
+
+        canvas.rectangle(rect.x, rect.y, rect.width, rect.height, ' ', theme::pick(ccode, &theme.background), true);
+        canvas.border(rect, LineStyle::Rounded, border);
+        let middle = rect.x.saturating_add(rect.width as isize / 2);
+        canvas.text(middle - 3, rect.y, &raster::truncate_to_width(" Keys ", inner, Truncation::End), theme::pick(ccode, &theme.title));
+        for (i, (key, description)) in shown.iter().enumerate() {
+            let (x, y) = (rect.x.saturating_add(2), rect.y.saturating_add(1 + i as isize));
+            canvas.text(x, y, &raster::truncate_to_width(key, inner, Truncation::End), theme::pick(ccode, &theme.accent));
+            if inner > key_width + 2 {
+                let x = x.saturating_add(key_width as isize + 2);
+                canvas.text(x, y, &raster::truncate_to_width(description, inner - key_width - 2, Truncation::End), theme::pick(ccode, &theme.text));
+            }
+        }
+        if pages > 1 {
+            let right = rect.x.saturating_add(rect.width as isize - 1);
+            let bottom = rect.y.saturating_add(rect.height as isize - 1);
+            let label = format!(" {}/{} ", page + 1, pages);
+            let x = right - raster::text_width(&label) as isize;
+            canvas.text(x, bottom, &label, border);
+        }
+        pages
+    }
+}
@@ -1,28 +1,197 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+#![allow(clippy::too_many_arguments)]
+
+#[cfg(feature = "std")]
+pub mod ansi;
+#[cfg(feature = "std")]
+pub mod automata;
+#[cfg(feature = "std")]
+pub mod backend;
+#[cfg(feature = "bidi")]
+pub mod bidi;
+#[cfg(feature = "std")]
+pub mod braille;
+#[cfg(feature = "std")]
+pub mod canvas;
+#[cfg(feature = "std")]
+pub mod chart;
+pub mod chars;
+pub mod core;
+#[cfg(feature = "widgets")]
+pub mod debug;
+#[cfg(feature = "std")]
 pub mod draw;
+#[cfg(feature = "std")]
 pub mod drawc;
+#[cfg(feature = "std")]
+pub mod effects;
+#[cfg(feature = "std")]
+pub mod fov;
+#[cfg(feature = "std")]
+pub mod fmt;
+#[cfg(feature = "std")]
+pub mod headless;
+#[cfg(all(feature = "input", unix))]
+pub mod input;
+#[cfg(all(feature = "input", unix))]
+pub mod keymap;
+#[cfg(feature = "std")]
+pub mod lsystem;
+#[cfg(feature = "std")]
+pub mod maze;
+#[cfg(feature = "std")]
+pub mod noise;
+#[cfg(feature = "std")]
+pub mod particles;
+#[cfg(feature = "std")]
+pub mod path;
+#[cfg(feature = "std")]
+pub mod player;
+/// Common items, so that one `use` line covers a typical program.
+/// Uncolored drawing stays under `draw` and colored drawing under `drawc`, both of which the prelude brings into scope.
+///
+/// Example
+/// ```
+/// use terminalgl::prelude::*;
+/// clear();
+/// draw::rectangle(0, 0, 13, 3, '#', false);
+/// drawc::text(1, 1, "hello world", drawc::GREEN);
+/// drawc::pixel(12, 1, '!', NamedColor::BrightRed.fg_code());
+///
+/// Sketch::with_size(14, 3)
+///     .rect(0, 0, 14, 3, '#')
+///     .style(drawc::GREEN)
+///     .text(1, 1, "hello sketch")
+///     .present()
+///     .unwrap();
+/// ```
+#[cfg(feature = "std")]
+pub mod prelude;
+#[cfg(feature = "std")]
+pub mod present;
+#[cfg(feature = "std")]
+pub mod raster;
+#[cfg(feature = "std")]
+pub mod template;
+#[cfg(feature = "std")]
+pub mod theme;
+#[cfg(feature = "std")]
+pub mod sketch;
+#[cfg(feature = "std")]
+pub mod tilemap;
+#[cfg(feature = "std")]
+pub mod timer;
+#[cfg(feature = "std")]
+pub mod turtle;
+#[cfg(feature = "widgets")]
+pub mod ui;
 
 
 /// Moves the cursor to `(x, y)` with the top left corner being `(0, 0)`.
+/// The escape sequence itself is 1-based, so this prints `ESC[{y+1};{x+1}H`.
+#[cfg(feature = "std")]
 pub fn cursorto(x: usize, y: usize) {
-    print!("\x1b[{};{}H", y+1, x+1);
+    print!("\x1b[{};{}H", y.saturating_add(1), x.saturating_add(1));
+}
+
+
+/// Moves the cursor to `(x, y)` like `cursorto`, or returns `OutOfBounds` without printing anything
+/// if the cell is not on the terminal. The terminal size is looked up once and reused until the next `size` or `flush`,
+/// so checking every cell of a frame costs a single lookup.
+///
+/// Example
+/// ```
+/// use terminalgl as tgl;
+/// assert!(tgl::cursorto_checked(0, 0).is_ok());
+/// let err = tgl::cursorto_checked(-1, isize::MAX).unwrap_err();
+/// assert_eq!((err.x, err.y), (-1, isize::MAX));
+/// ```
+#[cfg(feature = "std")]
+pub fn cursorto_checked(x: isize, y: isize) -> Result<(), OutOfBounds> {
+    let size = cached_size();
+    if !Rect::from_size(size).contains(x, y) {
+        return Err(OutOfBounds { x, y, size });
+    }
+    cursorto(x as usize, y as usize);
+    Ok(())
+}
+
+
+/// Error for a cell `(x, y)` that is not on a terminal of `size` `(cols, rows)`.
+#[cfg(feature = "std")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct OutOfBounds {
+    pub x: isize,
+    pub y: isize,
+    pub size: (u16, u16)
+}
+
+#[cfg(feature = "std")]
+impl std::fmt::Display for OutOfBounds {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "cell ({}, {}) is outside the {}x{} terminal", self.x, self.y, self.size.0, self.size.1)
+    }
 }
 
+#[cfg(feature = "std")]
+impl std::error::Error for OutOfBounds {}
 
-/// Clear the terminal.
+
+/// Clear the terminal, along with any images `drawc::image_native` showed.
+#[cfg(feature = "std")]
 pub fn clear() {
+    #[cfg(feature = "image")]
+    drawc::delete_images();
     print!("\x1b[2J\x1b[0;0H");
 }
 
 
+/// Send everything printed so far to the terminal. Drawing functions print without flushing,
+/// so call this once per frame when nothing else (such as a newline) flushes the output.
+/// The terminal size `cursorto_checked` uses is looked up again after this, so drawing follows resizes from frame to frame.
+#[cfg(feature = "std")]
+pub fn flush() {
+    let _ = std::io::Write::flush(&mut std::io::stdout());
+    SIZE.store(0, std::sync::atomic::Ordering::Relaxed);
+}
+
+
 /// Get terminal size as `(cols, rows)`.
+/// Falls back to `(80, 24)` when the output is not a terminal.
+#[cfg(feature = "std")]
 pub fn size() -> (u16, u16) {
-    termsize::get().map(|size| {
+    let size = termsize::get().map(|size| {
         (size.cols, size.rows)
-    }).unwrap()
+    }).unwrap_or((80, 24));
+    SIZE.store((size.0 as u32) << 16 | size.1 as u32, std::sync::atomic::Ordering::Relaxed);
+    size
+}
+
+
+/// The terminal size last found by `size`, packed as `cols << 16 | rows`, or 0 if it needs looking up.
+#[cfg(feature = "std")]
+static SIZE: std::sync::atomic::AtomicU32 = std::sync::atomic::AtomicU32::new(0);
+
+
+/// Get the terminal size last found by `size`, looking it up if there is none.
+#[cfg(feature = "std")]
+fn cached_size() -> (u16, u16) {
+    match SIZE.load(std::sync::atomic::Ordering::Relaxed) {
+        0 => size(),
+        packed => ((packed >> 16) as u16, packed as u16)
+    }
 }
 
 
-/// Direction for `straight_line`.
+/// Get the terminal as a `Rect` with its top left corner at `(0, 0)`. Same as `Rect::screen`.
+#[cfg(feature = "std")]
+pub fn screen_rect() -> Rect {
+    Rect::screen()
+}
+
+
+/// Direction for `straight_line`, and the side of the center `raster::ellipse_half` keeps.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum Direction {
     Left,
     Right,
@@ -32,8 +201,508 @@ pub enum Direction {
 
 
 /// Alignment for `text_aligned`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum TextAlignment {
     Left,
     Right,
     Center
 }
+
+
+/// Reading direction for text drawn with `raster::text_directed` and the drawing functions built on it.
+/// `Rtl` text is laid out from right to left, and alignments name its start and end, so `TextAlignment::Left` text
+/// read right to left ends at the anchor like `TextAlignment::Right` text read left to right.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum TextDirection {
+    #[default]
+    Ltr,
+    Rtl
+}
+
+
+/// Where `raster::truncate_to_width` cuts text that is too wide: `End` keeps the start and `Start` keeps the end, each marking the cut
+/// with `…`, `Middle` keeps both ends around a `…`, and `None` cuts the end off with no mark.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Truncation {
+    #[default]
+    End,
+    Start,
+    Middle,
+    None
+}
+
+
+/// Options for `raster::wrap_text`.
+/// With `break_anywhere`, words wider than a line are split between any two graphemes; without it they stick out past the end of a line of their own.
+/// With `hyphenate`, soft hyphens (U+00AD) are places a word may break, shown as `-` where a line breaks at one and left out elsewhere.
+/// With `preserve_whitespace`, runs of spaces inside a line are kept as written rather than collapsed into one, as are spaces starting a paragraph.
+/// With `attach_punctuation`, lines never start with closing punctuation such as `,` `.` `)` or `。`, nor end with opening punctuation such as `(` or `「`.
+/// The default turns everything on but `preserve_whitespace`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct WrapOptions {
+    pub break_anywhere: bool,
+    pub hyphenate: bool,
+    pub preserve_whitespace: bool,
+    pub attach_punctuation: bool
+}
+
+impl Default for WrapOptions {
+    fn default() -> WrapOptions {
+        WrapOptions { break_anywhere: true, hyphenate: true, preserve_whitespace: false, attach_punctuation: true }
+    }
+}
+
+
+/// Vertical alignment for `Rect::align_in`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum VAlign {
+    Top,
+    Middle,
+    Bottom
+}
+
+
+/// Where `Rect::anchor` places a rectangle inside its parent: a corner, the middle of an edge, or the center.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Anchor {
+    TopLeft,
+    Top,
+    TopRight,
+    Left,
+    Center,
+    Right,
+    BottomLeft,
+    Bottom,
+    BottomRight
+}
+
+
+/// A quarter of an ellipse for `raster::ellipse_quadrant`, named by the corner of the ellipse's bounding box it is in.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Quadrant {
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    BottomRight
+}
+
+
+/// Arrowhead style for `arrow`.
+/// `Char` draws a single `<`, `>`, `^`, or `v` chosen by the arrow's direction,
+/// `Chevron` draws two short strokes, and `Triangle` draws a filled triangle of the given size.
+#[derive(Clone, Copy)]
+pub enum ArrowHead {
+    Char,
+    Chevron,
+    Triangle(usize)
+}
+
+
+/// A rectangle of cells with its top left corner at `(x, y)`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Rect {
+    pub x: isize,
+    pub y: isize,
+    pub width: usize,
+    pub height: usize
+}
+
+impl Rect {
+    /// Create a rectangle at `(x, y)` with width `width` and height `height`.
+    pub fn new(x: isize, y: isize, width: usize, height: usize) -> Rect {
+        Rect { x, y, width, height }
+    }
+
+    /// Create a rectangle at `(0, 0)` covering a terminal of `size` `(cols, rows)`, as returned by `size`.
+    ///
+    /// Example
+    /// ```
+    /// use terminalgl::Rect;
+    /// assert_eq!(Rect::from_size((80, 24)), Rect::new(0, 0, 80, 24));
+    /// ```
+    pub fn from_size(size: (u16, u16)) -> Rect {
+        Rect::new(0, 0, size.0 as usize, size.1 as usize)
+    }
+
+    /// Get the terminal as a rectangle with its top left corner at `(0, 0)`.
+    ///
+    /// Example
+    /// ```
+    /// use terminalgl as tgl;
+    /// use tgl::Rect;
+    /// assert_eq!(Rect::screen(), Rect::from_size(tgl::size()));
+    /// for (x, y) in Rect::screen().cells() {
+    ///     tgl::draw::pixel(x, y, '.');
+    /// }
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn screen() -> Rect {
+        Rect::from_size(size())
+    }
+
+    /// Create the smallest rectangle holding both corner cells `a` and `b`, whichever way round they are,
+    /// such as the cell a drag started in and the one it is over now.
+    ///
+    /// Example
+    /// ```
+    /// use terminalgl::Rect;
+    /// assert_eq!(Rect::spanning((2, 1), (5, 3)), Rect::new(2, 1, 4, 3));
+    /// assert_eq!(Rect::spanning((5, 3), (2, 1)), Rect::new(2, 1, 4, 3));
+    /// assert_eq!(Rect::spanning((4, 0), (4, 0)), Rect::new(4, 0, 1, 1));
+    /// ```
+    pub fn spanning(a: (isize, isize), b: (isize, isize)) -> Rect {
+        let width = a.0.abs_diff(b.0).saturating_add(1);
+        let height = a.1.abs_diff(b.1).saturating_add(1);
+        Rect::new(a.0.min(b.0), a.1.min(b.1), width, height)
+    }
+
+    /// Get every cell of the rectangle in row order, leaving out any past the limits of `isize`.
+    ///
+    /// Example
+    /// ```
+    /// use terminalgl::Rect;
+    /// let cells: Vec<(isize, isize)> = Rect::new(-1, 2, 2, 2).cells().collect();
+    /// assert_eq!(cells, vec![(-1, 2), (0, 2), (-1, 3), (0, 3)]);
+    /// assert_eq!(Rect::new(isize::MAX - 1, 0, 5, 1).cells().count(), 2);
+    /// ```
+    pub fn cells(&self) -> impl Iterator<Item = (isize, isize)> {
+        let Rect { x, y, width, height } = *self;
+        (0..height).map_while(move |row| y.checked_add_unsigned(row)).flat_map(move |y| {
+            (0..width).map_while(move |col| x.checked_add_unsigned(col)).map(move |x| (x, y))
+        })
+    }
+
+    /// Get the overlap of this rectangle and `other`, which is empty (zero width and height) if they don't overlap.
+    ///
+    /// Example
+    /// ```
+    /// use terminalgl::Rect;
+    /// assert_eq!(Rect::new(0, 0, 10, 5).intersect(Rect::new(-2, 3, 4, 4)), Rect::new(0, 3, 2, 2));
+    /// assert_eq!(Rect::new(0, 0, 2, 2).intersect(Rect::new(5, 5, 2, 2)).width, 0);
+    /// assert_eq!(Rect::new(isize::MAX, 0, usize::MAX, 1).intersect(Rect::new(isize::MIN, 0, usize::MAX, 1)).width, 0);
+    /// ```
+    pub fn intersect(&self, other: Rect) -> Rect {
+        let x1 = self.x.max(other.x);
+        let y1 = self.y.max(other.y);
+        let x2 = (self.x as i128 + self.width as i128).min(other.x as i128 + other.width as i128);
+        let y2 = (self.y as i128 + self.height as i128).min(other.y as i128 + other.height as i128);
+        if x2 <= x1 as i128 || y2 <= y1 as i128 {
+            return Rect::new(x1, y1, 0, 0);
+        }
+        Rect::new(x1, y1, (x2 - x1 as i128) as usize, (y2 - y1 as i128) as usize)
+    }
+
+    /// Get whether the cell `(x, y)` is inside the rectangle.
+    pub fn contains(&self, x: isize, y: isize) -> bool {
+        x >= self.x && y >= self.y && (x as i128) < self.x as i128 + self.width as i128 && (y as i128) < self.y as i128 + self.height as i128
+    }
+
+    /// Get the absolute position of the cell `(x, y)` measured from the rectangle's top left corner,
+    /// or `None` if it is past the limits of `isize`.
+    ///
+    /// Example
+    /// ```
+    /// use terminalgl::Rect;
+    /// assert_eq!(Rect::new(5, 2, 10, 10).translate(1, 1), Some((6, 3)));
+    /// assert_eq!(Rect::new(isize::MAX, 0, 10, 10).translate(1, 0), None);
+    /// ```
+    pub fn translate(&self, x: isize, y: isize) -> Option<(isize, isize)> {
+        Some((self.x.checked_add(x)?, self.y.checked_add(y)?))
+    }
+
+    /// Split the rectangle into columns sized by `constraints`, left to right. The columns always tile the rectangle exactly,
+    /// with every column as tall as it and every cell in exactly one column, so splits nest without gaps or overlaps.
+    ///
+    /// Sizes are worked out like this, so rounding always hands spare cells to later columns:
+    /// - `Fixed` and `Percent` columns are sized first, in order, each getting at most what the columns before it left.
+    /// - The `Percent` columns are cut where the running total of their percentages falls: a column that brings the total to `q` percent
+    ///   ends `floor(q * width / 100)` cells into the rectangle, so 33, 33, and 34 percent of 7 cells are 2, 2, and 3 cells wide.
+    /// - `Fill` columns share the cells left over the same way, cut by the running total of their weights.
+    /// - Any cells still left over (when there are no `Fill` columns to take them) go to the last column.
+    ///
+    /// Example
+    /// ```
+    /// use terminalgl::{Constraint::*, Rect};
+    /// let widths = |rects: Vec<Rect>| rects.iter().map(|r| r.width).collect::<Vec<_>>();
+    /// assert_eq!(widths(Rect::new(0, 0, 7, 1).split_h(&[Percent(33), Percent(33), Percent(34)])), vec![2, 2, 3]);
+    /// assert_eq!(widths(Rect::new(0, 0, 7, 1).split_h(&[Fill(1), Fill(1), Fill(1)])), vec![2, 2, 3]);
+    /// assert_eq!(widths(Rect::new(0, 0, 7, 1).split_h(&[Percent(50), Percent(20)])), vec![3, 4]);
+    /// assert_eq!(widths(Rect::new(0, 0, 10, 1).split_h(&[Fixed(2), Fill(1), Percent(50)])), vec![2, 3, 5]);
+    /// assert_eq!(widths(Rect::new(0, 0, 5, 1).split_h(&[Fixed(4), Fixed(4), Fill(1)])), vec![4, 1, 0]);
+    ///
+    /// let sidebar = Rect::new(2, 1, 80, 24).split_h(&[Fixed(20), Fill(1)]);
+    /// assert_eq!(sidebar, vec![Rect::new(2, 1, 20, 24), Rect::new(22, 1, 60, 24)]);
+    ///
+    /// // Every mix of constraints tiles every width exactly.
+    /// let sets = [
+    ///     vec![Percent(33), Percent(33), Percent(34)], vec![Fill(1), Fill(2), Fill(3)], vec![Fixed(3), Percent(40), Fill(1), Fill(1)],
+    ///     vec![Percent(70), Percent(70)], vec![Fixed(1)], vec![Fill(0), Percent(10)], vec![Percent(25), Fixed(5), Percent(25), Fill(2)]
+    /// ];
+    /// for width in 0..60 {
+    ///     for set in &sets {
+    ///         let rects = Rect::new(-3, 4, width, 2).split_h(set);
+    ///         assert_eq!(rects.len(), set.len());
+    ///         let mut x = -3;
+    ///         for rect in &rects {
+    ///             assert_eq!((rect.x, rect.y, rect.height), (x, 4, 2));
+    ///             x += rect.width as isize;
+    ///         }
+    ///         assert_eq!(x, width as isize - 3);
+    ///     }
+    /// }
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn split_h(&self, constraints: &[Constraint]) -> Vec<Rect> {
+        let mut x = self.x;
+        split_sizes(self.width, constraints).into_iter().map(|width| {
+            let rect = Rect::new(x, self.y, width, self.height);
+            x = x.saturating_add_unsigned(width);
+            rect
+        }).collect()
+    }
+
+    /// Split the rectangle into rows sized by `constraints`, top to bottom, rounding like `split_h`.
+    ///
+    /// Example
+    /// ```
+    /// use terminalgl::{Constraint::*, Rect};
+    /// let rows = Rect::new(0, 0, 80, 24).split_v(&[Fixed(1), Fill(1), Fixed(3)]);
+    /// assert_eq!(rows, vec![Rect::new(0, 0, 80, 1), Rect::new(0, 1, 80, 20), Rect::new(0, 21, 80, 3)]);
+    /// let panels = rows[1].split_h(&[Percent(50), Fill(1)]);
+    /// assert_eq!(panels[1].split_v(&[Fill(1), Fill(1)]), vec![Rect::new(40, 1, 40, 10), Rect::new(40, 11, 40, 10)]);
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn split_v(&self, constraints: &[Constraint]) -> Vec<Rect> {
+        let mut y = self.y;
+        split_sizes(self.height, constraints).into_iter().map(|height| {
+            let rect = Rect::new(self.x, y, self.width, height);
+            y = y.saturating_add_unsigned(height);
+            rect
+        }).collect()
+    }
+
+    /// Get a rectangle of `size` as `(width, height)` placed at `anchor` inside this one, kept `margin` cells away from
+    /// the edges it is anchored to. Use `Rect::screen().anchor(...)` to place it on the screen, and call it again after
+    /// a resize to place it against the new size.
+    ///
+    /// Centered rectangles are placed like `align_in`.
+    ///
+    /// Example
+    /// ```
+    /// use terminalgl::{Anchor, Rect};
+    /// let screen = Rect::new(0, 0, 80, 24);
+    /// assert_eq!(screen.anchor(Anchor::TopRight, (10, 3), 1), Rect::new(69, 1, 10, 3));
+    /// assert_eq!(screen.anchor(Anchor::BottomLeft, (10, 3), 0), Rect::new(0, 21, 10, 3));
+    /// assert_eq!(screen.anchor(Anchor::Right, (10, 3), 2), Rect::new(68, 10, 10, 3));
+    /// assert_eq!(screen.anchor(Anchor::Bottom, (9, 1), 1), Rect::new(35, 22, 9, 1));
+    /// assert_eq!(screen.anchor(Anchor::Center, (20, 5), 3), Rect::new(30, 9, 20, 5));
+    /// assert_eq!(Rect::new(5, 5, 10, 10).anchor(Anchor::TopLeft, (3, 3), 2), Rect::new(7, 7, 3, 3));
+    /// ```
+    pub fn anchor(&self, anchor: Anchor, size: (usize, usize), margin: usize) -> Rect {
+        let (horizontal, vertical) = match anchor {
+            Anchor::TopLeft => (TextAlignment::Left, VAlign::Top),
+            Anchor::Top => (TextAlignment::Center, VAlign::Top),
+            Anchor::TopRight => (TextAlignment::Right, VAlign::Top),
+            Anchor::Left => (TextAlignment::Left, VAlign::Middle),
+            Anchor::Center => (TextAlignment::Center, VAlign::Middle),
+            Anchor::Right => (TextAlignment::Right, VAlign::Middle),
+            Anchor::BottomLeft => (TextAlignment::Left, VAlign::Bottom),
+            Anchor::Bottom => (TextAlignment::Center, VAlign::Bottom),
+            Anchor::BottomRight => (TextAlignment::Right, VAlign::Bottom)
+        };
+        let vertical = match vertical {
+            VAlign::Top => TextAlignment::Left,
+            VAlign::Middle => TextAlignment::Center,
+            VAlign::Bottom => TextAlignment::Right
+        };
+        Rect::new(
+            align_start(self.x, self.width, size.0, horizontal, margin),
+            align_start(self.y, self.height, size.1, vertical, margin),
+            size.0,
+            size.1
+        )
+    }
+
+    /// Get this rectangle moved to sit inside `parent` with the given alignment, keeping its size.
+    /// When centering leaves an odd number of spare cells, the extra cell goes after the rectangle, so it leans left and up.
+    /// A rectangle bigger than `parent` overhangs it, by one more cell on the left or top when the overhang is odd.
+    ///
+    /// Example
+    /// ```
+    /// use terminalgl::{Rect, TextAlignment, VAlign};
+    /// let centered = |parent: usize, child: usize| {
+    ///     Rect::new(0, 0, child, child).align_in(Rect::new(0, 0, parent, parent), TextAlignment::Center, VAlign::Middle).x
+    /// };
+    /// assert_eq!(centered(8, 4), 2);
+    /// assert_eq!(centered(8, 3), 2);
+    /// assert_eq!(centered(7, 4), 1);
+    /// assert_eq!(centered(7, 3), 2);
+    /// assert_eq!(centered(4, 8), -2);
+    /// assert_eq!(centered(4, 7), -2);
+    /// assert_eq!(centered(3, 8), -3);
+    /// assert_eq!(centered(3, 7), -2);
+    ///
+    /// let parent = Rect::new(10, 4, 7, 5);
+    /// for (parent_len, child_len) in (0..12).flat_map(|p| (0..12).map(move |c| (p, c))) {
+    ///     let spare = parent_len as isize - child_len as isize;
+    ///     let before = centered(parent_len, child_len);
+    ///     assert_eq!(before, spare.div_euclid(2));
+    ///     assert!(spare - 2 * before == 0 || spare - 2 * before == 1);
+    /// }
+    /// assert_eq!(Rect::new(0, 0, 3, 2).align_in(parent, TextAlignment::Right, VAlign::Bottom), Rect::new(14, 7, 3, 2));
+    /// assert_eq!(Rect::new(0, 0, 3, 2).align_in(parent, TextAlignment::Left, VAlign::Middle), Rect::new(10, 5, 3, 2));
+    /// ```
+    pub fn align_in(&self, parent: Rect, horizontal: TextAlignment, vertical: VAlign) -> Rect {
+        let anchor = match (horizontal, vertical) {
+            (TextAlignment::Left, VAlign::Top) => Anchor::TopLeft,
+            (TextAlignment::Center, VAlign::Top) => Anchor::Top,
+            (TextAlignment::Right, VAlign::Top) => Anchor::TopRight,
+            (TextAlignment::Left, VAlign::Middle) => Anchor::Left,
+            (TextAlignment::Center, VAlign::Middle) => Anchor::Center,
+            (TextAlignment::Right, VAlign::Middle) => Anchor::Right,
+            (TextAlignment::Left, VAlign::Bottom) => Anchor::BottomLeft,
+            (TextAlignment::Center, VAlign::Bottom) => Anchor::Bottom,
+            (TextAlignment::Right, VAlign::Bottom) => Anchor::BottomRight
+        };
+        parent.anchor(anchor, (self.width, self.height), 0)
+    }
+}
+
+
+/// Get where a span of `inner` cells starts when aligned inside `outer` cells starting at `start`,
+/// kept `margin` cells from the edge it is aligned to. Centering rounds toward the start.
+fn align_start(start: isize, outer: usize, inner: usize, align: TextAlignment, margin: usize) -> isize {
+    let (start, outer, inner, margin) = (start as i128, outer as i128, inner as i128, margin as i128);
+    let pos = match align {
+        TextAlignment::Left => start + margin,
+        TextAlignment::Right => start + outer - inner - margin,
+        TextAlignment::Center => start + (outer - inner).div_euclid(2)
+    };
+    pos.clamp(isize::MIN as i128, isize::MAX as i128) as isize
+}
+
+
+/// How `Rect::split_h` and `Rect::split_v` size one part of a rectangle.
+/// `Fixed(n)` is `n` cells, `Percent(p)` is `p` percent of the whole rectangle,
+/// and `Fill(weight)` shares the cells the other parts leave with the other `Fill` parts, in proportion to `weight`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Constraint {
+    Fixed(usize),
+    Percent(usize),
+    Fill(usize)
+}
+
+
+/// Get the size of each part when splitting `total` cells by `constraints`, following the rules in `Rect::split_h`.
+#[cfg(feature = "std")]
+fn split_sizes(total: usize, constraints: &[Constraint]) -> Vec<usize> {
+    // Cut `total` cells at the running total `cut` out of `whole`, rounding down.
+    let cut = |cut: usize, whole: usize| (total as u128 * cut as u128 / whole as u128).min(total as u128) as usize;
+    let mut sizes = vec![0; constraints.len()];
+    let mut left = total;
+    let mut percent = 0;
+    for (size, constraint) in sizes.iter_mut().zip(constraints) {
+        let wanted = match *constraint {
+            Constraint::Fixed(n) => n,
+            Constraint::Percent(p) => {
+                let start = cut(percent, 100);
+                percent = percent.saturating_add(p);
+                cut(percent, 100) - start
+            },
+            Constraint::Fill(_) => continue
+        };
+        *size = wanted.min(left);
+        left -= *size;
+    }
+    let weights: usize = constraints.iter().map(|c| if let Constraint::Fill(w) = c { *w } else { 0 }).fold(0, usize::saturating_add);
+    let (fill_total, mut weight) = (left, 0usize);
+    let cut = |cut: usize, whole: usize| if whole == 0 { 0 } else { (fill_total as u128 * cut as u128 / whole as u128) as usize };
+    for (size, constraint) in sizes.iter_mut().zip(constraints) {
+        if let Constraint::Fill(w) = *constraint {
+            let start = cut(weight, weights);
+            weight = weight.saturating_add(w);
+            *size = cut(weight, weights) - start;
+            left -= *size;
+        }
+    }
+    if let Some(last) = sizes.last_mut() {
+        *last += left;
+    }
+    sizes
+}
+
+
+/// Style for `connector`.
+/// Use `ascii` to draw with `-`, `|` and `+` instead of box-drawing characters,
+/// and `arrow` to end the connector with an arrowhead.
+#[derive(Clone, Copy, Default)]
+pub struct ConnectorStyle {
+    pub ascii: bool,
+    pub arrow: bool
+}
+
+
+/// A node of a tree for `tree`, with a label and any number of children.
+/// `ccode` optionally overrides the label's color when drawn with `drawc::tree`.
+#[cfg(feature = "std")]
+#[derive(Clone, Debug, Default)]
+pub struct TreeNode {
+    pub label: String,
+    pub children: Vec<TreeNode>,
+    pub ccode: Option<String>
+}
+
+#[cfg(feature = "std")]
+impl TreeNode {
+    /// Create a node with no children.
+    pub fn leaf(label: &str) -> TreeNode {
+        TreeNode { label: label.to_string(), children: Vec::new(), ccode: None }
+    }
+
+    /// Create a node with children `children`.
+    pub fn new(label: &str, children: Vec<TreeNode>) -> TreeNode {
+        TreeNode { label: label.to_string(), children, ccode: None }
+    }
+}
+
+
+/// Style for `tree`.
+/// Use `ascii` to draw guides with `|`, `` ` `` and `-` instead of box-drawing characters.
+/// Lines longer than `max_width` are cut off with `…`, and trees taller than `max_height`
+/// end with a `+N more` line counting the rows that were left out.
+#[derive(Clone, Copy, Default)]
+pub struct TreeStyle {
+    pub ascii: bool,
+    pub max_width: Option<usize>,
+    pub max_height: Option<usize>
+}
+
+
+/// Options for `calendar`.
+/// Use `monday_first` to start weeks on Monday instead of Sunday.
+/// `today` and `marked` are days of the month that the colored variant highlights.
+#[cfg(feature = "std")]
+#[derive(Clone, Debug, Default)]
+pub struct CalendarOptions {
+    pub monday_first: bool,
+    pub today: Option<u32>,
+    pub marked: Vec<u32>
+}
+
+
+/// Options for `gauge`.
+/// `sweep` is the angle covered by the dial in degrees (e.g. `180.0` or `270.0`), centered on the top of the dial.
+/// The dial is drawn with `c`, the needle (if any) with its character, and `label` shows the fraction as a percentage.
+#[derive(Clone, Copy)]
+pub struct GaugeOptions {
+    pub sweep: f64,
+    pub c: char,
+    pub needle: Option<char>,
+    pub label: bool
+}
+
+impl Default for GaugeOptions {
+    fn default() -> GaugeOptions {
+        GaugeOptions { sweep: 180.0, c: '█', needle: None, label: true }
+    }
+}
\ No newline at end of file
@@ -0,0 +1,251 @@
+// L-systems: rewriting strings by rules, and drawing the result with a turtle.
+use super::canvas::Canvas;
+use super::turtle::Turtle;
+use super::Rect;
+
+
+/// Longest string, in bytes, that `expand` grows an L-system to.
+pub const DEFAULT_MAX_LENGTH: usize = 1 << 22;
+
+
+/// Rewrite `axiom` `iterations` times by the L-system `rules`, replacing every character that has a rule with its replacement
+/// all at once and keeping the rest. Stops early at the last iteration no longer than `DEFAULT_MAX_LENGTH` bytes.
+///
+/// Example
+/// ```
+/// use terminalgl::lsystem;
+/// // Lindenmayer's algae.
+/// let algae = [('A', "AB"), ('B', "A")];
+/// assert_eq!(lsystem::expand("A", &algae, 0), "A");
+/// assert_eq!(lsystem::expand("A", &algae, 4), "ABAABABA");
+///
+/// // The Koch curve.
+/// assert_eq!(lsystem::expand("F", &[('F', "F+F-F-F+F")], 2), "\
+/// F+F-F-F+F+F+F-F-F+F-F+F-F-F+F-F+F-F-F+F+F+F-F-F+F");
+///
+/// // A fractal plant, where `[` and `]` save and restore the turtle.
+/// let plant = lsystem::expand("X", &[('X', "F[+X][-X]FX"), ('F', "FF")], 2);
+/// assert_eq!(plant, "FF[+F[+X][-X]FX][-F[+X][-X]FX]FFF[+X][-X]FX");
+///
+/// // Growth stops at the cap, whatever the number of iterations.
+/// assert_eq!(lsystem::expand("A", &[('A', "AA")], usize::MAX).len(), lsystem::DEFAULT_MAX_LENGTH);
+/// ```
+pub fn expand(axiom: &str, rules: &[(char, &str)], iterations: usize) -> String {
+    expand_with(axiom, rules, iterations, DEFAULT_MAX_LENGTH)
+}
+
+
+/// Rewrite `axiom` like `expand`, stopping early at the last iteration no longer than `max_length` bytes.
+/// Rules that come back around to an earlier string, such as `A` to `B` and `B` to `A`, are noticed,
+/// so any number of iterations takes only as long as the cycle.
+///
+/// Example
+/// ```
+/// use terminalgl::lsystem;
+/// let swap = [('A', "B"), ('B', "A")];
+/// assert_eq!(lsystem::expand_with("AAB", &swap, usize::MAX, 100), "BBA");
+/// assert_eq!(lsystem::expand_with("AAB", &swap, usize::MAX - 1, 100), "AAB");
+///
+/// // Too long already: nothing is rewritten.
+/// assert_eq!(lsystem::expand_with("F", &[('F', "FF")], 10, 6), "FFFF");
+/// assert_eq!(lsystem::expand_with("FFFFFFFF", &[('F', "FF")], 10, 6), "FFFFFFFF");
+/// ```
+pub fn expand_with(axiom: &str, rules: &[(char, &str)], iterations: usize, max_length: usize) -> String {
+    let mut current = axiom.to_string();
+    // Brent's cycle finding: compare each string with one saved at the last power of 2 iterations.
+    let (mut saved, mut power, mut since) = (current.clone(), 1, 0);
+    let mut done = 0;
+    while done < iterations {
+        let Some(next) = rewrite(&current, rules, max_length) else {
+            break;
+        };
+        current = next;
+        done += 1;
+        since += 1;
+        if current == saved {
+            // A cycle of `since` iterations, which all fit, so the rest can skip whole turns of it.
+            for _ in 0..(iterations - done) % since {
+                current = rewrite(&current, rules, usize::MAX).unwrap_or_default();
+            }
+            break;
+        }
+        if since == power {
+            saved = current.clone();
+            power *= 2;
+            since = 0;
+        }
+    }
+    current
+}
+
+
+/// Rewrite `current` once by `rules`, or get `None` if the result would be longer than `max_length` bytes.
+fn rewrite(current: &str, rules: &[(char, &str)], max_length: usize) -> Option<String> {
+    let mut next = String::with_capacity(current.len().saturating_mul(2).min(max_length));
+    for c in current.chars() {
+        match rules.iter().find(|(from, _)| *from == c) {
+            Some((_, to)) => next.push_str(to),
+            None => next.push(c)
+        }
+        if next.len() > max_length {
+            return None;
+        }
+    }
+    Some(next)
+}
+
+
+/// What `draw` and `draw_fit` draw: an L-system and how the turtle follows it.
+/// In the expanded string, `F` and `G` move forward `step` drawing, `f` moves forward without drawing, `+` and `-` turn left
+/// and right by `angle` degrees, `|` turns around, `[` saves the turtle and `]` restores it, and every other character is skipped.
+/// The turtle starts at `start` with `heading` (0 degrees points right and 90 up), scales vertical movement by `aspect`
+/// (see `Turtle::set_aspect`), and draws `c` with `ccode`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Spec<'a> {
+    pub axiom: &'a str,
+    pub rules: &'a [(char, &'a str)],
+    pub iterations: usize,
+    pub angle: f64,
+    pub step: f64,
+    pub start: (f64, f64),
+    pub heading: f64,
+    pub aspect: f64,
+    pub c: char,
+    pub ccode: &'a str
+}
+
+impl<'a> Spec<'a> {
+    /// Create a spec for `axiom` rewritten `iterations` times by `rules`, turning by `angle` degrees,
+    /// starting at `(0, 0)` heading right with a step of 1 and an aspect of 1, drawing `*` without color.
+    pub fn new(axiom: &'a str, rules: &'a [(char, &'a str)], iterations: usize, angle: f64) -> Spec<'a> {
+        Spec { axiom, rules, iterations, angle, step: 1.0, start: (0.0, 0.0), heading: 0.0, aspect: 1.0, c: '*', ccode: "" }
+    }
+}
+
+
+/// Carry out command `c` from the expanded string of `spec` with `turtle`, moving `step` at a time.
+fn command(turtle: &mut Turtle, c: char, spec: &Spec, step: f64) {
+    match c {
+        'F' | 'G' => turtle.forward(step),
+        'f' => {
+            let down = turtle.is_pen_down();
+            turtle.pen_up();
+            turtle.forward(step);
+            if down {
+                turtle.pen_down();
+            }
+        },
+        '+' => turtle.turn_left(spec.angle),
+        '-' => turtle.turn_right(spec.angle),
+        '|' => turtle.turn_left(180.0),
+        '[' => turtle.push(),
+        ']' => {
+            turtle.pop();
+        },
+        _ => {}
+    }
+}
+
+
+/// Point `turtle` and set its pen the way `spec` starts.
+fn set_up(turtle: &mut Turtle, spec: &Spec) {
+    turtle.set_heading(spec.heading);
+    turtle.set_aspect(spec.aspect);
+    turtle.set_char(spec.c);
+    turtle.set_color(spec.ccode);
+}
+
+
+/// Expand the L-system of `spec` (see `expand`) and draw it into `canvas` with a turtle. Lines past the edges of the canvas are clipped.
+///
+/// Example
+/// ```
+/// use terminalgl::canvas::Canvas;
+/// use terminalgl::lsystem::{self, Spec};
+/// use terminalgl::assert_frame_eq;
+/// // The quadratic Koch curve at a step of 1: `5^n` segments cover at most `5^n + 1` cells, fewer where the curve touches itself.
+/// let rules = [('F', "F+F-F-F+F")];
+/// for (iterations, cells) in [(0, 2), (1, 6), (2, 24), (3, 108)] {
+///     let mut canvas = Canvas::new(40, 40);
+///     let spec = Spec { start: (1.0, 20.0), ..Spec::new("F", &rules, iterations, 90.0) };
+///     lsystem::draw(&mut canvas, &spec);
+///     let drawn = (0..40).flat_map(|y| (0..40).map(move |x| (x, y))).filter(|&(x, y)| canvas.get(x, y).unwrap().c == '*').count();
+///     assert_eq!(drawn, cells);
+///     assert!(drawn <= 5usize.pow(iterations as u32) + 1);
+/// }
+///
+/// let mut canvas = Canvas::new(11, 4);
+/// lsystem::draw(&mut canvas, &Spec { start: (1.0, 3.0), step: 3.0, c: '#', ..Spec::new("F", &rules, 1, 90.0) });
+/// assert_frame_eq!(canvas, "\
+/// |    ####   |
+/// |    #  #   |
+/// |    #  #   |
+/// | ####  ####|");
+/// ```
+pub fn draw(canvas: &mut Canvas, spec: &Spec) {
+    let commands = expand(spec.axiom, spec.rules, spec.iterations);
+    let mut turtle = Turtle::on(canvas, spec.start.0, spec.start.1);
+    set_up(&mut turtle, spec);
+    for c in commands.chars() {
+        command(&mut turtle, c, spec, spec.step);
+    }
+}
+
+
+/// Draw the L-system of `spec` like `draw`, scaled and moved to fill as much of `rect` as it can while keeping its shape,
+/// and centered in it. `start` and `step` are ignored, and the step used is returned.
+///
+/// Example
+/// ```
+/// use terminalgl::canvas::Canvas;
+/// use terminalgl::lsystem::{self, Spec};
+/// use terminalgl::{assert_frame_eq, Rect};
+/// // Sierpinski's triangle, with `G` also drawing, squashed to look right in terminal cells.
+/// let rules = [('F', "F+G-F-G+F"), ('G', "GG")];
+/// let spec = Spec { aspect: 0.5, c: '#', ..Spec::new("F+G+G", &rules, 1, 120.0) };
+/// let mut canvas = Canvas::new(17, 7);
+/// let step = lsystem::draw_fit(&mut canvas, Rect::new(1, 1, 15, 5), &spec);
+/// assert!((step - 4.62).abs() < 0.01);
+/// assert_frame_eq!(canvas, "\
+/// |                 |
+/// |        #        |
+/// |       # #       |
+/// |      #####      |
+/// |    ## # # ##    |
+/// |   ###########   |
+/// |                 |");
+/// ```
+pub fn draw_fit(canvas: &mut Canvas, rect: Rect, spec: &Spec) -> f64 {
+    let commands = expand(spec.axiom, spec.rules, spec.iterations);
+
+    // Walk it once with the pen up at a step of 1 to find how big it is.
+    let mut turtle = Turtle::new(0.0, 0.0);
+    set_up(&mut turtle, spec);
+    turtle.pen_up();
+    let (mut min, mut max) = ((0.0f64, 0.0f64), (0.0f64, 0.0f64));
+    for c in commands.chars() {
+        command(&mut turtle, c, spec, 1.0);
+        let (x, y) = turtle.position();
+        min = (min.0.min(x), min.1.min(y));
+        max = (max.0.max(x), max.1.max(y));
+    }
+
+    let (width, height) = (max.0 - min.0, max.1 - min.1);
+    let room = (rect.width.saturating_sub(1) as f64, rect.height.saturating_sub(1) as f64);
+    let step = match (width > 1e-9, height > 1e-9) {
+        (true, true) => (room.0 / width).min(room.1 / height),
+        (true, false) => room.0 / width,
+        (false, true) => room.1 / height,
+        (false, false) => 1.0
+    };
+    let start = (
+        rect.x as f64 + (room.0 - width * step) / 2.0 - min.0 * step,
+        rect.y as f64 + (room.1 - height * step) / 2.0 - min.1 * step
+    );
+    let mut turtle = Turtle::on(canvas, start.0, start.1);
+    set_up(&mut turtle, spec);
+    for c in commands.chars() {
+        command(&mut turtle, c, spec, step);
+    }
+    step
+}
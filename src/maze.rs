@@ -0,0 +1,210 @@
+// Maze generation on a grid of walls and passages, and drawing mazes into a canvas.
+use super::canvas::{Canvas, Cell};
+use super::chars::{self, LineStyle};
+use super::particles::Rng;
+use super::Rect;
+
+
+/// How `generate` carves a maze.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Algorithm {
+    /// Randomized depth-first search, which makes long winding corridors with few dead ends.
+    Backtracker,
+    /// Randomized Prim's algorithm, which grows out from a cell and makes many short dead ends.
+    Prim
+}
+
+
+/// How `render` draws a maze.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MazeStyle {
+    /// Walls as box-drawing lines, each joined with the walls around it.
+    Lines(LineStyle),
+    /// Walls as full blocks.
+    Blocks
+}
+
+
+/// A maze on a grid of walls and passages, from `generate`. A maze of `width` by `height` rooms is `2 * width + 1`
+/// by `2 * height + 1` cells: rooms are at odd coordinates, with a wall or passage between each pair of neighboring rooms
+/// and walls all around. The entrance is a gap in the top wall above the top left room, and the exit a gap in the bottom
+/// wall below the bottom right room.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Maze {
+    width: usize,
+    height: usize,
+    walls: Vec<bool>
+}
+
+impl Maze {
+    /// Get the size of the maze in cells, walls included, as `(width, height)`.
+    pub fn size(&self) -> (usize, usize) {
+        (self.width, self.height)
+    }
+
+    /// Get whether the cell at `(x, y)` is a wall. Cells outside the maze are walls.
+    pub fn is_wall(&self, x: isize, y: isize) -> bool {
+        if x < 0 || y < 0 || x as usize >= self.width || y as usize >= self.height {
+            return true;
+        }
+        self.walls[y as usize * self.width + x as usize]
+    }
+
+    /// Get the cell of the entrance, in the top wall.
+    pub fn entrance(&self) -> (isize, isize) {
+        (1, 0)
+    }
+
+    /// Get the cell of the exit, in the bottom wall.
+    pub fn exit(&self) -> (isize, isize) {
+        (self.width as isize - 2, self.height as isize - 1)
+    }
+
+    fn open(&mut self, x: usize, y: usize) {
+        self.walls[y * self.width + x] = false;
+    }
+
+    /// Get the maze as text, one line per row, with `#` for walls and `.` for passages.
+    pub fn to_text(&self) -> String {
+        let rows: Vec<String> = self.walls.chunks(self.width).map(|row| {
+            row.iter().map(|&wall| if wall { '#' } else { '.' }).collect()
+        }).collect();
+        rows.join("\n")
+    }
+}
+
+
+/// Carve a maze of `width` by `height` rooms (at least 1 each) with `algorithm`. The same `seed` always gives the same maze.
+/// Every room can be reached from every other one by exactly one route, so there is always a way from the entrance to the exit.
+///
+/// Example
+/// ```
+/// use terminalgl::maze::{self, Algorithm};
+/// use terminalgl::path;
+/// for algorithm in [Algorithm::Backtracker, Algorithm::Prim] {
+///     let maze = maze::generate(12, 8, 3, algorithm);
+///     assert_eq!(maze.size(), (25, 17));
+///     assert_eq!(maze, maze::generate(12, 8, 3, algorithm));
+///     assert_ne!(maze, maze::generate(12, 8, 4, algorithm));
+///
+///     // A* finds its way through, and so would anyone else.
+///     let route = path::astar(maze.entrance(), maze.exit(), |x, y| !maze.is_wall(x, y), false).unwrap();
+///     assert_eq!((route[0], route[route.len() - 1]), ((1, 0), (23, 16)));
+///
+///     // A perfect maze: its 96 rooms are joined by exactly 95 passages.
+///     let open = maze.to_text().chars().filter(|&c| c == '.').count();
+///     assert_eq!(open, 96 + 95 + 2);
+/// }
+/// ```
+pub fn generate(width: usize, height: usize, seed: u64, algorithm: Algorithm) -> Maze {
+    let (width, height) = (width.max(1), height.max(1));
+    let mut maze = Maze { width: 2 * width + 1, height: 2 * height + 1, walls: vec![true; (2 * width + 1) * (2 * height + 1)] };
+    let mut rng = Rng::new(seed);
+    let mut pick = |n: usize| (rng.next_u64() % n as u64) as usize;
+    let mut visited = vec![false; width * height];
+    let neighbors = |room: usize| {
+        let (x, y) = (room % width, room / width);
+        let mut rooms = Vec::with_capacity(4);
+        if y > 0 { rooms.push(room - width); }
+        if y + 1 < height { rooms.push(room + width); }
+        if x > 0 { rooms.push(room - 1); }
+        if x + 1 < width { rooms.push(room + 1); }
+        rooms
+    };
+    // Open a room, and the wall between it and a neighboring one.
+    let join = |maze: &mut Maze, a: usize, b: usize| {
+        let (ax, ay, bx, by) = (a % width, a / width, b % width, b / width);
+        maze.open(2 * bx + 1, 2 * by + 1);
+        maze.open(ax + bx + 1, ay + by + 1);
+    };
+
+    let start = pick(width * height);
+    visited[start] = true;
+    maze.open(2 * (start % width) + 1, 2 * (start / width) + 1);
+    match algorithm {
+        Algorithm::Backtracker => {
+            let mut stack = vec![start];
+            while let Some(&room) = stack.last() {
+                let unvisited: Vec<usize> = neighbors(room).into_iter().filter(|&n| !visited[n]).collect();
+                if unvisited.is_empty() {
+                    stack.pop();
+                    continue;
+                }
+                let next = unvisited[pick(unvisited.len())];
+                visited[next] = true;
+                join(&mut maze, room, next);
+                stack.push(next);
+            }
+        },
+        Algorithm::Prim => {
+            let mut frontier: Vec<usize> = neighbors(start);
+            let mut in_frontier = vec![false; width * height];
+            for &room in &frontier {
+                in_frontier[room] = true;
+            }
+            while !frontier.is_empty() {
+                let room = frontier.swap_remove(pick(frontier.len()));
+                let (done, todo): (Vec<usize>, Vec<usize>) = neighbors(room).into_iter().partition(|&n| visited[n]);
+                join(&mut maze, done[pick(done.len())], room);
+                visited[room] = true;
+                for n in todo {
+                    if !in_frontier[n] {
+                        in_frontier[n] = true;
+                        frontier.push(n);
+                    }
+                }
+            }
+        }
+    }
+    let ((ex, ey), (xx, xy)) = (maze.entrance(), maze.exit());
+    maze.open(ex as usize, ey as usize);
+    maze.open(xx as usize, xy as usize);
+    maze
+}
+
+
+/// Draw `maze` into `rect` on `canvas`, one cell per cell of the maze, leaving passages untouched.
+/// Cells of the maze past `rect` are left out.
+///
+/// Example
+/// ```
+/// use terminalgl::canvas::Canvas;
+/// use terminalgl::chars::LineStyle;
+/// use terminalgl::maze::{self, Algorithm, MazeStyle};
+/// use terminalgl::{assert_frame_eq, Rect};
+/// let maze = maze::generate(4, 2, 1, Algorithm::Backtracker);
+/// let mut canvas = Canvas::new(9, 5);
+/// maze::render(&mut canvas, Rect::new(0, 0, 9, 5), &maze, MazeStyle::Lines(LineStyle::Light));
+/// assert_frame_eq!(canvas, "\
+/// |╷ ╶─┬───┐|
+/// |│   │   │|
+/// |│ ╶─┘ ╷ │|
+/// |│     │ │|
+/// |└─────┘ ╵|");
+///
+/// maze::render(&mut canvas, Rect::new(0, 0, 9, 5), &maze, MazeStyle::Blocks);
+/// assert_frame_eq!(canvas, "\
+/// |█ ███████|
+/// |█   █   █|
+/// |█ ███ █ █|
+/// |█     █ █|
+/// |███████ █|");
+/// ```
+pub fn render(canvas: &mut Canvas, rect: Rect, maze: &Maze, style: MazeStyle) {
+    let width = rect.width.min(maze.width);
+    let height = rect.height.min(maze.height);
+    // Walls past the edges of the maze aren't drawn, so they don't get arms.
+    let wall = |x: isize, y: isize| x >= 0 && y >= 0 && (x as usize) < maze.width && (y as usize) < maze.height && maze.is_wall(x, y);
+    for y in 0..height as isize {
+        for x in 0..width as isize {
+            if !maze.is_wall(x, y) {
+                continue;
+            }
+            let c = match style {
+                MazeStyle::Lines(line) => chars::junction(wall(x, y - 1), wall(x, y + 1), wall(x - 1, y), wall(x + 1, y), line),
+                MazeStyle::Blocks => chars::blocks().full
+            };
+            canvas.put(rect.x.saturating_add(x), rect.y.saturating_add(y), Cell::new(c, ""));
+        }
+    }
+}
@@ -0,0 +1,99 @@
+// Seeded value noise for procedural textures and terrain.
+use super::Rect;
+
+
+/// Hash the lattice point `(x, y)` with `seed` into a number in `0.0..=1.0`.
+/// Only integer arithmetic is used, so the result is the same on every platform.
+fn lattice(x: i64, y: i64, seed: u64) -> f64 {
+    let mut h = seed ^ 0x9e37_79b9_7f4a_7c15;
+    h = (h ^ x as u64).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    h = (h ^ (h >> 31) ^ y as u64).wrapping_mul(0x94d0_49bb_1331_11eb);
+    h ^= h >> 29;
+    (h >> 11) as f64 / ((1u64 << 53) - 1) as f64
+}
+
+
+/// Ease `t` in `0.0..=1.0` so the noise has no visible creases at lattice lines.
+fn smoothstep(t: f64) -> f64 {
+    t * t * (3.0 - 2.0 * t)
+}
+
+
+/// Get value noise at `(x, y)` for `seed`, in `0.0..=1.0`.
+/// Noise changes smoothly between integer coordinates, so divide coordinates by a scale to make features larger.
+///
+/// Example
+/// ```
+/// use terminalgl::noise;
+/// let v = noise::noise2(3.25, 7.5, 1);
+/// assert!(v >= 0.0 && v <= 1.0);
+/// assert_eq!(v, noise::noise2(3.25, 7.5, 1));
+/// assert_ne!(v, noise::noise2(3.25, 7.5, 2));
+/// assert!((noise::noise2(3.0, 7.0, 1) - noise::noise2(3.001, 7.0, 1)).abs() < 0.01);
+/// let far = noise::noise2(f64::MAX, 1e300, 1);
+/// assert!(far >= 0.0 && far <= 1.0);
+/// ```
+pub fn noise2(x: f64, y: f64, seed: u64) -> f64 {
+    let (x0, y0) = (x.floor(), y.floor());
+    let (tx, ty) = (smoothstep(x - x0), smoothstep(y - y0));
+    let (ix, iy) = (x0 as i64, y0 as i64);
+    // Coordinates past the range of `i64` saturate, so the lattice points after them wrap around instead of overflowing.
+    let (jx, jy) = (ix.wrapping_add(1), iy.wrapping_add(1));
+    let top = lattice(ix, iy, seed) * (1.0 - tx) + lattice(jx, iy, seed) * tx;
+    let bottom = lattice(ix, jy, seed) * (1.0 - tx) + lattice(jx, jy, seed) * tx;
+    top * (1.0 - ty) + bottom * ty
+}
+
+
+/// Get fractal noise at `(x, y)` for `seed`, in `0.0..=1.0`, by summing `octaves` layers of `noise2`.
+/// Each octave has double the frequency and half the weight of the one before it, adding finer detail.
+///
+/// Example
+/// ```
+/// use terminalgl::noise;
+/// let v = noise::fractal2(0.5, 0.5, 9, 4);
+/// assert!(v >= 0.0 && v <= 1.0);
+/// assert_eq!(noise::fractal2(0.5, 0.5, 9, 1), noise::noise2(0.5, 0.5, 9));
+/// ```
+pub fn fractal2(x: f64, y: f64, seed: u64, octaves: u32) -> f64 {
+    let mut total = 0.0;
+    let mut weight = 1.0;
+    let mut weights = 0.0;
+    let mut frequency = 1.0;
+    for octave in 0..octaves.max(1) {
+        total += noise2(x * frequency, y * frequency, seed.wrapping_add(octave as u64)) * weight;
+        weights += weight;
+        weight *= 0.5;
+        frequency *= 2.0;
+    }
+    total / weights
+}
+
+
+/// Number of octaves used by `field`.
+pub const FIELD_OCTAVES: u32 = 4;
+
+
+/// Get fractal noise for every cell of `rect` as `(x, y, value)` in row order.
+/// Cells are sampled at their offset from the top left of `rect` divided by `scale`, plus `offset`;
+/// move `offset` over time to animate the field.
+///
+/// Example
+/// ```
+/// use terminalgl::{noise, Rect};
+/// let field = noise::field(Rect::new(2, 1, 3, 2), 8.0, 5, (0.0, 0.0));
+/// assert_eq!(field.len(), 6);
+/// assert_eq!((field[0].0, field[0].1), (2, 1));
+/// assert_eq!(field[0].2, noise::fractal2(0.0, 0.0, 5, noise::FIELD_OCTAVES));
+/// ```
+pub fn field(rect: Rect, scale: f64, seed: u64, offset: (f64, f64)) -> Vec<(isize, isize, f64)> {
+    let scale = if scale > 0.0 { scale } else { 1.0 };
+    let mut cells = Vec::with_capacity(rect.width * rect.height);
+    for row in 0..rect.height {
+        for col in 0..rect.width {
+            let v = fractal2(col as f64 / scale + offset.0, row as f64 / scale + offset.1, seed, FIELD_OCTAVES);
+            cells.push((rect.x.saturating_add(col as isize), rect.y.saturating_add(row as isize), v));
+        }
+    }
+    cells
+}
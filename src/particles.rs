@@ -0,0 +1,268 @@
+// Particle effects drawn into a canvas.
+use super::canvas::Canvas;
+use super::drawc::{self, ColorKind};
+use std::f64::consts::TAU;
+
+
+/// A small seeded random number generator (SplitMix64), so that effects play out the same way every time for a given seed.
+///
+/// Example
+/// ```
+/// use terminalgl::particles::Rng;
+/// let mut a = Rng::new(7);
+/// let mut b = Rng::new(7);
+/// assert_eq!(a.next_u64(), b.next_u64());
+/// let x = a.range(2.0, 3.0);
+/// assert!(x >= 2.0 && x < 3.0);
+/// ```
+#[derive(Clone, Debug)]
+pub struct Rng {
+    state: u64
+}
+
+impl Rng {
+    /// Create a generator from `seed`.
+    pub fn new(seed: u64) -> Rng {
+        Rng { state: seed }
+    }
+
+    /// Get the next random 64-bit number.
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// Get a random number in `0.0..1.0`.
+    pub fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    /// Get a random number in `lo..hi`.
+    pub fn range(&mut self, lo: f64, hi: f64) -> f64 {
+        lo + (hi - lo) * self.next_f64()
+    }
+}
+
+
+/// How a particle looks over its life.
+/// `chars` is stepped through evenly from birth to death (e.g. `*`, `+`, `.`),
+/// and `colors` is blended across with `drawc::gradient`. Particles with no colors are drawn in the default color.
+#[derive(Clone, Debug, Default)]
+pub struct ParticleStyle {
+    pub chars: Vec<char>,
+    pub colors: Vec<(u8, u8, u8)>
+}
+
+impl ParticleStyle {
+    /// Create a style stepping through the characters of `chars` and blending through `colors`.
+    pub fn new(chars: &str, colors: &[(u8, u8, u8)]) -> ParticleStyle {
+        ParticleStyle { chars: chars.chars().collect(), colors: colors.to_vec() }
+    }
+
+    /// Get the character and color code of a particle `t` of the way through its life (`0.0` to `1.0`).
+    ///
+    /// Example
+    /// ```
+    /// use terminalgl::particles::ParticleStyle;
+    /// let style = ParticleStyle::new("*+.", &[(255, 255, 0), (255, 0, 0)]);
+    /// assert_eq!(style.look(0.0), ('*', "\x1b[38;2;255;255;0m".to_string()));
+    /// assert_eq!(style.look(0.5).0, '+');
+    /// assert_eq!(style.look(1.0), ('.', "\x1b[38;2;255;0;0m".to_string()));
+    /// ```
+    pub fn look(&self, t: f64) -> (char, String) {
+        let t = t.clamp(0.0, 1.0);
+        let c = match self.chars.len() {
+            0 => '*',
+            n => self.chars[((t * n as f64) as usize).min(n - 1)]
+        };
+        let ccode = match drawc::gradient(&self.colors, t) {
+            Some(rgb) => drawc::rgb_to_ccode(rgb, ColorKind::Fg),
+            None => String::new()
+        };
+        (c, ccode)
+    }
+}
+
+
+/// A single particle at `(x, y)` moving at `(vx, vy)` cells per second.
+/// It is removed once `age` reaches `lifetime`, and drawn with the style at index `style` of its system.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Particle {
+    pub x: f64,
+    pub y: f64,
+    pub vx: f64,
+    pub vy: f64,
+    pub age: f64,
+    pub lifetime: f64,
+    pub style: usize
+}
+
+
+/// Where and how an emitter spawns particles.
+/// Angles are in radians, with `0.0` pointing right and increasing clockwise (so `-FRAC_PI_2` points up).
+#[derive(Clone, Copy, Debug)]
+pub enum EmitterShape {
+    /// Spawn at `(x, y)` moving at `speed` in a direction within `spread / 2` of `angle`.
+    /// A spread of `TAU` sends particles in every direction.
+    Point { x: f64, y: f64, speed: f64, angle: f64, spread: f64 },
+    /// Spawn anywhere along the line from `(x1, y1)` to `(x2, y2)` moving at `(vx, vy)`, e.g. for rain.
+    Line { x1: f64, y1: f64, x2: f64, y2: f64, vx: f64, vy: f64 }
+}
+
+
+/// A continuous source of particles, spawning `rate` particles per second with a lifetime between `lifetime.0` and `lifetime.1`.
+#[derive(Clone, Debug)]
+pub struct Emitter {
+    pub shape: EmitterShape,
+    pub rate: f64,
+    pub lifetime: (f64, f64),
+    pub style: usize,
+    pending: f64
+}
+
+impl Emitter {
+    /// Create an emitter spawning `rate` particles per second from `shape`.
+    pub fn new(shape: EmitterShape, rate: f64, lifetime: (f64, f64), style: usize) -> Emitter {
+        Emitter { shape, rate, lifetime, style, pending: 0.0 }
+    }
+}
+
+
+/// A set of particles and the emitters that spawn them.
+/// Every random choice comes from a generator seeded in `new`, so a system updated with the same steps always ends up the same.
+/// `gravity` accelerates particles downwards in cells per second squared.
+///
+/// Example
+/// ```
+/// use terminalgl::canvas::Canvas;
+/// use terminalgl::particles::{Emitter, EmitterShape, ParticleStyle, ParticleSystem};
+/// let mut system = ParticleSystem::new(42);
+/// let spark = system.add_style(ParticleStyle::new("*+.", &[(255, 255, 255), (255, 128, 0)]));
+/// system.burst(10.0, 5.0, 20, 4.0, (0.5, 1.0), spark);
+/// system.emitters.push(Emitter::new(EmitterShape::Line { x1: 0.0, y1: 0.0, x2: 19.0, y2: 0.0, vx: 0.0, vy: 8.0 }, 10.0, (1.0, 1.0), spark));
+/// system.gravity = 9.8;
+/// assert_eq!(system.len(), 20);
+///
+/// let mut copy = system.clone();
+/// for _ in 0..10 {
+///     system.update(0.05);
+///     copy.update(0.05);
+/// }
+/// assert_eq!(system.particles(), copy.particles());
+///
+/// let mut canvas = Canvas::new(20, 10);
+/// system.render(&mut canvas);
+/// system.update(1.0);
+/// assert!(system.particles().iter().all(|p| p.age < p.lifetime));
+/// ```
+#[derive(Clone, Debug)]
+pub struct ParticleSystem {
+    rng: Rng,
+    styles: Vec<ParticleStyle>,
+    particles: Vec<Particle>,
+    pub emitters: Vec<Emitter>,
+    pub gravity: f64
+}
+
+impl ParticleSystem {
+    /// Create an empty system with no gravity, seeding its random number generator with `seed`.
+    pub fn new(seed: u64) -> ParticleSystem {
+        ParticleSystem { rng: Rng::new(seed), styles: Vec::new(), particles: Vec::new(), emitters: Vec::new(), gravity: 0.0 }
+    }
+
+    /// Add a style and get its index for use in particles and emitters.
+    pub fn add_style(&mut self, style: ParticleStyle) -> usize {
+        self.styles.push(style);
+        self.styles.len() - 1
+    }
+
+    /// Get the live particles.
+    pub fn particles(&self) -> &[Particle] {
+        &self.particles
+    }
+
+    /// Get the number of live particles.
+    pub fn len(&self) -> usize {
+        self.particles.len()
+    }
+
+    /// Get whether there are no live particles.
+    pub fn is_empty(&self) -> bool {
+        self.particles.is_empty()
+    }
+
+    /// Add a single particle.
+    pub fn spawn(&mut self, particle: Particle) {
+        self.particles.push(particle);
+    }
+
+    /// Spawn `count` particles at once from `shape`, each with a lifetime between `lifetime.0` and `lifetime.1`.
+    pub fn emit(&mut self, shape: EmitterShape, count: usize, lifetime: (f64, f64), style: usize) {
+        for _ in 0..count {
+            let particle = self.particle_from(shape, lifetime, style);
+            self.particles.push(particle);
+        }
+    }
+
+    /// Spawn `count` particles at `(x, y)` flying out in every direction at up to `speed`, e.g. for an explosion.
+    pub fn burst(&mut self, x: f64, y: f64, count: usize, speed: f64, lifetime: (f64, f64), style: usize) {
+        for _ in 0..count {
+            let speed = self.rng.range(0.0, speed);
+            let shape = EmitterShape::Point { x, y, speed, angle: 0.0, spread: TAU };
+            let particle = self.particle_from(shape, lifetime, style);
+            self.particles.push(particle);
+        }
+    }
+
+    /// Advance the system by `dt` seconds: move and age every particle, remove the expired ones, then run the emitters.
+    pub fn update(&mut self, dt: f64) {
+        let gravity = self.gravity;
+        for p in self.particles.iter_mut() {
+            p.vy += gravity * dt;
+            p.x += p.vx * dt;
+            p.y += p.vy * dt;
+            p.age += dt;
+        }
+        self.particles.retain(|p| p.age < p.lifetime);
+
+        for i in 0..self.emitters.len() {
+            self.emitters[i].pending += self.emitters[i].rate * dt;
+            while self.emitters[i].pending >= 1.0 {
+                self.emitters[i].pending -= 1.0;
+                let Emitter { shape, lifetime, style, .. } = self.emitters[i];
+                let particle = self.particle_from(shape, lifetime, style);
+                self.particles.push(particle);
+            }
+        }
+    }
+
+    /// Draw every live particle into `canvas`. Particles outside the canvas are skipped.
+    pub fn render(&self, canvas: &mut Canvas) {
+        for p in &self.particles {
+            let t = if p.lifetime > 0.0 { p.age / p.lifetime } else { 1.0 };
+            let (c, ccode) = match self.styles.get(p.style) {
+                Some(style) => style.look(t),
+                None => ('*', String::new())
+            };
+            canvas.set(p.x.floor() as isize, p.y.floor() as isize, c, &ccode);
+        }
+    }
+
+    fn particle_from(&mut self, shape: EmitterShape, lifetime: (f64, f64), style: usize) -> Particle {
+        let (x, y, vx, vy) = match shape {
+            EmitterShape::Point { x, y, speed, angle, spread } => {
+                let angle = angle + self.rng.range(-spread / 2.0, spread / 2.0);
+                (x, y, speed * angle.cos(), speed * angle.sin())
+            },
+            EmitterShape::Line { x1, y1, x2, y2, vx, vy } => {
+                let t = self.rng.next_f64();
+                (x1 + (x2 - x1) * t, y1 + (y2 - y1) * t, vx, vy)
+            }
+        };
+        let lifetime = self.rng.range(lifetime.0, lifetime.1);
+        Particle { x, y, vx, vy, age: 0.0, lifetime, style }
+    }
+}
@@ -0,0 +1,125 @@
+// Pathfinding on grid maps.
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap};
+
+
+/// Most cells `astar` expands before giving up.
+pub const DEFAULT_SEARCH_LIMIT: usize = 100_000;
+
+
+/// Find a shortest path from `start` to `goal`, where `passable(x, y)` tells whether a cell can be walked through.
+/// Every step costs the same. Use `diagonal` to allow diagonal steps (8-connectivity) in addition to orthogonal ones.
+/// Returns the path including both endpoints, or `None` if `goal` can't be reached within `DEFAULT_SEARCH_LIMIT` expanded cells.
+///
+/// Examples
+/// ```
+/// use terminalgl::path;
+/// let maze = [
+///     "S.#....",
+///     ".##.##.",
+///     "...#..G",
+///     ".#...#.",
+/// ];
+/// let passable = |x: isize, y: isize| {
+///     maze.get(y as usize).and_then(|row| row.as_bytes().get(x as usize)).is_some_and(|&c| c != b'#')
+/// };
+/// let route = path::astar((0, 0), (6, 2), passable, false).unwrap();
+/// assert_eq!(route.len(), 11);
+/// assert_eq!((route[0], route[10]), ((0, 0), (6, 2)));
+///
+/// // Diagonal steps, which can't squeeze past the corner at (1, 1)
+/// let open = |x: isize, y: isize| (0..4).contains(&x) && (0..4).contains(&y);
+/// assert_eq!(path::astar((0, 0), (3, 3), open, true).unwrap().len(), 4);
+/// assert_eq!(path::astar((0, 0), (3, 3), |x, y| open(x, y) && (x, y) != (1, 1), true).unwrap().len(), 6);
+///
+/// // Unreachable goal and start == goal
+/// assert_eq!(path::astar((0, 0), (5, 0), |x, y| passable(x, y) && (x, y) != (6, 1), false), None);
+/// assert_eq!(path::astar((3, 3), (3, 3), passable, false), Some(vec![(3, 3)]));
+///
+/// // Paths stop at the edge of the coordinates.
+/// let edge = path::astar((isize::MAX, 0), (isize::MAX - 1, 5), |_, _| true, true).unwrap();
+/// assert_eq!(edge.len(), 6);
+/// assert!(path::astar_with((isize::MIN, 0), (isize::MAX, 0), |_, _| true, |_, _| 1, false, 100).is_none());
+/// ```
+pub fn astar(start: (isize, isize), goal: (isize, isize), passable: impl Fn(isize, isize) -> bool, diagonal: bool) -> Option<Vec<(isize, isize)>> {
+    astar_with(start, goal, passable, |_, _| 1, diagonal, DEFAULT_SEARCH_LIMIT)
+}
+
+
+/// Find a cheapest path from `start` to `goal`, where `passable(x, y)` tells whether a cell can be walked through
+/// and `cost(x, y)` is the cost of stepping into a cell (at least `1`).
+/// Use `diagonal` to allow diagonal steps, which never cut the corner of an impassable cell.
+/// Gives up and returns `None` after expanding `limit` cells.
+///
+/// Example
+/// ```
+/// use terminalgl::path;
+/// // Going around the swamp in the middle column is cheaper than wading through it.
+/// let swamp = |x: isize, y: isize| if x == 2 && y > 0 { 10 } else { 1 };
+/// let passable = |x: isize, y: isize| (0..5).contains(&x) && (0..5).contains(&y);
+/// let route = path::astar_with((0, 4), (4, 4), passable, swamp, false, 1000).unwrap();
+/// assert!(route.contains(&(2, 0)));
+/// ```
+pub fn astar_with(start: (isize, isize), goal: (isize, isize), passable: impl Fn(isize, isize) -> bool,
+                  cost: impl Fn(isize, isize) -> u32, diagonal: bool, limit: usize) -> Option<Vec<(isize, isize)>> {
+    if start == goal {
+        return Some(vec![start]);
+    }
+    if !passable(goal.0, goal.1) {
+        return None;
+    }
+    let heuristic = |(x, y): (isize, isize)| {
+        let (dx, dy) = (x.abs_diff(goal.0) as u64, y.abs_diff(goal.1) as u64);
+        if diagonal { dx.max(dy) } else { dx.saturating_add(dy) }
+    };
+    const ORTHOGONAL: [(isize, isize); 4] = [(1, 0), (-1, 0), (0, 1), (0, -1)];
+    const DIAGONAL: [(isize, isize); 4] = [(1, 1), (1, -1), (-1, 1), (-1, -1)];
+
+    let mut open = BinaryHeap::new();
+    let mut came_from: HashMap<(isize, isize), (isize, isize)> = HashMap::new();
+    let mut best: HashMap<(isize, isize), u64> = HashMap::new();
+    best.insert(start, 0);
+    open.push(Reverse((heuristic(start), 0u64, start)));
+    let mut expanded = 0;
+
+    while let Some(Reverse((_, g, cell))) = open.pop() {
+        if cell == goal {
+            let mut path = vec![goal];
+            let mut current = goal;
+            while let Some(&prev) = came_from.get(&current) {
+                path.push(prev);
+                current = prev;
+            }
+            path.reverse();
+            return Some(path);
+        }
+        if best.get(&cell).is_some_and(|&b| g > b) {
+            continue;
+        }
+        expanded += 1;
+        if expanded > limit {
+            return None;
+        }
+        let steps = ORTHOGONAL.iter().chain(if diagonal { DIAGONAL.iter() } else { [].iter() });
+        for &(dx, dy) in steps {
+            // Steps past the edge of `isize` lead nowhere.
+            let (Some(x), Some(y)) = (cell.0.checked_add(dx), cell.1.checked_add(dy)) else {
+                continue;
+            };
+            let next = (x, y);
+            if !passable(x, y) {
+                continue;
+            }
+            if dx != 0 && dy != 0 && !(passable(x, cell.1) && passable(cell.0, y)) {
+                continue;
+            }
+            let next_g = g + cost(next.0, next.1).max(1) as u64;
+            if best.get(&next).is_none_or(|&b| next_g < b) {
+                best.insert(next, next_g);
+                came_from.insert(next, cell);
+                open.push(Reverse((next_g.saturating_add(heuristic(next)), next_g, next)));
+            }
+        }
+    }
+    None
+}
@@ -0,0 +1,234 @@
+// Play back sequences of pre-rendered frames.
+use super::ansi;
+use super::canvas::Canvas;
+#[cfg(all(feature = "input", unix))]
+use super::input::{self, Event, Key, RawMode};
+#[cfg(all(feature = "input", unix))]
+use std::io::{self, IsTerminal};
+use std::thread;
+use std::time::{Duration, Instant};
+
+
+/// A single frame of a sequence, held as a canvas.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Frame {
+    canvas: Canvas
+}
+
+impl Frame {
+    /// Create a frame from plain text, as wide as its longest line.
+    ///
+    /// Example
+    /// ```
+    /// use terminalgl::player::Frame;
+    /// let frame = Frame::from_text("ab\nc");
+    /// assert_eq!((frame.canvas().width(), frame.canvas().height()), (2, 2));
+    /// ```
+    pub fn from_text(text: &str) -> Frame {
+        let lines: Vec<&str> = text.lines().collect();
+        let width = lines.iter().map(|line| line.chars().count()).max().unwrap_or(0);
+        let mut canvas = Canvas::new(width, lines.len());
+        for (y, line) in lines.iter().enumerate() {
+            canvas.text(0, y as isize, line, "");
+        }
+        Frame { canvas }
+    }
+
+    /// Create a frame `width` cells wide from text with ANSI escape sequences, parsed with `ansi::parse`.
+    pub fn from_ansi(input: &str, width: usize) -> Frame {
+        Frame { canvas: ansi::parse(input, width) }
+    }
+
+    /// Create a frame from a canvas.
+    pub fn from_canvas(canvas: Canvas) -> Frame {
+        Frame { canvas }
+    }
+
+    /// Get the frame's cells.
+    pub fn canvas(&self) -> &Canvas {
+        &self.canvas
+    }
+}
+
+
+/// Options for `Player` and `play`.
+/// Use `looping` to start over from the first frame after the last one instead of stopping.
+#[derive(Clone, Copy, Default)]
+pub struct PlayerOptions {
+    pub looping: bool
+}
+
+
+/// Playback state for a sequence of frames shown at `fps` frames per second.
+/// Advance it with `update` from an application's own loop, and map its keys to `toggle_pause` and `step`
+/// to pause or step through the frames one at a time.
+///
+/// Example
+/// ```
+/// use terminalgl::player::{Frame, Player, PlayerOptions};
+/// let frames = [Frame::from_text("1"), Frame::from_text("2"), Frame::from_text("3")];
+/// let mut player = Player::new(&frames, 10.0, PlayerOptions::default());
+/// assert!(!player.update(0.05));
+/// assert!(player.update(0.05));
+/// assert_eq!(player.index(), 1);
+///
+/// player.toggle_pause();
+/// assert!(!player.update(1.0));
+/// player.step();
+/// assert_eq!(player.index(), 2);
+/// player.step();
+/// assert!(player.is_finished());
+/// ```
+#[derive(Clone, Debug)]
+pub struct Player<'a> {
+    frames: &'a [Frame],
+    frame_time: f64,
+    looping: bool,
+    index: usize,
+    elapsed: f64,
+    paused: bool,
+    finished: bool
+}
+
+impl<'a> Player<'a> {
+    /// Create a player at the first of `frames`, showing `fps` frames per second.
+    pub fn new(frames: &'a [Frame], fps: f64, opts: PlayerOptions) -> Player<'a> {
+        let frame_time = if fps > 0.0 { 1.0 / fps } else { f64::INFINITY };
+        Player { frames, frame_time, looping: opts.looping, index: 0, elapsed: 0.0, paused: false, finished: frames.is_empty() }
+    }
+
+    /// Get the current frame, or `None` if there are no frames.
+    pub fn frame(&self) -> Option<&'a Frame> {
+        self.frames.get(self.index)
+    }
+
+    /// Get the index of the current frame.
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    /// Get whether playback is paused.
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    /// Get whether playback has gone past the last frame. Never true when looping.
+    pub fn is_finished(&self) -> bool {
+        self.finished
+    }
+
+    /// Pause or resume playback.
+    pub fn toggle_pause(&mut self) {
+        self.paused = !self.paused;
+    }
+
+    /// Advance playback by `dt` seconds and get whether the current frame changed. Does nothing while paused.
+    pub fn update(&mut self, dt: f64) -> bool {
+        if self.paused || self.finished {
+            return false;
+        }
+        self.elapsed += dt;
+        let mut changed = false;
+        while self.elapsed >= self.frame_time && !self.finished {
+            self.elapsed -= self.frame_time;
+            changed |= self.advance();
+        }
+        changed
+    }
+
+    /// Move to the next frame right away, e.g. to step through frames while paused.
+    pub fn step(&mut self) -> bool {
+        self.elapsed = 0.0;
+        self.advance()
+    }
+
+    fn advance(&mut self) -> bool {
+        if self.finished {
+            return false;
+        }
+        if self.index + 1 < self.frames.len() {
+            self.index += 1;
+            return true;
+        }
+        if self.looping {
+            let changed = self.index != 0;
+            self.index = 0;
+            return changed;
+        }
+        self.finished = true;
+        false
+    }
+}
+
+
+/// Play `frames` at `fps` frames per second in the terminal, only redrawing the cells that change between frames,
+/// and return once the last frame has been shown. With `opts.looping` it plays until stopped.
+/// With the `input` feature, keys control playback while stdin is a terminal: space pauses and resumes,
+/// `.` or the right arrow steps to the next frame, and q or Esc stops. Raw mode is turned on until it returns,
+/// unless it is already on. Use a `Player` in your own loop to handle other keys.
+///
+/// Example
+/// ```
+/// use terminalgl::player::{self, Frame, PlayerOptions};
+/// let frames = [Frame::from_text("|"), Frame::from_text("/"), Frame::from_text("-"), Frame::from_text("\\")];
+/// player::play(&frames, 200.0, PlayerOptions::default());
+/// ```
+pub fn play(frames: &[Frame], fps: f64, opts: PlayerOptions) {
+    #[cfg(all(feature = "input", unix))]
+    let _raw = RawMode::enable().ok();
+    let mut player = Player::new(frames, fps, opts);
+    let tick = Duration::from_secs_f64(player.frame_time.min(0.01));
+    let mut shown: Option<&Canvas> = None;
+    let mut last = Instant::now();
+    while let Some(frame) = player.frame() {
+        match shown {
+            Some(previous) => frame.canvas().present_diff(previous),
+            None => frame.canvas().present()
+        }
+        shown = Some(frame.canvas());
+
+        loop {
+            let Some(stepped) = wait(&mut player, tick) else {
+                return;
+            };
+            let now = Instant::now();
+            let dt = now.duration_since(last).as_secs_f64();
+            last = now;
+            if player.update(dt) || stepped || player.is_finished() {
+                break;
+            }
+        }
+        if player.is_finished() {
+            break;
+        }
+    }
+}
+
+
+/// Wait up to `timeout` for keys and apply them to `player`, as described in `play`.
+/// Get `None` if playback should stop, or else whether a step changed the frame.
+#[cfg(all(feature = "input", unix))]
+fn wait(player: &mut Player, timeout: Duration) -> Option<bool> {
+    if !io::stdin().is_terminal() {
+        thread::sleep(timeout);
+        return Some(false);
+    }
+    let mut stepped = false;
+    for event in input::read_events(timeout).unwrap_or_default() {
+        match event {
+            Event::Key(Key::Char('q') | Key::Esc | Key::Ctrl('c')) | Event::Interrupt => return None,
+            Event::Key(Key::Char(' ')) => player.toggle_pause(),
+            Event::Key(Key::Char('.') | Key::Right) => stepped |= player.step(),
+            _ => {}
+        }
+    }
+    Some(stepped)
+}
+
+
+/// Wait for `timeout`; without the `input` feature there are no keys to handle.
+#[cfg(not(all(feature = "input", unix)))]
+fn wait(_player: &mut Player, timeout: Duration) -> Option<bool> {
+    thread::sleep(timeout);
+    Some(false)
+}
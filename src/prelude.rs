@@ -0,0 +1,7 @@
+// Common items, for importing everything a typical program needs with a single `use terminalgl::prelude::*;`.
+pub use super::canvas::{BlendMode, Canvas, Cell};
+pub use super::drawc::{ColorKind, NamedColor, Shading};
+pub use super::sketch::Sketch;
+pub use super::{clear, cursorto, cursorto_checked, screen_rect, size};
+pub use super::{draw, drawc, raster};
+pub use super::{Anchor, ArrowHead, CalendarOptions, ConnectorStyle, Constraint, Direction, GaugeOptions, OutOfBounds, Quadrant, Rect, TextAlignment, TreeNode, TreeStyle, VAlign};
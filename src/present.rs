@@ -0,0 +1,305 @@
+// Present canvases with screen shake and transitions between frames.
+use super::canvas::{Canvas, Cell};
+use super::drawc;
+use super::particles::Rng;
+
+
+/// An effect applied to the frames a `Presenter` prints while it runs.
+/// Once an effect has finished, frames are printed exactly as drawn again.
+///
+/// Example
+/// ```
+/// use terminalgl::canvas::Canvas;
+/// use terminalgl::present::{Effect, Presenter};
+/// let mut canvas = Canvas::new(8, 4);
+/// canvas.text(2, 2, "boom", "");
+/// let mut presenter = Presenter::new(7);
+/// presenter.start(Effect::Shake { magnitude: 2.0, duration: 0.5 });
+/// for _ in 0..4 {
+///     assert_eq!(presenter.render(&canvas, 0.1).width(), 8);
+/// }
+/// assert_eq!(presenter.render(&canvas, 0.1), canvas);
+///
+/// presenter.start(Effect::FadeToBlack { frames: 4 });
+/// presenter.render(&canvas, 0.0);
+/// let black = presenter.render(&canvas, 0.0);
+/// assert_eq!(black.get(2, 2).unwrap().c, 'b');
+/// assert_eq!(black.ccode_at(2, 2), Some("\x1b[38;2;0;0;0m"));
+/// presenter.render(&canvas, 0.0);
+/// assert_eq!(presenter.render(&canvas, 0.0), canvas);
+///
+/// let mut next = Canvas::new(8, 4);
+/// next.text(2, 2, "next", "");
+/// presenter.present(&canvas, 0.0);
+/// presenter.start(Effect::Crossfade { duration: 0.5 });
+/// assert_eq!(presenter.render(&next, 0.2).get(2, 2).unwrap().c, 'b');
+/// assert_eq!(presenter.render(&next, 0.2).get(2, 2).unwrap().c, 'n');
+/// assert_eq!(presenter.render(&next, 0.2), next);
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Effect {
+    /// Offset each frame by a random jitter of up to `magnitude` cells, shrinking to nothing over `duration` seconds.
+    Shake { magnitude: f64, duration: f64 },
+    /// Slide the new frame in over the previous one from the right edge, over `frames` frames.
+    WipeLeft { frames: usize },
+    /// Slide the new frame in over the previous one from the left edge, over `frames` frames.
+    WipeRight { frames: usize },
+    /// Open a growing circle from the center showing the new frame, over `frames` frames.
+    Iris { frames: usize },
+    /// Darken the previous frame to black over the first half of `frames`, then brighten the new frame from black over the second half.
+    FadeToBlack { frames: usize },
+    /// Blend the previous frame into the new one with `crossfade`, over `duration` seconds.
+    Crossfade { duration: f64 },
+    /// Blend the previous frame into the new one with `dissolve` and `seed`, over `duration` seconds.
+    Dissolve { duration: f64, seed: u64 }
+}
+
+
+/// Prints canvases to the terminal with `present_diff`, keeping the frame shown last so effects can draw over it.
+///
+/// Example
+/// ```
+/// use terminalgl::canvas::Canvas;
+/// use terminalgl::present::{Effect, Presenter};
+/// let mut title = Canvas::new(10, 3);
+/// title.text(0, 1, "title", "");
+/// let mut game = Canvas::new(10, 3);
+/// game.text(0, 1, "game", "");
+/// let mut presenter = Presenter::new(1);
+/// presenter.present(&title, 0.0);
+/// presenter.start(Effect::WipeRight { frames: 2 });
+/// let halfway = presenter.render(&game, 0.0);
+/// assert_eq!(halfway.get(0, 1).unwrap().c, 'g');
+/// assert_eq!(halfway.get(9, 1), title.get(9, 1));
+/// assert_eq!(presenter.render(&game, 0.0), game);
+/// assert!(!presenter.is_active());
+/// ```
+#[derive(Clone, Debug)]
+pub struct Presenter {
+    shown: Option<Canvas>,
+    from: Option<Canvas>,
+    effect: Option<Effect>,
+    frame: usize,
+    elapsed: f64,
+    rng: Rng
+}
+
+impl Presenter {
+    /// Create a presenter with no effect running, seeding the random number generator used for shaking with `seed`.
+    pub fn new(seed: u64) -> Presenter {
+        Presenter { shown: None, from: None, effect: None, frame: 0, elapsed: 0.0, rng: Rng::new(seed) }
+    }
+
+    /// Start `effect`, replacing any effect already running. Transitions start from the frame shown last.
+    pub fn start(&mut self, effect: Effect) {
+        self.from = self.shown.clone();
+        self.effect = Some(effect);
+        self.frame = 0;
+        self.elapsed = 0.0;
+    }
+
+    /// Get whether an effect is still running.
+    pub fn is_active(&self) -> bool {
+        self.effect.is_some()
+    }
+
+    /// Get the frame shown last, with any effect applied, or `None` before the first frame.
+    pub fn shown(&self) -> Option<&Canvas> {
+        self.shown.as_ref()
+    }
+
+    /// Get `canvas` with the running effect applied, then advance the effect by one frame of `dt` seconds.
+    /// The last frame of an effect, and every frame after it, is `canvas` unchanged.
+    pub fn render(&mut self, canvas: &Canvas, dt: f64) -> Canvas {
+        let Some(effect) = self.effect else {
+            return canvas.clone();
+        };
+        self.frame += 1;
+        self.elapsed += dt;
+        let blank = Canvas::new(canvas.width(), canvas.height());
+        let from = self.from.as_ref().unwrap_or(&blank);
+        let (width, height) = (canvas.width() as f64, canvas.height() as f64);
+        let out = match effect {
+            Effect::Shake { magnitude, duration } => {
+                if self.elapsed >= duration {
+                    None
+                }
+                else {
+                    let strength = magnitude * (1.0 - self.elapsed / duration);
+                    let dx = self.rng.range(-strength, strength).round() as isize;
+                    let dy = (self.rng.range(-strength, strength) / 2.0).round() as isize;
+                    Some(shifted(canvas, dx, dy))
+                }
+            },
+            Effect::WipeLeft { frames } => self.progress(frames).map(|t| {
+                let edge = width * (1.0 - t);
+                mix(from, canvas, |x, _| x as f64 >= edge)
+            }),
+            Effect::WipeRight { frames } => self.progress(frames).map(|t| {
+                let edge = width * t;
+                mix(from, canvas, |x, _| (x as f64) < edge)
+            }),
+            Effect::Iris { frames } => self.progress(frames).map(|t| {
+                // Cells are about twice as tall as they are wide, so distances across are halved to keep the circle round.
+                let (cx, cy) = (width / 2.0, height / 2.0);
+                let radius = t * (cx * cx / 4.0 + cy * cy).sqrt();
+                mix(from, canvas, |x, y| {
+                    let (dx, dy) = ((x as f64 + 0.5 - cx) / 2.0, y as f64 + 0.5 - cy);
+                    dx * dx + dy * dy <= radius * radius
+                })
+            }),
+            Effect::FadeToBlack { frames } => self.progress(frames).map(|t| {
+                let mut faded = if t < 0.5 { from.clone() } else { canvas.clone() };
+                faded.darken(1.0 - (2.0 * t - 1.0).abs());
+                faded
+            }),
+            Effect::Crossfade { duration } => self.timed(duration).map(|t| over(canvas, crossfade(from, canvas, t))),
+            Effect::Dissolve { duration, seed } => self.timed(duration).map(|t| over(canvas, dissolve(from, canvas, t, seed)))
+        };
+        out.unwrap_or_else(|| {
+            self.effect = None;
+            self.from = None;
+            canvas.clone()
+        })
+    }
+
+    /// Get how far through a transition of `frames` frames the current frame is, or `None` on its last frame.
+    fn progress(&self, frames: usize) -> Option<f64> {
+        if self.frame >= frames { None } else { Some(self.frame as f64 / frames as f64) }
+    }
+
+    /// Get how far through a transition of `duration` seconds the current frame is, or `None` once it has run that long.
+    fn timed(&self, duration: f64) -> Option<f64> {
+        if self.elapsed >= duration { None } else { Some(self.elapsed / duration) }
+    }
+
+    /// Print `canvas` with the running effect applied, printing only the cells that differ from the frame shown last.
+    pub fn present(&mut self, canvas: &Canvas, dt: f64) {
+        let frame = self.render(canvas, dt);
+        match &self.shown {
+            Some(shown) => frame.present_diff(shown),
+            None => frame.present()
+        }
+        self.shown = Some(frame);
+    }
+
+    /// Start `effect` and print its first frame over `canvas`, like calling `start` then `present`.
+    pub fn present_with(&mut self, canvas: &Canvas, effect: Effect, dt: f64) {
+        self.start(effect);
+        self.present(canvas, dt);
+    }
+}
+
+
+/// Get a frame between `from` and `to`, where `t` of `0.0` gives `from` and `1.0` gives `to`: each cell's colors are blended
+/// with `drawc::lerp_ccode`, and its character switches from `from`'s to `to`'s halfway. Canvases of different sizes are
+/// blended where they overlap, so the frame is as wide as the narrower and as tall as the shorter.
+///
+/// Example
+/// ```
+/// use terminalgl::canvas::Canvas;
+/// use terminalgl::drawc::{self, ColorKind};
+/// use terminalgl::present;
+/// let mut menu = Canvas::new(6, 2);
+/// menu.text(0, 0, "menu", &drawc::rgb_to_ccode((200, 0, 0), ColorKind::Fg));
+/// let mut game = Canvas::new(4, 3);
+/// game.text(0, 0, "game", &drawc::rgb_to_ccode((0, 200, 0), ColorKind::Fg));
+/// let early = present::crossfade(&menu, &game, 0.25);
+/// assert_eq!((early.width(), early.height()), (4, 2));
+/// assert_eq!(early.get(0, 0).unwrap().c, 'm');
+/// assert_eq!(early.ccode_at(0, 0), Some("\x1b[38;2;150;50;0m"));
+/// let late = present::crossfade(&menu, &game, 0.75);
+/// assert_eq!(late.get(0, 0).unwrap().c, 'g');
+/// assert_eq!(late.ccode_at(0, 0), Some("\x1b[38;2;50;150;0m"));
+/// let done = present::crossfade(&menu, &game, 1.0);
+/// assert_eq!((done.get(3, 0).unwrap().c, done.ccode_at(3, 0)), ('e', game.ccode_at(3, 0)));
+/// ```
+pub fn crossfade(from: &Canvas, to: &Canvas, t: f64) -> Canvas {
+    blend(from, to, t, |_, _| t >= 0.5)
+}
+
+
+/// Get a frame between `from` and `to` like `crossfade`, but with each cell switching character at its own point between
+/// `0.0` and `1.0`, picked at random from `seed` and its position, so the new frame's characters speckle in.
+/// The same seed always gives the same pattern.
+///
+/// Example
+/// ```
+/// use terminalgl::present;
+/// use terminalgl::sketch::Sketch;
+/// let mut menu = Sketch::with_size(20, 5);
+/// menu.fill_rect(0, 0, 20, 5, '.');
+/// let mut game = Sketch::with_size(20, 5);
+/// game.fill_rect(0, 0, 20, 5, '#');
+/// let (menu, game) = (menu.into_canvas(), game.into_canvas());
+/// let switched = |t| {
+///     let frame = present::dissolve(&menu, &game, t, 42);
+///     frame.rect().cells().filter(|&(x, y)| frame.get(x, y).unwrap().c == '#').count()
+/// };
+/// assert_eq!(switched(0.0), 0);
+/// assert!(switched(0.3) < switched(0.6));
+/// assert!(switched(0.3) > 0 && switched(0.6) < 100);
+/// assert_eq!(switched(1.0), 100);
+/// assert_eq!(present::dissolve(&menu, &game, 0.5, 42), present::dissolve(&menu, &game, 0.5, 42));
+/// ```
+pub fn dissolve(from: &Canvas, to: &Canvas, t: f64, seed: u64) -> Canvas {
+    blend(from, to, t, |x, y| {
+        let threshold = Rng::new(seed ^ ((y as u64) << 32 | x as u64)).next_f64();
+        t >= 1.0 || t > threshold
+    })
+}
+
+
+/// Get the cells where `from` and `to` overlap blended by `t`, with `to`'s character where `show_new(x, y)` is true.
+fn blend(from: &Canvas, to: &Canvas, t: f64, show_new: impl Fn(isize, isize) -> bool) -> Canvas {
+    let mut out = Canvas::new(from.width().min(to.width()), from.height().min(to.height()));
+    for (x, y) in out.rect().cells() {
+        let (Some(old), Some(new)) = (from.get(x, y), to.get(x, y)) else {
+            continue;
+        };
+        let ccode = drawc::lerp_ccode(from.ccode_at(x, y).unwrap_or(""), to.ccode_at(x, y).unwrap_or(""), t);
+        let c = if show_new(x, y) { new.c } else { old.c };
+        out.put(x, y, Cell::new(c, &ccode));
+    }
+    out
+}
+
+
+/// Get `canvas` with `blended` over its top left corner, for a transition from a frame of another size.
+fn over(canvas: &Canvas, blended: Canvas) -> Canvas {
+    if (blended.width(), blended.height()) == (canvas.width(), canvas.height()) {
+        return blended;
+    }
+    mix(&blended, canvas, |x, y| x as usize >= blended.width() || y as usize >= blended.height())
+}
+
+
+/// Get `canvas` moved by `(dx, dy)`, with blank cells where nothing moved in.
+fn shifted(canvas: &Canvas, dx: isize, dy: isize) -> Canvas {
+    let mut out = Canvas::new(canvas.width(), canvas.height());
+    for (x, y) in canvas.rect().cells() {
+        if let Some(cell) = canvas.get(x - dx, y - dy) {
+            out.put(x, y, resolved(canvas, x - dx, y - dy, cell));
+        }
+    }
+    out
+}
+
+
+/// Get a canvas the size of `to` with its cells where `show_new(x, y)` is true and the cells of `from` elsewhere.
+fn mix(from: &Canvas, to: &Canvas, show_new: impl Fn(isize, isize) -> bool) -> Canvas {
+    let mut out = Canvas::new(to.width(), to.height());
+    for (x, y) in to.rect().cells() {
+        let source = if show_new(x, y) { to } else { from };
+        if let Some(cell) = source.get(x, y) {
+            out.put(x, y, resolved(source, x, y, cell));
+        }
+    }
+    out
+}
+
+
+/// Get `cell` of `canvas` with its palette index replaced by the color code it is drawn with, so it can move to another canvas.
+fn resolved(canvas: &Canvas, x: isize, y: isize, cell: &Cell) -> Cell {
+    Cell::new(cell.c, canvas.ccode_at(x, y).unwrap_or(""))
+}
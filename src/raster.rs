@@ -0,0 +1,2075 @@
+// Pure geometry and layout shared by `draw` and `drawc`.
+use std::borrow::Cow;
+use std::f64::consts::PI;
+use std::ops::Range;
+use super::chars::{self, LineStyle};
+use super::core::{self, clip_bounds};
+use super::{ArrowHead, ConnectorStyle, Direction, GaugeOptions, Quadrant, Rect, TextAlignment, TextDirection, TreeNode, TreeStyle, Truncation, WrapOptions};
+
+
+/// Get the cells of a `width` by `height` rectangle at `(x, y)`, each exactly once, in row order.
+/// With `fill` every cell is included, otherwise only the cells on its edge.
+/// A width or height of 0 gives no cells, 1 gives a single line, and 2 gives an outline with no interior.
+///
+/// Example
+/// ```
+/// use terminalgl::raster;
+/// assert_eq!(raster::rectangle(1, 1, 3, 3, false).len(), 8);
+/// assert_eq!(raster::rectangle(0, 0, 5, 1, false), vec![(0, 0), (1, 0), (2, 0), (3, 0), (4, 0)]);
+/// assert!(raster::rectangle(0, 0, 0, 5, true).is_empty());
+///
+/// for width in 0..=3 {
+///     for height in 0..=3 {
+///         let all: Vec<(isize, isize)> = (0..height).flat_map(|y| (0..width).map(move |x| (x, y))).collect();
+///         let edge: Vec<(isize, isize)> = all.iter().copied().filter(|&(x, y)| x == 0 || y == 0 || x == width - 1 || y == height - 1).collect();
+///         assert_eq!(raster::rectangle(0, 0, width as usize, height as usize, true), all);
+///         assert_eq!(raster::rectangle(0, 0, width as usize, height as usize, false), edge);
+///     }
+/// }
+/// ```
+pub fn rectangle(x: isize, y: isize, width: usize, height: usize, fill: bool) -> Vec<(isize, isize)> {
+    rectangle_clipped(x, y, width, height, fill, Rect::new(x, y, width, height))
+}
+
+
+/// Get the cells of a rectangle like `rectangle`, but only those inside `clip`.
+/// Only the clipped area is visited, so huge rectangles cost no more than the clip area.
+///
+/// Example
+/// ```
+/// use terminalgl::{raster, Rect};
+/// let cells = raster::rectangle_clipped(-5, 1, usize::MAX, 3, false, Rect::new(0, 0, 4, 4));
+/// assert_eq!(cells, vec![(0, 1), (1, 1), (2, 1), (3, 1), (0, 3), (1, 3), (2, 3), (3, 3)]);
+/// ```
+pub fn rectangle_clipped(x: isize, y: isize, width: usize, height: usize, fill: bool, clip: Rect) -> Vec<(isize, isize)> {
+    core::rectangle_clipped(x, y, width, height, fill, clip).collect()
+}
+
+
+/// Get the cells of a straight line starting at `(x, y)` with length `length` in direction `dir`, in drawing order,
+/// but only those inside `clip`. A negative `length` goes the opposite way, so `-3` cells `Left` is `3` cells `Right`,
+/// and a length of `0` gives no cells. Only the clipped part of the line is visited, so any length costs no more than the clip area.
+///
+/// Example
+/// ```
+/// use terminalgl::{raster, Direction, Rect};
+/// use Direction::*;
+/// let clip = Rect::new(0, 0, 4, 4);
+/// assert_eq!(raster::straight_line_clipped(2, 1, 3, Right, clip), vec![(2, 1), (3, 1)]);
+/// assert_eq!(raster::straight_line_clipped(2, 1, -3, Down, clip), vec![(2, 1), (2, 0)]);
+/// assert_eq!(raster::straight_line_clipped(isize::MAX, 2, isize::MIN, Right, clip).len(), 4);
+///
+/// let cases = [
+///     (Right, 2, vec![(1, 2), (2, 2)]), (Right, -2, vec![(1, 2), (0, 2)]), (Right, isize::MIN, vec![(1, 2), (0, 2)]),
+///     (Left, 2, vec![(1, 2), (0, 2)]), (Left, -2, vec![(1, 2), (2, 2)]), (Left, isize::MIN, vec![(1, 2), (2, 2), (3, 2)]),
+///     (Up, 2, vec![(1, 2), (1, 1)]), (Up, -2, vec![(1, 2), (1, 3)]), (Up, isize::MIN, vec![(1, 2), (1, 3)]),
+///     (Down, 2, vec![(1, 2), (1, 3)]), (Down, -2, vec![(1, 2), (1, 1)]), (Down, isize::MIN, vec![(1, 2), (1, 1), (1, 0)]),
+/// ];
+/// for (dir, length, cells) in cases {
+///     assert_eq!(raster::straight_line_clipped(1, 2, length, dir, clip), cells);
+///     assert!(raster::straight_line_clipped(1, 2, 0, dir, clip).is_empty());
+/// }
+/// ```
+pub fn straight_line_clipped(x: isize, y: isize, length: isize, dir: Direction, clip: Rect) -> Vec<(isize, isize)> {
+    core::straight_line_clipped(x, y, length, dir, clip).collect()
+}
+
+
+/// Get the offset from the center to the top and bottom of the column `dx` cells from the center of an ellipse
+/// with horizontal radius `a` and vertical radius `b`, or `None` outside the ellipse.
+/// Offsets are rounded from the ellipse equation, so the leftmost and rightmost columns hold just the center row.
+fn ellipse_half_height(a: i128, b: i128, dx: i128) -> Option<i128> {
+    if dx.abs() > a {
+        return None;
+    }
+    if a == 0 {
+        return Some(b);
+    }
+    let ratio = dx as f64 / a as f64;
+    Some((b as f64 * (1.0 - ratio * ratio).max(0.0).sqrt()).round() as i128)
+}
+
+
+/// Get the cells of an ellipse centered at `(h, k)` with horizontal radius `a` and vertical radius `b`, each exactly once, in column order.
+/// With `fill` every cell inside is included, otherwise only the filled cells that have an empty neighbor above, below, left or right,
+/// so the outline is exactly the boundary of the filled ellipse.
+///
+/// Example
+/// ```
+/// use terminalgl::{assert_frame_eq, raster};
+/// use terminalgl::canvas::Canvas;
+/// let filled = raster::ellipse(0, 0, 4, 2, true);
+/// let outline = raster::ellipse(0, 0, 4, 2, false);
+/// assert_eq!(filled.len(), 33);
+/// assert_eq!(outline.len(), 16);
+/// assert!(outline.iter().all(|cell| filled.contains(cell)));
+/// assert!(filled.contains(&(-4, 0)) && !filled.contains(&(-4, 1)));
+///
+/// let mut unique = filled.clone();
+/// unique.sort();
+/// unique.dedup();
+/// assert_eq!(unique.len(), filled.len());
+/// assert_eq!(raster::ellipse(3, 3, 0, 0, true), vec![(3, 3)]);
+///
+/// let mut canvas = Canvas::new(9, 5);
+/// for (x, y) in raster::ellipse(4, 2, 4, 2, false) {
+///     canvas.set(x, y, '#', "");
+/// }
+/// assert_frame_eq!(canvas, "\
+/// |  #####  |
+/// | #     # |
+/// |#       #|
+/// | #     # |
+/// |  #####  |");
+/// ```
+pub fn ellipse(h: isize, k: isize, a: usize, b: usize, fill: bool) -> Vec<(isize, isize)> {
+    let bounds = Rect::new(h.saturating_sub_unsigned(a), k.saturating_sub_unsigned(b), a.saturating_mul(2).saturating_add(1), b.saturating_mul(2).saturating_add(1));
+    ellipse_clipped(h, k, a, b, fill, bounds)
+}
+
+
+/// Get the cells of an ellipse like `ellipse`, but only those inside `clip`.
+/// Only the clipped area is visited, so huge radii cost no more than the clip area.
+///
+/// Example
+/// ```
+/// use terminalgl::{raster, Rect};
+/// use terminalgl::canvas::Canvas;
+/// let clip = Rect::new(0, 0, 80, 24);
+/// let cells = raster::ellipse_clipped(40, 12, usize::MAX / 2, usize::MAX / 2, true, clip);
+/// assert_eq!(cells.len(), 80 * 24);
+/// assert_eq!(raster::ellipse_clipped(40, 12, usize::MAX / 2, 3, false, clip).len(), 80 * 2);
+/// assert_eq!(raster::ellipse_clipped(0, 0, 4, 2, true, Rect::new(-10, -10, 20, 20)), raster::ellipse(0, 0, 4, 2, true));
+///
+/// // Ellipses at each corner and just outside each edge match an unclipped draw into a larger buffer, offset by (20, 10).
+/// let clip = Rect::new(0, 0, 20, 10);
+/// for (h, k) in [(0, 0), (19, 0), (0, 9), (19, 9), (-1, 5), (20, 5), (10, -1), (10, 10), (isize::MIN, 3)] {
+///     for (a, b, fill) in [(0, 0, true), (3, 2, false), (7, 5, true), (12, 1, false), (1, 12, true)] {
+///         let mut reference = Canvas::new(60, 30);
+///         for (x, y) in raster::ellipse(h, k, a, b, fill) {
+///             reference.set(x.saturating_add(20), y.saturating_add(10), '#', "");
+///         }
+///         let mut clipped = Canvas::new(20, 10);
+///         for (x, y) in raster::ellipse_clipped(h, k, a, b, fill, clip) {
+///             clipped.set(x, y, '#', "");
+///         }
+///         for (x, y) in (0..20).flat_map(|x| (0..10).map(move |y| (x, y))) {
+///             assert_eq!(reference.get(x + 20, y + 10), clipped.get(x, y));
+///         }
+///     }
+/// }
+/// ```
+pub fn ellipse_clipped(h: isize, k: isize, a: usize, b: usize, fill: bool, clip: Rect) -> Vec<(isize, isize)> {
+    let (h, k, a, b) = (h as i128, k as i128, a as i128, b as i128);
+    let (cx0, cy0, cx1, cy1) = clip_bounds(clip);
+    let inside = |dx: i128, dy: i128| ellipse_half_height(a, b, dx).is_some_and(|half| dy.abs() <= half);
+    let mut cells = Vec::new();
+    for x in (h - a).max(cx0)..=(h + a).min(cx1) {
+        let dx = x - h;
+        let Some(half) = ellipse_half_height(a, b, dx) else {
+            continue;
+        };
+        for y in (k - half).max(cy0)..=(k + half).min(cy1) {
+            let dy = y - k;
+            if fill || dy.abs() == half || !inside(dx - 1, dy) || !inside(dx + 1, dy) {
+                cells.push((x as isize, y as isize));
+            }
+        }
+    }
+    cells
+}
+
+
+/// Get the cells of one quarter of the ellipse `ellipse` gives for the same arguments, for rounded corners and pie-like shapes.
+/// The quarter includes the cells on the ellipse's center row and column on its side, so neighbouring quarters share them,
+/// and drawing all four covers exactly the cells of the whole ellipse: the cells come from the same rasterizer, so the
+/// edges line up with it, and an outline keeps only the cells on the whole ellipse's outline. Terminal cells are about
+/// twice as tall as they are wide, so a `b` of about half `a` looks round.
+///
+/// Example
+/// ```
+/// use terminalgl::canvas::Canvas;
+/// use terminalgl::{assert_frame_eq, raster, Quadrant};
+/// let mut canvas = Canvas::new(5, 3);
+/// for (x, y) in raster::ellipse_quadrant(4, 2, 4, 2, Quadrant::TopLeft, false) {
+///     canvas.set(x, y, '#', "");
+/// }
+/// assert_frame_eq!(canvas, "\
+/// |  ###|
+/// | #   |
+/// |#    |");
+///
+/// // The four quadrants drawn together are the whole ellipse, filled or outlined, at any size.
+/// let quadrants = [Quadrant::TopLeft, Quadrant::TopRight, Quadrant::BottomLeft, Quadrant::BottomRight];
+/// for (a, b) in [(0, 0), (1, 0), (0, 3), (4, 2), (7, 3), (10, 5), (3, 9)] {
+///     for fill in [true, false] {
+///         let mut whole = Canvas::new(30, 30);
+///         for (x, y) in raster::ellipse(12, 12, a, b, fill) {
+///             whole.set(x, y, '#', "");
+///         }
+///         let mut assembled = Canvas::new(30, 30);
+///         for quadrant in quadrants {
+///             for (x, y) in raster::ellipse_quadrant(12, 12, a, b, quadrant, fill) {
+///                 assembled.set(x, y, '#', "");
+///             }
+///         }
+///         assert_eq!(assembled, whole, "{}x{} fill {}", a, b, fill);
+///     }
+/// }
+/// ```
+pub fn ellipse_quadrant(h: isize, k: isize, a: usize, b: usize, quadrant: Quadrant, fill: bool) -> Vec<(isize, isize)> {
+    ellipse_quadrant_clipped(h, k, a, b, quadrant, fill, Rect::new(isize::MIN, isize::MIN, usize::MAX, usize::MAX))
+}
+
+
+/// Get the cells of a quarter of an ellipse like `ellipse_quadrant`, but only those inside `clip`.
+pub fn ellipse_quadrant_clipped(h: isize, k: isize, a: usize, b: usize, quadrant: Quadrant, fill: bool, clip: Rect) -> Vec<(isize, isize)> {
+    let (left, top) = match quadrant {
+        Quadrant::TopLeft => (true, true),
+        Quadrant::TopRight => (false, true),
+        Quadrant::BottomLeft => (true, false),
+        Quadrant::BottomRight => (false, false)
+    };
+    let x = if left { h.saturating_sub_unsigned(a) } else { h };
+    let y = if top { k.saturating_sub_unsigned(b) } else { k };
+    let part = Rect::new(x, y, a.saturating_add(1), b.saturating_add(1));
+    ellipse_clipped(h, k, a, b, fill, part.intersect(clip))
+}
+
+
+/// Get the cells of the half of the ellipse `ellipse` gives for the same arguments on `side` of its center, including
+/// the center row or column, like `ellipse_quadrant` does for quarters. The two opposite halves together are the whole ellipse.
+///
+/// Example
+/// ```
+/// use terminalgl::canvas::Canvas;
+/// use terminalgl::{assert_frame_eq, raster, Direction};
+/// let mut canvas = Canvas::new(9, 3);
+/// for (x, y) in raster::ellipse_half(4, 2, 4, 2, Direction::Up, true) {
+///     canvas.set(x, y, '#', "");
+/// }
+/// assert_frame_eq!(canvas, "\
+/// |  #####  |
+/// | ####### |
+/// |#########|");
+///
+/// for (a, b, fill) in [(4, 2, false), (7, 3, true), (2, 6, false)] {
+///     let whole = raster::ellipse(0, 0, a, b, fill);
+///     for (one, other) in [(Direction::Up, Direction::Down), (Direction::Left, Direction::Right)] {
+///         let mut halves = raster::ellipse_half(0, 0, a, b, one, fill);
+///         halves.extend(raster::ellipse_half(0, 0, a, b, other, fill));
+///         halves.sort();
+///         halves.dedup();
+///         let mut expected = whole.clone();
+///         expected.sort();
+///         assert_eq!(halves, expected);
+///     }
+/// }
+/// ```
+pub fn ellipse_half(h: isize, k: isize, a: usize, b: usize, side: Direction, fill: bool) -> Vec<(isize, isize)> {
+    ellipse_half_clipped(h, k, a, b, side, fill, Rect::new(isize::MIN, isize::MIN, usize::MAX, usize::MAX))
+}
+
+
+/// Get the cells of half of an ellipse like `ellipse_half`, but only those inside `clip`.
+pub fn ellipse_half_clipped(h: isize, k: isize, a: usize, b: usize, side: Direction, fill: bool, clip: Rect) -> Vec<(isize, isize)> {
+    let (x, y) = (h.saturating_sub_unsigned(a), k.saturating_sub_unsigned(b));
+    let (width, height) = (a.saturating_mul(2).saturating_add(1), b.saturating_mul(2).saturating_add(1));
+    let part = match side {
+        Direction::Left => Rect::new(x, y, a.saturating_add(1), height),
+        Direction::Right => Rect::new(h, y, a.saturating_add(1), height),
+        Direction::Up => Rect::new(x, y, width, b.saturating_add(1)),
+        Direction::Down => Rect::new(x, k, width, b.saturating_add(1))
+    };
+    ellipse_clipped(h, k, a, b, fill, part.intersect(clip))
+}
+
+
+/// Get the vertices of a regular polygon centered at `(cx, cy)` with `sides` sides.
+/// `rotation` is in radians, and `sides` is clamped to a minimum of 3. Vertical distances are scaled by `aspect`:
+/// terminal cells are about twice as tall as they are wide, so `0.5` makes the polygon look regular, and `1.0` leaves it as is.
+///
+/// Example
+/// ```
+/// use terminalgl::raster;
+/// use std::f64::consts::PI;
+/// let square = raster::regular_polygon_points(0, 0, 2, 4, 0.0, 1.0);
+/// assert_eq!(square, vec![(2, 0), (0, 2), (-2, 0), (0, -2)]);
+/// // A pentagon pointing up, half as tall in cells as it is wide so it looks regular.
+/// let pentagon = raster::regular_polygon_points(0, 0, 10, 5, -PI / 2.0, 0.5);
+/// assert_eq!(pentagon, vec![(0, -5), (10, -2), (6, 4), (-6, 4), (-10, -2)]);
+/// ```
+pub fn regular_polygon_points(cx: isize, cy: isize, radius: usize, sides: usize, rotation: f64, aspect: f64) -> Vec<(isize, isize)> {
+    let sides = sides.max(3);
+    let step = 2.0 * PI / sides as f64;
+    (0..sides).map(|i| {
+        point_on_ellipse(cx, cy, radius as f64, radius as f64 * aspect, rotation + step * i as f64)
+    }).collect()
+}
+
+
+/// Get the vertices of a star centered at `(cx, cy)` with `points` points.
+/// Vertices alternate between `outer_radius` and `inner_radius`, starting with an outer one.
+/// `rotation` is in radians, `points` is clamped to a minimum of 3, and vertical distances are scaled by `aspect`
+/// like in `regular_polygon_points`.
+///
+/// Example
+/// ```
+/// use terminalgl::raster;
+/// let star = raster::star_points(0, 0, 10, 4, 5, 0.0, 1.0);
+/// assert_eq!(star.len(), 10);
+/// assert_eq!(star[0], (10, 0));
+/// let squat = raster::star_points(0, 0, 10, 4, 4, 0.0, 0.5);
+/// assert_eq!(squat.iter().step_by(2).copied().collect::<Vec<_>>(), vec![(10, 0), (0, 5), (-10, 0), (0, -5)]);
+/// ```
+pub fn star_points(cx: isize, cy: isize, outer_radius: usize, inner_radius: usize, points: usize, rotation: f64, aspect: f64) -> Vec<(isize, isize)> {
+    let points = points.max(3);
+    let step = PI / points as f64;
+    (0..points*2).map(|i| {
+        let radius = if i % 2 == 0 { outer_radius } else { inner_radius } as f64;
+        point_on_ellipse(cx, cy, radius, radius * aspect, rotation + step * i as f64)
+    }).collect()
+}
+
+
+fn point_on_circle(cx: isize, cy: isize, radius: f64, angle: f64) -> (isize, isize) {
+    point_on_ellipse(cx, cy, radius, radius, angle)
+}
+
+
+fn point_on_ellipse(h: isize, k: isize, a: f64, b: f64, angle: f64) -> (isize, isize) {
+    let x = h as f64 + a * angle.cos();
+    let y = k as f64 + b * angle.sin();
+    (x.round() as isize, y.round() as isize)
+}
+
+
+/// Get the cells of an elliptical arc centered at `(h, k)` with horizontal radius `a` and vertical radius `b`,
+/// going from angle `start` to angle `end`. Angles are in radians and increase clockwise on screen,
+/// so `0` points right and `PI / 2` points down. Arcs are traced in at most 4096 steps joined by lines,
+/// so huge sweeps take no longer than a full turn of a large ellipse; use `arc_clipped` for huge radii.
+///
+/// Example
+/// ```
+/// use terminalgl::{assert_frame_eq, raster};
+/// use terminalgl::canvas::Canvas;
+/// use std::f64::consts::PI;
+/// let cells = raster::arc(0, 0, 3, 3, 0.0, PI / 2.0);
+/// assert_eq!(cells.first(), Some(&(3, 0)));
+/// assert_eq!(cells.last(), Some(&(0, 3)));
+/// assert!(raster::arc(0, 0, 3, 3, 0.0, 1e300).iter().all(|&(x, y)| x.abs() <= 3 && y.abs() <= 3));
+///
+/// let mut canvas = Canvas::new(7, 4);
+/// for (x, y) in raster::arc(3, 0, 3, 3, 0.0, PI) {
+///     canvas.set(x, y, '#', "");
+/// }
+/// assert_frame_eq!(canvas, "\
+/// |#     #|
+/// |#     #|
+/// | #   # |
+/// | ##### |");
+/// ```
+pub fn arc(h: isize, k: isize, a: usize, b: usize, start: f64, end: f64) -> Vec<(isize, isize)> {
+    let steps = arc_steps(a, b, start, end).min(ARC_MAX_STEPS) as usize;
+    let mut cells: Vec<(isize, isize)> = Vec::new();
+    let mut prev = point_on_ellipse(h, k, a as f64, b as f64, start);
+    cells.push(prev);
+    for i in 1..=steps {
+        let angle = start + (end - start) * i as f64 / steps as f64;
+        let next = point_on_ellipse(h, k, a as f64, b as f64, angle);
+        for cell in line(prev.0, prev.1, next.0, next.1).into_iter().skip(1) {
+            if cells.last() != Some(&cell) {
+                cells.push(cell);
+            }
+        }
+        prev = next;
+    }
+    cells
+}
+
+
+/// Most steps `arc` traces an arc in, and the longest arc `arc_clipped` traces before switching to the ellipse outline.
+const ARC_MAX_STEPS: f64 = 4096.0;
+
+
+/// Get the number of steps `arc` traces an arc in, one per half cell along its widest radius.
+fn arc_steps(a: usize, b: usize, start: f64, end: f64) -> f64 {
+    ((a.max(b) as f64) * (end - start).abs() * 2.0).ceil().max(1.0)
+}
+
+
+/// Get the cells of an arc like `arc`, but only those inside `clip`.
+/// Arcs too long to trace are instead taken from the outline of `ellipse_clipped`, keeping the cells whose angle
+/// lies between `start` and `end`, so huge radii cost no more than the clip area. Arcs of ellipses entirely outside `clip` are not traced at all.
+///
+/// Example
+/// ```
+/// use terminalgl::{raster, Rect};
+/// use terminalgl::canvas::Canvas;
+/// use std::f64::consts::PI;
+/// let clip = Rect::new(0, 0, 80, 24);
+/// let inside: Vec<(isize, isize)> = raster::arc(2, 2, 5, 3, 0.0, PI).into_iter().filter(|&(x, y)| clip.contains(x, y)).collect();
+/// assert_eq!(raster::arc_clipped(2, 2, 5, 3, 0.0, PI, clip), inside);
+///
+/// let cells = raster::arc_clipped(40, 12, usize::MAX / 2, 3, 0.0, PI, clip);
+/// assert_eq!(cells.len(), 80);
+/// assert!(cells.iter().all(|&(_, y)| y == 15));
+///
+/// // Arcs at each corner and just outside each edge match an unclipped draw into a larger buffer, offset by (20, 10).
+/// let clip = Rect::new(0, 0, 20, 10);
+/// for (h, k) in [(0, 0), (19, 0), (0, 9), (19, 9), (-1, 5), (20, 5), (10, -1), (10, 10), (isize::MAX, 3)] {
+///     for (a, b, start, end) in [(0, 0, 0.0, PI), (3, 2, -PI / 2.0, PI / 3.0), (7, 5, PI, -PI), (12, 1, 0.0, 2.0 * PI)] {
+///         let mut reference = Canvas::new(60, 30);
+///         for (x, y) in raster::arc(h, k, a, b, start, end) {
+///             reference.set(x.saturating_add(20), y.saturating_add(10), '#', "");
+///         }
+///         let mut clipped = Canvas::new(20, 10);
+///         for (x, y) in raster::arc_clipped(h, k, a, b, start, end, clip) {
+///             clipped.set(x, y, '#', "");
+///         }
+///         for (x, y) in (0..20).flat_map(|x| (0..10).map(move |y| (x, y))) {
+///             assert_eq!(reference.get(x + 20, y + 10), clipped.get(x, y));
+///         }
+///     }
+/// }
+/// ```
+pub fn arc_clipped(h: isize, k: isize, a: usize, b: usize, start: f64, end: f64, clip: Rect) -> Vec<(isize, isize)> {
+    let (cx0, cy0, cx1, cy1) = clip_bounds(clip);
+    let (ch, ck, ca, cb) = (h as i128, k as i128, a as i128, b as i128);
+    if ch + ca < cx0 || ch - ca > cx1 || ck + cb < cy0 || ck - cb > cy1 {
+        return Vec::new();
+    }
+    if arc_steps(a, b, start, end) <= ARC_MAX_STEPS {
+        return arc(h, k, a, b, start, end).into_iter().filter(|&(x, y)| clip.contains(x, y)).collect();
+    }
+    let sweep = end - start;
+    let in_sweep = |x: isize, y: isize| {
+        let angle = ((y as i128 - k as i128) as f64 * a as f64).atan2((x as i128 - h as i128) as f64 * b as f64);
+        let offset = (angle - start).rem_euclid(2.0 * PI);
+        sweep.abs() >= 2.0 * PI || if sweep >= 0.0 { offset <= sweep } else { offset == 0.0 || offset >= 2.0 * PI + sweep }
+    };
+    ellipse_clipped(h, k, a, b, false, clip).into_iter().filter(|&(x, y)| in_sweep(x, y)).collect()
+}
+
+
+/// Hand-drawn dial outlines for radii 2 and 3, as the cells of their top left quarter relative to the center.
+/// Traced arcs this small come out lumpy, so these are mirrored into full rings instead.
+const SMALL_DIALS: [&[(isize, isize)]; 2] = [
+    &[(0, -2), (-1, -2), (-2, -2), (-3, -1), (-4, 0)],
+    &[(0, -3), (-1, -3), (-2, -3), (-3, -3), (-4, -2), (-5, -2), (-6, -1), (-6, 0)]
+];
+
+
+/// Get the cells of the hand-drawn dial `quarter` that lie in the sweep from `start`, as offsets from the center
+/// in the order the sweep passes them.
+fn small_dial(quarter: &[(isize, isize)], a: f64, b: f64, start: f64, sweep: f64) -> Vec<(isize, isize)> {
+    let mut ring: Vec<(isize, isize)> = Vec::new();
+    for &(x, y) in quarter {
+        for cell in [(x, y), (-x, y), (x, -y), (-x, -y)] {
+            if !ring.contains(&cell) {
+                ring.push(cell);
+            }
+        }
+    }
+    let mut dial: Vec<(f64, (isize, isize))> = ring.into_iter().filter_map(|(x, y)| {
+        let offset = ((y as f64 / b).atan2(x as f64 / a) - start).rem_euclid(2.0 * PI);
+        // Cells at either end of the sweep land on it exactly up to rounding.
+        let offset = if offset > 2.0 * PI - 1e-9 { 0.0 } else { offset };
+        (offset <= sweep + 1e-9).then_some((offset, (x, y)))
+    }).collect();
+    dial.sort_by(|p, q| p.0.total_cmp(&q.0));
+    dial.into_iter().map(|(_, cell)| cell).collect()
+}
+
+
+/// Get the cells of a dial centered at `(cx, cy)` with radius `radius` filled to `fraction` (clamped to `0.0..=1.0`),
+/// inside `clip`, as `(x, y, c, filled)` in drawing order. The dial is twice as wide as it is tall so it looks round
+/// in most terminal fonts. `filled` is `true` for the filled part of the dial, the needle, and the label.
+/// Dials of radius 2 and 3 use hand-drawn rings filled cell by cell, so they stay round and fill evenly.
+///
+/// Example
+/// ```
+/// use terminalgl::{assert_frame_eq, raster, GaugeOptions};
+/// use terminalgl::canvas::Canvas;
+/// let mut canvas = Canvas::new(24, 7);
+/// let small = GaugeOptions { c: '#', label: false, ..Default::default() };
+/// let large = GaugeOptions { sweep: 270.0, c: '#', needle: Some('*'), label: true };
+/// let cells = raster::gauge_clipped(4, 2, 2, 0.5, small, canvas.rect()).into_iter()
+///     .chain(raster::gauge_clipped(17, 4, 3, 0.75, large, canvas.rect()));
+/// for (x, y, c, filled) in cells {
+///     canvas.set(x, y, if filled { c } else { '.' }, "");
+/// }
+/// assert_frame_eq!(canvas, "\
+/// |  ###..                 |
+/// | #     .      #######   |
+/// |#       .   ##       ## |
+/// |           #        ***.|
+/// |           #     ***   .|
+/// |           #    75%    .|
+/// |            ##       .. |");
+/// ```
+pub fn gauge_clipped(cx: isize, cy: isize, radius: usize, fraction: f64, opts: GaugeOptions, clip: Rect) -> Vec<(isize, isize, char, bool)> {
+    let fraction = if fraction.is_nan() { 0.0 } else { fraction.clamp(0.0, 1.0) };
+    let sweep = opts.sweep.clamp(0.0, 360.0).to_radians();
+    let start = 1.5 * PI - sweep / 2.0;
+    let split = start + sweep * fraction;
+    let (a, b) = (radius.saturating_mul(2), radius);
+
+    let mut cells = Vec::new();
+    match SMALL_DIALS.get(radius.wrapping_sub(2)) {
+        Some(quarter) => {
+            let dial = small_dial(quarter, a as f64, b as f64, start, sweep);
+            let filled = (fraction * dial.len() as f64).round() as usize;
+            for (i, (dx, dy)) in dial.into_iter().enumerate() {
+                if let (Some(x), Some(y)) = (cx.checked_add(dx), cy.checked_add(dy)) {
+                    cells.push((x, y, opts.c, i < filled));
+                }
+            }
+        },
+        None => {
+            cells.extend(arc_clipped(cx, cy, a, b, start, start + sweep, clip).into_iter().map(|(x, y)| (x, y, opts.c, false)));
+            if fraction > 0.0 {
+                cells.extend(arc_clipped(cx, cy, a, b, start, split, clip).into_iter().map(|(x, y)| (x, y, opts.c, true)));
+            }
+        }
+    }
+    if let Some(needle) = opts.needle {
+        let tip_x = cx.saturating_add(((a as f64 - 1.0).max(0.0) * split.cos()).round() as isize);
+        let tip_y = cy.saturating_add(((b as f64 - 1.0).max(0.0) * split.sin()).round() as isize);
+        cells.extend(line_clipped(cx, cy, tip_x, tip_y, clip).into_iter().map(|(x, y)| (x, y, needle, true)));
+    }
+    if opts.label {
+        let label = format!("{:.0}%", fraction * 100.0);
+        let text = text_directed(cx, cy.saturating_add(1), &label, TextAlignment::Center, TextDirection::Ltr);
+        cells.extend(text.into_iter().map(|(x, y, c)| (x, y, c, true)));
+    }
+    cells.retain(|&(x, y, _, _)| clip.contains(x, y));
+    cells
+}
+
+
+/// Get the horizontal spans covering the inside of the polygon `points` as `(y, x_start, x_end)`, inclusive.
+/// Uses the even-odd rule with each edge covering the rows from its top vertex up to, but not including, its bottom vertex,
+/// so the bottom row of the polygon is left for the outline.
+///
+/// Example
+/// ```
+/// use terminalgl::{assert_frame_eq, raster};
+/// use terminalgl::canvas::Canvas;
+/// let spans = raster::polygon_spans(&[(0, 0), (4, 0), (4, 2), (0, 2)]);
+/// assert_eq!(spans, vec![(0, 0, 4), (1, 0, 4)]);
+///
+/// let mut canvas = Canvas::new(7, 4);
+/// for (y, start, end) in raster::polygon_spans(&[(0, 0), (6, 0), (3, 3)]) {
+///     for x in start..=end {
+///         canvas.set(x, y, '#', "");
+///     }
+/// }
+/// assert_frame_eq!(canvas, "\
+/// |#######|
+/// | ##### |
+/// |  ###  |
+/// |       |");
+/// ```
+pub fn polygon_spans(points: &[(isize, isize)]) -> Vec<(isize, isize, isize)> {
+    polygon_spans_clipped(points, bounds(points))
+}
+
+
+/// Get the spans of a polygon like `polygon_spans`, but cut down to the part inside `clip`.
+/// Only the rows inside `clip` are visited, so polygons with far-off vertices cost no more than the clip area.
+///
+/// Example
+/// ```
+/// use terminalgl::{raster, Rect};
+/// let spans = raster::polygon_spans_clipped(&[(-100, 0), (100, 0), (100, 2), (-100, 2)], Rect::new(0, 1, 5, 5));
+/// assert_eq!(spans, vec![(1, 0, 4)]);
+/// assert_eq!(raster::polygon_spans_clipped(&[(isize::MIN, isize::MIN), (isize::MAX, 0), (0, isize::MAX)], Rect::new(0, 0, 4, 2)).len(), 2);
+/// ```
+pub fn polygon_spans_clipped(points: &[(isize, isize)], clip: Rect) -> Vec<(isize, isize, isize)> {
+    let mut spans = Vec::new();
+    if points.len() < 3 || clip.width == 0 || clip.height == 0 {
+        return spans;
+    }
+    let min_y = points.iter().map(|p| p.1).min().unwrap().max(clip.y);
+    let max_y = points.iter().map(|p| p.1).max().unwrap().min(clip.y.saturating_add_unsigned(clip.height - 1));
+    let (left, right) = (clip.x, clip.x.saturating_add_unsigned(clip.width - 1));
+
+    for y in min_y..=max_y {
+        let mut crossings: Vec<f64> = Vec::new();
+        for i in 0..points.len() {
+            let (x1, y1) = points[i];
+            let (x2, y2) = points[(i+1) % points.len()];
+            if (y1 <= y && y2 > y) || (y2 <= y && y1 > y) {
+                let t = (y as f64 - y1 as f64) / (y2 as f64 - y1 as f64);
+                crossings.push(x1 as f64 + t * (x2 as f64 - x1 as f64));
+            }
+        }
+        crossings.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        for pair in crossings.chunks_exact(2) {
+            let start = (pair[0].round() as isize).max(left);
+            let end = (pair[1].round() as isize).min(right);
+            if start <= end {
+                spans.push((y, start, end));
+            }
+        }
+    }
+    spans
+}
+
+
+/// Get the smallest rectangle holding every cell in `points`, short of the rightmost column and bottom row
+/// if they are more than `usize::MAX` cells away from the leftmost and topmost.
+fn bounds(points: &[(isize, isize)]) -> Rect {
+    let (Some(min_x), Some(max_x)) = (points.iter().map(|p| p.0).min(), points.iter().map(|p| p.0).max()) else {
+        return Rect::new(0, 0, 0, 0);
+    };
+    let (min_y, max_y) = (points.iter().map(|p| p.1).min().unwrap(), points.iter().map(|p| p.1).max().unwrap());
+    let span = |min: isize, max: isize| max.abs_diff(min).saturating_add(1);
+    Rect::new(min_x, min_y, span(min_x, max_x), span(min_y, max_y))
+}
+
+
+/// Get the cells of a line with starting point `(x1, y1)` and ending point `(x2, y2)`.
+/// The line takes one cell per step along its longer axis, so each cell appears once and both endpoints are included.
+///
+/// Example
+/// ```
+/// use terminalgl::{assert_frame_eq, raster};
+/// use terminalgl::canvas::Canvas;
+/// assert_eq!(raster::line(0, 0, 3, 0), vec![(0, 0), (1, 0), (2, 0), (3, 0)]);
+/// assert_eq!(raster::line(0, 0, 3, 3), vec![(0, 0), (1, 1), (2, 2), (3, 3)]);
+/// assert_eq!(raster::line(2, 5, 2, 5), vec![(2, 5)]);
+///
+/// let mut canvas = Canvas::new(8, 4);
+/// for (x, y) in raster::line(0, 0, 7, 3) {
+///     canvas.set(x, y, '#', "");
+/// }
+/// assert_frame_eq!(canvas, "\
+/// |##      |
+/// |  ##    |
+/// |    ##  |
+/// |      ##|");
+/// ```
+pub fn line(x1: isize, y1: isize, x2: isize, y2: isize) -> Vec<(isize, isize)> {
+    core::line(x1, y1, x2, y2).collect()
+}
+
+
+/// Get the cells of a line like `line`, but only those inside `clip`.
+/// The line is clipped before it is stepped through, so lines between far-off points cost only the cells they light.
+///
+/// Example
+/// ```
+/// use terminalgl::{raster, Rect};
+/// let clip = Rect::new(0, 0, 4, 4);
+/// assert_eq!(raster::line_clipped(-2, 1, 10, 1, clip), vec![(0, 1), (1, 1), (2, 1), (3, 1)]);
+/// assert_eq!(raster::line_clipped(isize::MIN, isize::MIN, isize::MAX, isize::MAX, clip), vec![(0, 0), (1, 1), (2, 2), (3, 3)]);
+/// let inside: Vec<(isize, isize)> = raster::line(-3, -1, 9, 5).into_iter().filter(|&(x, y)| clip.contains(x, y)).collect();
+/// assert_eq!(raster::line_clipped(-3, -1, 9, 5, clip), inside);
+///
+/// // Random segments light exactly the cells of the whole line that fall inside the clip.
+/// let mut seed = 7u64;
+/// let mut random = || { seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407); (seed >> 33) as isize % 61 - 30 };
+/// for _ in 0..2000 {
+///     let (x1, y1, x2, y2) = (random(), random(), random(), random());
+///     let clip = Rect::new(random() / 3, random() / 3, random().unsigned_abs() / 2, random().unsigned_abs() / 2);
+///     let naive: Vec<(isize, isize)> = raster::line(x1, y1, x2, y2).into_iter().filter(|&(x, y)| clip.contains(x, y)).collect();
+///     assert_eq!(raster::line_clipped(x1, y1, x2, y2, clip), naive);
+/// }
+/// ```
+pub fn line_clipped(x1: isize, y1: isize, x2: isize, y2: isize, clip: Rect) -> Vec<(isize, isize)> {
+    core::line_clipped(x1, y1, x2, y2, clip).collect()
+}
+
+
+/// Characters that antialiased shapes are shaded with by default, from empty to solid.
+pub const AA_RAMP: &str = " .:*#";
+
+
+/// Get the cells of a line from `(x1, y1)` to `(x2, y2)` with how much of each the ideal line covers, as `(x, y, coverage)`.
+/// Like Wu's algorithm, each step along the longer axis is split between the two cells either side of the ideal line,
+/// so cells the line passes straight through have a coverage of exactly `1.0`. Cells with no coverage are left out.
+///
+/// Example
+/// ```
+/// use terminalgl::{assert_frame_eq, raster};
+/// use terminalgl::canvas::Canvas;
+/// let mut canvas = Canvas::new(7, 3);
+/// for (x, y, coverage) in raster::line_aa(0, 0, 6, 2) {
+///     canvas.set(x, y, raster::ramp_char(raster::AA_RAMP, coverage), "");
+/// }
+/// assert_frame_eq!(canvas, "\
+/// |#*.    |
+/// | .*#*. |
+/// |    .*#|");
+/// assert_eq!(raster::line_aa(0, 0, 4, 0), raster::line(0, 0, 4, 0).into_iter().map(|(x, y)| (x, y, 1.0)).collect::<Vec<_>>());
+/// ```
+pub fn line_aa(x1: isize, y1: isize, x2: isize, y2: isize) -> Vec<(isize, isize, f64)> {
+    line_aa_clipped(x1, y1, x2, y2, bounds(&[(x1, y1), (x2, y2)]))
+}
+
+
+/// Get the cells of a line like `line_aa`, but only those inside `clip`.
+/// Only the steps along the longer axis that reach `clip` are visited, so lines between far-off points stay cheap.
+///
+/// Example
+/// ```
+/// use terminalgl::{raster, Rect};
+/// let clip = Rect::new(0, 0, 4, 2);
+/// let inside: Vec<(isize, isize, f64)> = raster::line_aa(-3, -1, 9, 3).into_iter().filter(|&(x, y, _)| clip.contains(x, y)).collect();
+/// assert_eq!(raster::line_aa_clipped(-3, -1, 9, 3, clip), inside);
+/// assert_eq!(raster::line_aa_clipped(isize::MIN, isize::MIN, isize::MAX, isize::MAX, clip), vec![(0, 0, 1.0), (1, 1, 1.0)]);
+/// ```
+pub fn line_aa_clipped(x1: isize, y1: isize, x2: isize, y2: isize, clip: Rect) -> Vec<(isize, isize, f64)> {
+    let (cx0, cy0, cx1, cy1) = clip_bounds(clip);
+    let (dx, dy) = (x2 as i128 - x1 as i128, y2 as i128 - y1 as i128);
+    let steep = dy.abs() > dx.abs();
+    // Step along the major axis, splitting each step between two cells on the minor axis.
+    let (major, minor, d_major, d_minor, min, max) = if steep {
+        (y1 as i128, x1 as i128, dy, dx, cy0, cy1)
+    }
+    else {
+        (x1 as i128, y1 as i128, dx, dy, cx0, cx1)
+    };
+    let (steps, dir) = (d_major.abs(), d_major.signum());
+    let (first, last) = if dir < 0 { (major - max, major - min) } else { (min - major, max - major) };
+    let mut cells = Vec::new();
+    for t in first.max(0)..=last.min(steps) {
+        let (offset, frac) = if steps == 0 { (0, 0.0) } else { floor_frac(t, d_minor, steps) };
+        let m = major + dir * t;
+        for (n, coverage) in [(minor + offset, 1.0 - frac), (minor + offset + 1, frac)] {
+            let (x, y) = if steep { (n, m) } else { (m, n) };
+            if coverage > 0.0 && (cx0..=cx1).contains(&x) && (cy0..=cy1).contains(&y) {
+                cells.push((x as isize, y as isize, coverage));
+            }
+        }
+    }
+    cells
+}
+
+
+/// Get the floor of `t * d / steps` and the fraction left over, for `t` in `0..=steps`.
+fn floor_frac(t: i128, d: i128, steps: i128) -> (i128, f64) {
+    let steps_abs = steps.unsigned_abs();
+    let p = t.unsigned_abs() * d.unsigned_abs();
+    let (q, r) = ((p / steps_abs) as i128, p % steps_abs);
+    match (d < 0, r) {
+        (false, _) => (q, r as f64 / steps_abs as f64),
+        (true, 0) => (-q, 0.0),
+        (true, _) => (-q - 1, (steps_abs - r) as f64 / steps_abs as f64)
+    }
+}
+
+
+/// Get the outline of an ellipse centered at `(h, k)` with horizontal radius `a` and vertical radius `b`,
+/// with how much of each cell the ideal curve covers, as `(x, y, coverage)`. Each cell appears once.
+/// Like Wu's algorithm, the flatter parts of the curve are split between two cells in each column and the steeper parts
+/// between two cells in each row, so cells the curve passes straight through have a coverage of exactly `1.0`.
+///
+/// Example
+/// ```
+/// use terminalgl::{assert_frame_eq, raster};
+/// use terminalgl::canvas::Canvas;
+/// let mut canvas = Canvas::new(13, 7);
+/// for (x, y, coverage) in raster::ellipse_aa(6, 3, 6, 3) {
+///     canvas.set(x, y, raster::ramp_char(raster::AA_RAMP, coverage), "");
+/// }
+/// assert_frame_eq!(canvas, "\
+/// |  .:#####:.  |
+/// | **:     :** |
+/// |*.         .*|
+/// |#           #|
+/// |*.         .*|
+/// | **:     :** |
+/// |  .:#####:.  |");
+/// ```
+pub fn ellipse_aa(h: isize, k: isize, a: usize, b: usize) -> Vec<(isize, isize, f64)> {
+    let bounds = Rect::new(h.saturating_sub_unsigned(a), k.saturating_sub_unsigned(b), a.saturating_mul(2).saturating_add(1), b.saturating_mul(2).saturating_add(1));
+    ellipse_aa_clipped(h, k, a, b, bounds)
+}
+
+
+/// Get the cells of an ellipse outline like `ellipse_aa`, but only those inside `clip`.
+/// Only the columns and rows of the ellipse that reach `clip` are visited, so huge radii cost no more than the clip area.
+///
+/// Example
+/// ```
+/// use terminalgl::{raster, Rect};
+/// let clip = Rect::new(0, 0, 6, 4);
+/// let inside: Vec<(isize, isize, f64)> = raster::ellipse_aa(1, 1, 5, 3).into_iter().filter(|&(x, y, _)| clip.contains(x, y)).collect();
+/// assert_eq!(raster::ellipse_aa_clipped(1, 1, 5, 3, clip), inside);
+/// assert_eq!(raster::ellipse_aa_clipped(40, 12, usize::MAX / 2, usize::MAX / 2, Rect::new(0, 0, 80, 24)), vec![]);
+/// ```
+pub fn ellipse_aa_clipped(h: isize, k: isize, a: usize, b: usize, clip: Rect) -> Vec<(isize, isize, f64)> {
+    if a == 0 || b == 0 {
+        let (x1, y1) = (h.saturating_sub_unsigned(a), k.saturating_sub_unsigned(b));
+        return line_aa_clipped(x1, y1, h.saturating_add_unsigned(a), k.saturating_add_unsigned(b), clip);
+    }
+    let (cx0, cy0, cx1, cy1) = clip_bounds(clip);
+    let (h, k, af, bf) = (h as i128, k as i128, a as f64, b as f64);
+    let diagonal = af.hypot(bf);
+    let mut cells = Vec::new();
+    // Columns out to where the curve reaches 45 degrees, then rows out to the same point, mirrored into every quadrant.
+    // Each pass visits only the offsets whose mirrored column or row lands in `clip`.
+    for (steep, center, across, radius, other, split, min, max) in [
+        (false, h, k, af, bf, (af * af / diagonal).ceil() as i128, cx0, cx1),
+        (true, k, h, bf, af, (bf * bf / diagonal).ceil() as i128, cy0, cy1)
+    ] {
+        for (lo, hi) in [(min - center, max - center), (center - max, center - min)] {
+            for d in lo.max(0)..=hi.min(split) {
+                let ratio = d as f64 / radius;
+                let ideal = other * (1.0 - ratio * ratio).max(0.0).sqrt();
+                let (n, frac) = (ideal.floor(), ideal - ideal.floor());
+                for (n, coverage) in [(n as i128, 1.0 - frac), (n as i128 + 1, frac)] {
+                    for (along, side) in [(center + d, across + n), (center + d, across - n), (center - d, across + n), (center - d, across - n)] {
+                        let (x, y) = if steep { (side, along) } else { (along, side) };
+                        if coverage > 0.0 && (cx0..=cx1).contains(&x) && (cy0..=cy1).contains(&y) {
+                            cells.push((x as isize, y as isize, coverage));
+                        }
+                    }
+                }
+            }
+        }
+    }
+    // Keep the strongest coverage of cells reached more than once, by the mirroring or by both passes.
+    cells.sort_by(|p, q| (p.0, p.1).cmp(&(q.0, q.1)).then(q.2.total_cmp(&p.2)));
+    cells.dedup_by_key(|cell| (cell.0, cell.1));
+    cells
+}
+
+
+/// Get the cells of an arrowhead pointing at `(x2, y2)` for an arrow coming from `(x1, y1)`.
+/// Returns `(x, y, c)` triplets, where `c` is the character to draw in that cell.
+/// A zero-length arrow points right.
+///
+/// Examples
+/// ```
+/// use terminalgl::{raster, ArrowHead};
+/// assert_eq!(raster::arrow_head(0, 0, 5, 1, ArrowHead::Char, '#'), vec![(5, 1, '>')]);
+/// assert_eq!(raster::arrow_head(3, 9, 3, 2, ArrowHead::Char, '#'), vec![(3, 2, '^')]);
+/// assert!(raster::arrow_head(0, 0, 8, 0, ArrowHead::Chevron, '#').contains(&(7, 1, '#')));
+/// ```
+pub fn arrow_head(x1: isize, y1: isize, x2: isize, y2: isize, head: ArrowHead, c: char) -> Vec<(isize, isize, char)> {
+    let reach = match head {
+        ArrowHead::Triangle(size) => size.saturating_add(size / 4).saturating_add(2),
+        _ => 2
+    };
+    let bounds = Rect::new(x2.saturating_sub_unsigned(reach), y2.saturating_sub_unsigned(reach), reach.saturating_mul(2).saturating_add(1), reach.saturating_mul(2).saturating_add(1));
+    arrow_head_clipped(x1, y1, x2, y2, head, c, bounds)
+}
+
+
+/// Get the cells of an arrowhead like `arrow_head`, but only those inside `clip`.
+/// Only the clipped part of the head is visited, so huge triangles cost no more than the clip area.
+///
+/// Example
+/// ```
+/// use terminalgl::{raster, ArrowHead, Rect};
+/// let clip = Rect::new(0, 0, 10, 10);
+/// let cells = raster::arrow_head_clipped(0, 0, 8, 0, ArrowHead::Chevron, '#', clip);
+/// assert_eq!(cells, raster::arrow_head(0, 0, 8, 0, ArrowHead::Chevron, '#').into_iter().filter(|&(x, y, _)| clip.contains(x, y)).collect::<Vec<_>>());
+/// assert!(!raster::arrow_head_clipped(isize::MIN, 0, 5, 5, ArrowHead::Triangle(usize::MAX), '#', clip).is_empty());
+/// ```
+pub fn arrow_head_clipped(x1: isize, y1: isize, x2: isize, y2: isize, head: ArrowHead, c: char, clip: Rect) -> Vec<(isize, isize, char)> {
+    let (dx, dy) = if x1 == x2 && y1 == y2 { (1, 0) } else { (x2 as i128 - x1 as i128, y2 as i128 - y1 as i128) };
+    let angle = (dy as f64).atan2(dx as f64);
+    match head {
+        ArrowHead::Char => {
+            let c = if dx.abs() >= dy.abs() {
+                if dx >= 0 { '>' } else { '<' }
+            } else if dy >= 0 { 'v' } else { '^' };
+            if clip.contains(x2, y2) { vec![(x2, y2, c)] } else { Vec::new() }
+        },
+        ArrowHead::Chevron => {
+            let mut cells = Vec::new();
+            for side in [-1.0, 1.0] {
+                let (wx, wy) = point_on_circle(x2, y2, 2.0, angle + PI + side * PI / 4.0);
+                cells.extend(line_clipped(x2, y2, wx, wy, clip).into_iter().map(|(x, y)| (x, y, c)));
+            }
+            cells
+        },
+        ArrowHead::Triangle(size) => {
+            let size = size.max(1) as f64;
+            let (bx, by) = (x2 as f64 - size * angle.cos(), y2 as f64 - size * angle.sin());
+            let (px, py) = (-angle.sin() * size * 0.6, angle.cos() * size * 0.6);
+            let points = [
+                (x2, y2),
+                ((bx + px).round() as isize, (by + py).round() as isize),
+                ((bx - px).round() as isize, (by - py).round() as isize),
+            ];
+            let mut cells: Vec<(isize, isize, char)> = Vec::new();
+            for (y, start, end) in polygon_spans_clipped(&points, clip) {
+                cells.extend((start..=end).map(|x| (x, y, c)));
+            }
+            for i in 0..points.len() {
+                let (ax, ay) = points[i];
+                let (bx, by) = points[(i+1) % points.len()];
+                cells.extend(line_clipped(ax, ay, bx, by, clip).into_iter().map(|(x, y)| (x, y, c)));
+            }
+            cells
+        }
+    }
+}
+
+
+const UP: u8 = 1;
+const DOWN: u8 = 2;
+const LEFT: u8 = 4;
+const RIGHT: u8 = 8;
+
+
+/// Get the cells of an orthogonal connector between the nearest edges of `from` and `to`.
+/// Returns `(x, y, c)` triplets, where `c` is the character to draw in that cell.
+///
+/// The connector leaves `from` perpendicular to the edge facing `to` and bends once or twice at the midpoint.
+/// Rectangles that overlap are connected by a loop around their right sides (or bottoms, if their centers share a row).
+///
+/// Examples
+/// ```
+/// use terminalgl::{raster, ConnectorStyle, Rect};
+/// let style = ConnectorStyle { ascii: false, arrow: true };
+///
+/// // Left to right
+/// let cells = raster::connector(Rect::new(0, 0, 3, 3), Rect::new(8, 2, 3, 3), style);
+/// assert_eq!(cells, vec![(3, 1, '─'), (4, 1, '─'), (5, 1, '┐'), (5, 2, '│'), (5, 3, '└'), (6, 3, '─'), (7, 3, '▶')]);
+///
+/// // Stacked
+/// let cells = raster::connector(Rect::new(0, 0, 5, 2), Rect::new(0, 5, 5, 2), style);
+/// assert_eq!(cells, vec![(2, 2, '│'), (2, 3, '│'), (2, 4, '▼')]);
+///
+/// // Overlapping
+/// let cells = raster::connector(Rect::new(0, 0, 4, 3), Rect::new(2, 2, 4, 3), ConnectorStyle::default());
+/// assert_eq!(cells, vec![(4, 1, '─'), (5, 1, '─'), (6, 1, '─'), (7, 1, '┐'), (7, 2, '│'), (7, 3, '┘'), (6, 3, '─')]);
+/// ```
+pub fn connector(from: Rect, to: Rect, style: ConnectorStyle) -> Vec<(isize, isize, char)> {
+    let (x0, y0) = (from.x.min(to.x) as i128 - 1, from.y.min(to.y) as i128 - 1);
+    let x1 = (from.x as i128 + from.width as i128).max(to.x as i128 + to.width as i128) + 1;
+    let y1 = (from.y as i128 + from.height as i128).max(to.y as i128 + to.height as i128) + 1;
+    let span = |min: i128, max: i128| (max - min.max(isize::MIN as i128) + 1).min(usize::MAX as i128) as usize;
+    let bounds = Rect::new(x0.max(isize::MIN as i128) as isize, y0.max(isize::MIN as i128) as isize, span(x0, x1), span(y0, y1));
+    connector_clipped(from, to, style, bounds)
+}
+
+
+/// Get the cells of a connector like `connector`, but only those inside `clip`.
+/// Straight runs outside `clip` are skipped over, so connectors between far-off rectangles cost no more than the clip area.
+///
+/// Example
+/// ```
+/// use terminalgl::{raster, ConnectorStyle, Rect};
+/// let (from, to) = (Rect::new(0, 0, 3, 3), Rect::new(8, 2, 3, 3));
+/// let clip = Rect::new(4, 0, 3, 5);
+/// let inside: Vec<(isize, isize, char)> = raster::connector(from, to, ConnectorStyle::default()).into_iter().filter(|&(x, y, _)| clip.contains(x, y)).collect();
+/// assert_eq!(raster::connector_clipped(from, to, ConnectorStyle::default(), clip), inside);
+///
+/// let far = Rect::new(isize::MAX - 2, 1, usize::MAX, 1);
+/// assert_eq!(raster::connector_clipped(Rect::new(0, 0, 2, 3), far, ConnectorStyle::default(), Rect::new(0, 0, 5, 3)).len(), 3);
+/// ```
+pub fn connector_clipped(from: Rect, to: Rect, style: ConnectorStyle, clip: Rect) -> Vec<(isize, isize, char)> {
+    let (fx, fy, fw, fh) = (from.x as i128, from.y as i128, from.width as i128, from.height as i128);
+    let (tx, ty, tw, th) = (to.x as i128, to.y as i128, to.width as i128, to.height as i128);
+    let (fcx, fcy) = (fx + fw/2, fy + fh/2);
+    let (tcx, tcy) = (tx + tw/2, ty + th/2);
+
+    let gap_right = tx - (fx + fw);
+    let gap_left = fx - (tx + tw);
+    let gap_down = ty - (fy + fh);
+    let gap_up = fy - (ty + th);
+    let gap_x = gap_right.max(gap_left);
+    let gap_y = gap_down.max(gap_up);
+
+    let (corners, exit_dir) = if gap_x >= 1 && gap_x >= gap_y {
+        let (sx, ex, dir) = if gap_right >= 1 { (fx + fw, tx - 1, RIGHT) } else { (fx - 1, tx + tw, LEFT) };
+        let mx = (sx + ex) / 2;
+        (vec![(sx, fcy), (mx, fcy), (mx, tcy), (ex, tcy)], dir)
+    }
+    else if gap_y >= 1 {
+        let (sy, ey, dir) = if gap_down >= 1 { (fy + fh, ty - 1, DOWN) } else { (fy - 1, ty + th, UP) };
+        let my = (sy + ey) / 2;
+        (vec![(fcx, sy), (fcx, my), (tcx, my), (tcx, ey)], dir)
+    }
+    else if fcy != tcy {
+        let (sx, ex) = (fx + fw, tx + tw);
+        let mx = sx.max(ex) + 1;
+        (vec![(sx, fcy), (mx, fcy), (mx, tcy), (ex, tcy)], RIGHT)
+    }
+    else if fcx != tcx {
+        let (sy, ey) = (fy + fh, ty + th);
+        let my = sy.max(ey) + 1;
+        (vec![(fcx, sy), (fcx, my), (tcx, my), (tcx, ey)], DOWN)
+    }
+    else {
+        return Vec::new();
+    };
+
+    // Walk the path, jumping over cells that are neither next to `clip` nor a corner.
+    // Jumps go the same way as the steps they replace, so the glyphs of the cells that are kept don't change.
+    let (cx0, cy0, cx1, cy1) = clip_bounds(clip);
+    let skip = |v: i128, end: i128, lo: i128, hi: i128| {
+        if (lo..=hi).contains(&v) { v }
+        else if v < lo && end > v { lo.min(end) }
+        else if v > hi && end < v { hi.max(end) }
+        else { end }
+    };
+    let mut path: Vec<(i128, i128)> = vec![corners[0]];
+    for pair in corners.windows(2) {
+        let (mut x, mut y) = pair[0];
+        let (x2, y2) = pair[1];
+        while (x, y) != (x2, y2) {
+            x += (x2 - x).signum();
+            y += (y2 - y).signum();
+            if x != x2 {
+                x = if (cy0 - 1..=cy1 + 1).contains(&y) { skip(x, x2, cx0 - 1, cx1 + 1) } else { x2 };
+            }
+            if y != y2 {
+                y = if (cx0 - 1..=cx1 + 1).contains(&x) { skip(y, y2, cy0 - 1, cy1 + 1) } else { y2 };
+            }
+            path.push((x, y));
+        }
+    }
+
+    let step = |a: (i128, i128), b: (i128, i128)| {
+        if b.0 > a.0 { RIGHT } else if b.0 < a.0 { LEFT } else if b.1 > a.1 { DOWN } else { UP }
+    };
+    let opposite = |d: u8| match d { UP => DOWN, DOWN => UP, LEFT => RIGHT, _ => LEFT };
+
+    let last = path.len() - 1;
+    let mut cells = Vec::new();
+    for i in 0..path.len() {
+        let (x, y) = path[i];
+        if x < cx0 || x > cx1 || y < cy0 || y > cy1 {
+            continue;
+        }
+        let (x, y) = (x as isize, y as isize);
+        let mask = if path.len() == 1 {
+            if style.arrow {
+                cells.push((x, y, arrow_glyph(exit_dir, style.ascii)));
+                continue;
+            }
+            exit_dir | opposite(exit_dir)
+        }
+        else if i == 0 {
+            let d = step(path[0], path[1]);
+            d | opposite(d)
+        }
+        else if i == last {
+            let d = step(path[i-1], path[i]);
+            if style.arrow {
+                cells.push((x, y, arrow_glyph(d, style.ascii)));
+                continue;
+            }
+            d | opposite(d)
+        }
+        else {
+            step(path[i], path[i-1]) | step(path[i], path[i+1])
+        };
+        cells.push((x, y, connector_glyph(mask, style.ascii)));
+    }
+    cells
+}
+
+
+fn connector_glyph(mask: u8, ascii: bool) -> char {
+    let style = if ascii { LineStyle::Ascii } else { LineStyle::Light };
+    chars::junction(mask & UP != 0, mask & DOWN != 0, mask & LEFT != 0, mask & RIGHT != 0, style)
+}
+
+
+fn arrow_glyph(dir: u8, ascii: bool) -> char {
+    match (dir, ascii) {
+        (UP, false) => '▲',
+        (DOWN, false) => '▼',
+        (LEFT, false) => '◀',
+        (_, false) => '▶',
+        (UP, true) => '^',
+        (DOWN, true) => 'v',
+        (LEFT, true) => '<',
+        (_, true) => '>'
+    }
+}
+
+
+/// Lay out `root` as rows of `(guide, label, node)`, where `guide` is the indentation and branch characters
+/// preceding `label`. The row summarizing rows cut off by `style.max_height` has no node.
+///
+/// Example
+/// ```
+/// use terminalgl::{raster, TreeNode, TreeStyle};
+/// let root = TreeNode::new("src", vec![
+///     TreeNode::new("draw", vec![TreeNode::leaf("line.rs"), TreeNode::leaf("text.rs")]),
+///     TreeNode::leaf("lib.rs"),
+/// ]);
+/// let rows: Vec<String> = raster::tree_rows(&root, TreeStyle::default()).into_iter()
+///     .map(|(guide, label, _)| guide + &label)
+///     .collect();
+/// assert_eq!(rows, vec!["src", "├── draw", "│   ├── line.rs", "│   └── text.rs", "└── lib.rs"]);
+///
+/// let style = TreeStyle { ascii: true, max_width: Some(10), max_height: Some(3) };
+/// let rows: Vec<String> = raster::tree_rows(&root, style).into_iter()
+///     .map(|(guide, label, _)| guide + &label)
+///     .collect();
+/// assert_eq!(rows, vec!["src", "|-- draw", "+3 more"]);
+/// ```
+pub fn tree_rows(root: &TreeNode, style: TreeStyle) -> Vec<(String, String, Option<&TreeNode>)> {
+    let mut rows = vec![(String::new(), root.label.clone(), Some(root))];
+    tree_rows_inner(root, String::new(), style.ascii, &mut rows);
+
+    if let Some(max_height) = style.max_height {
+        if rows.len() > max_height {
+            let hidden = rows.len() - max_height.saturating_sub(1);
+            rows.truncate(max_height.saturating_sub(1));
+            if max_height > 0 {
+                rows.push((String::new(), format!("+{} more", hidden), None));
+            }
+        }
+    }
+    if let Some(max_width) = style.max_width {
+        for (guide, label, _) in rows.iter_mut() {
+            let guide_width = text_width(guide);
+            if guide_width >= max_width {
+                *guide = truncate_to_width(guide, max_width, Truncation::None).into_owned();
+                label.clear();
+            }
+            else if let Cow::Owned(cut) = truncate_to_width(label, max_width - guide_width, Truncation::End) {
+                *label = cut;
+            }
+        }
+    }
+    rows
+}
+
+
+fn tree_rows_inner<'a>(node: &'a TreeNode, indent: String, ascii: bool, rows: &mut Vec<(String, String, Option<&'a TreeNode>)>) {
+    let (branch, last_branch, pipe) = if ascii { ("|-- ", "`-- ", "|   ") } else { ("├── ", "└── ", "│   ") };
+    for (i, child) in node.children.iter().enumerate() {
+        let last = i == node.children.len() - 1;
+        rows.push((indent.clone() + if last { last_branch } else { branch }, child.label.clone(), Some(child)));
+        tree_rows_inner(child, indent.clone() + if last { "    " } else { pipe }, ascii, rows);
+    }
+}
+
+
+/// Width of a calendar drawn with `calendar`, in columns.
+pub const CALENDAR_WIDTH: usize = 20;
+
+const MONTH_NAMES: [&str; 12] = [
+    "January", "February", "March", "April", "May", "June",
+    "July", "August", "September", "October", "November", "December"
+];
+
+
+/// Get whether `year` is a leap year in the Gregorian calendar.
+pub fn is_leap_year(year: i32) -> bool {
+    (year % 4 == 0 && year % 100 != 0) || year % 400 == 0
+}
+
+
+/// Get the number of days in `month` (1 to 12) of `year`.
+///
+/// Example
+/// ```
+/// use terminalgl::raster;
+/// assert_eq!(raster::days_in_month(2024, 2), 29);
+/// assert_eq!(raster::days_in_month(1900, 2), 28);
+/// assert_eq!(raster::days_in_month(2023, 12), 31);
+/// ```
+pub fn days_in_month(year: i32, month: u32) -> u32 {
+    match month {
+        2 if is_leap_year(year) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31
+    }
+}
+
+
+/// Get the day of the week of a date, with `0` being Sunday and `6` being Saturday.
+///
+/// Example
+/// ```
+/// use terminalgl::raster;
+/// assert_eq!(raster::day_of_week(2024, 2, 1), 4);  // Thursday
+/// assert_eq!(raster::day_of_week(2000, 1, 1), 6);  // Saturday
+/// ```
+pub fn day_of_week(year: i32, month: u32, day: u32) -> u32 {
+    const OFFSETS: [i32; 12] = [0, 3, 2, 5, 0, 3, 5, 1, 4, 6, 2, 4];
+    let y = if month < 3 { year - 1 } else { year };
+    let m = month.clamp(1, 12) as usize;
+    (y + y.div_euclid(4) - y.div_euclid(100) + y.div_euclid(400) + OFFSETS[m-1] + day as i32).rem_euclid(7) as u32
+}
+
+
+/// Get the position of `day` within a calendar laid out by `calendar_rows` as `(column, row)`,
+/// or `None` if the month has no such day.
+///
+/// Example
+/// ```
+/// use terminalgl::raster;
+/// assert_eq!(raster::calendar_position(2024, 2, 1, false), Some((12, 2)));
+/// assert_eq!(raster::calendar_position(2024, 2, 29, true), Some((9, 6)));
+/// assert_eq!(raster::calendar_position(2024, 2, 0, false), None);
+/// assert_eq!(raster::calendar_position(2023, 2, 29, false), None);
+/// ```
+pub fn calendar_position(year: i32, month: u32, day: u32, monday_first: bool) -> Option<(usize, usize)> {
+    if !(1..=days_in_month(year, month)).contains(&day) {
+        return None;
+    }
+    let first = (day_of_week(year, month, 1) + if monday_first { 6 } else { 0 }) % 7;
+    let index = (first + day - 1) as usize;
+    Some((index % 7 * 3, index / 7 + 2))
+}
+
+
+/// Lay out a month as rows of text, each `CALENDAR_WIDTH` columns wide:
+/// a centered title, the weekday headers, and one row per week.
+/// `month` is clamped to 1 to 12.
+///
+/// Example
+/// ```
+/// use terminalgl::raster;
+/// assert_eq!(raster::calendar_rows(2024, 2, false), vec![
+///     "   February 2024    ",
+///     "Su Mo Tu We Th Fr Sa",
+///     "             1  2  3",
+///     " 4  5  6  7  8  9 10",
+///     "11 12 13 14 15 16 17",
+///     "18 19 20 21 22 23 24",
+///     "25 26 27 28 29      ",
+/// ]);
+/// assert_eq!(raster::calendar_rows(2024, 9, true)[1..3], [
+///     "Mo Tu We Th Fr Sa Su",
+///     "                   1",
+/// ]);
+/// ```
+pub fn calendar_rows(year: i32, month: u32, monday_first: bool) -> Vec<String> {
+    let month = month.clamp(1, 12);
+    let title = format!("{} {}", MONTH_NAMES[month as usize - 1], year);
+    let mut rows = vec![format!("{:^width$}", title, width = CALENDAR_WIDTH)];
+    rows.push(String::from(if monday_first { "Mo Tu We Th Fr Sa Su" } else { "Su Mo Tu We Th Fr Sa" }));
+
+    let first_column = calendar_position(year, month, 1, monday_first).map_or(0, |(column, _)| column);
+    let mut week: Vec<String> = vec![String::from("  "); first_column / 3];
+    for day in 1..=days_in_month(year, month) {
+        week.push(format!("{:>2}", day));
+        if week.len() == 7 {
+            rows.push(week.join(" "));
+            week.clear();
+        }
+    }
+    if !week.is_empty() {
+        rows.push(format!("{:<width$}", week.join(" "), width = CALENDAR_WIDTH));
+    }
+    rows
+}
+
+
+/// Ranges of characters two cells wide: CJK, Hangul, fullwidth forms, and emoji.
+const WIDE_CHARS: [(u32, u32); 15] = [
+    (0x1100, 0x115F), (0x2E80, 0x303E), (0x3041, 0x33FF), (0x3400, 0x4DBF), (0x4E00, 0x9FFF),
+    (0xA000, 0xA4CF), (0xAC00, 0xD7A3), (0xF900, 0xFAFF), (0xFE30, 0xFE4F), (0xFF00, 0xFF60),
+    (0xFFE0, 0xFFE6), (0x1F300, 0x1F64F), (0x1F900, 0x1F9FF), (0x20000, 0x2FFFD), (0x30000, 0x3FFFD)
+];
+
+/// Ranges of characters that take no cells of their own: combining marks (including Hebrew points and Arabic vowel marks),
+/// zero width joiners, and variation selectors.
+const ZERO_WIDTH_CHARS: [(u32, u32); 18] = [
+    (0x0300, 0x036F), (0x0591, 0x05BD), (0x05BF, 0x05BF), (0x05C1, 0x05C2), (0x05C4, 0x05C5), (0x05C7, 0x05C7),
+    (0x0610, 0x061A), (0x064B, 0x065F), (0x0670, 0x0670), (0x06D6, 0x06DC), (0x06DF, 0x06E4), (0x06E7, 0x06E8),
+    (0x06EA, 0x06ED), (0x1AB0, 0x1AFF), (0x200B, 0x200D), (0x20D0, 0x20FF), (0xFE00, 0xFE0F), (0xFE20, 0xFE2F)
+];
+
+
+/// Get the number of cells `c` takes up in a terminal: `2` for wide characters like CJK and emoji,
+/// `0` for marks that combine with the character before them, and `1` for everything else.
+///
+/// Example
+/// ```
+/// use terminalgl::raster;
+/// assert_eq!(raster::char_width('a'), 1);
+/// assert_eq!(raster::char_width('漢'), 2);
+/// assert_eq!(raster::char_width('🙂'), 2);
+/// assert_eq!(raster::char_width('\u{301}'), 0);
+/// ```
+pub fn char_width(c: char) -> usize {
+    let in_any = |ranges: &[(u32, u32)]| ranges.iter().any(|&(lo, hi)| (lo..=hi).contains(&(c as u32)));
+    if in_any(&WIDE_CHARS) { 2 } else if in_any(&ZERO_WIDTH_CHARS) { 0 } else { 1 }
+}
+
+
+/// Get the number of cells `text` takes up in a terminal, adding up `grapheme_width` of each of its `graphemes`.
+///
+/// Example
+/// ```
+/// use terminalgl::raster;
+/// assert_eq!(raster::text_width("hi 漢字"), 7);
+/// assert_eq!(raster::text_width("e\u{301}👩\u{200D}💻!"), 4);
+/// ```
+pub fn text_width(text: &str) -> usize {
+    graphemes(text).map(grapheme_width).sum()
+}
+
+
+/// Get the number of cells the grapheme `grapheme` takes up in a terminal: the `char_width` of its first character,
+/// or `2` for a flag or a character followed by the emoji variation selector `U+FE0F`.
+///
+/// Example
+/// ```
+/// use terminalgl::raster;
+/// assert_eq!(raster::grapheme_width("a\u{301}"), 1);
+/// assert_eq!(raster::grapheme_width("👨\u{200D}👩\u{200D}👧"), 2);
+/// assert_eq!(raster::grapheme_width("🇳🇿"), 2);
+/// assert_eq!(raster::grapheme_width("❤\u{FE0F}"), 2);
+/// ```
+pub fn grapheme_width(grapheme: &str) -> usize {
+    match grapheme.chars().next() {
+        Some(c) if is_regional_indicator(c) || grapheme.contains('\u{FE0F}') => 2,
+        Some(c) => char_width(c),
+        None => 0
+    }
+}
+
+
+/// Split `text` into graphemes, the characters a reader sees: a character together with the marks combining with it,
+/// an emoji sequence joined by zero width joiners or with a skin tone, a flag made of two regional indicators, or `\r\n`.
+/// Control characters are always graphemes of their own.
+///
+/// Example
+/// ```
+/// use terminalgl::raster;
+/// let text = "ae\u{301}👩\u{200D}💻漢🇳🇿👍🏽\r\n";
+/// assert_eq!(raster::graphemes(text).collect::<Vec<_>>(), vec!["a", "e\u{301}", "👩\u{200D}💻", "漢", "🇳🇿", "👍🏽", "\r\n"]);
+/// ```
+pub fn graphemes(text: &str) -> impl Iterator<Item = &str> {
+    let mut start = 0;
+    grapheme_ends(text).map(move |end| {
+        let grapheme = &text[start..end];
+        start = end;
+        grapheme
+    })
+}
+
+
+/// Get the byte offset just past the grapheme (see `graphemes`) that `byte_idx` is in, or `text.len()` past the end.
+/// Use it to step a cursor right by one grapheme.
+///
+/// Example
+/// ```
+/// use terminalgl::raster;
+/// let text = "e\u{301}x";
+/// assert_eq!(raster::next_grapheme(text, 0), 3);
+/// assert_eq!(raster::next_grapheme(text, 1), 3);
+/// assert_eq!(raster::next_grapheme(text, 3), 4);
+/// assert_eq!(raster::next_grapheme(text, 4), 4);
+/// ```
+pub fn next_grapheme(text: &str, byte_idx: usize) -> usize {
+    grapheme_ends(text).find(|&end| end > byte_idx).unwrap_or(text.len())
+}
+
+
+/// Get the byte offset where the grapheme (see `graphemes`) before `byte_idx` starts, or `0` at the start.
+/// Use it to step a cursor left by one grapheme, or to find what Backspace removes.
+///
+/// Example
+/// ```
+/// use terminalgl::raster;
+/// let text = "x👩\u{200D}💻";
+/// assert_eq!(raster::prev_grapheme(text, text.len()), 1);
+/// assert_eq!(raster::prev_grapheme(text, 1), 0);
+/// assert_eq!(raster::prev_grapheme(text, 0), 0);
+/// ```
+pub fn prev_grapheme(text: &str, byte_idx: usize) -> usize {
+    std::iter::once(0).chain(grapheme_ends(text)).take_while(|&start| start < byte_idx).last().unwrap_or(0)
+}
+
+
+/// Get the byte offset just past each grapheme of `text`, in order.
+fn grapheme_ends(text: &str) -> impl Iterator<Item = usize> + '_ {
+    let mut chars = text.char_indices().peekable();
+    std::iter::from_fn(move || {
+        let (_, mut prev) = chars.next()?;
+        let mut half_flag = is_regional_indicator(prev);
+        while let Some(&(i, c)) = chars.peek() {
+            let joins = if prev.is_control() || c.is_control() {
+                prev == '\r' && c == '\n'
+            }
+            else if is_regional_indicator(c) {
+                half_flag
+            }
+            else {
+                prev == '\u{200D}' || char_width(c) == 0 || ('\u{1F3FB}'..='\u{1F3FF}').contains(&c)
+            };
+            if !joins {
+                return Some(i);
+            }
+            half_flag = false;
+            prev = c;
+            chars.next();
+        }
+        Some(text.len())
+    })
+}
+
+
+/// Get whether `c` is a regional indicator, two of which make a flag.
+fn is_regional_indicator(c: char) -> bool {
+    ('\u{1F1E6}'..='\u{1F1FF}').contains(&c)
+}
+
+
+/// Split `text` into lines no wider than `width`, breaking at spaces where possible, with `wrap_text` and the default `WrapOptions`.
+/// Words longer than `width` are split, and newlines in `text` always start a new line.
+///
+/// Example
+/// ```
+/// use terminalgl::raster;
+/// assert_eq!(raster::wrap_words("the quick brown fox", 10), vec!["the quick", "brown fox"]);
+/// assert_eq!(raster::wrap_words("abcdefgh ij", 3), vec!["abc", "def", "gh", "ij"]);
+/// ```
+pub fn wrap_words(text: &str, width: usize) -> Vec<String> {
+    wrap_text(text, width, WrapOptions::default()).into_iter().map(Cow::into_owned).collect()
+}
+
+
+/// A piece of a paragraph that `wrap_text` keeps on one line, with where it is and how many cells it takes.
+struct WrapPiece {
+    range: Range<usize>,
+    width: usize,
+    kind: WrapPieceKind
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum WrapPieceKind {
+    Text,
+    Space,
+    SoftHyphen
+}
+
+
+/// Get whether a line may not start with `grapheme`, because it is punctuation that closes what comes before it.
+fn is_closing(grapheme: &str) -> bool {
+    grapheme.chars().next().is_some_and(|c| ",.;:!?)]}%'\"»’”、。，．：；！？）］｝」』】〉》〕〗〙〛ー々ゝゞ・".contains(c))
+}
+
+
+/// Get whether a line may not end with `grapheme`, because it is punctuation that opens what comes after it.
+fn is_opening(grapheme: &str) -> bool {
+    grapheme.chars().next().is_some_and(|c| "([{«‘“（［｛「『【〈《〔〖〘〚".contains(c))
+}
+
+
+/// Split `text` into lines no wider than `width` cells (see `text_width`), as `options` says, for drawing or for measuring ahead of time.
+/// Lines break at spaces, which are left out at the break, after `-` and `/` inside words, and between wide graphemes
+/// such as CJK ideographs, which need no spaces between them. Newlines in `text` always start a new line,
+/// and a `width` of 0 counts as 1. Lines that are a slice of `text` are borrowed from it.
+///
+/// Example
+/// ```
+/// use terminalgl::{raster, WrapOptions};
+/// let options = WrapOptions::default();
+/// assert_eq!(raster::wrap_text("see https://example.com/docs/wrapping-text", 16, options),
+///     vec!["see https://", "example.com/", "docs/wrapping-", "text"]);
+/// assert_eq!(raster::wrap_text("日本語の文章です。", 8, options), vec!["日本語の", "文章で", "す。"]);
+/// assert_eq!(raster::wrap_text("in\u{AD}ter\u{AD}na\u{AD}tion\u{AD}al", 8, options), vec!["interna-", "tional"]);
+/// assert_eq!(raster::wrap_text("a  b   c", 8, options), vec!["a b c"]);
+/// assert_eq!(raster::wrap_text("a  b   c", 4, WrapOptions { preserve_whitespace: true, ..options }), vec!["a  b", "c"]);
+/// assert_eq!(raster::wrap_text("done (finally).", 5, options), vec!["done", "(fina", "lly)."]);
+/// assert_eq!(raster::wrap_text("abcdef", 4, WrapOptions { break_anywhere: false, ..options }), vec!["abcdef"]);
+/// assert_eq!(raster::wrap_text("one\n\ntwo", 8, options), vec!["one", "", "two"]);
+/// ```
+///
+/// A width of 1 still puts something on every line, even graphemes too wide for it, and punctuation only stays attached while it fits:
+/// ```
+/// use terminalgl::{raster, WrapOptions};
+/// let options = WrapOptions::default();
+/// assert_eq!(raster::wrap_text("ab, c", 1, options), vec!["a", "b", ",", "c"]);
+/// assert_eq!(raster::wrap_text("漢字", 1, options), vec!["漢", "字"]);
+/// assert_eq!(raster::wrap_text("a\u{AD}b", 1, options), vec!["a", "b"]);
+/// ```
+///
+/// Lines that are slices of the text are borrowed:
+/// ```
+/// use terminalgl::{raster, WrapOptions};
+/// use std::borrow::Cow;
+/// let lines = raster::wrap_text("the quick brown fox", 10, WrapOptions::default());
+/// assert!(lines.iter().all(|line| matches!(line, Cow::Borrowed(_))));
+/// ```
+pub fn wrap_text<'a>(text: &'a str, width: usize, options: WrapOptions) -> Vec<Cow<'a, str>> {
+    let width = width.max(1);
+    let mut lines = Vec::new();
+    let mut start = 0;
+    for paragraph in text.split('\n') {
+        let pieces = wrap_pieces(text, start..start + paragraph.len(), options);
+        start += paragraph.len() + 1;
+        // Ranges of `text` on the current line, its width, and spaces waiting for text to follow them.
+        let mut line: Vec<Range<usize>> = Vec::new();
+        let mut used = 0;
+        let mut space: Option<(Range<usize>, usize)> = None;
+        let mut first = true;
+        let finish = |line: &mut Vec<Range<usize>>, used: &mut usize, hyphen: bool, lines: &mut Vec<Cow<'a, str>>| {
+            let contiguous = line.windows(2).all(|pair| pair[0].end == pair[1].start);
+            let joined: Cow<'a, str> = match (line.first(), line.last()) {
+                (Some(first), Some(last)) if contiguous && !hyphen => Cow::Borrowed(&text[first.start..last.end]),
+                _ => Cow::Owned(line.iter().map(|range| &text[range.clone()]).collect::<String>() + if hyphen { "-" } else { "" })
+            };
+            lines.push(joined);
+            line.clear();
+            *used = 0;
+        };
+        for (i, piece) in pieces.iter().enumerate() {
+            match piece.kind {
+                WrapPieceKind::Space => {
+                    if options.preserve_whitespace && (first || !line.is_empty()) {
+                        space = Some((piece.range.clone(), piece.width));
+                    }
+                    else if !line.is_empty() {
+                        space = Some((piece.range.start..piece.range.start + 1, 1));
+                    }
+                },
+                WrapPieceKind::SoftHyphen => {},
+                WrapPieceKind::Text => {
+                    let hyphen_after = pieces.get(i + 1).is_some_and(|next| next.kind == WrapPieceKind::SoftHyphen);
+                    let space_width = space.as_ref().map_or(0, |(_, w)| *w);
+                    if used + space_width + piece.width + hyphen_after as usize > width && !line.is_empty() {
+                        let hyphen = i > 0 && pieces[i - 1].kind == WrapPieceKind::SoftHyphen && used < width;
+                        finish(&mut line, &mut used, hyphen, &mut lines);
+                        space = None;
+                    }
+                    else if let Some((range, w)) = space.take() {
+                        if used + w + piece.width <= width {
+                            line.push(range);
+                            used += w;
+                        }
+                    }
+                    first = false;
+                    if piece.width <= width || !options.break_anywhere {
+                        line.push(piece.range.clone());
+                        used += piece.width;
+                        continue;
+                    }
+                    // Too wide for any line: fill lines with as much of it as fits, keeping punctuation with its neighbors.
+                    let graphemes: Vec<&str> = graphemes(&text[piece.range.clone()]).collect();
+                    let mut offset = piece.range.start;
+                    let mut rest = &graphemes[..];
+                    while !rest.is_empty() {
+                        let mut take = 0;
+                        let mut taken = 0;
+                        while take < rest.len() && (take == 0 || taken + grapheme_width(rest[take]) <= width - used) {
+                            taken += grapheme_width(rest[take]);
+                            take += 1;
+                        }
+                        while options.attach_punctuation && take > 1 && take < rest.len()
+                            && (is_closing(rest[take]) || is_opening(rest[take - 1])) {
+                            take -= 1;
+                            taken -= grapheme_width(rest[take]);
+                        }
+                        let len: usize = rest[..take].iter().map(|g| g.len()).sum();
+                        line.push(offset..offset + len);
+                        used += taken;
+                        offset += len;
+                        rest = &rest[take..];
+                        if !rest.is_empty() {
+                            finish(&mut line, &mut used, false, &mut lines);
+                        }
+                    }
+                }
+            }
+        }
+        if let Some((range, w)) = space.filter(|_| options.preserve_whitespace) {
+            if used + w <= width {
+                line.push(range);
+            }
+        }
+        finish(&mut line, &mut used, false, &mut lines);
+    }
+    lines
+}
+
+
+/// Split the paragraph at `range` of `text` into the pieces `wrap_text` lays out: runs of spaces, soft hyphens,
+/// and runs of text with no place to break inside them.
+fn wrap_pieces(text: &str, range: Range<usize>, options: WrapOptions) -> Vec<WrapPiece> {
+    let mut pieces: Vec<WrapPiece> = Vec::new();
+    let mut i = range.start;
+    let mut prev: Option<&str> = None;
+    // Whether the text piece being built has something other than `-` and `/`, so it may break after one of those.
+    let mut wordy = false;
+    while i < range.end {
+        let end = next_grapheme(text, i).min(range.end);
+        let grapheme = &text[i..end];
+        let kind = match grapheme {
+            " " => WrapPieceKind::Space,
+            "\u{AD}" if options.hyphenate => WrapPieceKind::SoftHyphen,
+            _ => WrapPieceKind::Text
+        };
+        let width = if kind == WrapPieceKind::SoftHyphen { 0 } else { grapheme_width(grapheme) };
+        let joins = match (pieces.last(), prev) {
+            (Some(last), Some(prev)) if last.kind == kind && kind != WrapPieceKind::SoftHyphen => {
+                let attached = options.attach_punctuation && (is_closing(grapheme) || is_opening(prev));
+                let after_dash = wordy && matches!(prev, "-" | "/") && !matches!(grapheme, "-" | "/");
+                let breaks = after_dash || grapheme_width(prev) == 2 || width == 2;
+                kind == WrapPieceKind::Space || attached || !breaks
+            },
+            _ => false
+        };
+        if joins {
+            let last = pieces.last_mut().unwrap();
+            last.range.end = end;
+            last.width += width;
+        }
+        else {
+            pieces.push(WrapPiece { range: i..end, width, kind });
+            wordy = false;
+        }
+        wordy |= kind == WrapPieceKind::Text && grapheme != "-" && grapheme != "/";
+        prev = Some(grapheme);
+        i = end;
+    }
+    pieces
+}
+
+
+
+/// Fit `text` into `width` cells (see `text_width`), cutting graphemes off as `policy` says if it is too wide.
+/// Text that fits is returned as it is, without allocating. Cut text is always exactly `width` cells wide: where a wide
+/// grapheme would have been split, it is left out and a space pads the text next to the `…`, or at the end for `Truncation::None`.
+/// The `…` goes on the logical end or start of the text, so text laid out right to left with `text_directed` shows `End`'s on the left.
+///
+/// Example
+/// ```
+/// use terminalgl::{raster, Truncation};
+/// use std::borrow::Cow;
+/// assert_eq!(raster::truncate_to_width("long text", 8, Truncation::End), "long te…");
+/// assert_eq!(raster::truncate_to_width("long text", 8, Truncation::Start), "…ng text");
+/// assert_eq!(raster::truncate_to_width("long text", 7, Truncation::Middle), "lon…ext");
+/// assert_eq!(raster::truncate_to_width("long text", 7, Truncation::None), "long te");
+/// assert!(matches!(raster::truncate_to_width("fits", 8, Truncation::End), Cow::Borrowed("fits")));
+/// assert_eq!(raster::truncate_to_width("漢字漢字", 6, Truncation::End), "漢字… ");
+/// assert_eq!(raster::truncate_to_width("漢字漢字", 6, Truncation::Start), " …漢字");
+/// assert_eq!(raster::truncate_to_width("漢字漢字", 5, Truncation::None), "漢字 ");
+/// assert_eq!(raster::truncate_to_width("hello", 0, Truncation::End), "");
+/// ```
+///
+/// The result is never wider than `width`, and exactly as wide when `text` was wider:
+/// ```
+/// use terminalgl::{raster, Truncation};
+/// use terminalgl::particles::Rng;
+/// let mut rng = Rng::new(3);
+/// let alphabet: Vec<&str> = vec!["a", "b", " ", "漢", "字", "é", "e\u{301}", "👍🏽", "🇳🇴", "\t"];
+/// for _ in 0..2000 {
+///     let text: String = (0..rng.next_u64() % 12).map(|_| alphabet[rng.next_u64() as usize % alphabet.len()]).collect();
+///     let width = (rng.next_u64() % 10) as usize;
+///     for policy in [Truncation::End, Truncation::Start, Truncation::Middle, Truncation::None] {
+///         let fitted = raster::truncate_to_width(&text, width, policy);
+///         assert!(raster::text_width(&fitted) <= width, "{:?} {} {:?}", text, width, policy);
+///         if raster::text_width(&text) >= width {
+///             assert_eq!(raster::text_width(&fitted), width, "{:?} {} {:?}", text, width, policy);
+///         }
+///     }
+/// }
+/// ```
+pub fn truncate_to_width(text: &str, width: usize, policy: Truncation) -> Cow<'_, str> {
+    if text_width(text) <= width {
+        return Cow::Borrowed(text);
+    }
+    // Take graphemes from the front (or back) of `graphemes` while they fit in `budget` cells, and get them with the cells used.
+    fn take<'a>(graphemes: impl Iterator<Item = &'a str>, budget: usize) -> (Vec<&'a str>, usize) {
+        let mut kept = Vec::new();
+        let mut used = 0;
+        for grapheme in graphemes {
+            let w = grapheme_width(grapheme);
+            if used + w > budget {
+                break;
+            }
+            kept.push(grapheme);
+            used += w;
+        }
+        (kept, used)
+    }
+    let all: Vec<&str> = graphemes(text).collect();
+    let room = width.saturating_sub(1);
+    let pad = |used: usize| " ".repeat(width.saturating_sub(used));
+    let fitted = match policy {
+        _ if width == 0 => String::new(),
+        Truncation::End => {
+            let (head, used) = take(all.iter().copied(), room);
+            head.concat() + "…" + &pad(used + 1)
+        },
+        Truncation::Start => {
+            let (mut tail, used) = take(all.iter().rev().copied(), room);
+            tail.reverse();
+            pad(used + 1) + "…" + &tail.concat()
+        },
+        Truncation::Middle => {
+            let (head, head_used) = take(all.iter().copied(), room - room / 2);
+            let (mut tail, tail_used) = take(all[head.len()..].iter().rev().copied(), room - head_used);
+            tail.reverse();
+            head.concat() + "…" + &pad(head_used + tail_used + 1) + &tail.concat()
+        },
+        Truncation::None => {
+            let (head, used) = take(all.iter().copied(), width);
+            head.concat() + &pad(used)
+        }
+    };
+    Cow::Owned(fitted)
+}
+
+
+/// Get one line of `text`, read in `direction`, in the order its characters appear on screen from left to right.
+/// Right-to-left text has its graphemes reversed and its brackets mirrored, so that a Hebrew or Arabic label reads correctly.
+///
+/// With the `bidi` feature, lines are reordered with `bidi::reorder` instead, so numbers and left-to-right words
+/// inside right-to-left text (and the other way round) keep their own order.
+///
+/// Example
+/// ```
+/// use terminalgl::{raster, TextDirection};
+/// assert_eq!(raster::visual_order("abc", TextDirection::Ltr), "abc");
+/// assert_eq!(raster::visual_order("(שלום)", TextDirection::Rtl), "(םולש)");
+/// assert_eq!(raster::visual_order("ל\u{5B9}א", TextDirection::Rtl), "אל\u{5B9}");
+/// ```
+pub fn visual_order(text: &str, direction: TextDirection) -> String {
+    #[cfg(feature = "bidi")]
+    return super::bidi::reorder(text, direction);
+    #[cfg(not(feature = "bidi"))]
+    match direction {
+        TextDirection::Ltr => text.to_string(),
+        TextDirection::Rtl => graphemes(text).collect::<Vec<_>>().into_iter().rev().flat_map(|g| g.chars().map(mirror)).collect()
+    }
+}
+
+
+/// Get the character drawn in place of `c` in right-to-left text, for the pairs that mirror.
+pub(crate) fn mirror(c: char) -> char {
+    match c {
+        '(' => ')',
+        ')' => '(',
+        '[' => ']',
+        ']' => '[',
+        '{' => '}',
+        '}' => '{',
+        '<' => '>',
+        '>' => '<',
+        '«' => '»',
+        '»' => '«',
+        c => c
+    }
+}
+
+
+/// Lay out `text`, sanitized with `chars::sanitize_text` and read in `direction`, on row `y` with alignment `align` at `x`,
+/// and get its `(x, y, c)` cells. Alignment works like `draw::text_aligned` for left-to-right text, and is mirrored
+/// for right-to-left text: `Left` aligned text ends at `x`, `Right` aligned text starts there, and `Center` stays centered.
+///
+/// Example
+/// ```
+/// use terminalgl::{raster, TextAlignment, TextDirection, Truncation};
+/// let row = |cells: Vec<(isize, isize, char)>| (cells[0].0, cells.iter().map(|c| c.2).collect::<String>());
+/// assert_eq!(row(raster::text_directed(10, 0, "שלום", TextAlignment::Left, TextDirection::Rtl)), (6, "םולש".to_string()));
+/// assert_eq!(row(raster::text_directed(10, 0, "שלום", TextAlignment::Right, TextDirection::Rtl)), (10, "םולש".to_string()));
+/// assert_eq!(row(raster::text_directed(10, 0, "שלום", TextAlignment::Center, TextDirection::Rtl)), (8, "םולש".to_string()));
+/// assert_eq!(row(raster::text_directed(10, 0, "abcd", TextAlignment::Right, TextDirection::Ltr)), (6, "abcd".to_string()));
+///
+/// // Truncated text keeps its start, at the right end when read right to left.
+/// let cut = raster::truncate_to_width("שלום עולם", 6, Truncation::End);
+/// assert_eq!(row(raster::text_directed(6, 0, &cut, TextAlignment::Left, TextDirection::Rtl)), (0, "… םולש".to_string()));
+/// ```
+pub fn text_directed(x: isize, y: isize, text: &str, align: TextAlignment, direction: TextDirection) -> Vec<(isize, isize, char)> {
+    let text: String = chars::sanitize_text(text).collect();
+    let visual = visual_order(&text, direction);
+    let len = visual.chars().count() as isize;
+    let align = match (align, direction) {
+        (TextAlignment::Left, TextDirection::Rtl) => TextAlignment::Right,
+        (TextAlignment::Right, TextDirection::Rtl) => TextAlignment::Left,
+        (align, _) => align
+    };
+    let start = match align {
+        TextAlignment::Left => x,
+        TextAlignment::Center => x.saturating_sub(len / 2),
+        TextAlignment::Right => x.saturating_sub(len)
+    };
+    visual.chars().enumerate().map(|(i, c)| (start.saturating_add(i as isize), y, c)).collect()
+}
+
+
+/// Lay out `text` wrapped with `wrap_words` to lines at most `width` cells wide, read in `direction`, starting on row `y`,
+/// and get its `(x, y, c)` cells. Each line is aligned with `align` inside the columns from `x` to `x + width`, mirrored
+/// for right-to-left text like `text_directed`, so right-to-left lines start at the right edge by default.
+///
+/// Example
+/// ```
+/// use terminalgl::{raster, TextAlignment, TextDirection};
+/// let cells = raster::text_wrapped_directed(0, 0, 6, "אב גד הוז", TextAlignment::Left, TextDirection::Rtl);
+/// let rows: Vec<(isize, String)> = (0..2).map(|y| {
+///     let row: Vec<_> = cells.iter().filter(|c| c.1 == y).collect();
+///     (row[0].0, row.iter().map(|c| c.2).collect())
+/// }).collect();
+/// assert_eq!(rows, vec![(1, "דג בא".to_string()), (3, "זוה".to_string())]);
+/// ```
+pub fn text_wrapped_directed(x: isize, y: isize, width: usize, text: &str, align: TextAlignment, direction: TextDirection) -> Vec<(isize, isize, char)> {
+    let anchor = match (align, direction) {
+        (TextAlignment::Left, TextDirection::Ltr) | (TextAlignment::Right, TextDirection::Rtl) => x,
+        (TextAlignment::Center, _) => x.saturating_add((width / 2) as isize),
+        _ => x.saturating_add_unsigned(width)
+    };
+    wrap_words(text, width).iter().enumerate()
+        .flat_map(|(i, line)| text_directed(anchor, y.saturating_add(i as isize), line, align, direction))
+        .collect()
+}
+
+/// Widest a tooltip's text can be before it wraps, in columns.
+pub const TOOLTIP_MAX_WIDTH: usize = 30;
+
+
+/// Lay out a bordered, word-wrapped tooltip for `text` pointing at `(anchor_x, anchor_y)` inside `screen`.
+/// Returns the rectangle the tooltip covers and its `(x, y, c)` cells.
+///
+/// The tooltip goes below and to the right of the anchor, flipping above or to the left when it would run off `screen`,
+/// and clamping to `screen` when it fits neither way. A `▲` or `▼` on the border points at the anchor.
+///
+/// Example
+/// ```
+/// use terminalgl::{raster, Rect};
+/// let screen = Rect::new(0, 0, 20, 10);
+/// let (rect, cells) = raster::tooltip(2, 2, "hi", screen);
+/// assert_eq!(rect, Rect::new(1, 3, 4, 3));
+/// assert!(cells.contains(&(2, 3, '▲')));
+///
+/// // Flipped above and to the left near the bottom right corner
+/// let (rect, cells) = raster::tooltip(18, 9, "hi", screen);
+/// assert_eq!(rect, Rect::new(16, 6, 4, 3));
+/// assert!(cells.contains(&(18, 8, '▼')));
+/// ```
+pub fn tooltip(anchor_x: isize, anchor_y: isize, text: &str, screen: Rect) -> (Rect, Vec<(isize, isize, char)>) {
+    let max_width = TOOLTIP_MAX_WIDTH.min(screen.width.saturating_sub(2)).max(1);
+    let lines = wrap_words(text, max_width);
+    let width = lines.iter().map(|l| l.chars().count()).max().unwrap_or(0) as isize + 2;
+    let height = lines.len() as isize + 2;
+    let (right, bottom) = (screen.x.saturating_add_unsigned(screen.width), screen.y.saturating_add_unsigned(screen.height));
+
+    let mut x = anchor_x.saturating_sub(1);
+    if x.saturating_add(width) > right {
+        x = anchor_x.saturating_sub(width - 2);
+    }
+    x = x.min(right.saturating_sub(width)).max(screen.x);
+    let below = anchor_y.saturating_add(1 + height) <= bottom || anchor_y.saturating_sub(height) < screen.y;
+    let mut y = if below { anchor_y.saturating_add(1) } else { anchor_y.saturating_sub(height) };
+    y = y.min(bottom.saturating_sub(height)).max(screen.y);
+    let rect = Rect::new(x, y, width as usize, height as usize);
+
+    let b = chars::box_drawing(LineStyle::Light);
+    let mut cells = Vec::new();
+    for row in 0..height {
+        for col in 0..width {
+            let c = match (row, col) {
+                (0, 0) => b.tl,
+                (0, c) if c == width - 1 => b.tr,
+                (r, 0) if r == height - 1 => b.bl,
+                (r, c) if r == height - 1 && c == width - 1 => b.br,
+                (0, _) => b.h,
+                (r, _) if r == height - 1 => b.h,
+                (_, 0) => b.v,
+                (_, c) if c == width - 1 => b.v,
+                (r, c) => lines[r as usize - 1].chars().nth(c as usize - 1).unwrap_or(' ')
+            };
+            cells.push((x.saturating_add(col), y.saturating_add(row), c));
+        }
+    }
+    if width > 2 {
+        let pointer_x = anchor_x.clamp(x.saturating_add(1), x.saturating_add(width - 2));
+        if Some(y) == anchor_y.checked_add(1) {
+            cells.push((pointer_x, y, '▲'));
+        }
+        else if y.checked_add(height) == Some(anchor_y) {
+            cells.push((pointer_x, y + height - 1, '▼'));
+        }
+    }
+    (rect, cells)
+}
+
+
+/// Get the cells of a shadow for `text` drawn at `(x, y)`: a copy of it moved by `offset`, as `(x, y, c)`,
+/// leaving out the cells the text itself covers so drawing both never prints a cell twice.
+///
+/// Example
+/// ```
+/// use terminalgl::raster;
+/// assert_eq!(raster::text_shadow(0, 0, "abc", (1, 1)), vec![(1, 1, 'a'), (2, 1, 'b'), (3, 1, 'c')]);
+/// assert_eq!(raster::text_shadow(0, 0, "abc", (1, 0)), vec![(3, 0, 'c')]);
+/// ```
+pub fn text_shadow(x: isize, y: isize, text: &str, offset: (isize, isize)) -> Vec<(isize, isize, char)> {
+    let len = text.chars().count();
+    let covered = |cx: isize, cy: isize| cy == y && cx >= x && cx.abs_diff(x) < len;
+    text.chars().enumerate()
+        .map(|(i, c)| (x.saturating_add(i as isize).saturating_add(offset.0), y.saturating_add(offset.1), c))
+        .filter(|&(cx, cy, _)| !covered(cx, cy))
+        .collect()
+}
+
+
+/// Get the cells of an outline around `text` drawn at `(x, y)`, as `(x, y, c)`: copies of it stamped at the 8 neighboring offsets
+/// in reading order, each cell taking the character of the last copy to cover it.
+/// Each cell appears once, and the cells the text itself covers are left out.
+///
+/// Example
+/// ```
+/// use terminalgl::{assert_frame_eq, raster};
+/// use terminalgl::canvas::Canvas;
+/// let mut canvas = Canvas::new(6, 3);
+/// for (x, y, c) in raster::text_outline(1, 1, "ab") {
+///     canvas.set(x, y, c, "");
+/// }
+/// assert_eq!(raster::text_outline(1, 1, "ab").len(), 10);
+/// assert_frame_eq!(canvas, "\
+/// |aaab  |
+/// |a  b  |
+/// |aaab  |");
+/// ```
+pub fn text_outline(x: isize, y: isize, text: &str) -> Vec<(isize, isize, char)> {
+    let mut cells: Vec<(isize, isize, char)> = Vec::new();
+    for (dy, dx) in [(-1, -1), (-1, 0), (-1, 1), (0, -1), (0, 1), (1, -1), (1, 0), (1, 1)] {
+        for (cx, cy, c) in text_shadow(x, y, text, (dx, dy)) {
+            match cells.iter_mut().find(|cell| (cell.0, cell.1) == (cx, cy)) {
+                Some(cell) => cell.2 = c,
+                None => cells.push((cx, cy, c))
+            }
+        }
+    }
+    cells
+}
+
+
+/// Get the cells of the shadow a panel covering `rect` casts down and to the right: the column just right of it and
+/// the row just below it, each moved one cell along so the shadow starts below its top right and left corners.
+/// Only cells inside `clip` are included.
+///
+/// Example
+/// ```
+/// use terminalgl::{assert_frame_eq, raster, Rect};
+/// use terminalgl::canvas::Canvas;
+/// let mut canvas = Canvas::new(6, 4);
+/// for (x, y) in raster::box_shadow_clipped(Rect::new(0, 0, 4, 2), canvas.rect()) {
+///     canvas.set(x, y, '░', "");
+/// }
+/// assert_frame_eq!(canvas, "\
+/// |      |
+/// |    ░ |
+/// | ░░░░ |
+/// |      |");
+/// assert_eq!(raster::box_shadow_clipped(Rect::new(-5, -5, usize::MAX, 10), Rect::new(0, 0, 80, 24)).len(), 80);
+/// ```
+pub fn box_shadow_clipped(rect: Rect, clip: Rect) -> Vec<(isize, isize)> {
+    if rect.width == 0 || rect.height == 0 {
+        return Vec::new();
+    }
+    let (right, bottom) = (rect.x.saturating_add_unsigned(rect.width), rect.y.saturating_add_unsigned(rect.height));
+    let mut cells = rectangle_clipped(right, rect.y.saturating_add(1), 1, rect.height, true, clip);
+    cells.extend(rectangle_clipped(rect.x.saturating_add(1), bottom, rect.width - 1, 1, true, clip));
+    cells
+}
+
+
+/// Density ramp from empty to full, for shading with characters.
+pub const DENSITY_RAMP: &str = " .:-=+*#%@";
+
+
+/// Get the character of `ramp` for `value` in `0.0..=1.0`, where `0.0` is the first character and `1.0` the last.
+/// Values outside the range are clamped, and an empty ramp gives a space.
+///
+/// Example
+/// ```
+/// use terminalgl::raster;
+/// assert_eq!(raster::ramp_char(raster::DENSITY_RAMP, 0.0), ' ');
+/// assert_eq!(raster::ramp_char(raster::DENSITY_RAMP, 0.5), '+');
+/// assert_eq!(raster::ramp_char(raster::DENSITY_RAMP, 1.5), '@');
+/// ```
+pub fn ramp_char(ramp: &str, value: f64) -> char {
+    let chars: Vec<char> = ramp.chars().collect();
+    if chars.is_empty() {
+        return ' ';
+    }
+    let i = (value.clamp(0.0, 1.0) * chars.len() as f64) as usize;
+    chars[i.min(chars.len() - 1)]
+}
+
+
+/// Get the source indices destination index `i` takes its block from when shrinking `src_len` samples to `dst_len`.
+/// Blocks are at least one sample long, so stretching repeats samples instead of leaving gaps.
+pub(crate) fn block(i: usize, src_len: usize, dst_len: usize) -> Range<usize> {
+    if src_len == 0 || dst_len == 0 {
+        return 0..0;
+    }
+    let edge = |i: usize| (i as u128 * src_len as u128 / dst_len as u128) as usize;
+    let start = edge(i).min(src_len - 1);
+    start..edge(i + 1).clamp(start + 1, src_len)
+}
+
+
+/// Get the source indices covering destination index `i` when stretching `src_len` samples over `dst_len`,
+/// with the fraction of the destination sample each one covers.
+fn box_weights(src_len: usize, dst_len: usize, i: usize) -> Vec<(usize, f64)> {
+    let ratio = src_len as f64 / dst_len as f64;
+    let (start, end) = (i as f64 * ratio, (i + 1) as f64 * ratio);
+    let mut weights = Vec::new();
+    let mut s = start.floor() as usize;
+    while (s as f64) < end && s < src_len {
+        let overlap = end.min(s as f64 + 1.0) - start.max(s as f64);
+        if overlap > 0.0 {
+            weights.push((s, overlap / ratio));
+        }
+        s += 1;
+    }
+    weights
+}
+
+
+/// Resample the row-major buffer `src`, `src_width` samples wide, to `width` by `height` samples by averaging
+/// the area of `src` each new sample covers (a box filter). Works for both shrinking and stretching.
+/// Rows left incomplete at the end of `src` are ignored, and an empty source gives an empty buffer.
+///
+/// Example
+/// ```
+/// use terminalgl::raster;
+/// let src = [0.0, 1.0, 0.0, 1.0,
+///            1.0, 1.0, 0.0, 0.0];
+/// assert_eq!(raster::resample(&src, 4, 2, 1), vec![0.75, 0.25]);
+/// assert_eq!(raster::resample(&[0.0, 1.0], 2, 4, 1), vec![0.0, 0.0, 1.0, 1.0]);
+/// ```
+pub fn resample(src: &[f64], src_width: usize, width: usize, height: usize) -> Vec<f64> {
+    if src_width == 0 || src.len() < src_width || width == 0 || height == 0 {
+        return Vec::new();
+    }
+    let src_height = src.len() / src_width;
+    let columns: Vec<Vec<(usize, f64)>> = (0..width).map(|x| box_weights(src_width, width, x)).collect();
+    let mut out = Vec::with_capacity(width * height);
+    for y in 0..height {
+        let rows = box_weights(src_height, height, y);
+        for column in &columns {
+            let mut total = 0.0;
+            for &(sy, wy) in &rows {
+                for &(sx, wx) in column {
+                    total += src[sy * src_width + sx] * wy * wx;
+                }
+            }
+            out.push(total);
+        }
+    }
+    out
+}
+
+
+/// Convert the row-major luminance buffer `luminance` (`0.0` to `1.0`), `src_width` samples wide,
+/// into `height` rows of `width` characters from `ramp`, resampling it with `resample`.
+///
+/// Example
+/// ```
+/// use terminalgl::raster;
+/// let gradient: Vec<f64> = (0..20).map(|i| i as f64 / 19.0).collect();
+/// assert_eq!(raster::ascii_rows(&gradient, 20, 10, 1, raster::DENSITY_RAMP), vec![" .:-=+*#%@"]);
+/// assert_eq!(raster::ascii_rows(&gradient, 10, 5, 2, "-#"), vec!["-----", "#####"]);
+/// ```
+#[cfg(feature = "image")]
+pub fn ascii_rows(luminance: &[f64], src_width: usize, width: usize, height: usize, ramp: &str) -> Vec<String> {
+    let samples = resample(luminance, src_width, width, height);
+    samples.chunks(width.max(1)).map(|row| row.iter().map(|&v| ramp_char(ramp, v)).collect()).collect()
+}
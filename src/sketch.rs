@@ -0,0 +1,161 @@
+// Chainable drawing into a canvas that is printed all at once.
+use super::canvas::Canvas;
+use super::raster;
+use std::io::{self, Write};
+
+
+/// A canvas with a current style, drawn to with chainable calls and printed with `present`.
+/// Nothing touches the terminal until `present`, and `present_to` writes to any other target, such as a `Vec<u8>` in tests.
+///
+/// The style set with `style` applies to every call after it until it is changed,
+/// and `with_style` applies a style to the calls inside a closure only.
+///
+/// Example
+/// ```
+/// use terminalgl::assert_frame_eq;
+/// use terminalgl::sketch::Sketch;
+/// use terminalgl::drawc;
+/// let mut sketch = Sketch::with_size(12, 4);
+/// sketch.rect(0, 0, 12, 4, '#')
+///     .style(drawc::RED)
+///     .text(2, 1, "hi")
+///     .with_style(drawc::GREEN, |f| { f.pixel(4, 1, '!'); })
+///     .line(1, 2, 10, 2, '-');
+/// assert_eq!(sketch.to_text(), "############\n# hi!      #\n#----------#\n############");
+/// assert_frame_eq!(sketch.canvas(), r#"|############|
+/// |# hi!      #|
+/// |#----------#|
+/// |############|
+///
+/// |............|
+/// |..AAB.......|
+/// |.AAAAAAAAAA.|
+/// |............|
+/// A "\u{1b}[31m"
+/// B "\u{1b}[32m""#);
+///
+/// let mut out = Vec::new();
+/// sketch.present_to(&mut out).unwrap();
+/// assert_eq!(String::from_utf8(out).unwrap(), sketch.canvas().to_ansi());
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Sketch {
+    canvas: Canvas,
+    ccode: String
+}
+
+impl Sketch {
+    /// Create a blank sketch the size of the terminal.
+    pub fn new() -> Sketch {
+        Sketch::from_canvas(Canvas::screen())
+    }
+
+    /// Create a blank sketch `width` by `height` cells in size.
+    pub fn with_size(width: usize, height: usize) -> Sketch {
+        Sketch::from_canvas(Canvas::new(width, height))
+    }
+
+    /// Create a sketch that draws over `canvas`.
+    pub fn from_canvas(canvas: Canvas) -> Sketch {
+        Sketch { canvas, ccode: String::new() }
+    }
+
+    /// Get the sketch's cells.
+    pub fn canvas(&self) -> &Canvas {
+        &self.canvas
+    }
+
+    /// Take the sketch's cells, e.g. to keep them for `Canvas::present_diff`.
+    pub fn into_canvas(self) -> Canvas {
+        self.canvas
+    }
+
+    /// Get the current style.
+    pub fn current_style(&self) -> &str {
+        &self.ccode
+    }
+
+    /// Draw the calls after this one with `ccode`, until the style is changed again. An empty `ccode` draws with the terminal's default colors.
+    pub fn style(&mut self, ccode: &str) -> &mut Sketch {
+        self.ccode = ccode.to_string();
+        self
+    }
+
+    /// Draw the calls made by `draw` with `ccode`, then go back to the style from before.
+    pub fn with_style(&mut self, ccode: &str, draw: impl FnOnce(&mut Sketch)) -> &mut Sketch {
+        let previous = std::mem::replace(&mut self.ccode, ccode.to_string());
+        draw(self);
+        self.ccode = previous;
+        self
+    }
+
+    /// Draw `c` at `(x, y)`. Cells outside the sketch are ignored.
+    pub fn pixel(&mut self, x: isize, y: isize, c: char) -> &mut Sketch {
+        self.canvas.set(x, y, c, &self.ccode);
+        self
+    }
+
+    /// Draw the outline of a `width` by `height` rectangle of `c` at `(x, y)`.
+    pub fn rect(&mut self, x: isize, y: isize, width: usize, height: usize, c: char) -> &mut Sketch {
+        self.cells(raster::rectangle_clipped(x, y, width, height, false, self.canvas.rect()), c)
+    }
+
+    /// Draw a filled `width` by `height` rectangle of `c` at `(x, y)`.
+    pub fn fill_rect(&mut self, x: isize, y: isize, width: usize, height: usize, c: char) -> &mut Sketch {
+        self.cells(raster::rectangle_clipped(x, y, width, height, true, self.canvas.rect()), c)
+    }
+
+    /// Draw a line of `c` from `(x1, y1)` to `(x2, y2)`.
+    pub fn line(&mut self, x1: isize, y1: isize, x2: isize, y2: isize, c: char) -> &mut Sketch {
+        self.cells(raster::line_clipped(x1, y1, x2, y2, self.canvas.rect()), c)
+    }
+
+    /// Draw an ellipse of `c` centered at `(h, k)` with horizontal radius `a` and vertical radius `b`, outlined or filled.
+    pub fn ellipse(&mut self, h: isize, k: isize, a: usize, b: usize, c: char, fill: bool) -> &mut Sketch {
+        self.cells(raster::ellipse_clipped(h, k, a, b, fill, self.canvas.rect()), c)
+    }
+
+    /// Draw `text` starting at `(x, y)`.
+    pub fn text(&mut self, x: isize, y: isize, text: &str) -> &mut Sketch {
+        self.canvas.text(x, y, text, &self.ccode);
+        self
+    }
+
+    /// Reset every cell to a blank space. The current style is kept.
+    pub fn clear(&mut self) -> &mut Sketch {
+        self.canvas.clear();
+        self
+    }
+
+    fn cells(&mut self, cells: Vec<(isize, isize)>, c: char) -> &mut Sketch {
+        for (x, y) in cells {
+            self.canvas.set(x, y, c, &self.ccode);
+        }
+        self
+    }
+
+    /// Get the sketch's characters without colors, one line per row.
+    pub fn to_text(&self) -> String {
+        let rows: Vec<String> = (0..self.canvas.height() as isize).map(|y| {
+            (0..self.canvas.width() as isize).map(|x| self.canvas.get(x, y).unwrap().c).collect()
+        }).collect();
+        rows.join("\n")
+    }
+
+    /// Print the sketch to the terminal, like `Canvas::present`, and flush it.
+    pub fn present(&self) -> io::Result<()> {
+        self.present_to(&mut io::stdout().lock())
+    }
+
+    /// Write what `present` prints to `out` instead of the terminal, and flush it.
+    pub fn present_to(&self, out: &mut impl Write) -> io::Result<()> {
+        out.write_all(self.canvas.to_ansi().as_bytes())?;
+        out.flush()
+    }
+}
+
+impl Default for Sketch {
+    fn default() -> Sketch {
+        Sketch::new()
+    }
+}
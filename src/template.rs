@@ -0,0 +1,221 @@
+// Hand-drawn text art with named fields filled in at runtime.
+use super::{draw, drawc, raster, TextAlignment, Truncation};
+use std::fmt;
+
+
+/// A named field of a `Template`, `width` columns wide at `(x, y)` relative to the template's top left corner.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Field {
+    pub name: String,
+    pub x: usize,
+    pub y: usize,
+    pub width: usize,
+    pub align: TextAlignment
+}
+
+
+/// Error from `Template::parse`, at the 1-based `line` and `column` of the template text.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseError {
+    pub line: usize,
+    pub column: usize,
+    pub message: String
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "line {}, column {}: {}", self.line, self.column, self.message)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+
+/// Multi-line text art with named fields, such as a HUD frame.
+///
+/// Fields are written `{name}` or `{name:spec}`, where `spec` is an optional alignment (`<` left, `^` center, `>` right)
+/// followed by an optional width. A field without a width is as wide as its own `{...}` text, so the art around it stays
+/// where it was drawn; the art after a field with a width moves to make room for exactly that many columns.
+/// Values are aligned within their field and cut off when too long. Write `{{` and `}}` for literal braces.
+///
+/// Example
+/// ```
+/// use terminalgl::template::Template;
+/// let hud = Template::parse("+--------------+\n| HP {hp:>3}/{max:<3} |\n| {name:^12} |\n+--------------+").unwrap();
+/// assert_eq!(hud.lines(&[("hp", "42"), ("max", "100"), ("name", "Adventurer!!!")]), vec![
+///     "+--------------+",
+///     "| HP  42/100 |",
+///     "| Adventurer!! |",
+///     "+--------------+",
+/// ]);
+/// assert_eq!(hud.field("hp").unwrap().x, 5);
+///
+/// let err = Template::parse("ok\n  {score:>x}").unwrap_err();
+/// assert_eq!((err.line, err.column), (2, 9));
+/// assert_eq!(Template::parse("{open").unwrap_err().to_string(), "line 1, column 1: unclosed `{`");
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Template {
+    lines: Vec<Vec<Segment>>,
+    fields: Vec<Field>
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Segment {
+    Text(String),
+    Field(usize)
+}
+
+impl Template {
+    /// Parse `text` into a template, or get the position of the first malformed field.
+    pub fn parse(text: &str) -> Result<Template, ParseError> {
+        let mut lines = Vec::new();
+        let mut fields = Vec::new();
+        for (row, line) in text.lines().enumerate() {
+            let error = |column: usize, message: &str| ParseError { line: row + 1, column: column + 1, message: message.to_string() };
+            let chars: Vec<char> = line.chars().collect();
+            let mut segments = Vec::new();
+            let mut literal = String::new();
+            let mut x = 0;
+            let mut i = 0;
+            while i < chars.len() {
+                match chars[i] {
+                    '{' if chars.get(i + 1) == Some(&'{') => {
+                        literal.push('{');
+                        i += 2;
+                    },
+                    '}' if chars.get(i + 1) == Some(&'}') => {
+                        literal.push('}');
+                        i += 2;
+                    },
+                    '}' => return Err(error(i, "unmatched `}`")),
+                    '{' => {
+                        let Some(len) = chars[i..].iter().position(|&c| c == '}') else {
+                            return Err(error(i, "unclosed `{`"));
+                        };
+                        let inner: String = chars[i + 1..i + len].iter().collect();
+                        if let Some(j) = inner.find('{') {
+                            return Err(error(i + 1 + inner[..j].chars().count(), "unexpected `{` inside a field"));
+                        }
+                        let (name, spec) = inner.split_once(':').unwrap_or((&inner, ""));
+                        if name.trim().is_empty() {
+                            return Err(error(i, "field has no name"));
+                        }
+                        let (align, width) = parse_spec(spec).ok_or_else(|| {
+                            error(i + 1 + name.chars().count(), &format!("invalid field spec `{}`", spec))
+                        })?;
+                        let width = width.unwrap_or(len + 1);
+                        if !literal.is_empty() {
+                            x += literal.chars().count();
+                            segments.push(Segment::Text(std::mem::take(&mut literal)));
+                        }
+                        segments.push(Segment::Field(fields.len()));
+                        fields.push(Field { name: name.trim().to_string(), x, y: row, width, align });
+                        x += width;
+                        i += len + 1;
+                    },
+                    c => {
+                        literal.push(c);
+                        i += 1;
+                    }
+                }
+            }
+            if !literal.is_empty() {
+                segments.push(Segment::Text(literal));
+            }
+            lines.push(segments);
+        }
+        Ok(Template { lines, fields })
+    }
+
+    /// Get the template's fields, in the order they appear.
+    pub fn fields(&self) -> &[Field] {
+        &self.fields
+    }
+
+    /// Get the first field named `name`.
+    pub fn field(&self, name: &str) -> Option<&Field> {
+        self.fields.iter().find(|f| f.name == name)
+    }
+
+    /// Fill in the fields with `values`, given as `(name, value)` pairs, and get the resulting lines.
+    /// Fields without a value are left blank.
+    pub fn lines(&self, values: &[(&str, &str)]) -> Vec<String> {
+        self.lines.iter().map(|segments| {
+            segments.iter().map(|segment| match segment {
+                Segment::Text(text) => text.clone(),
+                Segment::Field(i) => {
+                    let field = &self.fields[*i];
+                    let value = values.iter().find(|(name, _)| *name == field.name).map_or("", |(_, v)| v);
+                    fit(value, field.width, field.align)
+                }
+            }).collect()
+        }).collect()
+    }
+
+    /// Draw the template with its top left corner at `(x, y)`, filling in the fields with `values` like `lines`.
+    ///
+    /// Example
+    /// ```
+    /// use terminalgl::template::Template;
+    /// let hud = Template::parse("[ score: {score:>6} ]").unwrap();
+    /// hud.render(1, 1, &[("score", "1200")]);
+    /// ```
+    pub fn render(&self, x: isize, y: isize, values: &[(&str, &str)]) {
+        for (i, line) in self.lines(values).iter().enumerate() {
+            draw::text(x, y.saturating_add(i as isize), line);
+        }
+    }
+
+    /// Draw the template like `render`, with the art in `ccode` and the filled in fields in `field_ccode`.
+    ///
+    /// Example
+    /// ```
+    /// use terminalgl::template::Template;
+    /// use terminalgl::drawc;
+    /// let hud = Template::parse("[ score: {score:>6} ]").unwrap();
+    /// hud.render_colored(1, 1, &[("score", "1200")], drawc::WHITE, drawc::YELLOW);
+    /// ```
+    pub fn render_colored(&self, x: isize, y: isize, values: &[(&str, &str)], ccode: &str, field_ccode: &str) {
+        for (i, line) in self.lines(values).iter().enumerate() {
+            drawc::text(x, y.saturating_add(i as isize), line, ccode);
+        }
+        for field in &self.fields {
+            let value = values.iter().find(|(name, _)| *name == field.name).map_or("", |(_, v)| v);
+            let (fx, fy) = (x.saturating_add(field.x as isize), y.saturating_add(field.y as isize));
+            drawc::text(fx, fy, &fit(value, field.width, field.align), field_ccode);
+        }
+        print!("{}", drawc::RESET);
+    }
+}
+
+
+/// Parse a field spec of an optional alignment and an optional width, or get `None` if it is malformed.
+fn parse_spec(spec: &str) -> Option<(TextAlignment, Option<usize>)> {
+    let (align, width) = match spec.chars().next() {
+        Some('<') => (TextAlignment::Left, &spec[1..]),
+        Some('^') => (TextAlignment::Center, &spec[1..]),
+        Some('>') => (TextAlignment::Right, &spec[1..]),
+        _ => (TextAlignment::Left, spec)
+    };
+    if width.is_empty() {
+        return Some((align, None));
+    }
+    match width.parse::<usize>() {
+        Ok(w) if w > 0 => Some((align, Some(w))),
+        _ => None
+    }
+}
+
+
+/// Pad or cut `value` to exactly `width` cells with alignment `align`.
+fn fit(value: &str, width: usize, align: TextAlignment) -> String {
+    let value = raster::truncate_to_width(value, width, Truncation::None);
+    let pad = width - raster::text_width(&value);
+    let (left, right) = match align {
+        TextAlignment::Left => (0, pad),
+        TextAlignment::Center => (pad / 2, pad - pad / 2),
+        TextAlignment::Right => (pad, 0)
+    };
+    format!("{}{}{}", " ".repeat(left), value, " ".repeat(right))
+}
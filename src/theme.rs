@@ -0,0 +1,280 @@
+// Color themes for the built-in widgets, set once for the whole program and switchable while it runs.
+use super::drawc::{self, ColorKind, NamedColor};
+use std::fmt;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Mutex, PoisonError};
+
+
+/// The color codes the built-in widgets draw each part of themselves with. Every slot is a whole color code, background
+/// included, so widgets can draw any slot over any other. An empty slot draws with the terminal's default colors.
+///
+/// Widgets take their colors from the current theme (see `set_theme`) wherever they are given an empty color code,
+/// and use a color code they are given instead, so a single call can still stand out.
+///
+/// Example
+/// ```
+/// use terminalgl::theme::Theme;
+/// let dark = Theme::dark();
+/// assert!(!dark.text.is_empty());
+/// assert_eq!(Theme::default(), Theme::plain());
+/// assert_eq!(Theme::plain().selection, "\x1b[7m");
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Theme {
+    /// Behind everything a widget draws, such as the inside of dialogs and toasts.
+    pub background: String,
+    /// Body text.
+    pub text: String,
+    /// Titles of dialogs and panels.
+    pub title: String,
+    /// Borders and frames.
+    pub border: String,
+    /// The focused or selected item.
+    pub selection: String,
+    /// Items that can't be used, and hints.
+    pub disabled: String,
+    /// Things to draw the eye to, like key chords and selection outlines.
+    pub accent: String,
+    /// Shadows under raised things.
+    pub shadow: String,
+    /// Errors and warnings.
+    pub error: String
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Theme::plain()
+    }
+}
+
+/// Get a color code setting the foreground to `fg` over background `bg`.
+fn on(fg: (u8, u8, u8), bg: (u8, u8, u8)) -> String {
+    drawc::rgb_to_ccode(fg, ColorKind::Fg) + &drawc::rgb_to_ccode(bg, ColorKind::Bg)
+}
+
+impl Theme {
+    /// The terminal's default colors for everything, with the selection in reverse video. This is the theme until `set_theme` is called.
+    pub fn plain() -> Theme {
+        Theme {
+            background: String::new(),
+            text: String::new(),
+            title: String::new(),
+            border: String::new(),
+            selection: "\x1b[7m".to_string(),
+            disabled: String::new(),
+            accent: String::new(),
+            shadow: String::new(),
+            error: String::new()
+        }
+    }
+
+    /// Light text on a dark blue-gray background.
+    pub fn dark() -> Theme {
+        let bg = (30, 32, 40);
+        Theme {
+            background: on((210, 212, 220), bg),
+            text: on((210, 212, 220), bg),
+            title: on((255, 255, 255), bg),
+            border: on((100, 106, 130), bg),
+            selection: on((20, 22, 28), (120, 170, 255)),
+            disabled: on((100, 104, 116), bg),
+            accent: on((120, 170, 255), bg),
+            shadow: on((10, 10, 14), (10, 10, 14)),
+            error: on((255, 100, 100), bg)
+        }
+    }
+
+    /// Dark text on an off-white background.
+    pub fn light() -> Theme {
+        let bg = (240, 240, 235);
+        Theme {
+            background: on((40, 40, 48), bg),
+            text: on((40, 40, 48), bg),
+            title: on((0, 0, 0), bg),
+            border: on((150, 150, 160), bg),
+            selection: on((255, 255, 255), (40, 100, 200)),
+            disabled: on((160, 160, 165), bg),
+            accent: on((30, 90, 190), bg),
+            shadow: on((150, 150, 150), (150, 150, 150)),
+            error: on((200, 30, 30), bg)
+        }
+    }
+
+    /// Get the slot called `name`, as it is named in a theme file (see `parse`).
+    pub fn slot(&self, name: &str) -> Option<&str> {
+        let slot = match name {
+            "background" => &self.background,
+            "text" => &self.text,
+            "title" => &self.title,
+            "border" => &self.border,
+            "selection" => &self.selection,
+            "disabled" => &self.disabled,
+            "accent" => &self.accent,
+            "shadow" => &self.shadow,
+            "error" => &self.error,
+            _ => return None
+        };
+        Some(slot)
+    }
+
+    fn slot_mut(&mut self, name: &str) -> Option<&mut String> {
+        let slot = match name {
+            "background" => &mut self.background,
+            "text" => &mut self.text,
+            "title" => &mut self.title,
+            "border" => &mut self.border,
+            "selection" => &mut self.selection,
+            "disabled" => &mut self.disabled,
+            "accent" => &mut self.accent,
+            "shadow" => &mut self.shadow,
+            "error" => &mut self.error,
+            _ => return None
+        };
+        Some(slot)
+    }
+
+    /// Read a theme from the text of a theme file, starting from `plain` for any slot it leaves out.
+    /// Each line sets a slot to a foreground color, optionally followed by `on` and a background color, as in `text = white on #1e2028`.
+    /// Colors are `default`, the name of a `NamedColor` (see `NamedColor::name`, with `_` or `-` for spaces),
+    /// or `#rrggbb`. Blank lines and lines starting with `#` are skipped.
+    ///
+    /// Example
+    /// ```
+    /// use terminalgl::theme::Theme;
+    /// use terminalgl::drawc;
+    /// let theme = Theme::parse("
+    ///     ## Amber on black
+    ///     text = #ffb000 on black
+    ///     selection = black on bright_yellow
+    ///     error = bright red
+    /// ").unwrap();
+    /// assert_eq!(theme.text, "\x1b[38;2;255;176;0m\x1b[40m");
+    /// assert_eq!(theme.selection, drawc::BLACK.to_string() + drawc::BRIGHT_YELLOW_BG);
+    /// assert_eq!(theme.error, drawc::BRIGHT_RED);
+    /// assert_eq!(theme.border, "");
+    ///
+    /// let err = Theme::parse("text = white\nborders = blue").unwrap_err();
+    /// assert_eq!(err.to_string(), "line 2: unknown slot \"borders\"");
+    /// assert_eq!(Theme::parse("text = #12345").unwrap_err().line, 1);
+    /// ```
+    pub fn parse(text: &str) -> Result<Theme, ThemeError> {
+        let mut theme = Theme::plain();
+        for (i, line) in text.lines().enumerate() {
+            let error = |message: String| ThemeError { line: i + 1, message };
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let Some((name, value)) = line.split_once('=') else {
+                return Err(error(format!("expected `slot = color`, found {:?}", line)));
+            };
+            let name = name.trim();
+            let Some(slot) = theme.slot_mut(name) else {
+                return Err(error(format!("unknown slot {:?}", name)));
+            };
+            let (fg, bg) = match value.split_once(" on ") {
+                Some((fg, bg)) => (fg, Some(bg)),
+                None => (value, None)
+            };
+            let fg = parse_color(fg, ColorKind::Fg).ok_or_else(|| error(format!("unknown color {:?}", fg.trim())))?;
+            let bg = match bg {
+                Some(bg) => parse_color(bg, ColorKind::Bg).ok_or_else(|| error(format!("unknown color {:?}", bg.trim())))?,
+                None => String::new()
+            };
+            *slot = fg + &bg;
+        }
+        Ok(theme)
+    }
+}
+
+
+/// Get the color code for color `text` from a theme file, as a foreground or background.
+fn parse_color(text: &str, kind: ColorKind) -> Option<String> {
+    let text = text.trim();
+    if text == "default" {
+        return Some(String::new());
+    }
+    if let Some(hex) = text.strip_prefix('#') {
+        if hex.len() != 6 || !hex.is_ascii() {
+            return None;
+        }
+        let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).ok();
+        return Some(drawc::rgb_to_ccode((channel(0)?, channel(2)?, channel(4)?), kind));
+    }
+    let name = text.replace(['_', '-'], " ");
+    let named = NamedColor::ALL.into_iter().find(|named| named.name() == name)?;
+    Some(match kind {
+        ColorKind::Fg => named.fg_code(),
+        ColorKind::Bg => named.bg_code()
+    }.to_string())
+}
+
+
+/// Error for a line of a theme file that `Theme::parse` couldn't read, numbered from 1.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ThemeError {
+    pub line: usize,
+    pub message: String
+}
+
+impl fmt::Display for ThemeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl std::error::Error for ThemeError {}
+
+
+/// The current theme, or `None` for `Theme::plain`.
+static THEME: Mutex<Option<Theme>> = Mutex::new(None);
+
+/// How many times the theme has been set, so widgets kept between frames know to redraw.
+static GENERATION: AtomicU64 = AtomicU64::new(0);
+
+
+/// Set the theme every built-in widget draws with from now on. Widgets kept between frames (see `ui::Widget`) become dirty,
+/// so they are redrawn in the new theme.
+///
+/// Example
+/// ```
+/// # #[cfg(feature = "widgets")] {
+/// use terminalgl::theme::{self, Theme};
+/// use terminalgl::ui::{TextReveal, Widget};
+/// use terminalgl::canvas::Canvas;
+/// let mut reveal = TextReveal::new("hi");
+/// reveal.skip();
+/// let mut canvas = Canvas::new(2, 1);
+/// let rect = canvas.rect();
+/// reveal.render_if_dirty(&mut canvas, rect);
+/// assert_eq!(canvas.ccode_at(0, 0), Some(""));
+///
+/// theme::set_theme(Theme::dark());
+/// assert!(reveal.render_if_dirty(&mut canvas, rect));
+/// assert_eq!(canvas.ccode_at(0, 0), Some(Theme::dark().text.as_str()));
+/// theme::set_theme(Theme::plain());
+/// # }
+/// ```
+pub fn set_theme(theme: Theme) {
+    *THEME.lock().unwrap_or_else(PoisonError::into_inner) = Some(theme);
+    GENERATION.fetch_add(1, Ordering::Relaxed);
+}
+
+
+/// Get the theme the built-in widgets draw with.
+pub fn current() -> Theme {
+    THEME.lock().unwrap_or_else(PoisonError::into_inner).clone().unwrap_or_default()
+}
+
+
+/// Get a number that changes every time the theme is set.
+pub fn generation() -> u64 {
+    GENERATION.load(Ordering::Relaxed)
+}
+
+
+/// Get `ccode` if it is set, and otherwise `slot`, for a widget given an empty color code to draw with the theme.
+#[cfg(any(feature = "widgets", all(feature = "input", unix)))]
+pub(crate) fn pick<'a>(ccode: &'a str, slot: &'a str) -> &'a str {
+    if ccode.is_empty() { slot } else { ccode }
+}
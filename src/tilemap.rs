@@ -0,0 +1,213 @@
+// Grids of tiles drawn through a camera.
+use super::canvas::{Canvas, Cell, Tally};
+use super::{raster, Rect};
+
+
+/// Tile id that is never drawn, letting lower layers (or the canvas) show through.
+pub const EMPTY: u16 = 0;
+
+
+/// A grid of tile ids in one or more layers, with a registry mapping ids to the cell they are drawn as.
+/// Layers are drawn bottom to top, so a tile in a higher layer covers the ones below it unless it is `EMPTY`.
+///
+/// Example
+/// ```
+/// use terminalgl::canvas::{Canvas, Cell};
+/// use terminalgl::tilemap::TileMap;
+/// use terminalgl::{drawc, Rect};
+/// const FLOOR: u16 = 1;
+/// const WALL: u16 = 2;
+/// const PLAYER: u16 = 3;
+///
+/// let mut map = TileMap::new(8, 4, 2);
+/// map.define(FLOOR, '.', drawc::BRIGHT_BLACK);
+/// map.define(WALL, '#', drawc::WHITE);
+/// map.define(PLAYER, '@', drawc::YELLOW);
+/// map.fill(0, FLOOR);
+/// map.set(0, 3, 0, WALL);
+/// map.set(1, 2, 1, PLAYER);
+///
+/// let mut canvas = Canvas::new(4, 3);
+/// map.render(&mut canvas, Rect::new(0, 0, 4, 3), 1, -1);
+/// assert_eq!(canvas.get(0, 0).unwrap().c, ' ');  // Above the map
+/// assert_eq!(canvas.get(2, 1).unwrap().c, '#');
+/// assert_eq!(canvas.get(1, 2).unwrap().c, '@');
+/// assert_eq!(canvas.get(0, 2).unwrap().c, '.');
+///
+/// map.out_of_bounds = Some(Cell::new('~', drawc::BLUE));
+/// map.render(&mut canvas, Rect::new(0, 0, 4, 3), isize::MAX - 1, 0);
+/// assert_eq!(canvas.get(3, 2).unwrap().c, '~');
+/// ```
+#[derive(Clone, Debug)]
+pub struct TileMap {
+    width: usize,
+    height: usize,
+    layers: Vec<Vec<u16>>,
+    tiles: Vec<Option<Cell>>,
+    /// Cell drawn where the camera looks past the edge of the map, or `None` to leave those cells untouched.
+    pub out_of_bounds: Option<Cell>
+}
+
+impl TileMap {
+    /// Create a map `width` by `height` tiles in size with `layers` layers, all `EMPTY`.
+    pub fn new(width: usize, height: usize, layers: usize) -> TileMap {
+        TileMap {
+            width,
+            height,
+            layers: vec![vec![EMPTY; width * height]; layers.max(1)],
+            tiles: Vec::new(),
+            out_of_bounds: Some(Cell::default())
+        }
+    }
+
+    /// Get the size of the map in tiles as `(width, height)`.
+    pub fn size(&self) -> (usize, usize) {
+        (self.width, self.height)
+    }
+
+    /// Register tile `id` to be drawn as `c` with `ccode`. Defining `EMPTY` has no effect.
+    pub fn define(&mut self, id: u16, c: char, ccode: &str) {
+        if id == EMPTY {
+            return;
+        }
+        let id = id as usize;
+        if self.tiles.len() <= id {
+            self.tiles.resize(id + 1, None);
+        }
+        self.tiles[id] = Some(Cell::new(c, ccode));
+    }
+
+    /// Get the cell tile `id` is drawn as, if it has been defined.
+    pub fn tile(&self, id: u16) -> Option<&Cell> {
+        self.tiles.get(id as usize).and_then(|t| t.as_ref())
+    }
+
+    fn index(&self, x: isize, y: isize) -> Option<usize> {
+        if x >= 0 && y >= 0 && (x as usize) < self.width && (y as usize) < self.height {
+            Some(y as usize * self.width + x as usize)
+        }
+        else {
+            None
+        }
+    }
+
+    /// Get the tile id at `(x, y)` in `layer`, or `None` if it is outside the map.
+    pub fn get(&self, layer: usize, x: isize, y: isize) -> Option<u16> {
+        let i = self.index(x, y)?;
+        self.layers.get(layer).map(|l| l[i])
+    }
+
+    /// Set the tile at `(x, y)` in `layer` to `id`. Tiles outside the map are ignored.
+    pub fn set(&mut self, layer: usize, x: isize, y: isize, id: u16) {
+        if let (Some(i), true) = (self.index(x, y), layer < self.layers.len()) {
+            self.layers[layer][i] = id;
+        }
+    }
+
+    /// Set every tile in `layer` to `id`.
+    pub fn fill(&mut self, layer: usize, id: u16) {
+        if let Some(l) = self.layers.get_mut(layer) {
+            l.fill(id);
+        }
+    }
+
+    /// Get the cell drawn for map position `(x, y)`: the topmost defined, non-`EMPTY` tile,
+    /// or `out_of_bounds` outside the map.
+    pub fn cell_at(&self, x: isize, y: isize) -> Option<&Cell> {
+        let Some(i) = self.index(x, y) else {
+            return self.out_of_bounds.as_ref();
+        };
+        self.top_tile(i).map(|(_, cell)| cell)
+    }
+
+    /// Get the id and cell of the topmost defined, non-`EMPTY` tile at index `i`.
+    fn top_tile(&self, i: usize) -> Option<(u16, &Cell)> {
+        self.layers.iter().rev().find_map(|l| self.tile(l[i]).map(|cell| (l[i], cell)))
+    }
+
+    /// Draw the part of the map seen by a camera whose top left corner is at map position `(camera_x, camera_y)`
+    /// into `dest` on `canvas`. Only cells inside `dest` are touched.
+    pub fn render(&self, canvas: &mut Canvas, dest: Rect, camera_x: isize, camera_y: isize) {
+        let dest = dest.intersect(canvas.rect());
+        for y in dest.y..dest.y + dest.height as isize {
+            for x in dest.x..dest.x + dest.width as isize {
+                // Positions past the edge of `isize` are outside the map like any other.
+                let cell = match (camera_x.checked_add(x - dest.x), camera_y.checked_add(y - dest.y)) {
+                    (Some(map_x), Some(map_y)) => self.cell_at(map_x, map_y),
+                    _ => self.out_of_bounds.as_ref()
+                };
+                if let Some(cell) = cell {
+                    canvas.put(x, y, cell.clone());
+                }
+            }
+        }
+    }
+    /// Draw the whole map shrunk to fit `dest` on `canvas`, for a minimap. Each cell of `dest` covers a block of the map
+    /// and gets the most common character drawn there (see `cell_at`), colored with the average of `color_for_tile`
+    /// over the ids of those tiles. Blocks with no tiles and cells outside `dest` are left untouched.
+    /// Shrinking a 512 by 512 map to 32 by 16 cells visits each tile once, so it is cheap enough to do every frame.
+    ///
+    /// Example
+    /// ```
+    /// use terminalgl::canvas::Canvas;
+    /// use terminalgl::tilemap::TileMap;
+    /// use terminalgl::{drawc, Rect};
+    /// const WATER: u16 = 1;
+    /// const GRASS: u16 = 2;
+    /// let mut map = TileMap::new(4, 4, 1);
+    /// map.define(WATER, '~', drawc::BLUE);
+    /// map.define(GRASS, '"', drawc::GREEN);
+    /// map.fill(0, WATER);
+    /// for (x, y) in [(2, 0), (3, 0), (3, 1)] {
+    ///     map.set(0, x, y, GRASS);
+    /// }
+    /// let color = |id| if id == WATER { (0, 0, 200) } else { (0, 200, 0) };
+    /// let mut canvas = Canvas::new(2, 2);
+    /// map.render_minimap(&mut canvas, Rect::new(0, 0, 2, 2), color);
+    /// assert_eq!(canvas.get(1, 0).unwrap().c, '"');
+    /// assert_eq!(canvas.ccode_at(1, 0), Some("\x1b[38;2;0;150;50m"));
+    /// assert_eq!(canvas.get(0, 1).unwrap().c, '~');
+    /// ```
+    pub fn render_minimap(&self, canvas: &mut Canvas, dest: Rect, color_for_tile: impl Fn(u16) -> (u8, u8, u8)) {
+        let area = dest.intersect(canvas.rect());
+        for y in area.y..area.y + area.height as isize {
+            let rows = raster::block(y.abs_diff(dest.y), self.height, dest.height);
+            for x in area.x..area.x + area.width as isize {
+                let columns = raster::block(x.abs_diff(dest.x), self.width, dest.width);
+                let mut tally = Tally::default();
+                for i in rows.clone().flat_map(|row| columns.clone().map(move |column| row * self.width + column)) {
+                    if let Some((id, cell)) = self.top_tile(i) {
+                        tally.add(cell.c, color_for_tile(id), None);
+                    }
+                }
+                if let Some(cell) = tally.cell() {
+                    canvas.put(x, y, cell);
+                }
+            }
+        }
+    }
+
+    /// Get the part of `dest` that map area `area` lands on when the map is drawn there by `render_minimap`,
+    /// at least one cell across, for outlining what the camera sees.
+    ///
+    /// Example
+    /// ```
+    /// use terminalgl::tilemap::TileMap;
+    /// use terminalgl::Rect;
+    /// let map = TileMap::new(512, 512, 1);
+    /// assert_eq!(map.minimap_rect(Rect::new(40, 2, 32, 16), Rect::new(64, 256, 80, 24)), Rect::new(44, 10, 5, 1));
+    /// assert_eq!(map.minimap_rect(Rect::new(40, 2, 32, 16), Rect::new(-100, -100, 1000, 1000)), Rect::new(40, 2, 32, 16));
+    /// ```
+    pub fn minimap_rect(&self, dest: Rect, area: Rect) -> Rect {
+        // Map edges scale to the minimap rounding outward, clamped to the minimap.
+        let scale = |start: isize, len: usize, map: usize, dest: usize| {
+            let (map, dest) = (map.max(1) as i128, dest as i128);
+            let first = (start as i128 * dest).div_euclid(map).clamp(0, dest);
+            let end = ((start as i128 + len as i128) * dest + map - 1).div_euclid(map).clamp(0, dest);
+            (first.min(dest - 1).max(0), (end - first).max(1))
+        };
+        let (x, width) = scale(area.x, area.width, self.width, dest.width);
+        let (y, height) = scale(area.y, area.height, self.height, dest.height);
+        Rect::new(dest.x.saturating_add(x as isize), dest.y.saturating_add(y as isize), width as usize, height as usize)
+    }
+}
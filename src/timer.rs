@@ -0,0 +1,206 @@
+// Timers for animations, blinking, and timeouts, fired from an application's own loop.
+use std::time::{Duration, Instant};
+
+
+/// A timer registered with a `Scheduler`, for cancelling, pausing, or resuming it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Timer(u64);
+
+
+/// Get the time `delay` after `now`, or as late as can be represented if that is too far off.
+pub(crate) fn saturating_add(now: Instant, delay: Duration) -> Instant {
+    now.checked_add(delay).unwrap_or_else(|| {
+        let mut step = delay;
+        let mut later = now;
+        while !step.is_zero() {
+            match later.checked_add(step) {
+                Some(t) => later = t,
+                None => step /= 2
+            }
+        }
+        later
+    })
+}
+
+
+/// A registered timer: what it fires, when, and how often.
+#[derive(Clone, Debug)]
+struct Entry<T> {
+    timer: Timer,
+    id: T,
+    deadline: Instant,
+    interval: Option<Duration>,
+    paused: Option<Duration>
+}
+
+
+/// Timers that fire ids of type `T` (such as an enum of the things to update) once after a delay or repeatedly at an interval.
+/// Nothing runs on its own: call `tick` with the current time each frame to get the ids that fired since the last tick.
+///
+/// Timers fire at absolute deadlines counted from when they were set, so however irregular the frames are, an interval
+/// timer keeps its phase and never drifts. An interval timer due several times over in one tick fires once, then skips to
+/// its next deadline after that tick, so a slow frame doesn't cause a burst. `poll` fires one timer at a time, moving the
+/// scheduler's clock to its deadline, so a timer set in response (with `after` or `every`) counts from that deadline too.
+///
+/// Example
+/// ```
+/// use terminalgl::timer::Scheduler;
+/// use std::time::Duration;
+/// #[derive(Clone, Debug, PartialEq)]
+/// enum Tick { Blink, Toast }
+/// let ms = Duration::from_millis;
+/// let mut scheduler = Scheduler::new();
+/// let start = scheduler.now();
+/// let blink = scheduler.every(ms(500), Tick::Blink);
+/// scheduler.after(ms(1200), Tick::Toast);
+///
+/// assert_eq!(scheduler.tick(start + ms(499)), []);
+/// assert_eq!(scheduler.tick(start + ms(520)), [Tick::Blink]);
+/// // Late frames don't push later blinks back, and a frame missing several blinks fires one.
+/// assert_eq!(scheduler.tick(start + ms(1000)), [Tick::Blink]);
+/// assert_eq!(scheduler.tick(start + ms(2600)), [Tick::Toast, Tick::Blink]);
+/// assert_eq!(scheduler.tick(start + ms(3000)), [Tick::Blink]);
+///
+/// scheduler.pause(blink);
+/// assert_eq!(scheduler.tick(start + ms(3800)), []);
+/// scheduler.resume(blink);
+/// assert_eq!(scheduler.tick(start + ms(4299)), []);
+/// assert_eq!(scheduler.tick(start + ms(4300)), [Tick::Blink]);
+/// assert!(scheduler.cancel(blink));
+/// assert!(scheduler.is_empty());
+/// ```
+#[derive(Clone, Debug)]
+pub struct Scheduler<T> {
+    now: Instant,
+    next: u64,
+    entries: Vec<Entry<T>>
+}
+
+impl<T: Clone> Default for Scheduler<T> {
+    fn default() -> Self {
+        Scheduler::new()
+    }
+}
+
+impl<T: Clone> Scheduler<T> {
+    /// Create a scheduler with no timers, its clock starting now.
+    pub fn new() -> Scheduler<T> {
+        Scheduler::starting_at(Instant::now())
+    }
+
+    /// Create a scheduler with no timers, its clock starting at `now`.
+    pub fn starting_at(now: Instant) -> Scheduler<T> {
+        Scheduler { now, next: 0, entries: Vec::new() }
+    }
+
+    /// Get the scheduler's clock: the time of the last `tick`, or of the last timer fired by `poll`.
+    pub fn now(&self) -> Instant {
+        self.now
+    }
+
+    /// Fire `id` once, `delay` after the scheduler's clock.
+    pub fn after(&mut self, delay: Duration, id: T) -> Timer {
+        self.add(delay, None, id)
+    }
+
+    /// Fire `id` every `interval`, first at `interval` after the scheduler's clock. A zero interval fires on every tick.
+    pub fn every(&mut self, interval: Duration, id: T) -> Timer {
+        self.add(interval, Some(interval), id)
+    }
+
+    fn add(&mut self, delay: Duration, interval: Option<Duration>, id: T) -> Timer {
+        let timer = Timer(self.next);
+        self.next += 1;
+        self.entries.push(Entry { timer, id, deadline: saturating_add(self.now, delay), interval, paused: None });
+        timer
+    }
+
+    /// Remove `timer`, and get whether it was still registered. One-shot timers are removed once they fire.
+    pub fn cancel(&mut self, timer: Timer) -> bool {
+        let count = self.entries.len();
+        self.entries.retain(|entry| entry.timer != timer);
+        self.entries.len() != count
+    }
+
+    /// Stop `timer` from firing, keeping the time left until it is due.
+    pub fn pause(&mut self, timer: Timer) {
+        let now = self.now;
+        if let Some(entry) = self.entry(timer).filter(|entry| entry.paused.is_none()) {
+            entry.paused = Some(entry.deadline.saturating_duration_since(now));
+        }
+    }
+
+    /// Let a paused `timer` fire again, due after the time it had left when paused.
+    pub fn resume(&mut self, timer: Timer) {
+        let now = self.now;
+        if let Some(entry) = self.entry(timer) {
+            if let Some(left) = entry.paused.take() {
+                entry.deadline = saturating_add(now, left);
+            }
+        }
+    }
+
+    /// Get whether `timer` is registered and paused.
+    pub fn is_paused(&self, timer: Timer) -> bool {
+        self.entries.iter().any(|entry| entry.timer == timer && entry.paused.is_some())
+    }
+
+    /// Get whether `timer` is still registered.
+    pub fn contains(&self, timer: Timer) -> bool {
+        self.entries.iter().any(|entry| entry.timer == timer)
+    }
+
+    fn entry(&mut self, timer: Timer) -> Option<&mut Entry<T>> {
+        self.entries.iter_mut().find(|entry| entry.timer == timer)
+    }
+
+    /// Get the number of timers registered, including paused ones.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Get whether no timers are registered.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Fire the timer due soonest at or before `now`, if any, moving the scheduler's clock to its deadline, and get its id.
+    /// Timers due at the same time fire in the order they were set.
+    pub fn poll(&mut self, now: Instant) -> Option<T> {
+        let i = self.entries.iter().enumerate()
+            .filter(|(_, entry)| entry.paused.is_none() && entry.deadline <= now)
+            .min_by_key(|(_, entry)| entry.deadline)?.0;
+        self.now = self.now.max(self.entries[i].deadline);
+        let Some(interval) = self.entries[i].interval else {
+            return Some(self.entries.remove(i).id);
+        };
+        let entry = &mut self.entries[i];
+        // The first deadline after `now` in step with the old ones, so the timer doesn't fire again until the next tick.
+        let missed = now.duration_since(entry.deadline).as_nanos() / interval.as_nanos().max(1) + 1;
+        let step = match interval.is_zero() {
+            true => now.duration_since(entry.deadline) + Duration::from_nanos(1),
+            false => interval.saturating_mul(missed.min(u32::MAX as u128) as u32)
+        };
+        entry.deadline = saturating_add(entry.deadline, step);
+        if entry.deadline <= now {
+            // Out of representable time to fire again in.
+            return Some(self.entries.remove(i).id);
+        }
+        Some(entry.id.clone())
+    }
+
+    /// Fire every timer due at or before `now` and move the scheduler's clock to `now`, and get the ids fired in the order they were due.
+    pub fn tick(&mut self, now: Instant) -> Vec<T> {
+        let mut fired = Vec::new();
+        while let Some(id) = self.poll(now) {
+            fired.push(id);
+        }
+        self.now = self.now.max(now);
+        fired
+    }
+
+    /// Like `tick`, at `dt` after the scheduler's clock, for loops that count time in frame lengths.
+    pub fn advance(&mut self, dt: Duration) -> Vec<T> {
+        self.tick(saturating_add(self.now, dt))
+    }
+}
@@ -0,0 +1,209 @@
+// Turtle graphics: a pen that moves and turns, drawing lines behind it, for sketches, teaching, and L-system fractals (see `lsystem`).
+use super::canvas::Canvas;
+use super::drawc;
+
+
+/// Everything `Turtle::push` saves and `Turtle::pop` brings back.
+#[derive(Clone, Debug, PartialEq)]
+struct State {
+    x: f64,
+    y: f64,
+    heading: f64,
+    pen_down: bool,
+    c: char,
+    ccode: String
+}
+
+
+/// A pen at a position with a heading, drawing a line of its character from where it was to where it goes while the pen is down.
+/// Positions are kept as `f64`, so turning by any angle and moving by any distance adds no rounding error over many moves;
+/// only the lines drawn are rounded to cells. A heading of 0 degrees points right and 90 points up.
+///
+/// A turtle draws to the terminal (`new`) or into a canvas (`on`).
+///
+/// Example
+/// ```
+/// use terminalgl::canvas::Canvas;
+/// use terminalgl::turtle::Turtle;
+/// use terminalgl::assert_frame_eq;
+/// let mut canvas = Canvas::new(7, 5);
+/// let mut turtle = Turtle::on(&mut canvas, 1.0, 4.0);
+/// turtle.set_char('#');
+/// for _ in 0..4 {
+///     turtle.forward(4.0);
+///     turtle.turn_left(90.0);
+/// }
+/// turtle.pen_up();
+/// turtle.goto(3.0, 2.0);
+/// turtle.pen_down();
+/// turtle.set_char('*');
+/// turtle.forward(0.0);
+/// assert_frame_eq!(canvas, "\
+/// | ##### |
+/// | #   # |
+/// | # * # |
+/// | #   # |
+/// | ##### |");
+/// ```
+#[derive(Debug)]
+pub struct Turtle<'a> {
+    canvas: Option<&'a mut Canvas>,
+    state: State,
+    stack: Vec<State>,
+    aspect: f64
+}
+
+impl<'a> Turtle<'a> {
+    /// Create a turtle drawing to the terminal, at `(x, y)` heading right with the pen down, drawing `*` without color.
+    pub fn new(x: f64, y: f64) -> Turtle<'a> {
+        Turtle {
+            canvas: None,
+            state: State { x, y, heading: 0.0, pen_down: true, c: '*', ccode: String::new() },
+            stack: Vec::new(),
+            aspect: 1.0
+        }
+    }
+
+    /// Create a turtle like `new`, drawing into `canvas` instead.
+    pub fn on(canvas: &'a mut Canvas, x: f64, y: f64) -> Turtle<'a> {
+        Turtle { canvas: Some(canvas), ..Turtle::new(x, y) }
+    }
+
+    /// Get the position of the turtle as `(x, y)`.
+    pub fn position(&self) -> (f64, f64) {
+        (self.state.x, self.state.y)
+    }
+
+    /// Get the heading of the turtle in degrees, from 0 up to 360, counterclockwise from right.
+    pub fn heading(&self) -> f64 {
+        self.state.heading
+    }
+
+    /// Point the turtle at `degrees` counterclockwise from right.
+    pub fn set_heading(&mut self, degrees: f64) {
+        self.state.heading = degrees.rem_euclid(360.0);
+    }
+
+    /// Turn counterclockwise by `degrees`.
+    pub fn turn_left(&mut self, degrees: f64) {
+        self.set_heading(self.state.heading + degrees);
+    }
+
+    /// Turn clockwise by `degrees`.
+    pub fn turn_right(&mut self, degrees: f64) {
+        self.set_heading(self.state.heading - degrees);
+    }
+
+    /// Scale vertical movement by `aspect`. Terminal cells are about twice as tall as they are wide,
+    /// so `0.5` makes shapes come out in proportion, such as squares that look square. Defaults to `1.0`.
+    ///
+    /// Example
+    /// ```
+    /// use terminalgl::canvas::Canvas;
+    /// use terminalgl::turtle::Turtle;
+    /// let mut canvas = Canvas::new(1, 10);
+    /// let mut turtle = Turtle::on(&mut canvas, 0.0, 8.0);
+    /// turtle.set_aspect(0.5);
+    /// turtle.turn_left(90.0);
+    /// turtle.forward(8.0);
+    /// assert!((turtle.position().1 - 4.0).abs() < 1e-9);
+    /// let drawn: Vec<isize> = (0..10).filter(|&y| canvas.get(0, y).is_some_and(|cell| cell.c == '*')).collect();
+    /// assert_eq!(drawn, [4, 5, 6, 7, 8]);
+    /// ```
+    pub fn set_aspect(&mut self, aspect: f64) {
+        self.aspect = aspect;
+    }
+
+    /// Lift the pen, so the turtle moves without drawing.
+    pub fn pen_up(&mut self) {
+        self.state.pen_down = false;
+    }
+
+    /// Put the pen down, so the turtle draws as it moves.
+    pub fn pen_down(&mut self) {
+        self.state.pen_down = true;
+    }
+
+    /// Get whether the pen is down.
+    pub fn is_pen_down(&self) -> bool {
+        self.state.pen_down
+    }
+
+    /// Draw with character `c`.
+    pub fn set_char(&mut self, c: char) {
+        self.state.c = c;
+    }
+
+    /// Draw with color code `ccode`, such as `drawc::GREEN` or `""` for none.
+    pub fn set_color(&mut self, ccode: &str) {
+        self.state.ccode = ccode.to_string();
+    }
+
+    /// Move `distance` along the heading, drawing a line if the pen is down.
+    pub fn forward(&mut self, distance: f64) {
+        let radians = self.state.heading.to_radians();
+        let (x, y) = (self.state.x + distance * radians.cos(), self.state.y - distance * radians.sin() * self.aspect);
+        self.goto(x, y);
+    }
+
+    /// Move `distance` against the heading, drawing a line if the pen is down. The heading stays the same.
+    pub fn back(&mut self, distance: f64) {
+        self.forward(-distance);
+    }
+
+    /// Move straight to `(x, y)`, drawing a line if the pen is down. The heading stays the same.
+    pub fn goto(&mut self, x: f64, y: f64) {
+        if self.state.pen_down {
+            let (x1, y1) = (self.state.x.round() as isize, self.state.y.round() as isize);
+            let (x2, y2) = (x.round() as isize, y.round() as isize);
+            let State { c, ccode, .. } = &self.state;
+            match &mut self.canvas {
+                Some(canvas) => {
+                    canvas.line(x1, y1, x2, y2, *c, ccode);
+                },
+                None => drawc::line(x1, y1, x2, y2, *c, ccode)
+            }
+        }
+        self.state.x = x;
+        self.state.y = y;
+    }
+
+    /// Save the position, heading, pen, character and color, to come back to with `pop`.
+    pub fn push(&mut self) {
+        self.stack.push(self.state.clone());
+    }
+
+    /// Go back to the state saved by the last `push` without drawing, and get whether there was one.
+    ///
+    /// Example
+    /// ```
+    /// use terminalgl::canvas::Canvas;
+    /// use terminalgl::turtle::Turtle;
+    /// use terminalgl::assert_frame_eq;
+    /// let mut canvas = Canvas::new(5, 3);
+    /// let mut turtle = Turtle::on(&mut canvas, 2.0, 2.0);
+    /// turtle.set_heading(90.0);
+    /// // Two branches from the same point.
+    /// turtle.push();
+    /// turtle.turn_left(45.0);
+    /// turtle.forward(2.9);
+    /// assert!(turtle.pop());
+    /// turtle.turn_right(45.0);
+    /// turtle.forward(2.9);
+    /// assert!(!turtle.pop());
+    /// assert_frame_eq!(canvas, "\
+    /// |*   *|
+    /// | * * |
+    /// |  *  |");
+    /// ```
+    pub fn pop(&mut self) -> bool {
+        match self.stack.pop() {
+            Some(state) => {
+                self.state = state;
+                true
+            },
+            None => false
+        }
+    }
+}
+
@@ -0,0 +1,1769 @@
+// Minimal widgets, drawn immediate mode or kept between frames and redrawn only when they change.
+use super::canvas::{Canvas, Snapshot};
+use super::chars::LineStyle;
+use super::theme;
+use super::timer::{self, Scheduler, Timer};
+use super::{drawc, raster, Anchor, Rect, TextAlignment, WrapOptions};
+#[cfg(all(feature = "input", unix))]
+use super::input::{self, Event, Key, Mouse, MouseButton, MouseKind, RawMode};
+use std::borrow::Cow;
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+
+/// Reverse video, used to show which widget has focus.
+const REVERSE: &str = "\x1b[7m";
+
+
+/// Input events for `UiContext::begin`, translated from whatever input handling the application uses.
+/// `Next` and `Previous` move focus (usually Tab and Shift+Tab), `Activate` activates the focused widget (usually Enter),
+/// `Click` activates the widget under a cell, and `Cancel` dismisses dialogs (usually Esc).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum UiEvent {
+    Next,
+    Previous,
+    Activate,
+    Click(isize, isize),
+    Cancel
+}
+
+
+/// Tracks focus between frames of immediate mode widgets.
+/// Widgets get their ids from the order they are drawn in, so a frame must draw them in the same order every time.
+///
+/// Example
+/// ```
+/// use terminalgl::ui::{self, UiContext, UiEvent};
+/// use terminalgl::{drawc, Rect};
+/// let mut ctx = UiContext::new();
+/// let events = [vec![], vec![UiEvent::Next], vec![UiEvent::Activate]];
+/// let mut quit = false;
+/// for frame_events in events {
+///     ctx.begin(&frame_events);
+///     ui::button(&mut ctx, Rect::new(1, 1, 12, 3), "Play", drawc::WHITE);
+///     ui::button(&mut ctx, Rect::new(1, 4, 12, 3), "Settings", drawc::WHITE);
+///     if ui::button(&mut ctx, Rect::new(1, 7, 12, 3), "Quit", drawc::WHITE) {
+///         quit = true;
+///     }
+/// }
+/// assert_eq!(ctx.focused(), 1);
+/// assert!(!quit);
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct UiContext {
+    focus: usize,
+    count: usize,
+    next_id: usize,
+    activate: bool,
+    click: Option<(isize, isize)>
+}
+
+impl UiContext {
+    /// Create a context with focus on the first widget.
+    pub fn new() -> UiContext {
+        UiContext::default()
+    }
+
+    /// Start a frame, applying `events` before any widgets are drawn.
+    /// Focus wraps around using the number of widgets drawn in the previous frame.
+    pub fn begin(&mut self, events: &[UiEvent]) {
+        if self.next_id > 0 {
+            self.count = self.next_id;
+        }
+        self.next_id = 0;
+        self.activate = false;
+        self.click = None;
+        for event in events {
+            match *event {
+                UiEvent::Next if self.count > 0 => self.focus = (self.focus + 1) % self.count,
+                UiEvent::Previous if self.count > 0 => self.focus = (self.focus + self.count - 1) % self.count,
+                UiEvent::Activate => self.activate = true,
+                UiEvent::Click(x, y) => self.click = Some((x, y)),
+                _ => {}
+            }
+        }
+    }
+
+    /// Get the id of the focused widget.
+    pub fn focused(&self) -> usize {
+        self.focus
+    }
+
+    /// Move focus to the widget with id `id`.
+    pub fn set_focus(&mut self, id: usize) {
+        self.focus = id;
+    }
+
+    /// Claim the id of the next widget drawn this frame, and get whether it was activated.
+    fn widget(&mut self, rect: Rect) -> (usize, bool) {
+        let id = self.next_id;
+        self.next_id += 1;
+        let clicked = self.click.is_some_and(|(x, y)| rect.contains(x, y));
+        if clicked {
+            self.focus = id;
+        }
+        (id, clicked || (self.activate && self.focus == id))
+    }
+}
+
+
+/// A widget that remembers what it shows between frames, so an application drawing many of them only redraws the ones that changed.
+/// A widget is dirty from when it is created or its state changes (a new log line, more text revealed) until `mark_clean`.
+/// `render_if_dirty` also redraws a clean widget when it is given a different rect than it was last drawn into.
+///
+/// Example
+/// ```
+/// use terminalgl::ui::{LogPane, TextReveal, Widget};
+/// use terminalgl::canvas::Canvas;
+/// use terminalgl::Rect;
+/// let mut widgets: Vec<(Box<dyn Widget>, Rect)> = vec![
+///     (Box::new(LogPane::new(Rect::new(0, 0, 10, 2), 10)), Rect::new(0, 0, 10, 2)),
+///     (Box::new(TextReveal::new("hello")), Rect::new(0, 2, 10, 1))
+/// ];
+/// let mut canvas = Canvas::new(10, 3);
+/// let mut frame = |widgets: &mut Vec<(Box<dyn Widget>, Rect)>, canvas: &mut Canvas| {
+///     widgets.iter_mut().filter_map(|(widget, rect)| widget.render_if_dirty(canvas, *rect).then_some(())).count()
+/// };
+/// assert_eq!(frame(&mut widgets, &mut canvas), 2);
+/// assert_eq!(frame(&mut widgets, &mut canvas), 0);
+///
+/// widgets[1].1 = Rect::new(1, 2, 9, 1);
+/// assert_eq!(frame(&mut widgets, &mut canvas), 1);
+/// assert_eq!(widgets[1].0.drawn_rect(), Some(Rect::new(1, 2, 9, 1)));
+/// ```
+pub trait Widget {
+    /// Clear `rect` of `canvas` and draw the widget into it.
+    fn render(&mut self, canvas: &mut Canvas, rect: Rect);
+
+    /// Get whether the widget has changed since `mark_clean`, or hasn't been marked clean yet.
+    fn is_dirty(&self) -> bool;
+
+    /// Mark the widget as up to date with what was last drawn.
+    fn mark_clean(&mut self);
+
+    /// Get the rect the widget was last drawn into with `render`, or `None` if it hasn't been drawn.
+    fn drawn_rect(&self) -> Option<Rect>;
+
+    /// Draw the widget into `rect` and mark it clean if it is dirty or was last drawn somewhere else, and get whether it was drawn.
+    fn render_if_dirty(&mut self, canvas: &mut Canvas, rect: Rect) -> bool {
+        if !self.is_dirty() && self.drawn_rect() == Some(rect) {
+            return false;
+        }
+        self.render(canvas, rect);
+        self.mark_clean();
+        true
+    }
+}
+
+
+/// Draw a button with `label` centered in `rect`, and get whether it was activated this frame.
+/// Buttons at least 3 rows tall get a border. The button is drawn with `ccode`, and in reverse video when focused,
+/// or with the theme's `text`, `border`, and `selection` colors if `ccode` is empty (see `theme::set_theme`).
+pub fn button(ctx: &mut UiContext, rect: Rect, label: &str, ccode: &str) -> bool {
+    let (id, activated) = ctx.widget(rect);
+    let theme = theme::current();
+    let (style, border) = match (ctx.focused() == id, ccode.is_empty()) {
+        (true, true) => (theme.selection.clone(), theme.selection),
+        (true, false) => (ccode.to_string() + REVERSE, ccode.to_string() + REVERSE),
+        (false, true) => (theme.text, theme.border),
+        (false, false) => (ccode.to_string(), ccode.to_string())
+    };
+    drawc::rectangle(rect.x, rect.y, rect.width, rect.height, ' ', &style, true);
+    if rect.height >= 3 {
+        drawc::rectangle(rect.x, rect.y, rect.width, rect.height, '#', &border, false);
+    }
+    let cx = rect.x + rect.width as isize / 2;
+    let cy = rect.y + rect.height as isize / 2;
+    drawc::text_aligned(cx, cy, label, TextAlignment::Center, &style);
+    print!("{}", drawc::RESET);
+    activated
+}
+
+
+/// A message box with a title, a message wrapped as `wrap` says (see `raster::wrap_text`), and a row of buttons,
+/// drawn into a canvas centered on it. `Next` and `Previous` move between buttons, `Activate` or a click chooses one,
+/// and `Cancel` chooses `cancel`. `message_box` shows one over a canvas and waits for the choice.
+///
+/// Example
+/// ```
+/// use terminalgl::assert_frame_eq;
+/// use terminalgl::canvas::Canvas;
+/// use terminalgl::ui::{MessageBox, UiEvent};
+/// let mut canvas = Canvas::new(23, 7);
+/// let mut dialog = MessageBox::new("Quit", "Save changes?", &["Save", "Discard"]);
+/// dialog.draw(&mut canvas, "");
+/// assert_frame_eq!(canvas, "\
+/// | ┌────── Quit ───────┐ |
+/// | │                   │ |
+/// | │ Save changes?     │ |
+/// | │                   │ |
+/// | │                   │ |
+/// | │ [Save]  [Discard] │ |
+/// | └───────────────────┘ |
+///
+/// |.......................|
+/// |.......................|
+/// |.......................|
+/// |.......................|
+/// |.......................|
+/// |...AAAAAA..............|
+/// |.......................|
+/// A \"\\u{1b}[7m\"");
+/// let area = canvas.rect();
+/// assert_eq!(dialog.update(&[UiEvent::Next], area), None);
+/// assert_eq!(dialog.update(&[UiEvent::Activate], area), Some(1));
+/// assert_eq!(dialog.update(&[UiEvent::Cancel], area), Some(1));
+/// assert_eq!(dialog.update(&[UiEvent::Click(5, 5)], area), Some(0));
+/// ```
+#[derive(Clone, Debug)]
+pub struct MessageBox {
+    pub title: String,
+    pub message: String,
+    pub buttons: Vec<String>,
+    pub selected: usize,
+    pub cancel: usize,
+    pub wrap: WrapOptions
+}
+
+impl MessageBox {
+    /// Create a message box with the first button selected and the last button as the cancel choice.
+    pub fn new(title: &str, message: &str, buttons: &[&str]) -> MessageBox {
+        MessageBox {
+            title: title.to_string(),
+            message: message.to_string(),
+            buttons: buttons.iter().map(|b| b.to_string()).collect(),
+            selected: 0,
+            cancel: buttons.len().saturating_sub(1),
+            wrap: WrapOptions::default()
+        }
+    }
+
+    /// Apply `events` to the message box as laid out in `area`, and get the index of the chosen button once one is chosen.
+    pub fn update(&mut self, events: &[UiEvent], area: Rect) -> Option<usize> {
+        let count = self.buttons.len().max(1);
+        for event in events {
+            match *event {
+                UiEvent::Next => self.selected = (self.selected + 1) % count,
+                UiEvent::Previous => self.selected = (self.selected + count - 1) % count,
+                UiEvent::Activate => return Some(self.selected),
+                UiEvent::Cancel => return Some(self.cancel),
+                UiEvent::Click(x, y) => {
+                    if let Some(i) = self.button_rects(area).iter().position(|r| r.contains(x, y)) {
+                        return Some(i);
+                    }
+                }
+            }
+        }
+        None
+    }
+
+    /// Get the rectangle the message box covers, centered in `area`.
+    pub fn rect(&self, area: Rect) -> Rect {
+        area.anchor(Anchor::Center, (self.content_width(area) + 4, self.lines(area).len() + 6), 0)
+    }
+
+    /// Draw the message box centered in `canvas` with `ccode`, with the selected button in reverse video,
+    /// or in the theme's colors if `ccode` is empty (see `theme::set_theme`).
+    pub fn draw(&self, canvas: &mut Canvas, ccode: &str) {
+        let area = canvas.rect();
+        let rect = self.rect(area);
+        let theme = theme::current();
+        let selection = if ccode.is_empty() { theme.selection.clone() } else { ccode.to_string() + REVERSE };
+        canvas.rectangle(rect.x, rect.y, rect.width, rect.height, ' ', theme::pick(ccode, &theme.background), true);
+        canvas.border(rect, LineStyle::Light, theme::pick(ccode, &theme.border));
+        let title = format!(" {} ", self.title);
+        let title_x = rect.x.saturating_add_unsigned(rect.width.saturating_sub(title.chars().count()) / 2);
+        canvas.text(title_x, rect.y, &title, theme::pick(ccode, &theme.title));
+        for (i, line) in self.lines(area).iter().enumerate() {
+            canvas.text(rect.x.saturating_add(2), rect.y.saturating_add(2 + i as isize), line, theme::pick(ccode, &theme.text));
+        }
+        for (i, (button, r)) in self.buttons.iter().zip(self.button_rects(area)).enumerate() {
+            let style = if i == self.selected { &selection } else { theme::pick(ccode, &theme.text) };
+            canvas.text(r.x, r.y, &format!("[{}]", button), style);
+        }
+    }
+
+    /// Get the width available to the message in `area`, wide enough for the title and buttons.
+    fn content_width(&self, area: Rect) -> usize {
+        let buttons = self.buttons_width();
+        let longest = self.message.split('\n').map(|l| l.chars().count()).max().unwrap_or(0);
+        let max = area.width.saturating_sub(4).max(buttons).max(1);
+        longest.max(buttons).max(self.title.chars().count() + 2).min(max)
+    }
+
+    fn buttons_width(&self) -> usize {
+        self.buttons.iter().map(|b| b.chars().count() + 2).sum::<usize>() + self.buttons.len().saturating_sub(1) * 2
+    }
+
+    fn lines(&self, area: Rect) -> Vec<Cow<'_, str>> {
+        raster::wrap_text(&self.message, self.content_width(area), self.wrap)
+    }
+
+    /// Get the rectangle of each button, laid out centered on the bottom row inside the border.
+    fn button_rects(&self, area: Rect) -> Vec<Rect> {
+        let rect = self.rect(area);
+        let row = rect.anchor(Anchor::Bottom, (self.buttons_width(), 1), 1);
+        let (mut x, y) = (row.x, row.y);
+        self.buttons.iter().map(|b| {
+            let r = Rect::new(x, y, b.chars().count() + 2, 1);
+            x += r.width as isize + 2;
+            r
+        }).collect()
+    }
+}
+
+
+/// Options for `message_box`.
+#[derive(Clone, Debug)]
+pub struct MessageBoxOptions {
+    /// Color code for the box, or empty for the theme's colors.
+    pub ccode: String,
+    /// How much to darken everything behind the box, from `0.0` (not at all) to `1.0` (black).
+    pub dim: f64,
+    /// The button Esc chooses, or `None` for the last one.
+    pub cancel: Option<usize>,
+    pub wrap: WrapOptions
+}
+
+impl Default for MessageBoxOptions {
+    fn default() -> MessageBoxOptions {
+        MessageBoxOptions { ccode: String::new(), dim: 0.5, cancel: None, wrap: WrapOptions::default() }
+    }
+}
+
+
+/// Show a `MessageBox` centered over `canvas` with everything behind it dimmed, wait for a button to be chosen, and get its index.
+/// Left and Right or Tab and Shift+Tab move between buttons, Enter or a click chooses one, and Esc chooses `opts.cancel`.
+/// The cells it covered are put back afterwards, on the canvas and on the terminal, so the scene doesn't need redrawing.
+/// The canvas is presented in full first, and raw mode with mouse reporting is turned on while waiting unless it already is.
+/// If input can't be read, the cancel button is chosen.
+///
+/// Example
+/// ```no_run
+/// use terminalgl::canvas::Canvas;
+/// use terminalgl::input::RawMode;
+/// use terminalgl::ui::{self, MessageBoxOptions};
+/// let _raw = RawMode::with_mouse().unwrap();
+/// let mut canvas = Canvas::screen();
+/// canvas.text(1, 1, "Unsaved drawing", "");
+/// let opts = MessageBoxOptions { cancel: Some(2), ..MessageBoxOptions::default() };
+/// let choice = ui::message_box(&mut canvas, "Quit", "Save changes before quitting?", &["Save", "Discard", "Cancel"], &opts);
+/// if choice == 2 {
+///     canvas.text(1, 2, "Back to drawing", "");
+/// }
+/// ```
+#[cfg(all(feature = "input", unix))]
+pub fn message_box(canvas: &mut Canvas, title: &str, message: &str, buttons: &[&str], opts: &MessageBoxOptions) -> usize {
+    let mut dialog = MessageBox::new(title, message, buttons);
+    dialog.cancel = opts.cancel.unwrap_or(dialog.cancel);
+    dialog.wrap = opts.wrap;
+    let _raw = RawMode::with_mouse().ok();
+    let scene = canvas.snapshot(canvas.rect());
+    canvas.darken(opts.dim);
+    dialog.draw(canvas, &opts.ccode);
+    canvas.present();
+    let mut shown = canvas.clone();
+    let choice = loop {
+        let Ok(events) = input::read_events(Duration::from_millis(100)) else {
+            break dialog.cancel;
+        };
+        let events: Vec<UiEvent> = events.iter().filter_map(|event| match event {
+            Event::Key(Key::Left) => Some(UiEvent::Previous),
+            Event::Key(Key::Right) => Some(UiEvent::Next),
+            event => event.ui_event()
+        }).collect();
+        if let Some(choice) = dialog.update(&events, canvas.rect()) {
+            break choice;
+        }
+        dialog.draw(canvas, &opts.ccode);
+        canvas.present_diff(&shown);
+        shown = canvas.clone();
+    };
+    canvas.restore(&scene);
+    canvas.present_diff(&shown);
+    choice
+}
+
+
+/// Get `secs` seconds as a duration, with negative and NaN counting as zero and anything too long as `Duration::MAX`.
+fn seconds(secs: f64) -> Duration {
+    Duration::try_from_secs_f64(secs.max(0.0)).unwrap_or(Duration::MAX)
+}
+
+
+/// Characters `TextReveal` pauses after.
+const PAUSE_AFTER: [char; 6] = ['.', ',', '!', '?', ';', ':'];
+
+
+/// Typewriter reveal of a block of text, for dialogue boxes. `tick` reveals `speed` characters a second, pausing for
+/// `punctuation_pause` extra seconds after punctuation, and `render` draws the revealed part wrapped into a rectangle as `wrap` says
+/// (see `raster::wrap_text`).
+/// The text is wrapped as a whole, so words never jump to the next line halfway through appearing,
+/// and a character is always revealed together with the marks combining with it, a grapheme at a time (see `raster::graphemes`).
+///
+/// Example
+/// ```
+/// use terminalgl::ui::TextReveal;
+/// use terminalgl::canvas::Canvas;
+/// use terminalgl::{assert_frame_eq, Rect};
+/// let mut reveal = TextReveal::new("Hi. Bye");
+/// reveal.speed = 4.0;
+/// reveal.punctuation_pause = 1.0;
+/// reveal.tick(0.5);
+/// assert_eq!(reveal.revealed(), "Hi.");
+/// reveal.tick(1.0);
+/// assert_eq!(reveal.revealed(), "Hi.");
+/// reveal.tick(0.25);
+/// assert_eq!(reveal.revealed(), "Hi. ");
+/// reveal.skip();
+/// assert!(reveal.done());
+///
+/// let mut reveal = TextReveal::new("ab cd ef");
+/// reveal.speed = 1.0;
+/// reveal.tick(3.0);
+/// let mut canvas = Canvas::new(6, 2);
+/// reveal.render(&mut canvas, Rect::new(1, 0, 5, 2));
+/// assert_frame_eq!(canvas, "\
+/// | ab c |
+/// |      |");
+///
+/// let mut reveal = TextReveal::new("e\u{301}x");
+/// reveal.tick(0.0);
+/// assert_eq!(reveal.revealed(), "e\u{301}");
+///
+/// // A line broken at a soft hyphen shows the hyphen once the soft hyphen is revealed.
+/// let mut reveal = TextReveal::new("in\u{AD}ter\u{AD}na\u{AD}tion\u{AD}al");
+/// reveal.speed = 1.0;
+/// reveal.tick(8.0);
+/// let mut canvas = Canvas::new(8, 2);
+/// reveal.render(&mut canvas, Rect::new(0, 0, 8, 2));
+/// assert_frame_eq!(canvas, "\
+/// |interna |
+/// |        |");
+/// reveal.tick(1.0);
+/// reveal.render(&mut canvas, Rect::new(0, 0, 8, 2));
+/// assert_frame_eq!(canvas, "\
+/// |interna-|
+/// |        |");
+///
+/// // The same time revealed in one frame or many reveals the same text.
+/// let text = "abcdefghijklmnopqrstuvwxyz0123456789";
+/// let (mut slow, mut fast) = (TextReveal::new(text), TextReveal::new(text));
+/// slow.tick(0.5);
+/// slow.tick(0.25);
+/// (0..75).for_each(|_| fast.tick(0.01));
+/// assert_eq!(slow.revealed(), "abcdefghijklmnopqrstuvw");
+/// assert_eq!(fast.revealed(), slow.revealed());
+/// ```
+///
+/// As a `Widget`, it is dirty whenever more of the text is revealed, or `ccode`, `wrap` or the theme changes:
+/// ```
+/// use terminalgl::ui::{TextReveal, Widget};
+/// use terminalgl::canvas::Canvas;
+/// let mut reveal = TextReveal::new("ab");
+/// let mut canvas = Canvas::new(2, 1);
+/// let rect = canvas.rect();
+/// assert!(reveal.render_if_dirty(&mut canvas, rect));
+/// reveal.tick(0.0);
+/// assert!(reveal.is_dirty());
+/// assert!(reveal.render_if_dirty(&mut canvas, rect));
+/// reveal.tick(0.001);
+/// assert!(!reveal.is_dirty());
+/// reveal.ccode = terminalgl::drawc::RED.to_string();
+/// assert!(reveal.is_dirty());
+/// ```
+#[derive(Clone, Debug)]
+pub struct TextReveal {
+    pub speed: f64,
+    pub punctuation_pause: f64,
+    pub ccode: String,
+    pub wrap: WrapOptions,
+    text: String,
+    ends: Vec<usize>,
+    shown: usize,
+    clock: Scheduler<()>,
+    dirty: bool,
+    drawn: Option<(Rect, String, WrapOptions, u64)>
+}
+
+impl TextReveal {
+    /// Create a reveal of `text` with nothing shown yet, at 30 characters a second with a quarter second pause after punctuation.
+    pub fn new(text: &str) -> TextReveal {
+        // Byte offsets just past each grapheme.
+        let ends: Vec<usize> = raster::graphemes(text).scan(0, |end, grapheme| {
+            *end += grapheme.len();
+            Some(*end)
+        }).collect();
+        // The first character is due straight away, and each one revealed sets the timer for the next.
+        let mut clock = Scheduler::new();
+        clock.after(Duration::ZERO, ());
+        TextReveal {
+            speed: 30.0,
+            punctuation_pause: 0.25,
+            ccode: String::new(),
+            wrap: WrapOptions::default(),
+            text: text.to_string(),
+            ends,
+            shown: 0,
+            clock,
+            dirty: true,
+            drawn: None
+        }
+    }
+
+    /// Get the whole text.
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Get the part of the text revealed so far.
+    pub fn revealed(&self) -> &str {
+        &self.text[..self.shown.checked_sub(1).map_or(0, |i| self.ends[i])]
+    }
+
+    /// Advance by `dt` seconds, revealing the characters due in that time. Nothing is revealed while `speed` isn't positive.
+    /// Each character is due a fixed time after the one before it, however the time is split between ticks.
+    pub fn tick(&mut self, dt: f64) {
+        if self.speed.is_nan() || self.speed <= 0.0 {
+            return;
+        }
+        let now = timer::saturating_add(self.clock.now(), seconds(dt));
+        while self.clock.poll(now).is_some() && !self.done() {
+            self.shown += 1;
+            self.dirty = true;
+            let pause = self.revealed().ends_with(PAUSE_AFTER) && !self.done();
+            let delay = 1.0 / self.speed + if pause { self.punctuation_pause } else { 0.0 };
+            self.clock.after(seconds(delay), ());
+        }
+        self.clock.tick(now);
+    }
+
+    /// Reveal the rest of the text at once.
+    pub fn skip(&mut self) {
+        self.dirty |= !self.done();
+        self.shown = self.ends.len();
+    }
+
+    /// Get whether the whole text is revealed.
+    pub fn done(&self) -> bool {
+        self.shown == self.ends.len()
+    }
+
+    /// Draw the revealed part of the text into `canvas` with `ccode`, or the theme's `text` color if it is empty,
+    /// wrapped to fit `rect`. Lines past the bottom of `rect` are left out.
+    pub fn render(&self, canvas: &mut Canvas, rect: Rect) {
+        let theme = theme::current();
+        let ccode = theme::pick(&self.ccode, &theme.text);
+        let end = self.revealed().len();
+        // Wrapping only drops and merges spaces and soft hyphens, and shows soft hyphens it breaks at as `-`,
+        // so each line's characters can be found in order in the text.
+        let mut offsets = self.text.char_indices();
+        for (row, line) in raster::wrap_text(&self.text, rect.width, self.wrap).iter().take(rect.height).enumerate() {
+            let mut shown = String::new();
+            for c in line.chars() {
+                match offsets.find(|&(_, t)| t == c || (c == '-' && t == '\u{AD}')) {
+                    Some((i, _)) if i < end => shown.push(c),
+                    _ => break
+                }
+            }
+            let complete = shown.len() == line.len();
+            canvas.text(rect.x, rect.y.saturating_add(row as isize), &shown, ccode);
+            if !complete {
+                return;
+            }
+        }
+    }
+}
+
+
+impl Widget for TextReveal {
+    fn render(&mut self, canvas: &mut Canvas, rect: Rect) {
+        canvas.rectangle(rect.x, rect.y, rect.width, rect.height, ' ', &theme::current().background, true);
+        TextReveal::render(self, canvas, rect);
+        self.drawn = Some((rect, self.ccode.clone(), self.wrap, theme::generation()));
+    }
+
+    fn is_dirty(&self) -> bool {
+        self.dirty || self.drawn.as_ref().is_some_and(|(_, ccode, wrap, generation)| {
+            *ccode != self.ccode || *wrap != self.wrap || *generation != theme::generation()
+        })
+    }
+
+    fn mark_clean(&mut self) {
+        self.dirty = false;
+    }
+
+    fn drawn_rect(&self) -> Option<Rect> {
+        self.drawn.as_ref().map(|(rect, _, _, _)| *rect)
+    }
+}
+
+
+/// A bounded log of styled lines for a messages area, drawn word-wrapped into `rect` with the newest line at the bottom.
+/// Once more than `capacity` lines have been pushed, the oldest are dropped. Scrolling back shows older lines
+/// until the next `push`, which snaps back to following the newest. With `timestamps` on, each line pushed
+/// starts with the time since the pane was created, as `[mm:ss] `.
+///
+/// Example
+/// ```
+/// use terminalgl::ui::LogPane;
+/// use terminalgl::canvas::Canvas;
+/// use terminalgl::{assert_frame_eq, Rect};
+/// let mut log = LogPane::new(Rect::new(0, 0, 10, 3), 3);
+/// let mut canvas = Canvas::new(10, 3);
+/// log.push("one", "");
+/// log.render(&mut canvas);
+/// assert_frame_eq!(canvas, "\
+/// |          |
+/// |          |
+/// |one       |");
+///
+/// log.push("two", "");
+/// log.push("three is long", "");
+/// log.push("four", "");
+/// assert_eq!(log.len(), 3);
+/// canvas.clear();
+/// log.render(&mut canvas);
+/// assert_frame_eq!(canvas, "\
+/// |three is  |
+/// |long      |
+/// |four      |");
+///
+/// log.scroll_by(5);
+/// assert!(!log.following());
+/// canvas.clear();
+/// log.render(&mut canvas);
+/// assert_frame_eq!(canvas, "\
+/// |two       |
+/// |three is  |
+/// |long      |");
+///
+/// log.timestamps = true;
+/// log.push("five", "");
+/// assert!(log.following());
+/// canvas.clear();
+/// log.render(&mut canvas);
+/// assert_frame_eq!(canvas, "\
+/// |four      |
+/// |[00:00]   |
+/// |five      |");
+/// ```
+#[derive(Clone, Debug)]
+pub struct LogPane {
+    pub rect: Rect,
+    pub capacity: usize,
+    pub timestamps: bool,
+    lines: VecDeque<(String, String)>,
+    scroll: usize,
+    start: Instant,
+    dirty: bool,
+    drawn: Option<(Rect, u64)>
+}
+
+impl LogPane {
+    /// Create an empty log pane covering `rect` that keeps the last `capacity` lines, without timestamps.
+    pub fn new(rect: Rect, capacity: usize) -> LogPane {
+        LogPane { rect, capacity, timestamps: false, lines: VecDeque::new(), scroll: 0, start: Instant::now(), dirty: true, drawn: None }
+    }
+
+    /// Add `line` drawn with `ccode`, or the theme's `text` color if it is empty, as the newest line, dropping the oldest lines past `capacity`, and follow the newest line again.
+    pub fn push(&mut self, line: &str, ccode: &str) {
+        let line = if self.timestamps {
+            let secs = self.start.elapsed().as_secs();
+            format!("[{:02}:{:02}] {}", secs / 60, secs % 60, line)
+        }
+        else {
+            line.to_string()
+        };
+        self.lines.push_back((line, ccode.to_string()));
+        while self.lines.len() > self.capacity {
+            self.lines.pop_front();
+        }
+        self.scroll = 0;
+        self.dirty = true;
+    }
+
+    /// Get the number of lines kept.
+    pub fn len(&self) -> usize {
+        self.lines.len()
+    }
+
+    /// Get whether no lines are kept.
+    pub fn is_empty(&self) -> bool {
+        self.lines.is_empty()
+    }
+
+    /// Remove every line.
+    pub fn clear(&mut self) {
+        self.dirty |= !self.lines.is_empty();
+        self.lines.clear();
+        self.scroll = 0;
+    }
+
+    /// Get whether the newest line is shown at the bottom, rather than the pane being scrolled back.
+    pub fn following(&self) -> bool {
+        self.scroll == 0
+    }
+
+    /// Scroll back by `rows` wrapped rows, or forward for negative `rows`, stopping when the oldest row reaches the top
+    /// or the newest reaches the bottom.
+    pub fn scroll_by(&mut self, rows: isize) {
+        self.scroll_to(self.scroll.saturating_add_signed(rows).min(self.max_scroll()));
+    }
+
+    /// Scroll back to the oldest row.
+    pub fn scroll_to_oldest(&mut self) {
+        self.scroll_to(self.max_scroll());
+    }
+
+    /// Stop scrolling back and follow the newest line.
+    pub fn follow(&mut self) {
+        self.scroll_to(0);
+    }
+
+    fn scroll_to(&mut self, scroll: usize) {
+        self.dirty |= scroll != self.scroll;
+        self.scroll = scroll;
+    }
+
+    /// Scroll for `event`, and get whether it was used: Up and Down or the mouse wheel move a row at a time,
+    /// Page Up and Page Down a page at a time, Home goes to the oldest row, and End follows the newest line.
+    #[cfg(all(feature = "input", unix))]
+    pub fn handle(&mut self, event: &Event) -> bool {
+        let page = self.rect.height.saturating_sub(1).max(1) as isize;
+        match event {
+            Event::Key(Key::Up) => self.scroll_by(1),
+            Event::Key(Key::Down) => self.scroll_by(-1),
+            Event::Key(Key::PageUp) => self.scroll_by(page),
+            Event::Key(Key::PageDown) => self.scroll_by(-page),
+            Event::Key(Key::Home) => self.scroll_to_oldest(),
+            Event::Key(Key::End) => self.follow(),
+            Event::Mouse(Mouse { kind: MouseKind::ScrollUp, .. }) => self.scroll_by(1),
+            Event::Mouse(Mouse { kind: MouseKind::ScrollDown, .. }) => self.scroll_by(-1),
+            _ => return false
+        }
+        true
+    }
+
+    /// Draw the shown rows into `canvas`, bottom-aligned in `rect`.
+    pub fn render(&self, canvas: &mut Canvas) {
+        let theme = theme::current();
+        let scroll = self.scroll.min(self.max_scroll());
+        for (i, (row, ccode)) in self.rows().skip(scroll).take(self.rect.height).enumerate() {
+            let y = self.rect.y.saturating_add((self.rect.height - 1 - i) as isize);
+            canvas.text(self.rect.x, y, &row, theme::pick(ccode, &theme.text));
+        }
+    }
+
+    /// Get the wrapped rows of every line, newest first.
+    fn rows(&self) -> impl Iterator<Item = (String, &str)> {
+        self.lines.iter().rev().flat_map(|(line, ccode)| {
+            raster::wrap_words(line, self.rect.width).into_iter().rev().map(move |row| (row, ccode.as_str()))
+        })
+    }
+
+    fn max_scroll(&self) -> usize {
+        self.rows().count().saturating_sub(self.rect.height)
+    }
+}
+
+/// As a `Widget`, a log pane is drawn into the rect it is given, which becomes its `rect`. It is dirty whenever a line is pushed,
+/// the log is cleared or scrolled, `rect` is changed directly, or the theme changes.
+///
+/// Example
+/// ```
+/// use terminalgl::ui::{LogPane, Widget};
+/// use terminalgl::canvas::Canvas;
+/// use terminalgl::{assert_frame_eq, Rect};
+/// let mut log = LogPane::new(Rect::new(0, 0, 6, 1), 10);
+/// let mut canvas = Canvas::new(6, 1);
+/// log.push("one", "");
+/// log.push("two", "");
+/// assert!(log.render_if_dirty(&mut canvas, Rect::new(0, 0, 6, 1)));
+/// assert!(!log.render_if_dirty(&mut canvas, Rect::new(0, 0, 6, 1)));
+///
+/// log.scroll_by(-1);
+/// assert!(!log.is_dirty());
+/// log.scroll_by(1);
+/// assert!(log.is_dirty());
+/// assert!(log.render_if_dirty(&mut canvas, Rect::new(0, 0, 6, 1)));
+/// assert_frame_eq!(canvas, "\
+/// |one   |");
+///
+/// log.rect.width = 3;
+/// assert!(log.is_dirty());
+/// ```
+impl Widget for LogPane {
+    fn render(&mut self, canvas: &mut Canvas, rect: Rect) {
+        self.rect = rect;
+        canvas.rectangle(rect.x, rect.y, rect.width, rect.height, ' ', &theme::current().background, true);
+        LogPane::render(self, canvas);
+        self.drawn = Some((rect, theme::generation()));
+    }
+
+    fn is_dirty(&self) -> bool {
+        self.dirty || self.drawn.is_some_and(|(drawn, generation)| drawn != self.rect || generation != theme::generation())
+    }
+
+    fn mark_clean(&mut self) {
+        self.dirty = false;
+    }
+
+    fn drawn_rect(&self) -> Option<Rect> {
+        self.drawn.map(|(rect, _)| rect)
+    }
+}
+
+
+/// A single line text input. Typed characters and pasted text go in at the cursor, which moves with the arrow keys,
+/// Home, and End, and Backspace and Delete remove the grapheme before or under it. Line breaks in pasted text
+/// become spaces and other control characters are left out, so the text stays on one line.
+/// The cursor moves a grapheme at a time (see `raster::graphemes`), so an accented letter or an emoji sequence
+/// is one step and one Backspace, and columns are counted with `raster::text_width`, like everything else that lays out text.
+/// When the text is wider than the field, it scrolls to keep the cursor in view.
+///
+/// Example
+/// ```
+/// # #[cfg(all(feature = "input", unix))] {
+/// use terminalgl::ui::{TextField, Widget};
+/// use terminalgl::input::{Event, Key};
+/// use terminalgl::canvas::Canvas;
+/// use terminalgl::{assert_frame_eq, Rect};
+/// let mut field = TextField::new("hi");
+/// assert!(field.handle(&Event::Key(Key::Left)));
+/// assert!(field.handle(&Event::Paste("ey\r\nthere\x07 ".to_string())));
+/// assert_eq!(field.text(), "hey there i");
+/// assert_eq!(field.cursor(), 10);
+/// assert!(field.handle(&Event::Key(Key::Backspace)));
+/// assert!(field.handle(&Event::Key(Key::End)));
+/// assert!(field.handle(&Event::Key(Key::Char('!'))));
+/// assert!(!field.handle(&Event::Key(Key::Enter)));
+/// assert_eq!(field.text(), "hey therei!");
+///
+/// let mut canvas = Canvas::new(8, 1);
+/// assert!(field.render_if_dirty(&mut canvas, Rect::new(0, 0, 8, 1)));
+/// assert_frame_eq!(canvas, r#"|therei! |
+///
+/// |.......A|
+/// A "\u{1b}[7m""#);
+///
+/// // ASCII, a combining accent, a zero width joiner sequence, and CJK.
+/// let mut field = TextField::new("ae\u{301}👩\u{200D}💻漢z");
+/// let mut columns = vec![field.cursor_column()];
+/// while field.cursor() > 0 {
+///     field.handle(&Event::Key(Key::Left));
+///     columns.push(field.cursor_column());
+/// }
+/// assert_eq!(columns, vec![7, 6, 4, 2, 1, 0]);
+/// field.handle(&Event::Key(Key::Right));
+/// field.handle(&Event::Key(Key::Right));
+/// field.handle(&Event::Key(Key::Delete));
+/// assert_eq!(field.text(), "ae\u{301}漢z");
+/// field.handle(&Event::Key(Key::Backspace));
+/// assert_eq!(field.text(), "a漢z");
+/// assert_eq!((field.cursor(), field.cursor_column()), (1, 1));
+///
+/// // Drawn at the columns it is edited at, with the cell after a wide grapheme left blank.
+/// field.handle(&Event::Key(Key::End));
+/// let mut canvas = Canvas::new(6, 1);
+/// field.render(&mut canvas, Rect::new(0, 0, 6, 1));
+/// assert_frame_eq!(canvas, r#"|a漢 z  |
+///
+/// |....A.|
+/// A "\u{1b}[7m""#);
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+pub struct TextField {
+    text: String,
+    cursor: usize,
+    dirty: bool,
+    drawn: Option<(Rect, u64)>
+}
+
+impl TextField {
+    /// Create a field holding `text`, with the cursor at its end.
+    pub fn new(text: &str) -> TextField {
+        let mut field = TextField { text: String::new(), cursor: 0, dirty: true, drawn: None };
+        field.insert(text);
+        field
+    }
+
+    /// Get the text in the field.
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Get the cursor position, as a byte offset into the text at the start of a grapheme.
+    pub fn cursor(&self) -> usize {
+        self.cursor
+    }
+
+    /// Get the column the cursor is at, counted in cells from the start of the text.
+    pub fn cursor_column(&self) -> usize {
+        raster::text_width(&self.text[..self.cursor])
+    }
+
+    /// Replace the text with `text`, and move the cursor to its end.
+    pub fn set_text(&mut self, text: &str) {
+        self.text.clear();
+        self.cursor = 0;
+        self.dirty = true;
+        self.insert(text);
+    }
+
+    /// Insert `text` at the cursor and move the cursor past it, turning line breaks into spaces and leaving out other control characters.
+    pub fn insert(&mut self, text: &str) {
+        let text: String = text.replace("\r\n", "\n").chars()
+            .filter_map(|c| match c {
+                '\n' | '\r' => Some(' '),
+                c if c.is_control() => None,
+                c => Some(c)
+            })
+            .collect();
+        if text.is_empty() {
+            return;
+        }
+        self.text.insert_str(self.cursor, &text);
+        self.cursor += text.len();
+        // A character inserted just before combining marks takes them, so step past the grapheme the cursor ended up inside.
+        if raster::next_grapheme(&self.text, raster::prev_grapheme(&self.text, self.cursor)) != self.cursor {
+            self.cursor = raster::next_grapheme(&self.text, self.cursor);
+        }
+        self.dirty = true;
+    }
+
+    /// Move the cursor to the start of the grapheme the byte offset `cursor` is in, or to the end of the text past it.
+    pub fn set_cursor(&mut self, cursor: usize) {
+        let cursor = if cursor >= self.text.len() { self.text.len() } else { raster::prev_grapheme(&self.text, cursor + 1) };
+        self.dirty |= cursor != self.cursor;
+        self.cursor = cursor;
+    }
+
+    /// Edit the text or move the cursor for `event`, and get whether it was used.
+    #[cfg(all(feature = "input", unix))]
+    pub fn handle(&mut self, event: &Event) -> bool {
+        match event {
+            Event::Key(Key::Char(c)) => self.insert(c.encode_utf8(&mut [0; 4])),
+            Event::Paste(text) => self.insert(text),
+            Event::Key(Key::Left) => self.set_cursor(raster::prev_grapheme(&self.text, self.cursor)),
+            Event::Key(Key::Right) => self.set_cursor(raster::next_grapheme(&self.text, self.cursor)),
+            Event::Key(Key::Home) => self.set_cursor(0),
+            Event::Key(Key::End) => self.set_cursor(self.text.len()),
+            Event::Key(Key::Backspace) => {
+                let start = raster::prev_grapheme(&self.text, self.cursor);
+                self.remove(start..self.cursor);
+                self.cursor = start;
+            },
+            Event::Key(Key::Delete) => self.remove(self.cursor..raster::next_grapheme(&self.text, self.cursor)),
+            _ => return false
+        }
+        true
+    }
+
+    #[cfg(all(feature = "input", unix))]
+    fn remove(&mut self, range: std::ops::Range<usize>) {
+        if !range.is_empty() {
+            self.text.replace_range(range, "");
+            self.dirty = true;
+        }
+    }
+}
+
+/// As a `Widget`, a text field draws as much of its text as fits on the first row of the rect it is given,
+/// with the cell under the cursor in the theme's `selection` color. Canvas cells hold one character each,
+/// so each grapheme is drawn as its first character at the column it starts at, with the cell after a wide one left blank.
+/// It is dirty whenever the text or cursor changes, or the theme changes.
+impl Widget for TextField {
+    fn render(&mut self, canvas: &mut Canvas, rect: Rect) {
+        let theme = theme::current();
+        canvas.rectangle(rect.x, rect.y, rect.width, rect.height, ' ', &theme.background, true);
+        if rect.width > 0 && rect.height > 0 {
+            let under = raster::graphemes(&self.text[self.cursor..]).next().unwrap_or(" ");
+            let cursor_column = self.cursor_column();
+            // Scroll by whole graphemes, just far enough for the cursor's cells to fit.
+            let wanted = (cursor_column + raster::grapheme_width(under).max(1)).saturating_sub(rect.width);
+            let mut column = 0;
+            let mut first = None;
+            for grapheme in raster::graphemes(&self.text) {
+                let width = raster::grapheme_width(grapheme);
+                if column >= wanted {
+                    let first = *first.get_or_insert(column);
+                    if column + width > first + rect.width {
+                        break;
+                    }
+                    let c = grapheme.chars().next().unwrap_or(' ');
+                    canvas.set(rect.x.saturating_add((column - first) as isize), rect.y, c, &theme.text);
+                }
+                column += width;
+            }
+            let first = first.unwrap_or(wanted);
+            let c = under.chars().next().unwrap_or(' ');
+            canvas.set(rect.x.saturating_add((cursor_column - first) as isize), rect.y, c, &theme.selection);
+        }
+        self.drawn = Some((rect, theme::generation()));
+    }
+
+    fn is_dirty(&self) -> bool {
+        self.dirty || self.drawn.is_some_and(|(_, generation)| generation != theme::generation())
+    }
+
+    fn mark_clean(&mut self) {
+        self.dirty = false;
+    }
+
+    fn drawn_rect(&self) -> Option<Rect> {
+        self.drawn.map(|(rect, _)| rect)
+    }
+}
+
+
+/// A message shown by a `ToastManager`, `hidden` columns short of fully slid in.
+#[derive(Clone, Debug)]
+struct Toast {
+    id: u64,
+    lines: Vec<String>,
+    ccode: String,
+    timer: Timer,
+    hidden: usize,
+    leaving: bool
+}
+
+impl Toast {
+    /// Get the size of the toast's box: its lines inside a border with a space of padding on each side.
+    fn size(&self) -> (usize, usize) {
+        let longest = self.lines.iter().map(|line| raster::text_width(line)).max().unwrap_or(0);
+        (longest + 4, self.lines.len() + 2)
+    }
+}
+
+
+/// Transient notifications, stacked in `corner` of a canvas with the newest nearest the corner.
+/// Each toast slides in from the side of the canvas one cell per `update`, stays for the duration it was shown with,
+/// then slides back out. Messages are word-wrapped to fit boxes at most `max_width` cells wide, and showing more than
+/// `max_count` toasts drops the oldest.
+///
+/// `render` draws the toasts over whatever is on the canvas, and puts back what was under them the next time it is called,
+/// so dismissed toasts disappear without the application repainting. Draw the frame, then render the toasts last.
+/// If the application draws over a toast's cells in between, it is left to cover them.
+///
+/// Example
+/// ```
+/// use terminalgl::ui::ToastManager;
+/// use terminalgl::canvas::Canvas;
+/// use terminalgl::{assert_frame_eq, Anchor};
+/// use std::time::Duration;
+/// let mut canvas = Canvas::new(12, 5);
+/// canvas.rectangle(0, 0, 12, 5, '.', "", true);
+/// let mut toasts = ToastManager::new(Anchor::BottomRight);
+/// toasts.margin = 0;
+/// toasts.toast("saved", "", Duration::from_secs(2));
+/// for _ in 0..4 {
+///     toasts.update(0.1);
+/// }
+/// toasts.render(&mut canvas);
+/// assert_frame_eq!(canvas, "\
+/// |............|
+/// |............|
+/// |........╭───|
+/// |........│ sa|
+/// |........╰───|");
+///
+/// for _ in 0..5 {
+///     toasts.update(0.1);
+/// }
+/// toasts.render(&mut canvas);
+/// assert_frame_eq!(canvas, "\
+/// |............|
+/// |............|
+/// |...╭───────╮|
+/// |...│ saved │|
+/// |...╰───────╯|");
+///
+/// toasts.update(2.0);
+/// while !toasts.is_empty() {
+///     toasts.update(0.1);
+/// }
+/// toasts.render(&mut canvas);
+/// assert_frame_eq!(canvas, "\
+/// |............|
+/// |............|
+/// |............|
+/// |............|
+/// |............|");
+/// ```
+///
+/// Toasts stack away from the corner, long messages wrap, and the oldest past `max_count` are dropped:
+/// ```
+/// use terminalgl::ui::ToastManager;
+/// use terminalgl::canvas::Canvas;
+/// use terminalgl::{assert_frame_eq, Anchor};
+/// use std::time::Duration;
+/// let mut canvas = Canvas::new(14, 7);
+/// let mut toasts = ToastManager::new(Anchor::TopLeft);
+/// toasts.max_width = 12;
+/// toasts.max_count = 2;
+/// for message in ["first", "second", "connection lost"] {
+///     toasts.toast(message, "", Duration::from_secs(5));
+/// }
+/// for _ in 0..20 {
+///     toasts.update(0.01);
+/// }
+/// assert_eq!(toasts.len(), 2);
+/// toasts.render(&mut canvas);
+/// assert_frame_eq!(canvas, "\
+/// |              |
+/// | ╭──────────╮ |
+/// | │ connecti │ |
+/// | │ on lost  │ |
+/// | ╰──────────╯ |
+/// | ╭────────╮   |
+/// | │ second │   |");
+/// ```
+#[derive(Clone, Debug)]
+pub struct ToastManager {
+    pub corner: Anchor,
+    pub max_count: usize,
+    pub max_width: usize,
+    pub margin: usize,
+    toasts: VecDeque<Toast>,
+    clock: Scheduler<u64>,
+    next: u64,
+    covered: Vec<(Snapshot, Snapshot)>
+}
+
+impl ToastManager {
+    /// Create a manager with no toasts showing that stacks them in `corner`, a cell in from the edges,
+    /// at most 5 at a time and 40 cells wide.
+    pub fn new(corner: Anchor) -> ToastManager {
+        ToastManager { corner, max_count: 5, max_width: 40, margin: 1, toasts: VecDeque::new(), clock: Scheduler::new(), next: 0, covered: Vec::new() }
+    }
+
+    /// Show `message` drawn with `ccode`, or the theme's colors if it is empty, for `duration`, counted from the last `update`, dropping the oldest toasts past `max_count`.
+    pub fn toast(&mut self, message: &str, ccode: &str, duration: Duration) {
+        let lines = raster::wrap_words(message, self.max_width.saturating_sub(4).max(1));
+        let timer = self.clock.after(duration, self.next);
+        let mut toast = Toast { id: self.next, lines, ccode: ccode.to_string(), timer, hidden: 0, leaving: false };
+        toast.hidden = toast.size().0;
+        self.next += 1;
+        self.toasts.push_back(toast);
+        while self.toasts.len() > self.max_count {
+            if let Some(oldest) = self.toasts.pop_front() {
+                self.clock.cancel(oldest.timer);
+            }
+        }
+    }
+
+    /// Advance by `dt` seconds, starting to dismiss the toasts whose time is up, and slide every toast one cell in or out.
+    /// Toasts that have slid all the way out are removed.
+    pub fn update(&mut self, dt: f64) {
+        for id in self.clock.advance(seconds(dt)) {
+            if let Some(toast) = self.toasts.iter_mut().find(|toast| toast.id == id) {
+                toast.leaving = true;
+            }
+        }
+        for toast in &mut self.toasts {
+            toast.hidden = if toast.leaving { toast.hidden + 1 } else { toast.hidden.saturating_sub(1) };
+        }
+        self.toasts.retain(|toast| !toast.leaving || toast.hidden < toast.size().0);
+    }
+
+    /// Put back what the toasts covered when last rendered, unless it has been drawn over since, then draw the toasts into `canvas`.
+    pub fn render(&mut self, canvas: &mut Canvas) {
+        for (under, drawn) in self.covered.drain(..).rev() {
+            if canvas.snapshot(drawn.rect()) == drawn {
+                canvas.restore(&under);
+            }
+        }
+        let from_left = matches!(self.corner, Anchor::TopLeft | Anchor::Left | Anchor::BottomLeft);
+        let upward = matches!(self.corner, Anchor::BottomLeft | Anchor::Bottom | Anchor::BottomRight);
+        let theme = theme::current();
+        let mut offset = 0isize;
+        for toast in self.toasts.iter().rev() {
+            let (width, height) = toast.size();
+            let rect = canvas.rect().anchor(self.corner, (width, height), self.margin);
+            let dx = if from_left { -(toast.hidden as isize) } else { toast.hidden as isize };
+            let dy = if upward { -offset } else { offset };
+            let rect = Rect::new(rect.x.saturating_add(dx), rect.y.saturating_add(dy), width, height);
+            offset = offset.saturating_add(height as isize);
+
+            let under = canvas.snapshot(rect);
+            canvas.rectangle(rect.x, rect.y, width, height, ' ', theme::pick(&toast.ccode, &theme.background), true);
+            canvas.border(rect, LineStyle::Rounded, theme::pick(&toast.ccode, &theme.border));
+            for (i, line) in toast.lines.iter().enumerate() {
+                canvas.text(rect.x.saturating_add(2), rect.y.saturating_add(1 + i as isize), line, theme::pick(&toast.ccode, &theme.text));
+            }
+            self.covered.push((under, canvas.snapshot(rect)));
+        }
+    }
+
+    /// Get the number of toasts showing, including ones sliding in or out.
+    pub fn len(&self) -> usize {
+        self.toasts.len()
+    }
+
+    /// Get whether no toasts are showing.
+    pub fn is_empty(&self) -> bool {
+        self.toasts.is_empty()
+    }
+
+    /// Remove every toast at once. The next `render` puts back what they covered.
+    pub fn clear(&mut self) {
+        for toast in self.toasts.drain(..) {
+            self.clock.cancel(toast.timer);
+        }
+    }
+}
+
+
+/// A change in what the pointer is over, from a `HitGrid`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Hover<Id> {
+    Enter(Id),
+    Leave(Id)
+}
+
+
+/// The cells a `HitGrid` region covers: a rectangle, or any cells, sorted for searching.
+#[derive(Clone, Debug)]
+enum Area {
+    Rect(Rect),
+    Cells(Vec<(isize, isize)>)
+}
+
+/// A region registered with a `HitGrid`.
+#[derive(Clone, Debug)]
+struct Region<Id> {
+    id: Id,
+    layer: i32,
+    area: Area
+}
+
+impl<Id> Region<Id> {
+    fn contains(&self, x: isize, y: isize) -> bool {
+        match &self.area {
+            Area::Rect(rect) => rect.contains(x, y),
+            Area::Cells(cells) => cells.binary_search(&(x, y)).is_ok()
+        }
+    }
+
+    fn intersects(&self, rect: Rect) -> bool {
+        match &self.area {
+            Area::Rect(area) => area.intersect(rect).width > 0,
+            Area::Cells(cells) => cells.iter().any(|&(x, y)| rect.contains(x, y))
+        }
+    }
+}
+
+
+/// Which id (such as an enum of the clickable things on screen) each part of the screen belongs to, for finding what a click
+/// landed on and what the mouse is over. Each frame, `clear` the grid and register the rectangles things are drawn in with
+/// `add_rect`, or the exact cells drawn with `Canvas::with_hit_id`, so regions from earlier frames never catch clicks.
+/// Where regions overlap, the one on the highest layer (see `set_layer`) wins, then the one registered last,
+/// the same way whatever is drawn last ends up on top.
+///
+/// The grid also tracks what the pointer hovers over, reporting each change as it leaves one region and enters another.
+/// Call `refresh_hover` once a frame's regions are registered, so things appearing under a still pointer are hovered too.
+///
+/// Example
+/// ```
+/// use terminalgl::ui::{HitGrid, Hover};
+/// use terminalgl::canvas::Canvas;
+/// use terminalgl::Rect;
+/// #[derive(Clone, Copy, Debug, PartialEq)]
+/// enum Target { Ok, Cancel, Dot, Menu }
+/// let mut canvas = Canvas::new(20, 6);
+/// let mut hits = HitGrid::new();
+/// hits.add_rect(Rect::new(0, 0, 6, 3), Target::Ok);
+/// hits.add_rect(Rect::new(7, 0, 6, 3), Target::Cancel);
+/// canvas.with_hit_id(&mut hits, Target::Dot, |canvas| canvas.ellipse(16, 3, 2, 2, '@', "", true));
+/// assert_eq!(hits.hit_test(2, 1), Some(Target::Ok));
+/// assert_eq!(hits.hit_test(16, 3), Some(Target::Dot));
+/// // Only the cells drawn count, not the corners of the circle's bounding box.
+/// assert_eq!(hits.hit_test(14, 1), None);
+///
+/// // A menu opened on a higher layer covers the buttons, even though they are registered after it.
+/// hits.clear();
+/// hits.set_layer(1);
+/// hits.add_rect(Rect::new(4, 0, 5, 4), Target::Menu);
+/// hits.set_layer(0);
+/// hits.add_rect(Rect::new(0, 0, 6, 3), Target::Ok);
+/// hits.add_rect(Rect::new(7, 0, 6, 3), Target::Cancel);
+/// assert_eq!(hits.hit_test(5, 1), Some(Target::Menu));
+/// assert_eq!(hits.hit_test(16, 3), None);
+///
+/// assert_eq!(hits.hover(1, 1), [Hover::Enter(Target::Ok)]);
+/// assert_eq!(hits.hover(2, 2), []);
+/// assert_eq!(hits.hover(5, 2), [Hover::Leave(Target::Ok), Hover::Enter(Target::Menu)]);
+/// assert_eq!(hits.hovered(), Some(Target::Menu));
+///
+/// // The menu closes with the pointer still, and the next frame's regions show what is under it now.
+/// hits.clear();
+/// hits.add_rect(Rect::new(0, 0, 6, 3), Target::Ok);
+/// assert_eq!(hits.refresh_hover(), [Hover::Leave(Target::Menu), Hover::Enter(Target::Ok)]);
+/// ```
+#[derive(Clone, Debug)]
+pub struct HitGrid<Id> {
+    regions: Vec<Region<Id>>,
+    layer: i32,
+    pointer: Option<(isize, isize)>,
+    hovered: Option<Id>
+}
+
+impl<Id: Copy + PartialEq> Default for HitGrid<Id> {
+    fn default() -> Self {
+        HitGrid::new()
+    }
+}
+
+impl<Id: Copy + PartialEq> HitGrid<Id> {
+    /// Create a grid with no regions, on layer 0, with nothing hovered.
+    pub fn new() -> HitGrid<Id> {
+        HitGrid { regions: Vec::new(), layer: 0, pointer: None, hovered: None }
+    }
+
+    /// Remove every region and go back to layer 0, to register the next frame's. What is hovered stays until
+    /// the pointer moves or `refresh_hover` is called.
+    pub fn clear(&mut self) {
+        self.regions.clear();
+        self.layer = 0;
+    }
+
+    /// Set the layer regions registered from now on are on. Regions on higher layers win over lower ones where they overlap.
+    pub fn set_layer(&mut self, layer: i32) {
+        self.layer = layer;
+    }
+
+    /// Get the layer regions are registered on.
+    pub fn layer(&self) -> i32 {
+        self.layer
+    }
+
+    /// Register `rect` as belonging to `id`.
+    pub fn add_rect(&mut self, rect: Rect, id: Id) {
+        self.regions.push(Region { id, layer: self.layer, area: Area::Rect(rect) });
+    }
+
+    /// Register `cells` as belonging to `id`.
+    pub fn add_cells(&mut self, cells: impl IntoIterator<Item = (isize, isize)>, id: Id) {
+        let mut cells: Vec<(isize, isize)> = cells.into_iter().collect();
+        cells.sort_unstable();
+        cells.dedup();
+        self.regions.push(Region { id, layer: self.layer, area: Area::Cells(cells) });
+    }
+
+    /// Get the id of the region on top at `(x, y)`, if any.
+    pub fn hit_test(&self, x: isize, y: isize) -> Option<Id> {
+        self.hit_test_by(x, y, |_| true)
+    }
+
+    /// Get the id of the region on top at `(x, y)` out of those whose ids `accept` accepts, looking through the rest.
+    pub fn hit_test_by(&self, x: isize, y: isize, accept: impl Fn(&Id) -> bool) -> Option<Id> {
+        // `max_by_key` keeps the last of equal keys, so the region registered last wins within a layer.
+        self.regions.iter()
+            .filter(|region| accept(&region.id) && region.contains(x, y))
+            .max_by_key(|region| region.layer)
+            .map(|region| region.id)
+    }
+
+    /// Get the ids with a region overlapping `rect`, each once, in the order they were first registered.
+    pub fn ids_in(&self, rect: Rect) -> Vec<Id> {
+        let mut ids = Vec::new();
+        for region in self.regions.iter().filter(|region| region.intersects(rect)) {
+            if !ids.contains(&region.id) {
+                ids.push(region.id);
+            }
+        }
+        ids
+    }
+
+    /// Get the smallest rectangle holding every region registered for `id`, or `None` if it has none.
+    pub fn bounds(&self, id: Id) -> Option<Rect> {
+        let mut cells = self.regions.iter().filter(|region| region.id == id).flat_map(|region| match &region.area {
+            Area::Rect(rect) if rect.width > 0 && rect.height > 0 => {
+                let (right, bottom) = (rect.x + rect.width as isize - 1, rect.y + rect.height as isize - 1);
+                vec![(rect.x, rect.y), (right, bottom)]
+            },
+            Area::Rect(_) => Vec::new(),
+            Area::Cells(cells) => cells.clone()
+        });
+        let (x, y) = cells.next()?;
+        let (left, top, right, bottom) = cells.fold((x, y, x, y), |(l, t, r, b), (x, y)| (l.min(x), t.min(y), r.max(x), b.max(y)));
+        Some(Rect::new(left, top, right.abs_diff(left) + 1, bottom.abs_diff(top) + 1))
+    }
+
+    /// Get the id of the region the pointer is over, if any.
+    pub fn hovered(&self) -> Option<Id> {
+        self.hovered
+    }
+
+    /// Move the pointer to `(x, y)`, and get the changes in what it is over.
+    pub fn hover(&mut self, x: isize, y: isize) -> Vec<Hover<Id>> {
+        self.pointer = Some((x, y));
+        self.refresh_hover()
+    }
+
+    /// Take the pointer away, such as when the terminal loses focus, and get the change, leaving whatever it was over.
+    pub fn unhover(&mut self) -> Vec<Hover<Id>> {
+        self.pointer = None;
+        self.refresh_hover()
+    }
+
+    /// Look up what the pointer is over again, after registering a frame's regions, and get the changes since the last time.
+    pub fn refresh_hover(&mut self) -> Vec<Hover<Id>> {
+        let now = self.pointer.and_then(|(x, y)| self.hit_test(x, y));
+        if now == self.hovered {
+            return Vec::new();
+        }
+        let changes = self.hovered.map(Hover::Leave).into_iter().chain(now.map(Hover::Enter)).collect();
+        self.hovered = now;
+        changes
+    }
+
+    /// Move the pointer to where a mouse event happened, and get the changes in what it is over. Other events change nothing.
+    /// The terminal only reports the mouse moving with no button held after `RawMode::with_mouse_motion`.
+    #[cfg(all(feature = "input", unix))]
+    pub fn handle_event(&mut self, event: &Event) -> Vec<Hover<Id>> {
+        match *event {
+            Event::Mouse(Mouse { x, y, .. }) => self.hover(x, y),
+            _ => Vec::new()
+        }
+    }
+
+    /// Get the id of the region a left click landed on, if `event` is one.
+    ///
+    /// Example
+    /// ```
+    /// use terminalgl::ui::HitGrid;
+    /// use terminalgl::input::{Event, Mouse, MouseButton, MouseKind};
+    /// use terminalgl::Rect;
+    /// let mut hits = HitGrid::new();
+    /// hits.add_rect(Rect::new(0, 0, 4, 1), "save");
+    /// let click = |x| Event::Mouse(Mouse { kind: MouseKind::Press(MouseButton::Left), x, y: 0 });
+    /// assert_eq!(hits.clicked(&click(2)), Some("save"));
+    /// assert_eq!(hits.clicked(&click(5)), None);
+    /// ```
+    #[cfg(all(feature = "input", unix))]
+    pub fn clicked(&self, event: &Event) -> Option<Id> {
+        match *event {
+            Event::Mouse(Mouse { kind: MouseKind::Press(MouseButton::Left), x, y }) => self.hit_test(x, y),
+            _ => None
+        }
+    }
+}
+
+
+/// What happened to a press or drag, from `DragController::handle_event`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Drag<Id> {
+    /// The pointer moved far enough from where the id was pressed to start dragging it.
+    Start(Id),
+    /// The id was pressed and released without the pointer moving far enough to drag it.
+    Click(Id),
+    /// The dragged id was released over `target`, or over no other region if it is `None`.
+    Drop { dragged: Id, target: Option<Id> },
+    /// Esc was pressed during the press or drag.
+    Cancel(Id)
+}
+
+
+/// An id being pressed or dragged by a `DragController`.
+#[derive(Clone, Debug)]
+struct Held<Id> {
+    id: Id,
+    from: (isize, isize),
+    at: (isize, isize),
+    dragging: bool,
+    origin: Rect,
+    ghost: Option<Snapshot>
+}
+
+
+/// Drag and drop over the regions of a `HitGrid`. A left press on a draggable region holds its id, which starts being dragged
+/// once the pointer moves `threshold` cells away, and is dropped on whatever region is under the pointer when the button
+/// is released. Releasing before the pointer has moved that far is a click instead, so the same regions can be clicked
+/// and dragged. Esc cancels.
+///
+/// While dragging, `offset` is how far the pointer has moved since the press, for moving the dragged thing along with it, and
+/// `render_ghost` draws a faded copy of the dragged region there.
+///
+/// Example
+/// ```
+/// # #[cfg(all(feature = "input", unix))] {
+/// use terminalgl::ui::{Drag, DragController, HitGrid};
+/// use terminalgl::input::{Event, Key, Mouse, MouseButton, MouseKind};
+/// use terminalgl::Rect;
+/// let mouse = |kind, x, y| Event::Mouse(Mouse { kind, x, y });
+/// let (press, drag, release) = (MouseKind::Press(MouseButton::Left), MouseKind::Drag(MouseButton::Left), MouseKind::Release);
+/// let mut hits = HitGrid::new();
+/// hits.add_rect(Rect::new(0, 0, 10, 1), "first");
+/// hits.add_rect(Rect::new(0, 1, 10, 1), "second");
+/// hits.add_rect(Rect::new(12, 0, 5, 2), "bin");
+/// let mut drags = DragController::new();
+/// let draggable = |id: &&str| *id != "bin";
+///
+/// assert_eq!(drags.handle_event(&mouse(press, 2, 0), &hits, draggable), None);
+/// assert_eq!(drags.handle_event(&mouse(drag, 3, 0), &hits, draggable), None);
+/// assert_eq!(drags.handle_event(&mouse(drag, 4, 1), &hits, draggable), Some(Drag::Start("first")));
+/// assert_eq!(drags.offset(), Some((2, 1)));
+/// // Dropped over the region it came from, the region under that is the target.
+/// assert_eq!(drags.handle_event(&mouse(release, 4, 1), &hits, draggable), Some(Drag::Drop { dragged: "first", target: Some("second") }));
+///
+/// // A press and release in place is a click.
+/// drags.handle_event(&mouse(press, 1, 1), &hits, draggable);
+/// assert_eq!(drags.handle_event(&mouse(release, 1, 1), &hits, draggable), Some(Drag::Click("second")));
+///
+/// // Dropped over nothing, cancelled with Esc, or pressed where nothing draggable is.
+/// drags.handle_event(&mouse(press, 1, 1), &hits, draggable);
+/// drags.handle_event(&mouse(drag, 1, 5), &hits, draggable);
+/// assert_eq!(drags.handle_event(&mouse(release, 1, 5), &hits, draggable), Some(Drag::Drop { dragged: "second", target: None }));
+/// drags.handle_event(&mouse(press, 1, 0), &hits, draggable);
+/// drags.handle_event(&mouse(drag, 14, 0), &hits, draggable);
+/// assert_eq!(drags.dragging(), Some("first"));
+/// assert_eq!(drags.handle_event(&Event::Key(Key::Esc), &hits, draggable), Some(Drag::Cancel("first")));
+/// assert_eq!(drags.handle_event(&mouse(release, 14, 0), &hits, draggable), None);
+/// assert_eq!(drags.handle_event(&mouse(press, 13, 0), &hits, draggable), None);
+/// assert_eq!(drags.handle_event(&mouse(release, 13, 0), &hits, draggable), None);
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+pub struct DragController<Id> {
+    pub threshold: usize,
+    held: Option<Held<Id>>
+}
+
+impl<Id: Copy + PartialEq> Default for DragController<Id> {
+    fn default() -> Self {
+        DragController::new()
+    }
+}
+
+impl<Id: Copy + PartialEq> DragController<Id> {
+    /// Create a controller with nothing held, which starts dragging once the pointer moves 2 cells.
+    pub fn new() -> DragController<Id> {
+        DragController { threshold: 2, held: None }
+    }
+
+    /// Apply `event`, looking up what was pressed and where things are dropped in `hits`, and get what happened, if anything.
+    /// Only ids `draggable` accepts can be pressed, and a dragged id is never its own drop target.
+    #[cfg(all(feature = "input", unix))]
+    pub fn handle_event(&mut self, event: &Event, hits: &HitGrid<Id>, draggable: impl Fn(&Id) -> bool) -> Option<Drag<Id>> {
+        match *event {
+            Event::Mouse(Mouse { kind: MouseKind::Press(MouseButton::Left), x, y }) => {
+                self.held = hits.hit_test(x, y).filter(&draggable).map(|id| Held {
+                    id,
+                    from: (x, y),
+                    at: (x, y),
+                    dragging: false,
+                    origin: hits.bounds(id).unwrap_or(Rect::new(x, y, 1, 1)),
+                    ghost: None
+                });
+                None
+            },
+            Event::Mouse(Mouse { kind: MouseKind::Drag(_) | MouseKind::Move, x, y }) => {
+                let held = self.held.as_mut()?;
+                held.at = (x, y);
+                let moved = held.from.0.abs_diff(x).max(held.from.1.abs_diff(y));
+                if held.dragging || moved < self.threshold {
+                    return None;
+                }
+                held.dragging = true;
+                Some(Drag::Start(held.id))
+            },
+            Event::Mouse(Mouse { kind: MouseKind::Release, x, y }) => {
+                let held = self.held.take()?;
+                if !held.dragging {
+                    return Some(Drag::Click(held.id));
+                }
+                let target = hits.hit_test_by(x, y, |id| *id != held.id);
+                Some(Drag::Drop { dragged: held.id, target })
+            },
+            Event::Key(Key::Esc) => self.held.take().map(|held| Drag::Cancel(held.id)),
+            _ => None
+        }
+    }
+
+    /// Get the id being dragged, once the pointer has moved far enough to start dragging it.
+    pub fn dragging(&self) -> Option<Id> {
+        self.held.as_ref().filter(|held| held.dragging).map(|held| held.id)
+    }
+
+    /// Get how far the pointer has moved since pressing the id being dragged, if one is.
+    pub fn offset(&self) -> Option<(isize, isize)> {
+        let held = self.held.as_ref().filter(|held| held.dragging)?;
+        Some((held.at.0 - held.from.0, held.at.1 - held.from.1))
+    }
+
+    /// Draw a faded copy of the dragged region moved by `offset`, if something is being dragged. The copy is taken from
+    /// `canvas` the first time this is called during a drag, so draw the frame with the region in its place before calling it.
+    ///
+    /// Example
+    /// ```
+    /// # #[cfg(all(feature = "input", unix))] {
+    /// use terminalgl::ui::{DragController, HitGrid};
+    /// use terminalgl::input::{Event, Mouse, MouseButton, MouseKind};
+    /// use terminalgl::canvas::Canvas;
+    /// use terminalgl::{assert_frame_eq, Rect};
+    /// let mut hits = HitGrid::new();
+    /// hits.add_rect(Rect::new(0, 0, 3, 1), 0);
+    /// let mut drags = DragController::new();
+    /// for (kind, x, y) in [(MouseKind::Press(MouseButton::Left), 1, 0), (MouseKind::Drag(MouseButton::Left), 5, 1)] {
+    ///     drags.handle_event(&Event::Mouse(Mouse { kind, x, y }), &hits, |_| true);
+    /// }
+    /// let mut canvas = Canvas::new(8, 2);
+    /// canvas.text(0, 0, "abc", "");
+    /// drags.render_ghost(&mut canvas);
+    /// assert_eq!((4..7).map(|x| canvas.get(x, 1).unwrap().c).collect::<String>(), "abc");
+    /// assert_eq!(canvas.ccode_at(4, 1), Some(terminalgl::drawc::darken("", 0.5).as_str()));
+    /// # }
+    /// ```
+    pub fn render_ghost(&mut self, canvas: &mut Canvas) {
+        let Some((dx, dy)) = self.offset() else {
+            return;
+        };
+        let Some(held) = self.held.as_mut() else {
+            return;
+        };
+        let ghost = held.ghost.get_or_insert_with(|| canvas.snapshot(held.origin));
+        let rect = ghost.rect();
+        canvas.paste(ghost, rect.x.saturating_add(dx), rect.y.saturating_add(dy), 0.5);
+    }
+
+    /// Drop whatever is held without reporting anything, such as when the regions it belonged to are gone.
+    pub fn reset(&mut self) {
+        self.held = None;
+    }
+}
+
+
+/// What a `RubberBand` selected.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Selection<Id> {
+    /// The button was released in the cell it was pressed in.
+    Click(isize, isize),
+    /// The button was released elsewhere, selecting `rect` and the ids of the regions overlapping it.
+    Rect { rect: Rect, ids: Vec<Id> }
+}
+
+
+/// Click-drag selection: a left press anchors a rectangle, which stretches to the cell the pointer is over until the button
+/// is released, whichever direction it is dragged in. `render` draws its outline in `LineStyle::Dashed` with `ccode`,
+/// or the theme's `accent` color if it is empty, over
+/// whatever is on the canvas, and puts back what was under the last outline first, so it can follow the pointer over a frame
+/// that isn't redrawn. Edges of the last outline the application has drawn over since are left alone. Esc cancels.
+///
+/// Example
+/// ```
+/// # #[cfg(all(feature = "input", unix))] {
+/// use terminalgl::ui::{HitGrid, RubberBand, Selection};
+/// use terminalgl::input::{Event, Mouse, MouseButton, MouseKind};
+/// use terminalgl::canvas::Canvas;
+/// use terminalgl::{assert_frame_eq, Rect};
+/// let mouse = |kind, x, y| Event::Mouse(Mouse { kind, x, y });
+/// let (press, drag, release) = (MouseKind::Press(MouseButton::Left), MouseKind::Drag(MouseButton::Left), MouseKind::Release);
+/// let mut canvas = Canvas::new(12, 5);
+/// canvas.text(1, 1, "a.txt", "");
+/// canvas.text(1, 3, "b.txt", "");
+/// let mut hits = HitGrid::new();
+/// hits.add_rect(Rect::new(1, 1, 5, 1), "a.txt");
+/// hits.add_rect(Rect::new(1, 3, 5, 1), "b.txt");
+/// let mut band = RubberBand::new();
+///
+/// // Dragged up and to the left, the rectangle still runs from its top left corner.
+/// band.handle_event(&mouse(press, 8, 2), &hits);
+/// band.handle_event(&mouse(drag, 3, 0), &hits);
+/// assert_eq!(band.rect(), Some(Rect::new(3, 0, 6, 3)));
+/// band.render(&mut canvas);
+/// assert_frame_eq!(canvas, "\
+/// |   ┌┄┄┄┄┐   |
+/// | a.┆xt  ┆   |
+/// |   └┄┄┄┄┘   |
+/// | b.txt      |
+/// |            |");
+///
+/// let selected = band.handle_event(&mouse(release, 3, 0), &hits);
+/// assert_eq!(selected, Some(Selection::Rect { rect: Rect::new(3, 0, 6, 3), ids: vec!["a.txt"] }));
+/// band.render(&mut canvas);
+/// assert_frame_eq!(canvas, "\
+/// |            |
+/// | a.txt      |
+/// |            |
+/// | b.txt      |
+/// |            |");
+///
+/// band.handle_event(&mouse(press, 2, 3), &hits);
+/// assert_eq!(band.handle_event(&mouse(release, 2, 3), &hits), Some(Selection::Click(2, 3)));
+/// # }
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct RubberBand {
+    pub ccode: String,
+    anchor: Option<(isize, isize)>,
+    at: (isize, isize),
+    covered: Vec<(Snapshot, Snapshot)>
+}
+
+impl RubberBand {
+    /// Create a rubber band with nothing selected, drawn in the default colors.
+    pub fn new() -> RubberBand {
+        RubberBand::default()
+    }
+
+    /// Apply `event`, and get the selection once the button is released, with the ids from `hits` whose regions it overlaps.
+    #[cfg(all(feature = "input", unix))]
+    pub fn handle_event<Id: Copy + PartialEq>(&mut self, event: &Event, hits: &HitGrid<Id>) -> Option<Selection<Id>> {
+        match *event {
+            Event::Mouse(Mouse { kind: MouseKind::Press(MouseButton::Left), x, y }) => {
+                self.anchor = Some((x, y));
+                self.at = (x, y);
+                None
+            },
+            Event::Mouse(Mouse { kind: MouseKind::Drag(_) | MouseKind::Move, x, y }) => {
+                self.at = (x, y);
+                None
+            },
+            Event::Mouse(Mouse { kind: MouseKind::Release, x, y }) => {
+                let anchor = self.anchor.take()?;
+                if anchor == (x, y) {
+                    return Some(Selection::Click(x, y));
+                }
+                let rect = Rect::spanning(anchor, (x, y));
+                Some(Selection::Rect { rect, ids: hits.ids_in(rect) })
+            },
+            Event::Key(Key::Esc) => {
+                self.anchor = None;
+                None
+            },
+            _ => None
+        }
+    }
+
+    /// Get the rectangle being selected, if the button is held.
+    pub fn rect(&self) -> Option<Rect> {
+        self.anchor.map(|anchor| Rect::spanning(anchor, self.at))
+    }
+
+    /// Put back what the outline covered when last rendered, except along any edge drawn over since,
+    /// then draw the outline of the rectangle being selected, if any, into `canvas`.
+    pub fn render(&mut self, canvas: &mut Canvas) {
+        // Edges share their corners, so check them all before putting any back.
+        let intact: Vec<bool> = self.covered.iter().map(|(_, drawn)| canvas.snapshot(drawn.rect()) == *drawn).collect();
+        for ((under, _), intact) in self.covered.drain(..).zip(intact) {
+            if intact {
+                canvas.restore(&under);
+            }
+        }
+        if let Some(rect) = self.rect() {
+            let (right, bottom) = (rect.x.saturating_add(rect.width as isize - 1), rect.y.saturating_add(rect.height as isize - 1));
+            let edges = [
+                Rect::new(rect.x, rect.y, rect.width, 1),
+                Rect::new(rect.x, bottom, rect.width, 1),
+                Rect::new(rect.x, rect.y, 1, rect.height),
+                Rect::new(right, rect.y, 1, rect.height)
+            ];
+            let under: Vec<Snapshot> = edges.iter().map(|&edge| canvas.snapshot(edge)).collect();
+            canvas.border(rect, LineStyle::Dashed, theme::pick(&self.ccode, &theme::current().accent));
+            self.covered = under.into_iter().zip(edges).map(|(under, edge)| (under, canvas.snapshot(edge))).collect();
+        }
+    }
+}